@@ -7,11 +7,14 @@ use tracing::{info, Level};
 use tracing_subscriber::FmtSubscriber;
 
 use zcad_core::entity::{Entity, EntityId};
+use zcad_core::font::{FontManager, CJK_FONT_PATHS};
 use zcad_core::geometry::{Arc, Circle, Geometry, Line, Point, Polyline};
-use zcad_core::history::{HistoryTree, OperationType, operations as hist_ops};
+use zcad_core::history::{HistoryTree, operations as hist_ops};
 use zcad_core::math::Point2;
 use zcad_core::properties::Color;
 use zcad_core::snap::SnapType;
+use zcad_core::tessellation::{self, TessellationCache};
+use zcad_core::transaction::{ChangeSet, Transaction};
 use zcad_file::Document;
 use zcad_ui::state::{DrawingTool, EditState, UiState};
 
@@ -33,6 +36,9 @@ struct ZcadApp {
     
     // 撤销/重做历史树
     history: HistoryTree,
+
+    // 按实体缓存的曲线细分结果
+    tessellation_cache: TessellationCache,
 }
 
 /// 文件操作类型
@@ -52,6 +58,7 @@ impl Default for ZcadApp {
             viewport_size: (800.0, 600.0),
             pending_file_op: None,
             history: HistoryTree::new(HISTORY_MAX_DEPTH),
+            tessellation_cache: TessellationCache::new(),
         };
         app.create_demo_content();
         app
@@ -100,48 +107,81 @@ impl ZcadApp {
         if ids.is_empty() {
             return;
         }
-        
-        // 使用分组操作记录多个删除
-        let mut operations = Vec::new();
+
+        let mut transaction = Transaction::new(&mut self.document, "");
+        let count = ids.iter().filter(|id| transaction.remove_entity(id).is_some()).count();
+        let mut changes = transaction.commit();
+        if count > 0 {
+            changes.description = format!("删除 {} 个实体", count);
+            self.record_changes(changes);
+            self.ui_state.status_message = format!("已删除 {} 个实体", count);
+        }
+        self.ui_state.clear_selection();
+    }
+
+    /// 分解选中的块参照（带撤销支持）
+    fn explode_selected_entities(&mut self) {
+        let ids: Vec<EntityId> = self.ui_state.selected_entities.clone();
+
+        // 每个块参照记录为一次删除加若干次创建
+        let mut transaction = Transaction::new(&mut self.document, "");
+        let mut exploded = 0;
         for id in &ids {
-            if let Some(entity) = self.document.remove_entity(id) {
-                let op = hist_ops::delete_entity(*id, Some(entity), "删除实体");
-                operations.push(op);
+            let Some(entity) = transaction.target().get_entity(id).cloned() else {
+                continue;
+            };
+            if !matches!(entity.geometry, Geometry::Insert(_)) {
+                continue;
             }
-        }
-        
-        if !operations.is_empty() {
-            let count = operations.len();
-            // 如果只有一个操作，直接添加；否则使用分组操作
-            if operations.len() == 1 {
-                let _ = self.history.add_operation(operations.remove(0));
-            } else {
-                let group_op = hist_ops::group_operation(
-                    "批量删除",
-                    operations,
-                    format!("删除 {} 个实体", count),
-                );
-                let _ = self.history.add_operation(group_op);
+            match transaction.target().blocks.explode(&entity) {
+                Ok(parts) => {
+                    transaction.remove_entity(id);
+                    for part in parts {
+                        transaction.add_entity(part);
+                    }
+                    exploded += 1;
+                }
+                Err(e) => {
+                    transaction.rollback();
+                    self.ui_state.status_message = format!("无法分解块参照: {}", e);
+                    return;
+                }
             }
-            self.ui_state.status_message = format!("已删除 {} 个实体", count);
         }
+
+        let mut changes = transaction.commit();
+        if changes.is_empty() {
+            self.ui_state.status_message = "未选中块参照".to_string();
+            return;
+        }
+        changes.description = format!("分解 {} 个块参照", exploded);
+        self.record_changes(changes);
+        self.ui_state.status_message = format!("已分解 {} 个块参照", exploded);
         self.ui_state.clear_selection();
     }
 
     /// 添加实体并记录历史（用于创建操作）
     fn add_entity_with_history(&mut self, entity: Entity, description: &str) -> EntityId {
-        let id = self.document.add_entity(entity.clone());
-        let op = hist_ops::create_entity(entity, description);
-        let _ = self.history.add_operation(op);
+        let mut transaction = Transaction::new(&mut self.document, description);
+        let id = transaction.add_entity(entity);
+        let changes = transaction.commit();
+        self.record_changes(changes);
         id
     }
 
+    /// 将事务提交的变更集记入历史
+    fn record_changes(&mut self, changes: ChangeSet) {
+        if !changes.is_empty() {
+            let _ = self.history.add_operation(hist_ops::change_set(changes));
+        }
+    }
+
     /// 执行撤销操作
     fn do_undo(&mut self) {
         // 先获取操作并克隆，避免借用问题
         let op_type = self.history.undo().map(|op| (op.operation_type.clone(), op.description.clone()));
         if let Some((op_type, desc)) = op_type {
-            self.apply_undo_operation(&op_type);
+            op_type.undo(&mut self.document);
             self.ui_state.status_message = format!("撤销: {}", desc);
         } else {
             self.ui_state.status_message = "没有可撤销的操作".to_string();
@@ -153,96 +193,13 @@ impl ZcadApp {
         // 先获取操作并克隆，避免借用问题
         let op_type = self.history.redo().map(|op| (op.operation_type.clone(), op.description.clone()));
         if let Some((op_type, desc)) = op_type {
-            self.apply_redo_operation(&op_type);
+            op_type.redo(&mut self.document);
             self.ui_state.status_message = format!("重做: {}", desc);
         } else {
             self.ui_state.status_message = "没有可重做的操作".to_string();
         }
     }
 
-    /// 应用撤销操作（反向执行）
-    fn apply_undo_operation(&mut self, op_type: &OperationType) {
-        match op_type {
-            OperationType::CreateEntity { entity } => {
-                // 撤销创建：删除实体
-                self.document.remove_entity(&entity.id);
-            }
-            OperationType::DeleteEntity { previous_entity, .. } => {
-                // 撤销删除：恢复实体
-                if let Some(entity) = previous_entity {
-                    self.document.add_entity(entity.clone());
-                }
-            }
-            OperationType::ModifyEntity { entity_id, previous_geometry, .. } => {
-                // 撤销修改：恢复到之前的几何
-                if let Some(entity) = self.document.get_entity(entity_id) {
-                    let mut restored = entity.clone();
-                    restored.geometry = previous_geometry.clone();
-                    self.document.update_entity(entity_id, restored);
-                }
-            }
-            OperationType::MoveEntities { .. } => {
-                // TODO: 移动操作的撤销需要额外的几何体变换支持
-                // 目前先跳过
-            }
-            OperationType::RotateEntities { .. } => {
-                // TODO: 旋转操作的撤销需要额外的几何体变换支持
-            }
-            OperationType::ScaleEntities { .. } => {
-                // TODO: 缩放操作的撤销需要额外的几何体变换支持
-            }
-            OperationType::GroupOperation { operations, .. } => {
-                // 反向撤销分组中的所有操作
-                for op in operations.iter().rev() {
-                    self.apply_undo_operation(&op.operation_type);
-                }
-            }
-            _ => {
-                // 其他操作类型暂不支持
-            }
-        }
-    }
-
-    /// 应用重做操作（正向执行）
-    fn apply_redo_operation(&mut self, op_type: &OperationType) {
-        match op_type {
-            OperationType::CreateEntity { entity } => {
-                // 重做创建：添加实体
-                self.document.add_entity(entity.clone());
-            }
-            OperationType::DeleteEntity { entity_id, .. } => {
-                // 重做删除：删除实体
-                self.document.remove_entity(entity_id);
-            }
-            OperationType::ModifyEntity { entity_id, new_geometry, .. } => {
-                // 重做修改：应用新几何
-                if let Some(entity) = self.document.get_entity(entity_id) {
-                    let mut modified = entity.clone();
-                    modified.geometry = new_geometry.clone();
-                    self.document.update_entity(entity_id, modified);
-                }
-            }
-            OperationType::MoveEntities { .. } => {
-                // TODO: 移动操作的重做需要额外的几何体变换支持
-            }
-            OperationType::RotateEntities { .. } => {
-                // TODO: 旋转操作的重做需要额外的几何体变换支持
-            }
-            OperationType::ScaleEntities { .. } => {
-                // TODO: 缩放操作的重做需要额外的几何体变换支持
-            }
-            OperationType::GroupOperation { operations, .. } => {
-                // 正向重做分组中的所有操作
-                for op in operations {
-                    self.apply_redo_operation(&op.operation_type);
-                }
-            }
-            _ => {
-                // 其他操作类型暂不支持
-            }
-        }
-    }
-
     /// 世界坐标转屏幕坐标
     fn world_to_screen(&self, point: Point2, rect: &egui::Rect) -> egui::Pos2 {
         let center = rect.center();
@@ -330,111 +287,85 @@ impl ZcadApp {
                 let end = self.world_to_screen(line.end, rect);
                 painter.line_segment([start, end], stroke);
             }
-            Geometry::Circle(circle) => {
-                let center = self.world_to_screen(circle.center, rect);
-                let radius = (circle.radius * self.camera_zoom) as f32;
-                painter.circle_stroke(center, radius, stroke);
+            Geometry::Circle(_)
+            | Geometry::Arc(_)
+            | Geometry::Polyline(_)
+            | Geometry::Ellipse(_)
+            | Geometry::Spline(_)
+            | Geometry::Hatch(_)
+            | Geometry::Leader(_) => {
+                // 曲线按屏幕精度细分；图案填充绘制裁剪后的图案线，实心填充绘制边界
+                let paths = tessellation::tessellate(geometry, self.tessellation_tolerance());
+                self.draw_paths(painter, rect, &paths, color);
             }
-            Geometry::Arc(arc) => {
-                // 简化：用线段近似弧线
-                let segments = 32;
-                let sweep = arc.sweep_angle();
-                let angle_step = sweep / segments as f64;
-                
-                for i in 0..segments {
-                    let a1 = arc.start_angle + i as f64 * angle_step;
-                    let a2 = arc.start_angle + (i + 1) as f64 * angle_step;
-                    
-                    let p1 = Point2::new(
-                        arc.center.x + arc.radius * a1.cos(),
-                        arc.center.y + arc.radius * a1.sin(),
-                    );
-                    let p2 = Point2::new(
-                        arc.center.x + arc.radius * a2.cos(),
-                        arc.center.y + arc.radius * a2.sin(),
+            Geometry::Text(text) => {
+                if text.height * self.camera_zoom < 2.0 {
+                    // 屏幕上过小的文字只画基线
+                    let (start, end) = text.baseline();
+                    painter.line_segment([self.world_to_screen(start, rect), self.world_to_screen(end, rect)], stroke);
+                } else if FontManager::shared().covers(&text.content, None) {
+                    // 按字形轮廓/笔画绘制，与打印输出一致
+                    for contour in text.outlines().iter().flat_map(|glyph| &glyph.contours) {
+                        let points = contour.iter().map(|p| self.world_to_screen(*p, rect)).collect();
+                        painter.add(egui::Shape::line(points, stroke));
+                    }
+                } else {
+                    let screen = self.world_to_screen(text.baseline().0, rect);
+                    painter.text(
+                        screen,
+                        egui::Align2::LEFT_BOTTOM,
+                        text.display_text(),
+                        egui::FontId::proportional(12.0),
+                        stroke_color,
                     );
-                    
-                    let s1 = self.world_to_screen(p1, rect);
-                    let s2 = self.world_to_screen(p2, rect);
-                    painter.line_segment([s1, s2], stroke);
                 }
             }
-            Geometry::Polyline(polyline) => {
-                if polyline.vertices.len() < 2 {
-                    return;
-                }
-                
-                for i in 0..polyline.segment_count() {
-                    let v1 = &polyline.vertices[i];
-                    let v2 = &polyline.vertices[(i + 1) % polyline.vertices.len()];
-                    
-                    let s1 = self.world_to_screen(v1.point, rect);
-                    let s2 = self.world_to_screen(v2.point, rect);
-                    painter.line_segment([s1, s2], stroke);
+            Geometry::MText(mtext) => {
+                // 按排版结果分解，带颜色的片段使用自身颜色
+                for (part, part_color) in mtext.explode_with_colors() {
+                    self.draw_geometry(painter, rect, &part, part_color.unwrap_or(color));
                 }
             }
-            Geometry::Text(text) => {
-                // 简化的文本绘制
-                let screen = self.world_to_screen(text.position, rect);
-                painter.text(
-                    screen,
-                    egui::Align2::LEFT_BOTTOM,
-                    &text.content,
-                    egui::FontId::proportional(12.0),
-                    stroke_color,
-                );
-            }
             Geometry::Dimension(dim) => {
-                // 简化的标注绘制
-                let p1 = self.world_to_screen(dim.definition_point1, rect);
-                let p2 = self.world_to_screen(dim.definition_point2, rect);
-                let line_loc = self.world_to_screen(dim.line_location, rect);
-                
-                // 绘制标注线
-                painter.line_segment([p1, line_loc], stroke);
-                painter.line_segment([p2, line_loc], stroke);
-                // 绘制文本
-                let distance = (dim.definition_point2 - dim.definition_point1).norm();
-                painter.text(
-                    line_loc,
-                    egui::Align2::CENTER_BOTTOM,
-                    format!("{:.2}", distance),
-                    egui::FontId::proportional(10.0),
-                    stroke_color,
-                );
+                // 按标注样式分解为线、箭头和文字
+                let style = self.document.dim_styles.resolve(&dim.style_name);
+                for mut part in dim.explode(style) {
+                    self.document.text_styles.update_fonts(&mut part);
+                    self.draw_geometry(painter, rect, &part, color);
+                }
             }
-            Geometry::Ellipse(ellipse) => {
-                // 用线段近似椭圆
-                let segments = 32;
-                let angle_step = std::f64::consts::TAU / segments as f64;
-                let major_len = ellipse.major_axis.norm();
-                let minor_len = major_len * ellipse.ratio;
-                
-                for i in 0..segments {
-                    let a1 = i as f64 * angle_step;
-                    let a2 = (i + 1) as f64 * angle_step;
-                    
-                    let p1 = Point2::new(
-                        ellipse.center.x + major_len * a1.cos(),
-                        ellipse.center.y + minor_len * a1.sin(),
-                    );
-                    let p2 = Point2::new(
-                        ellipse.center.x + major_len * a2.cos(),
-                        ellipse.center.y + minor_len * a2.sin(),
-                    );
-                    
-                    let s1 = self.world_to_screen(p1, rect);
-                    let s2 = self.world_to_screen(p2, rect);
-                    painter.line_segment([s1, s2], stroke);
+            Geometry::Insert(reference) => {
+                // 块参照在绘制前已按块表展开，到这里说明块定义缺失：只画插入点标记
+                for p in reference.all_insertion_points() {
+                    let screen = self.world_to_screen(p, rect);
+                    let size = 5.0;
+                    painter.line_segment([screen + egui::vec2(-size, -size), screen + egui::vec2(size, size)], stroke);
+                    painter.line_segment([screen + egui::vec2(-size, size), screen + egui::vec2(size, -size)], stroke);
                 }
             }
-            // 其他几何类型暂不渲染详细图形
-            Geometry::Spline(_) | Geometry::Hatch(_) | Geometry::Leader(_) => {
-                // TODO: 实现详细渲染
+            Geometry::Tolerance(tolerance) => {
+                // 特征控制框分解为框格线、符号、文字和引线
+                for part in tolerance.explode() {
+                    self.draw_geometry(painter, rect, &part, color);
+                }
             }
         }
     }
 
+    /// 曲线细分的弦高容差（随缩放变化，偏差不超过半个像素）
+    fn tessellation_tolerance(&self) -> f64 {
+        tessellation::screen_tolerance(1.0 / self.camera_zoom)
+    }
+
+    /// 绘制细分后的折线
+    fn draw_paths(&self, painter: &egui::Painter, rect: &egui::Rect, paths: &[Vec<Point2>], color: Color) {
+        let stroke = egui::Stroke::new(1.5, egui::Color32::from_rgb(color.r, color.g, color.b));
+        for path in paths {
+            let points: Vec<egui::Pos2> = path.iter().map(|p| self.world_to_screen(*p, rect)).collect();
+            painter.add(egui::Shape::line(points, stroke));
+        }
+    }
+
     /// 绘制十字光标
     fn draw_crosshair(&self, painter: &egui::Painter, rect: &egui::Rect, world_pos: Point2) {
         let screen = self.world_to_screen(world_pos, rect);
@@ -962,6 +893,72 @@ impl ZcadApp {
         }
     }
 
+    /// 编辑鼠标处的文字或多行文字
+    fn begin_text_edit(&mut self) {
+        let world_pos = self.ui_state.mouse_world_pos;
+        let state = self
+            .document
+            .query_point(&world_pos, 5.0 / self.camera_zoom)
+            .into_iter()
+            .find_map(EditState::edit_text);
+        if let Some(state) = state {
+            self.ui_state.edit_state = state;
+            self.ui_state.status_message = "编辑文字".to_string();
+        }
+    }
+
+    /// 文字编辑窗口：多行文字直接编辑格式代码
+    fn show_text_editor(&mut self, ctx: &egui::Context) {
+        let EditState::TextEdit { content, height, width, .. } = &mut self.ui_state.edit_state else {
+            return;
+        };
+        let mut apply = false;
+        let mut cancel = false;
+        egui::Window::new("编辑文字").collapsible(false).show(ctx, |ui| {
+            if width.is_some() {
+                ui.label("\\P 分段，{\\H2x;…} 字高，{\\C1;…} 颜色，\\S1/2; 分数");
+                ui.add(egui::TextEdit::multiline(content).desired_rows(6));
+            } else {
+                ui.text_edit_singleline(content);
+            }
+            ui.horizontal(|ui| {
+                ui.label("字高");
+                ui.add(egui::DragValue::new(height).speed(0.1).range(0.01..=f64::MAX));
+                if let Some(width) = width {
+                    ui.label("宽度");
+                    ui.add(egui::DragValue::new(width).speed(0.5).range(0.0..=f64::MAX));
+                }
+            });
+            ui.horizontal(|ui| {
+                apply = ui.button("确定").clicked();
+                cancel = ui.button("取消").clicked();
+            });
+        });
+
+        if apply {
+            self.apply_text_edit();
+        } else if cancel {
+            self.ui_state.edit_state = EditState::Idle;
+        }
+    }
+
+    /// 应用文字编辑并记录历史
+    fn apply_text_edit(&mut self) {
+        if let EditState::TextEdit { entity_id, .. } = &self.ui_state.edit_state {
+            let entity_id = *entity_id;
+            if let Some(entity) = self.document.get_entity(&entity_id).cloned() {
+                if let Some(geometry) = self.ui_state.edit_state.apply_text_edit(&entity.geometry) {
+                    let mut transaction = Transaction::new(&mut self.document, "编辑文字");
+                    transaction.set_geometry(&entity_id, geometry);
+                    let changes = transaction.commit();
+                    self.record_changes(changes);
+                    self.ui_state.status_message = "文字已修改".to_string();
+                }
+            }
+        }
+        self.ui_state.edit_state = EditState::Idle;
+    }
+
     /// 缩放到适合视图
     fn zoom_to_fit(&mut self) {
         if let Some(bounds) = self.document.bounds() {
@@ -1116,6 +1113,12 @@ impl eframe::App for ZcadApp {
                         format!("顶点数: {}", p.vertex_count()),
                         format!("长度: {:.3}", p.length()),
                     ],
+                    Geometry::Insert(r) => vec![
+                        format!("块名: {}", r.block_name),
+                        format!("插入点: ({:.2}, {:.2})", r.insertion_point.x, r.insertion_point.y),
+                        format!("比例: {:.3} x {:.3}", r.scale_x, r.scale_y),
+                        format!("旋转: {:.2}°", r.rotation.to_degrees()),
+                    ],
                     _ => vec![],
                 };
                 (name, props)
@@ -1127,6 +1130,9 @@ impl eframe::App for ZcadApp {
             .map(|l| (l.name.clone(), l.color.r, l.color.g, l.color.b, l.name == self.document.layers.current_layer().name))
             .collect();
 
+        // ===== 文字编辑窗口 =====
+        self.show_text_editor(ctx);
+
         // ===== 顶部菜单 =====
         #[allow(deprecated)]
         egui::TopBottomPanel::top("menu").show(ctx, |ui| {
@@ -1161,6 +1167,10 @@ impl eframe::App for ZcadApp {
                         self.delete_selected_entities();
                         ui.close();
                     }
+                    if ui.button("💥 分解 (X)").clicked() {
+                        self.explode_selected_entities();
+                        ui.close();
+                    }
                     ui.separator();
                     if ui.button("↩ 撤销 (Ctrl+Z)").clicked() {
                         self.do_undo();
@@ -1360,13 +1370,22 @@ impl eframe::App for ZcadApp {
                     self.handle_left_click();
                 }
 
+                // 双击文字进入编辑
+                if response.double_clicked_by(egui::PointerButton::Primary) {
+                    self.begin_text_edit();
+                }
+
                 // 处理右键（结束多段线或取消）
                 if response.clicked_by(egui::PointerButton::Secondary) {
                     self.handle_right_click();
                 }
 
-                // 处理键盘快捷键
+                // 处理键盘快捷键（文本框获得焦点时除 Esc 外不响应）
+                let typing = ui.ctx().wants_keyboard_input();
                 ui.input(|i| {
+                    if typing && !i.key_pressed(egui::Key::Escape) {
+                        return;
+                    }
                     // 文件操作
                     if i.modifiers.command && i.key_pressed(egui::Key::N) {
                         self.document = Document::new();
@@ -1391,6 +1410,9 @@ impl eframe::App for ZcadApp {
                     if i.key_pressed(egui::Key::Delete) || i.key_pressed(egui::Key::Backspace) {
                         self.delete_selected_entities();
                     }
+                    if i.key_pressed(egui::Key::X) {
+                        self.explode_selected_entities();
+                    }
                     // 撤销 Ctrl+Z
                     if i.modifiers.command && i.key_pressed(egui::Key::Z) && !i.modifiers.shift {
                         self.do_undo();
@@ -1445,18 +1467,46 @@ impl eframe::App for ZcadApp {
                 // 绘制网格
                 self.draw_grid(&painter, &rect);
 
-                // 绘制所有实体
+                // 绘制所有实体，曲线的细分结果按实体缓存（本帧未绘制的条目被清除）
+                let mut tessellation_cache = std::mem::take(&mut self.tessellation_cache);
+                tessellation_cache.evict_unused();
+                let tolerance = self.tessellation_tolerance();
                 for entity in self.document.all_entities() {
-                    let color = if self.ui_state.selected_entities.contains(&entity.id) {
-                        Color::from_hex(0x00FF00)
-                    } else if entity.properties.color.is_by_layer() {
-                        self.document.layers.get_layer_by_id(entity.layer_id)
-                            .map(|l| l.color).unwrap_or(Color::WHITE)
-                    } else {
-                        entity.properties.color
+                    let selected = self.ui_state.selected_entities.contains(&entity.id);
+                    let color_of = |e: &Entity| {
+                        if selected {
+                            Color::from_hex(0x00FF00)
+                        } else if e.properties.color.is_by_layer() {
+                            self.document.layers.get_layer_by_id(e.layer_id)
+                                .map(|l| l.color).unwrap_or(Color::WHITE)
+                        } else {
+                            e.properties.color
+                        }
                     };
-                    self.draw_geometry(&painter, &rect, &entity.geometry, color);
+                    match &entity.geometry {
+                        // 块参照展开为块定义中的图元（ByBlock 属性已继承）
+                        Geometry::Insert(_) => match self.document.blocks.resolve(entity) {
+                            Ok(parts) => {
+                                for part in &parts {
+                                    self.draw_geometry(&painter, &rect, &part.geometry, color_of(part));
+                                }
+                            }
+                            Err(_) => self.draw_geometry(&painter, &rect, &entity.geometry, color_of(entity)),
+                        },
+                        Geometry::Circle(_)
+                        | Geometry::Arc(_)
+                        | Geometry::Polyline(_)
+                        | Geometry::Ellipse(_)
+                        | Geometry::Spline(_)
+                        | Geometry::Hatch(_)
+                        | Geometry::Leader(_) => {
+                            let paths = tessellation_cache.get(entity, tolerance);
+                            self.draw_paths(&painter, &rect, paths, color_of(entity));
+                        }
+                        geometry => self.draw_geometry(&painter, &rect, geometry, color_of(entity)),
+                    }
                 }
+                self.tessellation_cache = tessellation_cache;
 
                 // 绘制预览
                 self.draw_preview(&painter, &rect);
@@ -1494,20 +1544,7 @@ fn setup_chinese_fonts(ctx: &egui::Context) {
     let mut fonts = egui::FontDefinitions::default();
     
     // 尝试从系统加载中文字体
-    let font_paths = [
-        // macOS
-        "/System/Library/Fonts/PingFang.ttc",
-        "/System/Library/Fonts/STHeiti Light.ttc",
-        "/System/Library/Fonts/Hiragino Sans GB.ttc",
-        // Linux
-        "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
-        "/usr/share/fonts/truetype/droid/DroidSansFallbackFull.ttf",
-        // Windows
-        "C:\\Windows\\Fonts\\msyh.ttc",
-        "C:\\Windows\\Fonts\\simsun.ttc",
-    ];
-    
-    for path in font_paths {
+    for path in CJK_FONT_PATHS {
         if let Ok(font_data) = std::fs::read(path) {
             fonts.font_data.insert(
                 "chinese".to_owned(),
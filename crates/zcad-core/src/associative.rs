@@ -0,0 +1,173 @@
+//! 关联标注
+//!
+//! 标注的测量点可以引用其它实体上的特征点（端点、中点、圆心、象限点），
+//! 被引用的实体修改后，标注按引用重新计算测量点。
+//!
+//! 特征点按固定顺序编号（如直线的起点为 0、终点为 1），
+//! 关联点只保存实体 ID、捕捉类型和序号，不保存坐标。
+
+use crate::entity::EntityId;
+use crate::geometry::{Geometry, Polyline};
+use crate::math::{Point2, Vector2};
+use crate::snap::SnapType;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::FRAC_PI_2;
+
+/// 关联点：引用实体上的一个特征点
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssociativePoint {
+    /// 被引用的实体
+    pub entity: EntityId,
+    /// 特征点类型（端点、中点、圆心、象限点）
+    pub snap: SnapType,
+    /// 同类特征点中的序号
+    pub index: usize,
+}
+
+impl AssociativePoint {
+    pub fn new(entity: EntityId, snap: SnapType, index: usize) -> Self {
+        Self { entity, snap, index }
+    }
+
+    /// 根据捕捉结果创建关联点
+    ///
+    /// 在几何体上查找与捕捉点重合的同类特征点；
+    /// 不支持关联的捕捉类型（交点、最近点等）或找不到特征点时返回 None
+    pub fn from_snap(entity: EntityId, geometry: &Geometry, snap: SnapType, point: Point2, tolerance: f64) -> Option<Self> {
+        feature_points(geometry, snap)
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (i, (p - point).norm()))
+            .filter(|(_, d)| *d <= tolerance)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(index, _)| Self::new(entity, snap, index))
+    }
+
+    /// 在被引用实体的当前几何体上求关联点的位置
+    pub fn evaluate(&self, geometry: &Geometry) -> Option<Point2> {
+        feature_points(geometry, self.snap).get(self.index).copied()
+    }
+}
+
+/// 几何体上指定类型的全部特征点，按固定顺序排列
+///
+/// 支持的类型：
+/// - 端点：直线、圆弧、非闭合椭圆弧和样条的起点/终点，多段线的顶点，点
+/// - 中点：直线、圆弧、多段线各段
+/// - 圆心：圆、圆弧、椭圆
+/// - 象限点：圆、圆弧、椭圆的 0°/90°/180°/270° 位置
+pub fn feature_points(geometry: &Geometry, snap: SnapType) -> Vec<Point2> {
+    match (snap, geometry) {
+        (SnapType::Endpoint, Geometry::Point(p)) => vec![p.position],
+        (SnapType::Endpoint, Geometry::Line(line)) => vec![line.start, line.end],
+        (SnapType::Endpoint, Geometry::Arc(arc)) => vec![arc.start_point(), arc.end_point()],
+        (SnapType::Endpoint, Geometry::Polyline(polyline)) => {
+            polyline.vertices.iter().map(|v| v.point).collect()
+        }
+        (SnapType::Endpoint, Geometry::Ellipse(ellipse)) if !ellipse.is_full() => {
+            vec![ellipse.start_point(), ellipse.end_point()]
+        }
+        (SnapType::Endpoint, Geometry::Spline(spline)) if !spline.closed => {
+            let (start, end) = spline.param_range();
+            vec![spline.point_at_param(start), spline.point_at_param(end)]
+        }
+
+        (SnapType::Midpoint, Geometry::Line(line)) => vec![line.midpoint()],
+        (SnapType::Midpoint, Geometry::Arc(arc)) => {
+            vec![arc.center + direction(arc.start_angle + arc.sweep_angle() / 2.0) * arc.radius]
+        }
+        (SnapType::Midpoint, Geometry::Polyline(polyline)) => polyline_midpoints(polyline),
+
+        (SnapType::Center, Geometry::Circle(circle)) => vec![circle.center],
+        (SnapType::Center, Geometry::Arc(arc)) => vec![arc.center],
+        (SnapType::Center, Geometry::Ellipse(ellipse)) => vec![ellipse.center],
+
+        (SnapType::Quadrant, Geometry::Circle(circle)) => quadrants(circle.center, circle.radius),
+        (SnapType::Quadrant, Geometry::Arc(arc)) => quadrants(arc.center, arc.radius),
+        (SnapType::Quadrant, Geometry::Ellipse(ellipse)) => (0..4)
+            .map(|i| ellipse.point_at_param(i as f64 * FRAC_PI_2))
+            .collect(),
+
+        _ => Vec::new(),
+    }
+}
+
+fn direction(angle: f64) -> Vector2 {
+    Vector2::new(angle.cos(), angle.sin())
+}
+
+fn quadrants(center: Point2, radius: f64) -> Vec<Point2> {
+    (0..4).map(|i| center + direction(i as f64 * FRAC_PI_2) * radius).collect()
+}
+
+/// 多段线各段的中点（圆弧段取弧上的中点）
+fn polyline_midpoints(polyline: &Polyline) -> Vec<Point2> {
+    let n = polyline.vertices.len();
+    (0..polyline.segment_count())
+        .map(|i| {
+            let v1 = &polyline.vertices[i];
+            let v2 = &polyline.vertices[(i + 1) % n];
+            let chord = v2.point - v1.point;
+            let mid = v1.point + chord * 0.5;
+            // 弧段中点位于弦中点的法向上，弧高 = 半弦长 × bulge
+            let normal = Vector2::new(chord.y, -chord.x) * 0.5;
+            mid + normal * v1.bulge
+        })
+        .collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::Entity;
+    use crate::geometry::{Circle, Dimension, Line, PolylineVertex};
+
+    #[test]
+    fn test_feature_points_and_from_snap() {
+        let polyline = Geometry::Polyline(Polyline::new(
+            vec![
+                PolylineVertex::with_bulge(Point2::new(0.0, 0.0), 1.0),
+                PolylineVertex::new(Point2::new(10.0, 0.0)),
+            ],
+            false,
+        ));
+        // bulge = 1 为逆时针半圆，中点在弦的右侧
+        let mid = feature_points(&polyline, SnapType::Midpoint);
+        assert!((mid[0] - Point2::new(5.0, -5.0)).norm() < 1e-9);
+
+        let entity = Entity::new(polyline);
+        let anchor = AssociativePoint::from_snap(entity.id, &entity.geometry, SnapType::Endpoint, Point2::new(10.0, 0.1), 0.5)
+            .unwrap();
+        assert_eq!(anchor.index, 1);
+        assert!(AssociativePoint::from_snap(entity.id, &entity.geometry, SnapType::Center, Point2::new(5.0, 0.0), 0.5).is_none());
+
+        let circle = Geometry::Circle(Circle::new(Point2::new(1.0, 1.0), 2.0));
+        let quadrants = feature_points(&circle, SnapType::Quadrant);
+        assert!((quadrants[1] - Point2::new(1.0, 3.0)).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_dimension_follows_references() {
+        let line = Entity::new(Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0))));
+        let mut dim = Dimension::new(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0), Point2::new(5.0, 5.0))
+            .with_associations(
+                Some(AssociativePoint::new(line.id, SnapType::Endpoint, 0)),
+                Some(AssociativePoint::new(line.id, SnapType::Endpoint, 1)),
+            );
+
+        // 直线绕起点旋转 90° 并加长
+        let moved = Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(0.0, 20.0)));
+        assert!(dim.update_associations(|id| (*id == line.id).then_some(&moved)));
+        assert_eq!(dim.definition_point2, Point2::new(0.0, 20.0));
+        assert!((dim.measurement() - 20.0).abs() < 1e-9);
+        // 标注线仍在测量方向左侧 5 个单位
+        assert!((dim.line_location - Point2::new(-5.0, 10.0)).norm() < 1e-9);
+        assert!(!dim.update_associations(|id| (*id == line.id).then_some(&moved)));
+
+        // 被引用实体不存在时解除关联并保持原位
+        assert!(dim.update_associations(|_| None));
+        assert!(!dim.is_associative());
+        assert_eq!(dim.definition_point2, Point2::new(0.0, 20.0));
+    }
+}
@@ -2,9 +2,18 @@
 //!
 //! 块是一组实体的集合，可以被重复使用。
 //! 块参照是块的一个实例，可以有自己的位置、旋转和缩放。
+//!
+//! 块参照以 `Geometry::Insert` 的形式存放在文档中，绘制、拾取和分解时
+//! 通过 [`BlockTable::resolve`] 展开为世界坐标下的基本图元。块可以嵌套，
+//! 展开时会检测循环引用。
+//!
+//! 块定义可以带属性定义（ATTDEF），块参照保存各自的属性值（ATTRIB）。
 
-use crate::entity::Entity;
-use crate::math::{Point2, Vector2};
+use crate::entity::{Entity, EntityId};
+use crate::geometry::{Geometry, Text};
+use crate::math::{BoundingBox2, Point2, Vector2, EPSILON};
+use crate::properties::{LineType, LineWeight};
+use crate::transform::{transform_attributes, transform_text, Transform2D};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -35,6 +44,9 @@ pub struct Block {
     pub description: String,
     /// 是否是匿名块（用于 Hatch 等）
     pub is_anonymous: bool,
+    /// 属性定义
+    #[serde(default)]
+    pub attribute_definitions: Vec<AttributeDefinition>,
 }
 
 impl Block {
@@ -54,6 +66,7 @@ impl Block {
             entities: Vec::new(),
             description: String::new(),
             is_anonymous: false,
+            attribute_definitions: Vec::new(),
         }
     }
 
@@ -67,6 +80,35 @@ impl Block {
         self.entities.extend(entities);
     }
 
+    /// 添加属性定义
+    pub fn add_attribute_definition(&mut self, definition: AttributeDefinition) {
+        self.attribute_definitions.push(definition);
+    }
+
+    /// 按属性定义为块参照生成属性值（使用默认值，常量属性除外）
+    pub fn default_attributes(&self, reference: &BlockReference) -> Vec<BlockAttribute> {
+        let Some(transform) = reference.instance_transforms(self.base_point).into_iter().next() else {
+            return Vec::new();
+        };
+        self.attribute_definitions
+            .iter()
+            .filter(|def| !def.constant)
+            .map(|def| BlockAttribute {
+                tag: def.tag.clone(),
+                text: transform_text(&def.text, &transform),
+                invisible: def.invisible,
+            })
+            .collect()
+    }
+
+    /// 可见的常量属性文字（块坐标）
+    fn constant_attribute_texts(&self) -> impl Iterator<Item = &Text> {
+        self.attribute_definitions
+            .iter()
+            .filter(|def| def.constant && !def.invisible)
+            .map(|def| &def.text)
+    }
+
     /// 获取实体数量
     pub fn entity_count(&self) -> usize {
         self.entities.len()
@@ -108,6 +150,64 @@ pub struct BlockReference {
     pub column_spacing: f64,
     /// 行间距
     pub row_spacing: f64,
+    /// 属性值
+    #[serde(default)]
+    pub attributes: Vec<BlockAttribute>,
+}
+
+/// 属性定义（ATTDEF）
+///
+/// 块中的可变文字字段，插入块时据此生成属性值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttributeDefinition {
+    /// 标记
+    pub tag: String,
+    /// 输入提示
+    pub prompt: String,
+    /// 文字（块坐标，内容为默认值）
+    pub text: Text,
+    /// 是否不可见
+    pub invisible: bool,
+    /// 是否为常量（直接随块定义显示，块参照中没有对应的属性值）
+    pub constant: bool,
+}
+
+impl AttributeDefinition {
+    pub fn new(tag: impl Into<String>, text: Text) -> Self {
+        Self {
+            tag: tag.into(),
+            prompt: String::new(),
+            text,
+            invisible: false,
+            constant: false,
+        }
+    }
+}
+
+/// 块参照的属性值（ATTRIB）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockAttribute {
+    /// 标记
+    pub tag: String,
+    /// 文字（块参照所在坐标系，内容为属性值）
+    pub text: Text,
+    /// 是否不可见
+    pub invisible: bool,
+}
+
+impl BlockAttribute {
+    pub fn new(tag: impl Into<String>, text: Text) -> Self {
+        Self {
+            tag: tag.into(),
+            text,
+            invisible: false,
+        }
+    }
+
+    /// 属性值
+    pub fn value(&self) -> &str {
+        &self.text.content
+    }
 }
 
 impl BlockReference {
@@ -123,6 +223,7 @@ impl BlockReference {
             row_count: 1,
             column_spacing: 0.0,
             row_spacing: 0.0,
+            attributes: Vec::new(),
         }
     }
 
@@ -161,6 +262,28 @@ impl BlockReference {
         self
     }
 
+    /// 设置属性值
+    pub fn with_attributes(mut self, attributes: Vec<BlockAttribute>) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
+    /// 按标记查找属性
+    pub fn attribute(&self, tag: &str) -> Option<&BlockAttribute> {
+        self.attributes.iter().find(|a| a.tag.eq_ignore_ascii_case(tag))
+    }
+
+    /// 修改属性值，找不到标记时返回 false
+    pub fn set_attribute_value(&mut self, tag: &str, value: impl Into<String>) -> bool {
+        match self.attributes.iter_mut().find(|a| a.tag.eq_ignore_ascii_case(tag)) {
+            Some(attribute) => {
+                attribute.text.content = value.into();
+                true
+            }
+            None => false,
+        }
+    }
+
     /// 变换点（从块坐标到世界坐标）
     pub fn transform_point(&self, point: Point2, base_point: Point2) -> Point2 {
         // 相对于基点
@@ -212,6 +335,33 @@ impl BlockReference {
         
         points
     }
+
+    /// 获取每个阵列实例的变换（从块坐标到世界坐标）
+    ///
+    /// 与 `transform_point` 一致：相对基点 → 缩放 → 旋转 → 平移到插入点
+    pub fn instance_transforms(&self, base_point: Point2) -> Vec<Transform2D> {
+        let local = Transform2D::rotation(self.rotation)
+            .then(&Transform2D::scale(self.scale_x, self.scale_y))
+            .then(&Transform2D::translation(-base_point.x, -base_point.y));
+
+        self.all_insertion_points()
+            .into_iter()
+            .map(|p| Transform2D::translation(p.x, p.y).then(&local))
+            .collect()
+    }
+}
+
+/// 块操作错误
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum BlockError {
+    #[error("Block not found: {0}")]
+    BlockNotFound(String),
+
+    #[error("Circular block reference: {}", .0.join(" -> "))]
+    CircularReference(Vec<String>),
+
+    #[error("Entity is not a block reference")]
+    NotABlockReference,
 }
 
 /// 块表 - 管理所有块定义
@@ -288,6 +438,198 @@ impl BlockTable {
     pub fn iter(&self) -> impl Iterator<Item = &Block> {
         self.blocks.values()
     }
+
+    /// 查找从指定块出发的循环引用
+    ///
+    /// 返回构成环的块名称链（首尾相同），没有循环时返回 None
+    pub fn find_cycle(&self, name: &str) -> Option<Vec<String>> {
+        self.find_cycle_from(name, &mut Vec::new())
+    }
+
+    fn find_cycle_from(&self, name: &str, stack: &mut Vec<String>) -> Option<Vec<String>> {
+        if let Some(pos) = stack.iter().position(|n| n == name) {
+            let mut cycle = stack[pos..].to_vec();
+            cycle.push(name.to_string());
+            return Some(cycle);
+        }
+
+        let block = self.get_block(name)?;
+        stack.push(name.to_string());
+        for entity in &block.entities {
+            if let Geometry::Insert(reference) = &entity.geometry {
+                if let Some(cycle) = self.find_cycle_from(&reference.block_name, stack) {
+                    return Some(cycle);
+                }
+            }
+        }
+        stack.pop();
+        None
+    }
+
+    /// 检查在块 `target` 中插入块 `inserted` 是否会形成循环引用
+    pub fn would_create_cycle(&self, target: &str, inserted: &str) -> bool {
+        inserted == target || self.references_block(inserted, target)
+    }
+
+    /// 块 `name` 是否直接或间接地参照了块 `target`
+    fn references_block(&self, name: &str, target: &str) -> bool {
+        let mut visited = Vec::new();
+        let mut pending = vec![name.to_string()];
+        while let Some(current) = pending.pop() {
+            if visited.contains(&current) {
+                continue;
+            }
+            if let Some(block) = self.get_block(&current) {
+                for entity in &block.entities {
+                    if let Geometry::Insert(reference) = &entity.geometry {
+                        if reference.block_name == target {
+                            return true;
+                        }
+                        pending.push(reference.block_name.clone());
+                    }
+                }
+            }
+            visited.push(current);
+        }
+        false
+    }
+
+    /// 将实体解析为世界坐标下的基本图元
+    ///
+    /// 块参照递归展开为块定义中的实体（应用插入变换、阵列以及 ByBlock 属性继承），
+    /// 其它实体原样返回。解析结果仅用于显示和查询，子实体保留块定义中的 ID。
+    pub fn resolve(&self, entity: &Entity) -> Result<Vec<Entity>, BlockError> {
+        let mut resolved = Vec::new();
+        self.resolve_into(entity, &Transform2D::identity(), &mut Vec::new(), &mut resolved)?;
+        Ok(resolved)
+    }
+
+    fn resolve_into(
+        &self,
+        entity: &Entity,
+        transform: &Transform2D,
+        stack: &mut Vec<String>,
+        resolved: &mut Vec<Entity>,
+    ) -> Result<(), BlockError> {
+        let Geometry::Insert(reference) = &entity.geometry else {
+            let mut leaf = entity.clone();
+            if !stack.is_empty() {
+                leaf.geometry = entity.geometry.transform(transform);
+            }
+            resolved.push(leaf);
+            return Ok(());
+        };
+
+        if let Some(pos) = stack.iter().position(|n| *n == reference.block_name) {
+            let mut cycle = stack[pos..].to_vec();
+            cycle.push(reference.block_name.clone());
+            return Err(BlockError::CircularReference(cycle));
+        }
+        let block = self
+            .get_block(&reference.block_name)
+            .ok_or_else(|| BlockError::BlockNotFound(reference.block_name.clone()))?;
+
+        stack.push(block.name.clone());
+        for instance in reference.instance_transforms(block.base_point) {
+            let instance = transform.then(&instance);
+            for child in &block.entities {
+                self.resolve_into(&inherit_from_reference(child, entity), &instance, stack, resolved)?;
+            }
+            for text in block.constant_attribute_texts() {
+                resolved.push(attribute_entity(entity, transform_text(text, &instance)));
+            }
+        }
+        stack.pop();
+
+        // 属性值属于块参照本身，阵列时只显示一组
+        for attribute in reference.attributes.iter().filter(|a| !a.invisible) {
+            resolved.push(attribute_entity(entity, transform_text(&attribute.text, transform)));
+        }
+        Ok(())
+    }
+
+    /// 分解块参照（只展开一层）
+    ///
+    /// 生成的实体使用新的 ID，保留块定义中的属性和图层（未指定图层的实体放到块参照所在图层）。
+    /// 嵌套的块参照仍保持为块参照；若组合后的变换无法用块参照表示（如非均匀缩放后旋转），
+    /// 则展开为基本图元。
+    pub fn explode(&self, entity: &Entity) -> Result<Vec<Entity>, BlockError> {
+        let Geometry::Insert(reference) = &entity.geometry else {
+            return Err(BlockError::NotABlockReference);
+        };
+        let block = self
+            .get_block(&reference.block_name)
+            .ok_or_else(|| BlockError::BlockNotFound(reference.block_name.clone()))?;
+        if let Some(cycle) = self.find_cycle(&block.name) {
+            return Err(BlockError::CircularReference(cycle));
+        }
+
+        let mut exploded = Vec::new();
+        for instance in reference.instance_transforms(block.base_point) {
+            for child in &block.entities {
+                let mut part = child.clone();
+                if part.layer_id.is_null() {
+                    part.layer_id = entity.layer_id;
+                }
+                match &child.geometry {
+                    Geometry::Insert(nested) => match compose_reference(nested, &instance) {
+                        Some(composed) => {
+                            part.geometry = Geometry::Insert(composed);
+                            exploded.push(part);
+                        }
+                        None => {
+                            let mut stack = vec![block.name.clone()];
+                            self.resolve_into(&part, &instance, &mut stack, &mut exploded)?;
+                        }
+                    },
+                    geometry => {
+                        part.geometry = geometry.transform(&instance);
+                        exploded.push(part);
+                    }
+                }
+            }
+            for text in block.constant_attribute_texts() {
+                exploded.push(attribute_entity(entity, transform_text(text, &instance)));
+            }
+        }
+        // 属性值分解为普通文字
+        for attribute in reference.attributes.iter().filter(|a| !a.invisible) {
+            exploded.push(attribute_entity(entity, attribute.text.clone()));
+        }
+
+        for part in &mut exploded {
+            part.id = EntityId::new();
+        }
+        Ok(exploded)
+    }
+
+    /// 计算实体的包围盒，块参照按块定义展开后计算
+    ///
+    /// 块定义缺失或存在循环引用时退回到插入点的包围盒
+    pub fn entity_bounding_box(&self, entity: &Entity) -> BoundingBox2 {
+        if !matches!(entity.geometry, Geometry::Insert(_)) {
+            return entity.bounding_box();
+        }
+        match self.resolve(entity) {
+            Ok(parts) if !parts.is_empty() => parts
+                .iter()
+                .fold(BoundingBox2::empty(), |bbox, part| bbox.union(&part.bounding_box())),
+            _ => entity.bounding_box(),
+        }
+    }
+
+    /// 检查点是否在实体上（考虑容差），块参照按块定义展开后检测
+    pub fn entity_contains_point(&self, entity: &Entity, point: &Point2, tolerance: f64) -> bool {
+        if !matches!(entity.geometry, Geometry::Insert(_)) {
+            return entity.geometry.contains_point(point, tolerance);
+        }
+        match self.resolve(entity) {
+            Ok(parts) => parts
+                .iter()
+                .any(|part| part.geometry.contains_point(point, tolerance)),
+            Err(_) => entity.geometry.contains_point(point, tolerance),
+        }
+    }
 }
 
 impl Default for BlockTable {
@@ -296,10 +638,75 @@ impl Default for BlockTable {
     }
 }
 
+/// 0 层的固定 ID（见 `Layer::default_layer`）
+const LAYER_ZERO_ID: EntityId = EntityId::from_raw(1, 0);
+
+/// 块中的实体继承块参照的属性
+///
+/// ByBlock 的颜色、线型、线宽取块参照实体的值；0 层或未指定图层的实体放到块参照所在图层。
+fn inherit_from_reference(child: &Entity, reference: &Entity) -> Entity {
+    let mut entity = child.clone();
+    let properties = &mut entity.properties;
+    if properties.color.is_by_block() {
+        properties.color = reference.properties.color;
+    }
+    if properties.line_type == LineType::ByBlock {
+        properties.line_type = reference.properties.line_type.clone();
+    }
+    if properties.line_weight == LineWeight::ByBlock {
+        properties.line_weight = reference.properties.line_weight;
+    }
+    if entity.layer_id.is_null() || entity.layer_id == LAYER_ZERO_ID {
+        entity.layer_id = reference.layer_id;
+    }
+    entity.visible &= reference.visible;
+    entity.locked |= reference.locked;
+    entity
+}
+
+/// 属性文字实体，使用块参照的图层和属性
+fn attribute_entity(reference: &Entity, text: Text) -> Entity {
+    let mut entity = reference.clone();
+    entity.geometry = Geometry::Text(text);
+    entity
+}
+
+/// 将变换作用到块参照上，得到等价的块参照
+///
+/// 组合后的线性部分必须能分解为“旋转 × 轴向缩放”，否则返回 None
+pub(crate) fn compose_reference(reference: &BlockReference, transform: &Transform2D) -> Option<BlockReference> {
+    if reference.scale_x.abs() < EPSILON || reference.scale_y.abs() < EPSILON {
+        return None;
+    }
+    let local = Transform2D::rotation(reference.rotation).then(&Transform2D::scale(reference.scale_x, reference.scale_y));
+    let u = transform.transform_vector(&local.transform_vector(&Vector2::x()));
+    let v = transform.transform_vector(&local.transform_vector(&Vector2::y()));
+    if u.norm() < EPSILON || u.dot(&v).abs() > 1e-9 * u.norm() * v.norm() {
+        return None;
+    }
+
+    let rotation = u.y.atan2(u.x);
+    let scale_x = u.norm();
+    let scale_y = v.dot(&Vector2::new(-rotation.sin(), rotation.cos()));
+
+    Some(BlockReference {
+        block_name: reference.block_name.clone(),
+        insertion_point: transform.transform_point(&reference.insertion_point),
+        scale_x,
+        scale_y,
+        rotation,
+        column_count: reference.column_count,
+        row_count: reference.row_count,
+        column_spacing: reference.column_spacing * scale_x / reference.scale_x,
+        row_spacing: reference.row_spacing * scale_y / reference.scale_y,
+        attributes: transform_attributes(&reference.attributes, transform),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::geometry::{Geometry, Line};
+    use crate::geometry::{Arc, Line};
 
     #[test]
     fn test_block_creation() {
@@ -345,4 +752,212 @@ mod tests {
         assert!(table.contains("Block1"));
         assert!(table.contains("Block2"));
     }
+
+    fn insert(reference: BlockReference) -> Entity {
+        Entity::new(Geometry::Insert(reference))
+    }
+
+    /// 块 "Unit"：从基点 (1, 1) 出发的单位线段；块 "Pair"：两个 "Unit" 参照
+    fn nested_table() -> BlockTable {
+        let mut table = BlockTable::new();
+        let mut unit = Block::new("Unit", Point2::new(1.0, 1.0));
+        unit.add_entity(Entity::new(Geometry::Line(Line::new(Point2::new(1.0, 1.0), Point2::new(2.0, 1.0)))));
+        table.add_block(unit);
+
+        let mut pair = Block::new("Pair", Point2::origin());
+        pair.add_entity(insert(BlockReference::new("Unit", Point2::new(0.0, 0.0))));
+        pair.add_entity(insert(BlockReference::new("Unit", Point2::new(0.0, 5.0)).with_uniform_scale(2.0)));
+        table.add_block(pair);
+        table
+    }
+
+    fn line_of(entity: &Entity) -> &Line {
+        match &entity.geometry {
+            Geometry::Line(line) => line,
+            other => panic!("expected line, got {}", other.type_name()),
+        }
+    }
+
+    #[test]
+    fn test_instance_transforms_match_transform_point() {
+        let reference = BlockReference::new("Test", Point2::new(100.0, 50.0))
+            .with_scale(2.0, 3.0)
+            .with_rotation_degrees(30.0)
+            .with_array(2, 3, 10.0, 20.0);
+        let base = Point2::new(5.0, -5.0);
+        let point = Point2::new(7.0, 2.0);
+
+        let transforms = reference.instance_transforms(base);
+        assert_eq!(transforms.len(), 6);
+        let first = transforms[0].transform_point(&point);
+        assert!((first - reference.transform_point(point, base)).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_resolve_nested_blocks() {
+        let table = nested_table();
+        let entity = insert(BlockReference::new("Pair", Point2::new(10.0, 0.0)).with_rotation_degrees(90.0));
+
+        let parts = table.resolve(&entity).unwrap();
+        assert_eq!(parts.len(), 2);
+
+        // 第一个 Unit：(0,0)-(1,0) 旋转 90° 后平移到 (10, 0)
+        let first = line_of(&parts[0]);
+        assert!((first.start - Point2::new(10.0, 0.0)).norm() < 1e-9);
+        assert!((first.end - Point2::new(10.0, 1.0)).norm() < 1e-9);
+
+        // 第二个 Unit：放大 2 倍并位于 (0, 5)，旋转后位于 (5, 0)
+        let second = line_of(&parts[1]);
+        assert!((second.start - Point2::new(5.0, 0.0)).norm() < 1e-9);
+        assert!((second.end - Point2::new(5.0, 2.0)).norm() < 1e-9);
+
+        let bbox = table.entity_bounding_box(&entity);
+        assert!((bbox.min - Point2::new(5.0, 0.0)).norm() < 1e-9);
+        assert!((bbox.max - Point2::new(10.0, 2.0)).norm() < 1e-9);
+
+        assert!(table.entity_contains_point(&entity, &Point2::new(5.0, 1.5), 0.01));
+        assert!(!table.entity_contains_point(&entity, &Point2::new(7.0, 1.0), 0.01));
+    }
+
+    #[test]
+    fn test_cycle_detection() {
+        let mut table = nested_table();
+        assert!(table.find_cycle("Pair").is_none());
+        assert!(table.would_create_cycle("Unit", "Pair"));
+        assert!(table.would_create_cycle("Unit", "Unit"));
+        assert!(!table.would_create_cycle("Pair", "Unit"));
+
+        // Unit 反过来参照 Pair，形成 Pair -> Unit -> Pair
+        table
+            .get_block_mut("Unit")
+            .unwrap()
+            .add_entity(insert(BlockReference::new("Pair", Point2::origin())));
+        assert_eq!(
+            table.find_cycle("Pair"),
+            Some(vec!["Pair".to_string(), "Unit".to_string(), "Pair".to_string()])
+        );
+
+        let entity = insert(BlockReference::new("Pair", Point2::origin()));
+        assert!(matches!(table.resolve(&entity), Err(BlockError::CircularReference(_))));
+        assert!(matches!(table.explode(&entity), Err(BlockError::CircularReference(_))));
+
+        // 块定义缺失时退回插入点包围盒
+        let missing = insert(BlockReference::new("Missing", Point2::new(3.0, 4.0)));
+        assert_eq!(table.resolve(&missing).unwrap_err(), BlockError::BlockNotFound("Missing".to_string()));
+        let bbox = table.entity_bounding_box(&missing);
+        assert_eq!(bbox.min, Point2::new(3.0, 4.0));
+    }
+
+    #[test]
+    fn test_explode_one_level_with_array() {
+        let table = nested_table();
+        let entity = insert(BlockReference::new("Pair", Point2::new(0.0, 0.0)).with_array(2, 1, 100.0, 0.0));
+
+        let parts = table.explode(&entity).unwrap();
+        assert_eq!(parts.len(), 4);
+        assert!(parts.iter().all(|p| p.id != entity.id));
+        assert!(parts.iter().all(|p| matches!(p.geometry, Geometry::Insert(_))));
+
+        let Geometry::Insert(nested) = &parts[3].geometry else { unreachable!() };
+        assert_eq!(nested.block_name, "Unit");
+        assert!((nested.insertion_point - Point2::new(100.0, 5.0)).norm() < 1e-9);
+        assert!((nested.scale_x - 2.0).abs() < 1e-9);
+
+        // 分解后再展开的结果与直接展开一致
+        let direct = table.resolve(&entity).unwrap();
+        let again: Vec<Entity> = parts.iter().flat_map(|p| table.resolve(p).unwrap()).collect();
+        assert_eq!(direct.len(), again.len());
+        for (a, b) in direct.iter().zip(&again) {
+            assert!((line_of(a).start - line_of(b).start).norm() < 1e-9);
+            assert!((line_of(a).end - line_of(b).end).norm() < 1e-9);
+        }
+
+        let line = Entity::new(Geometry::Line(Line::new(Point2::origin(), Point2::new(1.0, 0.0))));
+        assert_eq!(table.explode(&line).unwrap_err(), BlockError::NotABlockReference);
+    }
+
+    #[test]
+    fn test_mirrored_and_non_uniform_insert() {
+        let mut table = BlockTable::new();
+        let mut block = Block::new("ArcBlock", Point2::origin());
+        block.add_entity(Entity::new(Geometry::Arc(Arc::new(Point2::origin(), 1.0, 0.0, std::f64::consts::FRAC_PI_2))));
+        table.add_block(block);
+
+        // X 方向镜像：圆弧从第一象限翻到第二象限，方向仍为逆时针
+        let mirrored = insert(BlockReference::new("ArcBlock", Point2::origin()).with_scale(-1.0, 1.0));
+        let parts = table.resolve(&mirrored).unwrap();
+        let Geometry::Arc(arc) = &parts[0].geometry else { panic!("expected arc") };
+        assert!((arc.start_point() - Point2::new(0.0, 1.0)).norm() < 1e-9);
+        assert!((arc.end_point() - Point2::new(-1.0, 0.0)).norm() < 1e-9);
+
+        // 非均匀缩放：圆弧精确地变为椭圆弧
+        let stretched = insert(BlockReference::new("ArcBlock", Point2::origin()).with_scale(2.0, 1.0));
+        let parts = table.resolve(&stretched).unwrap();
+        let Geometry::Ellipse(ellipse) = &parts[0].geometry else { panic!("expected ellipse") };
+        assert!((ellipse.point_at_param(ellipse.start_param) - Point2::new(2.0, 0.0)).norm() < 1e-9);
+        assert!((ellipse.point_at_param(ellipse.end_param) - Point2::new(0.0, 1.0)).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_by_block_properties_inherited() {
+        use crate::properties::{Color, Properties};
+
+        let mut table = BlockTable::new();
+        let mut block = Block::new("Colored", Point2::origin());
+        let line = Geometry::Line(Line::new(Point2::origin(), Point2::new(1.0, 0.0)));
+        block.add_entity(Entity::new(line.clone()).with_properties(Properties::with_color(Color::BY_BLOCK)));
+        block.add_entity(Entity::new(line).with_properties(Properties::with_color(Color::GREEN)));
+        table.add_block(block);
+
+        let layer = EntityId::from_raw(42, 0);
+        let entity = insert(BlockReference::new("Colored", Point2::origin()))
+            .with_properties(Properties::with_color(Color::RED))
+            .with_layer(layer);
+        let parts = table.resolve(&entity).unwrap();
+        assert_eq!(parts[0].properties.color, Color::RED);
+        assert_eq!(parts[1].properties.color, Color::GREEN);
+        assert!(parts.iter().all(|p| p.layer_id == layer));
+    }
+
+    #[test]
+    fn test_attributes_resolve_and_explode() {
+        let mut table = nested_table();
+        let mut tag = Block::new("Tag", Point2::origin());
+        tag.add_entity(Entity::new(Geometry::Line(Line::new(Point2::origin(), Point2::new(10.0, 0.0)))));
+        tag.add_attribute_definition(AttributeDefinition::new("NO", Text::new(Point2::new(1.0, 1.0), "?", 2.5)));
+        let mut title = AttributeDefinition::new("TITLE", Text::new(Point2::new(0.0, 5.0), "VALVE", 2.5));
+        title.constant = true;
+        tag.add_attribute_definition(title);
+        table.add_block(tag);
+
+        let mut reference = BlockReference::new("Tag", Point2::new(100.0, 0.0)).with_uniform_scale(2.0);
+        let defaults = table.get_block("Tag").unwrap().default_attributes(&reference);
+        assert_eq!(defaults.len(), 1);
+        assert_eq!(defaults[0].value(), "?");
+        assert!((defaults[0].text.position - Point2::new(102.0, 2.0)).norm() < 1e-9);
+        assert!((defaults[0].text.height - 5.0).abs() < 1e-9);
+
+        reference = reference.with_attributes(defaults);
+        assert!(reference.set_attribute_value("no", "V-101"));
+        assert!(!reference.set_attribute_value("MISSING", "x"));
+
+        // 线段 + 常量属性 + 属性值
+        let entity = insert(reference);
+        let texts = |parts: &[Entity]| {
+            parts
+                .iter()
+                .filter_map(|p| match &p.geometry {
+                    Geometry::Text(text) => Some(text.content.clone()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+        let resolved = table.resolve(&entity).unwrap();
+        assert_eq!(resolved.len(), 3);
+        assert_eq!(texts(&resolved), vec!["VALVE", "V-101"]);
+
+        let exploded = table.explode(&entity).unwrap();
+        assert_eq!(texts(&exploded), vec!["VALVE", "V-101"]);
+        assert!(exploded.iter().all(|p| p.id != entity.id));
+    }
 }
@@ -0,0 +1,1138 @@
+//! 区域布尔运算
+//!
+//! 在 CPU 上对闭合区域执行精确的布尔运算（并集、交集、差集、异或），
+//! 不依赖 GPU，可用于无界面的批处理工具和单元测试。
+//!
+//! 支持的输入：
+//! - 闭合多段线（含凸度圆弧段）
+//! - 圆
+//! - 完整椭圆、闭合样条（以圆弧段拟合）
+//! - 填充边界（外边界与孔洞）
+//!
+//! 直线段与圆弧段在整个运算过程中保持精确表示，结果以闭合多段线返回，
+//! 圆弧段用凸度表示。每个操作数内部的多个环按奇偶规则组成区域，
+//! 因此孔洞可以直接作为内环传入。
+//!
+//! 结果环的方向统一为：外边界逆时针，孔洞顺时针。
+//!
+//! # 示例
+//!
+//! ```rust
+//! use zcad_core::boolean::{boolean_geometry, BooleanOp};
+//! use zcad_core::geometry::{Circle, Geometry};
+//! use zcad_core::math::Point2;
+//!
+//! let a = Geometry::Circle(Circle::new(Point2::new(0.0, 0.0), 10.0));
+//! let b = Geometry::Circle(Circle::new(Point2::new(10.0, 0.0), 10.0));
+//!
+//! let result = boolean_geometry(&a, &b, BooleanOp::Intersection).unwrap();
+//! assert_eq!(result.len(), 1);
+//! ```
+
+use crate::curve::PolylineSegment;
+use crate::geometry::{
+    Arc, Circle, Ellipse, Geometry, Hatch, HatchBoundaryElement, Line, Polyline, PolylineVertex,
+    Spline,
+};
+use crate::math::{BoundingBox2, Point2, Vector2, EPSILON};
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
+
+pub use crate::parametric::BooleanOp;
+
+/// 椭圆/样条拟合为圆弧时使用的圆弧段数
+const ARC_FIT_SEGMENTS: usize = 64;
+
+/// 相对容差（乘以参与运算区域的尺寸）
+const RELATIVE_TOLERANCE: f64 = 1e-9;
+
+/// 侧向探测距离相对于容差的倍数
+const PROBE_FACTOR: f64 = 1000.0;
+
+/// 对两个几何体执行布尔运算
+///
+/// 两个几何体都必须是闭合区域（见 [`region_loops`]），否则返回 `None`。
+pub fn boolean_geometry(a: &Geometry, b: &Geometry, op: BooleanOp) -> Option<Vec<Polyline>> {
+    let loops_a = region_loops(a)?;
+    let loops_b = region_loops(b)?;
+    Some(boolean(&loops_a, &loops_b, op))
+}
+
+/// 对两组闭合多段线执行布尔运算
+///
+/// 每组中的多段线按奇偶规则组成一个区域。未闭合或顶点不足的多段线会被忽略。
+pub fn boolean(a: &[Polyline], b: &[Polyline], op: BooleanOp) -> Vec<Polyline> {
+    let loops_a: Vec<Vec<Segment>> = a.iter().filter_map(polyline_to_loop).collect();
+    let loops_b: Vec<Vec<Segment>> = b.iter().filter_map(polyline_to_loop).collect();
+
+    BooleanSolver::new(loops_a, loops_b).run(op)
+}
+
+/// 将几何体转换为构成闭合区域的多段线环
+///
+/// - 闭合多段线：原样返回（去除重复的闭合顶点）
+/// - 圆：两段半圆弧
+/// - 完整椭圆、闭合样条：圆弧拟合
+/// - 填充：每个边界一个环
+///
+/// 其他几何体不构成区域，返回 `None`。
+pub fn region_loops(geometry: &Geometry) -> Option<Vec<Polyline>> {
+    let loops = match geometry {
+        Geometry::Polyline(pl) => vec![polyline_to_loop(pl)?],
+        Geometry::Circle(c) => vec![circle_to_loop(c)?],
+        Geometry::Ellipse(e) => vec![ellipse_to_loop(e)?],
+        Geometry::Spline(s) => vec![spline_to_loop(s)?],
+        Geometry::Hatch(h) => hatch_to_loops(h),
+        _ => return None,
+    };
+
+    if loops.is_empty() {
+        return None;
+    }
+    Some(loops.iter().map(|segments| loop_to_polyline(segments)).collect())
+}
+
+/// 计算闭合多段线的有向面积（逆时针为正，圆弧段按精确弓形面积计入）
+pub fn signed_area(polyline: &Polyline) -> f64 {
+    let n = polyline.vertices.len();
+    if n < 2 {
+        return 0.0;
+    }
+
+    let mut area = 0.0;
+    for i in 0..n {
+        let v1 = &polyline.vertices[i];
+        let v2 = &polyline.vertices[(i + 1) % n];
+        area += 0.5 * (v1.point.x * v2.point.y - v2.point.x * v1.point.y);
+
+        // 只有闭合多段线的最后一段才计入圆弧
+        if i + 1 == n && !polyline.closed {
+            continue;
+        }
+        if let Segment::Arc { radius, sweep, .. } = Segment::from_bulge(v1.point, v2.point, v1.bulge) {
+            area += 0.5 * radius * radius * (sweep - sweep.sin());
+        }
+    }
+    area
+}
+
+/// 计算一组区域环的总面积
+///
+/// 布尔运算结果的外边界为逆时针、孔洞为顺时针，因此直接对有向面积求和即可。
+pub fn region_area(loops: &[Polyline]) -> f64 {
+    loops.iter().map(signed_area).sum()
+}
+
+// ========== 内部线段表示 ==========
+
+/// 边界线段：直线或带符号扫掠角的圆弧
+#[derive(Debug, Clone, Copy)]
+enum Segment {
+    Line {
+        start: Point2,
+        end: Point2,
+    },
+    Arc {
+        center: Point2,
+        radius: f64,
+        start_angle: f64,
+        /// 扫掠角（正值逆时针，负值顺时针）
+        sweep: f64,
+    },
+}
+
+impl Segment {
+    /// 从多段线顶点对（起点、终点、凸度）创建线段
+    fn from_bulge(start: Point2, end: Point2, bulge: f64) -> Self {
+        let segment = PolylineSegment::from_bulge(start, end, bulge);
+        if !segment.is_arc() {
+            return Segment::Line { start, end };
+        }
+        Segment::Arc {
+            center: segment.center,
+            radius: segment.radius,
+            start_angle: segment.start_angle,
+            sweep: segment.sweep,
+        }
+    }
+
+    fn start(&self) -> Point2 {
+        self.point_at(0.0)
+    }
+
+    fn end(&self) -> Point2 {
+        self.point_at(1.0)
+    }
+
+    /// 归一化参数 t ∈ [0, 1] 处的点
+    fn point_at(&self, t: f64) -> Point2 {
+        match *self {
+            Segment::Line { start, end } => start + (end - start) * t,
+            Segment::Arc {
+                center,
+                radius,
+                start_angle,
+                sweep,
+            } => {
+                let a = start_angle + sweep * t;
+                Point2::new(center.x + radius * a.cos(), center.y + radius * a.sin())
+            }
+        }
+    }
+
+    /// 参数 t 处的切线方向（未归一化，沿线段方向）
+    fn tangent_at(&self, t: f64) -> Vector2 {
+        match *self {
+            Segment::Line { start, end } => end - start,
+            Segment::Arc {
+                start_angle, sweep, ..
+            } => {
+                let a = start_angle + sweep * t;
+                Vector2::new(-a.sin(), a.cos()) * sweep.signum()
+            }
+        }
+    }
+
+    /// 凸度（直线为 0）
+    fn bulge(&self) -> f64 {
+        match *self {
+            Segment::Line { .. } => 0.0,
+            Segment::Arc { sweep, .. } => (sweep / 4.0).tan(),
+        }
+    }
+
+    fn length(&self) -> f64 {
+        match *self {
+            Segment::Line { start, end } => (end - start).norm(),
+            Segment::Arc { radius, sweep, .. } => radius * sweep.abs(),
+        }
+    }
+
+    fn reversed(&self) -> Self {
+        match *self {
+            Segment::Line { start, end } => Segment::Line {
+                start: end,
+                end: start,
+            },
+            Segment::Arc {
+                center,
+                radius,
+                start_angle,
+                sweep,
+            } => Segment::Arc {
+                center,
+                radius,
+                start_angle: start_angle + sweep,
+                sweep: -sweep,
+            },
+        }
+    }
+
+    /// 参数区间 [t0, t1] 对应的子线段
+    fn sub(&self, t0: f64, t1: f64) -> Self {
+        match *self {
+            Segment::Line { .. } => Segment::Line {
+                start: self.point_at(t0),
+                end: self.point_at(t1),
+            },
+            Segment::Arc {
+                center,
+                radius,
+                start_angle,
+                sweep,
+            } => Segment::Arc {
+                center,
+                radius,
+                start_angle: start_angle + sweep * t0,
+                sweep: sweep * (t1 - t0),
+            },
+        }
+    }
+
+    /// 点在线段上的投影参数（圆弧按角度计算，可能超出 [0, 1]）
+    fn param_of(&self, p: &Point2) -> f64 {
+        match *self {
+            Segment::Line { start, end } => {
+                let d = end - start;
+                let len_sq = d.norm_squared();
+                if len_sq < EPSILON * EPSILON {
+                    0.0
+                } else {
+                    (p - start).dot(&d) / len_sq
+                }
+            }
+            Segment::Arc {
+                center,
+                start_angle,
+                sweep,
+                ..
+            } => {
+                let angle = (p.y - center.y).atan2(p.x - center.x);
+                let delta = if sweep >= 0.0 {
+                    (angle - start_angle).rem_euclid(TAU)
+                } else {
+                    -(start_angle - angle).rem_euclid(TAU)
+                };
+                let t = delta / sweep;
+                // 起点附近的角度可能被归一化到 2π 一侧
+                if t > 1.0 && (t - TAU / sweep.abs()).abs() * sweep.abs() < 1e-6 {
+                    0.0
+                } else {
+                    t
+                }
+            }
+        }
+    }
+
+    /// 点到线段的距离
+    fn distance_to(&self, p: &Point2) -> f64 {
+        match *self {
+            Segment::Line { start, end } => Line::new(start, end).distance_to_point(p),
+            Segment::Arc { center, radius, .. } => {
+                let t = self.param_of(p);
+                if (0.0..=1.0).contains(&t) {
+                    ((p - center).norm() - radius).abs()
+                } else {
+                    (p - self.start()).norm().min((p - self.end()).norm())
+                }
+            }
+        }
+    }
+
+    fn bounding_box(&self) -> BoundingBox2 {
+        match *self {
+            Segment::Line { start, end } => BoundingBox2::from_points([start, end]),
+            Segment::Arc {
+                center,
+                radius,
+                start_angle,
+                sweep,
+            } => {
+                let (lo, hi) = if sweep >= 0.0 {
+                    (start_angle, start_angle + sweep)
+                } else {
+                    (start_angle + sweep, start_angle)
+                };
+                let mut bbox = BoundingBox2::from_points([self.start(), self.end()]);
+                let mut k = (lo / FRAC_PI_2).ceil();
+                while k * FRAC_PI_2 < hi {
+                    let a = k * FRAC_PI_2;
+                    bbox.expand_to_include(&Point2::new(
+                        center.x + radius * a.cos(),
+                        center.y + radius * a.sin(),
+                    ));
+                    k += 1.0;
+                }
+                bbox
+            }
+        }
+    }
+
+    /// 水平射线（向 +X 方向）与线段的交叉次数
+    fn ray_crossings(&self, p: &Point2) -> usize {
+        match *self {
+            Segment::Line { start, end } => crossing(start, end, p, |y| {
+                start.x + (y - start.y) * (end.x - start.x) / (end.y - start.y)
+            }),
+            Segment::Arc {
+                center,
+                radius,
+                start_angle,
+                sweep,
+            } => {
+                // 在 Y 极值点处拆分为 Y 单调的子弧
+                let (lo, hi) = if sweep >= 0.0 {
+                    (start_angle, start_angle + sweep)
+                } else {
+                    (start_angle + sweep, start_angle)
+                };
+                let mut angles = vec![lo];
+                let mut k = ((lo - FRAC_PI_2) / PI).ceil();
+                while FRAC_PI_2 + k * PI < hi {
+                    angles.push(FRAC_PI_2 + k * PI);
+                    k += 1.0;
+                }
+                angles.push(hi);
+
+                let at = |a: f64| Point2::new(center.x + radius * a.cos(), center.y + radius * a.sin());
+                angles
+                    .windows(2)
+                    .map(|w| {
+                        let right_half = ((w[0] + w[1]) / 2.0).cos() >= 0.0;
+                        crossing(at(w[0]), at(w[1]), p, |y| {
+                            let dy = y - center.y;
+                            let dx = (radius * radius - dy * dy).max(0.0).sqrt();
+                            if right_half {
+                                center.x + dx
+                            } else {
+                                center.x - dx
+                            }
+                        })
+                    })
+                    .sum()
+            }
+        }
+    }
+}
+
+/// Y 单调曲线段的半开区间交叉判定
+fn crossing(a: Point2, b: Point2, p: &Point2, x_at: impl Fn(f64) -> f64) -> usize {
+    if (a.y > p.y) != (b.y > p.y) && x_at(p.y) > p.x {
+        1
+    } else {
+        0
+    }
+}
+
+/// 两条线段的交点
+fn segment_intersections(s1: &Segment, s2: &Segment, tol: f64) -> Vec<Point2> {
+    let candidates = match (*s1, *s2) {
+        (Segment::Line { start: a, end: b }, Segment::Line { start: c, end: d }) => {
+            let r = b - a;
+            let s = d - c;
+            let denom = r.x * s.y - r.y * s.x;
+            if denom.abs() < EPSILON * r.norm() * s.norm() {
+                // 平行或共线：重叠部分由端点检测处理
+                Vec::new()
+            } else {
+                let t = ((c.x - a.x) * s.y - (c.y - a.y) * s.x) / denom;
+                vec![a + r * t]
+            }
+        }
+        (Segment::Line { start, end }, Segment::Arc { center, radius, .. })
+        | (Segment::Arc { center, radius, .. }, Segment::Line { start, end }) => {
+            line_circle_points(start, end, center, radius, tol)
+        }
+        (
+            Segment::Arc {
+                center: c1,
+                radius: r1,
+                ..
+            },
+            Segment::Arc {
+                center: c2,
+                radius: r2,
+                ..
+            },
+        ) => circle_circle_points(c1, r1, c2, r2, tol),
+    };
+
+    candidates
+        .into_iter()
+        .filter(|p| s1.distance_to(p) <= tol && s2.distance_to(p) <= tol)
+        .collect()
+}
+
+/// 直线（无限延长）与圆的交点
+fn line_circle_points(start: Point2, end: Point2, center: Point2, radius: f64, tol: f64) -> Vec<Point2> {
+    let d = end - start;
+    let len = d.norm();
+    if len < EPSILON {
+        return Vec::new();
+    }
+    let dir = d / len;
+    let t_foot = (center - start).dot(&dir);
+    let foot = start + dir * t_foot;
+    let dist = (center - foot).norm();
+
+    if dist > radius + tol {
+        Vec::new()
+    } else if (dist - radius).abs() <= tol {
+        // 相切
+        vec![foot]
+    } else {
+        let h = (radius * radius - dist * dist).sqrt();
+        vec![foot - dir * h, foot + dir * h]
+    }
+}
+
+/// 两圆的交点
+fn circle_circle_points(c1: Point2, r1: f64, c2: Point2, r2: f64, tol: f64) -> Vec<Point2> {
+    let d_vec = c2 - c1;
+    let d = d_vec.norm();
+
+    if d < tol || d > r1 + r2 + tol || d < (r1 - r2).abs() - tol {
+        // 同心（含重合圆，重叠部分由端点检测处理）、相离或内含
+        return Vec::new();
+    }
+
+    let a = (r1 * r1 - r2 * r2 + d * d) / (2.0 * d);
+    let h_sq = r1 * r1 - a * a;
+    let dir = d_vec / d;
+    let base = c1 + dir * a;
+
+    if h_sq <= tol * tol.max(r1) {
+        // 相切
+        return vec![base];
+    }
+    let h = h_sq.sqrt();
+    let perp = Vector2::new(-dir.y, dir.x);
+    vec![base + perp * h, base - perp * h]
+}
+
+// ========== 区域构造 ==========
+
+fn polyline_to_loop(polyline: &Polyline) -> Option<Vec<Segment>> {
+    let mut vertices: Vec<&PolylineVertex> = polyline.vertices.iter().collect();
+
+    // 首尾重合的非闭合多段线同样视为闭合
+    if let (Some(first), Some(last)) = (vertices.first(), vertices.last()) {
+        if vertices.len() > 1 && (first.point - last.point).norm() < EPSILON {
+            vertices.pop();
+        } else if !polyline.closed {
+            return None;
+        }
+    }
+
+    let n = vertices.len();
+    let segments: Vec<Segment> = (0..n)
+        .map(|i| Segment::from_bulge(vertices[i].point, vertices[(i + 1) % n].point, vertices[i].bulge))
+        .filter(|s| s.length() > EPSILON)
+        .collect();
+
+    if segments.len() < 2 && !segments.iter().any(|s| matches!(s, Segment::Arc { .. })) {
+        return None;
+    }
+    Some(segments)
+}
+
+fn circle_to_loop(circle: &Circle) -> Option<Vec<Segment>> {
+    if circle.radius < EPSILON {
+        return None;
+    }
+    Some(arc_segments(circle.center, circle.radius, 0.0, TAU))
+}
+
+/// 将圆弧拆分为扫掠角不超过 π 的段
+fn arc_segments(center: Point2, radius: f64, start_angle: f64, sweep: f64) -> Vec<Segment> {
+    let count = (sweep.abs() / PI - EPSILON).ceil().max(1.0) as usize;
+    let step = sweep / count as f64;
+    (0..count)
+        .map(|i| Segment::Arc {
+            center,
+            radius,
+            start_angle: start_angle + step * i as f64,
+            sweep: step,
+        })
+        .collect()
+}
+
+fn ellipse_to_loop(ellipse: &Ellipse) -> Option<Vec<Segment>> {
+    if !ellipse.is_full() || ellipse.major_radius() < EPSILON {
+        return None;
+    }
+    if (ellipse.ratio - 1.0).abs() < EPSILON {
+        return circle_to_loop(&Circle::new(ellipse.center, ellipse.major_radius()));
+    }
+    Some(fit_arcs(&ellipse.sample_points(ARC_FIT_SEGMENTS * 2)))
+}
+
+fn spline_to_loop(spline: &Spline) -> Option<Vec<Segment>> {
+    let points = spline.sample_points(ARC_FIT_SEGMENTS * 2);
+    let first = points.first()?;
+    let last = points.last()?;
+    if (first - last).norm() > EPSILON.max(spline.bounding_box().width() * RELATIVE_TOLERANCE) {
+        return None;
+    }
+    Some(fit_arcs(&points))
+}
+
+fn hatch_to_loops(hatch: &Hatch) -> Vec<Vec<Segment>> {
+    hatch
+        .boundaries
+        .iter()
+        .filter_map(|boundary| {
+            let pieces: Vec<Vec<Segment>> = boundary.elements.iter().map(boundary_element_segments).collect();
+            chain_pieces(pieces)
+        })
+        .collect()
+}
+
+fn boundary_element_segments(element: &HatchBoundaryElement) -> Vec<Segment> {
+    match element {
+        HatchBoundaryElement::Line(line) => vec![Segment::Line {
+            start: line.start,
+            end: line.end,
+        }],
+        HatchBoundaryElement::Arc(arc) => arc_element_segments(arc),
+        HatchBoundaryElement::Ellipse(ellipse) => {
+            if (ellipse.ratio - 1.0).abs() < EPSILON {
+                let rotation = ellipse.rotation();
+                let sweep = ellipse.end_param - ellipse.start_param;
+                arc_segments(ellipse.center, ellipse.major_radius(), ellipse.start_param + rotation, sweep)
+            } else {
+                fit_arcs(&ellipse.sample_points(ARC_FIT_SEGMENTS * 2))
+            }
+        }
+        HatchBoundaryElement::Spline(spline) => fit_arcs(&spline.sample_points(ARC_FIT_SEGMENTS * 2)),
+    }
+}
+
+fn arc_element_segments(arc: &Arc) -> Vec<Segment> {
+    let mut sweep = arc.sweep_angle();
+    if sweep < EPSILON {
+        sweep = TAU;
+    }
+    arc_segments(arc.center, arc.radius, arc.start_angle, sweep)
+}
+
+/// 按端点连接边界元素，必要时反转元素方向
+fn chain_pieces(pieces: Vec<Vec<Segment>>) -> Option<Vec<Segment>> {
+    let mut pieces: Vec<Vec<Segment>> = pieces.into_iter().filter(|p| !p.is_empty()).collect();
+    if pieces.is_empty() {
+        return None;
+    }
+
+    let piece_start = |p: &[Segment]| p[0].start();
+    let piece_end = |p: &[Segment]| p[p.len() - 1].end();
+    let reverse = |p: Vec<Segment>| -> Vec<Segment> { p.iter().rev().map(Segment::reversed).collect() };
+
+    let bbox = pieces
+        .iter()
+        .flatten()
+        .fold(BoundingBox2::empty(), |b, s| b.union(&s.bounding_box()));
+    let join_tol = (bbox.width().max(bbox.height()) * 1e-6).max(EPSILON);
+
+    let mut result = pieces.remove(0);
+    // 第一个元素的方向由它与下一个元素的连接关系决定
+    if let Some(next) = pieces.first() {
+        let end = piece_end(&result);
+        let start = piece_start(&result);
+        let touches = |p: Point2| {
+            (p - piece_start(next)).norm() < join_tol || (p - piece_end(next)).norm() < join_tol
+        };
+        if !touches(end) && touches(start) {
+            result = reverse(result);
+        }
+    }
+
+    while !pieces.is_empty() {
+        let end = piece_end(&result);
+        let found = pieces.iter().enumerate().find_map(|(i, p)| {
+            if (piece_start(p) - end).norm() < join_tol {
+                Some((i, false))
+            } else if (piece_end(p) - end).norm() < join_tol {
+                Some((i, true))
+            } else {
+                None
+            }
+        });
+
+        match found {
+            Some((i, needs_reverse)) => {
+                let piece = pieces.remove(i);
+                result.extend(if needs_reverse { reverse(piece) } else { piece });
+            }
+            None => {
+                // 边界不连续时按原顺序连接
+                let piece = pieces.remove(0);
+                result.push(Segment::Line {
+                    start: end,
+                    end: piece_start(&piece),
+                });
+                result.extend(piece);
+            }
+        }
+    }
+
+    let (start, end) = (piece_start(&result), piece_end(&result));
+    if (start - end).norm() > join_tol {
+        result.push(Segment::Line { start: end, end: start });
+    }
+    Some(result)
+}
+
+/// 用三点圆弧拟合采样点序列（每段圆弧经过三个连续采样点）
+fn fit_arcs(points: &[Point2]) -> Vec<Segment> {
+    let mut segments = Vec::with_capacity(points.len() / 2 + 1);
+    let mut i = 0;
+    while i + 1 < points.len() {
+        let start = points[i];
+        if i + 2 < points.len() {
+            let (mid, end) = (points[i + 1], points[i + 2]);
+            segments.push(Segment::from_bulge(start, end, three_point_bulge(start, mid, end)));
+            i += 2;
+        } else {
+            segments.push(Segment::Line {
+                start,
+                end: points[i + 1],
+            });
+            i += 1;
+        }
+    }
+    segments
+}
+
+/// 经过三点（起点、中间点、终点）圆弧的凸度
+fn three_point_bulge(start: Point2, mid: Point2, end: Point2) -> f64 {
+    let Some(arc) = Arc::from_three_points(start, mid, end) else {
+        return 0.0;
+    };
+    // 中间点位于弦的右侧时圆弧为逆时针
+    let chord = end - start;
+    let to_mid = mid - start;
+    let ccw = chord.x * to_mid.y - chord.y * to_mid.x < 0.0;
+
+    let a0 = (start.y - arc.center.y).atan2(start.x - arc.center.x);
+    let a1 = (end.y - arc.center.y).atan2(end.x - arc.center.x);
+    let sweep = if ccw {
+        (a1 - a0).rem_euclid(TAU)
+    } else {
+        -(a0 - a1).rem_euclid(TAU)
+    };
+    (sweep / 4.0).tan()
+}
+
+fn loop_to_polyline(segments: &[Segment]) -> Polyline {
+    Polyline::new(
+        segments
+            .iter()
+            .map(|s| PolylineVertex::with_bulge(s.start(), s.bulge()))
+            .collect(),
+        true,
+    )
+}
+
+// ========== 求解器 ==========
+
+/// 拆分后的边界片段
+struct Piece {
+    segment: Segment,
+    /// 来源操作数（0 = A，1 = B）
+    operand: usize,
+}
+
+struct BooleanSolver {
+    operands: [Vec<Vec<Segment>>; 2],
+    tol: f64,
+    probe: f64,
+}
+
+impl BooleanSolver {
+    fn new(a: Vec<Vec<Segment>>, b: Vec<Vec<Segment>>) -> Self {
+        let bbox = a
+            .iter()
+            .chain(b.iter())
+            .flatten()
+            .fold(BoundingBox2::empty(), |bbox, s| bbox.union(&s.bounding_box()));
+        let size = if a.is_empty() && b.is_empty() {
+            1.0
+        } else {
+            bbox.width().max(bbox.height()).max(1.0)
+        };
+        let tol = size * RELATIVE_TOLERANCE;
+
+        Self {
+            operands: [a, b],
+            tol,
+            probe: tol * PROBE_FACTOR,
+        }
+    }
+
+    fn run(&self, op: BooleanOp) -> Vec<Polyline> {
+        let kept: Vec<Segment> = self
+            .split_pieces()
+            .into_iter()
+            .filter_map(|piece| self.classify(&piece, op))
+            .collect();
+
+        self.assemble(kept)
+            .iter()
+            .map(|segments| loop_to_polyline(segments))
+            .collect()
+    }
+
+    /// 在两个操作数的所有交点和重叠端点处拆分线段
+    fn split_pieces(&self) -> Vec<Piece> {
+        let segments: [Vec<Segment>; 2] = [
+            self.operands[0].iter().flatten().copied().collect(),
+            self.operands[1].iter().flatten().copied().collect(),
+        ];
+        let mut splits: [Vec<Vec<Point2>>; 2] = [
+            vec![Vec::new(); segments[0].len()],
+            vec![Vec::new(); segments[1].len()],
+        ];
+
+        for (i, sa) in segments[0].iter().enumerate() {
+            let bbox_a = sa.bounding_box();
+            for (j, sb) in segments[1].iter().enumerate() {
+                if !expand(&bbox_a, self.tol).intersects(&sb.bounding_box()) {
+                    continue;
+                }
+                for p in segment_intersections(sa, sb, self.tol) {
+                    splits[0][i].push(p);
+                    splits[1][j].push(p);
+                }
+                // 重叠与 T 形连接：端点落在对方线段上
+                for p in [sb.start(), sb.end()] {
+                    if sa.distance_to(&p) <= self.tol {
+                        splits[0][i].push(p);
+                    }
+                }
+                for p in [sa.start(), sa.end()] {
+                    if sb.distance_to(&p) <= self.tol {
+                        splits[1][j].push(p);
+                    }
+                }
+            }
+        }
+
+        let mut pieces = Vec::new();
+        for operand in 0..2 {
+            for (segment, points) in segments[operand].iter().zip(&splits[operand]) {
+                for piece in self.split_segment(segment, points) {
+                    pieces.push(Piece {
+                        segment: piece,
+                        operand,
+                    });
+                }
+            }
+        }
+        pieces
+    }
+
+    fn split_segment(&self, segment: &Segment, points: &[Point2]) -> Vec<Segment> {
+        let mut params: Vec<f64> = points
+            .iter()
+            .filter(|p| {
+                (*p - segment.start()).norm() > self.tol && (*p - segment.end()).norm() > self.tol
+            })
+            .map(|p| segment.param_of(p).clamp(0.0, 1.0))
+            .collect();
+        params.sort_by(|a, b| a.total_cmp(b));
+
+        let mut result = Vec::with_capacity(params.len() + 1);
+        let mut last = 0.0;
+        for t in params.into_iter().chain(std::iter::once(1.0)) {
+            if (segment.point_at(t) - segment.point_at(last)).norm() > self.tol {
+                result.push(segment.sub(last, t));
+                last = t;
+            }
+        }
+        result
+    }
+
+    /// 判断片段是否属于结果边界，返回使结果区域位于左侧的片段方向
+    fn classify(&self, piece: &Piece, op: BooleanOp) -> Option<Segment> {
+        let segment = &piece.segment;
+        let mid = segment.point_at(0.5);
+
+        // 与 A 边界重合的 B 片段由 A 的片段代表，避免重复
+        if piece.operand == 1 && self.on_boundary(0, &mid) {
+            return None;
+        }
+
+        let tangent = segment.tangent_at(0.5);
+        let len = tangent.norm();
+        if len < EPSILON {
+            return None;
+        }
+        let normal = Vector2::new(-tangent.y, tangent.x) / len;
+        let probe = self.probe.min(segment.length() * 0.25);
+        let left = mid + normal * probe;
+        let right = mid - normal * probe;
+
+        let in_left = apply(op, self.contains(0, &left), self.contains(1, &left));
+        let in_right = apply(op, self.contains(0, &right), self.contains(1, &right));
+
+        match (in_left, in_right) {
+            (true, false) => Some(*segment),
+            (false, true) => Some(segment.reversed()),
+            _ => None,
+        }
+    }
+
+    /// 按奇偶规则判断点是否在操作数区域内
+    fn contains(&self, operand: usize, p: &Point2) -> bool {
+        let crossings: usize = self.operands[operand]
+            .iter()
+            .flatten()
+            .map(|s| s.ray_crossings(p))
+            .sum();
+        crossings % 2 == 1
+    }
+
+    fn on_boundary(&self, operand: usize, p: &Point2) -> bool {
+        self.operands[operand]
+            .iter()
+            .flatten()
+            .any(|s| s.distance_to(p) <= self.tol * 10.0)
+    }
+
+    /// 将有向片段首尾相连组装为闭合环
+    fn assemble(&self, mut pieces: Vec<Segment>) -> Vec<Vec<Segment>> {
+        let join_tol = self.tol * 100.0;
+        let mut loops = Vec::new();
+
+        while let Some(first) = pieces.pop() {
+            let loop_start = first.start();
+            let mut current = vec![first];
+
+            loop {
+                let end = current[current.len() - 1].end();
+                if (end - loop_start).norm() <= join_tol {
+                    break;
+                }
+                let next = pieces
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, s)| (s.start() - end).norm() <= join_tol)
+                    .min_by(|(_, a), (_, b)| {
+                        (a.start() - end).norm().total_cmp(&(b.start() - end).norm())
+                    })
+                    .map(|(i, _)| i);
+                match next {
+                    Some(i) => current.push(pieces.swap_remove(i)),
+                    None => break,
+                }
+            }
+
+            let merged = merge_segments(current, join_tol);
+            if merged.len() >= 2 || merged.iter().any(|s| matches!(s, Segment::Arc { .. })) {
+                loops.push(merged);
+            }
+        }
+
+        loops
+    }
+}
+
+/// 布尔运算的逐点真值表
+fn apply(op: BooleanOp, in_a: bool, in_b: bool) -> bool {
+    match op {
+        BooleanOp::Union => in_a || in_b,
+        BooleanOp::Intersection => in_a && in_b,
+        BooleanOp::Difference => in_a && !in_b,
+        BooleanOp::Xor => in_a != in_b,
+    }
+}
+
+fn expand(bbox: &BoundingBox2, amount: f64) -> BoundingBox2 {
+    BoundingBox2::new(
+        Point2::new(bbox.min.x - amount, bbox.min.y - amount),
+        Point2::new(bbox.max.x + amount, bbox.max.y + amount),
+    )
+}
+
+/// 合并环中相邻的共线直线段和同圆圆弧段
+fn merge_segments(segments: Vec<Segment>, tol: f64) -> Vec<Segment> {
+    let mut result: Vec<Segment> = Vec::with_capacity(segments.len());
+    for segment in segments {
+        if let Some(last) = result.last_mut() {
+            if let Some(merged) = try_merge(last, &segment, tol) {
+                *last = merged;
+                continue;
+            }
+        }
+        result.push(segment);
+    }
+
+    // 首尾相接处
+    while result.len() > 2 {
+        match try_merge(&result[result.len() - 1], &result[0], tol) {
+            Some(merged) => {
+                result.pop();
+                result[0] = merged;
+            }
+            None => break,
+        }
+    }
+    result
+}
+
+fn try_merge(a: &Segment, b: &Segment, tol: f64) -> Option<Segment> {
+    match (*a, *b) {
+        (Segment::Line { start, end: mid }, Segment::Line { end, .. }) => {
+            let d1 = mid - start;
+            let d2 = end - mid;
+            let cross = d1.x * d2.y - d1.y * d2.x;
+            if d1.dot(&d2) > 0.0 && cross.abs() <= tol * (d1.norm() + d2.norm()) {
+                Some(Segment::Line { start, end })
+            } else {
+                None
+            }
+        }
+        (
+            Segment::Arc {
+                center: c1,
+                radius: r1,
+                start_angle,
+                sweep: s1,
+            },
+            Segment::Arc {
+                center: c2,
+                radius: r2,
+                sweep: s2,
+                ..
+            },
+        ) => {
+            let sweep = s1 + s2;
+            if (c1 - c2).norm() <= tol
+                && (r1 - r2).abs() <= tol
+                && s1.signum() == s2.signum()
+                && sweep.abs() < PI + EPSILON
+            {
+                Some(Segment::Arc {
+                    center: c1,
+                    radius: r1,
+                    start_angle,
+                    sweep,
+                })
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::HatchBoundary;
+
+    fn square(x: f64, y: f64, size: f64) -> Polyline {
+        Polyline::from_points(
+            [
+                Point2::new(x, y),
+                Point2::new(x + size, y),
+                Point2::new(x + size, y + size),
+                Point2::new(x, y + size),
+            ],
+            true,
+        )
+    }
+
+    #[test]
+    fn test_square_operations() {
+        let a = [square(0.0, 0.0, 10.0)];
+        let b = [square(5.0, 5.0, 10.0)];
+
+        assert!((region_area(&boolean(&a, &b, BooleanOp::Union)) - 175.0).abs() < 1e-6);
+        assert!((region_area(&boolean(&a, &b, BooleanOp::Intersection)) - 25.0).abs() < 1e-6);
+        assert!((region_area(&boolean(&a, &b, BooleanOp::Difference)) - 75.0).abs() < 1e-6);
+        assert!((region_area(&boolean(&a, &b, BooleanOp::Xor)) - 150.0).abs() < 1e-6);
+
+        // 交集应为单个矩形（拆分点已合并）
+        let inter = boolean(&a, &b, BooleanOp::Intersection);
+        assert_eq!(inter.len(), 1);
+        assert_eq!(inter[0].vertices.len(), 4);
+    }
+
+    #[test]
+    fn test_disjoint_and_contained() {
+        let a = [square(0.0, 0.0, 10.0)];
+        let far = [square(20.0, 0.0, 5.0)];
+        assert!(boolean(&a, &far, BooleanOp::Intersection).is_empty());
+        assert_eq!(boolean(&a, &far, BooleanOp::Union).len(), 2);
+
+        // 内含区域做差集得到带孔洞的区域
+        let inner = [square(2.0, 2.0, 2.0)];
+        let diff = boolean(&a, &inner, BooleanOp::Difference);
+        assert_eq!(diff.len(), 2);
+        assert!((region_area(&diff) - 96.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_shared_edge_union() {
+        let a = [square(0.0, 0.0, 10.0)];
+        let b = [square(10.0, 0.0, 10.0)];
+        let union = boolean(&a, &b, BooleanOp::Union);
+        assert_eq!(union.len(), 1);
+        assert_eq!(union[0].vertices.len(), 4);
+        assert!((region_area(&union) - 200.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_circle_intersection_is_exact() {
+        let a = Geometry::Circle(Circle::new(Point2::new(0.0, 0.0), 10.0));
+        let b = Geometry::Circle(Circle::new(Point2::new(10.0, 0.0), 10.0));
+
+        let lens = boolean_geometry(&a, &b, BooleanOp::Intersection).unwrap();
+        assert_eq!(lens.len(), 1);
+        // 透镜形由两段圆弧组成
+        assert!(lens[0].vertices.iter().all(|v| v.bulge.abs() > EPSILON));
+
+        // 两个半径 r、圆心距 r 的圆的交集面积：r²(2π/3 - √3/2)
+        let expected = 100.0 * (2.0 * PI / 3.0 - 3.0_f64.sqrt() / 2.0);
+        assert!((region_area(&lens) - expected).abs() < 1e-6);
+
+        let union = boolean_geometry(&a, &b, BooleanOp::Union).unwrap();
+        assert!((region_area(&union) - (2.0 * PI * 100.0 - expected)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_bulged_polyline_minus_square() {
+        // 由两段半圆组成的圆
+        let disk = Polyline::new(
+            vec![
+                PolylineVertex::with_bulge(Point2::new(-5.0, 0.0), 1.0),
+                PolylineVertex::with_bulge(Point2::new(5.0, 0.0), 1.0),
+            ],
+            true,
+        );
+        assert!((signed_area(&disk) - 25.0 * PI).abs() < 1e-9);
+
+        // 去掉下半部分
+        let lower = [Polyline::from_points(
+            [
+                Point2::new(-10.0, -10.0),
+                Point2::new(10.0, -10.0),
+                Point2::new(10.0, 0.0),
+                Point2::new(-10.0, 0.0),
+            ],
+            true,
+        )];
+        let half = boolean(&[disk], &lower, BooleanOp::Difference);
+        assert_eq!(half.len(), 1);
+        assert!((region_area(&half) - 12.5 * PI).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_hatch_with_hole() {
+        let outer = HatchBoundary::new(
+            vec![
+                HatchBoundaryElement::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0))),
+                // 故意反向的元素
+                HatchBoundaryElement::Line(Line::new(Point2::new(10.0, 10.0), Point2::new(10.0, 0.0))),
+                HatchBoundaryElement::Line(Line::new(Point2::new(10.0, 10.0), Point2::new(0.0, 10.0))),
+                HatchBoundaryElement::Line(Line::new(Point2::new(0.0, 10.0), Point2::new(0.0, 0.0))),
+            ],
+            true,
+        );
+        let hole = HatchBoundary::new(
+            vec![HatchBoundaryElement::Arc(Arc::new(Point2::new(5.0, 5.0), 2.0, 0.0, TAU))],
+            false,
+        );
+        let hatch = Geometry::Hatch(Hatch::solid(vec![outer, hole]));
+        let cutter = Geometry::Polyline(square(5.0, -5.0, 20.0));
+
+        let result = boolean_geometry(&hatch, &cutter, BooleanOp::Intersection).unwrap();
+        // 右半部分（5×10）减去半个孔洞
+        let expected = 50.0 - 2.0 * PI;
+        assert!((region_area(&result) - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_ellipse_region() {
+        let ellipse = Geometry::Ellipse(Ellipse::from_radii(Point2::origin(), 10.0, 5.0));
+        let loops = region_loops(&ellipse).unwrap();
+        assert!((region_area(&loops) - 50.0 * PI).abs() < 1e-3);
+
+        let right = Geometry::Polyline(square(0.0, -20.0, 40.0));
+        let half = boolean_geometry(&ellipse, &right, BooleanOp::Intersection).unwrap();
+        assert!((region_area(&half) - 25.0 * PI).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_non_region_rejected() {
+        let line = Geometry::Line(Line::new(Point2::origin(), Point2::new(1.0, 1.0)));
+        let circle = Geometry::Circle(Circle::new(Point2::origin(), 1.0));
+        assert!(boolean_geometry(&line, &circle, BooleanOp::Union).is_none());
+
+        let open = Geometry::Polyline(Polyline::from_points(
+            [Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), Point2::new(1.0, 1.0)],
+            false,
+        ));
+        assert!(region_loops(&open).is_none());
+    }
+}
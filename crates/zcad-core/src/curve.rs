@@ -0,0 +1,962 @@
+//! 曲线的统一参数化接口
+//!
+//! [`Curve`] 为直线、圆、圆弧、椭圆、样条和多段线提供一致的参数查询：参数范围、
+//! 点/切向/曲率、弧长与参数互换、最近点，以及在参数处打断、截取子曲线和反向。
+//! 修剪、打断、定数等分和对象捕捉可以据此统一实现，不必区分曲线类型。
+//!
+//! 各曲线的参数约定：
+//! - 直线：`[0, 1]`，0 为起点
+//! - 圆：角度 `[0, 2π]`
+//! - 圆弧：角度 `[start_angle, start_angle + sweep]`
+//! - 椭圆：椭圆参数 `[start_param, end_param]`
+//! - 样条：节点向量的定义域
+//! - 多段线：顶点序号，第 i 段对应 `[i, i + 1]`
+//!
+//! 多段线凸度段与圆弧的换算只在 [`PolylineSegment`] 中实现，求交、布尔运算、
+//! 离散化和导出都经由它取得圆心、半径和扫掠角。
+
+use crate::geometry::{Arc, Circle, Ellipse, Geometry, Line, Polyline, PolylineVertex, Spline};
+use crate::math::{Point2, Vector2, EPSILON};
+use std::f64::consts::{PI, TAU};
+
+/// 5 点 Gauss-Legendre 积分的节点和权重（区间 [-1, 1]）
+const GAUSS_NODES: [(f64, f64); 5] = [
+    (0.0, 0.568_888_888_888_888_9),
+    (-0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (-0.906_179_845_938_664, 0.236_926_885_056_189_1),
+    (0.906_179_845_938_664, 0.236_926_885_056_189_1),
+];
+
+/// 每个光滑片段积分时的细分数
+const LENGTH_SUBDIVISIONS: usize = 16;
+
+/// 最近点搜索时每个光滑片段的初始采样数
+const CLOSEST_SAMPLES: usize = 16;
+
+/// 曲线上的点及其参数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CurvePoint {
+    /// 参数
+    pub param: f64,
+    /// 曲线上的点
+    pub point: Point2,
+    /// 到查询点的距离
+    pub distance: f64,
+}
+
+/// 参数曲线
+///
+/// 实现者只需提供求值、导数以及打断/截取/反向，弧长、曲率和最近点由默认实现给出。
+/// 打断和截取的结果可能是另一种几何类型（例如圆截取后为圆弧）。
+pub trait Curve {
+    /// 参数范围
+    fn param_range(&self) -> (f64, f64);
+
+    /// 参数 t 处的点
+    fn point_at_param(&self, t: f64) -> Point2;
+
+    /// 参数 t 处的一阶导数
+    fn derivative_at_param(&self, t: f64) -> Vector2;
+
+    /// 参数 t 处的二阶导数
+    fn second_derivative_at_param(&self, t: f64) -> Vector2;
+
+    /// 是否闭合
+    fn is_closed(&self) -> bool;
+
+    /// 在参数 t 处打断为两段，t 不在参数范围内部时返回 None
+    ///
+    /// 闭合曲线在参数起点和 t 处断开。
+    fn split_at(&self, t: f64) -> Option<(Geometry, Geometry)>;
+
+    /// 截取参数区间 `[t0, t1]` 的子曲线
+    ///
+    /// 闭合曲线允许 `t0 > t1`，此时子曲线跨过参数起点。
+    fn sub_curve(&self, t0: f64, t1: f64) -> Option<Geometry>;
+
+    /// 方向相反的曲线，无法表示时返回 None
+    fn reversed(&self) -> Option<Geometry>;
+
+    /// 光滑片段的分界参数（含两端），弧长积分和最近点搜索按片段进行
+    fn breakpoints(&self) -> Vec<f64> {
+        let (start, end) = self.param_range();
+        vec![start, end]
+    }
+
+    /// 起点
+    fn start_point(&self) -> Point2 {
+        self.point_at_param(self.param_range().0)
+    }
+
+    /// 终点
+    fn end_point(&self) -> Point2 {
+        self.point_at_param(self.param_range().1)
+    }
+
+    /// 参数 t 处的单位切向，导数为零时返回 None
+    fn tangent_at_param(&self, t: f64) -> Option<Vector2> {
+        let derivative = self.derivative_at_param(t);
+        (derivative.norm() > EPSILON).then(|| derivative.normalize())
+    }
+
+    /// 参数 t 处的有向曲率（逆时针弯曲为正）
+    fn curvature_at_param(&self, t: f64) -> f64 {
+        let d1 = self.derivative_at_param(t);
+        let d2 = self.second_derivative_at_param(t);
+        let speed = d1.norm();
+        if speed < EPSILON {
+            return 0.0;
+        }
+        d1.perp(&d2) / (speed * speed * speed)
+    }
+
+    /// 曲线总长
+    fn length(&self) -> f64 {
+        let (start, end) = self.param_range();
+        self.length_between(start, end)
+    }
+
+    /// 参数区间 `[t0, t1]` 上的弧长
+    fn length_between(&self, t0: f64, t1: f64) -> f64 {
+        let (lo, hi) = if t0 <= t1 { (t0, t1) } else { (t1, t0) };
+        let breaks = self.breakpoints();
+        let mut total = 0.0;
+        for piece in breaks.windows(2) {
+            let (a, b) = (piece[0].max(lo), piece[1].min(hi));
+            if b <= a {
+                continue;
+            }
+            let step = (b - a) / LENGTH_SUBDIVISIONS as f64;
+            for k in 0..LENGTH_SUBDIVISIONS {
+                let mid = a + step * (k as f64 + 0.5);
+                total += GAUSS_NODES
+                    .iter()
+                    .map(|(x, w)| w * self.derivative_at_param(mid + 0.5 * step * x).norm())
+                    .sum::<f64>()
+                    * 0.5
+                    * step;
+            }
+        }
+        total
+    }
+
+    /// 从起点量取弧长 s 处的参数（s 超出范围时取端点）
+    fn param_at_length(&self, s: f64) -> f64 {
+        let (start, end) = self.param_range();
+        let total = self.length();
+        if s <= 0.0 || total < EPSILON {
+            return start;
+        }
+        if s >= total {
+            return end;
+        }
+
+        // 牛顿迭代，越出区间时退回二分
+        let (mut lo, mut hi) = (start, end);
+        let mut t = start + (end - start) * s / total;
+        for _ in 0..50 {
+            let error = self.length_between(start, t) - s;
+            if error.abs() < 1e-10 * total.max(1.0) {
+                break;
+            }
+            if error > 0.0 {
+                hi = t;
+            } else {
+                lo = t;
+            }
+            let speed = self.derivative_at_param(t).norm();
+            let next = if speed > EPSILON { t - error / speed } else { f64::NAN };
+            t = if next > lo && next < hi { next } else { 0.5 * (lo + hi) };
+        }
+        t
+    }
+
+    /// 曲线上离 point 最近的点
+    fn closest_point(&self, point: &Point2) -> CurvePoint {
+        let at = |t: f64| {
+            let p = self.point_at_param(t);
+            CurvePoint { param: t, point: p, distance: (p - point).norm() }
+        };
+
+        let breaks = self.breakpoints();
+        let mut best = at(breaks[0]);
+        for piece in breaks.windows(2) {
+            let (a, b) = (piece[0], piece[1]);
+            if b <= a {
+                continue;
+            }
+            let step = (b - a) / CLOSEST_SAMPLES as f64;
+            let (index, sample) = (0..=CLOSEST_SAMPLES)
+                .map(|k| at(a + step * k as f64))
+                .enumerate()
+                .min_by(|(_, x), (_, y)| x.distance.total_cmp(&y.distance))
+                .unwrap();
+
+            // 在最近采样点的邻域内用牛顿法求 (C(t) - P)·C'(t) = 0
+            let lo = a + step * index.saturating_sub(1) as f64;
+            let hi = (a + step * (index + 1) as f64).min(b);
+            let mut t = sample.param;
+            for _ in 0..20 {
+                let offset = self.point_at_param(t) - point;
+                let d1 = self.derivative_at_param(t);
+                let d2 = self.second_derivative_at_param(t);
+                let slope = d1.norm_squared() + offset.dot(&d2);
+                if slope.abs() < EPSILON {
+                    break;
+                }
+                let next = (t - offset.dot(&d1) / slope).clamp(lo, hi);
+                if (next - t).abs() < 1e-14 * (1.0 + t.abs()) {
+                    t = next;
+                    break;
+                }
+                t = next;
+            }
+
+            for candidate in [sample, at(t), at(b)] {
+                if candidate.distance < best.distance {
+                    best = candidate;
+                }
+            }
+        }
+        best
+    }
+}
+
+impl Geometry {
+    /// 作为参数曲线访问（直线、圆、圆弧、椭圆、样条、多段线）
+    pub fn as_curve(&self) -> Option<&dyn Curve> {
+        match self {
+            Geometry::Line(line) => Some(line),
+            Geometry::Circle(circle) => Some(circle),
+            Geometry::Arc(arc) => Some(arc),
+            Geometry::Ellipse(ellipse) => Some(ellipse),
+            Geometry::Spline(spline) => Some(spline),
+            Geometry::Polyline(polyline) => Some(polyline),
+            _ => None,
+        }
+    }
+}
+
+/// 参数是否位于区间内部（不含端点）
+fn is_interior(t: f64, (start, end): (f64, f64)) -> bool {
+    t > start + EPSILON && t < end - EPSILON
+}
+
+/// 参数区间是否位于范围内且不退化
+fn is_sub_range(t0: f64, t1: f64, (start, end): (f64, f64)) -> bool {
+    t0 >= start - EPSILON && t1 <= end + EPSILON && t1 - t0 > EPSILON
+}
+
+fn unit_vector(angle: f64) -> Vector2 {
+    Vector2::new(angle.cos(), angle.sin())
+}
+
+// ========== 直线 ==========
+
+impl Curve for Line {
+    fn param_range(&self) -> (f64, f64) {
+        (0.0, 1.0)
+    }
+
+    fn point_at_param(&self, t: f64) -> Point2 {
+        self.start + (self.end - self.start) * t
+    }
+
+    fn derivative_at_param(&self, _t: f64) -> Vector2 {
+        self.end - self.start
+    }
+
+    fn second_derivative_at_param(&self, _t: f64) -> Vector2 {
+        Vector2::zeros()
+    }
+
+    fn is_closed(&self) -> bool {
+        false
+    }
+
+    fn split_at(&self, t: f64) -> Option<(Geometry, Geometry)> {
+        if !is_interior(t, (0.0, 1.0)) {
+            return None;
+        }
+        let point = Curve::point_at_param(self, t);
+        Some((Geometry::Line(Line::new(self.start, point)), Geometry::Line(Line::new(point, self.end))))
+    }
+
+    fn sub_curve(&self, t0: f64, t1: f64) -> Option<Geometry> {
+        is_sub_range(t0, t1, (0.0, 1.0)).then(|| {
+            Geometry::Line(Line::new(Curve::point_at_param(self, t0), Curve::point_at_param(self, t1)))
+        })
+    }
+
+    fn reversed(&self) -> Option<Geometry> {
+        Some(Geometry::Line(Line::new(self.end, self.start)))
+    }
+
+    fn length(&self) -> f64 {
+        Line::length(self)
+    }
+
+    fn closest_point(&self, point: &Point2) -> CurvePoint {
+        let direction = self.end - self.start;
+        let length_sq = direction.norm_squared();
+        let param = if length_sq < EPSILON * EPSILON {
+            0.0
+        } else {
+            ((point - self.start).dot(&direction) / length_sq).clamp(0.0, 1.0)
+        };
+        let nearest = Curve::point_at_param(self, param);
+        CurvePoint { param, point: nearest, distance: (nearest - point).norm() }
+    }
+}
+
+// ========== 圆和圆弧 ==========
+
+/// 圆心、半径和角度确定的圆周点及其导数
+fn circular_point(center: Point2, radius: f64, angle: f64) -> Point2 {
+    center + unit_vector(angle) * radius
+}
+
+fn circular_derivative(radius: f64, angle: f64) -> Vector2 {
+    Vector2::new(-angle.sin(), angle.cos()) * radius
+}
+
+/// 顺时针的圆弧，以单段带负凸度的多段线表示
+fn clockwise_arc(center: Point2, radius: f64, from: f64, sweep: f64) -> Polyline {
+    let start = circular_point(center, radius, from);
+    let end = circular_point(center, radius, from - sweep);
+    Polyline::new(
+        vec![PolylineVertex::with_bulge(start, -(sweep / 4.0).tan()), PolylineVertex::new(end)],
+        false,
+    )
+}
+
+impl Curve for Circle {
+    fn param_range(&self) -> (f64, f64) {
+        (0.0, TAU)
+    }
+
+    fn point_at_param(&self, t: f64) -> Point2 {
+        circular_point(self.center, self.radius, t)
+    }
+
+    fn derivative_at_param(&self, t: f64) -> Vector2 {
+        circular_derivative(self.radius, t)
+    }
+
+    fn second_derivative_at_param(&self, t: f64) -> Vector2 {
+        -unit_vector(t) * self.radius
+    }
+
+    fn is_closed(&self) -> bool {
+        true
+    }
+
+    fn split_at(&self, t: f64) -> Option<(Geometry, Geometry)> {
+        is_interior(t, (0.0, TAU)).then(|| {
+            (
+                Geometry::Arc(Arc::new(self.center, self.radius, 0.0, t)),
+                Geometry::Arc(Arc::new(self.center, self.radius, t, TAU)),
+            )
+        })
+    }
+
+    fn sub_curve(&self, t0: f64, t1: f64) -> Option<Geometry> {
+        ((t1 - t0).rem_euclid(TAU) > EPSILON).then(|| Geometry::Arc(Arc::new(self.center, self.radius, t0, t1)))
+    }
+
+    /// 反向的圆以两段顺时针半圆组成的闭合多段线表示
+    fn reversed(&self) -> Option<Geometry> {
+        let vertices = [0.0, PI]
+            .iter()
+            .map(|&angle| PolylineVertex::with_bulge(circular_point(self.center, self.radius, angle), -1.0))
+            .collect();
+        Some(Geometry::Polyline(Polyline::new(vertices, true)))
+    }
+}
+
+impl Curve for Arc {
+    fn param_range(&self) -> (f64, f64) {
+        (self.start_angle, self.start_angle + self.sweep_angle())
+    }
+
+    fn point_at_param(&self, t: f64) -> Point2 {
+        circular_point(self.center, self.radius, t)
+    }
+
+    fn derivative_at_param(&self, t: f64) -> Vector2 {
+        circular_derivative(self.radius, t)
+    }
+
+    fn second_derivative_at_param(&self, t: f64) -> Vector2 {
+        -unit_vector(t) * self.radius
+    }
+
+    fn is_closed(&self) -> bool {
+        false
+    }
+
+    fn split_at(&self, t: f64) -> Option<(Geometry, Geometry)> {
+        let (start, end) = Curve::param_range(self);
+        is_interior(t, (start, end)).then(|| {
+            (
+                Geometry::Arc(Arc::new(self.center, self.radius, start, t)),
+                Geometry::Arc(Arc::new(self.center, self.radius, t, end)),
+            )
+        })
+    }
+
+    fn sub_curve(&self, t0: f64, t1: f64) -> Option<Geometry> {
+        is_sub_range(t0, t1, Curve::param_range(self))
+            .then(|| Geometry::Arc(Arc::new(self.center, self.radius, t0, t1)))
+    }
+
+    /// 圆弧总是逆时针，反向后以带负凸度的单段多段线表示
+    fn reversed(&self) -> Option<Geometry> {
+        let (_, end) = Curve::param_range(self);
+        Some(Geometry::Polyline(clockwise_arc(self.center, self.radius, end, self.sweep_angle())))
+    }
+}
+
+// ========== 椭圆 ==========
+
+impl Curve for Ellipse {
+    fn param_range(&self) -> (f64, f64) {
+        (self.start_param, self.end_param)
+    }
+
+    fn point_at_param(&self, t: f64) -> Point2 {
+        Ellipse::point_at_param(self, t)
+    }
+
+    fn derivative_at_param(&self, t: f64) -> Vector2 {
+        -self.major_axis * t.sin() + self.minor_axis() * t.cos()
+    }
+
+    fn second_derivative_at_param(&self, t: f64) -> Vector2 {
+        -self.major_axis * t.cos() - self.minor_axis() * t.sin()
+    }
+
+    fn is_closed(&self) -> bool {
+        self.is_full()
+    }
+
+    fn split_at(&self, t: f64) -> Option<(Geometry, Geometry)> {
+        is_interior(t, (self.start_param, self.end_param)).then(|| {
+            (
+                Geometry::Ellipse(Ellipse::arc(self.center, self.major_axis, self.ratio, self.start_param, t)),
+                Geometry::Ellipse(Ellipse::arc(self.center, self.major_axis, self.ratio, t, self.end_param)),
+            )
+        })
+    }
+
+    fn sub_curve(&self, t0: f64, t1: f64) -> Option<Geometry> {
+        let t1 = if self.is_full() && t1 < t0 { t1 + TAU } else { t1 };
+        let range = if self.is_full() { (t0, t0 + TAU) } else { (self.start_param, self.end_param) };
+        is_sub_range(t0, t1, range)
+            .then(|| Geometry::Ellipse(Ellipse::arc(self.center, self.major_axis, self.ratio, t0, t1)))
+    }
+
+    /// 椭圆总是沿逆时针方向，无法反向
+    fn reversed(&self) -> Option<Geometry> {
+        None
+    }
+}
+
+// ========== 样条 ==========
+
+impl Curve for Spline {
+    fn param_range(&self) -> (f64, f64) {
+        Spline::param_range(self)
+    }
+
+    fn point_at_param(&self, t: f64) -> Point2 {
+        Spline::point_at_param(self, t)
+    }
+
+    fn derivative_at_param(&self, t: f64) -> Vector2 {
+        Spline::derivative_at_param(self, t)
+    }
+
+    /// 由一阶导数的中心差分近似（节点处取单侧差分）
+    fn second_derivative_at_param(&self, t: f64) -> Vector2 {
+        let (start, end) = Spline::param_range(self);
+        let h = 1e-6 * (end - start).max(EPSILON);
+        let (a, b) = ((t - h).max(start), (t + h).min(end));
+        if b - a < EPSILON * EPSILON {
+            return Vector2::zeros();
+        }
+        (Spline::derivative_at_param(self, b) - Spline::derivative_at_param(self, a)) / (b - a)
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    fn breakpoints(&self) -> Vec<f64> {
+        let (start, end) = Spline::param_range(self);
+        let mut breaks = vec![start];
+        for &knot in self.knot_vector().iter() {
+            if knot > breaks[breaks.len() - 1] + EPSILON && knot < end - EPSILON {
+                breaks.push(knot);
+            }
+        }
+        breaks.push(end);
+        breaks
+    }
+
+    /// 重复插入节点 t 直到重数等于阶数，此时曲线在 t 处经过一个控制点，前后两半各自成为钳位样条
+    fn split_at(&self, t: f64) -> Option<(Geometry, Geometry)> {
+        if self.control_points.len() < 2 || !is_interior(t, Spline::param_range(self)) {
+            return None;
+        }
+        let mut refined = self.clone();
+        while refined.insert_knot(t) {}
+        let refined = refined.to_control_point_spline();
+        let degree = refined.eval_degree();
+        let knots = refined.knot_vector().into_owned();
+        let first = knots.iter().position(|k| (k - t).abs() < EPSILON)?;
+        let rational = !refined.weights.is_empty();
+
+        let mut left = refined.clone();
+        left.closed = false;
+        left.control_points.truncate(first);
+        left.knots = knots[..first + degree].iter().copied().chain([t]).collect();
+        if rational {
+            left.weights.truncate(first);
+        }
+
+        let mut right = refined;
+        right.closed = false;
+        right.control_points.drain(..first - 1);
+        right.knots = std::iter::once(t).chain(knots[first..].iter().copied()).collect();
+        if rational {
+            right.weights.drain(..first - 1);
+        }
+        Some((Geometry::Spline(left), Geometry::Spline(right)))
+    }
+
+    fn sub_curve(&self, t0: f64, t1: f64) -> Option<Geometry> {
+        let (start, end) = Spline::param_range(self);
+        if !is_sub_range(t0, t1, (start, end)) {
+            return None;
+        }
+        let tail = match self.split_at(t0) {
+            Some((_, Geometry::Spline(tail))) => tail,
+            _ => self.to_control_point_spline(),
+        };
+        match tail.split_at(t1) {
+            Some((head, _)) => Some(head),
+            None => Some(Geometry::Spline(tail)),
+        }
+    }
+
+    fn reversed(&self) -> Option<Geometry> {
+        let (start, end) = Spline::param_range(self);
+        let mut reversed = self.clone();
+        reversed.control_points.reverse();
+        reversed.weights.reverse();
+        reversed.knots = self.knot_vector().iter().rev().map(|k| start + end - k).collect();
+        reversed.fit_points.reverse();
+        reversed.start_tangent = self.end_tangent.map(|v| -v);
+        reversed.end_tangent = self.start_tangent.map(|v| -v);
+        Some(Geometry::Spline(reversed))
+    }
+}
+
+// ========== 多段线 ==========
+
+/// 多段线的一段：直线段的扫掠角为 0
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PolylineSegment {
+    pub start: Point2,
+    pub end: Point2,
+    /// 圆心（直线段为起点）
+    pub center: Point2,
+    /// 半径（直线段为 0）
+    pub radius: f64,
+    /// 起点的角度（弧度）
+    pub start_angle: f64,
+    /// 扫掠角：`4·atan(bulge)`，正值逆时针，负值顺时针
+    pub sweep: f64,
+}
+
+impl PolylineSegment {
+    /// 由起点、终点和起点的凸度创建
+    pub fn from_bulge(start: Point2, end: Point2, bulge: f64) -> Self {
+        let chord = end - start;
+        let length = chord.norm();
+        if bulge.abs() < EPSILON || length < EPSILON {
+            return Self { start, end, center: start, radius: 0.0, start_angle: 0.0, sweep: 0.0 };
+        }
+        // 凸度为 tan(θ/4)，圆心位于弦中点沿左法向偏移 (c/2)/tan(θ/2)
+        let sweep = 4.0 * bulge.atan();
+        let normal = Vector2::new(-chord.y, chord.x) / length;
+        let center = start + chord * 0.5 + normal * (0.5 * length / (0.5 * sweep).tan());
+        let radius = (0.5 * length / (0.5 * sweep).sin()).abs();
+        let offset = start - center;
+        Self { start, end, center, radius, start_angle: offset.y.atan2(offset.x), sweep }
+    }
+
+    /// 是否为圆弧段
+    pub fn is_arc(&self) -> bool {
+        self.sweep != 0.0
+    }
+
+    /// 段内参数 `u ∈ [0, 1]` 处的点（圆弧段按角度比例）
+    pub fn point(&self, u: f64) -> Point2 {
+        if self.is_arc() {
+            circular_point(self.center, self.radius, self.start_angle + u * self.sweep)
+        } else {
+            self.start + (self.end - self.start) * u
+        }
+    }
+
+    fn derivative(&self, u: f64) -> Vector2 {
+        if self.is_arc() {
+            circular_derivative(self.radius, self.start_angle + u * self.sweep) * self.sweep
+        } else {
+            self.end - self.start
+        }
+    }
+
+    fn second_derivative(&self, u: f64) -> Vector2 {
+        if self.is_arc() {
+            -unit_vector(self.start_angle + u * self.sweep) * (self.radius * self.sweep * self.sweep)
+        } else {
+            Vector2::zeros()
+        }
+    }
+
+    /// 段长
+    pub fn length(&self) -> f64 {
+        if self.is_arc() {
+            self.radius * self.sweep.abs()
+        } else {
+            (self.end - self.start).norm()
+        }
+    }
+
+    /// 转换为独立的线段或圆弧（圆弧总是逆时针，顺时针段的起止角互换）
+    pub fn to_geometry(&self) -> Geometry {
+        if !self.is_arc() {
+            return Geometry::Line(Line::new(self.start, self.end));
+        }
+        let end_angle = self.start_angle + self.sweep;
+        let (start_angle, end_angle) = if self.sweep > 0.0 {
+            (self.start_angle, end_angle)
+        } else {
+            (end_angle, self.start_angle)
+        };
+        Geometry::Arc(Arc::new(self.center, self.radius, start_angle, end_angle))
+    }
+}
+
+/// 子区间 `[u0, u1]` 对应的凸度
+fn partial_bulge(bulge: f64, u0: f64, u1: f64) -> f64 {
+    (bulge.atan() * (u1 - u0)).tan()
+}
+
+impl Polyline {
+    /// 第 `index` 段，闭合多段线的最后一段从末顶点回到首顶点
+    pub fn segment(&self, index: usize) -> PolylineSegment {
+        let v1 = &self.vertices[index];
+        let v2 = &self.vertices[(index + 1) % self.vertices.len()];
+        PolylineSegment::from_bulge(v1.point, v2.point, v1.bulge)
+    }
+
+    /// 依次遍历各段
+    pub fn segments(&self) -> impl Iterator<Item = PolylineSegment> + '_ {
+        (0..self.segment_count()).map(|index| self.segment(index))
+    }
+
+    /// 按段展开的顶点：闭合多段线在末尾重复第一个顶点
+    fn open_vertices(&self) -> Vec<PolylineVertex> {
+        let mut vertices = self.vertices.clone();
+        if self.closed && vertices.len() > 1 {
+            vertices.push(PolylineVertex::new(vertices[0].point));
+        }
+        vertices
+    }
+
+    /// 参数 t 所在的段及段内参数
+    fn segment_at(&self, t: f64) -> (PolylineSegment, f64) {
+        let count = self.segment_count();
+        let index = (t.floor().max(0.0) as usize).min(count - 1);
+        (self.segment(index), t - index as f64)
+    }
+
+    /// 截取展开顶点上的参数区间，要求 `t0 < t1`
+    fn sub_polyline(vertices: &[PolylineVertex], t0: f64, t1: f64) -> Polyline {
+        let last = vertices.len() - 2;
+        let first_index = (t0.floor().max(0.0) as usize).min(last);
+        let last_index = ((t1.ceil() as usize).saturating_sub(1)).clamp(first_index, last);
+        let point_at = |index: usize, u: f64| {
+            let (v1, v2) = (&vertices[index], &vertices[index + 1]);
+            PolylineSegment::from_bulge(v1.point, v2.point, v1.bulge).point(u)
+        };
+        let (u0, u1) = (t0 - first_index as f64, t1 - last_index as f64);
+
+        let mut result = Vec::with_capacity(last_index - first_index + 2);
+        let end_u = if first_index == last_index { u1 } else { 1.0 };
+        result.push(PolylineVertex::with_bulge(
+            point_at(first_index, u0),
+            partial_bulge(vertices[first_index].bulge, u0, end_u),
+        ));
+        for (index, vertex) in vertices.iter().enumerate().take(last_index + 1).skip(first_index + 1) {
+            let bulge = if index == last_index { partial_bulge(vertex.bulge, 0.0, u1) } else { vertex.bulge };
+            result.push(PolylineVertex::with_bulge(vertex.point, bulge));
+        }
+        result.push(PolylineVertex::new(point_at(last_index, u1)));
+        Polyline::new(result, false)
+    }
+}
+
+impl Curve for Polyline {
+    fn param_range(&self) -> (f64, f64) {
+        (0.0, self.segment_count() as f64)
+    }
+
+    fn point_at_param(&self, t: f64) -> Point2 {
+        match self.vertices.len() {
+            0 => Point2::origin(),
+            1 => self.vertices[0].point,
+            _ => {
+                let (segment, u) = self.segment_at(t);
+                segment.point(u)
+            }
+        }
+    }
+
+    fn derivative_at_param(&self, t: f64) -> Vector2 {
+        if self.vertices.len() < 2 {
+            return Vector2::zeros();
+        }
+        let (segment, u) = self.segment_at(t);
+        segment.derivative(u)
+    }
+
+    fn second_derivative_at_param(&self, t: f64) -> Vector2 {
+        if self.vertices.len() < 2 {
+            return Vector2::zeros();
+        }
+        let (segment, u) = self.segment_at(t);
+        segment.second_derivative(u)
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    fn breakpoints(&self) -> Vec<f64> {
+        (0..=self.segment_count()).map(|i| i as f64).collect()
+    }
+
+    fn split_at(&self, t: f64) -> Option<(Geometry, Geometry)> {
+        let range = Curve::param_range(self);
+        if !is_interior(t, range) {
+            return None;
+        }
+        let vertices = self.open_vertices();
+        Some((
+            Geometry::Polyline(Self::sub_polyline(&vertices, range.0, t)),
+            Geometry::Polyline(Self::sub_polyline(&vertices, t, range.1)),
+        ))
+    }
+
+    fn sub_curve(&self, t0: f64, t1: f64) -> Option<Geometry> {
+        let range = Curve::param_range(self);
+        let vertices = self.open_vertices();
+        if self.closed && t1 < t0 && t0 <= range.1 && t1 >= range.0 {
+            // 跨过起点：拼接 [t0, 末端] 和 [起点, t1]
+            let mut head = Self::sub_polyline(&vertices, t0, range.1);
+            if t1 > range.0 + EPSILON {
+                head.vertices.pop();
+                head.vertices.extend(Self::sub_polyline(&vertices, range.0, t1).vertices);
+            }
+            return Some(Geometry::Polyline(head));
+        }
+        is_sub_range(t0, t1, range).then(|| Geometry::Polyline(Self::sub_polyline(&vertices, t0, t1)))
+    }
+
+    fn reversed(&self) -> Option<Geometry> {
+        let vertices = self.open_vertices();
+        let mut reversed: Vec<PolylineVertex> = (0..vertices.len())
+            .rev()
+            .map(|i| {
+                let bulge = if i > 0 { -vertices[i - 1].bulge } else { 0.0 };
+                PolylineVertex::with_bulge(vertices[i].point, bulge)
+            })
+            .collect();
+        if self.closed && reversed.len() > 1 {
+            reversed.pop();
+        }
+        Some(Geometry::Polyline(Polyline::new(reversed, self.closed)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: Point2, b: Point2) {
+        assert!((a - b).norm() < 1e-6, "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn test_arc_length_and_curvature() {
+        let arc = Arc::new(Point2::new(1.0, 1.0), 2.0, 0.0, PI / 2.0);
+        assert!((Curve::length(&arc) - PI).abs() < 1e-9);
+        assert!((arc.curvature_at_param(0.3) - 0.5).abs() < 1e-9);
+
+        // 弧长一半处的参数位于中间角度
+        let t = arc.param_at_length(PI / 2.0);
+        assert!((t - PI / 4.0).abs() < 1e-9);
+
+        let ellipse = Ellipse::from_radii(Point2::origin(), 3.0, 3.0);
+        assert!((Curve::length(&ellipse) - 6.0 * PI).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_closest_point() {
+        let ellipse = Ellipse::from_radii(Point2::origin(), 4.0, 2.0);
+        let target = Point2::new(0.5, 5.0);
+        let nearest = ellipse.closest_point(&target);
+        // 最近点处的连线垂直于切向，且不比密集采样差
+        let tangent = ellipse.tangent_at_param(nearest.param).unwrap();
+        assert!((nearest.point - target).dot(&tangent).abs() < 1e-9);
+        let sampled = ellipse.sample_points(10_000).iter().map(|p| (p - target).norm()).fold(f64::MAX, f64::min);
+        assert!(nearest.distance <= sampled + 1e-9);
+
+        let spline = Spline::from_control_points(
+            vec![Point2::new(0.0, 0.0), Point2::new(1.0, 2.0), Point2::new(3.0, 2.0), Point2::new(4.0, 0.0)],
+            3,
+            false,
+        );
+        let direction = spline.derivative_at_param(0.37).normalize();
+        let target = spline.point_at_param(0.37) + Vector2::new(-direction.y, direction.x) * 0.1;
+        let nearest = spline.closest_point(&target);
+        assert!((nearest.param - 0.37).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_split_and_sub_curve() {
+        // 带圆弧段的多段线：打断后两段长度之和不变，端点连续
+        let polyline = Polyline::new(
+            vec![
+                PolylineVertex::new(Point2::new(0.0, 0.0)),
+                PolylineVertex::with_bulge(Point2::new(4.0, 0.0), 1.0),
+                PolylineVertex::new(Point2::new(4.0, 4.0)),
+            ],
+            false,
+        );
+        let total = Curve::length(&polyline);
+        assert!((total - (4.0 + 2.0 * PI)).abs() < 1e-9);
+        let (first, second) = polyline.split_at(1.5).unwrap();
+        let (first, second) = (first.as_curve().unwrap(), second.as_curve().unwrap());
+        assert!((first.length() + second.length() - total).abs() < 1e-9);
+        assert_close(first.end_point(), polyline.point_at_param(1.5));
+        assert_close(second.start_point(), polyline.point_at_param(1.5));
+        assert_close(second.end_point(), Point2::new(4.0, 4.0));
+
+        // 样条打断后两半分别与原曲线重合
+        let spline = Spline::from_control_points(
+            vec![Point2::new(0.0, 0.0), Point2::new(1.0, 3.0), Point2::new(2.0, -1.0), Point2::new(4.0, 2.0), Point2::new(5.0, 0.0)],
+            3,
+            false,
+        );
+        let (start, end) = Spline::param_range(&spline);
+        let cut = start + 0.4 * (end - start);
+        let (Geometry::Spline(left), Geometry::Spline(right)) = spline.split_at(cut).unwrap() else {
+            panic!("expected splines");
+        };
+        assert_close(left.end_point(), spline.point_at_param(cut));
+        assert_close(right.start_point(), spline.point_at_param(cut));
+        let middle = 0.5 * (start + cut);
+        assert_close(left.point_at_param(middle), spline.point_at_param(middle));
+        assert!((Curve::length(&left) + Curve::length(&right) - Curve::length(&spline)).abs() < 1e-6);
+
+        // 闭合曲线跨过起点截取
+        let circle = Circle::new(Point2::origin(), 1.0);
+        let Some(Geometry::Arc(arc)) = circle.sub_curve(3.0 * PI / 2.0, PI / 2.0) else {
+            panic!("expected arc");
+        };
+        assert!((arc.sweep_angle() - PI).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_reversed() {
+        let arc = Arc::new(Point2::origin(), 2.0, 0.0, PI / 2.0);
+        let reversed = arc.reversed().unwrap();
+        let reversed = reversed.as_curve().unwrap();
+        assert_close(reversed.start_point(), arc.end_point());
+        assert_close(reversed.end_point(), arc.start_point());
+        assert_close(reversed.point_at_param(0.5), circular_point(arc.center, 2.0, PI / 4.0));
+
+        let polyline = Polyline::new(
+            vec![
+                PolylineVertex::with_bulge(Point2::new(0.0, 0.0), 0.5),
+                PolylineVertex::new(Point2::new(2.0, 0.0)),
+                PolylineVertex::new(Point2::new(2.0, 2.0)),
+            ],
+            true,
+        );
+        let Some(Geometry::Polyline(reversed)) = polyline.reversed() else {
+            panic!("expected polyline");
+        };
+        assert!(reversed.closed);
+        for t in [0.25, 1.5, 2.75] {
+            assert_close(reversed.point_at_param(3.0 - t), polyline.point_at_param(t));
+        }
+
+        let spline = Spline::from_control_points(
+            vec![Point2::new(0.0, 0.0), Point2::new(1.0, 2.0), Point2::new(3.0, 2.0), Point2::new(4.0, 0.0)],
+            3,
+            false,
+        );
+        let Some(Geometry::Spline(reversed)) = spline.reversed() else {
+            panic!("expected spline");
+        };
+        let (start, end) = Spline::param_range(&spline);
+        assert_close(reversed.point_at_param(start + 0.3), spline.point_at_param(end - 0.3));
+    }
+
+    #[test]
+    fn test_polyline_segments() {
+        // 凸度 ±1 为半圆：正值逆时针（圆弧在弦右侧），负值顺时针
+        for bulge in [1.0, -1.0] {
+            let segment = PolylineSegment::from_bulge(Point2::new(0.0, 0.0), Point2::new(2.0, 0.0), bulge);
+            assert_close(segment.center, Point2::new(1.0, 0.0));
+            assert!((segment.radius - 1.0).abs() < 1e-12);
+            assert!((segment.sweep - bulge * PI).abs() < 1e-12);
+            assert_close(segment.point(0.5), Point2::new(1.0, -bulge));
+            assert!((segment.length() - PI).abs() < 1e-12);
+
+            let geometry = segment.to_geometry();
+            assert!(matches!(geometry, Geometry::Arc(_)));
+            assert!(geometry.contains_point(&Point2::new(1.0, -bulge), 1e-9));
+            assert!(!geometry.contains_point(&Point2::new(1.0, bulge), 1e-9));
+        }
+
+        let polyline = Polyline::new(
+            vec![
+                PolylineVertex::with_bulge(Point2::new(0.0, 0.0), -0.5),
+                PolylineVertex::new(Point2::new(4.0, 0.0)),
+                PolylineVertex::new(Point2::new(4.0, 3.0)),
+            ],
+            true,
+        );
+        let parts = polyline.explode();
+        assert!(matches!(parts[0], Geometry::Arc(_)));
+        let lengths: f64 = parts
+            .iter()
+            .map(|part| match part {
+                Geometry::Line(line) => line.length(),
+                Geometry::Arc(arc) => arc.length(),
+                _ => unreachable!(),
+            })
+            .sum();
+        assert!((lengths - polyline.length()).abs() < 1e-9);
+        assert!((polyline.length() - Curve::length(&polyline)).abs() < 1e-9);
+    }
+}
@@ -71,6 +71,15 @@ pub enum DimTextVertical {
     JIS,
 }
 
+/// 公差文字
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToleranceText {
+    /// 对称公差，与测量值写在同一行（如 "±0.10"）
+    Symmetric(String),
+    /// 上下偏差，叠放在测量值右侧
+    Deviation { upper: String, lower: String },
+}
+
 /// 标注样式
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DimStyle {
@@ -154,6 +163,24 @@ pub struct DimStyle {
     pub alternate_precision: u8,
     /// 替代单位比例因子
     pub alternate_scale_factor: f64,
+
+    // ===== 标注链 =====
+    /// 基线标注中相邻尺寸线的间距
+    #[serde(default = "default_baseline_spacing")]
+    pub baseline_spacing: f64,
+
+    // ===== 文字样式 =====
+    /// 标注文字使用的文字样式名称
+    #[serde(default = "default_text_style")]
+    pub text_style: String,
+}
+
+fn default_baseline_spacing() -> f64 {
+    3.75
+}
+
+fn default_text_style() -> String {
+    "Standard".to_string()
 }
 
 impl Default for DimStyle {
@@ -206,6 +233,12 @@ impl Default for DimStyle {
             alternate_unit: Unit::Inch,
             alternate_precision: 2,
             alternate_scale_factor: 1.0,
+
+            // 标注链
+            baseline_spacing: default_baseline_spacing(),  // mm
+
+            // 文字样式
+            text_style: default_text_style(),
         }
     }
 }
@@ -260,67 +293,96 @@ impl DimStyle {
             show_tolerance: true,
             tolerance_upper: 0.1,
             tolerance_lower: -0.1,
+            baseline_spacing: 7.0,
             ..Default::default()
         }
     }
     
-    /// 格式化测量值
+    /// 格式化测量值（单行：主单位 + 公差 + 替代单位）
     pub fn format_measurement(&self, value: f64) -> String {
-        use crate::units::format_linear;
-        
-        let scaled_value = value * self.linear_scale_factor;
-        let formatted = format_linear(
-            scaled_value,
+        let mut result = self.format_primary(value);
+        match self.format_tolerance() {
+            Some(ToleranceText::Symmetric(text)) => result.push_str(&text),
+            Some(ToleranceText::Deviation { upper, lower }) => {
+                result.push_str(&format!(" {}/{}", upper, lower));
+            }
+            None => {}
+        }
+        if let Some(alternate) = self.format_alternate(value) {
+            result.push(' ');
+            result.push_str(&alternate);
+        }
+        result
+    }
+    
+    /// 格式化角度
+    pub fn format_angle(&self, radians: f64) -> String {
+        crate::units::format_angle(radians, self.angle_format, self.angle_precision)
+    }
+
+    /// 格式化主单位文字：前缀 + 数值 + 后缀（不含公差和替代单位）
+    pub fn format_primary(&self, value: f64) -> String {
+        let mut formatted = crate::units::format_linear(
+            value * self.linear_scale_factor,
             self.linear_unit,
             self.linear_format,
             self.linear_precision,
             self.show_unit,
         );
-        
-        let mut result = String::new();
-        
-        // 添加前缀
-        if !self.prefix.is_empty() {
-            result.push_str(&self.prefix);
+        if self.zero_suppression && self.linear_format == LinearFormat::Decimal && !self.show_unit {
+            formatted = suppress_trailing_zeros(&formatted);
         }
-        
-        result.push_str(&formatted);
-        
-        // 添加公差
-        if self.show_tolerance {
-            result.push_str(&format!(
-                " +{:.prec$}/-{:.prec$}",
-                self.tolerance_upper,
-                self.tolerance_lower.abs(),
-                prec = self.tolerance_precision as usize
-            ));
+        format!("{}{}{}", self.prefix, formatted, self.suffix)
+    }
+
+    /// 替代单位文字（含方括号），未启用替代单位时返回 None
+    pub fn format_alternate(&self, value: f64) -> Option<String> {
+        if !self.show_alternate_units {
+            return None;
         }
-        
-        // 添加后缀
-        if !self.suffix.is_empty() {
-            result.push_str(&self.suffix);
+        let scaled_value = value * self.linear_scale_factor;
+        let alt_value = crate::units::convert(scaled_value, self.linear_unit, self.alternate_unit);
+        let formatted = crate::units::format_linear(
+            alt_value * self.alternate_scale_factor,
+            self.alternate_unit,
+            LinearFormat::Decimal,
+            self.alternate_precision,
+            true,
+        );
+        Some(format!("[{}]", formatted))
+    }
+
+    /// 公差文字，未启用公差时返回 None
+    ///
+    /// 上偏差与下偏差绝对值相等时为对称公差
+    pub fn format_tolerance(&self) -> Option<ToleranceText> {
+        if !self.show_tolerance {
+            return None;
         }
-        
-        // 添加替代单位
-        if self.show_alternate_units {
-            let alt_value = crate::units::convert(scaled_value, self.linear_unit, self.alternate_unit);
-            let alt_formatted = format_linear(
-                alt_value * self.alternate_scale_factor,
-                self.alternate_unit,
-                LinearFormat::Decimal,
-                self.alternate_precision,
-                true,
-            );
-            result.push_str(&format!(" [{}]", alt_formatted));
+        let prec = self.tolerance_precision as usize;
+        if (self.tolerance_upper + self.tolerance_lower).abs() < 1e-12 {
+            return Some(ToleranceText::Symmetric(format!("±{:.prec$}", self.tolerance_upper.abs())));
         }
-        
-        result
+        let signed = |v: f64| {
+            if v < 0.0 {
+                format!("-{:.prec$}", v.abs())
+            } else {
+                format!("+{:.prec$}", v)
+            }
+        };
+        Some(ToleranceText::Deviation {
+            upper: signed(self.tolerance_upper),
+            lower: signed(self.tolerance_lower),
+        })
     }
-    
-    /// 格式化角度
-    pub fn format_angle(&self, radians: f64) -> String {
-        crate::units::format_angle(radians, self.angle_format, self.angle_precision)
+}
+
+/// 去掉小数末尾的零（"2.500" -> "2.5"，"3.00" -> "3"）
+fn suppress_trailing_zeros(text: &str) -> String {
+    if !text.contains('.') {
+        return text.to_string();
     }
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
 }
 
 /// 标注样式管理器
@@ -346,6 +408,22 @@ impl DimStyleManager {
         }
     }
     
+    /// 由已有样式创建（如从文件加载）
+    ///
+    /// 样式列表为空时使用默认样式；找不到 `current` 时以第一个样式为当前样式
+    pub fn from_styles(styles: Vec<DimStyle>, current: &str) -> Self {
+        if styles.is_empty() {
+            let mut manager = Self::new();
+            manager.set_current_style(current);
+            return manager;
+        }
+        let current_style_index = styles.iter().position(|s| s.name == current).unwrap_or(0);
+        Self {
+            styles,
+            current_style_index,
+        }
+    }
+    
     /// 获取当前样式
     pub fn current_style(&self) -> &DimStyle {
         &self.styles[self.current_style_index]
@@ -370,6 +448,27 @@ impl DimStyleManager {
     pub fn add_style(&mut self, style: DimStyle) {
         self.styles.push(style);
     }
+
+    /// 添加样式，已有同名样式（不区分大小写）时替换
+    pub fn set_style(&mut self, style: DimStyle) {
+        match self.styles.iter_mut().find(|s| s.name.eq_ignore_ascii_case(&style.name)) {
+            Some(existing) => *existing = style,
+            None => self.styles.push(style),
+        }
+    }
+
+    /// 查找标注使用的样式（不区分大小写），名称为空或找不到时使用当前样式
+    pub fn resolve(&self, name: &str) -> &DimStyle {
+        self.styles
+            .iter()
+            .find(|s| s.name.eq_ignore_ascii_case(name))
+            .unwrap_or_else(|| self.current_style())
+    }
+    
+    /// 获取所有样式
+    pub fn styles(&self) -> &[DimStyle] {
+        &self.styles
+    }
     
     /// 获取所有样式名称
     pub fn style_names(&self) -> Vec<&str> {
@@ -412,4 +511,46 @@ mod tests {
         assert_eq!(manager.current_style().name, "Standard");
         assert_eq!(manager.style_names().len(), 4);
     }
+
+    #[test]
+    fn test_style_manager_from_styles() {
+        let mut custom = DimStyle::new("Custom");
+        custom.text_height = 5.0;
+        let manager = DimStyleManager::from_styles(vec![DimStyle::default(), custom], "Custom");
+        assert_eq!(manager.styles().len(), 2);
+        assert_eq!(manager.current_style().name, "Custom");
+        assert_eq!(manager.current_style().text_height, 5.0);
+
+        // 空列表回退到默认样式
+        let manager = DimStyleManager::from_styles(Vec::new(), "ISO-25");
+        assert_eq!(manager.style_names().len(), 4);
+        assert_eq!(manager.current_style().name, "ISO-25");
+    }
+
+    #[test]
+    fn test_format_parts() {
+        let mut style = DimStyle::mechanical();
+        assert_eq!(style.format_primary(12.5), "12.500");
+        assert_eq!(
+            style.format_tolerance(),
+            Some(ToleranceText::Symmetric("±0.10".to_string()))
+        );
+        style.tolerance_lower = -0.05;
+        assert_eq!(
+            style.format_tolerance(),
+            Some(ToleranceText::Deviation { upper: "+0.10".to_string(), lower: "-0.05".to_string() })
+        );
+
+        style.zero_suppression = true;
+        style.prefix = "L=".to_string();
+        assert_eq!(style.format_primary(12.5), "L=12.5");
+
+        style.show_alternate_units = true;
+        assert_eq!(style.format_alternate(25.4).as_deref(), Some("[1.00\"]"));
+        assert_eq!(style.format_measurement(25.4), "L=25.4 +0.10/-0.05 [1.00\"]");
+
+        let manager = DimStyleManager::new();
+        assert_eq!(manager.resolve("iso-25").name, "ISO-25");
+        assert_eq!(manager.resolve("").name, "Standard");
+    }
 }
@@ -8,6 +8,13 @@ use std::sync::atomic::{AtomicU64, Ordering};
 /// 全局实体ID生成器
 static ENTITY_COUNTER: AtomicU64 = AtomicU64::new(1);
 
+/// 全局实体修订号生成器
+static REVISION_COUNTER: AtomicU64 = AtomicU64::new(1);
+
+fn next_revision() -> u64 {
+    REVISION_COUNTER.fetch_add(1, Ordering::Relaxed)
+}
+
 /// 实体唯一标识符
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct EntityId {
@@ -27,7 +34,7 @@ impl EntityId {
     }
 
     /// 从指定值创建（用于文件加载）
-    pub fn from_raw(id: u64, generation: u32) -> Self {
+    pub const fn from_raw(id: u64, generation: u32) -> Self {
         Self { id, generation }
     }
 
@@ -71,6 +78,12 @@ pub struct Entity {
 
     /// 是否锁定（不可编辑）
     pub locked: bool,
+
+    /// 修订号：实体每次经文档修改都会换成新的值，渲染端据此判断细分缓存是否过期
+    ///
+    /// 不保存到文件，加载时重新分配
+    #[serde(skip, default = "next_revision")]
+    pub revision: u64,
 }
 
 impl Entity {
@@ -83,9 +96,15 @@ impl Entity {
             layer_id: EntityId::NULL,
             visible: true,
             locked: false,
+            revision: next_revision(),
         }
     }
 
+    /// 标记实体已修改，分配新的修订号
+    pub fn touch(&mut self) {
+        self.revision = next_revision();
+    }
+
     /// 获取包围盒
     pub fn bounding_box(&self) -> crate::math::BoundingBox2 {
         self.geometry.bounding_box()
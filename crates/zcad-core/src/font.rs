@@ -0,0 +1,895 @@
+//! 字体与文字排版
+//!
+//! 加载 TrueType/OpenType 字体（含 `.ttc` 字体集）和 SHX 形字体，提供：
+//! - 精确的文字范围，用于包围盒、拾取和捕捉
+//! - 字形几何：TrueType 为填充轮廓，SHX 为单线笔画，供 GPU 渲染器和导出器绘制
+//! - 逐字符回退：首选字体缺少的字符（如汉字）依次在其他已加载字体中查找
+//!
+//! 所有度量以字高为单位：TrueType 字体的 em 方框等于字高（与界面和光栅导出一致），
+//! SHX 字体以字体信息中的上行高度为字高。
+//!
+//! 全局字体管理器（[`FontManager::global`]）默认包含内置西文字体和系统中文字体；
+//! 任何字体都没有的字符按 [`Text::estimated_width`] 的规则估算宽度，不绘制字形。
+//! SHX 大字体（bigfont）需要 GBK 码表，暂不支持，中文请使用 TrueType 字体。
+
+use crate::geometry::Text;
+use crate::math::{Point2, Vector2};
+use crate::tessellation;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError, RwLock, RwLockReadGuard};
+
+/// 内置西文字体名称
+pub const BUILTIN_FONT_NAME: &str = "builtin";
+
+/// 系统中文字体（界面、光栅导出与排版使用同一查找列表）
+pub const CJK_FONT_PATHS: [&str; 7] = [
+    // macOS
+    "/System/Library/Fonts/PingFang.ttc",
+    "/System/Library/Fonts/STHeiti Light.ttc",
+    "/System/Library/Fonts/Hiragino Sans GB.ttc",
+    // Linux
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/truetype/droid/DroidSansFallbackFull.ttf",
+    // Windows
+    "C:\\Windows\\Fonts\\msyh.ttc",
+    "C:\\Windows\\Fonts\\simsun.ttc",
+];
+
+/// TrueType 二次/三次曲线的细分段数
+const CURVE_SEGMENTS: usize = 8;
+
+/// SHX 子形最大嵌套深度
+const MAX_SUBSHAPE_DEPTH: usize = 8;
+
+/// 字体加载错误
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum FontError {
+    #[error("IO error: {0}")]
+    Io(String),
+
+    #[error("Invalid TrueType font: {0}")]
+    TrueType(String),
+
+    #[error("Invalid SHX font: {0}")]
+    Shx(String),
+}
+
+/// 字形（以字高为单位，原点为基线起点）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Glyph {
+    /// 步进宽度
+    pub advance: f64,
+    /// 轮廓或笔画折线
+    pub contours: Vec<Vec<Point2>>,
+    /// true 为填充轮廓（TrueType，非零环绕规则），false 为单线笔画（SHX）
+    pub filled: bool,
+}
+
+impl Glyph {
+    /// 字形墨迹的纵向范围 (最低点, 最高点)；空白字符返回 None
+    pub fn vertical_extent(&self) -> Option<(f64, f64)> {
+        self.contours
+            .iter()
+            .flatten()
+            .map(|p| (p.y, p.y))
+            .reduce(|(min, max), (y, _)| (min.min(y), max.max(y)))
+    }
+
+    fn map_points(&self, f: impl Fn(Point2) -> Point2) -> Self {
+        Self {
+            advance: self.advance,
+            contours: self.contours.iter().map(|c| c.iter().map(|p| f(*p)).collect()).collect(),
+            filled: self.filled,
+        }
+    }
+}
+
+/// 文字范围（图纸单位）
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TextExtents {
+    /// 步进总宽度
+    pub width: f64,
+    /// 墨迹最高点到基线的距离
+    pub ascent: f64,
+    /// 墨迹最低点到基线的距离（基线以下为正）
+    pub descent: f64,
+}
+
+/// 已加载的字体
+pub struct Font {
+    name: String,
+    kind: FontKind,
+    cache: Mutex<HashMap<char, Option<Arc<Glyph>>>>,
+}
+
+enum FontKind {
+    TrueType { data: Vec<u8>, index: u32 },
+    Shx(ShxFont),
+}
+
+impl std::fmt::Debug for Font {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self.kind {
+            FontKind::TrueType { .. } => "TrueType",
+            FontKind::Shx(_) => "SHX",
+        };
+        f.debug_struct("Font").field("name", &self.name).field("kind", &kind).finish()
+    }
+}
+
+impl Font {
+    /// 从 TrueType/OpenType 数据创建字体，`index` 为字体集中的序号
+    pub fn from_truetype(name: impl Into<String>, data: Vec<u8>, index: u32) -> Result<Self, FontError> {
+        ttf_parser::Face::parse(&data, index).map_err(|e| FontError::TrueType(e.to_string()))?;
+        Ok(Self::with_kind(name, FontKind::TrueType { data, index }))
+    }
+
+    /// 从编译后的 SHX 数据创建字体
+    pub fn from_shx(name: impl Into<String>, data: &[u8]) -> Result<Self, FontError> {
+        Ok(Self::with_kind(name, FontKind::Shx(ShxFont::parse(data)?)))
+    }
+
+    /// 从文件加载字体，按扩展名区分 SHX 与 TrueType；字体名为不含扩展名的文件名
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, FontError> {
+        let path = path.as_ref();
+        let data = std::fs::read(path).map_err(|e| FontError::Io(e.to_string()))?;
+        let name = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        let is_shx = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("shx"));
+        if is_shx {
+            Self::from_shx(name, &data)
+        } else {
+            Self::from_truetype(name, data, 0)
+        }
+    }
+
+    fn with_kind(name: impl Into<String>, kind: FontKind) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 字体名称
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 是否为单线笔画字体（SHX）
+    pub fn is_stroke_font(&self) -> bool {
+        matches!(self.kind, FontKind::Shx(_))
+    }
+
+    /// 字体是否包含该字符
+    pub fn has_glyph(&self, c: char) -> bool {
+        self.glyph(c).is_some()
+    }
+
+    /// 字符的步进宽度
+    pub fn advance(&self, c: char) -> Option<f64> {
+        self.glyph(c).map(|glyph| glyph.advance)
+    }
+
+    /// 字符的字形（结果按字符缓存）
+    pub fn glyph(&self, c: char) -> Option<Arc<Glyph>> {
+        let mut cache = self.cache.lock().unwrap_or_else(PoisonError::into_inner);
+        cache
+            .entry(c)
+            .or_insert_with(|| {
+                match &self.kind {
+                    FontKind::TrueType { data, index } => truetype_glyph(data, *index, c),
+                    FontKind::Shx(shx) => shx.glyph(c),
+                }
+                .map(Arc::new)
+            })
+            .clone()
+    }
+}
+
+fn truetype_glyph(data: &[u8], index: u32, c: char) -> Option<Glyph> {
+    let face = ttf_parser::Face::parse(data, index).ok()?;
+    let id = face.glyph_index(c)?;
+    let scale = 1.0 / face.units_per_em() as f64;
+    let advance = face.glyph_hor_advance(id).unwrap_or(0) as f64 * scale;
+
+    let mut builder = OutlineCollector::default();
+    face.outline_glyph(id, &mut builder);
+    ttf_parser::OutlineBuilder::close(&mut builder);
+    let contours = builder
+        .contours
+        .into_iter()
+        .map(|contour| contour.into_iter().map(|p| Point2::new(p.x * scale, p.y * scale)).collect())
+        .collect();
+    Some(Glyph {
+        advance,
+        contours,
+        filled: true,
+    })
+}
+
+/// 收集 TrueType 轮廓并细分曲线（字体单位）
+#[derive(Default)]
+struct OutlineCollector {
+    contours: Vec<Vec<Point2>>,
+    current: Vec<Point2>,
+}
+
+impl OutlineCollector {
+    fn last(&self) -> Point2 {
+        self.current.last().copied().unwrap_or_else(Point2::origin)
+    }
+}
+
+impl ttf_parser::OutlineBuilder for OutlineCollector {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.close();
+        self.current.push(Point2::new(x as f64, y as f64));
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.current.push(Point2::new(x as f64, y as f64));
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let p0 = self.last();
+        let p1 = Point2::new(x1 as f64, y1 as f64);
+        let p2 = Point2::new(x as f64, y as f64);
+        for i in 1..=CURVE_SEGMENTS {
+            let t = i as f64 / CURVE_SEGMENTS as f64;
+            let s = 1.0 - t;
+            self.current.push(Point2::from(p0.coords * (s * s) + p1.coords * (2.0 * s * t) + p2.coords * (t * t)));
+        }
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let p0 = self.last();
+        let p1 = Point2::new(x1 as f64, y1 as f64);
+        let p2 = Point2::new(x2 as f64, y2 as f64);
+        let p3 = Point2::new(x as f64, y as f64);
+        for i in 1..=CURVE_SEGMENTS {
+            let t = i as f64 / CURVE_SEGMENTS as f64;
+            let s = 1.0 - t;
+            self.current.push(Point2::from(
+                p0.coords * (s * s * s)
+                    + p1.coords * (3.0 * s * s * t)
+                    + p2.coords * (3.0 * s * t * t)
+                    + p3.coords * (t * t * t),
+            ));
+        }
+    }
+
+    fn close(&mut self) {
+        if self.current.len() >= 2 {
+            // 轮廓显式闭合，便于按折线绘制
+            if self.current.first() != self.current.last() {
+                self.current.push(self.current[0]);
+            }
+            self.contours.push(std::mem::take(&mut self.current));
+        } else {
+            self.current.clear();
+        }
+    }
+}
+
+// ========== SHX 形字体 ==========
+
+/// 16 个标准方向（向量长度为 1 个单位，斜向为 1 或 0.5 的组合）
+const SHX_DIRECTIONS: [(f64, f64); 16] = [
+    (1.0, 0.0),
+    (1.0, 0.5),
+    (1.0, 1.0),
+    (0.5, 1.0),
+    (0.0, 1.0),
+    (-0.5, 1.0),
+    (-1.0, 1.0),
+    (-1.0, 0.5),
+    (-1.0, 0.0),
+    (-1.0, -0.5),
+    (-1.0, -1.0),
+    (-0.5, -1.0),
+    (0.0, -1.0),
+    (0.5, -1.0),
+    (1.0, -1.0),
+    (1.0, -0.5),
+];
+
+/// 编译后的 SHX 形字体（"AutoCAD-86 shapes 1.x" 或 "AutoCAD-86 unifont 1.0"）
+#[derive(Debug, Clone)]
+struct ShxFont {
+    /// 形编号 -> 形定义字节（已去掉形名）
+    shapes: HashMap<u16, Vec<u8>>,
+    /// 大写字母高度（形单位），即字高
+    above: f64,
+    /// unifont 的子形编号为两字节
+    unifont: bool,
+}
+
+struct ByteReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], FontError> {
+        let end = self.offset + len;
+        let bytes = self
+            .data
+            .get(self.offset..end)
+            .ok_or_else(|| FontError::Shx("unexpected end of file".to_string()))?;
+        self.offset = end;
+        Ok(bytes)
+    }
+
+    fn u16(&mut self) -> Result<u16, FontError> {
+        self.take(2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, FontError> {
+        self.take(4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn at_end(&self) -> bool {
+        self.offset >= self.data.len()
+    }
+}
+
+/// 去掉形定义开头以 0 结尾的形名
+fn strip_shape_name(bytes: &[u8]) -> Vec<u8> {
+    match bytes.iter().position(|&b| b == 0) {
+        Some(pos) => bytes[pos + 1..].to_vec(),
+        None => Vec::new(),
+    }
+}
+
+impl ShxFont {
+    fn parse(data: &[u8]) -> Result<Self, FontError> {
+        let header_end = data
+            .iter()
+            .take(64)
+            .position(|&b| b == 0x1A)
+            .ok_or_else(|| FontError::Shx("missing file header".to_string()))?;
+        let signature = String::from_utf8_lossy(&data[..header_end]).to_ascii_lowercase();
+        let mut reader = ByteReader {
+            data,
+            offset: header_end + 1,
+        };
+
+        let mut shapes = HashMap::new();
+        let unifont = if signature.starts_with("autocad-86 unifont") {
+            let count = reader.u32()?;
+            let info_len = reader.u16()? as usize;
+            shapes.insert(0, strip_shape_name(reader.take(info_len)?));
+            for _ in 1..count {
+                if reader.at_end() {
+                    break;
+                }
+                let number = reader.u16()?;
+                let len = reader.u16()? as usize;
+                shapes.insert(number, strip_shape_name(reader.take(len)?));
+            }
+            true
+        } else if signature.starts_with("autocad-86 shapes") {
+            let _first = reader.u16()?;
+            let _last = reader.u16()?;
+            let count = reader.u16()?;
+            let index = (0..count)
+                .map(|_| Ok((reader.u16()?, reader.u16()? as usize)))
+                .collect::<Result<Vec<_>, FontError>>()?;
+            for (number, len) in index {
+                shapes.insert(number, strip_shape_name(reader.take(len)?));
+            }
+            false
+        } else if signature.starts_with("autocad-86 bigfont") {
+            return Err(FontError::Shx("bigfont is not supported".to_string()));
+        } else {
+            return Err(FontError::Shx(format!("unknown signature '{}'", signature.trim())));
+        };
+
+        // 形 0 为字体信息：上行高度、下行高度、模式……
+        let above = shapes
+            .get(&0)
+            .and_then(|info| info.first())
+            .filter(|&&above| above > 0)
+            .map_or(1.0, |&above| above as f64);
+        Ok(Self { shapes, above, unifont })
+    }
+
+    fn glyph(&self, c: char) -> Option<Glyph> {
+        let number = u16::try_from(c as u32).ok().filter(|&n| n != 0)?;
+        let spec = self.shapes.get(&number)?;
+        let mut state = ShapeState::default();
+        self.run(spec, &mut state, 0);
+        state.pen_up();
+
+        let scale = 1.0 / self.above;
+        Some(Glyph {
+            advance: state.position.x * scale,
+            contours: state
+                .contours
+                .into_iter()
+                .map(|contour| contour.into_iter().map(|p| Point2::from(p.coords * scale)).collect())
+                .collect(),
+            filled: false,
+        })
+    }
+
+    fn run(&self, spec: &[u8], state: &mut ShapeState, depth: usize) {
+        let mut i = 0;
+        while self.step(spec, &mut i, state, depth).is_some() {}
+    }
+
+    /// 执行一条形命令；遇到结束码或数据不完整时返回 None
+    fn step(&self, spec: &[u8], i: &mut usize, state: &mut ShapeState, depth: usize) -> Option<()> {
+        let mut next = || {
+            let byte = spec.get(*i).copied();
+            *i += 1;
+            byte
+        };
+        let code = next()?;
+        match code {
+            0 => return None,
+            1 => state.pen_down = true,
+            2 => state.pen_up(),
+            3 => {
+                let divisor = next()?;
+                if divisor != 0 {
+                    state.scale /= divisor as f64;
+                }
+            }
+            4 => state.scale *= next()? as f64,
+            5 => state.stack.push(state.position),
+            6 => {
+                if let Some(position) = state.stack.pop() {
+                    state.jump(position);
+                }
+            }
+            7 => {
+                let number = if self.unifont {
+                    u16::from_be_bytes([next()?, next()?])
+                } else {
+                    next()? as u16
+                };
+                if depth < MAX_SUBSHAPE_DEPTH {
+                    if let Some(subshape) = self.shapes.get(&number) {
+                        self.run(subshape, state, depth + 1);
+                    }
+                }
+            }
+            8 => {
+                let (dx, dy) = (next()? as i8, next()? as i8);
+                state.move_by(Vector2::new(dx as f64, dy as f64));
+            }
+            9 => loop {
+                let (dx, dy) = (next()? as i8, next()? as i8);
+                if dx == 0 && dy == 0 {
+                    break;
+                }
+                state.move_by(Vector2::new(dx as f64, dy as f64));
+            },
+            10 => {
+                let radius = next()? as f64;
+                let (start_octant, octants, sign) = split_octant_byte(next()?);
+                state.arc(radius, start_octant * 45.0, sign * octants * 45.0);
+            }
+            11 => {
+                let start_offset = next()? as f64 * 45.0 / 256.0;
+                let end_offset = next()? as f64 * 45.0 / 256.0;
+                let radius = u16::from_be_bytes([next()?, next()?]) as f64;
+                let (start_octant, octants, sign) = split_octant_byte(next()?);
+                let start = start_octant * 45.0 + sign * start_offset;
+                let end = if end_offset == 0.0 {
+                    start_octant * 45.0 + sign * octants * 45.0
+                } else {
+                    start_octant * 45.0 + sign * ((octants - 1.0) * 45.0 + end_offset)
+                };
+                state.arc(radius, start, end - start);
+            }
+            12 => {
+                let (dx, dy, bulge) = (next()? as i8, next()? as i8, next()? as i8);
+                state.bulge_to(Vector2::new(dx as f64, dy as f64), bulge as f64 / 127.0);
+            }
+            13 => loop {
+                let (dx, dy) = (next()? as i8, next()? as i8);
+                if dx == 0 && dy == 0 {
+                    break;
+                }
+                let bulge = next()? as i8;
+                state.bulge_to(Vector2::new(dx as f64, dy as f64), bulge as f64 / 127.0);
+            },
+            14 => {
+                // 下一条命令仅用于竖排文字：解析后丢弃其效果
+                let mut vertical = state.clone();
+                self.step(spec, i, &mut vertical, depth)?;
+            }
+            _ => {
+                let length = (code >> 4) as f64;
+                let (dx, dy) = SHX_DIRECTIONS[(code & 0x0F) as usize];
+                state.move_by(Vector2::new(dx, dy) * length);
+            }
+        }
+        Some(())
+    }
+}
+
+/// 八分圆弧说明字节：最高位为方向（1 为顺时针），4-6 位为起始八分位，低 3 位为八分位数（0 表示整圆）
+fn split_octant_byte(byte: u8) -> (f64, f64, f64) {
+    let sign = if byte & 0x80 != 0 { -1.0 } else { 1.0 };
+    let start = ((byte >> 4) & 0x07) as f64;
+    let count = match byte & 0x07 {
+        0 => 8.0,
+        n => n as f64,
+    };
+    (start, count, sign)
+}
+
+/// SHX 形中圆弧离散的弦高容差（形单位）
+const SHAPE_ARC_TOLERANCE: f64 = 0.05;
+
+/// SHX 形解释器状态（形单位）
+#[derive(Debug, Clone)]
+struct ShapeState {
+    position: Point2,
+    pen_down: bool,
+    scale: f64,
+    stack: Vec<Point2>,
+    current: Vec<Point2>,
+    contours: Vec<Vec<Point2>>,
+}
+
+impl Default for ShapeState {
+    fn default() -> Self {
+        Self {
+            position: Point2::origin(),
+            pen_down: true,
+            scale: 1.0,
+            stack: Vec::new(),
+            current: Vec::new(),
+            contours: Vec::new(),
+        }
+    }
+}
+
+impl ShapeState {
+    fn pen_up(&mut self) {
+        self.pen_down = false;
+        self.finish_stroke();
+    }
+
+    fn finish_stroke(&mut self) {
+        if self.current.len() >= 2 {
+            self.contours.push(std::mem::take(&mut self.current));
+        } else {
+            self.current.clear();
+        }
+    }
+
+    /// 不落笔移动到指定位置
+    fn jump(&mut self, position: Point2) {
+        self.finish_stroke();
+        self.position = position;
+    }
+
+    fn draw(&mut self, points: impl IntoIterator<Item = Point2>) {
+        if self.pen_down && self.current.is_empty() {
+            self.current.push(self.position);
+        }
+        for point in points {
+            if self.pen_down {
+                self.current.push(point);
+            }
+            self.position = point;
+        }
+    }
+
+    fn move_by(&mut self, delta: Vector2) {
+        let target = self.position + delta * self.scale;
+        self.draw([target]);
+    }
+
+    /// 从当前点出发的圆弧，角度为度，起始角指向当前点
+    fn arc(&mut self, radius: f64, start_degrees: f64, sweep_degrees: f64) {
+        let radius = radius * self.scale;
+        let start = start_degrees.to_radians();
+        let center = self.position - Vector2::new(start.cos(), start.sin()) * radius;
+        let points = tessellation::arc_points(center, radius, start, sweep_degrees.to_radians(), SHAPE_ARC_TOLERANCE);
+        self.draw(points.into_iter().skip(1));
+    }
+
+    fn bulge_to(&mut self, delta: Vector2, bulge: f64) {
+        let target = self.position + delta * self.scale;
+        let points = tessellation::bulge_points(self.position, target, bulge, SHAPE_ARC_TOLERANCE);
+        self.draw(points.into_iter().skip(1));
+    }
+}
+
+// ========== 字体管理器 ==========
+
+/// 字体管理器：按名称查找字体，并在字体之间逐字符回退
+#[derive(Debug, Default)]
+pub struct FontManager {
+    fonts: Vec<Font>,
+}
+
+impl FontManager {
+    /// 创建空的字体管理器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 包含内置西文字体和系统中文字体（若存在）的字体管理器
+    pub fn with_default_fonts() -> Self {
+        let mut manager = Self::new();
+        if let Ok(font) = Font::from_truetype(BUILTIN_FONT_NAME, epaint_default_fonts::UBUNTU_LIGHT.to_vec(), 0) {
+            manager.add_font(font);
+        }
+        if let Some(font) = CJK_FONT_PATHS.iter().find_map(|path| Font::load(path).ok()) {
+            manager.add_font(font);
+        }
+        manager
+    }
+
+    /// 全局字体管理器，首次访问时加载默认字体
+    pub fn global() -> &'static RwLock<FontManager> {
+        static GLOBAL: OnceLock<RwLock<FontManager>> = OnceLock::new();
+        GLOBAL.get_or_init(|| RwLock::new(FontManager::with_default_fonts()))
+    }
+
+    /// 以只读方式访问全局字体管理器
+    pub fn shared() -> RwLockReadGuard<'static, FontManager> {
+        Self::global().read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// 添加字体；同名字体（不区分大小写）被替换
+    pub fn add_font(&mut self, font: Font) {
+        match self.fonts.iter_mut().find(|f| f.name.eq_ignore_ascii_case(&font.name)) {
+            Some(existing) => *existing = font,
+            None => self.fonts.push(font),
+        }
+    }
+
+    /// 从文件加载并添加字体，返回字体名称
+    pub fn load_file(&mut self, path: impl AsRef<Path>) -> Result<String, FontError> {
+        let font = Font::load(path)?;
+        let name = font.name.clone();
+        self.add_font(font);
+        Ok(name)
+    }
+
+    /// 按名称查找字体（不区分大小写，忽略扩展名）
+    pub fn font(&self, name: &str) -> Option<&Font> {
+        let stem = Path::new(name).file_stem().and_then(|s| s.to_str()).unwrap_or(name);
+        self.fonts.iter().find(|f| f.name.eq_ignore_ascii_case(stem))
+    }
+
+    /// 所有已加载的字体（按回退顺序）
+    pub fn fonts(&self) -> &[Font] {
+        &self.fonts
+    }
+
+    /// 选择绘制字符的字体：优先使用指定字体，缺字时按加载顺序回退
+    fn resolve(&self, c: char, preferred: Option<&str>) -> Option<(&Font, Arc<Glyph>)> {
+        let preferred = preferred.and_then(|name| self.font(name));
+        preferred
+            .into_iter()
+            .chain(self.fonts.iter())
+            .find_map(|font| font.glyph(c).map(|glyph| (font, glyph)))
+    }
+
+    /// 文字中的可见字符是否都有字形
+    pub fn covers(&self, text: &str, font: Option<&str>) -> bool {
+        decode_special_codes(text)
+            .chars()
+            .all(|c| c.is_whitespace() || self.resolve(c, font).is_some())
+    }
+
+    /// 测量文字范围；文字先经过 [`decode_special_codes`] 转换
+    pub fn measure(&self, text: &str, height: f64, font: Option<&str>) -> TextExtents {
+        let mut width = 0.0;
+        let mut ink: Option<(f64, f64)> = None;
+        for c in decode_special_codes(text).chars() {
+            let (advance, extent) = match self.resolve(c, font) {
+                Some((_, glyph)) => (glyph.advance, glyph.vertical_extent()),
+                None => (Text::estimated_char_width(c, 1.0), Some((0.0, 1.0))),
+            };
+            width += advance;
+            if let Some((low, high)) = extent {
+                ink = Some(ink.map_or((low, high), |(l, h)| (l.min(low), h.max(high))));
+            }
+        }
+        // 没有墨迹（空串或仅空白）时取基线到字高
+        let (low, high) = ink.unwrap_or((0.0, 1.0));
+        TextExtents {
+            width: width * height,
+            ascent: high * height,
+            descent: -low * height,
+        }
+    }
+
+    /// 排版文字字形：原点为基线起点，x 沿书写方向，已按字高缩放
+    pub fn layout(&self, text: &str, height: f64, font: Option<&str>) -> Vec<Glyph> {
+        let mut glyphs = Vec::new();
+        let mut pen_x = 0.0;
+        for c in decode_special_codes(text).chars() {
+            match self.resolve(c, font) {
+                Some((_, glyph)) => {
+                    let offset = pen_x;
+                    glyphs.push(glyph.map_points(|p| Point2::new(offset + p.x * height, p.y * height)));
+                    pen_x += glyph.advance * height;
+                }
+                None => pen_x += Text::estimated_char_width(c, height),
+            }
+        }
+        glyphs.retain(|glyph| !glyph.contours.is_empty());
+        glyphs
+    }
+}
+
+/// 转换 AutoCAD 文字控制码：`%%C` 直径符号、`%%D` 度、`%%P` 正负号、`%%%` 百分号、
+/// `%%nnn` 按字符编码插入；`%%U`/`%%O`/`%%K` 下划线、上划线、删除线开关直接去除
+pub fn decode_special_codes(text: &str) -> Cow<'_, str> {
+    if !text.contains("%%") {
+        return Cow::Borrowed(text);
+    }
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find("%%") {
+        result.push_str(&rest[..pos]);
+        let after = &rest[pos + 2..];
+        let replacement = match after.chars().next().map(|c| c.to_ascii_lowercase()) {
+            Some('c') => Some('Ø'),
+            Some('d') => Some('°'),
+            Some('p') => Some('±'),
+            Some('%') => Some('%'),
+            Some('u' | 'o' | 'k') => None,
+            Some(d) if d.is_ascii_digit() => {
+                let code = after
+                    .get(..3)
+                    .filter(|digits| digits.bytes().all(|b| b.is_ascii_digit()))
+                    .and_then(|digits| digits.parse::<u32>().ok())
+                    .and_then(char::from_u32);
+                match code {
+                    Some(c) => {
+                        result.push(c);
+                        rest = &after[3..];
+                        continue;
+                    }
+                    None => {
+                        result.push_str("%%");
+                        rest = after;
+                        continue;
+                    }
+                }
+            }
+            _ => {
+                result.push_str("%%");
+                rest = after;
+                continue;
+            }
+        };
+        result.extend(replacement);
+        rest = &after[1..];
+    }
+    result.push_str(rest);
+    Cow::Owned(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::TextAlignment;
+
+    /// 只含字符 'A' 的 SHX 字体：竖线 + 半圆弧，上行高度 8
+    fn test_shx() -> Vec<u8> {
+        let info: &[u8] = b"TEST\0\x08\x02\x00\x00";
+        let shape_a: &[u8] = &[
+            b'A', 0, // 形名
+            0x0E, 0x84, // 仅竖排：向上 8
+            0x84, // 向上 8
+            0x02, 0x8C, // 抬笔，向下 8 回到原点
+            0x01, 0x0A, 0x02, 0x04, // 落笔，半径 2、自 0 八分位逆时针 4 个八分位
+            0x02, 0x08, 0x0A, 0x00, // 抬笔，位移 (10, 0)
+            0x00,
+        ];
+        let mut data = b"AutoCAD-86 shapes 1.0\r\n\x1A".to_vec();
+        for value in [0u16, 0x41, 2, 0, info.len() as u16, 0x41, shape_a.len() as u16] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(info);
+        data.extend_from_slice(shape_a);
+        data
+    }
+
+    fn builtin() -> Font {
+        Font::from_truetype(BUILTIN_FONT_NAME, epaint_default_fonts::UBUNTU_LIGHT.to_vec(), 0).unwrap()
+    }
+
+    #[test]
+    fn test_decode_special_codes() {
+        assert_eq!(decode_special_codes("%%c20"), "Ø20");
+        assert_eq!(decode_special_codes("45%%D %%p0.1"), "45° ±0.1");
+        assert_eq!(decode_special_codes("%%uTitle%%u 50%%%"), "Title 50%");
+        assert_eq!(decode_special_codes("%%177"), "±");
+        assert_eq!(decode_special_codes("100%%x"), "100%%x");
+        assert!(matches!(decode_special_codes("plain"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_truetype_glyphs() {
+        let font = builtin();
+        assert!(!font.is_stroke_font());
+        let i = font.glyph('I').unwrap();
+        let w = font.glyph('W').unwrap();
+        assert!(i.filled && !i.contours.is_empty());
+        assert!(i.advance < w.advance);
+        // 大写字母约为 0.7 em
+        let (low, high) = w.vertical_extent().unwrap();
+        assert!(low.abs() < 0.01 && (0.6..0.8).contains(&high), "{high}");
+        assert!(font.glyph(' ').unwrap().vertical_extent().is_none());
+        assert!(!font.has_glyph('中'));
+    }
+
+    #[test]
+    fn test_shx_glyph() {
+        let font = Font::from_shx("test", &test_shx()).unwrap();
+        assert!(font.is_stroke_font());
+        let glyph = font.glyph('A').unwrap();
+        assert!(!glyph.filled);
+        assert!((glyph.advance - 0.75).abs() < 1e-9);
+        assert_eq!(glyph.contours.len(), 2);
+        assert_eq!(glyph.contours[0], vec![Point2::new(0.0, 0.0), Point2::new(0.0, 1.0)]);
+        // 半圆：圆心 (-0.25, 0)，终点 (-0.5, 0)，最高点 0.25
+        let arc = &glyph.contours[1];
+        assert!((arc.last().unwrap() - Point2::new(-0.5, 0.0)).norm() < 1e-9);
+        let top = arc.iter().map(|p| p.y).fold(f64::MIN, f64::max);
+        assert!((top - 0.25).abs() < 1e-9);
+        assert!(font.glyph('B').is_none());
+
+        assert!(Font::from_shx("bad", b"AutoCAD-86 bigfont 1.0\r\n\x1A").is_err());
+    }
+
+    #[test]
+    fn test_measure_with_fallback() {
+        let mut manager = FontManager::new();
+        manager.add_font(builtin());
+        manager.add_font(Font::from_shx("test", &test_shx()).unwrap());
+
+        // 首选 SHX 字体，缺少的 'B' 回退到内置字体，汉字按字高估算
+        let b = manager.font(BUILTIN_FONT_NAME).unwrap().advance('B').unwrap();
+        let extents = manager.measure("AB中", 10.0, Some("test.shx"));
+        assert!((extents.width - (7.5 + b * 10.0 + 10.0)).abs() < 1e-9);
+        assert!((extents.ascent - 10.0).abs() < 1e-9);
+        assert!(manager.covers("AB", Some("test")));
+        assert!(!manager.covers("A 中", None));
+
+        let glyphs = manager.layout("AB", 10.0, Some("test"));
+        assert_eq!(glyphs.len(), 2);
+        assert!(!glyphs[0].filled && glyphs[1].filled);
+        // 第二个字形从第一个字形的步进处开始
+        let min_x = glyphs[1].contours.iter().flatten().map(|p| p.x).fold(f64::MAX, f64::min);
+        assert!(min_x >= 7.5);
+
+        // 控制码先转换再测量
+        let percent = manager.measure("%%%", 10.0, None).width;
+        assert!((percent - manager.measure("%", 10.0, None).width).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_text_extents_and_outlines() {
+        let text = Text::new(Point2::new(100.0, 50.0), "HELLO", 10.0).with_alignment(TextAlignment::Right);
+        let width = text.width();
+        assert!(width > 20.0 && width < 40.0, "{width}");
+
+        let bbox = text.bounding_box();
+        assert!((bbox.max.x - 100.0).abs() < 1e-9);
+        assert!((bbox.min.x - (100.0 - width)).abs() < 1e-9);
+        assert!((bbox.min.y - 50.0).abs() < 0.3 && bbox.max.y < 58.0);
+
+        let outlines = text.outlines();
+        assert_eq!(outlines.len(), 5);
+        for p in outlines.iter().flat_map(|g| g.contours.iter().flatten()) {
+            assert!(p.x <= 100.0 + 1e-9 && p.x >= bbox.min.x - 1.0);
+        }
+    }
+}
@@ -0,0 +1,689 @@
+//! 形位公差（特征控制框）
+//!
+//! 对应 DXF 的 TOLERANCE 实体：一到两行公差框格，每行依次为几何特征符号、
+//! 公差值和最多三个基准，框格下方可附带延伸公差带和基准标识符。
+//! 框格按文字高度排版，分解为直线、圆、圆弧和文字供各渲染端绘制；
+//! 符号和包容条件使用线条绘制，不依赖 GDT 字体。
+
+use crate::geometry::{Arc, Circle, Geometry, Leader, Line, Text};
+use crate::math::{BoundingBox2, Point2, Vector2};
+use serde::{Deserialize, Serialize};
+
+/// 框格高度与文字高度之比
+const FRAME_HEIGHT_FACTOR: f64 = 2.0;
+/// 每行最多的基准数
+pub const MAX_DATUMS: usize = 3;
+
+/// 几何特征符号
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GdtSymbol {
+    /// 位置度
+    Position,
+    /// 同轴度
+    Concentricity,
+    /// 对称度
+    Symmetry,
+    /// 平行度
+    Parallelism,
+    /// 垂直度
+    Perpendicularity,
+    /// 倾斜度
+    Angularity,
+    /// 圆柱度
+    Cylindricity,
+    /// 平面度
+    Flatness,
+    /// 圆度
+    Circularity,
+    /// 直线度
+    Straightness,
+    /// 面轮廓度
+    ProfileOfSurface,
+    /// 线轮廓度
+    ProfileOfLine,
+    /// 圆跳动
+    CircularRunout,
+    /// 全跳动
+    TotalRunout,
+}
+
+impl GdtSymbol {
+    /// 所有符号
+    pub const ALL: [GdtSymbol; 14] = [
+        GdtSymbol::Position,
+        GdtSymbol::Concentricity,
+        GdtSymbol::Symmetry,
+        GdtSymbol::Parallelism,
+        GdtSymbol::Perpendicularity,
+        GdtSymbol::Angularity,
+        GdtSymbol::Cylindricity,
+        GdtSymbol::Flatness,
+        GdtSymbol::Circularity,
+        GdtSymbol::Straightness,
+        GdtSymbol::ProfileOfSurface,
+        GdtSymbol::ProfileOfLine,
+        GdtSymbol::CircularRunout,
+        GdtSymbol::TotalRunout,
+    ];
+
+    /// 中文名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            GdtSymbol::Position => "位置度",
+            GdtSymbol::Concentricity => "同轴度",
+            GdtSymbol::Symmetry => "对称度",
+            GdtSymbol::Parallelism => "平行度",
+            GdtSymbol::Perpendicularity => "垂直度",
+            GdtSymbol::Angularity => "倾斜度",
+            GdtSymbol::Cylindricity => "圆柱度",
+            GdtSymbol::Flatness => "平面度",
+            GdtSymbol::Circularity => "圆度",
+            GdtSymbol::Straightness => "直线度",
+            GdtSymbol::ProfileOfSurface => "面轮廓度",
+            GdtSymbol::ProfileOfLine => "线轮廓度",
+            GdtSymbol::CircularRunout => "圆跳动",
+            GdtSymbol::TotalRunout => "全跳动",
+        }
+    }
+
+    /// 是否需要基准（形状公差没有基准）
+    pub fn requires_datum(&self) -> bool {
+        !matches!(
+            self,
+            GdtSymbol::Cylindricity | GdtSymbol::Flatness | GdtSymbol::Circularity | GdtSymbol::Straightness
+        )
+    }
+}
+
+/// 包容条件（材料修饰符）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MaterialCondition {
+    /// 最大实体要求 Ⓜ
+    Maximum,
+    /// 最小实体要求 Ⓛ
+    Least,
+    /// 与尺寸无关 Ⓢ
+    RegardlessOfFeatureSize,
+}
+
+impl MaterialCondition {
+    /// 圆圈内的字母
+    pub fn letter(&self) -> char {
+        match self {
+            MaterialCondition::Maximum => 'M',
+            MaterialCondition::Least => 'L',
+            MaterialCondition::RegardlessOfFeatureSize => 'S',
+        }
+    }
+}
+
+/// 公差值
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ToleranceValue {
+    /// 公差数值（按输入原样保存）
+    pub value: String,
+    /// 是否带直径符号（圆柱形公差带）
+    pub diameter: bool,
+    /// 包容条件
+    pub material: Option<MaterialCondition>,
+}
+
+impl ToleranceValue {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self {
+            value: value.into(),
+            ..Default::default()
+        }
+    }
+
+    /// 添加直径符号
+    pub fn with_diameter(mut self) -> Self {
+        self.diameter = true;
+        self
+    }
+
+    /// 设置包容条件
+    pub fn with_material(mut self, material: MaterialCondition) -> Self {
+        self.material = Some(material);
+        self
+    }
+}
+
+/// 基准
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DatumReference {
+    /// 基准字母（可为 "A-B" 这样的公共基准）
+    pub datum: String,
+    /// 包容条件
+    pub material: Option<MaterialCondition>,
+}
+
+impl DatumReference {
+    pub fn new(datum: impl Into<String>) -> Self {
+        Self {
+            datum: datum.into(),
+            material: None,
+        }
+    }
+
+    /// 设置包容条件
+    pub fn with_material(mut self, material: MaterialCondition) -> Self {
+        self.material = Some(material);
+        self
+    }
+}
+
+/// 一行公差框格
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ToleranceRow {
+    /// 几何特征符号
+    pub symbol: Option<GdtSymbol>,
+    /// 第一个公差值
+    pub tolerance1: Option<ToleranceValue>,
+    /// 第二个公差值
+    pub tolerance2: Option<ToleranceValue>,
+    /// 基准（最多 [`MAX_DATUMS`] 个，多余的不显示也不导出）
+    pub datums: Vec<DatumReference>,
+}
+
+impl ToleranceRow {
+    pub fn new(symbol: GdtSymbol) -> Self {
+        Self {
+            symbol: Some(symbol),
+            ..Default::default()
+        }
+    }
+
+    /// 设置第一个公差值
+    pub fn with_tolerance(mut self, value: ToleranceValue) -> Self {
+        self.tolerance1 = Some(value);
+        self
+    }
+
+    /// 设置第二个公差值
+    pub fn with_second_tolerance(mut self, value: ToleranceValue) -> Self {
+        self.tolerance2 = Some(value);
+        self
+    }
+
+    /// 追加基准
+    pub fn with_datum(mut self, datum: DatumReference) -> Self {
+        self.datums.push(datum);
+        self
+    }
+
+    /// 是否没有任何内容
+    pub fn is_empty(&self) -> bool {
+        self.symbol.is_none()
+            && self.tolerance1.is_none()
+            && self.tolerance2.is_none()
+            && self.datums.iter().all(|d| d.datum.is_empty())
+    }
+}
+
+/// 形位公差（特征控制框）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tolerance {
+    /// 插入点：第一行框格左边的中点
+    pub position: Point2,
+    /// 旋转角度（弧度）
+    pub rotation: f64,
+    /// 文字高度，框格高度为其两倍
+    pub text_height: f64,
+    /// 公差框格行
+    pub rows: Vec<ToleranceRow>,
+    /// 延伸公差带高度（显示在框格下方，带 Ⓟ 符号）
+    pub projected_zone: Option<String>,
+    /// 基准标识符（显示在最下方）
+    pub datum_identifier: Option<String>,
+    /// 标注样式名称（空字符串表示当前样式）
+    pub style_name: String,
+    /// 指向被测要素的引线，最后一个顶点连接到框格
+    pub leader: Option<Leader>,
+}
+
+/// 框格内容元素
+enum CellItem {
+    Text(String),
+    Diameter,
+    Modifier(char),
+    Symbol(GdtSymbol),
+}
+
+/// 排版后的框格：所在行、左端位置、宽度和内容
+struct Cell {
+    row: usize,
+    left: f64,
+    width: f64,
+    items: Vec<CellItem>,
+}
+
+impl Tolerance {
+    /// 创建空的特征控制框
+    pub fn new(position: Point2, text_height: f64) -> Self {
+        Self {
+            position,
+            rotation: 0.0,
+            text_height,
+            rows: Vec::new(),
+            projected_zone: None,
+            datum_identifier: None,
+            style_name: String::new(),
+            leader: None,
+        }
+    }
+
+    /// 追加一行公差框格
+    pub fn with_row(mut self, row: ToleranceRow) -> Self {
+        self.rows.push(row);
+        self
+    }
+
+    /// 设置旋转角度
+    pub fn with_rotation(mut self, rotation: f64) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// 设置延伸公差带高度
+    pub fn with_projected_zone(mut self, height: impl Into<String>) -> Self {
+        self.projected_zone = Some(height.into());
+        self
+    }
+
+    /// 设置基准标识符
+    pub fn with_datum_identifier(mut self, identifier: impl Into<String>) -> Self {
+        self.datum_identifier = Some(identifier.into());
+        self
+    }
+
+    /// 附加引线；引线的最后一个顶点移到插入点
+    pub fn with_leader(mut self, mut leader: Leader) -> Self {
+        if let Some(last) = leader.vertices.last_mut() {
+            *last = self.position;
+        }
+        self.leader = Some(leader);
+        self
+    }
+
+    /// 单行框格高度
+    pub fn frame_height(&self) -> f64 {
+        self.text_height * FRAME_HEIGHT_FACTOR
+    }
+
+    /// 框格排列方向（单位向量）
+    pub fn direction(&self) -> Vector2 {
+        Vector2::new(self.rotation.cos(), self.rotation.sin())
+    }
+
+    /// 按行排版所有框格
+    fn layout(&self) -> Vec<Cell> {
+        let h = self.text_height;
+        let padding = h * 0.5;
+        let mut cells = Vec::new();
+        let mut row_index = 0;
+        let mut push_row = |groups: Vec<Vec<CellItem>>, cells: &mut Vec<Cell>| {
+            let mut left = 0.0;
+            for items in groups {
+                let width = if let [CellItem::Symbol(_)] = items.as_slice() {
+                    self.frame_height()
+                } else {
+                    items_width(&items, h) + padding * 2.0
+                };
+                cells.push(Cell {
+                    row: row_index,
+                    left,
+                    width,
+                    items,
+                });
+                left += width;
+            }
+            row_index += 1;
+        };
+
+        for row in self.rows.iter().filter(|row| !row.is_empty()) {
+            let mut groups = Vec::new();
+            if let Some(symbol) = row.symbol {
+                groups.push(vec![CellItem::Symbol(symbol)]);
+            }
+            for value in [&row.tolerance1, &row.tolerance2].into_iter().flatten() {
+                let mut items = Vec::new();
+                if value.diameter {
+                    items.push(CellItem::Diameter);
+                }
+                if !value.value.is_empty() {
+                    items.push(CellItem::Text(value.value.clone()));
+                }
+                items.extend(value.material.map(|m| CellItem::Modifier(m.letter())));
+                groups.push(items);
+            }
+            for datum in row.datums.iter().take(MAX_DATUMS).filter(|d| !d.datum.is_empty()) {
+                let mut items = vec![CellItem::Text(datum.datum.clone())];
+                items.extend(datum.material.map(|m| CellItem::Modifier(m.letter())));
+                groups.push(items);
+            }
+            push_row(groups, &mut cells);
+        }
+        if let Some(height) = self.projected_zone.as_ref().filter(|s| !s.is_empty()) {
+            push_row(vec![vec![CellItem::Text(height.clone()), CellItem::Modifier('P')]], &mut cells);
+        }
+        if let Some(identifier) = self.datum_identifier.as_ref().filter(|s| !s.is_empty()) {
+            push_row(vec![vec![CellItem::Text(identifier.clone())]], &mut cells);
+        }
+        cells
+    }
+
+    /// 点在框格局部坐标系中的位置（x 沿框格方向，y 向上，原点为插入点）
+    fn to_local(&self, point: &Point2) -> (f64, f64) {
+        let along = self.direction();
+        let up = Vector2::new(-along.y, along.x);
+        let offset = point - self.position;
+        (offset.dot(&along), offset.dot(&up))
+    }
+
+    /// 框格边线（每个框格四条边）
+    pub fn frame_lines(&self) -> Vec<Line> {
+        let frame = self.frame_height();
+        let pen = FramePen::new(self.position, self.rotation);
+        let mut parts = Vec::new();
+        for cell in self.layout() {
+            let top = frame / 2.0 - cell.row as f64 * frame;
+            let (left, right, bottom) = (cell.left, cell.left + cell.width, top - frame);
+            pen.polyline(&mut parts, &[(left, top), (right, top), (right, bottom), (left, bottom), (left, top)]);
+        }
+        parts
+            .into_iter()
+            .filter_map(|part| match part {
+                Geometry::Line(line) => Some(line),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// 分解为基本图元：引线、框格线、符号和文字
+    pub fn explode(&self) -> Vec<Geometry> {
+        let mut parts: Vec<Geometry> = self.leader.iter().cloned().map(Geometry::Leader).collect();
+        let frame = self.frame_height();
+        let h = self.text_height;
+        let pen = FramePen::new(self.position, self.rotation);
+
+        parts.extend(self.frame_lines().into_iter().map(Geometry::Line));
+        for cell in self.layout() {
+            let middle = -(cell.row as f64) * frame;
+            let mut x = cell.left + (cell.width - items_width(&cell.items, h)) / 2.0;
+            for item in &cell.items {
+                let width = item_width(item, h);
+                let center = (x + width / 2.0, middle);
+                match item {
+                    CellItem::Text(text) => pen.text(&mut parts, (x, middle - h / 2.0), text, h),
+                    CellItem::Diameter => {
+                        pen.circle(&mut parts, center, h * 0.35);
+                        pen.line(&mut parts, offset(center, h, -0.4, -0.4), offset(center, h, 0.4, 0.4));
+                    }
+                    CellItem::Modifier(letter) => {
+                        pen.circle(&mut parts, center, h * 0.5);
+                        let letter = letter.to_string();
+                        let letter_height = h * 0.6;
+                        let letter_width = Text::new(Point2::origin(), letter.as_str(), letter_height).width();
+                        pen.text(
+                            &mut parts,
+                            (center.0 - letter_width / 2.0, center.1 - letter_height / 2.0),
+                            &letter,
+                            letter_height,
+                        );
+                    }
+                    CellItem::Symbol(symbol) => pen.symbol(&mut parts, *symbol, center, h),
+                }
+                x += width + h * 0.2;
+            }
+        }
+        parts
+    }
+
+    /// 计算点到特征控制框的距离：框格内部为 0，否则取到框格或引线的最近距离
+    pub fn distance_to_point(&self, point: &Point2) -> f64 {
+        let frame = self.frame_height();
+        let (x, y) = self.to_local(point);
+        let frame_distance = self
+            .layout()
+            .iter()
+            .map(|cell| {
+                let top = frame / 2.0 - cell.row as f64 * frame;
+                let dx = (cell.left - x).max(x - (cell.left + cell.width)).max(0.0);
+                let dy = (top - frame - y).max(y - top).max(0.0);
+                dx.hypot(dy)
+            })
+            .fold(f64::MAX, f64::min);
+        let leader_distance = self.leader.as_ref().map_or(f64::MAX, |l| l.distance_to_point(point));
+        frame_distance.min(leader_distance)
+    }
+
+    /// 获取包围盒
+    pub fn bounding_box(&self) -> BoundingBox2 {
+        self.explode()
+            .iter()
+            .map(Geometry::bounding_box)
+            .fold(BoundingBox2::from_points([self.position]), |acc, bbox| acc.union(&bbox))
+    }
+}
+
+/// 内容元素宽度
+fn item_width(item: &CellItem, h: f64) -> f64 {
+    match item {
+        CellItem::Text(text) => Text::new(Point2::origin(), text.as_str(), h).width(),
+        CellItem::Diameter | CellItem::Modifier(_) | CellItem::Symbol(_) => h,
+    }
+}
+
+/// 一组内容元素的总宽度（元素之间留 0.2 倍文字高度的间隙）
+fn items_width(items: &[CellItem], h: f64) -> f64 {
+    let gaps = items.len().saturating_sub(1) as f64 * h * 0.2;
+    items.iter().map(|item| item_width(item, h)).sum::<f64>() + gaps
+}
+
+/// 以 `center` 为中心、按文字高度 `h` 缩放的局部坐标
+fn offset(center: (f64, f64), h: f64, dx: f64, dy: f64) -> (f64, f64) {
+    (center.0 + dx * h, center.1 + dy * h)
+}
+
+/// 在框格局部坐标系中绘制图元
+struct FramePen {
+    origin: Point2,
+    rotation: f64,
+    along: Vector2,
+    up: Vector2,
+}
+
+impl FramePen {
+    fn new(origin: Point2, rotation: f64) -> Self {
+        let along = Vector2::new(rotation.cos(), rotation.sin());
+        Self {
+            origin,
+            rotation,
+            along,
+            up: Vector2::new(-along.y, along.x),
+        }
+    }
+
+    fn point(&self, (x, y): (f64, f64)) -> Point2 {
+        self.origin + self.along * x + self.up * y
+    }
+
+    fn line(&self, parts: &mut Vec<Geometry>, a: (f64, f64), b: (f64, f64)) {
+        parts.push(Geometry::Line(Line::new(self.point(a), self.point(b))));
+    }
+
+    fn polyline(&self, parts: &mut Vec<Geometry>, points: &[(f64, f64)]) {
+        for pair in points.windows(2) {
+            self.line(parts, pair[0], pair[1]);
+        }
+    }
+
+    fn circle(&self, parts: &mut Vec<Geometry>, center: (f64, f64), radius: f64) {
+        parts.push(Geometry::Circle(Circle::new(self.point(center), radius)));
+    }
+
+    fn arc(&self, parts: &mut Vec<Geometry>, center: (f64, f64), radius: f64, start: f64, end: f64) {
+        parts.push(Geometry::Arc(Arc::new(
+            self.point(center),
+            radius,
+            start + self.rotation,
+            end + self.rotation,
+        )));
+    }
+
+    fn text(&self, parts: &mut Vec<Geometry>, baseline: (f64, f64), content: &str, height: f64) {
+        parts.push(Geometry::Text(
+            Text::new(self.point(baseline), content, height).with_rotation(self.rotation),
+        ));
+    }
+
+    /// 带箭头的线段，箭头在 `to` 端
+    fn arrow(&self, parts: &mut Vec<Geometry>, from: (f64, f64), to: (f64, f64), h: f64) {
+        self.line(parts, from, to);
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let len = dx.hypot(dy);
+        let (ux, uy) = (dx / len, dy / len);
+        let (back, side) = (h * 0.25, h * 0.1);
+        let base = (to.0 - ux * back, to.1 - uy * back);
+        self.polyline(
+            parts,
+            &[(base.0 - uy * side, base.1 + ux * side), to, (base.0 + uy * side, base.1 - ux * side)],
+        );
+    }
+
+    /// 几何特征符号，绘制在以 `c` 为中心、边长为 `h` 的方框内
+    fn symbol(&self, parts: &mut Vec<Geometry>, symbol: GdtSymbol, c: (f64, f64), h: f64) {
+        let p = |dx: f64, dy: f64| offset(c, h, dx, dy);
+        match symbol {
+            GdtSymbol::Position => {
+                self.circle(parts, c, h * 0.3);
+                self.line(parts, p(-0.45, 0.0), p(0.45, 0.0));
+                self.line(parts, p(0.0, -0.45), p(0.0, 0.45));
+            }
+            GdtSymbol::Concentricity => {
+                self.circle(parts, c, h * 0.2);
+                self.circle(parts, c, h * 0.4);
+            }
+            GdtSymbol::Symmetry => {
+                self.line(parts, p(-0.45, 0.0), p(0.45, 0.0));
+                self.line(parts, p(-0.3, 0.25), p(0.3, 0.25));
+                self.line(parts, p(-0.3, -0.25), p(0.3, -0.25));
+            }
+            GdtSymbol::Parallelism => {
+                self.line(parts, p(-0.4, -0.4), p(0.0, 0.4));
+                self.line(parts, p(0.0, -0.4), p(0.4, 0.4));
+            }
+            GdtSymbol::Perpendicularity => {
+                self.line(parts, p(-0.45, -0.4), p(0.45, -0.4));
+                self.line(parts, p(0.0, -0.4), p(0.0, 0.45));
+            }
+            GdtSymbol::Angularity => {
+                self.line(parts, p(-0.45, -0.4), p(0.45, -0.4));
+                self.line(parts, p(-0.45, -0.4), p(0.45, 0.2));
+            }
+            GdtSymbol::Cylindricity => {
+                self.circle(parts, c, h * 0.3);
+                self.line(parts, p(-0.5, -0.4), p(-0.1, 0.4));
+                self.line(parts, p(0.1, -0.4), p(0.5, 0.4));
+            }
+            GdtSymbol::Flatness => {
+                self.polyline(parts, &[p(-0.45, -0.2), p(0.25, -0.2), p(0.45, 0.2), p(-0.25, 0.2), p(-0.45, -0.2)]);
+            }
+            GdtSymbol::Circularity => self.circle(parts, c, h * 0.4),
+            GdtSymbol::Straightness => self.line(parts, p(-0.45, 0.0), p(0.45, 0.0)),
+            GdtSymbol::ProfileOfSurface => {
+                self.arc(parts, p(0.0, -0.25), h * 0.45, 0.0, std::f64::consts::PI);
+                self.line(parts, p(-0.45, -0.25), p(0.45, -0.25));
+            }
+            GdtSymbol::ProfileOfLine => self.arc(parts, p(0.0, -0.25), h * 0.45, 0.0, std::f64::consts::PI),
+            GdtSymbol::CircularRunout => self.arrow(parts, p(-0.3, -0.4), p(0.3, 0.4), h),
+            GdtSymbol::TotalRunout => {
+                self.arrow(parts, p(-0.45, -0.35), p(-0.05, 0.4), h);
+                self.arrow(parts, p(0.05, -0.35), p(0.45, 0.4), h);
+                self.line(parts, p(-0.45, -0.35), p(0.05, -0.35));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position_tolerance() -> Tolerance {
+        Tolerance::new(Point2::new(10.0, 20.0), 2.5).with_row(
+            ToleranceRow::new(GdtSymbol::Position)
+                .with_tolerance(
+                    ToleranceValue::new("0.05")
+                        .with_diameter()
+                        .with_material(MaterialCondition::Maximum),
+                )
+                .with_datum(DatumReference::new("A"))
+                .with_datum(DatumReference::new("B")),
+        )
+    }
+
+    fn frame_lines(parts: &[Geometry]) -> Vec<&Line> {
+        parts
+            .iter()
+            .filter_map(|part| match part {
+                Geometry::Line(line) => Some(line),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_frame_layout() {
+        let tolerance = position_tolerance();
+        let parts = tolerance.explode();
+
+        // 符号、公差值、两个基准共 4 个框格
+        let cells = tolerance.layout();
+        assert_eq!(cells.len(), 4);
+        assert!((cells[0].width - 5.0).abs() < 1e-9);
+        for pair in cells.windows(2) {
+            assert!((pair[0].left + pair[0].width - pair[1].left).abs() < 1e-9);
+        }
+
+        // 框格从插入点开始，上下各半个框格高度
+        let bbox = tolerance.bounding_box();
+        assert!((bbox.min.x - 10.0).abs() < 1e-9);
+        assert!((bbox.min.y - 17.5).abs() < 1e-9);
+        assert!((bbox.max.y - 22.5).abs() < 1e-9);
+
+        let texts: Vec<&str> = parts
+            .iter()
+            .filter_map(|part| match part {
+                Geometry::Text(text) => Some(text.content.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(texts, ["0.05", "M", "A", "B"]);
+        // 每个框格 4 条边，位置度符号 2 条，直径符号 1 条
+        assert_eq!(frame_lines(&parts).len(), 4 * 4 + 2 + 1);
+    }
+
+    #[test]
+    fn test_rotated_frame_and_leader() {
+        let leader = Leader::new(vec![Point2::new(0.0, 0.0), Point2::new(5.0, 5.0), Point2::origin()]);
+        let tolerance = position_tolerance()
+            .with_rotation(std::f64::consts::FRAC_PI_2)
+            .with_projected_zone("10")
+            .with_leader(leader);
+
+        assert_eq!(tolerance.leader.as_ref().unwrap().vertices[2], Point2::new(10.0, 20.0));
+        let parts = tolerance.explode();
+        assert!(matches!(parts[0], Geometry::Leader(_)));
+
+        // 旋转 90° 后框格沿 +y 排列，延伸公差带一行位于 +x 一侧
+        let bbox = tolerance.bounding_box();
+        assert!(bbox.min.x <= 0.0 && bbox.max.x >= 10.0 + 2.5 * 3.0 - 1e-9);
+        assert!(bbox.max.y > 20.0 + 20.0);
+
+        assert_eq!(tolerance.distance_to_point(&Point2::new(10.0, 22.0)), 0.0);
+        assert!(tolerance.distance_to_point(&Point2::new(2.5, 2.5)) < 1e-9);
+        assert!(tolerance.distance_to_point(&Point2::new(30.0, 0.0)) > 5.0);
+    }
+}
@@ -7,13 +7,25 @@
 //! - 圆弧 (Arc)
 //! - 多段线 (Polyline)
 //! - 文本 (Text)
+//! - 多行文字 (MText)
 //! - 椭圆 (Ellipse)
 //! - 样条曲线 (Spline)
 //! - 填充 (Hatch)
 //! - 引线 (Leader)
-
+//! - 形位公差 (Tolerance)
+//! - 块参照 (Insert)
+
+use crate::associative::AssociativePoint;
+use crate::block::BlockReference;
+use crate::dimstyle::{self, DimStyle, DimTextAlignment, DimTextVertical, ToleranceText};
+use crate::entity::EntityId;
+use crate::font::{self, FontManager, Glyph, TextExtents};
+use crate::gdt::Tolerance;
 use crate::math::{BoundingBox2, Point2, Vector2, EPSILON};
+use crate::mtext::MText;
+use crate::transform::Transform2D;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 
 /// 几何类型枚举
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,11 +36,17 @@ pub enum Geometry {
     Arc(Arc),
     Polyline(Polyline),
     Text(Text),
+    /// 多行文字
+    MText(MText),
     Dimension(Dimension),
     Ellipse(Ellipse),
     Spline(Spline),
     Hatch(Hatch),
     Leader(Leader),
+    /// 形位公差（特征控制框）
+    Tolerance(Tolerance),
+    /// 块参照，需要通过 `BlockTable` 展开为块定义中的实体
+    Insert(BlockReference),
 }
 
 impl Geometry {
@@ -41,11 +59,15 @@ impl Geometry {
             Geometry::Arc(a) => a.bounding_box(),
             Geometry::Polyline(pl) => pl.bounding_box(),
             Geometry::Text(t) => t.bounding_box(),
+            Geometry::MText(t) => t.bounding_box(),
             Geometry::Dimension(d) => d.bounding_box(),
             Geometry::Ellipse(e) => e.bounding_box(),
             Geometry::Spline(s) => s.bounding_box(),
             Geometry::Hatch(h) => h.bounding_box(),
             Geometry::Leader(l) => l.bounding_box(),
+            Geometry::Tolerance(t) => t.bounding_box(),
+            // 不依赖块表时只能给出插入点的范围，完整范围见 `BlockTable::entity_bounding_box`
+            Geometry::Insert(r) => BoundingBox2::from_points(r.all_insertion_points()),
         }
     }
 
@@ -58,11 +80,14 @@ impl Geometry {
             Geometry::Arc(_) => "Arc",
             Geometry::Polyline(_) => "Polyline",
             Geometry::Text(_) => "Text",
+            Geometry::MText(_) => "MText",
             Geometry::Dimension(_) => "Dimension",
             Geometry::Ellipse(_) => "Ellipse",
             Geometry::Spline(_) => "Spline",
             Geometry::Hatch(_) => "Hatch",
             Geometry::Leader(_) => "Leader",
+            Geometry::Tolerance(_) => "Tolerance",
+            Geometry::Insert(_) => "Insert",
         }
     }
 
@@ -75,11 +100,17 @@ impl Geometry {
             Geometry::Arc(a) => a.distance_to_point(point) <= tolerance,
             Geometry::Polyline(pl) => pl.distance_to_point(point) <= tolerance,
             Geometry::Text(t) => t.contains_point(point, tolerance),
+            Geometry::MText(t) => t.contains_point(point, tolerance),
             Geometry::Dimension(d) => d.contains_point(point, tolerance),
             Geometry::Ellipse(e) => e.distance_to_point(point) <= tolerance,
             Geometry::Spline(s) => s.distance_to_point(point) <= tolerance,
             Geometry::Hatch(h) => h.contains_point(point, tolerance),
             Geometry::Leader(l) => l.distance_to_point(point) <= tolerance,
+            Geometry::Tolerance(t) => t.distance_to_point(point) <= tolerance,
+            Geometry::Insert(r) => r
+                .all_insertion_points()
+                .iter()
+                .any(|p| (p - point).norm() <= tolerance),
         }
     }
 }
@@ -404,34 +435,7 @@ impl Polyline {
 
     /// 计算总长度
     pub fn length(&self) -> f64 {
-        if self.vertices.len() < 2 {
-            return 0.0;
-        }
-
-        let mut total = 0.0;
-        for i in 0..self.segment_count() {
-            let v1 = &self.vertices[i];
-            let v2 = &self.vertices[(i + 1) % self.vertices.len()];
-
-            if v1.bulge.abs() < EPSILON {
-                // 直线段
-                total += (v2.point - v1.point).norm();
-            } else {
-                // 弧线段
-                total += self.arc_segment_length(v1, v2);
-            }
-        }
-        total
-    }
-
-    /// 计算弧线段长度
-    fn arc_segment_length(&self, v1: &PolylineVertex, v2: &PolylineVertex) -> f64 {
-        let chord = (v2.point - v1.point).norm();
-        let s = chord / 2.0;
-        let bulge = v1.bulge.abs();
-        let radius = s * (1.0 + bulge * bulge) / (2.0 * bulge);
-        let angle = 4.0 * bulge.atan();
-        radius * angle.abs()
+        self.segments().map(|segment| segment.length()).sum()
     }
 
     /// 计算点到多段线的距离
@@ -471,7 +475,11 @@ impl Polyline {
     }
 }
 
-/// 文本对齐方式
+/// 文本对齐方式，对应 DXF 单行文字的 15 种对正方式
+///
+/// 插入点为对齐点：`Left`/`Center`/`Right` 位于基线上，`Bottom*` 位于字母下伸部分的底部，
+/// `Middle*` 位于字高的一半，`Top*` 位于字高处；`Aligned`/`Fit` 以插入点和
+/// [`Text::end_point`] 作为基线的两个端点
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum TextAlignment {
     /// 左对齐（默认）
@@ -481,8 +489,83 @@ pub enum TextAlignment {
     Center,
     /// 右对齐
     Right,
+    /// 对齐：文字布满两点之间，字高按比例缩放
+    Aligned,
+    /// 中间：水平居中，竖直方向位于文字实际范围的中部
+    Middle,
+    /// 布满：文字布满两点之间，字高不变，调整宽度系数
+    Fit,
+    /// 左下
+    BottomLeft,
+    /// 中下
+    BottomCenter,
+    /// 右下
+    BottomRight,
+    /// 左中
+    MiddleLeft,
+    /// 正中
+    MiddleCenter,
+    /// 右中
+    MiddleRight,
+    /// 左上
+    TopLeft,
+    /// 中上
+    TopCenter,
+    /// 右上
+    TopRight,
 }
 
+impl TextAlignment {
+    /// 水平锚点比例（0 为左端，1 为右端）
+    pub fn horizontal_factor(self) -> f64 {
+        match self {
+            TextAlignment::Left | TextAlignment::Aligned | TextAlignment::Fit => 0.0,
+            TextAlignment::BottomLeft | TextAlignment::MiddleLeft | TextAlignment::TopLeft => 0.0,
+            TextAlignment::Center | TextAlignment::Middle => 0.5,
+            TextAlignment::BottomCenter | TextAlignment::MiddleCenter | TextAlignment::TopCenter => 0.5,
+            TextAlignment::Right | TextAlignment::BottomRight | TextAlignment::MiddleRight | TextAlignment::TopRight => 1.0,
+        }
+    }
+
+    /// 是否由两点确定基线（对齐、布满）
+    pub fn is_two_point(self) -> bool {
+        matches!(self, TextAlignment::Aligned | TextAlignment::Fit)
+    }
+
+    /// 左右互换后的对齐方式（文字沿基线反向时使用）
+    pub fn mirrored_horizontally(self) -> Self {
+        match self {
+            TextAlignment::Left => TextAlignment::Right,
+            TextAlignment::Right => TextAlignment::Left,
+            TextAlignment::BottomLeft => TextAlignment::BottomRight,
+            TextAlignment::BottomRight => TextAlignment::BottomLeft,
+            TextAlignment::MiddleLeft => TextAlignment::MiddleRight,
+            TextAlignment::MiddleRight => TextAlignment::MiddleLeft,
+            TextAlignment::TopLeft => TextAlignment::TopRight,
+            TextAlignment::TopRight => TextAlignment::TopLeft,
+            other => other,
+        }
+    }
+
+    /// 上下互换后的对齐方式（文字翻到基线另一侧时使用）
+    ///
+    /// 基线对齐与顶部对齐互换，底部对齐按顶部处理；两点对齐保持不变
+    pub fn mirrored_vertically(self) -> Self {
+        match self {
+            TextAlignment::Left | TextAlignment::BottomLeft => TextAlignment::TopLeft,
+            TextAlignment::Center | TextAlignment::BottomCenter => TextAlignment::TopCenter,
+            TextAlignment::Right | TextAlignment::BottomRight => TextAlignment::TopRight,
+            TextAlignment::TopLeft => TextAlignment::Left,
+            TextAlignment::TopCenter => TextAlignment::Center,
+            TextAlignment::TopRight => TextAlignment::Right,
+            other => other,
+        }
+    }
+}
+
+/// 字母下伸部分与字高之比（txt.shx 上伸 6、下伸 2），用于底部对齐
+const TEXT_DESCENDER_RATIO: f64 = 1.0 / 3.0;
+
 /// 文本
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Text {
@@ -496,6 +579,31 @@ pub struct Text {
     pub rotation: f64,
     /// 对齐方式
     pub alignment: TextAlignment,
+    /// 文字样式名称（见 [`crate::textstyle`]）
+    #[serde(default)]
+    pub style_name: String,
+    /// 字体名称（由文字样式决定），None 为默认字体
+    #[serde(default)]
+    pub font: Option<String>,
+    /// 宽度系数
+    #[serde(default = "default_width_factor")]
+    pub width_factor: f64,
+    /// 倾斜角（弧度，相对竖直方向向右为正）
+    #[serde(default)]
+    pub oblique_angle: f64,
+    /// 反向（左右镜像）
+    #[serde(default)]
+    pub backwards: bool,
+    /// 倒置（上下镜像）
+    #[serde(default)]
+    pub upside_down: bool,
+    /// 两点对齐方式（对齐、布满）的基线终点
+    #[serde(default)]
+    pub end_point: Option<Point2>,
+}
+
+fn default_width_factor() -> f64 {
+    1.0
 }
 
 impl Text {
@@ -507,6 +615,13 @@ impl Text {
             height,
             rotation: 0.0,
             alignment: TextAlignment::Left,
+            style_name: String::new(),
+            font: None,
+            width_factor: 1.0,
+            oblique_angle: 0.0,
+            backwards: false,
+            upside_down: false,
+            end_point: None,
         }
     }
 
@@ -522,70 +637,173 @@ impl Text {
         self
     }
 
+    /// 设置两点对齐方式（对齐、布满）的基线终点
+    pub fn with_end_point(mut self, end_point: Point2) -> Self {
+        self.end_point = Some(end_point);
+        self
+    }
+
     /// 估算文本宽度（简化计算，假设每个字符宽度约为高度的0.6倍）
+    ///
+    /// 仅在没有可用字形时使用，精确宽度见 [`Text::width`]
     pub fn estimated_width(&self) -> f64 {
-        // 对于中文字符，宽度接近高度；对于英文，约为高度的0.6倍
-        // 这里采用简化的混合估算
-        let char_count = self.content.chars().count();
-        let cjk_count = self.content.chars().filter(|c| Self::is_cjk(*c)).count();
-        let ascii_count = char_count - cjk_count;
-        
-        (cjk_count as f64 * self.height) + (ascii_count as f64 * self.height * 0.6)
+        self.content.chars().map(|c| Self::estimated_char_width(c, self.height)).sum()
+    }
+
+    /// 单个字符的估算宽度：中文字符宽度接近高度，其他字符约为高度的0.6倍
+    pub(crate) fn estimated_char_width(c: char, height: f64) -> f64 {
+        if Self::is_cjk(c) {
+            height
+        } else {
+            height * 0.6
+        }
     }
 
     /// 检查是否是CJK字符
-    fn is_cjk(c: char) -> bool {
+    pub(crate) fn is_cjk(c: char) -> bool {
         matches!(c, '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' | '\u{F900}'..='\u{FAFF}')
     }
 
-    /// 获取包围盒
-    pub fn bounding_box(&self) -> BoundingBox2 {
-        let width = self.estimated_width();
-        let height = self.height;
-        
-        // 根据对齐方式计算基准点
-        let base_x = match self.alignment {
-            TextAlignment::Left => self.position.x,
-            TextAlignment::Center => self.position.x - width / 2.0,
-            TextAlignment::Right => self.position.x - width,
+    /// 显示的文字（已转换 `%%C`、`%%D`、`%%P` 等控制码）
+    pub fn display_text(&self) -> String {
+        font::decode_special_codes(&self.content).into_owned()
+    }
+
+    /// 按字体度量计算的文字范围（未乘宽度系数）
+    fn measure(&self) -> TextExtents {
+        FontManager::shared().measure(&self.content, self.height, self.font.as_deref())
+    }
+
+    /// 按字体度量计算的文字范围，宽度已乘宽度系数
+    pub fn extents(&self) -> TextExtents {
+        let extents = self.measure();
+        TextExtents {
+            width: extents.width * self.width_factor,
+            ..extents
+        }
+    }
+
+    /// 按字体度量计算的文字宽度
+    pub fn width(&self) -> f64 {
+        self.extents().width
+    }
+
+    /// 按两点调整后的文字：对齐、布满方式下旋转角取两点方向，对齐方式等比缩放字高，
+    /// 布满方式调整宽度系数；其它对齐方式（或未给出终点）原样返回
+    pub fn fitted(&self) -> Cow<'_, Text> {
+        let Some(end) = self.end_point.filter(|_| self.alignment.is_two_point()) else {
+            return Cow::Borrowed(self);
         };
-        
-        // 简化处理：忽略旋转的包围盒计算
-        if self.rotation.abs() < EPSILON {
-            BoundingBox2::new(
-                Point2::new(base_x, self.position.y),
-                Point2::new(base_x + width, self.position.y + height),
-            )
-        } else {
-            // 带旋转的包围盒：计算四个角点
-            let corners = [
-                Point2::new(0.0, 0.0),
-                Point2::new(width, 0.0),
-                Point2::new(width, height),
-                Point2::new(0.0, height),
-            ];
-            
-            let cos_r = self.rotation.cos();
-            let sin_r = self.rotation.sin();
-            
-            let rotated: Vec<Point2> = corners.iter().map(|p| {
-                let rx = p.x * cos_r - p.y * sin_r + base_x;
-                let ry = p.x * sin_r + p.y * cos_r + self.position.y;
-                Point2::new(rx, ry)
-            }).collect();
-            
-            BoundingBox2::from_points(rotated)
+        let d = end - self.position;
+        let length = d.norm();
+        if length < EPSILON {
+            return Cow::Borrowed(self);
         }
+        let mut text = self.clone();
+        text.rotation = d.y.atan2(d.x);
+        let width = text.measure().width;
+        if width > EPSILON {
+            match text.alignment {
+                TextAlignment::Aligned => text.height *= length / (width * text.width_factor),
+                _ => text.width_factor = length / width,
+            }
+        }
+        Cow::Owned(text)
+    }
+
+    /// 对齐点相对基线的高度
+    fn vertical_offset(&self, extents: &TextExtents) -> f64 {
+        match self.alignment {
+            TextAlignment::BottomLeft | TextAlignment::BottomCenter | TextAlignment::BottomRight => {
+                -self.height * TEXT_DESCENDER_RATIO
+            }
+            TextAlignment::MiddleLeft | TextAlignment::MiddleCenter | TextAlignment::MiddleRight => self.height / 2.0,
+            TextAlignment::TopLeft | TextAlignment::TopCenter | TextAlignment::TopRight => self.height,
+            TextAlignment::Middle => (extents.ascent - extents.descent) / 2.0,
+            _ => 0.0,
+        }
+    }
+
+    /// 排版坐标（基线起点为原点，x 沿书写方向，未乘宽度系数）到图纸坐标的映射，
+    /// 依次应用宽度系数、倾斜、对齐、反向/倒置和旋转；`extents` 为未乘宽度系数的范围
+    ///
+    /// 两点对齐方式需先经过 [`Text::fitted`]
+    fn local_to_world(&self, extents: &TextExtents) -> impl Fn(Point2) -> Point2 {
+        let (sin, cos) = self.rotation.sin_cos();
+        let dir = Vector2::new(cos, sin);
+        let perp = Vector2::new(-sin, cos);
+        let (position, width_factor, shear) = (self.position, self.width_factor, self.oblique_angle.tan());
+        let offset = extents.width * width_factor * self.alignment.horizontal_factor();
+        let rise = self.vertical_offset(extents);
+        let sx = if self.backwards { -1.0 } else { 1.0 };
+        let sy = if self.upside_down { -1.0 } else { 1.0 };
+        move |p: Point2| {
+            let x = p.x * width_factor + p.y * shear - offset;
+            position + dir * (sx * x) + perp * (sy * (p.y - rise))
+        }
+    }
+
+    /// 基线的起点和终点
+    pub fn baseline(&self) -> (Point2, Point2) {
+        let text = self.fitted();
+        let extents = text.measure();
+        let to_world = text.local_to_world(&extents);
+        (to_world(Point2::origin()), to_world(Point2::new(extents.width, 0.0)))
+    }
+
+    /// 图纸坐标下的字形轮廓（TrueType）或笔画（SHX）
+    pub fn outlines(&self) -> Vec<Glyph> {
+        let text = self.fitted();
+        let fonts = FontManager::shared();
+        let font = text.font.as_deref();
+        let to_world = text.local_to_world(&fonts.measure(&text.content, text.height, font));
+        fonts
+            .layout(&text.content, text.height, font)
+            .into_iter()
+            .map(|glyph| Glyph {
+                contours: glyph.contours.iter().map(|c| c.iter().map(|p| to_world(*p)).collect()).collect(),
+                ..glyph
+            })
+            .collect()
+    }
+
+    /// 文字框的四个角（按字形墨迹的上下范围和步进宽度）：左下、右下、右上、左上
+    pub fn corners(&self) -> [Point2; 4] {
+        let text = self.fitted();
+        let extents = text.measure();
+        let to_world = text.local_to_world(&extents);
+        [
+            to_world(Point2::new(0.0, -extents.descent)),
+            to_world(Point2::new(extents.width, -extents.descent)),
+            to_world(Point2::new(extents.width, extents.ascent)),
+            to_world(Point2::new(0.0, extents.ascent)),
+        ]
+    }
+
+    /// 获取包围盒
+    pub fn bounding_box(&self) -> BoundingBox2 {
+        BoundingBox2::from_points(self.corners())
     }
 
-    /// 检查点是否在文本包围盒内
+    /// 检查点是否在文字框内（旋转的文字按文字框本身判断，而非轴对齐包围盒）
     pub fn contains_point(&self, point: &Point2, tolerance: f64) -> bool {
-        let bbox = self.bounding_box();
-        let expanded = BoundingBox2::new(
-            Point2::new(bbox.min.x - tolerance, bbox.min.y - tolerance),
-            Point2::new(bbox.max.x + tolerance, bbox.max.y + tolerance),
-        );
-        expanded.contains(point)
+        let [origin, right, _, top] = self.corners();
+        let (along, up) = (right - origin, top - origin);
+        let det = along.x * up.y - along.y * up.x;
+        if det.abs() < EPSILON {
+            // 空文字：退化为包围盒判断
+            let bbox = BoundingBox2::from_points(self.corners());
+            return point.x >= bbox.min.x - tolerance
+                && point.x <= bbox.max.x + tolerance
+                && point.y >= bbox.min.y - tolerance
+                && point.y <= bbox.max.y + tolerance;
+        }
+        // 文字框（倾斜时为平行四边形）内的参数坐标
+        let d = point - origin;
+        let u = (d.x * up.y - d.y * up.x) / det;
+        let v = (along.x * d.y - along.y * d.x) / det;
+        let (tu, tv) = (tolerance / along.norm(), tolerance / up.norm());
+        (-tu..=1.0 + tu).contains(&u) && (-tv..=1.0 + tv).contains(&v)
     }
 }
 
@@ -609,7 +827,37 @@ pub enum DimensionType {
     Ordinate,
 }
 
+/// 坐标标注测量的坐标轴
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum OrdinateAxis {
+    /// X 坐标（引线沿竖直方向）
+    #[default]
+    X,
+    /// Y 坐标（引线沿水平方向）
+    Y,
+}
+
+impl OrdinateAxis {
+    /// 按引线方向选择坐标轴：引线偏竖直时标注 X 坐标，否则标注 Y 坐标
+    pub fn from_leader(feature: Point2, leader_end: Point2) -> Self {
+        let d = leader_end - feature;
+        if d.y.abs() > d.x.abs() {
+            OrdinateAxis::X
+        } else {
+            OrdinateAxis::Y
+        }
+    }
+}
+
 /// 尺寸标注
+///
+/// 各类型测量点的含义：
+/// - 对齐/线性：两个测量点为延伸线原点，`line_location` 为尺寸线上一点
+/// - 半径/直径：第一点为圆心，第二点为圆上一点，`line_location` 为文字位置
+/// - 角度：第一点为顶点，第二点和第三点分别在两条边上，`line_location` 为尺寸弧上一点，
+///   测量包含该点的那一侧角度
+/// - 弧长：第一点为圆心，第二点和第三点为圆弧起点和终点（逆时针），`line_location` 为尺寸弧上一点
+/// - 坐标：第一点为特征点，第二点为坐标原点，`line_location` 为引线端点
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Dimension {
     /// 第一个测量点
@@ -626,6 +874,18 @@ pub struct Dimension {
     pub text_height: f64,
     /// 文本位置 (如果为None，则自动计算默认位置)
     pub text_position: Option<Point2>,
+    /// 两个测量点的关联（None 表示该点不关联其它实体）
+    #[serde(default)]
+    pub associations: [Option<AssociativePoint>; 2],
+    /// 标注样式名称（为空时使用当前样式）
+    #[serde(default)]
+    pub style_name: String,
+    /// 第三个测量点（角度标注第二条边上的点、弧长标注的终点）
+    #[serde(default = "Point2::origin")]
+    pub definition_point3: Point2,
+    /// 坐标标注测量的坐标轴
+    #[serde(default)]
+    pub ordinate_axis: OrdinateAxis,
 }
 
 impl Dimension {
@@ -638,9 +898,127 @@ impl Dimension {
             text_override: None,
             text_height: 10.0, // 默认高度
             text_position: None,
+            associations: [None, None],
+            style_name: String::new(),
+            definition_point3: Point2::origin(),
+            ordinate_axis: OrdinateAxis::X,
         }
     }
 
+    /// 创建角度标注：顶点、两条边上各一点和尺寸弧位置
+    pub fn angular(vertex: Point2, first: Point2, second: Point2, arc_location: Point2) -> Self {
+        let mut dim = Self::new(vertex, first, arc_location);
+        dim.dim_type = DimensionType::Angular;
+        dim.definition_point3 = second;
+        dim
+    }
+
+    /// 创建弧长标注：圆心、圆弧起点和终点（逆时针）和尺寸弧位置
+    pub fn arc_length(center: Point2, start: Point2, end: Point2, arc_location: Point2) -> Self {
+        let mut dim = Self::new(center, start, arc_location);
+        dim.dim_type = DimensionType::ArcLength;
+        dim.definition_point3 = end;
+        dim
+    }
+
+    /// 创建坐标标注：特征点、坐标原点、引线端点和测量的坐标轴
+    pub fn ordinate(feature: Point2, origin: Point2, leader_end: Point2, axis: OrdinateAxis) -> Self {
+        let mut dim = Self::new(feature, origin, leader_end);
+        dim.dim_type = DimensionType::Ordinate;
+        dim.ordinate_axis = axis;
+        dim
+    }
+
+    /// 设置测量点关联
+    pub fn with_associations(mut self, first: Option<AssociativePoint>, second: Option<AssociativePoint>) -> Self {
+        self.associations = [first, second];
+        self
+    }
+
+    /// 是否为关联标注
+    pub fn is_associative(&self) -> bool {
+        self.associations.iter().any(Option::is_some)
+    }
+
+    /// 是否引用了指定实体
+    pub fn references(&self, id: &EntityId) -> bool {
+        self.associations.iter().flatten().any(|a| a.entity == *id)
+    }
+
+    /// 解除对指定实体的关联，测量点保持当前位置
+    ///
+    /// 返回是否有关联被解除
+    pub fn dissociate(&mut self, id: &EntityId) -> bool {
+        let mut changed = false;
+        for slot in &mut self.associations {
+            if slot.is_some_and(|a| a.entity == *id) {
+                *slot = None;
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// 按关联重新计算测量点
+    ///
+    /// `lookup` 返回被引用实体的当前几何体；实体不存在或特征点已不存在时
+    /// 解除该点的关联并保持原位置。返回标注是否发生变化
+    pub fn update_associations<'a>(&mut self, lookup: impl Fn(&EntityId) -> Option<&'a Geometry>) -> bool {
+        let mut points = [self.definition_point1, self.definition_point2];
+        let mut changed = false;
+        for (slot, point) in self.associations.iter_mut().zip(points.iter_mut()) {
+            let Some(association) = slot else { continue };
+            match lookup(&association.entity).and_then(|g| association.evaluate(g)) {
+                Some(p) => *point = p,
+                None => {
+                    *slot = None;
+                    changed = true;
+                }
+            }
+        }
+        if (points[0] - self.definition_point1).norm() > EPSILON
+            || (points[1] - self.definition_point2).norm() > EPSILON
+        {
+            self.set_definition_points(points[0], points[1]);
+            changed = true;
+        }
+        changed
+    }
+
+    /// 修改测量点，标注线位置和文本位置随之移动
+    ///
+    /// 线性和坐标标注只随测量点平移；其它类型同时跟随测量方向旋转，
+    /// 使标注线与测量点的相对位置保持不变
+    pub fn set_definition_points(&mut self, p1: Point2, p2: Point2) {
+        let (old_origin, new_origin) = match self.dim_type {
+            DimensionType::Aligned | DimensionType::Linear => (
+                Point2::from((self.definition_point1.coords + self.definition_point2.coords) / 2.0),
+                Point2::from((p1.coords + p2.coords) / 2.0),
+            ),
+            _ => (self.definition_point1, p1),
+        };
+        let rotation = match self.dim_type {
+            DimensionType::Linear | DimensionType::Ordinate => 0.0,
+            _ => {
+                let (old_dir, new_dir) = (self.definition_point2 - self.definition_point1, p2 - p1);
+                if old_dir.norm() < EPSILON || new_dir.norm() < EPSILON {
+                    0.0
+                } else {
+                    new_dir.y.atan2(new_dir.x) - old_dir.y.atan2(old_dir.x)
+                }
+            }
+        };
+        let offset = new_origin - old_origin;
+        let transform = Transform2D::translation(offset.x, offset.y)
+            .then(&Transform2D::rotation_around(old_origin, rotation));
+
+        self.line_location = transform.transform_point(&self.line_location);
+        self.definition_point3 = transform.transform_point(&self.definition_point3);
+        self.text_position = self.text_position.map(|p| transform.transform_point(&p));
+        self.definition_point1 = p1;
+        self.definition_point2 = p2;
+    }
+
     /// 获取文本的实际显示位置（如果未设置，则计算默认位置）
     pub fn get_text_position(&self) -> Point2 {
         if let Some(pos) = self.text_position {
@@ -672,12 +1050,13 @@ impl Dimension {
                 self.line_location
             }
             DimensionType::Angular | DimensionType::ArcLength => {
-                // 角度标注：文本位于角平分线上
-                let v1 = (self.definition_point2 - self.definition_point1).normalize();
-                let v2 = (self.line_location - self.definition_point1).normalize();
-                let bisector = (v1 + v2).normalize();
-                let radius = (self.definition_point2 - self.definition_point1).norm() * 0.6;
-                self.definition_point1 + bisector * radius
+                // 文本位于尺寸弧的中点
+                let center = self.definition_point1;
+                let radius = (self.line_location - center).norm();
+                match self.dimension_arc_angles() {
+                    Some((start, sweep, _)) => center + unit_vector(start + sweep / 2.0) * radius,
+                    None => self.line_location,
+                }
             }
             DimensionType::Ordinate => {
                 // 坐标标注：文本位于 line_location
@@ -686,13 +1065,12 @@ impl Dimension {
         }
     }
 
-    /// 获取文本包围盒
-    pub fn text_bounding_box(&self) -> BoundingBox2 {
+    /// 获取文本包围盒（按样式格式化的文字框，含公差，以文字位置为中心）
+    pub fn text_bounding_box(&self, style: &DimStyle) -> BoundingBox2 {
         let pos = self.get_text_position();
-        let content = self.display_text();
-        let text = Text::new(pos, content, self.text_height)
-            .with_alignment(TextAlignment::Center); // 标注文本通常是居中绘制
-        text.bounding_box()
+        let label = DimensionLabel::new(self, style, self.style_scale(style));
+        let half = Vector2::new(label.width / 2.0, label.box_height / 2.0);
+        BoundingBox2::new(pos - half, pos + half)
     }
 
     /// 获取测量值
@@ -715,59 +1093,45 @@ impl Dimension {
                 (self.definition_point2 - self.definition_point1).norm() * 2.0
             }
             DimensionType::Angular => {
-                // 角度标注：p1 是顶点，p2 是第一条边上的点，line_location 是第二条边上的点
-                let v1 = self.definition_point2 - self.definition_point1;
-                let v2 = self.line_location - self.definition_point1;
-                let dot = v1.x * v2.x + v1.y * v2.y;
-                let cross = v1.x * v2.y - v1.y * v2.x;
-                cross.atan2(dot).abs().to_degrees()
+                // 角度标注：两条边之间包含尺寸弧位置的一侧，单位为度
+                self.dimension_arc_angles()
+                    .map_or(0.0, |(_, sweep, _)| sweep.to_degrees())
             }
             DimensionType::ArcLength => {
-                // 弧长标注：p1 是圆心，p2 是起点，line_location 是终点
+                // 弧长标注：半径 × 起点到终点的逆时针圆心角
                 let radius = (self.definition_point2 - self.definition_point1).norm();
-                let v1 = self.definition_point2 - self.definition_point1;
-                let v2 = self.line_location - self.definition_point1;
-                let angle = (v1.x * v2.y - v1.y * v2.x).atan2(v1.x * v2.x + v1.y * v2.y).abs();
-                radius * angle
+                self.dimension_arc_angles()
+                    .map_or(0.0, |(_, sweep, _)| radius * sweep)
             }
             DimensionType::Ordinate => {
-                // 坐标标注：显示 x 或 y 坐标
-                // 根据 line_location 相对于 definition_point1 的位置决定
-                let dx = (self.line_location.x - self.definition_point1.x).abs();
-                let dy = (self.line_location.y - self.definition_point1.y).abs();
-                if dx > dy {
-                    self.definition_point1.x
-                } else {
-                    self.definition_point1.y
+                // 坐标标注：特征点相对原点的 X 或 Y 坐标（取绝对值）
+                let offset = self.definition_point1 - self.definition_point2;
+                match self.ordinate_axis {
+                    OrdinateAxis::X => offset.x.abs(),
+                    OrdinateAxis::Y => offset.y.abs(),
                 }
             }
         }
     }
 
-    /// 获取显示的文本
-    pub fn display_text(&self) -> String {
-        if let Some(text) = &self.text_override {
-            text.clone()
-        } else {
-            let val = self.measurement();
-            match self.dim_type {
-                DimensionType::Radius => format!("R{:.2}", val),
-                DimensionType::Diameter => format!("%%C{:.2}", val), // %%C 是 CAD 中直径符号的转义
-                DimensionType::Angular => format!("{:.1}°", val),
-                DimensionType::ArcLength => format!("⌒{:.2}", val),
-                DimensionType::Ordinate => format!("{:.2}", val),
-                _ => format!("{:.2}", val),
-            }
-        }
+    /// 获取显示的文本（按样式格式化，含紧跟主单位的对称公差）
+    pub fn display_text(&self, style: &DimStyle) -> String {
+        DimensionLabel::new(self, style, self.style_scale(style)).main
     }
 
     /// 计算包围盒 (简化估算)
     pub fn bounding_box(&self) -> BoundingBox2 {
-        BoundingBox2::from_points([
+        let bbox = BoundingBox2::from_points([
             self.definition_point1,
             self.definition_point2,
             self.line_location,
-        ])
+        ]);
+        match self.dimension_arc() {
+            Some(arc) => bbox
+                .union(&BoundingBox2::from_points([self.definition_point3]))
+                .union(&arc.bounding_box()),
+            None => bbox,
+        }
     }
 
     /// 检查点是否在标注上 (简化：检查是否在包围盒内)
@@ -781,73 +1145,594 @@ impl Dimension {
     }
 }
 
-impl Polyline {
-    /// 爆炸为独立的线段/圆弧
+/// 公差文字相对主文字的高度比例
+const DIM_TOLERANCE_SCALE: f64 = 0.7;
+
+impl Dimension {
+    /// 使用标注样式：记录样式名称，文字高度取样式的文字高度
+    pub fn with_style(mut self, style: &DimStyle) -> Self {
+        self.style_name = style.name.clone();
+        self.text_height = style.text_height;
+        self
+    }
+
+    /// 按样式格式化的标注文字（含替代单位，不含公差）
     ///
-    /// 这是我们要做好的功能 - 智能爆炸，只生成需要的几何体
-    pub fn explode(&self) -> Vec<Geometry> {
-        if self.vertices.len() < 2 {
-            return vec![];
+    /// 覆盖文本中的 "<>" 会被替换为测量值
+    pub fn styled_text(&self, style: &DimStyle) -> String {
+        self.apply_text_override(self.measured_text(style, None))
+    }
+
+    /// 测量文字：主单位、对称公差（紧跟主单位）和替代单位
+    fn measured_text(&self, style: &DimStyle, tolerance: Option<&str>) -> String {
+        let value = self.measurement();
+        let mut text = match self.dim_type {
+            DimensionType::Angular => style.format_angle(value.to_radians()),
+            DimensionType::Radius => format!("R{}", style.format_primary(value)),
+            DimensionType::Diameter => format!("%%C{}", style.format_primary(value)),
+            DimensionType::ArcLength => format!("⌒{}", style.format_primary(value)),
+            _ => style.format_primary(value),
+        };
+        if let Some(tolerance) = tolerance {
+            text.push_str(tolerance);
         }
+        if self.dim_type != DimensionType::Angular {
+            if let Some(alternate) = style.format_alternate(value) {
+                text.push(' ');
+                text.push_str(&alternate);
+            }
+        }
+        text
+    }
 
-        let mut result = Vec::with_capacity(self.segment_count());
+    fn apply_text_override(&self, measured: String) -> String {
+        match &self.text_override {
+            Some(text) if text.contains("<>") => text.replace("<>", &measured),
+            Some(text) => text.clone(),
+            None => measured,
+        }
+    }
 
-        for i in 0..self.segment_count() {
-            let v1 = &self.vertices[i];
-            let v2 = &self.vertices[(i + 1) % self.vertices.len()];
+    /// 样式尺寸（箭头、间距等）相对样式定义的缩放比例
+    fn style_scale(&self, style: &DimStyle) -> f64 {
+        if style.text_height > EPSILON {
+            self.text_height / style.text_height
+        } else {
+            1.0
+        }
+    }
 
-            if v1.bulge.abs() < EPSILON {
-                // 直线段
-                result.push(Geometry::Line(Line::new(v1.point, v2.point)));
-            } else {
-                // 弧线段
-                if let Some(arc) = self.vertex_pair_to_arc(v1, v2) {
-                    result.push(Geometry::Arc(arc));
-                } else {
-                    // 回退到直线
-                    result.push(Geometry::Line(Line::new(v1.point, v2.point)));
-                }
+    /// 对齐/线性标注的尺寸线方向（从第一测量点指向第二测量点）
+    ///
+    /// 线性标注按两点的主要差异方向取水平或竖直；其它类型或两点重合时返回 None
+    pub fn linear_direction(&self) -> Option<Vector2> {
+        let d = self.definition_point2 - self.definition_point1;
+        match self.dim_type {
+            DimensionType::Linear if d.x.abs() >= d.y.abs() => {
+                Some(Vector2::new(if d.x < 0.0 { -1.0 } else { 1.0 }, 0.0))
             }
+            DimensionType::Linear => Some(Vector2::new(0.0, d.y.signum())),
+            DimensionType::Aligned if d.norm() > EPSILON => Some(d.normalize()),
+            _ => None,
         }
+    }
 
-        result
+    /// 交换两个测量点及其关联，标注图形不变
+    pub fn swap_definition_points(&mut self) {
+        std::mem::swap(&mut self.definition_point1, &mut self.definition_point2);
+        self.associations.swap(0, 1);
     }
 
-    /// 将顶点对转换为圆弧
-    fn vertex_pair_to_arc(&self, v1: &PolylineVertex, v2: &PolylineVertex) -> Option<Arc> {
-        let chord = v2.point - v1.point;
-        let chord_len = chord.norm();
+    /// 基线标注：从本标注的第一个测量点到 `point` 的新标注
+    ///
+    /// 新尺寸线在本标注尺寸线远离测量点的一侧，间距为样式的基线间距（按文字高度缩放）。
+    /// 仅支持对齐和线性标注
+    pub fn baseline(&self, point: Point2, style: &DimStyle) -> Option<Dimension> {
+        let dir = self.linear_direction()?;
+        let perp = Vector2::new(-dir.y, dir.x);
+        let side = match (self.line_location - self.definition_point1).dot(&perp) {
+            h if h.abs() < EPSILON => 1.0,
+            h => h.signum(),
+        };
+        let location = self.line_location + perp * (side * style.baseline_spacing * self.style_scale(style));
+        self.chained(self.definition_point1, self.associations[0], point, location)
+    }
+
+    /// 连续标注：从本标注的第二个测量点到 `point` 的新标注，尺寸线与本标注对齐
+    ///
+    /// 仅支持对齐和线性标注
+    pub fn continued(&self, point: Point2) -> Option<Dimension> {
+        self.linear_direction()?;
+        self.chained(self.definition_point2, self.associations[1], point, self.line_location)
+    }
 
-        if chord_len < EPSILON {
+    /// 标注链中的下一个标注，沿用类型、样式和文字高度
+    fn chained(&self, start: Point2, association: Option<AssociativePoint>, end: Point2, location: Point2) -> Option<Dimension> {
+        if (end - start).norm() < EPSILON {
             return None;
         }
+        let mut dim = Dimension::new(start, end, location).with_associations(association, None);
+        dim.dim_type = self.dim_type;
+        dim.text_height = self.text_height;
+        dim.style_name = self.style_name.clone();
+        Some(dim)
+    }
 
-        let bulge = v1.bulge;
-        let s = chord_len / 2.0;
-        let h = s * bulge; // 弧高
+    /// 按标注样式分解为基本图元：延伸线、尺寸线、箭头和文字
+    ///
+    /// 标注自身的文字高度决定整体比例，样式中的箭头、间距和延伸线尺寸
+    /// 按 `text_height / style.text_height` 缩放。实心箭头和圆点输出为实心填充。
+    pub fn explode(&self, style: &DimStyle) -> Vec<Geometry> {
+        let scale = self.style_scale(style);
+        let mut parts = Vec::new();
+        match self.dim_type {
+            DimensionType::Aligned | DimensionType::Linear => self.explode_linear(style, scale, &mut parts),
+            DimensionType::Radius | DimensionType::Diameter => self.explode_radial(style, scale, &mut parts),
+            DimensionType::Angular | DimensionType::ArcLength => self.explode_angular(style, scale, &mut parts),
+            DimensionType::Ordinate => self.explode_ordinate(style, scale, &mut parts),
+        }
+        parts
+    }
 
-        // 计算圆心
-        let mid = Point2::new(
-            (v1.point.x + v2.point.x) / 2.0,
-            (v1.point.y + v2.point.y) / 2.0,
-        );
+    /// 角度/弧长标注的尺寸弧角度范围：(起始角, 逆时针扫掠角, 是否从第三点开始)
+    ///
+    /// 角度标注取两条边之间包含 `line_location` 的一侧（该点与顶点重合时取不大于 180° 的一侧）；
+    /// 弧长标注从第二点逆时针到第三点
+    fn dimension_arc_angles(&self) -> Option<(f64, f64, bool)> {
+        if !matches!(self.dim_type, DimensionType::Angular | DimensionType::ArcLength) {
+            return None;
+        }
+        let center = self.definition_point1;
+        let (v1, v2) = (self.definition_point2 - center, self.definition_point3 - center);
+        if v1.norm() < EPSILON || v2.norm() < EPSILON {
+            return None;
+        }
+        let sweep = ccw_angle(v1, v2);
+        let location = self.line_location - center;
+        let forward = match self.dim_type {
+            DimensionType::ArcLength => true,
+            _ if location.norm() < EPSILON => sweep <= std::f64::consts::PI,
+            _ => ccw_angle(v1, location) <= sweep,
+        };
+        if forward {
+            Some((v1.y.atan2(v1.x), sweep, false))
+        } else {
+            Some((v2.y.atan2(v2.x), std::f64::consts::TAU - sweep, true))
+        }
+    }
 
-        let radius = (s * s + h * h) / (2.0 * h.abs());
-        let d = radius - h.abs(); // 圆心到弦的距离
+    /// 角度/弧长标注的尺寸弧（以第一点为圆心，经过 `line_location`）
+    pub fn dimension_arc(&self) -> Option<Arc> {
+        let (start, sweep, _) = self.dimension_arc_angles()?;
+        let radius = (self.line_location - self.definition_point1).norm();
+        (radius > EPSILON).then(|| Arc::new(self.definition_point1, radius, start, start + sweep))
+    }
+
+    fn explode_angular(&self, style: &DimStyle, scale: f64, parts: &mut Vec<Geometry>) {
+        let Some((start, sweep, swapped)) = self.dimension_arc_angles() else {
+            return;
+        };
+        let center = self.definition_point1;
+        let radius = (self.line_location - center).norm();
+        if radius < EPSILON || sweep < EPSILON {
+            return;
+        }
+        let end = start + sweep;
+        let arrow_size = style.arrow_size * scale;
+        let gap = style.text_gap * scale;
+
+        // 延伸线：尺寸弧在测量点之外时，沿径向从测量点延伸到尺寸弧
+        let offset = style.extension_line_offset * scale;
+        let extension = style.extension_line_extension * scale;
+        let mut edges = [
+            (self.definition_point2, style.suppress_extension_line1, style.arrow_type1),
+            (self.definition_point3, style.suppress_extension_line2, style.arrow_type2),
+        ];
+        if swapped {
+            edges.swap(0, 1);
+        }
+        for ((point, suppressed, _), angle) in edges.iter().zip([start, end]) {
+            let u = unit_vector(angle);
+            let reach = radius - (point - center).dot(&u);
+            if !suppressed && reach > offset {
+                push_line(parts, point + u * offset, center + u * (radius + extension));
+            }
+        }
 
-        // 弦的垂直方向
-        let perp = if bulge > 0.0 {
-            Vector2::new(-chord.y, chord.x).normalize()
+        // 文字位于尺寸弧中点，居中时打断尺寸弧
+        let label = DimensionLabel::new(self, style, scale);
+        let mid = start + sweep / 2.0;
+        let radial = unit_vector(mid);
+        let tangent = Vector2::new(-radial.y, radial.x);
+        let rotation = if style.text_aligned {
+            readable_angle(tangent.y.atan2(tangent.x))
         } else {
-            Vector2::new(chord.y, -chord.x).normalize()
+            0.0
+        };
+        let on_arc = center + radial * radius;
+        let (text_center, on_line) = match self.text_position {
+            Some(position) => (position, false),
+            None if style.text_vertical == DimTextVertical::Centered => (on_arc, true),
+            None => (on_arc + radial * (gap + label.half_extent(rotation, radial)), false),
         };
+        let half_gap = (label.half_extent(rotation, tangent) + gap) / radius;
+        if on_line && half_gap * 2.0 < sweep {
+            parts.push(Geometry::Arc(Arc::new(center, radius, start, mid - half_gap)));
+            parts.push(Geometry::Arc(Arc::new(center, radius, mid + half_gap, end)));
+        } else {
+            parts.push(Geometry::Arc(Arc::new(center, radius, start, end)));
+        }
 
-        let center = mid + perp * d;
+        // 箭头沿尺寸弧端点的切线方向；弧长不足时放到外侧
+        let outward = if radius * sweep >= arrow_size * 2.0 { 1.0 } else { -1.0 };
+        let start_tangent = Vector2::new(-start.sin(), start.cos());
+        let end_tangent = Vector2::new(-end.sin(), end.cos());
+        parts.extend(dimension_arrow(center + unit_vector(start) * radius, -start_tangent * outward, edges[0].2, arrow_size));
+        parts.extend(dimension_arrow(center + unit_vector(end) * radius, end_tangent * outward, edges[1].2, arrow_size));
 
-        let start_angle = (v1.point.y - center.y).atan2(v1.point.x - center.x);
-        let end_angle = (v2.point.y - center.y).atan2(v2.point.x - center.x);
+        label.push(text_center, rotation, parts);
+    }
 
-        Some(Arc::new(center, radius, start_angle, end_angle))
+    fn explode_ordinate(&self, style: &DimStyle, scale: f64, parts: &mut Vec<Geometry>) {
+        let (feature, leader_end) = (self.definition_point1, self.line_location);
+        let d = leader_end - feature;
+        // 引线主方向：X 坐标沿竖直方向引出，Y 坐标沿水平方向引出
+        let dir = match self.ordinate_axis {
+            OrdinateAxis::X => Vector2::new(0.0, if d.y < 0.0 { -1.0 } else { 1.0 }),
+            OrdinateAxis::Y => Vector2::new(if d.x < 0.0 { -1.0 } else { 1.0 }, 0.0),
+        };
+        let length = d.dot(&dir);
+        let lateral = d - dir * length;
+        let offset = style.extension_line_offset * scale;
+        let gap = style.text_gap * scale;
+
+        // 引线端点与特征点不对齐时，在中间三分之一处折弯
+        let start = feature + dir * offset;
+        if lateral.norm() < EPSILON {
+            push_line(parts, start, leader_end);
+        } else {
+            let knee1 = feature + dir * (length / 3.0).max(offset);
+            let knee2 = feature + lateral + dir * (length * 2.0 / 3.0).max(offset);
+            push_line(parts, start, knee1);
+            push_line(parts, knee1, knee2);
+            push_line(parts, knee2, leader_end);
+        }
+
+        // 文字接在引线末端
+        let label = DimensionLabel::new(self, style, scale);
+        let rotation = if style.text_aligned {
+            readable_angle(dir.y.atan2(dir.x))
+        } else {
+            0.0
+        };
+        let text_center = self
+            .text_position
+            .unwrap_or_else(|| leader_end + dir * (gap + label.half_extent(rotation, dir)));
+        label.push(text_center, rotation, parts);
+    }
+
+    fn explode_linear(&self, style: &DimStyle, scale: f64, parts: &mut Vec<Geometry>) {
+        let (p1, p2) = (self.definition_point1, self.definition_point2);
+        let d = p2 - p1;
+        // 线性标注的尺寸线水平或垂直（与测量值的取法一致），对齐标注沿两点方向
+        let Some(dir) = self.linear_direction() else {
+            return;
+        };
+        let perp = Vector2::new(-dir.y, dir.x);
+        let q1 = p1 + perp * (self.line_location - p1).dot(&perp);
+        let q2 = p2 + perp * (self.line_location - p2).dot(&perp);
+        let length = (q2 - q1).dot(&dir);
+
+        let arrow_size = style.arrow_size * scale;
+        let gap = style.text_gap * scale;
+
+        // 延伸线：从测量点偏移一段距离开始，超出尺寸线一段距离
+        let offset = style.extension_line_offset * scale;
+        let extension = style.extension_line_extension * scale;
+        for (p, q, suppressed) in [
+            (p1, q1, style.suppress_extension_line1),
+            (p2, q2, style.suppress_extension_line2),
+        ] {
+            let v = q - p;
+            let len = v.norm();
+            if !suppressed && len > offset {
+                let u = v / len;
+                push_line(parts, p + u * offset, q + u * extension);
+            }
+        }
+
+        // 文字
+        let label = DimensionLabel::new(self, style, scale);
+        let rotation = if style.text_aligned {
+            readable_angle(dir.y.atan2(dir.x))
+        } else {
+            0.0
+        };
+        let along_half = label.half_extent(rotation, dir);
+        let mid_p = p1 + d * 0.5;
+        let away = match ((q1 + (q2 - q1) * 0.5) - mid_p).dot(&perp) {
+            s if s.abs() < EPSILON => perp,
+            s => perp * s.signum(),
+        };
+        let (text_center, on_line) = match self.text_position {
+            Some(position) => (position, false),
+            None => {
+                let t = match style.text_horizontal {
+                    DimTextAlignment::Left => arrow_size * 2.0 + along_half,
+                    DimTextAlignment::Right => length - arrow_size * 2.0 - along_half,
+                    DimTextAlignment::Outside => length + arrow_size + gap + along_half,
+                    DimTextAlignment::Center | DimTextAlignment::Above => length / 2.0,
+                };
+                let on_line_point = q1 + dir * t;
+                let side = match style.text_vertical {
+                    DimTextVertical::Centered => None,
+                    DimTextVertical::Outside => Some(away),
+                    DimTextVertical::Above | DimTextVertical::JIS => {
+                        // 文字上方；文字与尺寸线垂直时放在远离测量点的一侧
+                        let up = Vector2::new(-rotation.sin(), rotation.cos());
+                        match up.dot(&perp) {
+                            s if s.abs() < EPSILON => Some(away),
+                            s => Some(perp * s.signum()),
+                        }
+                    }
+                };
+                match side {
+                    Some(side) => (on_line_point + side * (gap + label.half_extent(rotation, side)), false),
+                    None => (on_line_point, true),
+                }
+            }
+        };
+
+        // 尺寸线：文字在尺寸线之外时延长到文字下方；空间不足时箭头放到延伸线外侧
+        let t_text = (text_center - q1).dot(&dir);
+        let mut start = 0.0_f64.min(t_text - along_half);
+        let mut end = length.max(t_text + along_half);
+        let arrows_inside = length >= arrow_size * 2.0;
+        if !arrows_inside {
+            start = start.min(-arrow_size * 2.0);
+            end = end.max(length + arrow_size * 2.0);
+        }
+        if on_line {
+            push_line(parts, q1 + dir * start, q1 + dir * (t_text - along_half - gap).max(start));
+            push_line(parts, q1 + dir * (t_text + along_half + gap).min(end), q1 + dir * end);
+        } else {
+            push_line(parts, q1 + dir * start, q1 + dir * end);
+        }
+
+        let outward = if arrows_inside { 1.0 } else { -1.0 };
+        parts.extend(dimension_arrow(q1, -dir * outward, style.arrow_type1, arrow_size));
+        parts.extend(dimension_arrow(q2, dir * outward, style.arrow_type2, arrow_size));
+
+        label.push(text_center, rotation, parts);
+    }
+
+    fn explode_radial(&self, style: &DimStyle, scale: f64, parts: &mut Vec<Geometry>) {
+        let (center, point) = (self.definition_point1, self.definition_point2);
+        let radial = point - center;
+        if radial.norm() < EPSILON {
+            return;
+        }
+        let dir = radial.normalize();
+        let arrow_size = style.arrow_size * scale;
+        let gap = style.text_gap * scale;
+
+        let label = DimensionLabel::new(self, style, scale);
+        let rotation = if style.text_aligned {
+            readable_angle(dir.y.atan2(dir.x))
+        } else {
+            0.0
+        };
+        let text_center = self.text_position.unwrap_or(self.line_location);
+
+        let start = if self.dim_type == DimensionType::Diameter {
+            center - radial
+        } else {
+            center
+        };
+        push_line(parts, start, point);
+
+        // 文字在圆外时从圆上的点引出到文字
+        if (text_center - center).norm() > radial.norm() {
+            let to_text = text_center - point;
+            let distance = to_text.norm();
+            if distance > EPSILON {
+                let u = to_text / distance;
+                let reach = distance - label.half_extent(rotation, u) - gap;
+                if reach > EPSILON {
+                    push_line(parts, point, point + u * reach);
+                }
+            }
+        }
+
+        // 箭头位于第二测量点（圆上），直径标注两端都有箭头
+        parts.extend(dimension_arrow(point, dir, style.arrow_type2, arrow_size));
+        if self.dim_type == DimensionType::Diameter {
+            parts.extend(dimension_arrow(start, -dir, style.arrow_type1, arrow_size));
+        }
+
+        label.push(text_center, rotation, parts);
+    }
+}
+
+/// 标注文字块：测量文字及叠放在右侧的上下偏差
+struct DimensionLabel {
+    main: String,
+    tolerance: Option<(String, String)>,
+    height: f64,
+    /// 文字样式名称
+    style_name: String,
+    /// 偏差文字起点距文字框左端的距离
+    tolerance_offset: f64,
+    width: f64,
+    box_height: f64,
+}
+
+impl DimensionLabel {
+    fn new(dim: &Dimension, style: &DimStyle, scale: f64) -> Self {
+        // 覆盖文本不含测量值时不显示公差
+        let shows_measurement = dim.text_override.as_ref().is_none_or(|text| text.contains("<>"));
+        let (symmetric, tolerance) = match style.format_tolerance() {
+            Some(_) if !shows_measurement => (None, None),
+            Some(ToleranceText::Symmetric(text)) => (Some(text), None),
+            Some(ToleranceText::Deviation { upper, lower }) => (None, Some((upper, lower))),
+            None => (None, None),
+        };
+        let main = dim.apply_text_override(dim.measured_text(style, symmetric.as_deref()));
+
+        let height = dim.text_height;
+        let width_of = |content: &str, height: f64| Text::new(Point2::origin(), content, height).width();
+        let main_width = width_of(&main, height);
+        let tolerance_offset = main_width + style.text_gap * scale;
+        let (width, box_height) = match &tolerance {
+            Some((upper, lower)) => {
+                let tol_height = height * DIM_TOLERANCE_SCALE;
+                let tol_width = width_of(upper, tol_height).max(width_of(lower, tol_height));
+                (tolerance_offset + tol_width, height.max(tol_height * 2.2))
+            }
+            None => (main_width, height),
+        };
+        Self {
+            main,
+            tolerance,
+            height,
+            style_name: style.text_style.clone(),
+            tolerance_offset,
+            width,
+            box_height,
+        }
+    }
+
+    /// 使用标注文字样式的单行文字
+    fn text(&self, position: Point2, content: &str, height: f64, rotation: f64) -> Geometry {
+        let mut text = Text::new(position, content, height).with_rotation(rotation);
+        text.style_name.clone_from(&self.style_name);
+        Geometry::Text(text)
+    }
+
+    /// 文字框在 `axis` 方向上的半宽
+    fn half_extent(&self, rotation: f64, axis: Vector2) -> f64 {
+        let (sin, cos) = rotation.sin_cos();
+        let along = Vector2::new(cos, sin);
+        let up = Vector2::new(-sin, cos);
+        (self.width / 2.0 * along.dot(&axis)).abs() + (self.box_height / 2.0 * up.dot(&axis)).abs()
+    }
+
+    /// 以 `center` 为文字框中心输出文字
+    fn push(&self, center: Point2, rotation: f64, parts: &mut Vec<Geometry>) {
+        if self.main.is_empty() {
+            return;
+        }
+        let (sin, cos) = rotation.sin_cos();
+        let along = Vector2::new(cos, sin);
+        let up = Vector2::new(-sin, cos);
+        let left = center - along * (self.width / 2.0);
+
+        parts.push(self.text(left - up * (self.height / 2.0), &self.main, self.height, rotation));
+        if let Some((upper, lower)) = &self.tolerance {
+            let tol_height = self.height * DIM_TOLERANCE_SCALE;
+            let start = left + along * self.tolerance_offset;
+            parts.push(self.text(start + up * (tol_height * 0.1), upper, tol_height, rotation));
+            parts.push(self.text(start - up * (tol_height * 1.1), lower, tol_height, rotation));
+        }
+    }
+}
+
+/// 文字方向保持正向可读（角度落在 (-90°, 90°]）
+fn readable_angle(angle: f64) -> f64 {
+    if angle > std::f64::consts::FRAC_PI_2 + EPSILON {
+        angle - std::f64::consts::PI
+    } else if angle <= -std::f64::consts::FRAC_PI_2 + EPSILON {
+        angle + std::f64::consts::PI
+    } else {
+        angle
+    }
+}
+
+fn unit_vector(angle: f64) -> Vector2 {
+    Vector2::new(angle.cos(), angle.sin())
+}
+
+/// 从 `from` 逆时针转到 `to` 的角度，范围 [0, 2π)
+fn ccw_angle(from: Vector2, to: Vector2) -> f64 {
+    let angle = (from.x * to.y - from.y * to.x).atan2(from.dot(&to));
+    if angle < 0.0 {
+        angle + std::f64::consts::TAU
+    } else {
+        angle
+    }
+}
+
+fn push_line(parts: &mut Vec<Geometry>, start: Point2, end: Point2) {
+    if (end - start).norm() > EPSILON {
+        parts.push(Geometry::Line(Line::new(start, end)));
+    }
+}
+
+/// 在 `tip` 处生成指向 `direction` 的标注箭头
+fn dimension_arrow(tip: Point2, direction: Vector2, arrow_type: dimstyle::ArrowType, size: f64) -> Vec<Geometry> {
+    use dimstyle::ArrowType as Arrow;
+
+    if size <= EPSILON || direction.norm() < EPSILON {
+        return Vec::new();
+    }
+    let dir = direction.normalize();
+    let perp = Vector2::new(-dir.y, dir.x);
+    let base = tip - dir * size;
+    let (left, right) = (base + perp * (size / 6.0), base - perp * (size / 6.0));
+    let open = |points: [Point2; 3]| {
+        Geometry::Polyline(Polyline::new(points.into_iter().map(PolylineVertex::new).collect(), false))
+    };
+    let filled_dot = |radius: f64| {
+        Geometry::Hatch(Hatch::solid(vec![HatchBoundary::new(
+            vec![HatchBoundaryElement::Arc(Arc::new(tip, radius, 0.0, 2.0 * std::f64::consts::PI))],
+            true,
+        )]))
+    };
+
+    match arrow_type {
+        Arrow::None => Vec::new(),
+        Arrow::ClosedFilled => vec![Geometry::Hatch(Hatch::solid(vec![HatchBoundary::new(
+            vec![
+                HatchBoundaryElement::Line(Line::new(tip, left)),
+                HatchBoundaryElement::Line(Line::new(left, right)),
+                HatchBoundaryElement::Line(Line::new(right, tip)),
+            ],
+            true,
+        )]))],
+        Arrow::ClosedBlank => vec![Geometry::Polyline(Polyline::new(
+            vec![PolylineVertex::new(tip), PolylineVertex::new(left), PolylineVertex::new(right)],
+            true,
+        ))],
+        Arrow::Open => vec![open([left, tip, right])],
+        Arrow::RightAngle => {
+            let back = tip - dir * (size / 2.0);
+            vec![open([back + perp * (size / 2.0), tip, back - perp * (size / 2.0)])]
+        }
+        Arrow::Dot => vec![filled_dot(size / 4.0)],
+        Arrow::DotSmall => vec![filled_dot(size / 8.0)],
+        Arrow::DotBlank => vec![Geometry::Circle(Circle::new(tip, size / 4.0))],
+        Arrow::Origin => vec![Geometry::Circle(Circle::new(tip, size / 2.0))],
+        Arrow::Oblique | Arrow::ArchitecturalTick => {
+            let slash = (dir + perp).normalize() * (size / 2.0);
+            vec![Geometry::Line(Line::new(tip - slash, tip + slash))]
+        }
+        Arrow::Integral => vec![Geometry::Polyline(Polyline::new(
+            vec![
+                PolylineVertex::with_bulge(tip - perp * (size / 2.0), 0.5),
+                PolylineVertex::with_bulge(tip, -0.5),
+                PolylineVertex::new(tip + perp * (size / 2.0)),
+            ],
+            false,
+        ))],
+    }
+}
+
+impl Polyline {
+    /// 爆炸为独立的线段/圆弧
+    ///
+    /// 这是我们要做好的功能 - 智能爆炸，只生成需要的几何体
+    pub fn explode(&self) -> Vec<Geometry> {
+        self.segments().map(|segment| segment.to_geometry()).collect()
     }
 }
 
@@ -1114,7 +1999,8 @@ pub enum SplineType {
 
 /// 样条曲线
 /// 
-/// 支持 B-样条和 NURBS 曲线，使用 De Boor 算法求值
+/// 支持 B-样条和 NURBS 曲线，按有理基函数求值。
+/// 拟合点样条的控制点由拟合点插值得到，相关算法见 [`crate::spline`]。
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Spline {
     /// 样条类型
@@ -1131,6 +2017,15 @@ pub struct Spline {
     pub closed: bool,
     /// 拟合点（用于样条拟合）
     pub fit_points: Vec<Point2>,
+    /// 拟合起点切向（`None` 表示自动）
+    #[serde(default)]
+    pub start_tangent: Option<Vector2>,
+    /// 拟合终点切向（`None` 表示自动）
+    #[serde(default)]
+    pub end_tangent: Option<Vector2>,
+    /// 拟合公差（0 表示曲线经过所有拟合点）
+    #[serde(default)]
+    pub fit_tolerance: f64,
 }
 
 impl Spline {
@@ -1144,104 +2039,40 @@ impl Spline {
             weights: Vec::new(),
             closed: false,
             fit_points: Vec::new(),
+            start_tangent: None,
+            end_tangent: None,
+            fit_tolerance: 0.0,
         }
     }
 
-    /// 从控制点创建 B-样条（自动生成均匀节点向量）
+    /// 从控制点创建 B-样条（自动生成钳位均匀节点向量，曲线经过首末控制点）
     pub fn from_control_points(control_points: Vec<Point2>, degree: u8, closed: bool) -> Self {
-        let n = control_points.len();
-        let k = degree as usize;
-        
-        // 生成均匀节点向量
-        let num_knots = n + k + 1;
-        let mut knots = Vec::with_capacity(num_knots);
-        
-        for i in 0..num_knots {
-            if i < k {
-                knots.push(0.0);
-            } else if i >= n {
-                knots.push((n - k + 1) as f64);
-            } else {
-                knots.push((i - k + 1) as f64);
-            }
-        }
-        
-        Self {
-            spline_type: SplineType::BSpline,
-            degree,
-            control_points,
-            knots,
-            weights: Vec::new(),
-            closed,
-            fit_points: Vec::new(),
+        let mut spline = Self::new(degree);
+        spline.control_points = control_points;
+        spline.closed = closed;
+        if spline.control_points.len() > 1 {
+            spline.knots = crate::spline::clamped_uniform_knots(spline.control_points.len(), spline.eval_degree());
         }
+        spline
     }
 
-    /// 使用 De Boor 算法计算样条曲线上的点
+    /// 计算样条曲线上参数 t 处的点（权重为空时为非有理 B-样条）
     pub fn point_at_param(&self, t: f64) -> Point2 {
-        if self.control_points.is_empty() {
-            return Point2::origin();
-        }
-        
-        if self.control_points.len() == 1 {
-            return self.control_points[0];
-        }
-        
-        let n = self.control_points.len();
-        let k = self.degree as usize;
-        
-        // 找到 t 所在的区间
-        let mut span = k;
-        while span < n && self.knots.get(span + 1).map_or(false, |&k| k <= t) {
-            span += 1;
+        match self.control_points.len() {
+            0 => Point2::origin(),
+            1 => self.control_points[0],
+            _ => self.evaluate(t).0,
         }
-        span = span.min(n - 1);
-        
-        // De Boor 算法
-        let mut d: Vec<Point2> = (0..=k)
-            .filter_map(|i| {
-                let idx = span.saturating_sub(k) + i;
-                self.control_points.get(idx).copied()
-            })
-            .collect();
-        
-        if d.len() <= k {
-            return self.control_points.last().copied().unwrap_or(Point2::origin());
-        }
-        
-        for r in 1..=k {
-            for j in (r..=k).rev() {
-                let idx = span.saturating_sub(k) + j;
-                let left = self.knots.get(idx).copied().unwrap_or(0.0);
-                let right = self.knots.get(idx + k + 1 - r).copied().unwrap_or(1.0);
-                
-                let denom = right - left;
-                if denom.abs() < EPSILON {
-                    continue;
-                }
-                
-                let alpha = (t - left) / denom;
-                let j_idx = j;
-                let j_prev = j - 1;
-                
-                if j_idx < d.len() && j_prev < d.len() {
-                    d[j_idx] = Point2::new(
-                        (1.0 - alpha) * d[j_prev].x + alpha * d[j_idx].x,
-                        (1.0 - alpha) * d[j_prev].y + alpha * d[j_idx].y,
-                    );
-                }
-            }
-        }
-        
-        d.get(k).copied().unwrap_or(Point2::origin())
     }
 
     /// 获取参数范围
     pub fn param_range(&self) -> (f64, f64) {
-        let k = self.degree as usize;
-        let start = self.knots.get(k).copied().unwrap_or(0.0);
-        let end = self.knots.get(self.knots.len().saturating_sub(k + 1)).copied().unwrap_or(1.0);
-        (start, end)
+        if self.control_points.len() < 2 {
+            return (0.0, 1.0);
+        }
+        let degree = self.eval_degree();
+        let knots = self.knot_vector();
+        (knots[degree], knots[self.control_points.len()])
     }
 
     /// 计算点到样条曲线的距离（近似值）
@@ -1304,6 +2135,124 @@ pub enum HatchBoundaryElement {
     Spline(Spline),
 }
 
+impl HatchBoundaryElement {
+    /// 起点
+    pub fn start_point(&self) -> Point2 {
+        match self {
+            HatchBoundaryElement::Line(line) => line.start,
+            HatchBoundaryElement::Arc(arc) => arc.start_point(),
+            HatchBoundaryElement::Ellipse(ellipse) => ellipse.start_point(),
+            HatchBoundaryElement::Spline(spline) => spline.point_at_param(spline.param_range().0),
+        }
+    }
+
+    /// 终点
+    pub fn end_point(&self) -> Point2 {
+        match self {
+            HatchBoundaryElement::Line(line) => line.end,
+            HatchBoundaryElement::Arc(arc) => arc.end_point(),
+            HatchBoundaryElement::Ellipse(ellipse) => ellipse.end_point(),
+            HatchBoundaryElement::Spline(spline) => spline.point_at_param(spline.param_range().1),
+        }
+    }
+
+    /// 点到边界元素的距离
+    pub fn distance_to_point(&self, point: &Point2) -> f64 {
+        match self {
+            HatchBoundaryElement::Line(line) => line.distance_to_point(point),
+            HatchBoundaryElement::Arc(arc) => arc.distance_to_point(point),
+            HatchBoundaryElement::Ellipse(ellipse) => ellipse.distance_to_point(point),
+            HatchBoundaryElement::Spline(spline) => spline.distance_to_point(point),
+        }
+    }
+
+    /// 沿元素方向的格林公式积分 [∮½(x dy − y dx), ∮½x² dy, −∮½y² dx]，
+    /// 闭合环上依次为有向面积和对 y 轴、x 轴的一阶矩
+    fn green_integrals(&self) -> [f64; 3] {
+        match self {
+            HatchBoundaryElement::Line(line) => {
+                let d = line.end - line.start;
+                integrate_green(|t| line.start + d * t, |_| d, 0.0, 1.0, 1)
+            }
+            HatchBoundaryElement::Arc(arc) => {
+                let sweep = arc.sweep_angle();
+                let sweep = if sweep < EPSILON { 2.0 * std::f64::consts::PI } else { sweep };
+                let r = arc.radius;
+                integrate_green(
+                    |a| arc.center + Vector2::new(a.cos(), a.sin()) * r,
+                    |a| Vector2::new(-a.sin(), a.cos()) * r,
+                    arc.start_angle,
+                    arc.start_angle + sweep,
+                    (sweep / std::f64::consts::FRAC_PI_4).ceil() as usize,
+                )
+            }
+            HatchBoundaryElement::Ellipse(ellipse) => {
+                let mut sweep = ellipse.end_param - ellipse.start_param;
+                while sweep <= EPSILON {
+                    sweep += 2.0 * std::f64::consts::PI;
+                }
+                let (major, minor) = (ellipse.major_axis, ellipse.minor_axis());
+                integrate_green(
+                    |t| ellipse.center + major * t.cos() + minor * t.sin(),
+                    |t| minor * t.cos() - major * t.sin(),
+                    ellipse.start_param,
+                    ellipse.start_param + sweep,
+                    (sweep / std::f64::consts::FRAC_PI_4).ceil() as usize,
+                )
+            }
+            HatchBoundaryElement::Spline(spline) => {
+                // 样条按细分折线积分
+                let points = spline.sample_points(64);
+                let mut sum = [0.0; 3];
+                for w in points.windows(2) {
+                    let part = HatchBoundaryElement::Line(Line::new(w[0], w[1])).green_integrals();
+                    for (s, p) in sum.iter_mut().zip(part) {
+                        *s += p;
+                    }
+                }
+                sum
+            }
+        }
+    }
+}
+
+/// 8 点 Gauss-Legendre 求积（区间 [-1, 1] 上的节点和权重）
+const GAUSS_NODES: [(f64, f64); 8] = [
+    (-0.960_289_856_497_536_2, 0.101_228_536_290_376_26),
+    (-0.796_666_477_413_626_7, 0.222_381_034_453_374_47),
+    (-0.525_532_409_916_329, 0.313_706_645_877_887_3),
+    (-0.183_434_642_495_649_8, 0.362_683_783_378_362),
+    (0.183_434_642_495_649_8, 0.362_683_783_378_362),
+    (0.525_532_409_916_329, 0.313_706_645_877_887_3),
+    (0.796_666_477_413_626_7, 0.222_381_034_453_374_47),
+    (0.960_289_856_497_536_2, 0.101_228_536_290_376_26),
+];
+
+/// 对参数曲线 p(t), t ∈ [a, b] 分段计算格林公式积分
+fn integrate_green(
+    point: impl Fn(f64) -> Point2,
+    derivative: impl Fn(f64) -> Vector2,
+    a: f64,
+    b: f64,
+    pieces: usize,
+) -> [f64; 3] {
+    let pieces = pieces.max(1);
+    let step = (b - a) / pieces as f64;
+    let mut sum = [0.0; 3];
+    for i in 0..pieces {
+        let mid = a + step * (i as f64 + 0.5);
+        for (node, weight) in GAUSS_NODES {
+            let t = mid + node * step / 2.0;
+            let (p, d) = (point(t), derivative(t));
+            let w = weight * step / 2.0;
+            sum[0] += w * 0.5 * (p.x * d.y - p.y * d.x);
+            sum[1] += w * 0.5 * p.x * p.x * d.y;
+            sum[2] -= w * 0.5 * p.y * p.y * d.x;
+        }
+    }
+    sum
+}
+
 /// 填充边界
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HatchBoundary {
@@ -1332,6 +2281,40 @@ impl HatchBoundary {
         }
         bbox
     }
+
+    /// 边界环的有向面积及一阶矩 [A, ∫∫x dA, ∫∫y dA]
+    ///
+    /// 元素方向可能不一致，按端点首尾相接确定遍历方向（与细分时的规则相同）；
+    /// 逆时针环的面积为正
+    pub fn signed_moments(&self) -> [f64; 3] {
+        let mut sum = [0.0; 3];
+        let mut end: Option<Point2> = None;
+        for element in &self.elements {
+            let (start, finish) = (element.start_point(), element.end_point());
+            let reversed = end.is_some_and(|e| (start - e).norm() > (finish - e).norm());
+            let sign = if reversed { -1.0 } else { 1.0 };
+            for (s, v) in sum.iter_mut().zip(element.green_integrals()) {
+                *s += sign * v;
+            }
+            end = Some(if reversed { start } else { finish });
+        }
+        sum
+    }
+}
+
+/// 偶奇规则下点是否在闭合折线内（射线法）
+fn ring_contains(ring: &[Point2], point: &Point2) -> bool {
+    let mut inside = false;
+    for w in ring.windows(2) {
+        let (a, b) = (w[0], w[1]);
+        if (a.y > point.y) != (b.y > point.y) {
+            let x = a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x);
+            if point.x < x {
+                inside = !inside;
+            }
+        }
+    }
+    inside
 }
 
 /// 填充图案类型
@@ -1355,7 +2338,7 @@ pub struct HatchPatternLine {
     pub angle: f64,
     /// 起点
     pub base_point: Point2,
-    /// 偏移（用于平行线）
+    /// 相邻平行线的偏移（沿线方向、垂直于线方向），与 .pat 文件相同
     pub offset: Vector2,
     /// 虚线模式（正数表示实线，负数表示间隙）
     pub dash_pattern: Vec<f64>,
@@ -1404,10 +2387,91 @@ impl Hatch {
         bbox
     }
 
-    /// 检查点是否在填充区域内
-    pub fn contains_point(&self, _point: &Point2, _tolerance: f64) -> bool {
-        // TODO: 实现点在多边形内的判断（射线法）
-        false
+    /// 检查点是否在填充区域内或在边界附近
+    ///
+    /// 所有边界环按奇偶规则共同决定填充区域，孤岛（环内的环）自然被排除
+    pub fn contains_point(&self, point: &Point2, tolerance: f64) -> bool {
+        let on_boundary = self
+            .boundaries
+            .iter()
+            .flat_map(|b| &b.elements)
+            .any(|e| e.distance_to_point(point) <= tolerance);
+        if on_boundary {
+            return true;
+        }
+
+        let crossings = self
+            .boundaries
+            .iter()
+            .map(|boundary| crate::tessellation::boundary_ring(boundary, crate::tessellation::DEFAULT_TOLERANCE))
+            .filter(|ring| ring.len() >= 3 && ring_contains(ring, point))
+            .count();
+        crossings % 2 == 1
+    }
+
+    /// 每个边界的嵌套深度（被其它边界包含的次数）
+    ///
+    /// 深度为偶数的边界是外边界，奇数为孤岛（孔洞）；退化边界的深度为 0
+    pub fn boundary_depths(&self) -> Vec<usize> {
+        let rings: Vec<Vec<Point2>> = self
+            .boundaries
+            .iter()
+            .map(|boundary| crate::tessellation::boundary_ring(boundary, crate::tessellation::DEFAULT_TOLERANCE))
+            .collect();
+        rings
+            .iter()
+            .enumerate()
+            .map(|(i, ring)| {
+                if ring.len() < 3 {
+                    return 0;
+                }
+                // 取首条边的中点作为代表点，避免落在相邻环共享的顶点上
+                let probe = Point2::from((ring[0].coords + ring[1].coords) / 2.0);
+                rings
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, other)| *j != i && other.len() >= 3 && ring_contains(other, &probe))
+                    .count()
+            })
+            .collect()
+    }
+
+    /// 按嵌套关系重新标记外边界和孤岛
+    pub fn detect_islands(&mut self) {
+        let depths = self.boundary_depths();
+        for (boundary, depth) in self.boundaries.iter_mut().zip(depths) {
+            boundary.is_outer = depth % 2 == 0;
+        }
+    }
+
+    /// 填充区域的面积（扣除孤岛）
+    pub fn area(&self) -> f64 {
+        self.region_moments()[0]
+    }
+
+    /// 填充区域的形心，面积为零时返回 None
+    pub fn centroid(&self) -> Option<Point2> {
+        let [area, mx, my] = self.region_moments();
+        if area.abs() < EPSILON {
+            return None;
+        }
+        Some(Point2::new(mx / area, my / area))
+    }
+
+    /// 区域的面积和一阶矩：各环取正向后按嵌套深度的奇偶加减
+    fn region_moments(&self) -> [f64; 3] {
+        let mut sum = [0.0; 3];
+        for (boundary, depth) in self.boundaries.iter().zip(self.boundary_depths()) {
+            let mut moments = boundary.signed_moments();
+            if moments[0] < 0.0 {
+                moments = moments.map(|m| -m);
+            }
+            let sign = if depth % 2 == 0 { 1.0 } else { -1.0 };
+            for (s, m) in sum.iter_mut().zip(moments) {
+                *s += sign * m;
+            }
+        }
+        sum
     }
 }
 
@@ -1444,6 +2508,12 @@ pub struct Leader {
     pub text: Option<String>,
     /// 文本高度
     pub text_height: f64,
+    /// 文字样式名称
+    #[serde(default)]
+    pub style_name: String,
+    /// 字体名称（由文字样式决定），None 为默认字体
+    #[serde(default)]
+    pub font: Option<String>,
 }
 
 impl Leader {
@@ -1455,6 +2525,8 @@ impl Leader {
             arrow_size: 3.0,
             text: None,
             text_height: 2.5,
+            style_name: String::new(),
+            font: None,
         }
     }
 
@@ -1491,6 +2563,15 @@ impl Leader {
         self.vertices.last().copied()
     }
 
+    /// 引线文字（位于最后一个顶点，使用引线的文字样式和字体）
+    pub fn text_entity(&self) -> Option<Text> {
+        let (content, position) = (self.text.as_ref()?, self.text_position()?);
+        let mut text = Text::new(position, content.clone(), self.text_height);
+        text.style_name = self.style_name.clone();
+        text.font = self.font.clone();
+        Some(text)
+    }
+
     /// 计算总长度
     pub fn length(&self) -> f64 {
         let mut total = 0.0;
@@ -1551,5 +2632,275 @@ mod tests {
         assert!(matches!(exploded[0], Geometry::Line(_)));
         assert!(matches!(exploded[1], Geometry::Line(_)));
     }
-}
 
+    fn square_boundary(min: f64, max: f64, is_outer: bool) -> HatchBoundary {
+        let p = [
+            Point2::new(min, min),
+            Point2::new(max, min),
+            Point2::new(max, max),
+            Point2::new(min, max),
+        ];
+        let elements = (0..4)
+            .map(|i| HatchBoundaryElement::Line(Line::new(p[i], p[(i + 1) % 4])))
+            .collect();
+        HatchBoundary::new(elements, is_outer)
+    }
+
+    #[test]
+    fn test_hatch_islands_area_and_contains() {
+        // 外边界 10x10，内部 2x2 孤岛（标记错误，由 detect_islands 纠正）
+        let mut hatch = Hatch::solid(vec![square_boundary(0.0, 10.0, true), square_boundary(4.0, 6.0, true)]);
+        assert_eq!(hatch.boundary_depths(), vec![0, 1]);
+        hatch.detect_islands();
+        assert!(hatch.boundaries[0].is_outer);
+        assert!(!hatch.boundaries[1].is_outer);
+
+        assert!((hatch.area() - 96.0).abs() < 1e-9);
+        let c = hatch.centroid().unwrap();
+        assert!((c.x - 5.0).abs() < 1e-9 && (c.y - 5.0).abs() < 1e-9);
+
+        assert!(hatch.contains_point(&Point2::new(1.0, 1.0), 0.01));
+        assert!(!hatch.contains_point(&Point2::new(5.0, 5.0), 0.01));
+        assert!(hatch.contains_point(&Point2::new(4.0, 5.0), 0.01));
+        assert!(!hatch.contains_point(&Point2::new(11.0, 5.0), 0.01));
+        assert!(hatch.contains_point(&Point2::new(10.005, 5.0), 0.01));
+    }
+
+    #[test]
+    fn test_hatch_curved_boundaries() {
+        use std::f64::consts::PI;
+        // 半圆（直线 + 圆弧）
+        let half = HatchBoundary::new(
+            vec![
+                HatchBoundaryElement::Line(Line::new(Point2::new(-2.0, 0.0), Point2::new(2.0, 0.0))),
+                HatchBoundaryElement::Arc(Arc::new(Point2::origin(), 2.0, 0.0, PI)),
+            ],
+            true,
+        );
+        let hatch = Hatch::solid(vec![half]);
+        assert!((hatch.area() - 2.0 * PI).abs() < 1e-9);
+        let c = hatch.centroid().unwrap();
+        assert!(c.x.abs() < 1e-9);
+        assert!((c.y - 8.0 / (3.0 * PI)).abs() < 1e-9);
+        assert!(hatch.contains_point(&Point2::new(0.0, 1.9), 0.0));
+        assert!(!hatch.contains_point(&Point2::new(0.0, -0.5), 0.0));
+
+        // 整圆外边界内含椭圆孤岛
+        let circle = HatchBoundary::new(
+            vec![HatchBoundaryElement::Arc(Arc::new(Point2::origin(), 5.0, 0.0, 2.0 * PI))],
+            true,
+        );
+        let ellipse = HatchBoundary::new(
+            vec![HatchBoundaryElement::Ellipse(Ellipse::new(
+                Point2::origin(),
+                Vector2::new(2.0, 0.0),
+                0.5,
+            ))],
+            false,
+        );
+        let hatch = Hatch::solid(vec![circle, ellipse]);
+        assert!((hatch.area() - (25.0 * PI - 2.0 * PI)).abs() < 1e-6);
+        assert!(!hatch.contains_point(&Point2::new(0.5, 0.0), 0.0));
+        assert!(hatch.contains_point(&Point2::new(0.0, 3.0), 0.0));
+    }
+
+    #[test]
+    fn test_dimension_explode_with_style() {
+        let style = DimStyle {
+            suppress_extension_line2: true,
+            arrow_type2: dimstyle::ArrowType::Open,
+            show_alternate_units: true,
+            ..DimStyle::default()
+        };
+        let dim = Dimension::new(Point2::new(0.0, 0.0), Point2::new(20.0, 0.0), Point2::new(10.0, 10.0)).with_style(&style);
+        let parts = dim.explode(&style);
+
+        // 一条延伸线（第二条被抑制）+ 尺寸线
+        let lines: Vec<&Line> = parts
+            .iter()
+            .filter_map(|g| match g {
+                Geometry::Line(line) => Some(line),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert!((lines[0].start - Point2::new(0.0, 0.625)).norm() < 1e-9);
+        assert!((lines[0].end - Point2::new(0.0, 11.25)).norm() < 1e-9);
+        assert!((lines[1].start - Point2::new(0.0, 10.0)).norm() < 1e-9);
+        assert!((lines[1].end - Point2::new(20.0, 10.0)).norm() < 1e-9);
+
+        // 第一个箭头实心填充，第二个为开放箭头
+        assert!(parts.iter().any(|g| matches!(g, Geometry::Hatch(h) if matches!(h.pattern_type, HatchPatternType::Solid))));
+        assert!(parts.iter().any(|g| matches!(g, Geometry::Polyline(p) if !p.closed)));
+
+        // 文字位于尺寸线上方，带方括号中的替代单位
+        let text = parts
+            .iter()
+            .find_map(|g| match g {
+                Geometry::Text(text) => Some(text),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(text.content, "20.00 [0.79\"]");
+        assert!(text.position.y > 10.0);
+    }
+
+    #[test]
+    fn test_dimension_tolerance_and_placement() {
+        let mut dim = Dimension::new(Point2::new(0.0, 0.0), Point2::new(0.0, 30.0), Point2::new(-8.0, 15.0))
+            .with_style(&DimStyle::mechanical());
+        dim.dim_type = DimensionType::Linear;
+        let style = DimStyle {
+            tolerance_lower: -0.05,
+            text_vertical: DimTextVertical::Centered,
+            ..DimStyle::mechanical()
+        };
+
+        let parts = dim.explode(&style);
+        let texts: Vec<&Text> = parts
+            .iter()
+            .filter_map(|g| match g {
+                Geometry::Text(text) => Some(text),
+                _ => None,
+            })
+            .collect();
+        // 主文字 + 上下偏差，文字沿竖直尺寸线方向
+        assert_eq!(texts.len(), 3);
+        assert_eq!(texts[0].content, "30.000");
+        assert_eq!(texts[1].content, "+0.10");
+        assert_eq!(texts[2].content, "-0.05");
+        assert!((texts[0].rotation - std::f64::consts::FRAC_PI_2).abs() < 1e-9);
+
+        // 显示文本与文字框跟随样式精度，文字框包含偏差文字
+        assert_eq!(dim.display_text(&style), "30.000");
+        let main_width = Text::new(Point2::origin(), "30.000", dim.text_height).width();
+        assert!(dim.text_bounding_box(&style).width() > main_width);
+
+        // 文字居中时尺寸线在文字处断开
+        let dimension_lines = parts
+            .iter()
+            .filter(|g| matches!(g, Geometry::Line(line) if (line.start.x + 8.0).abs() < 1e-9 && (line.end.x + 8.0).abs() < 1e-9))
+            .count();
+        assert_eq!(dimension_lines, 2);
+
+        // 覆盖文本不含测量值时不显示公差
+        dim.text_override = Some("REF".to_string());
+        let texts = dim.explode(&style).into_iter().filter(|g| matches!(g, Geometry::Text(_))).count();
+        assert_eq!(texts, 1);
+    }
+
+    #[test]
+    fn test_baseline_and_continued_dimensions() {
+        let style = DimStyle::default();
+        let mut base = Dimension::new(Point2::new(0.0, 0.0), Point2::new(20.0, 0.0), Point2::new(10.0, -8.0))
+            .with_style(&style);
+        base.dim_type = DimensionType::Linear;
+
+        // 基线：共用原点，尺寸线向外偏移一个基线间距
+        let second = base.baseline(Point2::new(35.0, 5.0), &style).unwrap();
+        assert_eq!(second.definition_point1, Point2::new(0.0, 0.0));
+        assert_eq!(second.dim_type, DimensionType::Linear);
+        assert!((second.line_location.y + 8.0 + style.baseline_spacing).abs() < 1e-9);
+        assert!((second.measurement() - 35.0).abs() < 1e-9);
+        let third = second.baseline(Point2::new(50.0, 0.0), &style).unwrap();
+        assert!((third.line_location.y + 8.0 + style.baseline_spacing * 2.0).abs() < 1e-9);
+
+        // 连续：从上一个标注的第二个测量点开始，尺寸线对齐
+        let next = base.continued(Point2::new(32.0, 0.0)).unwrap();
+        assert_eq!(next.definition_point1, Point2::new(20.0, 0.0));
+        assert_eq!(next.line_location, base.line_location);
+        assert!((next.measurement() - 12.0).abs() < 1e-9);
+        assert!(base.continued(Point2::new(20.0, 0.0)).is_none());
+
+        base.swap_definition_points();
+        assert_eq!(base.continued(Point2::new(-5.0, 0.0)).unwrap().definition_point1, Point2::new(0.0, 0.0));
+    }
+
+    #[test]
+    fn test_angular_arc_length_and_ordinate_dimensions() {
+        let style = DimStyle::default();
+        let vertex = Point2::new(0.0, 0.0);
+        let (first, second) = (Point2::new(10.0, 0.0), Point2::new(0.0, 10.0));
+
+        // 尺寸弧位置决定测量哪一侧的角度
+        let dim = Dimension::angular(vertex, first, second, Point2::new(14.0, 14.0)).with_style(&style);
+        assert!((dim.measurement() - 90.0).abs() < 1e-9);
+        let reflex = Dimension::angular(vertex, first, second, Point2::new(-14.0, -14.0));
+        assert!((reflex.measurement() - 270.0).abs() < 1e-9);
+
+        let parts = dim.explode(&style);
+        let arcs: Vec<&Arc> = parts
+            .iter()
+            .filter_map(|g| match g {
+                Geometry::Arc(arc) => Some(arc),
+                _ => None,
+            })
+            .collect();
+        assert!(!arcs.is_empty());
+        assert!(arcs.iter().all(|arc| (arc.radius - 19.79898987322333).abs() < 1e-9));
+        // 尺寸弧在测量点之外，两条延伸线沿径向引出
+        assert_eq!(parts.iter().filter(|g| matches!(g, Geometry::Line(_))).count(), 2);
+        assert!(parts.iter().any(|g| matches!(g, Geometry::Text(text) if text.content == style.format_angle(std::f64::consts::FRAC_PI_2))));
+
+        // 弧长：从起点逆时针到终点
+        let arc_dim = Dimension::arc_length(vertex, Point2::new(0.0, 10.0), Point2::new(10.0, 0.0), Point2::new(0.0, 15.0));
+        assert!((arc_dim.measurement() - 15.0 * std::f64::consts::PI).abs() < 1e-9);
+        assert!(arc_dim.bounding_box().min.y < -14.9);
+        let label = arc_dim.styled_text(&style);
+        assert!(label.starts_with('⌒'));
+
+        // 坐标标注：相对原点的坐标，引线竖直时测量 X
+        let feature = Point2::new(25.0, 40.0);
+        let axis = OrdinateAxis::from_leader(feature, Point2::new(28.0, 60.0));
+        assert_eq!(axis, OrdinateAxis::X);
+        let ordinate = Dimension::ordinate(feature, Point2::new(5.0, 10.0), Point2::new(28.0, 60.0), axis);
+        assert!((ordinate.measurement() - 20.0).abs() < 1e-9);
+        let ordinate_y = Dimension::ordinate(feature, Point2::new(5.0, 10.0), Point2::new(50.0, 40.0), OrdinateAxis::Y);
+        assert!((ordinate_y.measurement() - 30.0).abs() < 1e-9);
+        // 引线端点与特征点不对齐时折弯为三段
+        let leader_lines = ordinate.explode(&style).into_iter().filter(|g| matches!(g, Geometry::Line(_))).count();
+        assert_eq!(leader_lines, 3);
+    }
+
+    #[test]
+    fn test_text_alignment_modes() {
+        let position = Point2::new(10.0, 20.0);
+        let text = |alignment| Text::new(position, "HELLO", 5.0).with_alignment(alignment);
+
+        // 正中：对齐点位于基线起点之后半个宽度、基线之上半个字高
+        let middle = text(TextAlignment::MiddleCenter);
+        let (start, end) = middle.baseline();
+        assert!((start + (end - start) / 2.0 + Vector2::new(0.0, 2.5) - position).norm() < 1e-9);
+
+        // 右上：字高处的右端；左下：下伸部分的底部
+        let (_, end) = text(TextAlignment::TopRight).baseline();
+        assert!((end - Point2::new(10.0, 15.0)).norm() < 1e-9);
+        let (start, _) = text(TextAlignment::BottomLeft).baseline();
+        assert!((start - Point2::new(10.0, 20.0 + 5.0 / 3.0)).norm() < 1e-9);
+
+        // 布满：字高不变，宽度系数使文字布满两点；对齐：等比缩放字高
+        let end_point = Point2::new(10.0, 60.0);
+        let fit = text(TextAlignment::Fit).with_end_point(end_point);
+        let (start, end) = fit.baseline();
+        assert!((start - position).norm() < 1e-9 && (end - end_point).norm() < 1e-9);
+        assert_eq!(fit.fitted().height, 5.0);
+        let aligned = text(TextAlignment::Aligned).with_end_point(end_point);
+        let fitted = aligned.fitted();
+        assert!((fitted.width() - 40.0).abs() < 1e-9);
+        assert!((fitted.rotation - std::f64::consts::FRAC_PI_2).abs() < 1e-9);
+        assert!((aligned.baseline().1 - end_point).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_rotated_text_contains_point() {
+        let text = Text::new(Point2::origin(), "HELLO WORLD", 5.0)
+            .with_rotation(std::f64::consts::FRAC_PI_4)
+            .with_alignment(TextAlignment::MiddleCenter);
+        assert!(text.contains_point(&Point2::origin(), 0.0));
+        // 位于轴对齐包围盒内但在旋转的文字框之外
+        let bbox = text.bounding_box();
+        let corner = Point2::new(bbox.max.x - 0.5, bbox.min.y + 0.5);
+        assert!(bbox.contains(&corner));
+        assert!(!text.contains_point(&corner, 0.1));
+    }
+}
@@ -20,6 +20,8 @@ pub enum GripType {
     Quadrant,
     /// 控制点夹点 - 用于样条曲线
     ControlPoint,
+    /// 拟合点夹点 - 用于拟合点样条曲线
+    FitPoint,
     /// 基点夹点 - 用于块参照
     BasePoint,
     /// 旋转夹点 - 用于旋转操作
@@ -92,10 +94,13 @@ pub fn get_grips_for_geometry(geometry: &Geometry) -> Vec<Grip> {
         Geometry::Polyline(polyline) => get_polyline_grips(polyline),
         Geometry::Ellipse(ellipse) => get_ellipse_grips(ellipse),
         Geometry::Spline(spline) => get_spline_grips(spline),
-        Geometry::Text(_) => vec![], // 文本使用单独的编辑方式
+        Geometry::Text(text) => get_text_grips(text),
+        Geometry::MText(_) => vec![], // 多行文字使用单独的编辑方式
         Geometry::Dimension(_) => vec![], // 标注使用单独的编辑方式
         Geometry::Hatch(_) => vec![], // 填充使用边界编辑
         Geometry::Leader(leader) => get_leader_grips(leader),
+        Geometry::Tolerance(tolerance) => get_tolerance_grips(tolerance),
+        Geometry::Insert(reference) => vec![Grip::new(GripType::BasePoint, reference.insertion_point, 0)],
     }
 }
 
@@ -199,12 +204,30 @@ fn get_ellipse_grips(ellipse: &crate::geometry::Ellipse) -> Vec<Grip> {
 }
 
 /// 获取样条曲线的夹点
+///
+/// 拟合点样条显示拟合点；控制点样条显示控制点，NURBS 另有权重夹点（缩放夹点，附带当前权重）。
 fn get_spline_grips(spline: &crate::geometry::Spline) -> Vec<Grip> {
-    spline.control_points
+    if spline.is_fit_spline() {
+        return spline.fit_points
+            .iter()
+            .enumerate()
+            .map(|(i, pt)| Grip::new(GripType::FitPoint, *pt, i))
+            .collect();
+    }
+
+    let mut grips: Vec<Grip> = spline.control_points
         .iter()
         .enumerate()
         .map(|(i, pt)| Grip::new(GripType::ControlPoint, *pt, i))
-        .collect()
+        .collect();
+    if spline.spline_type == crate::geometry::SplineType::Nurbs {
+        for i in 0..spline.control_points.len() {
+            if let Some(pt) = spline.weight_grip_point(i) {
+                grips.push(Grip::with_data(GripType::Scale, pt, i, GripData::Scale(spline.weight(i))));
+            }
+        }
+    }
+    grips
 }
 
 /// 获取引线的夹点
@@ -216,6 +239,25 @@ fn get_leader_grips(leader: &crate::geometry::Leader) -> Vec<Grip> {
         .collect()
 }
 
+/// 单行文字：对齐点为基点夹点，两点对齐方式的基线终点为端点夹点
+fn get_text_grips(text: &crate::geometry::Text) -> Vec<Grip> {
+    let mut grips = vec![Grip::new(GripType::BasePoint, text.position, 0)];
+    if let Some(end) = text.end_point.filter(|_| text.alignment.is_two_point()) {
+        grips.push(Grip::new(GripType::Endpoint, end, 1));
+    }
+    grips
+}
+
+/// 形位公差：插入点为基点夹点，引线除连接框格的末端外每个顶点一个夹点
+fn get_tolerance_grips(tolerance: &crate::gdt::Tolerance) -> Vec<Grip> {
+    let mut grips = vec![Grip::new(GripType::BasePoint, tolerance.position, 0)];
+    if let Some(leader) = &tolerance.leader {
+        let count = leader.vertices.len().saturating_sub(1);
+        grips.extend(get_leader_grips(leader).into_iter().take(count));
+    }
+    grips
+}
+
 /// 通过移动夹点来更新几何体
 /// 
 /// 返回更新后的几何体副本，如果更新失败则返回 None
@@ -235,10 +277,62 @@ pub fn update_geometry_by_grip(
         Geometry::Ellipse(ellipse) => update_ellipse_by_grip(ellipse, grip, new_position),
         Geometry::Spline(spline) => update_spline_by_grip(spline, grip, new_position),
         Geometry::Leader(leader) => update_leader_by_grip(leader, grip, new_position),
+        Geometry::Text(text) => update_text_by_grip(text, grip, new_position),
+        Geometry::Tolerance(tolerance) => update_tolerance_by_grip(tolerance, grip, new_position),
+        Geometry::Insert(reference) => update_insert_by_grip(reference, grip, new_position),
         _ => None,
     }
 }
 
+/// 移动对齐点平移文字（两点对齐方式只移动基线起点），移动端点夹点改变基线终点
+fn update_text_by_grip(text: &crate::geometry::Text, grip: &Grip, new_pos: Point2) -> Option<Geometry> {
+    let mut new_text = text.clone();
+    match grip.grip_type {
+        GripType::BasePoint => {
+            if !text.alignment.is_two_point() {
+                if let Some(end) = new_text.end_point.as_mut() {
+                    *end += new_pos - text.position;
+                }
+            }
+            new_text.position = new_pos;
+        }
+        GripType::Endpoint if text.alignment.is_two_point() => new_text.end_point = Some(new_pos),
+        _ => return None,
+    }
+    Some(Geometry::Text(new_text))
+}
+
+/// 移动插入点时引线末端随框格移动
+fn update_tolerance_by_grip(tolerance: &crate::gdt::Tolerance, grip: &Grip, new_pos: Point2) -> Option<Geometry> {
+    let mut new_tolerance = tolerance.clone();
+    match grip.grip_type {
+        GripType::BasePoint => {
+            new_tolerance.position = new_pos;
+            if let Some(last) = new_tolerance.leader.as_mut().and_then(|l| l.vertices.last_mut()) {
+                *last = new_pos;
+            }
+        }
+        GripType::Endpoint => {
+            let leader = new_tolerance.leader.as_mut()?;
+            if grip.index + 1 >= leader.vertices.len() {
+                return None;
+            }
+            leader.vertices[grip.index] = new_pos;
+        }
+        _ => return None,
+    }
+    Some(Geometry::Tolerance(new_tolerance))
+}
+
+fn update_insert_by_grip(reference: &crate::block::BlockReference, grip: &Grip, new_pos: Point2) -> Option<Geometry> {
+    if grip.grip_type != GripType::BasePoint {
+        return None;
+    }
+    let mut new_reference = reference.clone();
+    new_reference.insertion_point = new_pos;
+    Some(Geometry::Insert(new_reference))
+}
+
 fn update_line_by_grip(line: &crate::geometry::Line, grip: &Grip, new_pos: Point2) -> Option<Geometry> {
     let mut new_line = line.clone();
     match grip.grip_type {
@@ -365,13 +459,22 @@ fn update_ellipse_by_grip(ellipse: &crate::geometry::Ellipse, grip: &Grip, new_p
 }
 
 fn update_spline_by_grip(spline: &crate::geometry::Spline, grip: &Grip, new_pos: Point2) -> Option<Geometry> {
-    if grip.grip_type == GripType::ControlPoint && grip.index < spline.control_points.len() {
-        let mut new_spline = spline.clone();
-        new_spline.control_points[grip.index] = new_pos;
-        Some(Geometry::Spline(new_spline))
-    } else {
-        None
+    let mut new_spline = spline.clone();
+    match grip.grip_type {
+        GripType::FitPoint if grip.index < spline.fit_points.len() => {
+            new_spline.fit_points[grip.index] = new_pos;
+            new_spline.refit();
+        }
+        GripType::ControlPoint if grip.index < spline.control_points.len() => {
+            new_spline.control_points[grip.index] = new_pos;
+        }
+        GripType::Scale => {
+            let weight = spline.weight_from_grip(grip.index, new_pos)?;
+            new_spline.set_weight(grip.index, weight);
+        }
+        _ => return None,
     }
+    Some(Geometry::Spline(new_spline))
 }
 
 fn update_leader_by_grip(leader: &crate::geometry::Leader, grip: &Grip, new_pos: Point2) -> Option<Geometry> {
@@ -0,0 +1,473 @@
+//! 填充图案
+//!
+//! 解析标准 `.pat` 图案文件（与 AutoCAD 的 acad.pat/acadiso.pat 格式相同），
+//! 并按填充的角度和比例生成图案线，裁剪到填充边界内。
+//!
+//! 裁剪使用奇偶规则，内边界（孔洞）自然形成孤岛。虚线中长度为 0 的项表示点，
+//! 生成为起点与终点重合的线段。
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::geometry::{Hatch, HatchPatternLine, HatchPatternType};
+use crate::math::{BoundingBox2, Point2, Vector2, EPSILON};
+use crate::tessellation;
+
+/// 内置图案（公制）
+const BUILTIN_PATTERNS: &str = include_str!("../patterns/zcadiso.pat");
+
+/// 单个图案线族最多生成的平行线数，超过时认为比例过小，跳过该线族
+const MAX_FAMILY_LINES: i64 = 20_000;
+
+/// 单个填充最多生成的线段数
+const MAX_SEGMENTS: usize = 500_000;
+
+/// 图案文件解析错误
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum PatternError {
+    #[error("Line {line}: {message}")]
+    Parse { line: usize, message: String },
+
+    #[error("IO error: {0}")]
+    Io(String),
+}
+
+/// 填充图案定义
+#[derive(Debug, Clone)]
+pub struct HatchPattern {
+    /// 图案名称
+    pub name: String,
+    /// 说明
+    pub description: String,
+    /// 图案线族
+    pub lines: Vec<HatchPatternLine>,
+}
+
+/// 图案库（名称不区分大小写）
+#[derive(Debug, Clone, Default)]
+pub struct PatternLibrary {
+    patterns: HashMap<String, HatchPattern>,
+}
+
+impl PatternLibrary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 内置图案库
+    pub fn builtin() -> &'static PatternLibrary {
+        static BUILTIN: OnceLock<PatternLibrary> = OnceLock::new();
+        BUILTIN.get_or_init(|| Self::parse(BUILTIN_PATTERNS).expect("内置图案文件格式错误"))
+    }
+
+    /// 解析 `.pat` 文件内容
+    ///
+    /// 每个图案以 `*名称, 说明` 开头，后续每行描述一个线族：
+    /// `角度, 基点x, 基点y, 偏移dx, 偏移dy [, 虚线长度...]`。`;` 之后为注释。
+    pub fn parse(source: &str) -> Result<Self, PatternError> {
+        let mut library = Self::new();
+        let mut current: Option<HatchPattern> = None;
+
+        for (index, raw) in source.lines().enumerate() {
+            let line_no = index + 1;
+            let line = raw.split(';').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(header) = line.strip_prefix('*') {
+                if let Some(pattern) = current.take() {
+                    library.add(pattern);
+                }
+                let (name, description) = header.split_once(',').unwrap_or((header, ""));
+                let name = name.trim();
+                if name.is_empty() {
+                    return Err(PatternError::Parse {
+                        line: line_no,
+                        message: "missing pattern name".to_string(),
+                    });
+                }
+                current = Some(HatchPattern {
+                    name: name.to_string(),
+                    description: description.trim().to_string(),
+                    lines: Vec::new(),
+                });
+                continue;
+            }
+
+            let Some(pattern) = current.as_mut() else {
+                return Err(PatternError::Parse {
+                    line: line_no,
+                    message: "line definition outside of a pattern".to_string(),
+                });
+            };
+            pattern.lines.push(parse_line_definition(line, line_no)?);
+        }
+
+        if let Some(pattern) = current.take() {
+            library.add(pattern);
+        }
+        Ok(library)
+    }
+
+    /// 从文件加载图案库
+    pub fn load_file(path: &Path) -> Result<Self, PatternError> {
+        let source = std::fs::read_to_string(path).map_err(|e| PatternError::Io(e.to_string()))?;
+        Self::parse(&source)
+    }
+
+    /// 添加图案（同名图案会被替换）
+    pub fn add(&mut self, pattern: HatchPattern) {
+        self.patterns.insert(pattern.name.to_uppercase(), pattern);
+    }
+
+    /// 合并另一个图案库
+    pub fn merge(&mut self, other: PatternLibrary) {
+        self.patterns.extend(other.patterns);
+    }
+
+    /// 按名称查找图案
+    pub fn get(&self, name: &str) -> Option<&HatchPattern> {
+        self.patterns.get(&name.to_uppercase())
+    }
+
+    /// 所有图案名称（排序）
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.patterns.values().map(|p| p.name.as_str()).collect();
+        names.sort_unstable();
+        names
+    }
+
+    pub fn len(&self) -> usize {
+        self.patterns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// 获取填充使用的图案线族，实心填充或图案不存在时返回 None
+    pub fn pattern_lines<'a>(&'a self, hatch: &'a Hatch) -> Option<&'a [HatchPatternLine]> {
+        match &hatch.pattern_type {
+            HatchPatternType::Solid => None,
+            HatchPatternType::Predefined(name) => self.get(name).map(|p| p.lines.as_slice()),
+            HatchPatternType::Custom { lines } => Some(lines.as_slice()),
+        }
+    }
+}
+
+fn parse_line_definition(line: &str, line_no: usize) -> Result<HatchPatternLine, PatternError> {
+    let values = line
+        .split(',')
+        .map(|field| {
+            field.trim().parse::<f64>().map_err(|_| PatternError::Parse {
+                line: line_no,
+                message: format!("invalid number '{}'", field.trim()),
+            })
+        })
+        .collect::<Result<Vec<f64>, _>>()?;
+
+    if values.len() < 5 {
+        return Err(PatternError::Parse {
+            line: line_no,
+            message: "expected angle, x-origin, y-origin, delta-x, delta-y".to_string(),
+        });
+    }
+
+    Ok(HatchPatternLine {
+        angle: values[0].to_radians(),
+        base_point: Point2::new(values[1], values[2]),
+        offset: Vector2::new(values[3], values[4]),
+        dash_pattern: values[5..].to_vec(),
+    })
+}
+
+/// 使用内置图案库生成图案线段
+pub fn pattern_segments(hatch: &Hatch) -> Vec<[Point2; 2]> {
+    pattern_segments_with(hatch, PatternLibrary::builtin())
+}
+
+/// 生成填充的图案线段（世界坐标）
+///
+/// 实心填充、未知图案或没有有效边界时返回空列表
+pub fn pattern_segments_with(hatch: &Hatch, library: &PatternLibrary) -> Vec<[Point2; 2]> {
+    let Some(lines) = library.pattern_lines(hatch) else {
+        return Vec::new();
+    };
+    if hatch.scale.abs() < EPSILON {
+        return Vec::new();
+    }
+
+    let loops = tessellation::hatch_loops(hatch, tessellation::DEFAULT_TOLERANCE);
+    let edges: Vec<(Point2, Point2)> = loops
+        .iter()
+        .flat_map(|ring| ring.windows(2).map(|w| (w[0], w[1])))
+        .collect();
+    if edges.is_empty() {
+        return Vec::new();
+    }
+    let bbox = BoundingBox2::from_points(loops.iter().flatten().copied());
+
+    let mut segments = Vec::new();
+    for line in lines {
+        let family = PatternFamily::new(line, hatch);
+        family.generate(&edges, &bbox, &mut segments);
+        if segments.len() >= MAX_SEGMENTS {
+            segments.truncate(MAX_SEGMENTS);
+            break;
+        }
+    }
+    segments
+}
+
+/// 将图案线定义按填充的角度和比例放置到世界坐标
+///
+/// 基点在图案坐标系中，偏移在线自身的坐标系中；返回的线族中角度、基点、偏移
+/// 均为世界坐标，虚线长度已缩放（与 DXF HATCH 实体中图案线的存储方式相同）。
+pub fn place_line(line: &HatchPatternLine, hatch: &Hatch) -> HatchPatternLine {
+    let (sin, cos) = hatch.angle.sin_cos();
+    let base = line.base_point.coords;
+    let angle = hatch.angle + line.angle;
+    let direction = Vector2::new(angle.cos(), angle.sin());
+    let normal = Vector2::new(-direction.y, direction.x);
+
+    HatchPatternLine {
+        angle,
+        base_point: Point2::new(base.x * cos - base.y * sin, base.x * sin + base.y * cos) * hatch.scale,
+        offset: (direction * line.offset.x + normal * line.offset.y) * hatch.scale,
+        dash_pattern: line.dash_pattern.iter().map(|d| d * hatch.scale).collect(),
+    }
+}
+
+/// 世界坐标下的一个图案线族
+struct PatternFamily {
+    /// 第 0 条线的起点（虚线相位从这里开始）
+    origin: Point2,
+    /// 线方向
+    direction: Vector2,
+    /// 线的法向
+    normal: Vector2,
+    /// 相邻两条线之间的偏移
+    offset: Vector2,
+    /// 虚线长度（已缩放）
+    dashes: Vec<f64>,
+}
+
+impl PatternFamily {
+    fn new(line: &HatchPatternLine, hatch: &Hatch) -> Self {
+        let placed = place_line(line, hatch);
+        let direction = Vector2::new(placed.angle.cos(), placed.angle.sin());
+        Self {
+            origin: placed.base_point,
+            direction,
+            normal: Vector2::new(-direction.y, direction.x),
+            offset: placed.offset,
+            dashes: placed.dash_pattern,
+        }
+    }
+
+    fn generate(&self, edges: &[(Point2, Point2)], bbox: &BoundingBox2, segments: &mut Vec<[Point2; 2]>) {
+        let spacing = self.offset.dot(&self.normal);
+        if spacing.abs() < EPSILON {
+            return;
+        }
+
+        // 覆盖包围盒所需的线序号范围
+        let corners = [
+            bbox.min,
+            Point2::new(bbox.max.x, bbox.min.y),
+            bbox.max,
+            Point2::new(bbox.min.x, bbox.max.y),
+        ];
+        let (lo, hi) = corners.iter().fold((f64::MAX, f64::MIN), |(lo, hi), c| {
+            let k = (c - self.origin).dot(&self.normal) / spacing;
+            (lo.min(k), hi.max(k))
+        });
+        let (first, last) = (lo.floor() as i64, hi.ceil() as i64);
+        if last - first > MAX_FAMILY_LINES {
+            return;
+        }
+
+        let mut crossings = Vec::new();
+        for k in first..=last {
+            let start = self.origin + self.offset * k as f64;
+            crossings.clear();
+            for (a, b) in edges {
+                let da = (a - start).dot(&self.normal);
+                let db = (b - start).dot(&self.normal);
+                // 半开区间判断，保证线恰好经过顶点时只计一次
+                if (da > 0.0) != (db > 0.0) {
+                    let hit = a + (b - a) * (da / (da - db));
+                    crossings.push((hit - start).dot(&self.direction));
+                }
+            }
+            crossings.sort_by(f64::total_cmp);
+
+            for span in crossings.chunks_exact(2) {
+                self.emit_dashes(start, span[0], span[1], segments);
+                if segments.len() >= MAX_SEGMENTS {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// 在线上的参数区间 [t0, t1] 内按虚线模式输出线段
+    fn emit_dashes(&self, start: Point2, t0: f64, t1: f64, segments: &mut Vec<[Point2; 2]>) {
+        let at = |t: f64| start + self.direction * t;
+        let period: f64 = self.dashes.iter().map(|d| d.abs()).sum();
+        if self.dashes.is_empty() || period < EPSILON {
+            segments.push([at(t0), at(t1)]);
+            return;
+        }
+
+        let mut position = (t0 / period).floor() * period;
+        while position < t1 {
+            for dash in &self.dashes {
+                let end = position + dash.abs();
+                if *dash == 0.0 {
+                    if position >= t0 && position <= t1 {
+                        segments.push([at(position), at(position)]);
+                    }
+                } else if *dash > 0.0 && end > t0 && position < t1 {
+                    segments.push([at(position.max(t0)), at(end.min(t1))]);
+                }
+                position = end;
+            }
+            if segments.len() >= MAX_SEGMENTS {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::{HatchBoundary, HatchBoundaryElement, Line};
+
+    fn square(min: f64, max: f64, is_outer: bool) -> HatchBoundary {
+        let corners = [
+            Point2::new(min, min),
+            Point2::new(max, min),
+            Point2::new(max, max),
+            Point2::new(min, max),
+        ];
+        HatchBoundary::new(
+            (0..4)
+                .map(|i| HatchBoundaryElement::Line(Line::new(corners[i], corners[(i + 1) % 4])))
+                .collect(),
+            is_outer,
+        )
+    }
+
+    fn inside(p: &Point2, min: f64, max: f64) -> bool {
+        p.x >= min - 1e-9 && p.x <= max + 1e-9 && p.y >= min - 1e-9 && p.y <= max + 1e-9
+    }
+
+    #[test]
+    fn test_parse_pat_file() {
+        let library = PatternLibrary::parse(
+            ";; comment\n*MYPAT, My pattern\n0, 0,0, 0,10\n90, 5,0, 0,10, 4,-2, 0,-2 ; dashed\n\n*Other\n45, 0,0, 0,1\n",
+        )
+        .unwrap();
+        assert_eq!(library.names(), vec!["MYPAT", "Other"]);
+
+        let pattern = library.get("mypat").unwrap();
+        assert_eq!(pattern.description, "My pattern");
+        assert_eq!(pattern.lines.len(), 2);
+        assert!((pattern.lines[1].angle - std::f64::consts::FRAC_PI_2).abs() < 1e-12);
+        assert_eq!(pattern.lines[1].base_point, Point2::new(5.0, 0.0));
+        assert_eq!(pattern.lines[1].dash_pattern, vec![4.0, -2.0, 0.0, -2.0]);
+
+        assert!(matches!(
+            PatternLibrary::parse("0, 0,0, 0,1"),
+            Err(PatternError::Parse { line: 1, .. })
+        ));
+        assert!(matches!(
+            PatternLibrary::parse("*BAD\n0, 0, x, 0, 1"),
+            Err(PatternError::Parse { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_builtin_library() {
+        let library = PatternLibrary::builtin();
+        for name in ["ANSI31", "ANSI37", "AR-CONC", "BRICK", "DOTS", "HONEY", "NET"] {
+            assert!(library.get(name).is_some(), "missing {name}");
+        }
+        let ansi31 = library.get("ansi31").unwrap();
+        assert!((ansi31.lines[0].offset.y - 3.175).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_lines_clipped_with_island() {
+        // 100×100 外边界，中间 40×40 孔洞；水平线间距 10
+        let hatch = Hatch {
+            boundaries: vec![square(0.0, 100.0, true), square(30.0, 70.0, false)],
+            pattern_type: HatchPatternType::Custom {
+                lines: vec![HatchPatternLine {
+                    angle: 0.0,
+                    base_point: Point2::new(0.0, 5.0),
+                    offset: Vector2::new(0.0, 10.0),
+                    dash_pattern: Vec::new(),
+                }],
+            },
+            angle: 0.0,
+            scale: 1.0,
+        };
+        let segments = pattern_segments(&hatch);
+
+        // y = 5..95 共 10 条线，其中 y = 35..65 的 4 条被孔洞分成两段
+        assert_eq!(segments.len(), 14);
+        for [a, b] in &segments {
+            assert!(inside(a, 0.0, 100.0) && inside(b, 0.0, 100.0));
+            let mid = Point2::new((a.x + b.x) / 2.0, a.y);
+            assert!(!(mid.x > 30.0 && mid.x < 70.0 && mid.y > 30.0 && mid.y < 70.0));
+        }
+        let total: f64 = segments.iter().map(|[a, b]| (b - a).norm()).sum();
+        assert!((total - (10.0 * 100.0 - 4.0 * 40.0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_angle_scale_and_dashes() {
+        let boundary = vec![square(0.0, 50.0, true)];
+
+        // ANSI31 旋转 45° 后为水平线，比例 2 时间距 6.35
+        let hatch = Hatch::pattern(boundary.clone(), "ANSI31", -std::f64::consts::FRAC_PI_4, 2.0);
+        let segments = pattern_segments(&hatch);
+        assert_eq!(segments.len(), (50.0f64 / 6.35).ceil() as usize);
+        for [a, b] in &segments {
+            assert!((a.y - b.y).abs() < 1e-9);
+            assert!((a.x.min(b.x)).abs() < 1e-9 && (a.x.max(b.x) - 50.0).abs() < 1e-9);
+        }
+
+        // 虚线：4 实 2 空，点（长度 0）生成重合的端点
+        let hatch = Hatch {
+            boundaries: boundary,
+            pattern_type: HatchPatternType::Custom {
+                lines: vec![HatchPatternLine {
+                    angle: 0.0,
+                    base_point: Point2::new(0.0, 25.0),
+                    offset: Vector2::new(0.0, 100.0),
+                    dash_pattern: vec![4.0, -2.0, 0.0, -2.0],
+                }],
+            },
+            angle: 0.0,
+            scale: 1.0,
+        };
+        let segments = pattern_segments(&hatch);
+        let dashes = segments.iter().filter(|[a, b]| (b - a).norm() > 1e-9).count();
+        let dots = segments.len() - dashes;
+        assert_eq!((dashes, dots), (7, 6));
+        assert!(segments.iter().all(|[a, b]| a.x >= -1e-9 && b.x <= 50.0 + 1e-9));
+    }
+
+    #[test]
+    fn test_solid_and_unknown_patterns_generate_nothing() {
+        let boundary = vec![square(0.0, 10.0, true)];
+        assert!(pattern_segments(&Hatch::solid(boundary.clone())).is_empty());
+        assert!(pattern_segments(&Hatch::pattern(boundary, "NO-SUCH-PATTERN", 0.0, 1.0)).is_empty());
+    }
+}
@@ -6,8 +6,13 @@
 //! - 分支历史
 //! - 操作依赖管理
 //! - 历史压缩和优化
+//!
+//! 文档编辑以 [`OperationType::ChangeSet`] 记录可逆变更集（见 [`crate::transaction`]），
+//! 撤销/重做通过 [`OperationType::undo`] / [`OperationType::redo`] 作用于 [`EditTarget`]。
 
 use crate::entity::{Entity, EntityId};
+use crate::transaction::{ChangeSet, EditTarget};
+use crate::transform::Transform2D;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -111,6 +116,119 @@ pub enum OperationType {
         name: String,
         data: Vec<u8>,
     },
+
+    /// 可逆变更集（事务提交的结果）
+    ChangeSet {
+        changes: ChangeSet,
+    },
+}
+
+impl OperationType {
+    /// 在目标上撤销该操作
+    ///
+    /// 约束和变量操作不作用于文档，由约束系统自行处理。
+    pub fn undo<T: EditTarget + ?Sized>(&self, target: &mut T) {
+        match self {
+            OperationType::CreateEntity { entity } => {
+                target.take_entity(&entity.id);
+            }
+            OperationType::DeleteEntity { previous_entity, .. } => {
+                if let Some(entity) = previous_entity {
+                    target.insert_entity(entity.clone());
+                }
+            }
+            OperationType::ModifyEntity { entity_id, previous_geometry, .. } => {
+                set_geometry(target, entity_id, previous_geometry);
+            }
+            OperationType::MoveEntities { entity_ids, offset, .. } => {
+                transform_entities(target, entity_ids, &Transform2D::translation(-offset.x, -offset.y));
+            }
+            OperationType::RotateEntities { entity_ids, center, angle, .. } => {
+                transform_entities(target, entity_ids, &Transform2D::rotation_around(*center, -angle));
+            }
+            OperationType::ScaleEntities { entity_ids, center, scale, .. } => {
+                if scale.abs() > f64::EPSILON {
+                    transform_entities(target, entity_ids, &Transform2D::scale_around(*center, 1.0 / scale, 1.0 / scale));
+                }
+            }
+            OperationType::BooleanOperation { result_entities, previous_entities, .. } => {
+                for entity in result_entities {
+                    target.take_entity(&entity.id);
+                }
+                for entity in previous_entities {
+                    target.insert_entity(entity.clone());
+                }
+            }
+            OperationType::GroupOperation { operations, .. } => {
+                for op in operations.iter().rev() {
+                    op.operation_type.undo(target);
+                }
+            }
+            OperationType::ChangeSet { changes } => changes.undo(target),
+            OperationType::AddConstraint { .. }
+            | OperationType::RemoveConstraint { .. }
+            | OperationType::ModifyVariable { .. }
+            | OperationType::Custom { .. } => {}
+        }
+    }
+
+    /// 在目标上重做该操作
+    pub fn redo<T: EditTarget + ?Sized>(&self, target: &mut T) {
+        match self {
+            OperationType::CreateEntity { entity } => target.insert_entity(entity.clone()),
+            OperationType::DeleteEntity { entity_id, .. } => {
+                target.take_entity(entity_id);
+            }
+            OperationType::ModifyEntity { entity_id, new_geometry, .. } => {
+                set_geometry(target, entity_id, new_geometry);
+            }
+            OperationType::MoveEntities { entity_ids, offset, .. } => {
+                transform_entities(target, entity_ids, &Transform2D::translation(offset.x, offset.y));
+            }
+            OperationType::RotateEntities { entity_ids, center, angle, .. } => {
+                transform_entities(target, entity_ids, &Transform2D::rotation_around(*center, *angle));
+            }
+            OperationType::ScaleEntities { entity_ids, center, scale, .. } => {
+                transform_entities(target, entity_ids, &Transform2D::scale_around(*center, *scale, *scale));
+            }
+            OperationType::BooleanOperation { result_entities, previous_entities, .. } => {
+                for entity in previous_entities {
+                    target.take_entity(&entity.id);
+                }
+                for entity in result_entities {
+                    target.insert_entity(entity.clone());
+                }
+            }
+            OperationType::GroupOperation { operations, .. } => {
+                for op in operations {
+                    op.operation_type.redo(target);
+                }
+            }
+            OperationType::ChangeSet { changes } => changes.redo(target),
+            OperationType::AddConstraint { .. }
+            | OperationType::RemoveConstraint { .. }
+            | OperationType::ModifyVariable { .. }
+            | OperationType::Custom { .. } => {}
+        }
+    }
+}
+
+fn set_geometry<T: EditTarget + ?Sized>(target: &mut T, id: &EntityId, geometry: &crate::geometry::Geometry) {
+    if let Some(entity) = target.entity(id) {
+        let mut entity = entity.clone();
+        entity.geometry = geometry.clone();
+        target.replace_entity(entity);
+    }
+}
+
+fn transform_entities<T: EditTarget + ?Sized>(target: &mut T, ids: &[EntityId], transform: &Transform2D) {
+    for id in ids {
+        if let Some(entity) = target.entity(id) {
+            let mut entity = entity.clone();
+            entity.geometry = entity.geometry.transform(transform);
+            target.replace_entity(entity);
+        }
+    }
 }
 
 /// 操作
@@ -646,6 +764,13 @@ pub mod operations {
         )
     }
 
+    /// 变更集操作（描述和受影响实体取自变更集）
+    pub fn change_set(changes: ChangeSet) -> Operation {
+        let description = changes.description.clone();
+        let affected = changes.affected_entities();
+        Operation::new(OperationType::ChangeSet { changes }, description).with_affected_entities(affected)
+    }
+
     /// 分组操作
     pub fn group_operation(name: impl Into<String>, operations: Vec<Operation>, description: impl Into<String>) -> Operation {
         Operation::new(
@@ -0,0 +1,772 @@
+//! 曲线求交
+//!
+//! 为任意两个 [`Geometry`] 计算全部交点，并给出交点在两条曲线上的参数。
+//! 对象捕捉、修剪、延伸等工具共用这里的实现，保证行为一致。
+//!
+//! 各几何类型的参数定义：
+//! - 线段：`t ∈ [0, 1]`，从起点到终点
+//! - 圆：角度（弧度），`[0, 2π)`
+//! - 圆弧：角度（弧度），`[start_angle, start_angle + sweep]`
+//! - 椭圆：椭圆参数（与 [`Ellipse::point_at_param`] 一致）
+//! - 样条：节点参数（与 [`Spline::point_at_param`] 一致）
+//! - 多段线、引线：`i + t`，`i` 为线段序号，`t ∈ [0, 1]` 为段内位置（圆弧段按角度比例）
+//! - 填充：各边界元素依次编号，`i + t` 含义同上
+//! - 标注：标注线与尺寸界线依次编号，`i + t` 含义同上
+//!
+//! 直线、圆、圆弧以及直线与椭圆之间使用解析解；
+//! 涉及样条或椭圆与其他曲线时先用弦线段求初值，再用牛顿迭代精化。
+
+use crate::curve::PolylineSegment;
+use crate::geometry::{
+    Dimension, DimensionType, Ellipse, Geometry, HatchBoundaryElement, Line, Polyline, Spline,
+};
+use crate::math::{Point2, Vector2, EPSILON};
+use std::f64::consts::TAU;
+
+/// 判断交点落在曲线范围内的距离容差
+const TOLERANCE: f64 = 1e-9;
+
+/// 椭圆/样条求初值时的弦线段数量
+const CURVE_SAMPLES: usize = 128;
+
+/// 牛顿迭代最大次数
+const MAX_NEWTON_ITERATIONS: usize = 32;
+
+/// 交点
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Intersection {
+    /// 交点坐标
+    pub point: Point2,
+    /// 交点在第一个几何体上的参数
+    pub param1: f64,
+    /// 交点在第二个几何体上的参数
+    pub param2: f64,
+}
+
+impl Intersection {
+    /// 交换两个几何体的参数
+    pub fn swapped(self) -> Self {
+        Self {
+            point: self.point,
+            param1: self.param2,
+            param2: self.param1,
+        }
+    }
+}
+
+/// 计算两个几何体的全部交点
+///
+/// 结果按第一个几何体上的参数升序排列，重合的交点只保留一个。
+pub fn intersect(geom1: &Geometry, geom2: &Geometry) -> Vec<Intersection> {
+    let pieces1 = pieces(geom1);
+    let pieces2 = pieces(geom2);
+
+    let mut result: Vec<Intersection> = Vec::new();
+    for p1 in &pieces1 {
+        for p2 in &pieces2 {
+            for (t1, t2) in intersect_primitives(&p1.primitive, &p2.primitive) {
+                let point = p1.primitive.point_at(t1);
+                let hit = Intersection {
+                    point,
+                    param1: p1.global_param(t1),
+                    param2: p2.global_param(t2),
+                };
+                let scale = 1.0 + point.coords.norm();
+                if !result
+                    .iter()
+                    .any(|r| (r.point - point).norm() <= TOLERANCE * scale * 100.0)
+                {
+                    result.push(hit);
+                }
+            }
+        }
+    }
+
+    result.sort_by(|a, b| a.param1.total_cmp(&b.param1));
+    result
+}
+
+/// 计算两个几何体的全部交点坐标
+pub fn intersection_points(geom1: &Geometry, geom2: &Geometry) -> Vec<Point2> {
+    intersect(geom1, geom2).into_iter().map(|i| i.point).collect()
+}
+
+// ========== 基本曲线 ==========
+
+/// 参与求交的基本曲线
+#[derive(Debug, Clone)]
+enum Primitive {
+    /// 线段，参数 t ∈ [0, 1]
+    Line { start: Point2, end: Point2 },
+    /// 圆弧，参数为角度，范围 [start_angle, start_angle + sweep]（sweep 为负表示顺时针）
+    Arc {
+        center: Point2,
+        radius: f64,
+        start_angle: f64,
+        sweep: f64,
+    },
+    /// 椭圆（弧），参数为椭圆参数
+    Ellipse(Ellipse),
+    /// 样条，参数为节点参数
+    Spline(Spline),
+}
+
+impl Primitive {
+    /// 参数范围（按曲线走向，起点参数在前）
+    fn range(&self) -> (f64, f64) {
+        match self {
+            Primitive::Line { .. } => (0.0, 1.0),
+            Primitive::Arc {
+                start_angle, sweep, ..
+            } => (*start_angle, start_angle + sweep),
+            Primitive::Ellipse(e) => (e.start_param, e.end_param),
+            Primitive::Spline(s) => s.param_range(),
+        }
+    }
+
+    fn point_at(&self, t: f64) -> Point2 {
+        match self {
+            Primitive::Line { start, end } => start + (end - start) * t,
+            Primitive::Arc { center, radius, .. } => {
+                Point2::new(center.x + radius * t.cos(), center.y + radius * t.sin())
+            }
+            Primitive::Ellipse(e) => e.point_at_param(t),
+            Primitive::Spline(s) => s.point_at_param(t),
+        }
+    }
+
+    fn derivative(&self, t: f64) -> Vector2 {
+        match self {
+            Primitive::Line { start, end } => end - start,
+            Primitive::Arc { radius, .. } => Vector2::new(-radius * t.sin(), radius * t.cos()),
+            Primitive::Ellipse(e) => {
+                let major_dir = e.major_axis / e.major_radius();
+                let minor_dir = e.minor_axis_direction();
+                major_dir * (-e.major_radius() * t.sin()) + minor_dir * (e.minor_radius() * t.cos())
+            }
+            Primitive::Spline(s) => {
+                // 数值微分
+                let (lo, hi) = s.param_range();
+                let h = ((hi - lo) * 1e-6).max(EPSILON);
+                let t0 = (t - h).max(lo);
+                let t1 = (t + h).min(hi);
+                if t1 - t0 < EPSILON {
+                    return Vector2::zeros();
+                }
+                (s.point_at_param(t1) - s.point_at_param(t0)) / (t1 - t0)
+            }
+        }
+    }
+
+    fn is_analytic(&self) -> bool {
+        matches!(self, Primitive::Line { .. } | Primitive::Arc { .. })
+    }
+
+    /// 检查参数是否在范围内（端点附近按距离判断），返回修正后的参数
+    fn accept(&self, t: f64) -> Option<f64> {
+        let (a, b) = self.range();
+        let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+        if t >= lo && t <= hi {
+            return Some(t);
+        }
+        let p = self.point_at(t);
+        let tol = TOLERANCE * (1.0 + p.coords.norm());
+        if (p - self.point_at(a)).norm() <= tol {
+            Some(a)
+        } else if (p - self.point_at(b)).norm() <= tol {
+            Some(b)
+        } else {
+            None
+        }
+    }
+
+    /// 将点所在的角度映射到圆弧参数范围内
+    fn arc_param(&self, point: &Point2) -> Option<f64> {
+        let Primitive::Arc {
+            center,
+            start_angle,
+            sweep,
+            ..
+        } = self
+        else {
+            return None;
+        };
+        let angle = (point.y - center.y).atan2(point.x - center.x);
+        let delta = if *sweep >= 0.0 {
+            (angle - start_angle).rem_euclid(TAU)
+        } else {
+            -(start_angle - angle).rem_euclid(TAU)
+        };
+        let t = start_angle + delta;
+        self.accept(t).or_else(|| {
+            // 起点附近的角度可能被归一化到另一侧
+            let wrapped = if *sweep >= 0.0 { t - TAU } else { t + TAU };
+            self.accept(wrapped)
+        })
+    }
+
+    /// 把曲线拆成弦线段，返回 (线段, 起点参数, 终点参数)
+    fn chords(&self) -> Vec<(Primitive, f64, f64)> {
+        if self.is_analytic() {
+            return vec![(self.clone(), 0.0, 1.0)];
+        }
+        let (a, b) = self.range();
+        let params: Vec<f64> = (0..=CURVE_SAMPLES)
+            .map(|i| a + (b - a) * i as f64 / CURVE_SAMPLES as f64)
+            .collect();
+        params
+            .windows(2)
+            .map(|w| {
+                (
+                    Primitive::Line {
+                        start: self.point_at(w[0]),
+                        end: self.point_at(w[1]),
+                    },
+                    w[0],
+                    w[1],
+                )
+            })
+            .collect()
+    }
+}
+
+/// 几何体分解后的一段基本曲线及其参数映射
+struct Piece {
+    primitive: Primitive,
+    /// 段序号（多段曲线时）
+    index: usize,
+    /// 是否把局部参数归一化为 `index + [0, 1]`
+    normalized: bool,
+}
+
+impl Piece {
+    fn whole(primitive: Primitive) -> Self {
+        Self {
+            primitive,
+            index: 0,
+            normalized: false,
+        }
+    }
+
+    fn segment(primitive: Primitive, index: usize) -> Self {
+        Self {
+            primitive,
+            index,
+            normalized: true,
+        }
+    }
+
+    fn global_param(&self, t: f64) -> f64 {
+        if !self.normalized {
+            return t;
+        }
+        let (a, b) = self.primitive.range();
+        let local = if (b - a).abs() < EPSILON {
+            0.0
+        } else {
+            (t - a) / (b - a)
+        };
+        self.index as f64 + local.clamp(0.0, 1.0)
+    }
+}
+
+/// 把几何体分解为基本曲线
+fn pieces(geometry: &Geometry) -> Vec<Piece> {
+    match geometry {
+        Geometry::Point(_) | Geometry::Text(_) | Geometry::MText(_) | Geometry::Insert(_) => Vec::new(),
+        Geometry::Line(line) => vec![Piece::whole(line_primitive(line))],
+        Geometry::Circle(circle) => vec![Piece::whole(Primitive::Arc {
+            center: circle.center,
+            radius: circle.radius,
+            start_angle: 0.0,
+            sweep: TAU,
+        })],
+        Geometry::Arc(arc) => vec![Piece::whole(Primitive::Arc {
+            center: arc.center,
+            radius: arc.radius,
+            start_angle: arc.start_angle,
+            sweep: arc.sweep_angle(),
+        })],
+        Geometry::Polyline(polyline) => polyline_pieces(polyline),
+        Geometry::Ellipse(ellipse) => vec![Piece::whole(Primitive::Ellipse(ellipse.clone()))],
+        Geometry::Spline(spline) => vec![Piece::whole(Primitive::Spline(spline.clone()))],
+        Geometry::Hatch(hatch) => hatch
+            .boundaries
+            .iter()
+            .flat_map(|b| b.elements.iter())
+            .enumerate()
+            .map(|(i, element)| {
+                let primitive = match element {
+                    HatchBoundaryElement::Line(line) => line_primitive(line),
+                    HatchBoundaryElement::Arc(arc) => Primitive::Arc {
+                        center: arc.center,
+                        radius: arc.radius,
+                        start_angle: arc.start_angle,
+                        sweep: arc.sweep_angle(),
+                    },
+                    HatchBoundaryElement::Ellipse(e) => Primitive::Ellipse(e.clone()),
+                    HatchBoundaryElement::Spline(s) => Primitive::Spline(s.clone()),
+                };
+                Piece::segment(primitive, i)
+            })
+            .collect(),
+        Geometry::Leader(leader) => leader
+            .vertices
+            .windows(2)
+            .enumerate()
+            .map(|(i, w)| Piece::segment(Primitive::Line { start: w[0], end: w[1] }, i))
+            .collect(),
+        Geometry::Tolerance(tolerance) => tolerance
+            .frame_lines()
+            .into_iter()
+            .chain(tolerance.leader.iter().flat_map(|l| l.vertices.windows(2).map(|w| Line::new(w[0], w[1]))))
+            .enumerate()
+            .map(|(i, line)| Piece::segment(line_primitive(&line), i))
+            .collect(),
+        Geometry::Dimension(dim) => dimension_lines(dim)
+            .iter()
+            .enumerate()
+            .map(|(i, line)| Piece::segment(line_primitive(line), i))
+            .collect(),
+    }
+}
+
+fn line_primitive(line: &Line) -> Primitive {
+    Primitive::Line {
+        start: line.start,
+        end: line.end,
+    }
+}
+
+fn polyline_pieces(polyline: &Polyline) -> Vec<Piece> {
+    polyline
+        .segments()
+        .enumerate()
+        .map(|(i, segment)| Piece::segment(segment_primitive(&segment), i))
+        .collect()
+}
+
+/// 多段线的一段转换为基本曲线
+fn segment_primitive(segment: &PolylineSegment) -> Primitive {
+    if !segment.is_arc() {
+        return Primitive::Line {
+            start: segment.start,
+            end: segment.end,
+        };
+    }
+    Primitive::Arc {
+        center: segment.center,
+        radius: segment.radius,
+        start_angle: segment.start_angle,
+        sweep: segment.sweep,
+    }
+}
+
+/// 标注中参与求交的线段（尺寸线、尺寸界线、引线）
+fn dimension_lines(dim: &Dimension) -> Vec<Line> {
+    let p1 = dim.definition_point1;
+    let p2 = dim.definition_point2;
+    let loc = dim.line_location;
+
+    match dim.dim_type {
+        DimensionType::Aligned | DimensionType::Linear => {
+            let d = p2 - p1;
+            if d.norm() < EPSILON {
+                return Vec::new();
+            }
+            let dir = d.normalize();
+            let perp = Vector2::new(-dir.y, dir.x);
+            let offset = perp * (loc - p1).dot(&perp);
+            let q1 = p1 + offset;
+            let q2 = p2 + offset;
+            vec![Line::new(q1, q2), Line::new(p1, q1), Line::new(p2, q2)]
+        }
+        DimensionType::Radius => vec![Line::new(p1, p2)],
+        DimensionType::Diameter => vec![Line::new(p1 - (p2 - p1), p2)],
+        DimensionType::Angular | DimensionType::ArcLength => {
+            vec![Line::new(p1, p2), Line::new(p1, dim.definition_point3)]
+        }
+        DimensionType::Ordinate => vec![Line::new(p1, loc)],
+    }
+}
+
+// ========== 基本曲线求交 ==========
+
+fn intersect_primitives(a: &Primitive, b: &Primitive) -> Vec<(f64, f64)> {
+    match (a, b) {
+        (Primitive::Line { .. }, Primitive::Line { .. }) => line_line(a, b),
+        (Primitive::Line { .. }, Primitive::Arc { .. }) => line_arc(a, b),
+        (Primitive::Arc { .. }, Primitive::Line { .. }) => swap(line_arc(b, a)),
+        (Primitive::Arc { .. }, Primitive::Arc { .. }) => arc_arc(a, b),
+        (Primitive::Line { .. }, Primitive::Ellipse(_)) => line_ellipse(a, b),
+        (Primitive::Ellipse(_), Primitive::Line { .. }) => swap(line_ellipse(b, a)),
+        _ => numeric(a, b),
+    }
+}
+
+fn swap(pairs: Vec<(f64, f64)>) -> Vec<(f64, f64)> {
+    pairs.into_iter().map(|(a, b)| (b, a)).collect()
+}
+
+fn line_line(a: &Primitive, b: &Primitive) -> Vec<(f64, f64)> {
+    let (Primitive::Line { start: p, end: p_end }, Primitive::Line { start: q, end: q_end }) = (a, b) else {
+        return Vec::new();
+    };
+    let r = p_end - p;
+    let s = q_end - q;
+    let denom = r.x * s.y - r.y * s.x;
+    if denom.abs() <= EPSILON * r.norm() * s.norm() {
+        // 平行或共线
+        return Vec::new();
+    }
+    let d = q - p;
+    let t = (d.x * s.y - d.y * s.x) / denom;
+    let u = (d.x * r.y - d.y * r.x) / denom;
+
+    match (a.accept(t), b.accept(u)) {
+        (Some(t), Some(u)) => vec![(t, u)],
+        _ => Vec::new(),
+    }
+}
+
+fn line_arc(line: &Primitive, arc: &Primitive) -> Vec<(f64, f64)> {
+    let (Primitive::Line { start, end }, Primitive::Arc { center, radius, .. }) = (line, arc) else {
+        return Vec::new();
+    };
+    let d = end - start;
+    let len_sq = d.norm_squared();
+    if len_sq < EPSILON * EPSILON {
+        return Vec::new();
+    }
+
+    // 垂足参数与垂距
+    let t_foot = (center - start).dot(&d) / len_sq;
+    let foot = start + d * t_foot;
+    let dist = (center - foot).norm();
+    let tol = TOLERANCE * (1.0 + radius);
+
+    let params = if dist > radius + tol {
+        return Vec::new();
+    } else if (dist - radius).abs() <= tol {
+        // 相切
+        vec![t_foot]
+    } else {
+        let h = (radius * radius - dist * dist).sqrt() / len_sq.sqrt();
+        vec![t_foot - h, t_foot + h]
+    };
+
+    params
+        .into_iter()
+        .filter_map(|t| {
+            let t = line.accept(t)?;
+            let angle = arc.arc_param(&line.point_at(t))?;
+            Some((t, angle))
+        })
+        .collect()
+}
+
+fn arc_arc(a: &Primitive, b: &Primitive) -> Vec<(f64, f64)> {
+    let (
+        Primitive::Arc {
+            center: c1,
+            radius: r1,
+            ..
+        },
+        Primitive::Arc {
+            center: c2,
+            radius: r2,
+            ..
+        },
+    ) = (a, b)
+    else {
+        return Vec::new();
+    };
+
+    let d_vec = c2 - c1;
+    let d = d_vec.norm();
+    let tol = TOLERANCE * (1.0 + r1.max(*r2));
+
+    // 同心（含重合）、相离或内含
+    if d < tol || d > r1 + r2 + tol || d < (r1 - r2).abs() - tol {
+        return Vec::new();
+    }
+
+    let along = (r1 * r1 - r2 * r2 + d * d) / (2.0 * d);
+    let h_sq = r1 * r1 - along * along;
+    let dir = d_vec / d;
+    let base = c1 + dir * along;
+
+    let points = if h_sq <= tol * tol.max(*r1) {
+        vec![base]
+    } else {
+        let h = h_sq.sqrt();
+        let perp = Vector2::new(-dir.y, dir.x);
+        vec![base + perp * h, base - perp * h]
+    };
+
+    points
+        .iter()
+        .filter_map(|p| Some((a.arc_param(p)?, b.arc_param(p)?)))
+        .collect()
+}
+
+fn line_ellipse(line: &Primitive, ellipse: &Primitive) -> Vec<(f64, f64)> {
+    let (Primitive::Line { start, end }, Primitive::Ellipse(e)) = (line, ellipse) else {
+        return Vec::new();
+    };
+    let major = e.major_radius();
+    let minor = e.minor_radius();
+    if major < EPSILON || minor < EPSILON {
+        return Vec::new();
+    }
+
+    // 变换到椭圆局部坐标系并缩放为单位圆
+    let major_dir = e.major_axis / major;
+    let minor_dir = e.minor_axis_direction();
+    let to_unit = |p: Point2| {
+        let v = p - e.center;
+        Point2::new(v.dot(&major_dir) / major, v.dot(&minor_dir) / minor)
+    };
+    let unit_line = Primitive::Line {
+        start: to_unit(*start),
+        end: to_unit(*end),
+    };
+    let unit_circle = Primitive::Arc {
+        center: Point2::origin(),
+        radius: 1.0,
+        start_angle: e.start_param,
+        sweep: e.end_param - e.start_param,
+    };
+
+    // 单位圆上的角度即椭圆参数，线段参数在仿射变换下不变
+    line_arc(&unit_line, &unit_circle)
+        .into_iter()
+        .filter_map(|(t, param)| {
+            let t = line.accept(t)?;
+            let param = ellipse.accept(param)?;
+            Some((t, param))
+        })
+        .collect()
+}
+
+/// 数值求交：弦线段求初值，牛顿迭代精化
+fn numeric(a: &Primitive, b: &Primitive) -> Vec<(f64, f64)> {
+    let chords_a = a.chords();
+    let chords_b = b.chords();
+    let mut result: Vec<(f64, f64)> = Vec::new();
+
+    for (ca, a0, a1) in &chords_a {
+        for (cb, b0, b1) in &chords_b {
+            for (u, v) in intersect_primitives(ca, cb) {
+                // 解析曲线直接使用自身参数，弦线段按比例映射回曲线参数
+                let ta = if a.is_analytic() { u } else { a0 + (a1 - a0) * u };
+                let tb = if b.is_analytic() { v } else { b0 + (b1 - b0) * v };
+                let Some((ta, tb)) = refine(a, b, ta, tb) else {
+                    continue;
+                };
+                let p = a.point_at(ta);
+                let tol = TOLERANCE * (1.0 + p.coords.norm()) * 100.0;
+                if !result.iter().any(|(ra, _)| (a.point_at(*ra) - p).norm() <= tol) {
+                    result.push((ta, tb));
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// 牛顿迭代求解 a(ta) = b(tb)
+fn refine(a: &Primitive, b: &Primitive, mut ta: f64, mut tb: f64) -> Option<(f64, f64)> {
+    let clamp = |p: &Primitive, t: f64| {
+        let (x, y) = p.range();
+        t.clamp(x.min(y), x.max(y))
+    };
+
+    for _ in 0..MAX_NEWTON_ITERATIONS {
+        let f = a.point_at(ta) - b.point_at(tb);
+        if f.norm() <= TOLERANCE * (1.0 + a.point_at(ta).coords.norm()) {
+            break;
+        }
+        let da = a.derivative(ta);
+        let db = b.derivative(tb);
+        // 求解 [da, -db] * [Δta, Δtb] = -f
+        let det = -da.x * db.y + db.x * da.y;
+        if det.abs() < EPSILON * EPSILON {
+            // 相切，保留当前近似值
+            break;
+        }
+        let dta = (f.x * db.y - db.x * f.y) / det;
+        let dtb = (f.x * da.y - da.x * f.y) / det;
+        ta = clamp(a, ta + dta);
+        tb = clamp(b, tb + dtb);
+    }
+
+    let p = a.point_at(ta);
+    let q = b.point_at(tb);
+    let accept_tol = 1e-6 * (1.0 + p.coords.norm());
+    if (p - q).norm() > accept_tol {
+        return None;
+    }
+    Some((a.accept(ta)?, b.accept(tb)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::{Arc, Circle, Leader, PolylineVertex};
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    fn assert_point(p: Point2, x: f64, y: f64) {
+        assert!((p.x - x).abs() < 1e-6 && (p.y - y).abs() < 1e-6, "{:?} != ({}, {})", p, x, y);
+    }
+
+    #[test]
+    fn test_line_line() {
+        let l1 = Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(10.0, 10.0)));
+        let l2 = Geometry::Line(Line::new(Point2::new(0.0, 10.0), Point2::new(10.0, 0.0)));
+
+        let hits = intersect(&l1, &l2);
+        assert_eq!(hits.len(), 1);
+        assert_point(hits[0].point, 5.0, 5.0);
+        assert!((hits[0].param1 - 0.5).abs() < 1e-9);
+        assert!((hits[0].param2 - 0.5).abs() < 1e-9);
+
+        // 不相交的线段
+        let l3 = Geometry::Line(Line::new(Point2::new(20.0, 0.0), Point2::new(30.0, 10.0)));
+        assert!(intersect(&l1, &l3).is_empty());
+    }
+
+    #[test]
+    fn test_line_circle_and_arc() {
+        let line = Geometry::Line(Line::new(Point2::new(-10.0, 0.0), Point2::new(10.0, 0.0)));
+        let circle = Geometry::Circle(Circle::new(Point2::origin(), 5.0));
+        let hits = intersect(&line, &circle);
+        assert_eq!(hits.len(), 2);
+        assert_point(hits[0].point, -5.0, 0.0);
+        assert_point(hits[1].point, 5.0, 0.0);
+
+        // 上半圆弧只与线段在端点相交
+        let arc = Geometry::Arc(Arc::new(Point2::origin(), 5.0, 0.0, PI));
+        let vertical = Geometry::Line(Line::new(Point2::new(0.0, -10.0), Point2::new(0.0, 10.0)));
+        let hits = intersect(&vertical, &arc);
+        assert_eq!(hits.len(), 1);
+        assert_point(hits[0].point, 0.0, 5.0);
+        assert!((hits[0].param2 - FRAC_PI_2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_circle_circle() {
+        let c1 = Geometry::Circle(Circle::new(Point2::new(0.0, 0.0), 5.0));
+        let c2 = Geometry::Circle(Circle::new(Point2::new(8.0, 0.0), 5.0));
+        let hits = intersect(&c1, &c2);
+        assert_eq!(hits.len(), 2);
+        for hit in &hits {
+            assert!((hit.point.x - 4.0).abs() < 1e-9);
+            assert!((hit.point.y.abs() - 3.0).abs() < 1e-9);
+        }
+
+        // 外切
+        let c3 = Geometry::Circle(Circle::new(Point2::new(10.0, 0.0), 5.0));
+        let hits = intersect(&c1, &c3);
+        assert_eq!(hits.len(), 1);
+        assert_point(hits[0].point, 5.0, 0.0);
+    }
+
+    #[test]
+    fn test_bulged_polyline() {
+        // 从 (-5, 0) 到 (5, 0) 的下半圆（逆时针）
+        let poly = Geometry::Polyline(Polyline::new(
+            vec![
+                PolylineVertex::with_bulge(Point2::new(-5.0, 0.0), 1.0),
+                PolylineVertex::new(Point2::new(5.0, 0.0)),
+            ],
+            false,
+        ));
+        let line = Geometry::Line(Line::new(Point2::new(0.0, -10.0), Point2::new(0.0, 10.0)));
+        let hits = intersect(&line, &poly);
+        assert_eq!(hits.len(), 1);
+        assert_point(hits[0].point, 0.0, -5.0);
+        assert!((hits[0].param2 - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_line_ellipse() {
+        let ellipse = Geometry::Ellipse(Ellipse::from_radii(Point2::origin(), 10.0, 5.0));
+        let line = Geometry::Line(Line::new(Point2::new(0.0, -10.0), Point2::new(0.0, 10.0)));
+        let hits = intersect(&line, &ellipse);
+        assert_eq!(hits.len(), 2);
+        assert_point(hits[0].point, 0.0, -5.0);
+        assert_point(hits[1].point, 0.0, 5.0);
+    }
+
+    #[test]
+    fn test_circle_ellipse_numeric() {
+        let ellipse = Geometry::Ellipse(Ellipse::from_radii(Point2::origin(), 10.0, 5.0));
+        let circle = Geometry::Circle(Circle::new(Point2::origin(), 7.0));
+        let hits = intersect(&circle, &ellipse);
+        assert_eq!(hits.len(), 4);
+        for hit in &hits {
+            assert!((hit.point.coords.norm() - 7.0).abs() < 1e-6);
+            let (x, y) = (hit.point.x / 10.0, hit.point.y / 5.0);
+            assert!((x * x + y * y - 1.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_spline_line() {
+        // 三次 Bézier 形式的样条：y(t) = 30·t(1-t)(1-2t)
+        let mut spline = Spline::from_control_points(
+            vec![
+                Point2::new(0.0, 0.0),
+                Point2::new(5.0, 10.0),
+                Point2::new(10.0, -10.0),
+                Point2::new(15.0, 0.0),
+            ],
+            3,
+            false,
+        );
+        spline.knots = vec![0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0];
+        let spline = Geometry::Spline(spline);
+        let axis = Geometry::Line(Line::new(Point2::new(-1.0, 0.0), Point2::new(16.0, 0.0)));
+        let hits = intersect(&spline, &axis);
+        // 两个端点加中间一次穿越
+        assert_eq!(hits.len(), 3);
+        for hit in &hits {
+            assert!(hit.point.y.abs() < 1e-6);
+        }
+        assert!((hits[1].param1 - 0.5).abs() < 1e-6);
+        assert_point(hits[1].point, 7.5, 0.0);
+    }
+
+    #[test]
+    fn test_leader_and_dimension() {
+        let leader = Geometry::Leader(Leader::new(vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(10.0, 10.0),
+            Point2::new(20.0, 10.0),
+        ]));
+        let line = Geometry::Line(Line::new(Point2::new(15.0, 0.0), Point2::new(15.0, 20.0)));
+        let hits = intersect(&leader, &line);
+        assert_eq!(hits.len(), 1);
+        assert_point(hits[0].point, 15.0, 10.0);
+        assert!((hits[0].param1 - 1.5).abs() < 1e-9);
+
+        let dim = Geometry::Dimension(Dimension::new(
+            Point2::new(0.0, 0.0),
+            Point2::new(10.0, 0.0),
+            Point2::new(5.0, 5.0),
+        ));
+        let cross = Geometry::Line(Line::new(Point2::new(5.0, -10.0), Point2::new(5.0, 10.0)));
+        let hits = intersect(&dim, &cross);
+        assert_eq!(hits.len(), 1);
+        assert_point(hits[0].point, 5.0, 5.0);
+    }
+
+    #[test]
+    fn test_non_curves() {
+        let text = Geometry::Text(crate::geometry::Text::new(Point2::origin(), "A", 2.5));
+        let line = Geometry::Line(Line::new(Point2::new(-1.0, 0.0), Point2::new(1.0, 0.0)));
+        assert!(intersect(&text, &line).is_empty());
+    }
+}
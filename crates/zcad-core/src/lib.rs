@@ -21,24 +21,36 @@
 //! println!("Length: {}", line.length());
 //! ```
 
+pub mod associative;
 pub mod async_core;
 pub mod block;
+pub mod boolean;
 pub mod buffer;
+pub mod curve;
 pub mod dimstyle;
 pub mod entity;
+pub mod font;
+pub mod gdt;
 pub mod geometry;
 pub mod grip;
+pub mod hatch_pattern;
 pub mod layout;
 pub mod history;
 pub mod input_parser;
+pub mod intersect;
 pub mod layer;
 pub mod math;
+pub mod mtext;
 pub mod parametric;
 pub mod performance;
 pub mod properties;
 pub mod snap;
 pub mod solver;
 pub mod spatial;
+pub mod spline;
+pub mod tessellation;
+pub mod textstyle;
+pub mod transaction;
 pub mod transform;
 pub mod units;
 pub mod version_control;
@@ -48,16 +60,23 @@ pub mod prelude {
     pub use crate::async_core::{AsyncCore, Message, MessageBus};
     pub use crate::block::{Block, BlockId, BlockReference, BlockTable};
     pub use crate::buffer::{DoubleBufferedEntities, EntityBuffer};
+    pub use crate::curve::{Curve, CurvePoint, PolylineSegment};
     pub use crate::entity::{Entity, EntityId};
+    pub use crate::font::{Font, FontManager, TextExtents};
+    pub use crate::gdt::{DatumReference, GdtSymbol, MaterialCondition, Tolerance, ToleranceRow, ToleranceValue};
     pub use crate::geometry::{Arc, Circle, Ellipse, Geometry, Hatch, Leader, Line, Point, Polyline, Spline, Text, TextAlignment};
     pub use crate::history::{HistoryTree, Operation, OperationId};
     pub use crate::layer::Layer;
     pub use crate::input_parser::{InputParser, InputValue, ParseError};
     pub use crate::math::{Point2, Point3, Vector2, Vector3};
+    pub use crate::mtext::{MText, MTextAttachment};
     pub use crate::parametric::{Constraint, ConstraintSystem, Variable};
     pub use crate::properties::{Color, LineType, Properties};
     pub use crate::snap::{SnapConfig, SnapEngine, SnapMask, SnapPoint, SnapType};
     pub use crate::solver::NewtonSolver;
+    pub use crate::textstyle::{TextStyle, TextStyleManager};
+    pub use crate::transaction::{Change, ChangeSet, EditTarget, Transaction};
+    pub use crate::tessellation::TessellationCache;
     pub use crate::transform::Transform2D;
     pub use crate::version_control::{VersionControl, Commit, Branch};
     pub use crate::grip::{Grip, GripType, GripData, get_grips_for_geometry, update_geometry_by_grip};
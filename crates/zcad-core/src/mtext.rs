@@ -0,0 +1,763 @@
+//! 多行文字（MTEXT）
+//!
+//! 内容按 DXF MTEXT 格式代码保存：`\P` 分段，`{}` 限定格式范围，`\H` 字高，`\W` 宽度系数，
+//! `\C`/`\c` 颜色，`\f` 字体及粗体/斜体，`\L`/`\O` 下划线/上划线，`\S` 堆叠分数。
+//! 解析后得到带格式的文字片段，按参考宽度自动换行（西文按单词、中文按字），
+//! 排版结果分解为单行文字和直线（下划线、分数线）供各渲染端绘制。
+
+use crate::font::FontManager;
+use crate::geometry::{Geometry, Line, Text};
+use crate::math::{BoundingBox2, Point2, Vector2};
+use crate::properties::Color;
+use serde::{Deserialize, Serialize};
+
+/// 单倍行距与字高之比
+const LINE_SPACING_FACTOR: f64 = 5.0 / 3.0;
+/// 堆叠文字与字高之比
+const STACK_SCALE: f64 = 0.7;
+
+/// 附着点：插入点位于文字框的哪个位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MTextAttachment {
+    #[default]
+    TopLeft,
+    TopCenter,
+    TopRight,
+    MiddleLeft,
+    MiddleCenter,
+    MiddleRight,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+}
+
+impl MTextAttachment {
+    /// 水平位置：0 为左，0.5 为中，1 为右
+    pub fn horizontal_factor(&self) -> f64 {
+        match self {
+            Self::TopLeft | Self::MiddleLeft | Self::BottomLeft => 0.0,
+            Self::TopCenter | Self::MiddleCenter | Self::BottomCenter => 0.5,
+            Self::TopRight | Self::MiddleRight | Self::BottomRight => 1.0,
+        }
+    }
+
+    /// 竖直位置：0 为上，0.5 为中，1 为下
+    pub fn vertical_factor(&self) -> f64 {
+        match self {
+            Self::TopLeft | Self::TopCenter | Self::TopRight => 0.0,
+            Self::MiddleLeft | Self::MiddleCenter | Self::MiddleRight => 0.5,
+            Self::BottomLeft | Self::BottomCenter | Self::BottomRight => 1.0,
+        }
+    }
+}
+
+/// 堆叠方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StackKind {
+    /// 水平分数线（`a/b`）
+    Horizontal,
+    /// 斜分数线（`a#b`）
+    Diagonal,
+    /// 公差堆叠，无分数线（`a^b`）
+    Tolerance,
+}
+
+impl StackKind {
+    fn from_separator(c: char) -> Option<Self> {
+        match c {
+            '/' => Some(Self::Horizontal),
+            '#' => Some(Self::Diagonal),
+            '^' => Some(Self::Tolerance),
+            _ => None,
+        }
+    }
+
+    /// 格式代码中的分隔符
+    pub fn separator(&self) -> char {
+        match self {
+            Self::Horizontal => '/',
+            Self::Diagonal => '#',
+            Self::Tolerance => '^',
+        }
+    }
+}
+
+/// 堆叠文字（分数或上下偏差）
+#[derive(Debug, Clone, PartialEq)]
+pub struct StackedText {
+    pub upper: String,
+    pub lower: String,
+    pub kind: StackKind,
+}
+
+/// 文字片段的格式
+#[derive(Debug, Clone, PartialEq)]
+pub struct MTextFormat {
+    /// 字高
+    pub height: f64,
+    /// 宽度系数
+    pub width_factor: f64,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub overline: bool,
+    /// 颜色，None 表示随实体
+    pub color: Option<Color>,
+    /// 字体名
+    pub font: Option<String>,
+}
+
+impl MTextFormat {
+    fn new(height: f64) -> Self {
+        Self {
+            height,
+            width_factor: 1.0,
+            bold: false,
+            italic: false,
+            underline: false,
+            overline: false,
+            color: None,
+            font: None,
+        }
+    }
+}
+
+/// 格式相同的一段文字；堆叠文字单独成段，此时 `text` 为空
+#[derive(Debug, Clone, PartialEq)]
+pub struct MTextRun {
+    pub text: String,
+    pub format: MTextFormat,
+    pub stacked: Option<StackedText>,
+}
+
+impl MTextRun {
+    /// 排版宽度
+    pub fn width(&self) -> f64 {
+        let format = &self.format;
+        match &self.stacked {
+            Some(stacked) => {
+                let h = format.height;
+                let font = format.font.as_deref();
+                let upper = text_width(&stacked.upper, h * STACK_SCALE, font) * format.width_factor;
+                let lower = text_width(&stacked.lower, h * STACK_SCALE, font) * format.width_factor;
+                match stacked.kind {
+                    StackKind::Diagonal => upper + lower + h * 0.4,
+                    StackKind::Horizontal | StackKind::Tolerance => upper.max(lower),
+                }
+            }
+            None => text_width(&self.text, format.height, format.font.as_deref()) * format.width_factor,
+        }
+    }
+}
+
+/// 排版后的文字片段，`position` 为基线起点（世界坐标）
+#[derive(Debug, Clone, PartialEq)]
+pub struct MTextFragment {
+    pub position: Point2,
+    pub width: f64,
+    pub run: MTextRun,
+}
+
+/// 多行文字
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MText {
+    /// 插入点
+    pub position: Point2,
+    /// 带格式代码的内容
+    pub content: String,
+    /// 默认字高
+    pub height: f64,
+    /// 参考宽度，0 表示不自动换行
+    pub width: f64,
+    /// 旋转角度（弧度）
+    pub rotation: f64,
+    /// 附着点
+    pub attachment: MTextAttachment,
+    /// 行距系数
+    pub line_spacing: f64,
+    /// 文字样式名称
+    #[serde(default)]
+    pub style_name: String,
+    /// 默认字体（由文字样式确定），None 表示默认字体
+    #[serde(default)]
+    pub font: Option<String>,
+}
+
+/// 局部坐标中的片段：x 为左端，y 为基线（文字框左上角为原点）
+struct LocalFragment {
+    x: f64,
+    baseline: f64,
+    width: f64,
+    run: MTextRun,
+}
+
+/// 排版结果：片段和文字框尺寸
+struct MTextLayout {
+    fragments: Vec<LocalFragment>,
+    width: f64,
+    height: f64,
+}
+
+impl MText {
+    /// 创建不自动换行、左上角附着的多行文字
+    pub fn new(position: Point2, content: impl Into<String>, height: f64) -> Self {
+        Self {
+            position,
+            content: content.into(),
+            height,
+            width: 0.0,
+            rotation: 0.0,
+            attachment: MTextAttachment::TopLeft,
+            line_spacing: 1.0,
+            style_name: String::new(),
+            font: None,
+        }
+    }
+
+    /// 设置参考宽度
+    pub fn with_width(mut self, width: f64) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// 设置旋转角度
+    pub fn with_rotation(mut self, rotation: f64) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// 设置附着点
+    pub fn with_attachment(mut self, attachment: MTextAttachment) -> Self {
+        self.attachment = attachment;
+        self
+    }
+
+    /// 设置行距系数
+    pub fn with_line_spacing(mut self, line_spacing: f64) -> Self {
+        self.line_spacing = line_spacing;
+        self
+    }
+
+    /// 解析格式代码，按段落返回文字片段
+    pub fn paragraphs(&self) -> Vec<Vec<MTextRun>> {
+        let mut paragraphs = parse_mtext(&self.content, self.height);
+        if self.font.is_some() {
+            for run in paragraphs.iter_mut().flatten() {
+                if run.format.font.is_none() {
+                    run.format.font.clone_from(&self.font);
+                }
+            }
+        }
+        paragraphs
+    }
+
+    /// 去掉格式代码后的纯文本，段落以换行分隔
+    pub fn plain_text(&self) -> String {
+        self.paragraphs()
+            .iter()
+            .map(|paragraph| {
+                paragraph
+                    .iter()
+                    .map(|run| match &run.stacked {
+                        Some(stacked) => format!("{}{}{}", stacked.upper, stacked.kind.separator(), stacked.lower),
+                        None => run.text.replace('\u{a0}', " "),
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// 文字方向（单位向量）
+    pub fn direction(&self) -> Vector2 {
+        Vector2::new(self.rotation.cos(), self.rotation.sin())
+    }
+
+    /// 按参考宽度换行排版
+    fn layout(&self) -> MTextLayout {
+        let mut lines: Vec<Vec<Atom>> = Vec::new();
+        for paragraph in self.paragraphs() {
+            let mut line: Vec<Atom> = Vec::new();
+            let mut line_width = 0.0;
+            let mut wrapped = false;
+            for atom in atoms(&paragraph) {
+                if self.width > 0.0 && !atom.space && line.iter().any(|a| !a.space) && line_width + atom.width > self.width
+                {
+                    while line.last().is_some_and(|a| a.space) {
+                        line.pop();
+                    }
+                    lines.push(std::mem::take(&mut line));
+                    line_width = 0.0;
+                    wrapped = true;
+                }
+                if atom.space && line.is_empty() && wrapped {
+                    // 自动换行后的行首空格不占位
+                    continue;
+                }
+                line_width += atom.width;
+                line.push(atom);
+            }
+            lines.push(line);
+        }
+
+        let mut fragments = Vec::new();
+        let mut line_widths = Vec::new();
+        let mut baseline = 0.0;
+        for (index, line) in lines.into_iter().enumerate() {
+            let line_height = line.iter().map(|a| a.run.format.height).reduce(f64::max).unwrap_or(self.height);
+            baseline -= if index == 0 {
+                line_height
+            } else {
+                line_height * LINE_SPACING_FACTOR * self.line_spacing
+            };
+            let first = fragments.len();
+            let mut x = 0.0;
+            for atom in line {
+                // 同一片段中相邻的原子合并输出
+                let merge = fragments.len() > first
+                    && fragments.last().is_some_and(|f: &LocalFragment| {
+                        f.run.stacked.is_none() && atom.run.stacked.is_none() && f.run.format == atom.run.format
+                    });
+                match fragments.last_mut() {
+                    Some(fragment) if merge => {
+                        fragment.run.text.push_str(&atom.run.text);
+                        fragment.width += atom.width;
+                    }
+                    _ => fragments.push(LocalFragment { x, baseline, width: atom.width, run: atom.run }),
+                }
+                x += atom.width;
+            }
+            line_widths.push((first, fragments.len(), x));
+        }
+
+        let width = if self.width > 0.0 {
+            self.width
+        } else {
+            line_widths.iter().fold(0.0, |max: f64, (_, _, w)| max.max(*w))
+        };
+        let factor = self.attachment.horizontal_factor();
+        for (first, last, line_width) in line_widths {
+            for fragment in &mut fragments[first..last] {
+                fragment.x += (width - line_width) * factor;
+            }
+        }
+        MTextLayout { fragments, width, height: -baseline }
+    }
+
+    /// 局部坐标转世界坐标
+    fn local_to_world(&self, layout: &MTextLayout, x: f64, y: f64) -> Point2 {
+        let x = x - layout.width * self.attachment.horizontal_factor();
+        let y = y + layout.height * self.attachment.vertical_factor();
+        let direction = self.direction();
+        let up = Vector2::new(-direction.y, direction.x);
+        self.position + direction * x + up * y
+    }
+
+    /// 排版后的文字片段（世界坐标）
+    pub fn fragments(&self) -> Vec<MTextFragment> {
+        let layout = self.layout();
+        layout
+            .fragments
+            .iter()
+            .map(|f| MTextFragment {
+                position: self.local_to_world(&layout, f.x, f.baseline),
+                width: f.width,
+                run: f.run.clone(),
+            })
+            .collect()
+    }
+
+    /// 文字框的四个角点（左上、右上、右下、左下）
+    pub fn frame_corners(&self) -> [Point2; 4] {
+        let layout = self.layout();
+        let (w, h) = (layout.width, layout.height);
+        [
+            self.local_to_world(&layout, 0.0, 0.0),
+            self.local_to_world(&layout, w, 0.0),
+            self.local_to_world(&layout, w, -h),
+            self.local_to_world(&layout, 0.0, -h),
+        ]
+    }
+
+    /// 分解为单行文字以及下划线、上划线和分数线
+    pub fn explode(&self) -> Vec<Geometry> {
+        self.explode_with_colors().into_iter().map(|(geometry, _)| geometry).collect()
+    }
+
+    /// 分解并附带各部分的颜色，None 表示随实体
+    pub fn explode_with_colors(&self) -> Vec<(Geometry, Option<Color>)> {
+        let layout = self.layout();
+        let point = |x: f64, y: f64| self.local_to_world(&layout, x, y);
+        let text = |x: f64, y: f64, content: &str, height: f64, format: &MTextFormat| {
+            let mut text = Text::new(point(x, y), content, height).with_rotation(self.rotation);
+            text.style_name.clone_from(&self.style_name);
+            text.font.clone_from(&format.font);
+            text.width_factor = format.width_factor;
+            Geometry::Text(text)
+        };
+        let line = |x1: f64, y1: f64, x2: f64, y2: f64| Geometry::Line(Line::new(point(x1, y1), point(x2, y2)));
+
+        let mut parts = Vec::new();
+        for fragment in &layout.fragments {
+            let (x, base, width) = (fragment.x, fragment.baseline, fragment.width);
+            let format = &fragment.run.format;
+            let h = format.height;
+            let mut push = |geometry: Geometry| parts.push((geometry, format.color));
+            match &fragment.run.stacked {
+                Some(stacked) => {
+                    let s = h * STACK_SCALE;
+                    let font = format.font.as_deref();
+                    let upper = text_width(&stacked.upper, s, font) * format.width_factor;
+                    let lower = text_width(&stacked.lower, s, font) * format.width_factor;
+                    match stacked.kind {
+                        StackKind::Horizontal => {
+                            let bar = base + h * 0.4;
+                            push(text(x + (width - upper) / 2.0, bar + h * 0.1, &stacked.upper, s, format));
+                            push(line(x, bar, x + width, bar));
+                            push(text(x + (width - lower) / 2.0, bar - h * 0.1 - s, &stacked.lower, s, format));
+                        }
+                        StackKind::Tolerance => {
+                            let middle = base + h * 0.4;
+                            push(text(x, middle + h * 0.1, &stacked.upper, s, format));
+                            push(text(x, middle - h * 0.1 - s, &stacked.lower, s, format));
+                        }
+                        StackKind::Diagonal => {
+                            push(text(x, base + h * 0.45, &stacked.upper, s, format));
+                            push(line(x + upper + h * 0.05, base, x + upper + h * 0.35, base + h));
+                            push(text(x + upper + h * 0.4, base, &stacked.lower, s, format));
+                        }
+                    }
+                }
+                None if !fragment.run.text.trim().is_empty() => {
+                    push(text(x, base, &fragment.run.text, h, format));
+                }
+                None => {}
+            }
+            if format.underline {
+                push(line(x, base - h * 0.2, x + width, base - h * 0.2));
+            }
+            if format.overline {
+                push(line(x, base + h * 1.2, x + width, base + h * 1.2));
+            }
+        }
+        parts
+    }
+
+    /// 获取包围盒（文字框）
+    pub fn bounding_box(&self) -> BoundingBox2 {
+        BoundingBox2::from_points(self.frame_corners())
+    }
+
+    /// 检查点是否在文字框内
+    pub fn contains_point(&self, point: &Point2, tolerance: f64) -> bool {
+        let [top_left, top_right, _, bottom_left] = self.frame_corners();
+        let along = top_right - top_left;
+        let down = bottom_left - top_left;
+        let d = point - top_left;
+        let inside = |v: Vector2, value: f64| {
+            let length = v.norm();
+            if length < f64::EPSILON {
+                value.abs() <= tolerance
+            } else {
+                let t = value / length;
+                t >= -tolerance && t <= length + tolerance
+            }
+        };
+        inside(along, d.dot(&along)) && inside(down, d.dot(&down))
+    }
+}
+
+/// 换行的最小单位：一个单词、一个空格、一个中文字符或一组堆叠文字
+struct Atom {
+    run: MTextRun,
+    width: f64,
+    space: bool,
+}
+
+/// 将段落拆分为换行单位
+fn atoms(paragraph: &[MTextRun]) -> Vec<Atom> {
+    let mut atoms = Vec::new();
+    for run in paragraph {
+        if run.stacked.is_some() {
+            atoms.push(Atom { width: run.width(), run: run.clone(), space: false });
+            continue;
+        }
+        let mut word = String::new();
+        let push = |text: String, space: bool, atoms: &mut Vec<Atom>| {
+            let piece = MTextRun { text, format: run.format.clone(), stacked: None };
+            atoms.push(Atom { width: piece.width(), run: piece, space });
+        };
+        for c in run.text.chars() {
+            if c == ' ' || Text::is_cjk(c) {
+                if !word.is_empty() {
+                    push(std::mem::take(&mut word), false, &mut atoms);
+                }
+                push(c.to_string(), c == ' ', &mut atoms);
+            } else {
+                word.push(c);
+            }
+        }
+        if !word.is_empty() {
+            push(word, false, &mut atoms);
+        }
+    }
+    atoms
+}
+
+/// 文字宽度，与分解后 [`Text::width`] 的度量一致
+fn text_width(text: &str, height: f64, font: Option<&str>) -> f64 {
+    FontManager::shared().measure(text, height, font).width
+}
+
+/// 格式代码解析状态
+struct Parser {
+    paragraphs: Vec<Vec<MTextRun>>,
+    text: String,
+    format: MTextFormat,
+    groups: Vec<MTextFormat>,
+}
+
+impl Parser {
+    /// 输出当前累积的文字
+    fn flush(&mut self) {
+        if !self.text.is_empty() {
+            let run = MTextRun { text: std::mem::take(&mut self.text), format: self.format.clone(), stacked: None };
+            if let Some(paragraph) = self.paragraphs.last_mut() {
+                paragraph.push(run);
+            }
+        }
+    }
+
+    /// 修改格式前先输出之前的文字
+    fn set(&mut self, change: impl FnOnce(&mut MTextFormat)) {
+        self.flush();
+        change(&mut self.format);
+    }
+
+    fn new_paragraph(&mut self) {
+        self.flush();
+        self.paragraphs.push(Vec::new());
+    }
+}
+
+/// 读取格式代码参数，直到分号（不含）
+fn read_argument(chars: &mut std::str::Chars) -> String {
+    let mut argument = String::new();
+    for c in chars.by_ref() {
+        if c == ';' {
+            break;
+        }
+        argument.push(c);
+    }
+    argument
+}
+
+/// 解析数值参数，`x` 结尾表示相对当前值的倍数
+fn scaled_value(argument: &str, current: f64) -> Option<f64> {
+    match argument.strip_suffix(['x', 'X']) {
+        Some(factor) => factor.trim().parse::<f64>().ok().map(|f| current * f),
+        None => argument.trim().parse::<f64>().ok(),
+    }
+}
+
+/// 解析 `\S` 堆叠参数，分隔符前的 `\` 表示转义
+fn parse_stack(argument: &str) -> Option<StackedText> {
+    let mut upper = String::new();
+    let mut chars = argument.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(escaped) = chars.next() {
+                upper.push(escaped);
+            }
+        } else if let Some(kind) = StackKind::from_separator(c) {
+            let lower: String = chars.collect();
+            return Some(StackedText { upper, lower: lower.replace('\\', ""), kind });
+        } else {
+            upper.push(c);
+        }
+    }
+    None
+}
+
+/// 解析 MTEXT 格式代码，按段落返回文字片段
+pub fn parse_mtext(content: &str, height: f64) -> Vec<Vec<MTextRun>> {
+    let mut parser = Parser {
+        paragraphs: vec![Vec::new()],
+        text: String::new(),
+        format: MTextFormat::new(height),
+        groups: Vec::new(),
+    };
+    let mut chars = content.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' => {
+                parser.flush();
+                parser.groups.push(parser.format.clone());
+            }
+            '}' => {
+                parser.flush();
+                if let Some(format) = parser.groups.pop() {
+                    parser.format = format;
+                }
+            }
+            '\n' => parser.new_paragraph(),
+            '\\' => match chars.next() {
+                Some('P') | Some('N') => parser.new_paragraph(),
+                Some('~') => parser.text.push('\u{a0}'),
+                Some(c @ ('\\' | '{' | '}')) => parser.text.push(c),
+                Some('L') => parser.set(|f| f.underline = true),
+                Some('l') => parser.set(|f| f.underline = false),
+                Some('O') => parser.set(|f| f.overline = true),
+                Some('o') => parser.set(|f| f.overline = false),
+                Some('K') | Some('k') => {}
+                Some('H') => {
+                    let argument = read_argument(&mut chars);
+                    if let Some(h) = scaled_value(&argument, parser.format.height).filter(|h| *h > 0.0) {
+                        parser.set(|f| f.height = h);
+                    }
+                }
+                Some('W') => {
+                    let argument = read_argument(&mut chars);
+                    if let Some(w) = scaled_value(&argument, parser.format.width_factor).filter(|w| *w > 0.0) {
+                        parser.set(|f| f.width_factor = w);
+                    }
+                }
+                Some('C') => {
+                    // 0 为随块，256 为随层，都按随实体处理
+                    let color = read_argument(&mut chars)
+                        .trim()
+                        .parse::<u16>()
+                        .ok()
+                        .and_then(|index| u8::try_from(index).ok())
+                        .filter(|index| *index != 0)
+                        .map(Color::from_aci);
+                    parser.set(|f| f.color = color);
+                }
+                Some('c') => {
+                    if let Ok(rgb) = read_argument(&mut chars).trim().parse::<u32>() {
+                        parser.set(|f| f.color = Some(Color::from_hex(rgb & 0xFF_FFFF)));
+                    }
+                }
+                Some('f') | Some('F') => {
+                    let argument = read_argument(&mut chars);
+                    let mut fields = argument.split('|');
+                    let name = fields.next().unwrap_or_default().to_string();
+                    let flags: Vec<&str> = fields.collect();
+                    let flag = |prefix: char| flags.iter().find_map(|f| f.strip_prefix(prefix)).map(|v| v == "1");
+                    let (bold, italic) = (flag('b'), flag('i'));
+                    parser.set(|f| {
+                        f.font = (!name.is_empty()).then_some(name);
+                        f.bold = bold.unwrap_or(false);
+                        f.italic = italic.unwrap_or(false);
+                    });
+                }
+                Some('S') => {
+                    let argument = read_argument(&mut chars);
+                    match parse_stack(&argument) {
+                        Some(stacked) => {
+                            parser.flush();
+                            let run = MTextRun { text: String::new(), format: parser.format.clone(), stacked: Some(stacked) };
+                            if let Some(paragraph) = parser.paragraphs.last_mut() {
+                                paragraph.push(run);
+                            }
+                        }
+                        None => parser.text.push_str(&argument),
+                    }
+                }
+                // 对齐、倾斜、字距和段落缩进不影响片段内容
+                Some('A') | Some('Q') | Some('T') | Some('p') => {
+                    read_argument(&mut chars);
+                }
+                Some(other) => {
+                    parser.text.push('\\');
+                    parser.text.push(other);
+                }
+                None => parser.text.push('\\'),
+            },
+            c => parser.text.push(c),
+        }
+    }
+    parser.flush();
+    parser.paragraphs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_format_codes() {
+        let paragraphs = parse_mtext("普通{\\fArial|b1|i0;粗体}\\P{\\H2x;\\C1;大}\\S1/2;\\Lu\\l", 2.5);
+        assert_eq!(paragraphs.len(), 2);
+
+        let first = &paragraphs[0];
+        assert_eq!(first.len(), 2);
+        assert_eq!(first[0].text, "普通");
+        assert!(!first[0].format.bold);
+        assert_eq!(first[1].text, "粗体");
+        assert!(first[1].format.bold && !first[1].format.italic);
+        assert_eq!(first[1].format.font.as_deref(), Some("Arial"));
+
+        let second = &paragraphs[1];
+        assert_eq!(second[0].text, "大");
+        assert_eq!(second[0].format.height, 5.0);
+        assert_eq!(second[0].format.color, Some(Color::RED));
+        // 分组结束后恢复格式
+        let stacked = second[1].stacked.as_ref().unwrap();
+        assert_eq!((stacked.upper.as_str(), stacked.lower.as_str()), ("1", "2"));
+        assert_eq!(stacked.kind, StackKind::Horizontal);
+        assert_eq!(second[1].format.height, 2.5);
+        assert!(second[2].format.underline);
+        assert_eq!(second[2].text, "u");
+
+        let mtext = MText::new(Point2::origin(), "a\\~b\\P\\{x\\}", 1.0);
+        assert_eq!(mtext.plain_text(), "a b\n{x}");
+    }
+
+    #[test]
+    fn test_word_wrap_and_attachment() {
+        // 参考宽度 4 只能容纳一个五字母单词
+        let mtext = MText::new(Point2::new(10.0, 20.0), "hello world again", 1.0).with_width(4.0);
+        let fragments = mtext.fragments();
+        let texts: Vec<&str> = fragments.iter().map(|f| f.run.text.as_str()).collect();
+        assert_eq!(texts, ["hello", "world", "again"]);
+        assert!((fragments[0].position.y - 19.0).abs() < 1e-9);
+        let spacing = fragments[0].position.y - fragments[1].position.y;
+        assert!((spacing - LINE_SPACING_FACTOR).abs() < 1e-9);
+        assert!(fragments.iter().all(|f| (f.position.x - 10.0).abs() < 1e-9));
+
+        // 中文按字换行
+        let chinese = MText::new(Point2::origin(), "多行文字", 1.0).with_width(2.5);
+        assert_eq!(chinese.fragments().len(), 2);
+
+        // 右下角附着：文字框右下角位于插入点
+        let bottom_right = MText::new(Point2::new(10.0, 20.0), "ab\\Pabcd", 1.0)
+            .with_attachment(MTextAttachment::BottomRight);
+        let bbox = bottom_right.bounding_box();
+        assert!((bbox.max.x - 10.0).abs() < 1e-9);
+        assert!((bbox.min.y - 20.0).abs() < 1e-9);
+        let fragments = bottom_right.fragments();
+        let ab = FontManager::shared().measure("ab", 1.0, None).width;
+        assert!((fragments[0].position.x - (10.0 - ab)).abs() < 1e-9);
+        assert!(bottom_right.contains_point(&Point2::new(9.0, 20.5), 0.0));
+        assert!(!bottom_right.contains_point(&Point2::new(11.0, 20.5), 0.0));
+    }
+
+    #[test]
+    fn test_explode_stacked_and_underline() {
+        let mtext = MText::new(Point2::origin(), "\\L1\\l\\S3/4;", 2.0).with_rotation(std::f64::consts::FRAC_PI_2);
+        let parts = mtext.explode();
+        let texts: Vec<&Text> = parts
+            .iter()
+            .filter_map(|g| match g {
+                Geometry::Text(text) => Some(text),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(texts.len(), 3);
+        assert!(texts.iter().all(|t| (t.rotation - std::f64::consts::FRAC_PI_2).abs() < 1e-9));
+        assert!((texts[1].height - 2.0 * STACK_SCALE).abs() < 1e-9);
+        // 下划线和分数线
+        assert_eq!(parts.iter().filter(|g| matches!(g, Geometry::Line(_))).count(), 2);
+    }
+}
@@ -5,21 +5,33 @@
 use serde::{Deserialize, Serialize};
 
 /// RGBA颜色
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+///
+/// 来自 AutoCAD 颜色索引（ACI）的颜色会记录原索引，导出时原样写回；
+/// 比较相等只看 RGBA 分量。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Color {
     pub r: u8,
     pub g: u8,
     pub b: u8,
     pub a: u8,
+    /// 原 ACI 索引，真彩色为 None
+    #[serde(default)]
+    pub aci: Option<u8>,
+}
+
+impl PartialEq for Color {
+    fn eq(&self, other: &Self) -> bool {
+        (self.r, self.g, self.b, self.a) == (other.r, other.g, other.b, other.a)
+    }
 }
 
 impl Color {
     pub const fn new(r: u8, g: u8, b: u8) -> Self {
-        Self { r, g, b, a: 255 }
+        Self { r, g, b, a: 255, aci: None }
     }
 
     pub const fn with_alpha(r: u8, g: u8, b: u8, a: u8) -> Self {
-        Self { r, g, b, a }
+        Self { r, g, b, a, aci: None }
     }
 
     /// 从十六进制值创建（如 0xFF0000 表示红色）
@@ -29,9 +41,15 @@ impl Color {
             g: ((hex >> 8) & 0xFF) as u8,
             b: (hex & 0xFF) as u8,
             a: 255,
+            aci: None,
         }
     }
 
+    /// 转换为十六进制值（如红色为 0xFF0000），与 [`Color::from_hex`] 互逆
+    pub const fn to_hex(&self) -> u32 {
+        ((self.r as u32) << 16) | ((self.g as u32) << 8) | self.b as u32
+    }
+
     /// 转换为 [0.0, 1.0] 范围的浮点数组
     pub fn to_f32_array(&self) -> [f32; 4] {
         [
@@ -43,15 +61,15 @@ impl Color {
     }
 
     // 预定义颜色（AutoCAD ACI颜色兼容）
-    pub const RED: Color = Color::new(255, 0, 0);
-    pub const YELLOW: Color = Color::new(255, 255, 0);
-    pub const GREEN: Color = Color::new(0, 255, 0);
-    pub const CYAN: Color = Color::new(0, 255, 255);
-    pub const BLUE: Color = Color::new(0, 0, 255);
-    pub const MAGENTA: Color = Color::new(255, 0, 255);
-    pub const WHITE: Color = Color::new(255, 255, 255);
+    pub const RED: Color = Color::from_aci(1);
+    pub const YELLOW: Color = Color::from_aci(2);
+    pub const GREEN: Color = Color::from_aci(3);
+    pub const CYAN: Color = Color::from_aci(4);
+    pub const BLUE: Color = Color::from_aci(5);
+    pub const MAGENTA: Color = Color::from_aci(6);
+    pub const WHITE: Color = Color::from_aci(7);
     pub const BLACK: Color = Color::new(0, 0, 0);
-    pub const GRAY: Color = Color::new(128, 128, 128);
+    pub const GRAY: Color = Color::from_aci(8);
 
     /// 颜色跟随图层（ByLayer）
     pub const BY_LAYER: Color = Color::with_alpha(0, 0, 0, 0);
@@ -59,6 +77,44 @@ impl Color {
     /// 颜色跟随块（ByBlock）
     pub const BY_BLOCK: Color = Color::with_alpha(0, 0, 0, 1);
 
+    /// 从 AutoCAD 颜色索引（ACI）创建，0 为随块（ByBlock）
+    pub const fn from_aci(index: u8) -> Self {
+        if index == 0 {
+            return Self::BY_BLOCK;
+        }
+        let [r, g, b] = ACI_PALETTE[index as usize];
+        Self { r, g, b, a: 255, aci: Some(index) }
+    }
+
+    /// 写入 DXF 组码 62 的颜色索引
+    ///
+    /// 记录的索引仍与颜色一致时原样返回，否则取调色板中最接近的颜色
+    pub fn to_aci(&self) -> u8 {
+        match self.aci {
+            Some(index) if self.is_aci() => index,
+            _ => self.nearest_aci(),
+        }
+    }
+
+    /// 调色板 1..=255 中与本颜色 RGB 距离最近的索引，距离相同时取较小的索引
+    pub fn nearest_aci(&self) -> u8 {
+        let distance = |[r, g, b]: [u8; 3]| {
+            let dr = r as i32 - self.r as i32;
+            let dg = g as i32 - self.g as i32;
+            let db = b as i32 - self.b as i32;
+            dr * dr + dg * dg + db * db
+        };
+        (1..=255u8)
+            .min_by_key(|&index| distance(ACI_PALETTE[index as usize]))
+            .unwrap_or(7)
+    }
+
+    /// 是否为调色板颜色（记录的索引与 RGB 一致），否则需要以真彩色保存
+    pub fn is_aci(&self) -> bool {
+        self.aci
+            .is_some_and(|index| index != 0 && ACI_PALETTE[index as usize] == [self.r, self.g, self.b])
+    }
+
     pub fn is_by_layer(&self) -> bool {
         self.a == 0
     }
@@ -68,6 +124,63 @@ impl Color {
     }
 }
 
+/// AutoCAD 标准颜色索引（ACI）调色板，索引 0（随块）占位为黑色
+pub const ACI_PALETTE: [[u8; 3]; 256] = build_aci_palette();
+
+/// 生成 ACI 调色板
+///
+/// 10..=249 为 24 种色相（每 15° 一种），每种色相依次为五级亮度，
+/// 每级亮度再分饱和与浅色两档；250..=255 为灰度。
+const fn build_aci_palette() -> [[u8; 3]; 256] {
+    const STANDARD: [[u8; 3]; 10] = [
+        [0, 0, 0],
+        [255, 0, 0],
+        [255, 255, 0],
+        [0, 255, 0],
+        [0, 255, 255],
+        [0, 0, 255],
+        [255, 0, 255],
+        [255, 255, 255],
+        [128, 128, 128],
+        [192, 192, 192],
+    ];
+    const BRIGHTNESS: [u32; 5] = [255, 204, 153, 127, 76];
+    const GRAYS: [u8; 6] = [51, 91, 132, 173, 214, 255];
+
+    let mut palette = [[0u8; 3]; 256];
+    let mut index = 0;
+    while index < 10 {
+        palette[index] = STANDARD[index];
+        index += 1;
+    }
+    while index < 250 {
+        let hue = (index - 10) / 10;
+        let shade = (index - 10) % 10;
+        let high = BRIGHTNESS[shade / 2];
+        // 浅色档的最低分量为亮度的一半
+        let low = if shade % 2 == 1 { high / 2 } else { 0 };
+        let step = (hue % 4) as u32;
+        let rising = (low + (high - low) * step / 4) as u8;
+        let falling = (low + (high - low) * (4 - step) / 4) as u8;
+        let (high, low) = (high as u8, low as u8);
+        palette[index] = match hue / 4 {
+            0 => [high, rising, low],
+            1 => [falling, high, low],
+            2 => [low, high, rising],
+            3 => [low, falling, high],
+            4 => [rising, low, high],
+            _ => [high, low, falling],
+        };
+        index += 1;
+    }
+    while index < 256 {
+        let gray = GRAYS[index - 250];
+        palette[index] = [gray, gray, gray];
+        index += 1;
+    }
+    palette
+}
+
 impl Default for Color {
     fn default() -> Self {
         Self::BY_LAYER
@@ -214,3 +327,39 @@ impl Properties {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aci_palette() {
+        assert_eq!(Color::from_aci(1), Color::new(255, 0, 0));
+        assert_eq!(Color::from_aci(7), Color::WHITE);
+        assert_eq!(Color::from_aci(0), Color::BY_BLOCK);
+        assert_eq!(Color::from_aci(20), Color::new(255, 63, 0));
+        assert_eq!(Color::from_aci(61), Color::new(223, 255, 127));
+        assert_eq!(Color::from_aci(250), Color::new(51, 51, 51));
+
+        // 每个索引都能原样写回
+        for index in 1..=255u8 {
+            let color = Color::from_aci(index);
+            assert!(color.is_aci());
+            assert_eq!(color.to_aci(), index);
+        }
+    }
+
+    #[test]
+    fn test_nearest_aci() {
+        assert_eq!(Color::new(250, 5, 5).nearest_aci(), 1);
+        assert_eq!(Color::new(255, 255, 255).nearest_aci(), 7);
+        assert_eq!(Color::new(130, 130, 131).to_aci(), 252);
+
+        // 修改过 RGB 的颜色不再沿用原索引
+        let mut color = Color::from_aci(30);
+        color.b = 200;
+        assert!(!color.is_aci());
+        assert_eq!(color.to_aci(), color.nearest_aci());
+        assert_eq!(Color::from_hex(Color::from_aci(30).to_hex()), Color::from_aci(30));
+    }
+}
@@ -12,9 +12,11 @@
 //! - 最近点 (Nearest)
 //! - 网格点 (Grid)
 
+use crate::curve::Curve;
 use crate::entity::{Entity, EntityId};
 use crate::geometry::{Arc, Circle, Ellipse, Geometry, Leader, Line, Polyline, Spline};
-use crate::math::{Point2, Vector2, EPSILON};
+use crate::intersect;
+use crate::math::{Point2, EPSILON};
 use serde::{Deserialize, Serialize};
 
 /// 捕捉类型
@@ -346,16 +348,25 @@ impl SnapEngine {
                 self.collect_polyline_snap_points(polyline, entity.id, mouse, tolerance, reference_point);
             }
             Geometry::Text(text) => {
-                // 文本只捕捉插入点
+                // 文本捕捉插入点和按字体度量的基线两端
                 if self.config.enabled_types.is_enabled(SnapType::Endpoint) {
-                    let dist = (text.position - mouse).norm();
+                    let (start, end) = text.baseline();
+                    let mut points = vec![text.position];
+                    points.extend([start, end].into_iter().filter(|p| (p - text.position).norm() > EPSILON));
+                    for pt in points {
+                        let dist = (pt - mouse).norm();
+                        if dist <= tolerance {
+                            self.candidates.push(SnapPoint::new(pt, SnapType::Endpoint, Some(entity.id), dist));
+                        }
+                    }
+                }
+            }
+            Geometry::MText(mtext) => {
+                // 多行文字只捕捉插入点
+                if self.config.enabled_types.is_enabled(SnapType::Endpoint) {
+                    let dist = (mtext.position - mouse).norm();
                     if dist <= tolerance {
-                        self.candidates.push(SnapPoint::new(
-                            text.position,
-                            SnapType::Endpoint,
-                            Some(entity.id),
-                            dist,
-                        ));
+                        self.candidates.push(SnapPoint::new(mtext.position, SnapType::Endpoint, Some(entity.id), dist));
                     }
                 }
             }
@@ -387,6 +398,29 @@ impl SnapEngine {
             Geometry::Leader(leader) => {
                 self.collect_leader_snap_points(leader, entity.id, mouse, tolerance);
             }
+            Geometry::Tolerance(frame) => {
+                // 特征控制框捕捉插入点和引线
+                if self.config.enabled_types.is_enabled(SnapType::Endpoint) {
+                    let dist = (frame.position - mouse).norm();
+                    if dist <= tolerance {
+                        self.candidates.push(SnapPoint::new(frame.position, SnapType::Endpoint, Some(entity.id), dist));
+                    }
+                }
+                if let Some(leader) = &frame.leader {
+                    self.collect_leader_snap_points(leader, entity.id, mouse, tolerance);
+                }
+            }
+            Geometry::Insert(reference) => {
+                // 块参照只捕捉插入点，块内部图元需展开后再捕捉
+                if self.config.enabled_types.is_enabled(SnapType::Endpoint) {
+                    for point in reference.all_insertion_points() {
+                        let dist = (point - mouse).norm();
+                        if dist <= tolerance {
+                            self.candidates.push(SnapPoint::new(point, SnapType::Endpoint, Some(entity.id), dist));
+                        }
+                    }
+                }
+            }
         }
     }
 
@@ -663,44 +697,34 @@ impl SnapEngine {
             }
         }
 
-        // 最近点和垂足需要遍历所有线段
-        if enabled.is_enabled(SnapType::Nearest) || enabled.is_enabled(SnapType::Perpendicular) {
-            for i in 0..polyline.segment_count() {
-                let v1 = &polyline.vertices[i];
-                let v2 = &polyline.vertices[(i + 1) % polyline.vertices.len()];
-
-                // 只处理直线段
-                if v1.bulge.abs() < EPSILON {
-                    let line = Line::new(v1.point, v2.point);
-
-                    if enabled.is_enabled(SnapType::Nearest) {
-                        let nearest = self.nearest_point_on_line(&line, mouse);
-                        let dist = (nearest - mouse).norm();
-                        if dist <= tolerance {
-                            self.candidates.push(SnapPoint::new(
-                                nearest,
-                                SnapType::Nearest,
-                                Some(entity_id),
-                                dist,
-                            ));
-                        }
-                    }
+        // 最近点（含圆弧段）
+        if enabled.is_enabled(SnapType::Nearest) {
+            self.collect_nearest_on_curve(polyline, entity_id, mouse, tolerance);
+        }
 
-                    if enabled.is_enabled(SnapType::Perpendicular) {
-                        if let Some(ref_point) = reference_point {
-                            if let Some(perp) = self.perpendicular_to_line(&line, ref_point) {
-                                let dist = (perp - mouse).norm();
-                                if dist <= tolerance {
-                                    self.candidates.push(SnapPoint::new(
-                                        perp,
-                                        SnapType::Perpendicular,
-                                        Some(entity_id),
-                                        dist,
-                                    ));
-                                }
-                            }
-                        }
-                    }
+        // 垂足（只处理直线段）
+        if !self.config.enabled_types.is_enabled(SnapType::Perpendicular) {
+            return;
+        }
+        let Some(ref_point) = reference_point else {
+            return;
+        };
+        for i in 0..polyline.segment_count() {
+            let v1 = &polyline.vertices[i];
+            let v2 = &polyline.vertices[(i + 1) % polyline.vertices.len()];
+            if v1.bulge.abs() >= EPSILON {
+                continue;
+            }
+            let line = Line::new(v1.point, v2.point);
+            if let Some(perp) = self.perpendicular_to_line(&line, ref_point) {
+                let dist = (perp - mouse).norm();
+                if dist <= tolerance {
+                    self.candidates.push(SnapPoint::new(
+                        perp,
+                        SnapType::Perpendicular,
+                        Some(entity_id),
+                        dist,
+                    ));
                 }
             }
         }
@@ -776,27 +800,7 @@ impl SnapEngine {
 
         // 最近点
         if enabled.is_enabled(SnapType::Nearest) {
-            // 使用采样点找最近点
-            let samples = ellipse.sample_points(64);
-            let mut min_dist = f64::MAX;
-            let mut nearest = mouse;
-            
-            for pt in samples {
-                let dist = (pt - mouse).norm();
-                if dist < min_dist {
-                    min_dist = dist;
-                    nearest = pt;
-                }
-            }
-            
-            if min_dist <= tolerance {
-                self.candidates.push(SnapPoint::new(
-                    nearest,
-                    SnapType::Nearest,
-                    Some(entity_id),
-                    min_dist,
-                ));
-            }
+            self.collect_nearest_on_curve(ellipse, entity_id, mouse, tolerance);
         }
     }
 
@@ -842,26 +846,7 @@ impl SnapEngine {
 
         // 最近点
         if enabled.is_enabled(SnapType::Nearest) {
-            let samples = spline.sample_points(64);
-            let mut min_dist = f64::MAX;
-            let mut nearest = mouse;
-            
-            for pt in samples {
-                let dist = (pt - mouse).norm();
-                if dist < min_dist {
-                    min_dist = dist;
-                    nearest = pt;
-                }
-            }
-            
-            if min_dist <= tolerance {
-                self.candidates.push(SnapPoint::new(
-                    nearest,
-                    SnapType::Nearest,
-                    Some(entity_id),
-                    min_dist,
-                ));
-            }
+            self.collect_nearest_on_curve(spline, entity_id, mouse, tolerance);
         }
     }
 
@@ -974,6 +959,19 @@ impl SnapEngine {
         line.start + v * b
     }
 
+    /// 曲线上的最近点
+    fn collect_nearest_on_curve(&mut self, curve: &dyn Curve, entity_id: EntityId, mouse: Point2, tolerance: f64) {
+        let nearest = curve.closest_point(&mouse);
+        if nearest.distance <= tolerance {
+            self.candidates.push(SnapPoint::new(
+                nearest.point,
+                SnapType::Nearest,
+                Some(entity_id),
+                nearest.distance,
+            ));
+        }
+    }
+
     /// 计算从参考点到线段的垂足
     fn perpendicular_to_line(&self, line: &Line, ref_point: Point2) -> Option<Point2> {
         let v = line.end - line.start;
@@ -1031,155 +1029,7 @@ impl SnapEngine {
 
     /// 计算两个几何体的交点
     fn find_intersections(&self, geom1: &Geometry, geom2: &Geometry) -> Vec<Point2> {
-        match (geom1, geom2) {
-            (Geometry::Line(l1), Geometry::Line(l2)) => {
-                self.line_line_intersection(l1, l2).into_iter().collect()
-            }
-            (Geometry::Line(line), Geometry::Circle(circle)) 
-            | (Geometry::Circle(circle), Geometry::Line(line)) => {
-                self.line_circle_intersection(line, circle)
-            }
-            (Geometry::Circle(c1), Geometry::Circle(c2)) => {
-                self.circle_circle_intersection(c1, c2)
-            }
-            (Geometry::Line(line), Geometry::Arc(arc))
-            | (Geometry::Arc(arc), Geometry::Line(line)) => {
-                self.line_arc_intersection(line, arc)
-            }
-            // 多段线：展开为线段/弧后计算
-            (Geometry::Line(line), Geometry::Polyline(poly))
-            | (Geometry::Polyline(poly), Geometry::Line(line)) => {
-                self.line_polyline_intersection(line, poly)
-            }
-            // 其他情况暂不处理
-            _ => vec![],
-        }
-    }
-
-    /// 线段-线段交点
-    fn line_line_intersection(&self, l1: &Line, l2: &Line) -> Option<Point2> {
-        let d1 = l1.end - l1.start;
-        let d2 = l2.end - l2.start;
-
-        let cross = d1.x * d2.y - d1.y * d2.x;
-        
-        // 平行
-        if cross.abs() < EPSILON {
-            return None;
-        }
-
-        let d = l2.start - l1.start;
-        let t1 = (d.x * d2.y - d.y * d2.x) / cross;
-        let t2 = (d.x * d1.y - d.y * d1.x) / cross;
-
-        // 检查交点是否在两条线段上
-        if t1 >= 0.0 && t1 <= 1.0 && t2 >= 0.0 && t2 <= 1.0 {
-            Some(l1.start + d1 * t1)
-        } else {
-            None
-        }
-    }
-
-    /// 线段-圆交点
-    fn line_circle_intersection(&self, line: &Line, circle: &Circle) -> Vec<Point2> {
-        let d = line.end - line.start;
-        let f = line.start - circle.center;
-
-        let a = d.dot(&d);
-        let b = 2.0 * f.dot(&d);
-        let c = f.dot(&f) - circle.radius * circle.radius;
-
-        let discriminant = b * b - 4.0 * a * c;
-
-        if discriminant < 0.0 {
-            return vec![];
-        }
-
-        let mut intersections = Vec::new();
-
-        if discriminant.abs() < EPSILON {
-            // 一个交点（相切）
-            let t = -b / (2.0 * a);
-            if t >= 0.0 && t <= 1.0 {
-                intersections.push(line.start + d * t);
-            }
-        } else {
-            // 两个交点
-            let sqrt_disc = discriminant.sqrt();
-            let t1 = (-b - sqrt_disc) / (2.0 * a);
-            let t2 = (-b + sqrt_disc) / (2.0 * a);
-
-            if t1 >= 0.0 && t1 <= 1.0 {
-                intersections.push(line.start + d * t1);
-            }
-            if t2 >= 0.0 && t2 <= 1.0 {
-                intersections.push(line.start + d * t2);
-            }
-        }
-
-        intersections
-    }
-
-    /// 圆-圆交点
-    fn circle_circle_intersection(&self, c1: &Circle, c2: &Circle) -> Vec<Point2> {
-        let d = (c2.center - c1.center).norm();
-
-        // 不相交情况
-        if d > c1.radius + c2.radius || d < (c1.radius - c2.radius).abs() || d < EPSILON {
-            return vec![];
-        }
-
-        let a = (c1.radius * c1.radius - c2.radius * c2.radius + d * d) / (2.0 * d);
-        let h = (c1.radius * c1.radius - a * a).sqrt();
-
-        let p = c1.center + (c2.center - c1.center) * (a / d);
-
-        let dir = (c2.center - c1.center) / d;
-        let perp = Vector2::new(-dir.y, dir.x);
-
-        if h < EPSILON {
-            // 一个交点（相切）
-            vec![p]
-        } else {
-            // 两个交点
-            vec![
-                p + perp * h,
-                p - perp * h,
-            ]
-        }
-    }
-
-    /// 线段-圆弧交点
-    fn line_arc_intersection(&self, line: &Line, arc: &Arc) -> Vec<Point2> {
-        // 先求线段-完整圆的交点，再过滤在弧范围内的
-        let circle = Circle::new(arc.center, arc.radius);
-        let circle_intersections = self.line_circle_intersection(line, &circle);
-
-        circle_intersections
-            .into_iter()
-            .filter(|p| arc.contains_point(p))
-            .collect()
-    }
-
-    /// 线段-多段线交点
-    fn line_polyline_intersection(&self, line: &Line, polyline: &Polyline) -> Vec<Point2> {
-        let mut intersections = Vec::new();
-
-        for i in 0..polyline.segment_count() {
-            let v1 = &polyline.vertices[i];
-            let v2 = &polyline.vertices[(i + 1) % polyline.vertices.len()];
-
-            if v1.bulge.abs() < EPSILON {
-                // 直线段
-                let seg = Line::new(v1.point, v2.point);
-                if let Some(p) = self.line_line_intersection(line, &seg) {
-                    intersections.push(p);
-                }
-            }
-            // TODO: 处理弧线段
-        }
-
-        intersections
+        intersect::intersection_points(geom1, geom2)
     }
 }
 
@@ -1466,33 +1316,6 @@ impl Default for SnapEngine {
     }
 }
 
-// 为Arc添加辅助方法
-impl Arc {
-    /// 检查点是否在弧上（角度范围内）
-    fn contains_point(&self, point: &Point2) -> bool {
-        let angle = (point.y - self.center.y).atan2(point.x - self.center.x);
-        
-        let mut a = angle;
-        let mut start = self.start_angle;
-        let mut end = self.end_angle;
-
-        // 归一化到 [0, 2π)
-        let two_pi = 2.0 * std::f64::consts::PI;
-        while a < 0.0 { a += two_pi; }
-        while start < 0.0 { start += two_pi; }
-        while end < 0.0 { end += two_pi; }
-        a %= two_pi;
-        start %= two_pi;
-        end %= two_pi;
-
-        if start <= end {
-            a >= start && a <= end
-        } else {
-            a >= start || a <= end
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1518,10 +1341,10 @@ mod tests {
         let l1 = Line::new(Point2::new(0.0, 0.0), Point2::new(10.0, 10.0));
         let l2 = Line::new(Point2::new(0.0, 10.0), Point2::new(10.0, 0.0));
 
-        let intersection = engine.line_line_intersection(&l1, &l2);
-        assert!(intersection.is_some());
+        let intersections = engine.find_intersections(&Geometry::Line(l1), &Geometry::Line(l2));
+        assert_eq!(intersections.len(), 1);
 
-        let p = intersection.unwrap();
+        let p = intersections[0];
         assert!((p.x - 5.0).abs() < EPSILON);
         assert!((p.y - 5.0).abs() < EPSILON);
     }
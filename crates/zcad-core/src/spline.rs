@@ -0,0 +1,858 @@
+//! 样条曲线算法
+//!
+//! 基于 NURBS 基函数实现样条曲线的求值与编辑：
+//! - 有理求值及一阶导数
+//! - 拟合点全局插值（弦长参数化，支持起止切向和拟合公差）
+//! - 拟合点表示与控制点表示之间的转换
+//! - 节点插入（Boehm 算法）和升阶
+//! - NURBS 权重夹点
+//!
+//! 拟合点样条以 `fit_points` 为主数据，控制点和节点向量由 [`Spline::refit`] 重新计算。
+//! 与 AutoCAD 一致，直接修改控制点、权重、节点或阶数会丢弃拟合数据，转为控制点样条。
+
+use crate::geometry::{Spline, SplineType};
+use crate::math::{Point2, Vector2, EPSILON};
+use std::borrow::Cow;
+
+/// 齐次坐标控制点 (w·x, w·y, w)
+type Homogeneous = [f64; 3];
+
+/// 拖动权重夹点时允许的曲线点位置比例范围（避免权重趋于 0 或无穷大）
+const WEIGHT_GRIP_LIMIT: f64 = 1e-3;
+
+/// 查找参数 `u` 所在的节点区间 `[knots[span], knots[span + 1])`
+///
+/// `n` 为最后一个控制点的索引，参数超出定义域时返回首/尾非退化区间。
+pub(crate) fn find_span(n: usize, degree: usize, u: f64, knots: &[f64]) -> usize {
+    if u >= knots[n + 1] {
+        let mut span = n;
+        while span > degree && knots[span] >= knots[n + 1] {
+            span -= 1;
+        }
+        return span;
+    }
+    if u <= knots[degree] {
+        let mut span = degree;
+        while span < n && knots[span + 1] <= knots[degree] {
+            span += 1;
+        }
+        return span;
+    }
+
+    let (mut low, mut high) = (degree, n + 1);
+    let mut mid = (low + high) / 2;
+    while u < knots[mid] || u >= knots[mid + 1] {
+        if u < knots[mid] {
+            high = mid;
+        } else {
+            low = mid;
+        }
+        mid = (low + high) / 2;
+    }
+    mid
+}
+
+/// 计算区间 `span` 内非零的基函数 N[span-degree..=span](u)
+pub(crate) fn basis_functions(span: usize, u: f64, degree: usize, knots: &[f64]) -> Vec<f64> {
+    let mut basis = vec![0.0; degree + 1];
+    let mut left = vec![0.0; degree + 1];
+    let mut right = vec![0.0; degree + 1];
+    basis[0] = 1.0;
+
+    for j in 1..=degree {
+        left[j] = u - knots[span + 1 - j];
+        right[j] = knots[span + j] - u;
+        let mut saved = 0.0;
+        for r in 0..j {
+            let denom = right[r + 1] + left[j - r];
+            let temp = if denom.abs() < EPSILON { 0.0 } else { basis[r] / denom };
+            basis[r] = saved + right[r + 1] * temp;
+            saved = left[j - r] * temp;
+        }
+        basis[j] = saved;
+    }
+
+    basis
+}
+
+/// 计算区间 `span` 内非零基函数的一阶导数
+fn basis_derivatives(span: usize, u: f64, degree: usize, knots: &[f64]) -> Vec<f64> {
+    let mut ders = vec![0.0; degree + 1];
+    if degree == 0 {
+        return ders;
+    }
+
+    // N'(i,p) = p/(u[i+p]-u[i])·N(i,p-1) - p/(u[i+p+1]-u[i+1])·N(i+1,p-1)
+    let lower = basis_functions(span, u, degree - 1, knots);
+    let p = degree as f64;
+    for k in 0..=degree {
+        let i = span - degree + k;
+        if k > 0 {
+            let denom = knots[i + degree] - knots[i];
+            if denom > EPSILON {
+                ders[k] += p * lower[k - 1] / denom;
+            }
+        }
+        if k < degree {
+            let denom = knots[i + degree + 1] - knots[i + 1];
+            if denom > EPSILON {
+                ders[k] -= p * lower[k] / denom;
+            }
+        }
+    }
+
+    ders
+}
+
+/// 生成钳位（clamped）均匀节点向量，两端节点重复 degree + 1 次
+pub(crate) fn clamped_uniform_knots(count: usize, degree: usize) -> Vec<f64> {
+    (0..count + degree + 1)
+        .map(|i| {
+            if i <= degree {
+                0.0
+            } else if i >= count {
+                (count - degree) as f64
+            } else {
+                (i - degree) as f64
+            }
+        })
+        .collect()
+}
+
+/// 高斯消元（列主元）求解线性方程组，`rhs` 每行为对应方程的多个右端分量
+fn solve_linear(mut matrix: Vec<Vec<f64>>, mut rhs: Vec<Vec<f64>>) -> Option<Vec<Vec<f64>>> {
+    let n = matrix.len();
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&a, &b| matrix[a][col].abs().total_cmp(&matrix[b][col].abs()))?;
+        if matrix[pivot][col].abs() < 1e-14 {
+            return None;
+        }
+        matrix.swap(col, pivot);
+        rhs.swap(col, pivot);
+
+        let pivot_row = matrix[col].clone();
+        let pivot_rhs = rhs[col].clone();
+        for row in col + 1..n {
+            let factor = matrix[row][col] / pivot_row[col];
+            if factor == 0.0 {
+                continue;
+            }
+            for (value, pivot) in matrix[row].iter_mut().zip(&pivot_row).skip(col) {
+                *value -= factor * pivot;
+            }
+            for (value, pivot) in rhs[row].iter_mut().zip(&pivot_rhs) {
+                *value -= factor * pivot;
+            }
+        }
+    }
+
+    for row in (0..n).rev() {
+        for k in row + 1..n {
+            let factor = matrix[row][k];
+            let solved = rhs[k].clone();
+            for (value, x) in rhs[row].iter_mut().zip(&solved) {
+                *value -= factor * x;
+            }
+        }
+        let diagonal = matrix[row][row];
+        for value in &mut rhs[row] {
+            *value /= diagonal;
+        }
+    }
+
+    Some(rhs)
+}
+
+/// 弦长参数化，参数归一化到 [0, 1]，同时返回总弦长
+fn chord_length_params(points: &[Point2]) -> (Vec<f64>, f64) {
+    let mut params = Vec::with_capacity(points.len());
+    let mut total = 0.0;
+    params.push(0.0);
+    for pair in points.windows(2) {
+        total += (pair[1] - pair[0]).norm();
+        params.push(total);
+    }
+
+    if total < EPSILON {
+        let last = (points.len() - 1).max(1) as f64;
+        return ((0..points.len()).map(|i| i as f64 / last).collect(), 0.0);
+    }
+    for param in &mut params {
+        *param /= total;
+    }
+    (params, total)
+}
+
+/// 平均法生成插值节点向量（Piegl & Tiller 式 9.8）
+fn averaged_knots(params: &[f64], degree: usize) -> Vec<f64> {
+    let count = params.len();
+    let mut knots = vec![0.0; degree + 1];
+    for j in 1..count - degree {
+        knots.push(params[j..j + degree].iter().sum::<f64>() / degree as f64);
+    }
+    knots.extend(std::iter::repeat_n(1.0, degree + 1));
+    knots
+}
+
+/// 最小二乘逼近的节点向量（Piegl & Tiller 式 9.69），保证每个节点区间内至少有一个拟合参数
+fn approximation_knots(params: &[f64], count: usize, degree: usize) -> Vec<f64> {
+    let d = params.len() as f64 / (count - degree) as f64;
+    let mut knots = vec![0.0; degree + 1];
+    for j in 1..count - degree {
+        let x = j as f64 * d;
+        let i = x as usize;
+        let alpha = x - i as f64;
+        knots.push((1.0 - alpha) * params[i - 1] + alpha * params[i]);
+    }
+    knots.extend(std::iter::repeat_n(1.0, degree + 1));
+    knots
+}
+
+/// 拟合的输入数据
+struct FitInput<'a> {
+    points: &'a [Point2],
+    params: &'a [f64],
+    degree: usize,
+    /// 起点导数（已按总弦长缩放）
+    start_derivative: Option<Vector2>,
+    /// 终点导数（已按总弦长缩放）
+    end_derivative: Option<Vector2>,
+}
+
+impl FitInput<'_> {
+    /// 在给定节点向量下求解 `count` 个控制点
+    ///
+    /// 首末控制点与首末拟合点重合，给定切向时第二个/倒数第二个控制点由端点导数确定，
+    /// 其余控制点使内部拟合点的误差平方和最小（控制点数与方程数相同时即为精确插值）。
+    fn solve(&self, knots: &[f64], count: usize) -> Option<Vec<Point2>> {
+        let degree = self.degree;
+        let first = self.points[0];
+        let last = *self.points.last()?;
+
+        let mut fixed: Vec<Option<Point2>> = vec![None; count];
+        fixed[0] = Some(first);
+        fixed[count - 1] = Some(last);
+        if let Some(d) = self.start_derivative {
+            fixed[1] = Some(first + d * (knots[degree + 1] - knots[1]) / degree as f64);
+        }
+        if let Some(d) = self.end_derivative {
+            fixed[count - 2] = Some(last - d * (knots[count - 1 + degree] - knots[count - 1]) / degree as f64);
+        }
+
+        let free: Vec<usize> = (0..count).filter(|&i| fixed[i].is_none()).collect();
+        if free.is_empty() {
+            return fixed.into_iter().collect();
+        }
+        let column = |i: usize| free.iter().position(|&f| f == i);
+
+        let mut rows = Vec::new();
+        let mut rhs = Vec::new();
+        for k in 1..self.points.len() - 1 {
+            let u = self.params[k];
+            let span = find_span(count - 1, degree, u, knots);
+            let basis = basis_functions(span, u, degree, knots);
+            let mut row = vec![0.0; free.len()];
+            let mut target = self.points[k].coords;
+            for (j, value) in basis.iter().enumerate() {
+                let i = span - degree + j;
+                match fixed[i] {
+                    Some(p) => target -= p.coords * *value,
+                    None => row[column(i)?] = *value,
+                }
+            }
+            rows.push(row);
+            rhs.push(vec![target.x, target.y]);
+        }
+        if rows.len() < free.len() {
+            return None;
+        }
+
+        let solution = if rows.len() == free.len() {
+            solve_linear(rows, rhs)?
+        } else {
+            // 法方程 AᵀA·P = AᵀQ
+            let m = free.len();
+            let mut normal = vec![vec![0.0; m]; m];
+            let mut normal_rhs = vec![vec![0.0; 2]; m];
+            for (row, target) in rows.iter().zip(&rhs) {
+                for a in 0..m {
+                    if row[a] == 0.0 {
+                        continue;
+                    }
+                    for b in 0..m {
+                        normal[a][b] += row[a] * row[b];
+                    }
+                    normal_rhs[a][0] += row[a] * target[0];
+                    normal_rhs[a][1] += row[a] * target[1];
+                }
+            }
+            solve_linear(normal, normal_rhs)?
+        };
+
+        for (i, value) in free.iter().zip(solution) {
+            fixed[*i] = Some(Point2::new(value[0], value[1]));
+        }
+        fixed.into_iter().collect()
+    }
+
+    /// 拟合点到曲线（在对应参数处）的最大偏差
+    fn max_deviation(&self, control_points: &[Point2], knots: &[f64]) -> f64 {
+        let mut spline = Spline::new(self.degree as u8);
+        spline.control_points = control_points.to_vec();
+        spline.knots = knots.to_vec();
+        self.points
+            .iter()
+            .zip(self.params)
+            .map(|(p, u)| (spline.point_at_param(*u) - p).norm())
+            .fold(0.0, f64::max)
+    }
+}
+
+impl Spline {
+    /// 从拟合点创建样条（全局插值，曲线经过所有拟合点）
+    ///
+    /// 闭合样条会在首尾拟合点处保持切向连续。
+    pub fn from_fit_points(fit_points: Vec<Point2>, degree: u8, closed: bool) -> Self {
+        let mut spline = Self::new(degree);
+        spline.fit_points = fit_points;
+        spline.closed = closed;
+        spline.refit();
+        spline
+    }
+
+    /// 设置起止切向（仅方向有效，`None` 表示由拟合点自动确定）
+    pub fn with_end_tangents(mut self, start: Option<Vector2>, end: Option<Vector2>) -> Self {
+        self.start_tangent = start;
+        self.end_tangent = end;
+        self.refit();
+        self
+    }
+
+    /// 设置拟合公差（0 表示曲线精确经过拟合点，大于 0 时使用尽量少的控制点逼近）
+    pub fn with_fit_tolerance(mut self, tolerance: f64) -> Self {
+        self.fit_tolerance = tolerance.max(0.0);
+        self.refit();
+        self
+    }
+
+    /// 是否为拟合点样条
+    pub fn is_fit_spline(&self) -> bool {
+        !self.fit_points.is_empty()
+    }
+
+    /// 是否为有理样条（存在不为 1 的权重）
+    pub fn is_rational(&self) -> bool {
+        self.weights.iter().any(|w| (w - 1.0).abs() > EPSILON)
+    }
+
+    /// 控制点 `index` 的权重（未设置时为 1）
+    pub fn weight(&self, index: usize) -> f64 {
+        self.weights.get(index).copied().filter(|w| *w > 0.0).unwrap_or(1.0)
+    }
+
+    /// 设置控制点权重，样条转为 NURBS 控制点样条
+    pub fn set_weight(&mut self, index: usize, weight: f64) {
+        if index >= self.control_points.len() || weight <= 0.0 {
+            return;
+        }
+        let count = self.control_points.len();
+        self.weights.resize(count, 1.0);
+        self.weights[index] = weight;
+        self.spline_type = SplineType::Nurbs;
+        self.clear_fit_data();
+    }
+
+    /// 由拟合点重新计算控制点和节点向量
+    ///
+    /// 拟合点不足两个时不做修改。
+    pub fn refit(&mut self) {
+        let mut points = self.fit_points.clone();
+        points.dedup_by(|a, b| (*a - *b).norm() < EPSILON);
+        if points.len() < 2 {
+            return;
+        }
+
+        let mut start_tangent = self.start_tangent;
+        let mut end_tangent = self.end_tangent;
+        if self.closed && points.len() > 2 {
+            // 闭合样条：首点追加到末尾，并使首尾切向一致
+            if (points[0] - points[points.len() - 1]).norm() >= EPSILON {
+                points.push(points[0]);
+            }
+            let n = points.len();
+            let tangent = start_tangent.or(end_tangent).unwrap_or(points[1] - points[n - 2]);
+            start_tangent = Some(tangent);
+            end_tangent = Some(tangent);
+        }
+
+        let (params, total) = chord_length_params(&points);
+        let derivative = |t: Option<Vector2>| t.filter(|v| v.norm() > EPSILON).map(|v| v.normalize() * total);
+        let mut start_derivative = derivative(start_tangent);
+        let mut end_derivative = derivative(end_tangent);
+
+        let constraints = |s: &Option<Vector2>, e: &Option<Vector2>| s.is_some() as usize + e.is_some() as usize;
+        let mut degree = (self.degree.max(1) as usize).min(points.len() - 1 + constraints(&start_derivative, &end_derivative));
+        if degree < 2 {
+            // 一次样条无法同时满足切向约束
+            start_derivative = None;
+            end_derivative = None;
+            degree = 1;
+        }
+        let extra = constraints(&start_derivative, &end_derivative);
+
+        let input = FitInput {
+            points: &points,
+            params: &params,
+            degree,
+            start_derivative,
+            end_derivative,
+        };
+
+        let interpolation_count = points.len() + extra;
+        let mut result = None;
+        if self.fit_tolerance > 0.0 {
+            for count in (degree + 1).max(2 + extra)..interpolation_count {
+                let knots = approximation_knots(&params, count, degree);
+                if let Some(control_points) = input.solve(&knots, count) {
+                    if input.max_deviation(&control_points, &knots) <= self.fit_tolerance {
+                        result = Some((control_points, knots));
+                        break;
+                    }
+                }
+            }
+        }
+        if result.is_none() {
+            // 端点给定导数时，对应参数在平均法中重复一次
+            let mut augmented = params.clone();
+            if start_derivative.is_some() {
+                augmented.insert(0, 0.0);
+            }
+            if end_derivative.is_some() {
+                augmented.push(1.0);
+            }
+            let knots = averaged_knots(&augmented, degree);
+            result = input.solve(&knots, interpolation_count).map(|control_points| (control_points, knots));
+        }
+
+        if let Some((control_points, knots)) = result {
+            self.control_points = control_points;
+            self.knots = knots;
+            self.weights.clear();
+            self.degree = degree as u8;
+            if self.spline_type == SplineType::Nurbs {
+                self.spline_type = SplineType::BSpline;
+            }
+        }
+    }
+
+    /// 转换为控制点样条（保持曲线形状，丢弃拟合数据）
+    pub fn to_control_point_spline(&self) -> Spline {
+        let mut spline = self.clone();
+        spline.clear_fit_data();
+        spline
+    }
+
+    /// 转换为拟合点样条
+    ///
+    /// 将每个节点区间按阶数等分，以等分点处的曲线点作为拟合点，起止切向取曲线端点导数方向；
+    /// 重新插值得到的曲线与原曲线在拟合点处重合，其余位置为近似。
+    pub fn to_fit_point_spline(&self) -> Spline {
+        if self.is_fit_spline() || self.control_points.len() < 2 {
+            return self.clone();
+        }
+
+        let degree = self.eval_degree();
+        let knots = self.knot_vector();
+        let (start, end) = self.param_range();
+        let mut breaks: Vec<f64> = knots[degree..knots.len() - degree].to_vec();
+        breaks.dedup_by(|a, b| (*a - *b).abs() < EPSILON);
+        let mut params = vec![start];
+        for pair in breaks.windows(2) {
+            params.extend((1..=degree).map(|i| pair[0] + (pair[1] - pair[0]) * i as f64 / degree as f64));
+        }
+
+        let mut fit_points: Vec<Point2> = params.iter().map(|u| self.point_at_param(*u)).collect();
+        let mut spline = Spline::new(self.degree);
+        spline.closed = self.closed;
+        if self.closed && fit_points.len() > 2 && (fit_points[0] - fit_points[fit_points.len() - 1]).norm() < EPSILON {
+            fit_points.pop();
+        } else {
+            spline.start_tangent = Some(self.derivative_at_param(start));
+            spline.end_tangent = Some(self.derivative_at_param(end));
+        }
+        spline.fit_points = fit_points;
+        spline.refit();
+        spline
+    }
+
+    /// 在参数 `u` 处插入一个节点（Boehm 算法），曲线形状不变
+    ///
+    /// 参数不在定义域内部或该节点重数已达阶数时返回 false。
+    pub fn insert_knot(&mut self, u: f64) -> bool {
+        if self.control_points.len() < 2 {
+            return false;
+        }
+        let degree = self.eval_degree();
+        let mut knots = self.knot_vector().into_owned();
+        let n = self.control_points.len() - 1;
+        if u <= knots[degree] + EPSILON || u >= knots[n + 1] - EPSILON {
+            return false;
+        }
+        let multiplicity = knots.iter().filter(|k| (*k - u).abs() < EPSILON).count();
+        if multiplicity >= degree {
+            return false;
+        }
+
+        let span = find_span(n, degree, u, &knots);
+        let old = self.homogeneous_points();
+        let points: Vec<Homogeneous> = (0..=n + 1)
+            .map(|i| {
+                if i + degree <= span {
+                    old[i]
+                } else if i > span {
+                    old[i - 1]
+                } else {
+                    let alpha = (u - knots[i]) / (knots[i + degree] - knots[i]);
+                    std::array::from_fn(|c| alpha * old[i][c] + (1.0 - alpha) * old[i - 1][c])
+                }
+            })
+            .collect();
+        knots.insert(span + 1, u);
+
+        self.set_homogeneous_points(&points);
+        self.knots = knots;
+        self.degree = degree as u8;
+        self.clear_fit_data();
+        true
+    }
+
+    /// 升高一阶，曲线形状不变
+    ///
+    /// 各内部节点重数加一，在新节点向量的 Greville 横坐标处对原曲线（齐次坐标）插值。
+    pub fn elevate_degree(&mut self) -> bool {
+        if self.control_points.len() < 2 {
+            return false;
+        }
+        let degree = self.eval_degree();
+        let knots = self.knot_vector().into_owned();
+        let n = self.control_points.len() - 1;
+        let (start, end) = (knots[degree], knots[n + 1]);
+
+        let new_degree = degree + 1;
+        let mut new_knots = vec![start; new_degree + 1];
+        let mut i = degree + 1;
+        while i <= n {
+            let value = knots[i];
+            let multiplicity = knots[i..=n].iter().take_while(|k| (*k - value).abs() < EPSILON).count();
+            if value > start + EPSILON && value < end - EPSILON {
+                new_knots.extend(std::iter::repeat_n(value, multiplicity + 1));
+            }
+            i += multiplicity;
+        }
+        new_knots.extend(std::iter::repeat_n(end, new_degree + 1));
+
+        let count = new_knots.len() - new_degree - 1;
+        let mut matrix = Vec::with_capacity(count);
+        let mut rhs = Vec::with_capacity(count);
+        for i in 0..count {
+            let xi = new_knots[i + 1..=i + new_degree].iter().sum::<f64>() / new_degree as f64;
+            let span = find_span(count - 1, new_degree, xi, &new_knots);
+            let basis = basis_functions(span, xi, new_degree, &new_knots);
+            let mut row = vec![0.0; count];
+            row[span - new_degree..=span].copy_from_slice(&basis);
+            matrix.push(row);
+            rhs.push(self.homogeneous_at(xi).to_vec());
+        }
+
+        let Some(solution) = solve_linear(matrix, rhs) else {
+            return false;
+        };
+        let points: Vec<Homogeneous> = solution.iter().map(|p| [p[0], p[1], p[2]]).collect();
+        self.set_homogeneous_points(&points);
+        self.knots = new_knots;
+        self.degree = new_degree as u8;
+        self.clear_fit_data();
+        true
+    }
+
+    /// 控制点 `index` 的权重夹点位置
+    ///
+    /// 位于该控制点 Greville 参数处的曲线点；改变权重时该点沿直线向控制点移动。
+    /// 曲线点与控制点重合（如钳位样条的端点）时权重无法通过拖动调整，返回 `None`。
+    pub fn weight_grip_point(&self, index: usize) -> Option<Point2> {
+        self.weight_line(index).map(|(_, param, _, _)| self.point_at_param(param))
+    }
+
+    /// 根据权重夹点的拖动目标位置计算控制点 `index` 的新权重
+    pub fn weight_from_grip(&self, index: usize, target: Point2) -> Option<f64> {
+        let (base, _, basis, rest) = self.weight_line(index)?;
+        let direction = self.control_points[index] - base;
+        let length_sq = direction.norm_squared();
+        if length_sq < EPSILON {
+            return None;
+        }
+
+        // C(w) = Q + s·(P - Q)，其中 s = N·w / (R + N·w)
+        let s = ((target - base).dot(&direction) / length_sq).clamp(WEIGHT_GRIP_LIMIT, 1.0 - WEIGHT_GRIP_LIMIT);
+        Some(s * rest / (basis * (1.0 - s)))
+    }
+
+    /// 控制点 `index` 的权重调整直线：(权重为 0 时的曲线点 Q, Greville 参数, 基函数值 N, 其余项权重和 R)
+    fn weight_line(&self, index: usize) -> Option<(Point2, f64, f64, f64)> {
+        if index >= self.control_points.len() || self.control_points.len() < 2 {
+            return None;
+        }
+        let degree = self.eval_degree();
+        let knots = self.knot_vector();
+        let n = self.control_points.len() - 1;
+        let (start, end) = (knots[degree], knots[n + 1]);
+        let param = (knots[index + 1..=index + degree].iter().sum::<f64>() / degree as f64).clamp(start, end);
+
+        let span = find_span(n, degree, param, &knots);
+        let basis = basis_functions(span, param, degree, &knots);
+        let mut own = 0.0;
+        let mut rest = 0.0;
+        let mut sum = Vector2::zeros();
+        for (k, value) in basis.iter().enumerate() {
+            let i = span - degree + k;
+            if i == index {
+                own = *value;
+            } else {
+                let w = value * self.weight(i);
+                rest += w;
+                sum += self.control_points[i].coords * w;
+            }
+        }
+        if own < EPSILON || rest < EPSILON {
+            return None;
+        }
+        Some((Point2::from(sum / rest), param, own, rest))
+    }
+
+    /// 参数 t 处的一阶导数
+    pub fn derivative_at_param(&self, t: f64) -> Vector2 {
+        if self.control_points.len() < 2 {
+            return Vector2::zeros();
+        }
+        self.evaluate(t).1
+    }
+
+    /// 求值时使用的阶数（不超过控制点数减一）
+    pub(crate) fn eval_degree(&self) -> usize {
+        (self.degree.max(1) as usize).min(self.control_points.len().saturating_sub(1).max(1))
+    }
+
+    /// 求值时使用的节点向量，节点数与控制点数不匹配时使用钳位均匀节点
+    pub(crate) fn knot_vector(&self) -> Cow<'_, [f64]> {
+        let degree = self.eval_degree();
+        let count = self.control_points.len().max(degree + 1);
+        let valid = self.knots.len() == count + degree + 1
+            && self.knots.windows(2).all(|w| w[0] <= w[1])
+            && self.knots[count] > self.knots[degree];
+        if valid {
+            Cow::Borrowed(&self.knots)
+        } else {
+            Cow::Owned(clamped_uniform_knots(count, degree))
+        }
+    }
+
+    /// 计算参数 t 处的点和一阶导数（有理基函数）
+    pub(crate) fn evaluate(&self, t: f64) -> (Point2, Vector2) {
+        let degree = self.eval_degree();
+        let knots = self.knot_vector();
+        let n = self.control_points.len() - 1;
+        let u = t.clamp(knots[degree], knots[n + 1]);
+        let span = find_span(n, degree, u, &knots);
+        let basis = basis_functions(span, u, degree, &knots);
+        let ders = basis_derivatives(span, u, degree, &knots);
+
+        let (mut a, mut w) = (Vector2::zeros(), 0.0);
+        let (mut da, mut dw) = (Vector2::zeros(), 0.0);
+        for k in 0..=degree {
+            let i = span - degree + k;
+            let weight = self.weight(i);
+            let p = self.control_points[i].coords;
+            a += p * (basis[k] * weight);
+            w += basis[k] * weight;
+            da += p * (ders[k] * weight);
+            dw += ders[k] * weight;
+        }
+        if w.abs() < EPSILON {
+            return (self.control_points[span], Vector2::zeros());
+        }
+
+        let point = a / w;
+        (Point2::from(point), (da - point * dw) / w)
+    }
+
+    /// 参数 u 处的齐次坐标点
+    fn homogeneous_at(&self, u: f64) -> Homogeneous {
+        let degree = self.eval_degree();
+        let knots = self.knot_vector();
+        let n = self.control_points.len() - 1;
+        let span = find_span(n, degree, u, &knots);
+        let basis = basis_functions(span, u, degree, &knots);
+        let points = self.homogeneous_points();
+        let mut result = [0.0; 3];
+        for (k, value) in basis.iter().enumerate() {
+            for (c, component) in result.iter_mut().enumerate() {
+                *component += value * points[span - degree + k][c];
+            }
+        }
+        result
+    }
+
+    fn homogeneous_points(&self) -> Vec<Homogeneous> {
+        self.control_points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let w = self.weight(i);
+                [p.x * w, p.y * w, w]
+            })
+            .collect()
+    }
+
+    fn set_homogeneous_points(&mut self, points: &[Homogeneous]) {
+        let rational = self.is_rational();
+        self.control_points = points.iter().map(|p| Point2::new(p[0] / p[2], p[1] / p[2])).collect();
+        self.weights = if rational { points.iter().map(|p| p[2]).collect() } else { Vec::new() };
+    }
+
+    /// 丢弃拟合数据（拟合点、端点切向、拟合公差）
+    fn clear_fit_data(&mut self) {
+        self.fit_points.clear();
+        self.start_tangent = None;
+        self.end_tangent = None;
+        self.fit_tolerance = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: Point2, b: Point2, tolerance: f64) {
+        assert!((a - b).norm() < tolerance, "{:?} != {:?}", a, b);
+    }
+
+    fn sample_curve() -> Spline {
+        Spline::from_control_points(
+            vec![
+                Point2::new(0.0, 0.0),
+                Point2::new(10.0, 20.0),
+                Point2::new(30.0, -10.0),
+                Point2::new(45.0, 15.0),
+                Point2::new(60.0, 0.0),
+            ],
+            3,
+            false,
+        )
+    }
+
+    #[test]
+    fn test_fit_points_interpolation() {
+        let points = vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(10.0, 8.0),
+            Point2::new(25.0, 3.0),
+            Point2::new(40.0, 12.0),
+            Point2::new(50.0, 0.0),
+        ];
+        let spline = Spline::from_fit_points(points.clone(), 3, false);
+        assert_eq!(spline.control_points.len(), points.len());
+
+        // 曲线经过所有拟合点
+        for p in &points {
+            assert!(spline.distance_to_point(p) < 0.05, "{:?}", p);
+        }
+
+        // 端点切向
+        let spline = spline.with_end_tangents(Some(Vector2::new(0.0, 1.0)), Some(Vector2::new(1.0, 0.0)));
+        assert_eq!(spline.control_points.len(), points.len() + 2);
+        let (start, end) = spline.param_range();
+        assert_close(spline.point_at_param(start), points[0], 1e-9);
+        assert_close(spline.point_at_param(end), points[4], 1e-9);
+        let d0 = spline.derivative_at_param(start).normalize();
+        let d1 = spline.derivative_at_param(end).normalize();
+        assert!((d0 - Vector2::new(0.0, 1.0)).norm() < 1e-9);
+        assert!((d1 - Vector2::new(1.0, 0.0)).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_fit_tolerance_reduces_control_points() {
+        let points: Vec<Point2> = (0..=20)
+            .map(|i| {
+                let x = i as f64;
+                Point2::new(x, (x * 0.3).sin() * 5.0)
+            })
+            .collect();
+        let exact = Spline::from_fit_points(points.clone(), 3, false);
+        let approx = Spline::from_fit_points(points.clone(), 3, false).with_fit_tolerance(0.05);
+        assert!(approx.control_points.len() < exact.control_points.len());
+        for p in &points {
+            assert!(approx.distance_to_point(p) < 0.06);
+        }
+        assert_close(approx.control_points[0], points[0], 1e-9);
+    }
+
+    #[test]
+    fn test_fit_and_control_conversion() {
+        let fit = Spline::from_fit_points(
+            vec![Point2::new(0.0, 0.0), Point2::new(5.0, 5.0), Point2::new(10.0, 0.0), Point2::new(15.0, 5.0)],
+            3,
+            false,
+        );
+        let control = fit.to_control_point_spline();
+        assert!(!control.is_fit_spline());
+        assert_eq!(control.control_points, fit.control_points);
+
+        let curve = sample_curve();
+        let refitted = curve.to_fit_point_spline();
+        assert!(refitted.is_fit_spline());
+        for p in &refitted.fit_points {
+            assert!(curve.distance_to_point(p) < 0.05);
+        }
+        for p in curve.sample_points(16) {
+            assert!(refitted.distance_to_point(&p) < 1.0);
+        }
+    }
+
+    #[test]
+    fn test_knot_insertion_and_degree_elevation_keep_shape() {
+        let mut curve = sample_curve();
+        curve.set_weight(2, 3.0);
+        let (start, end) = curve.param_range();
+        let params: Vec<f64> = (0..=10).map(|i| start + (end - start) * i as f64 / 10.0).collect();
+        let original: Vec<Point2> = params.iter().map(|u| curve.point_at_param(*u)).collect();
+
+        let mut inserted = curve.clone();
+        assert!(inserted.insert_knot(start + (end - start) * 0.3));
+        assert_eq!(inserted.control_points.len(), curve.control_points.len() + 1);
+        for (u, p) in params.iter().zip(&original) {
+            assert_close(inserted.point_at_param(*u), *p, 1e-9);
+        }
+
+        let mut elevated = curve.clone();
+        assert!(elevated.elevate_degree());
+        assert_eq!(elevated.degree, 4);
+        for (u, p) in params.iter().zip(&original) {
+            assert_close(elevated.point_at_param(*u), *p, 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_weight_grip() {
+        let mut curve = sample_curve();
+        curve.spline_type = SplineType::Nurbs;
+        assert!(curve.weight_grip_point(0).is_none());
+
+        let grip = curve.weight_grip_point(2).unwrap();
+        let target = grip + (curve.control_points[2] - grip) * 0.5;
+        let weight = curve.weight_from_grip(2, target).unwrap();
+        assert!(weight > 1.0);
+
+        curve.set_weight(2, weight);
+        assert_close(curve.weight_grip_point(2).unwrap(), target, 1e-9);
+    }
+}
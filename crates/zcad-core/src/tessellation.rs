@@ -0,0 +1,510 @@
+//! 几何体细分
+//!
+//! 将曲线几何体离散为折线。GPU 渲染器、egui 绘制、软件光栅化器和 SVG 导出共用这里的结果，
+//! 保证屏幕显示与导出图像一致。
+//!
+//! 离散精度由弦高容差（绘图单位）决定：折线与曲线之间的最大偏差不超过容差。
+//! 屏幕显示按 [`screen_tolerance`] 由每像素对应的绘图单位换算，打印输出按 [`plot_tolerance`]
+//! 由输出分辨率和出图比例换算；没有视图信息的几何运算使用 [`DEFAULT_TOLERANCE`]。
+//! 渲染端通过 [`TessellationCache`] 按实体缓存结果，实体修订号和容差档位不变时不重复细分。
+//!
+//! 每条折线至少包含两个点；闭合曲线的最后一个点与第一个点重合。
+//! 点、文本和标注不在此处理，由各渲染端自行绘制；块参照需先经 `BlockTable::resolve` 展开。
+//! 形位公差输出框格、符号和引线的线条，多行文字输出下划线和分数线，其中的文字同样由渲染端绘制。
+//! 图案填充输出裁剪后的图案线（见 [`crate::hatch_pattern`]），实心填充输出边界环。
+
+use crate::curve::{Curve, PolylineSegment};
+use crate::entity::{Entity, EntityId};
+use crate::geometry::{
+    Arc, Circle, Ellipse, Geometry, Hatch, HatchBoundary, HatchBoundaryElement, Leader, Polyline, Spline,
+};
+use crate::hatch_pattern;
+use crate::math::{Point2, Vector2, EPSILON};
+use std::collections::HashMap;
+use std::f64::consts::TAU;
+
+/// 没有视图或输出分辨率时（几何运算、DXF 导出等）使用的弦高容差（绘图单位）
+pub const DEFAULT_TOLERANCE: f64 = 0.01;
+
+/// 屏幕显示允许的弦高偏差（像素）
+const PIXEL_TOLERANCE: f64 = 0.5;
+
+/// 每整圈的最少段数，容差大于半径时曲线也不会退化成几条折线
+const MIN_SEGMENTS_PER_TURN: f64 = 8.0;
+
+/// 单条圆弧、椭圆弧的最多段数，防止极度放大时点数失控
+const MAX_SEGMENTS: usize = 4096;
+
+/// 样条每个节点区间的初始等分数，避免 S 形区间的中点恰好落在弦上而漏细分
+const SPAN_SUBDIVISIONS: usize = 4;
+
+/// 样条自适应细分的最大递归深度
+const MAX_SUBDIVISION_DEPTH: u32 = 10;
+
+const MM_PER_INCH: f64 = 25.4;
+
+/// 屏幕显示的弦高容差
+///
+/// `units_per_pixel` 为一个像素对应的绘图单位（见 `Camera2D::units_per_pixel`），偏差不超过半个像素
+pub fn screen_tolerance(units_per_pixel: f64) -> f64 {
+    units_per_pixel * PIXEL_TOLERANCE
+}
+
+/// 打印输出的弦高容差
+///
+/// `dpi` 为输出分辨率，`scale` 为每个绘图单位对应的纸面毫米数，偏差不超过半个输出像素
+pub fn plot_tolerance(dpi: f64, scale: f64) -> f64 {
+    screen_tolerance(MM_PER_INCH / dpi / scale)
+}
+
+/// 按容差细分几何体为折线列表
+pub fn tessellate(geometry: &Geometry, tolerance: f64) -> Vec<Vec<Point2>> {
+    let paths = match geometry {
+        Geometry::Line(line) => vec![vec![line.start, line.end]],
+        Geometry::Circle(circle) => vec![circle_points(circle, tolerance)],
+        Geometry::Arc(arc) => vec![arc_geometry_points(arc, tolerance)],
+        Geometry::Polyline(polyline) => vec![polyline_points(polyline, tolerance)],
+        Geometry::Ellipse(ellipse) => vec![ellipse_points(ellipse, tolerance)],
+        Geometry::Spline(spline) => vec![spline_points(spline, tolerance)],
+        Geometry::Hatch(hatch) => hatch_paths(hatch, tolerance),
+        Geometry::Leader(leader) => leader_paths(leader),
+        Geometry::Tolerance(gdt) => gdt.explode().iter().flat_map(|part| tessellate(part, tolerance)).collect(),
+        Geometry::MText(mtext) => mtext.explode().iter().flat_map(|part| tessellate(part, tolerance)).collect(),
+        Geometry::Point(_) | Geometry::Text(_) | Geometry::Dimension(_) | Geometry::Insert(_) => Vec::new(),
+    };
+    paths.into_iter().filter(|p| p.len() >= 2).collect()
+}
+
+/// 半径为 `radius`、圆心角为 `sweep` 的圆弧满足弦高容差所需的段数
+///
+/// 每段圆心角 θ 的弦高为 r·(1 - cos(θ/2))
+pub fn arc_segment_count(radius: f64, sweep: f64, tolerance: f64) -> usize {
+    let sweep = sweep.abs();
+    let min = (sweep / TAU * MIN_SEGMENTS_PER_TURN).ceil().max(1.0);
+    let count = if tolerance > 0.0 && tolerance < radius {
+        (sweep / (2.0 * (1.0 - tolerance / radius).acos())).ceil()
+    } else if tolerance > 0.0 {
+        min
+    } else {
+        MAX_SEGMENTS as f64
+    };
+    (count.max(min) as usize).min(MAX_SEGMENTS)
+}
+
+/// 整圆（闭合）
+pub fn circle_points(circle: &Circle, tolerance: f64) -> Vec<Point2> {
+    let segments = arc_segment_count(circle.radius, TAU, tolerance);
+    let angle_step = TAU / segments as f64;
+    let mut points: Vec<Point2> = (0..segments)
+        .map(|i| circle.point_at_angle(i as f64 * angle_step))
+        .collect();
+    points.push(points[0]);
+    points
+}
+
+/// 圆弧，`sweep` 带符号（正值为逆时针）
+pub fn arc_points(center: Point2, radius: f64, start_angle: f64, sweep: f64, tolerance: f64) -> Vec<Point2> {
+    let segments = arc_segment_count(radius, sweep, tolerance);
+    let angle_step = sweep / segments as f64;
+    (0..=segments)
+        .map(|i| {
+            let angle = start_angle + i as f64 * angle_step;
+            Point2::new(center.x + radius * angle.cos(), center.y + radius * angle.sin())
+        })
+        .collect()
+}
+
+fn arc_geometry_points(arc: &Arc, tolerance: f64) -> Vec<Point2> {
+    arc_points(arc.center, arc.radius, arc.start_angle, arc.sweep_angle(), tolerance)
+}
+
+/// 带凸度的多段线线段（包含起点和终点）
+pub fn bulge_points(p1: Point2, p2: Point2, bulge: f64, tolerance: f64) -> Vec<Point2> {
+    segment_points(&PolylineSegment::from_bulge(p1, p2, bulge), tolerance)
+}
+
+/// 多段线的一段（包含起点和终点）
+fn segment_points(segment: &PolylineSegment, tolerance: f64) -> Vec<Point2> {
+    if !segment.is_arc() {
+        return vec![segment.start, segment.end];
+    }
+    let segments = arc_segment_count(segment.radius, segment.sweep, tolerance);
+    let mut points: Vec<Point2> = (0..segments).map(|i| segment.point(i as f64 / segments as f64)).collect();
+    // 终点使用原始顶点，避免累积误差造成缝隙
+    points.push(segment.end);
+    points
+}
+
+/// 多段线（闭合时首尾相接）
+pub fn polyline_points(polyline: &Polyline, tolerance: f64) -> Vec<Point2> {
+    if polyline.vertices.len() < 2 {
+        return Vec::new();
+    }
+
+    let mut points = vec![polyline.vertices[0].point];
+    for segment in polyline.segments() {
+        points.extend(segment_points(&segment, tolerance).into_iter().skip(1));
+    }
+    points
+}
+
+/// 椭圆或椭圆弧
+///
+/// 椭圆是半径为长半轴的圆的仿射像，按参数等分时弦高不超过同参数步长下该圆的弦高
+pub fn ellipse_points(ellipse: &Ellipse, tolerance: f64) -> Vec<Point2> {
+    let sweep = ellipse.end_param - ellipse.start_param;
+    ellipse.sample_points(arc_segment_count(ellipse.major_radius(), sweep, tolerance))
+}
+
+/// 样条，在每个节点区间内按弦高自适应细分
+pub fn spline_points(spline: &Spline, tolerance: f64) -> Vec<Point2> {
+    if spline.control_points.len() < 2 {
+        return Vec::new();
+    }
+
+    let breaks = Curve::breakpoints(spline);
+    let mut points = vec![Curve::point_at_param(spline, breaks[0])];
+    for span in breaks.windows(2) {
+        let step = (span[1] - span[0]) / SPAN_SUBDIVISIONS as f64;
+        for i in 0..SPAN_SUBDIVISIONS {
+            let t0 = span[0] + i as f64 * step;
+            let t1 = t0 + step;
+            let start = (t0, points[points.len() - 1]);
+            subdivide(spline, start, (t1, Curve::point_at_param(spline, t1)), tolerance, 0, &mut points);
+        }
+    }
+    points
+}
+
+/// 细分参数区间（参数与对应点），中点偏离弦超过容差时二分，结果不含起点
+fn subdivide(
+    curve: &dyn Curve,
+    start: (f64, Point2),
+    end: (f64, Point2),
+    tolerance: f64,
+    depth: u32,
+    out: &mut Vec<Point2>,
+) {
+    let tm = (start.0 + end.0) / 2.0;
+    let mid = (tm, curve.point_at_param(tm));
+    if depth < MAX_SUBDIVISION_DEPTH && chord_deviation(mid.1, start.1, end.1) > tolerance {
+        subdivide(curve, start, mid, tolerance, depth + 1, out);
+        subdivide(curve, mid, end, tolerance, depth + 1, out);
+    } else {
+        out.push(end.1);
+    }
+}
+
+/// 点到弦（线段）的距离
+fn chord_deviation(point: Point2, start: Point2, end: Point2) -> f64 {
+    let chord = end - start;
+    let len_sq = chord.norm_squared();
+    if len_sq < EPSILON * EPSILON {
+        return (point - start).norm();
+    }
+    let t = ((point - start).dot(&chord) / len_sq).clamp(0.0, 1.0);
+    (point - (start + chord * t)).norm()
+}
+
+/// 填充边界环
+///
+/// 边界元素的方向可能不一致，这里按端点首尾相接地排列，每个环显式闭合
+pub fn hatch_loops(hatch: &Hatch, tolerance: f64) -> Vec<Vec<Point2>> {
+    hatch
+        .boundaries
+        .iter()
+        .map(|boundary| boundary_ring(boundary, tolerance))
+        .filter(|ring| ring.len() >= 3)
+        .collect()
+}
+
+/// 单个填充边界环（显式闭合，退化的边界可能少于 3 个点）
+pub fn boundary_ring(boundary: &HatchBoundary, tolerance: f64) -> Vec<Point2> {
+    let mut ring: Vec<Point2> = Vec::new();
+    for element in &boundary.elements {
+        let mut points = match element {
+            HatchBoundaryElement::Line(line) => vec![line.start, line.end],
+            HatchBoundaryElement::Arc(arc) => arc_geometry_points(arc, tolerance),
+            HatchBoundaryElement::Ellipse(ellipse) => ellipse_points(ellipse, tolerance),
+            HatchBoundaryElement::Spline(spline) => spline_points(spline, tolerance),
+        };
+        let (Some(first), Some(last)) = (points.first().copied(), points.last().copied()) else {
+            continue;
+        };
+        if let Some(end) = ring.last().copied() {
+            if (first - end).norm() > (last - end).norm() {
+                points.reverse();
+            }
+            if (points[0] - end).norm() < EPSILON {
+                points.remove(0);
+            }
+        }
+        ring.extend(points);
+    }
+    if let Some(first) = ring.first().copied() {
+        let last = ring.len() - 1;
+        if (first - ring[last]).norm() > EPSILON {
+            ring.push(first);
+        } else {
+            // 首尾用同一个点，避免舍入误差使环在射线法判断中多出一次穿越
+            ring[last] = first;
+        }
+    }
+    ring
+}
+
+/// 填充的线框表示
+///
+/// 图案填充为图案线段（点为两端重合的线段）；实心填充以及图案未知时退回到边界环
+pub fn hatch_paths(hatch: &Hatch, tolerance: f64) -> Vec<Vec<Point2>> {
+    let segments = hatch_pattern::pattern_segments(hatch);
+    if segments.is_empty() {
+        return hatch_loops(hatch, tolerance);
+    }
+    segments.into_iter().map(|[a, b]| vec![a, b]).collect()
+}
+
+/// 引线及其箭头（两条短线）
+pub fn leader_paths(leader: &Leader) -> Vec<Vec<Point2>> {
+    let mut paths = vec![leader.vertices.clone()];
+
+    if let (Some(tip), Some(dir)) = (leader.arrow_point(), leader.arrow_direction()) {
+        let perp = Vector2::new(-dir.y, dir.x);
+        let size = leader.arrow_size;
+        let p1 = tip - dir * size + perp * size * 0.3;
+        let p2 = tip - dir * size - perp * size * 0.3;
+        paths.push(vec![p1, tip, p2]);
+    }
+    paths
+}
+
+/// 按实体缓存的细分结果
+///
+/// 以实体 ID、修订号（[`Entity::revision`]）和容差档位为键，三者都不变时复用上次的折线。
+/// 文档每次修改实体都会分配新的修订号，缓存不需要比较几何体。容差向下取整到 2 的幂作为档位，
+/// 视图缩放不超过两倍时不必重新细分。块参照展开得到的图元与块定义共用实体 ID，不应放入缓存。
+///
+/// 每帧开始时调用 [`evict_unused`](Self::evict_unused)，清除上一帧没有用到的条目（已删除的实体）。
+#[derive(Debug, Default)]
+pub struct TessellationCache {
+    entries: HashMap<EntityId, CacheEntry>,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    revision: u64,
+    level: i32,
+    paths: Vec<Vec<Point2>>,
+    used: bool,
+}
+
+impl CacheEntry {
+    fn new(entity: &Entity, level: i32) -> Self {
+        Self {
+            revision: entity.revision,
+            level,
+            paths: tessellate(&entity.geometry, 2f64.powi(level)),
+            used: false,
+        }
+    }
+}
+
+impl TessellationCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 获取实体的细分结果，修订号或容差档位变化时重新细分
+    pub fn get(&mut self, entity: &Entity, tolerance: f64) -> &[Vec<Point2>] {
+        let level = tolerance.log2().floor() as i32;
+        let entry = self
+            .entries
+            .entry(entity.id)
+            .or_insert_with(|| CacheEntry::new(entity, level));
+        if entry.revision != entity.revision || entry.level != level {
+            *entry = CacheEntry::new(entity, level);
+        }
+        entry.used = true;
+        &entry.paths
+    }
+
+    /// 移除实体的缓存
+    pub fn invalidate(&mut self, id: &EntityId) {
+        self.entries.remove(id);
+    }
+
+    /// 移除自上次调用以来没有被 [`get`](Self::get) 访问过的条目
+    pub fn evict_unused(&mut self) {
+        self.entries.retain(|_, entry| std::mem::take(&mut entry.used));
+    }
+
+    /// 清空缓存
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// 缓存的实体数
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::{Line, PolylineVertex};
+    use crate::math::approx_eq;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_circle_is_closed() {
+        let points = circle_points(&Circle::new(Point2::new(1.0, 2.0), 10.0), 0.1);
+        // 弦高 0.1、半径 10：每段圆心角 2·acos(0.99) ≈ 0.283，共 23 段
+        assert_eq!(points.len(), 24);
+        assert_eq!(points.first(), points.last());
+        for p in &points {
+            assert!(approx_eq((p - Point2::new(1.0, 2.0)).norm(), 10.0));
+        }
+    }
+
+    #[test]
+    fn test_bulge_points_both_directions() {
+        let p1 = Point2::new(0.0, 0.0);
+        let p2 = Point2::new(2.0, 0.0);
+
+        // 正凸度：逆时针半圆，经过 (1, -1)
+        let ccw = bulge_points(p1, p2, 1.0, DEFAULT_TOLERANCE);
+        assert!(ccw.iter().all(|p| p.y <= 1e-9));
+        assert!(ccw.iter().any(|p| approx_eq(p.y, -1.0)));
+        assert_eq!(*ccw.last().unwrap(), p2);
+
+        // 负凸度：顺时针半圆，经过 (1, 1)
+        let cw = bulge_points(p1, p2, -1.0, DEFAULT_TOLERANCE);
+        assert!(cw.iter().all(|p| p.y >= -1e-9));
+        assert!(cw.iter().any(|p| approx_eq(p.y, 1.0)));
+    }
+
+    #[test]
+    fn test_closed_polyline_and_hatch_loop() {
+        let square = [
+            Point2::new(0.0, 0.0),
+            Point2::new(10.0, 0.0),
+            Point2::new(10.0, 10.0),
+            Point2::new(0.0, 10.0),
+        ];
+        let polyline = Polyline::new(square.iter().map(|p| PolylineVertex::new(*p)).collect(), true);
+        let points = polyline_points(&polyline, DEFAULT_TOLERANCE);
+        assert_eq!(points.len(), 5);
+        assert_eq!(points[0], points[4]);
+
+        // 第二条边方向相反，仍应连接成一个环
+        let boundary = HatchBoundary::new(
+            vec![
+                HatchBoundaryElement::Line(Line::new(square[0], square[1])),
+                HatchBoundaryElement::Line(Line::new(square[2], square[1])),
+                HatchBoundaryElement::Line(Line::new(square[2], square[3])),
+                HatchBoundaryElement::Line(Line::new(square[3], square[0])),
+            ],
+            true,
+        );
+        let loops = hatch_loops(&Hatch::solid(vec![boundary]), DEFAULT_TOLERANCE);
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0], vec![square[0], square[1], square[2], square[3], square[0]]);
+    }
+
+    #[test]
+    fn test_arc_points_endpoints() {
+        let arc = Arc::new(Point2::origin(), 5.0, 0.0, PI / 2.0);
+        let points = tessellate(&Geometry::Arc(arc), DEFAULT_TOLERANCE);
+        assert_eq!(points.len(), 1);
+        let path = &points[0];
+        assert!((path[0] - Point2::new(5.0, 0.0)).norm() < 1e-9);
+        assert!((path[path.len() - 1] - Point2::new(0.0, 5.0)).norm() < 1e-9);
+    }
+
+    /// 曲线上的采样点到折线的最大距离
+    fn max_deviation(path: &[Point2], samples: impl IntoIterator<Item = Point2>) -> f64 {
+        samples
+            .into_iter()
+            .map(|p| {
+                path.windows(2)
+                    .map(|w| chord_deviation(p, w[0], w[1]))
+                    .fold(f64::MAX, f64::min)
+            })
+            .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn test_segment_count_follows_tolerance() {
+        let arc = Arc::new(Point2::new(3.0, -1.0), 50.0, 0.3, 2.5);
+        let mut previous = usize::MAX;
+        for tolerance in [0.001, 0.01, 0.1, 1.0] {
+            let path = &tessellate(&Geometry::Arc(arc.clone()), tolerance)[0];
+            let samples = (0..=1000).map(|i| Curve::point_at_param(&arc, 0.3 + 2.2 * i as f64 / 1000.0));
+            assert!(max_deviation(path, samples) <= tolerance + 1e-9);
+            assert!(path.len() < previous);
+            previous = path.len();
+        }
+
+        // 容差大于半径时仍保留最少段数
+        assert_eq!(arc_segment_count(1.0, TAU, 10.0), 8);
+        assert_eq!(arc_segment_count(1.0, TAU, 0.0), MAX_SEGMENTS);
+        assert!((screen_tolerance(0.2) - 0.1).abs() < 1e-12);
+        assert!((plot_tolerance(254.0, 0.5) - 0.1).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_ellipse_and_spline_within_tolerance() {
+        let mut ellipse = Ellipse::new(Point2::new(1.0, 1.0), Vector2::new(30.0, 40.0), 0.3);
+        ellipse.start_param = 0.5;
+        ellipse.end_param = 4.0;
+        let path = ellipse_points(&ellipse, 0.05);
+        let samples = (0..=1000).map(|i| ellipse.point_at_param(0.5 + 3.5 * i as f64 / 1000.0));
+        assert!(max_deviation(&path, samples) <= 0.05 + 1e-9);
+        assert_eq!(path[0], ellipse.start_point());
+
+        let fit = [(0.0, 0.0), (10.0, 8.0), (20.0, -5.0), (35.0, 12.0), (40.0, 0.0)];
+        let spline = Spline::from_fit_points(fit.iter().map(|&(x, y)| Point2::new(x, y)).collect(), 3, false);
+        let (start, end) = spline.param_range();
+        for tolerance in [0.5, 0.01] {
+            let path = spline_points(&spline, tolerance);
+            let samples = (0..=2000).map(|i| spline.point_at_param(start + (end - start) * i as f64 / 2000.0));
+            assert!(max_deviation(&path, samples) <= tolerance * 1.01);
+            assert!((path[path.len() - 1] - spline.point_at_param(end)).norm() < 1e-9);
+        }
+        assert!(spline_points(&spline, 0.01).len() > spline_points(&spline, 0.5).len());
+    }
+
+    #[test]
+    fn test_cache_reuses_and_invalidates() {
+        let mut cache = TessellationCache::new();
+        let mut entity = Entity::new(Geometry::Circle(Circle::new(Point2::origin(), 100.0)));
+
+        let first = cache.get(&entity, 0.1).as_ptr();
+        // 容差在同一档位内（0.0625..0.125）复用结果
+        assert_eq!(cache.get(&entity, 0.07).as_ptr(), first);
+        let coarse = cache.get(&entity, 1.0)[0].len();
+        assert!(coarse < tessellate(&entity.geometry, 0.0625)[0].len());
+
+        // 修订号变化后重新细分
+        entity.geometry = Geometry::Circle(Circle::new(Point2::new(5.0, 0.0), 100.0));
+        entity.touch();
+        assert!((cache.get(&entity, 1.0)[0][0] - Point2::new(105.0, 0.0)).norm() < 1e-9);
+
+        // 本帧没有访问的条目被清除
+        let other = Entity::new(entity.geometry.clone());
+        cache.get(&other, 1.0);
+        cache.evict_unused();
+        assert_eq!(cache.len(), 2);
+        cache.get(&entity, 1.0);
+        cache.evict_unused();
+        assert_eq!(cache.len(), 1);
+        let id = entity.id;
+        cache.invalidate(&id);
+        assert!(cache.is_empty());
+    }
+}
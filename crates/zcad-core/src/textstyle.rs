@@ -0,0 +1,271 @@
+//! 文字样式（Text Style）
+//!
+//! 对应 DXF 的 STYLE 表，定义字体文件、固定字高、宽度系数、倾斜角以及竖排/反向/倒置标志。
+//!
+//! 单行文字、多行文字和引线按名称引用文字样式，标注通过标注样式的 `text_style` 引用。
+//! 样式在创建文字时写入宽度系数、倾斜角等属性（与 DXF 一致，单个文字可以覆盖），
+//! 字体则由 [`TextStyleManager::update_fonts`] 按样式名称刷新到几何体上，供排版和绘制使用。
+//! 竖排标志仅随样式保存，绘制时按横排处理。
+
+use crate::geometry::{Geometry, Leader, Text};
+use crate::mtext::MText;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// 文字样式
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextStyle {
+    /// 样式名称
+    pub name: String,
+    /// 字体文件（如 "txt.shx"、"simsun.ttc"），为空时使用默认字体
+    pub font_file: String,
+    /// 大字体文件（SHX 亚洲字符字体）
+    pub big_font_file: String,
+    /// 固定字高，0 表示由文字自身指定
+    pub height: f64,
+    /// 宽度系数
+    pub width_factor: f64,
+    /// 倾斜角（弧度，相对竖直方向向右为正）
+    pub oblique_angle: f64,
+    /// 竖排
+    pub vertical: bool,
+    /// 反向（左右镜像）
+    pub backwards: bool,
+    /// 倒置（上下镜像）
+    pub upside_down: bool,
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        Self::new("Standard")
+    }
+}
+
+impl TextStyle {
+    /// 创建使用默认字体的文字样式
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            font_file: String::new(),
+            big_font_file: String::new(),
+            height: 0.0,
+            width_factor: 1.0,
+            oblique_angle: 0.0,
+            vertical: false,
+            backwards: false,
+            upside_down: false,
+        }
+    }
+
+    /// 设置字体文件
+    pub fn with_font(mut self, font_file: impl Into<String>) -> Self {
+        self.font_file = font_file.into();
+        self
+    }
+
+    /// 设置固定字高
+    pub fn with_height(mut self, height: f64) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// 设置宽度系数
+    pub fn with_width_factor(mut self, width_factor: f64) -> Self {
+        self.width_factor = width_factor;
+        self
+    }
+
+    /// 设置倾斜角（弧度）
+    pub fn with_oblique_angle(mut self, oblique_angle: f64) -> Self {
+        self.oblique_angle = oblique_angle;
+        self
+    }
+
+    /// 排版使用的字体名称：字体文件去掉路径和扩展名，未指定字体时为 None
+    pub fn font_name(&self) -> Option<String> {
+        Path::new(&self.font_file)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .filter(|stem| !stem.is_empty())
+    }
+
+    /// 用样式初始化新建的单行文字：样式名、字体、宽度系数、倾斜角和镜像标志，固定字高时覆盖字高
+    pub fn apply_to_text(&self, text: &mut Text) {
+        text.style_name = self.name.clone();
+        text.font = self.font_name();
+        text.width_factor = self.width_factor;
+        text.oblique_angle = self.oblique_angle;
+        text.backwards = self.backwards;
+        text.upside_down = self.upside_down;
+        if self.height > 0.0 {
+            text.height = self.height;
+        }
+    }
+
+    /// 用样式初始化新建的多行文字：样式名、字体，固定字高时覆盖字高
+    pub fn apply_to_mtext(&self, mtext: &mut MText) {
+        mtext.style_name = self.name.clone();
+        mtext.font = self.font_name();
+        if self.height > 0.0 {
+            mtext.height = self.height;
+        }
+    }
+
+    /// 用样式初始化新建的引线文字
+    pub fn apply_to_leader(&self, leader: &mut Leader) {
+        leader.style_name = self.name.clone();
+        leader.font = self.font_name();
+        if self.height > 0.0 {
+            leader.text_height = self.height;
+        }
+    }
+}
+
+/// 文字样式管理器
+#[derive(Debug, Clone)]
+pub struct TextStyleManager {
+    /// 所有文字样式
+    styles: Vec<TextStyle>,
+    /// 当前活动样式索引
+    current_style_index: usize,
+}
+
+impl Default for TextStyleManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TextStyleManager {
+    /// 创建只含 "Standard" 样式的管理器
+    pub fn new() -> Self {
+        Self {
+            styles: vec![TextStyle::default()],
+            current_style_index: 0,
+        }
+    }
+
+    /// 由已有样式创建（如从文件加载）
+    ///
+    /// 样式列表为空时使用默认样式；找不到 `current` 时以第一个样式为当前样式
+    pub fn from_styles(styles: Vec<TextStyle>, current: &str) -> Self {
+        if styles.is_empty() {
+            return Self::new();
+        }
+        let current_style_index = styles.iter().position(|s| s.name.eq_ignore_ascii_case(current)).unwrap_or(0);
+        Self {
+            styles,
+            current_style_index,
+        }
+    }
+
+    /// 获取当前样式
+    pub fn current_style(&self) -> &TextStyle {
+        &self.styles[self.current_style_index]
+    }
+
+    /// 设置当前样式
+    pub fn set_current_style(&mut self, name: &str) -> bool {
+        if let Some(index) = self.styles.iter().position(|s| s.name.eq_ignore_ascii_case(name)) {
+            self.current_style_index = index;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// 添加样式，已有同名样式（不区分大小写）时替换
+    pub fn set_style(&mut self, style: TextStyle) {
+        match self.styles.iter_mut().find(|s| s.name.eq_ignore_ascii_case(&style.name)) {
+            Some(existing) => *existing = style,
+            None => self.styles.push(style),
+        }
+    }
+
+    /// 按名称获取样式（不区分大小写）
+    pub fn get_style(&self, name: &str) -> Option<&TextStyle> {
+        self.styles.iter().find(|s| s.name.eq_ignore_ascii_case(name))
+    }
+
+    /// 查找文字使用的样式，名称为空或找不到时使用当前样式
+    pub fn resolve(&self, name: &str) -> &TextStyle {
+        self.get_style(name).unwrap_or_else(|| self.current_style())
+    }
+
+    /// 删除样式；当前样式和最后一个样式不能删除
+    pub fn remove_style(&mut self, name: &str) -> bool {
+        let Some(index) = self.styles.iter().position(|s| s.name.eq_ignore_ascii_case(name)) else {
+            return false;
+        };
+        if index == self.current_style_index || self.styles.len() == 1 {
+            return false;
+        }
+        self.styles.remove(index);
+        if index < self.current_style_index {
+            self.current_style_index -= 1;
+        }
+        true
+    }
+
+    /// 获取所有样式
+    pub fn styles(&self) -> &[TextStyle] {
+        &self.styles
+    }
+
+    /// 获取所有样式名称
+    pub fn style_names(&self) -> Vec<&str> {
+        self.styles.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    /// 按引用的样式刷新几何体的字体（文字、多行文字和引线），其它几何体不变
+    pub fn update_fonts(&self, geometry: &mut Geometry) {
+        match geometry {
+            Geometry::Text(text) => text.font = self.resolve(&text.style_name).font_name(),
+            Geometry::MText(mtext) => mtext.font = self.resolve(&mtext.style_name).font_name(),
+            Geometry::Leader(leader) => leader.font = self.resolve(&leader.style_name).font_name(),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Point2;
+
+    #[test]
+    fn test_style_manager() {
+        let mut manager = TextStyleManager::new();
+        manager.set_style(TextStyle::new("Chinese").with_font("fonts/simsun.ttc").with_width_factor(0.8));
+        manager.set_style(TextStyle::new("Title").with_font("romans.shx").with_height(5.0));
+        assert_eq!(manager.style_names(), ["Standard", "Chinese", "Title"]);
+        assert!(manager.set_current_style("title"));
+        assert!(!manager.remove_style("Title"));
+        assert!(manager.remove_style("Chinese"));
+        assert_eq!(manager.current_style().name, "Title");
+
+        // 找不到时回退到当前样式
+        assert_eq!(manager.resolve("missing").name, "Title");
+        assert_eq!(manager.resolve("STANDARD").font_name(), None);
+        assert_eq!(manager.current_style().font_name().as_deref(), Some("romans"));
+    }
+
+    #[test]
+    fn test_apply_and_update_fonts() {
+        let style = TextStyle::new("Narrow").with_font("simsun.ttc").with_width_factor(0.7).with_height(3.5);
+        let mut text = Text::new(Point2::origin(), "标题", 10.0);
+        style.apply_to_text(&mut text);
+        assert_eq!(text.height, 3.5);
+        assert_eq!(text.width_factor, 0.7);
+        assert_eq!(text.font.as_deref(), Some("simsun"));
+
+        let mut manager = TextStyleManager::new();
+        manager.set_style(style.with_font("txt.shx"));
+        let mut geometry = Geometry::Text(text);
+        manager.update_fonts(&mut geometry);
+        let Geometry::Text(text) = geometry else { unreachable!() };
+        assert_eq!(text.font.as_deref(), Some("txt"));
+        // 宽度系数作为文字自身的属性保留
+        assert_eq!(text.width_factor, 0.7);
+    }
+}
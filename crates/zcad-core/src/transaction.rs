@@ -0,0 +1,413 @@
+//! 事务与可逆变更集
+//!
+//! 对文档的每次编辑都通过 [`Transaction`] 执行：事务在修改目标的同时把每一步记录为
+//! 可逆的 [`Change`]，提交后得到 [`ChangeSet`]。撤销时按相反顺序应用各变更的逆变更，
+//! 重做时按原顺序再次应用。移动、旋转、缩放、镜像、属性和图层修改最终都归结为
+//! 实体的增删改和图层表的变化，因此撤销/重做不需要了解具体的编辑命令。
+//!
+//! 编辑一个实体时，依赖它的实体（如关联标注）可能被目标连带修改，
+//! 事务会同时记录这些实体的前后状态，保证撤销后完全恢复。
+//!
+//! 文档类型通过实现 [`EditTarget`] 接入（zcad-file 的 `Document` 实现了该 trait）。
+
+use crate::entity::{Entity, EntityId};
+use crate::geometry::Geometry;
+use crate::layer::LayerManager;
+use crate::math::{Point2, Vector2};
+use crate::transform::Transform2D;
+use serde::{Deserialize, Serialize};
+
+/// 可编辑的目标（文档）
+pub trait EditTarget {
+    /// 获取实体
+    fn entity(&self, id: &EntityId) -> Option<&Entity>;
+
+    /// 插入实体（ID 已存在时覆盖）
+    fn insert_entity(&mut self, entity: Entity);
+
+    /// 移除实体并返回
+    fn take_entity(&mut self, id: &EntityId) -> Option<Entity>;
+
+    /// 用新状态替换同 ID 的实体
+    fn replace_entity(&mut self, entity: Entity);
+
+    /// 依赖指定实体的实体（如关联标注），编辑该实体时目标可能会连带修改它们
+    fn dependent_entities(&self, id: &EntityId) -> Vec<EntityId>;
+
+    /// 图层表
+    fn layers(&self) -> &LayerManager;
+
+    /// 图层表（可变）
+    fn layers_mut(&mut self) -> &mut LayerManager;
+}
+
+/// 单个可逆变更
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Change {
+    /// 添加实体
+    AddEntity(Entity),
+    /// 删除实体
+    RemoveEntity(Entity),
+    /// 修改实体（几何、属性、图层等）
+    ModifyEntity { before: Box<Entity>, after: Box<Entity> },
+    /// 图层表变化（图层表很小，直接保存前后快照）
+    Layers { before: LayerManager, after: LayerManager },
+}
+
+impl Change {
+    /// 逆变更
+    pub fn inverse(&self) -> Change {
+        match self {
+            Change::AddEntity(entity) => Change::RemoveEntity(entity.clone()),
+            Change::RemoveEntity(entity) => Change::AddEntity(entity.clone()),
+            Change::ModifyEntity { before, after } => Change::ModifyEntity {
+                before: after.clone(),
+                after: before.clone(),
+            },
+            Change::Layers { before, after } => Change::Layers {
+                before: after.clone(),
+                after: before.clone(),
+            },
+        }
+    }
+
+    /// 在目标上应用变更
+    pub fn apply<T: EditTarget + ?Sized>(&self, target: &mut T) {
+        match self {
+            Change::AddEntity(entity) => target.insert_entity(entity.clone()),
+            Change::RemoveEntity(entity) => {
+                target.take_entity(&entity.id);
+            }
+            Change::ModifyEntity { after, .. } => target.replace_entity(after.as_ref().clone()),
+            Change::Layers { after, .. } => *target.layers_mut() = after.clone(),
+        }
+    }
+
+    /// 变更涉及的实体
+    pub fn entity_id(&self) -> Option<EntityId> {
+        match self {
+            Change::AddEntity(entity) | Change::RemoveEntity(entity) => Some(entity.id),
+            Change::ModifyEntity { after, .. } => Some(after.id),
+            Change::Layers { .. } => None,
+        }
+    }
+}
+
+/// 变更集：一次编辑命令产生的全部变更
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChangeSet {
+    /// 描述（显示在撤销/重做提示中）
+    pub description: String,
+    /// 按执行顺序排列的变更
+    pub changes: Vec<Change>,
+}
+
+impl ChangeSet {
+    /// 创建空变更集
+    pub fn new(description: impl Into<String>) -> Self {
+        Self {
+            description: description.into(),
+            changes: Vec::new(),
+        }
+    }
+
+    /// 是否没有任何变更
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// 撤销：按相反顺序应用逆变更
+    pub fn undo<T: EditTarget + ?Sized>(&self, target: &mut T) {
+        for change in self.changes.iter().rev() {
+            change.inverse().apply(target);
+        }
+    }
+
+    /// 重做：按原顺序应用变更
+    pub fn redo<T: EditTarget + ?Sized>(&self, target: &mut T) {
+        for change in &self.changes {
+            change.apply(target);
+        }
+    }
+
+    /// 逆变更集
+    pub fn inverse(&self) -> ChangeSet {
+        ChangeSet {
+            description: self.description.clone(),
+            changes: self.changes.iter().rev().map(Change::inverse).collect(),
+        }
+    }
+
+    /// 受影响的实体（去重，保持首次出现的顺序）
+    pub fn affected_entities(&self) -> Vec<EntityId> {
+        let mut ids = Vec::new();
+        for id in self.changes.iter().filter_map(Change::entity_id) {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+        ids
+    }
+}
+
+/// 事务
+///
+/// 通过事务修改目标，每一步都会被记录；[`Transaction::commit`] 返回变更集，
+/// [`Transaction::rollback`] 撤销已执行的修改。
+pub struct Transaction<'a, T: EditTarget + ?Sized> {
+    target: &'a mut T,
+    change_set: ChangeSet,
+}
+
+impl<'a, T: EditTarget + ?Sized> Transaction<'a, T> {
+    /// 在目标上开始事务
+    pub fn new(target: &'a mut T, description: impl Into<String>) -> Self {
+        Self {
+            target,
+            change_set: ChangeSet::new(description),
+        }
+    }
+
+    /// 只读访问目标
+    pub fn target(&self) -> &T {
+        self.target
+    }
+
+    /// 添加实体
+    pub fn add_entity(&mut self, entity: Entity) -> EntityId {
+        let id = entity.id;
+        self.target.insert_entity(entity.clone());
+        self.change_set.changes.push(Change::AddEntity(entity));
+        id
+    }
+
+    /// 删除实体
+    pub fn remove_entity(&mut self, id: &EntityId) -> Option<Entity> {
+        let dependents = self.snapshot_dependents(id);
+        let removed = self.target.take_entity(id)?;
+        self.change_set.changes.push(Change::RemoveEntity(removed.clone()));
+        self.record_dependents(dependents);
+        Some(removed)
+    }
+
+    /// 修改实体，闭包返回后记录前后状态
+    pub fn modify_entity(&mut self, id: &EntityId, edit: impl FnOnce(&mut Entity)) -> bool {
+        let Some(before) = self.target.entity(id).cloned() else {
+            return false;
+        };
+        let mut after = before.clone();
+        edit(&mut after);
+        after.id = before.id;
+
+        let dependents = self.snapshot_dependents(id);
+        self.target.replace_entity(after.clone());
+        self.change_set.changes.push(Change::ModifyEntity {
+            before: Box::new(before),
+            after: Box::new(after),
+        });
+        self.record_dependents(dependents);
+        true
+    }
+
+    /// 替换实体的几何
+    pub fn set_geometry(&mut self, id: &EntityId, geometry: Geometry) -> bool {
+        self.modify_entity(id, |entity| entity.geometry = geometry)
+    }
+
+    /// 对一组实体应用仿射变换，返回实际变换的实体数
+    pub fn transform_entities(&mut self, ids: &[EntityId], transform: &Transform2D) -> usize {
+        ids.iter()
+            .filter(|id| {
+                self.modify_entity(id, |entity| {
+                    entity.geometry = entity.geometry.transform(transform);
+                })
+            })
+            .count()
+    }
+
+    /// 移动实体
+    pub fn move_entities(&mut self, ids: &[EntityId], offset: Vector2) -> usize {
+        self.transform_entities(ids, &Transform2D::translation(offset.x, offset.y))
+    }
+
+    /// 绕中心旋转实体（弧度）
+    pub fn rotate_entities(&mut self, ids: &[EntityId], center: Point2, angle: f64) -> usize {
+        self.transform_entities(ids, &Transform2D::rotation_around(center, angle))
+    }
+
+    /// 以中心等比缩放实体
+    pub fn scale_entities(&mut self, ids: &[EntityId], center: Point2, factor: f64) -> usize {
+        self.transform_entities(ids, &Transform2D::scale_around(center, factor, factor))
+    }
+
+    /// 以直线 p1-p2 为镜像线镜像实体
+    pub fn mirror_entities(&mut self, ids: &[EntityId], p1: Point2, p2: Point2) -> usize {
+        self.transform_entities(ids, &Transform2D::mirror_line(p1, p2))
+    }
+
+    /// 修改图层表（新建、删除、重命名图层，修改图层属性或切换当前图层）
+    pub fn modify_layers<R>(&mut self, edit: impl FnOnce(&mut LayerManager) -> R) -> R {
+        let before = self.target.layers().clone();
+        let result = edit(self.target.layers_mut());
+        let after = self.target.layers().clone();
+        self.change_set.changes.push(Change::Layers { before, after });
+        result
+    }
+
+    /// 提交事务，返回变更集
+    pub fn commit(self) -> ChangeSet {
+        self.change_set
+    }
+
+    /// 回滚事务，撤销已执行的全部修改
+    pub fn rollback(self) {
+        self.change_set.undo(self.target);
+    }
+
+    fn snapshot_dependents(&self, id: &EntityId) -> Vec<Entity> {
+        self.target
+            .dependent_entities(id)
+            .iter()
+            .filter_map(|dependent| self.target.entity(dependent).cloned())
+            .collect()
+    }
+
+    fn record_dependents(&mut self, dependents: Vec<Entity>) {
+        for before in dependents {
+            if let Some(after) = self.target.entity(&before.id).cloned() {
+                self.change_set.changes.push(Change::ModifyEntity {
+                    before: Box::new(before),
+                    after: Box::new(after),
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::{Circle, Line};
+    use crate::layer::Layer;
+    use std::collections::HashMap;
+
+    #[derive(Default)]
+    struct TestDocument {
+        entities: HashMap<EntityId, Entity>,
+        layers: LayerManager,
+    }
+
+    impl EditTarget for TestDocument {
+        fn entity(&self, id: &EntityId) -> Option<&Entity> {
+            self.entities.get(id)
+        }
+
+        fn insert_entity(&mut self, entity: Entity) {
+            self.entities.insert(entity.id, entity);
+        }
+
+        fn take_entity(&mut self, id: &EntityId) -> Option<Entity> {
+            self.entities.remove(id)
+        }
+
+        fn replace_entity(&mut self, entity: Entity) {
+            self.entities.insert(entity.id, entity);
+        }
+
+        fn dependent_entities(&self, _id: &EntityId) -> Vec<EntityId> {
+            Vec::new()
+        }
+
+        fn layers(&self) -> &LayerManager {
+            &self.layers
+        }
+
+        fn layers_mut(&mut self) -> &mut LayerManager {
+            &mut self.layers
+        }
+    }
+
+    fn line_start(document: &TestDocument, id: &EntityId) -> Point2 {
+        match &document.entity(id).unwrap().geometry {
+            Geometry::Line(line) => line.start,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_transform_undo_redo() {
+        let mut document = TestDocument::default();
+        let line = Entity::new(Geometry::Line(Line::new(Point2::new(1.0, 0.0), Point2::new(2.0, 0.0))));
+        let circle = Entity::new(Geometry::Circle(Circle::new(Point2::new(5.0, 5.0), 1.0)));
+        let (line_id, circle_id) = (line.id, circle.id);
+        document.insert_entity(line);
+        document.insert_entity(circle);
+
+        let ids = [line_id, circle_id];
+        let mut transaction = Transaction::new(&mut document, "编辑");
+        assert_eq!(transaction.move_entities(&ids, Vector2::new(10.0, 0.0)), 2);
+        transaction.rotate_entities(&ids, Point2::origin(), std::f64::consts::FRAC_PI_2);
+        transaction.scale_entities(&ids, Point2::origin(), 2.0);
+        transaction.mirror_entities(&ids, Point2::origin(), Point2::new(1.0, 0.0));
+        transaction.modify_entity(&line_id, |e| e.properties.color = crate::properties::Color::RED);
+        let change_set = transaction.commit();
+        assert_eq!(change_set.affected_entities(), vec![line_id, circle_id]);
+
+        // (1,0) → (11,0) → (0,11) → (0,22) → (0,-22)
+        assert!((line_start(&document, &line_id) - Point2::new(0.0, -22.0)).norm() < 1e-9);
+
+        change_set.undo(&mut document);
+        assert!((line_start(&document, &line_id) - Point2::new(1.0, 0.0)).norm() < 1e-9);
+        assert_ne!(document.entity(&line_id).unwrap().properties.color, crate::properties::Color::RED);
+        match &document.entity(&circle_id).unwrap().geometry {
+            Geometry::Circle(c) => assert!((c.radius - 1.0).abs() < 1e-12),
+            _ => unreachable!(),
+        }
+
+        change_set.redo(&mut document);
+        assert!((line_start(&document, &line_id) - Point2::new(0.0, -22.0)).norm() < 1e-9);
+        assert_eq!(document.entity(&line_id).unwrap().properties.color, crate::properties::Color::RED);
+    }
+
+    #[test]
+    fn test_add_remove_and_layers() {
+        let mut document = TestDocument::default();
+        let existing = Entity::new(Geometry::Line(Line::new(Point2::origin(), Point2::new(1.0, 1.0))));
+        let existing_id = existing.id;
+        document.insert_entity(existing);
+
+        let mut transaction = Transaction::new(&mut document, "编辑");
+        let added = transaction.add_entity(Entity::new(Geometry::Circle(Circle::new(Point2::origin(), 3.0))));
+        transaction.remove_entity(&existing_id);
+        transaction.modify_layers(|layers| {
+            layers.add_layer(Layer::new("标注"));
+            layers.set_current_layer("标注")
+        });
+        let change_set = transaction.commit();
+        assert!(document.entity(&existing_id).is_none());
+        assert_eq!(document.layers.current_layer().name, "标注");
+
+        change_set.undo(&mut document);
+        assert!(document.entity(&added).is_none());
+        assert!(document.entity(&existing_id).is_some());
+        assert_eq!(document.layers.count(), 1);
+        assert_eq!(document.layers.current_layer().name, "0");
+
+        change_set.inverse().undo(&mut document);
+        assert!(document.entity(&added).is_some());
+        assert_eq!(document.layers.current_layer().name, "标注");
+    }
+
+    #[test]
+    fn test_rollback() {
+        let mut document = TestDocument::default();
+        let entity = Entity::new(Geometry::Line(Line::new(Point2::origin(), Point2::new(1.0, 0.0))));
+        let id = entity.id;
+        document.insert_entity(entity);
+
+        let mut transaction = Transaction::new(&mut document, "移动");
+        transaction.move_entities(&[id], Vector2::new(5.0, 5.0));
+        transaction.rollback();
+        assert!((line_start(&document, &id) - Point2::origin()).norm() < 1e-12);
+    }
+}
@@ -1,8 +1,15 @@
 //! 2D变换操作
 //!
-//! 支持平移、旋转、缩放、镜像等变换。
-
-use crate::math::{Matrix3, Point2, Vector2};
+//! 支持平移、旋转、缩放、镜像等变换。[`Geometry::transform`] 把任意变换作用到各类几何体上，
+//! 块展开、编辑命令和撤销重做共用这一实现。
+
+use crate::block::{compose_reference, BlockAttribute};
+use crate::geometry::{
+    Arc, Circle, DimensionType, Ellipse, Geometry, Hatch, HatchBoundary, HatchBoundaryElement, Line, Point, Polyline,
+    PolylineVertex, Spline, Text,
+};
+use crate::math::{Matrix3, Point2, Vector2, EPSILON};
+use crate::tessellation;
 use serde::{Deserialize, Serialize};
 
 /// 2D仿射变换
@@ -165,9 +172,353 @@ impl std::ops::Mul for Transform2D {
     }
 }
 
+/// 相似变换的分解：均匀缩放、旋转以及是否镜像
+struct Similarity {
+    scale: f64,
+    rotation: f64,
+    mirrored: bool,
+}
+
+impl Similarity {
+    /// 分解变换的线性部分，非相似变换（非均匀缩放、错切）返回 None
+    fn of(transform: &Transform2D) -> Option<Self> {
+        let m = transform.matrix();
+        let (a, b, c, d) = (m[(0, 0)], m[(0, 1)], m[(1, 0)], m[(1, 1)]);
+        let (sx, sy) = transform.scale_component();
+        if sx < EPSILON || (sx - sy).abs() > 1e-9 * sx || (a * b + c * d).abs() > 1e-9 * sx * sy {
+            return None;
+        }
+        Some(Self {
+            scale: sx,
+            rotation: c.atan2(a),
+            mirrored: a * d - b * c < 0.0,
+        })
+    }
+
+    /// 变换方向角
+    fn angle(&self, angle: f64) -> f64 {
+        if self.mirrored {
+            self.rotation - angle
+        } else {
+            self.rotation + angle
+        }
+    }
+
+    /// 变换圆弧：镜像时圆弧方向反转，起止角互换
+    fn arc(&self, arc: &Arc, transform: &Transform2D) -> Arc {
+        let (start, end) = if self.mirrored {
+            (self.angle(arc.end_angle), self.angle(arc.start_angle))
+        } else {
+            (self.angle(arc.start_angle), self.angle(arc.end_angle))
+        };
+        Arc::new(transform.transform_point(&arc.center), arc.radius * self.scale, start, end)
+    }
+
+    /// 变换椭圆：镜像时参数方向反转
+    fn ellipse(&self, ellipse: &Ellipse, transform: &Transform2D) -> Ellipse {
+        let (start, end) = if self.mirrored {
+            (-ellipse.end_param, -ellipse.start_param)
+        } else {
+            (ellipse.start_param, ellipse.end_param)
+        };
+        Ellipse::arc(
+            transform.transform_point(&ellipse.center),
+            transform.transform_vector(&ellipse.major_axis),
+            ellipse.ratio,
+            start,
+            end,
+        )
+    }
+}
+
+/// 变换的线性缩放系数（面积缩放的平方根），用于文字高度、箭头大小等标量
+fn linear_scale(transform: &Transform2D) -> f64 {
+    let m = transform.matrix();
+    (m[(0, 0)] * m[(1, 1)] - m[(0, 1)] * m[(1, 0)]).abs().sqrt()
+}
+
+/// 将细分后的曲线变换为多段线
+fn transformed_polyline(mut points: Vec<Point2>, closed: bool, transform: &Transform2D) -> Geometry {
+    if closed && points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+    Geometry::Polyline(Polyline::from_points(
+        points.iter().map(|p| transform.transform_point(p)),
+        closed,
+    ))
+}
+
+/// 由共轭半径构造椭圆：曲线为 `center + u·cos t + v·sin t`，t 取 `[start, end]`
+///
+/// 仿射变换把圆和椭圆映射为以变换后共轭半径描述的椭圆，这里求出主轴并换算参数，
+/// u、v 反向（镜像）时参数方向随之反转。
+fn conjugate_ellipse(center: Point2, u: Vector2, v: Vector2, start: f64, end: f64) -> Ellipse {
+    let t0 = 0.5 * (2.0 * u.dot(&v)).atan2(u.norm_squared() - v.norm_squared());
+    let major = u * t0.cos() + v * t0.sin();
+    let minor = v * t0.cos() - u * t0.sin();
+    let ratio = if major.norm() > EPSILON { minor.norm() / major.norm() } else { 1.0 };
+
+    if (end - start - 2.0 * std::f64::consts::PI).abs() < EPSILON {
+        return Ellipse::new(center, major, ratio);
+    }
+    let (start, end) = if major.perp(&minor) >= 0.0 {
+        (start - t0, end - t0)
+    } else {
+        (t0 - end, t0 - start)
+    };
+    Ellipse::arc(center, major, ratio, start, end)
+}
+
+/// 圆弧在一般仿射变换下的像
+fn affine_arc(arc: &Arc, transform: &Transform2D) -> Ellipse {
+    conjugate_ellipse(
+        transform.transform_point(&arc.center),
+        transform.transform_vector(&Vector2::new(arc.radius, 0.0)),
+        transform.transform_vector(&Vector2::new(0.0, arc.radius)),
+        arc.start_angle,
+        arc.start_angle + arc.sweep_angle(),
+    )
+}
+
+/// 椭圆在一般仿射变换下的像
+fn affine_ellipse(ellipse: &Ellipse, transform: &Transform2D) -> Ellipse {
+    conjugate_ellipse(
+        transform.transform_point(&ellipse.center),
+        transform.transform_vector(&ellipse.major_axis),
+        transform.transform_vector(&(ellipse.minor_axis_direction() * ellipse.minor_radius())),
+        ellipse.start_param,
+        ellipse.end_param,
+    )
+}
+
+impl Geometry {
+    /// 对几何体施加仿射变换，返回变换后的几何体
+    ///
+    /// 相似变换（平移、旋转、均匀缩放、镜像）保持图元类型；非均匀缩放和错切时圆、圆弧
+    /// 精确地变为椭圆（弧），带凸度的多段线退化为细分后的多段线。文字只变换插入点、方向
+    /// 和高度，镜像后仍然可读。块参照在无法用块参照表示时保留近似的缩放和旋转。
+    pub fn transform(&self, transform: &Transform2D) -> Geometry {
+        let similarity = Similarity::of(transform);
+        let point = |p: &Point2| transform.transform_point(p);
+
+        match self {
+            Geometry::Point(p) => Geometry::Point(Point { position: point(&p.position) }),
+            Geometry::Line(line) => Geometry::Line(Line::new(point(&line.start), point(&line.end))),
+            Geometry::Circle(circle) => match &similarity {
+                Some(s) => Geometry::Circle(Circle::new(point(&circle.center), circle.radius * s.scale)),
+                None => Geometry::Ellipse(conjugate_ellipse(
+                    point(&circle.center),
+                    transform.transform_vector(&Vector2::new(circle.radius, 0.0)),
+                    transform.transform_vector(&Vector2::new(0.0, circle.radius)),
+                    0.0,
+                    2.0 * std::f64::consts::PI,
+                )),
+            },
+            Geometry::Arc(arc) => match &similarity {
+                Some(s) => Geometry::Arc(s.arc(arc, transform)),
+                None => Geometry::Ellipse(affine_arc(arc, transform)),
+            },
+            Geometry::Polyline(polyline) => {
+                let has_bulge = polyline.vertices.iter().any(|v| v.bulge.abs() > EPSILON);
+                match &similarity {
+                    Some(s) => {
+                        let sign = if s.mirrored { -1.0 } else { 1.0 };
+                        Geometry::Polyline(Polyline::new(
+                            polyline
+                                .vertices
+                                .iter()
+                                .map(|v| PolylineVertex::with_bulge(point(&v.point), v.bulge * sign))
+                                .collect(),
+                            polyline.closed,
+                        ))
+                    }
+                    None if has_bulge => transformed_polyline(
+                        tessellation::polyline_points(polyline, tessellation::DEFAULT_TOLERANCE),
+                        polyline.closed,
+                        transform,
+                    ),
+                    None => Geometry::Polyline(Polyline::from_points(
+                        polyline.vertices.iter().map(|v| point(&v.point)),
+                        polyline.closed,
+                    )),
+                }
+            }
+            Geometry::Text(text) => Geometry::Text(transform_text(text, transform)),
+            Geometry::MText(mtext) => {
+                // 按插入点处的单行文字变换，参考宽度沿文字方向缩放
+                let mut mtext = mtext.clone();
+                let anchor = Text::new(mtext.position, "", mtext.height).with_rotation(mtext.rotation);
+                let text = transform_text(&anchor, transform);
+                mtext.width *= transform.transform_vector(&mtext.direction()).norm();
+                mtext.position = text.position;
+                mtext.rotation = text.rotation;
+                mtext.height = text.height;
+                Geometry::MText(mtext)
+            }
+            Geometry::Dimension(dim) => {
+                let mut dim = dim.clone();
+                dim.definition_point1 = point(&dim.definition_point1);
+                dim.definition_point2 = point(&dim.definition_point2);
+                dim.line_location = point(&dim.line_location);
+                dim.definition_point3 = point(&dim.definition_point3);
+                dim.text_position = dim.text_position.map(|p| point(&p));
+                dim.text_height *= linear_scale(transform);
+                // 镜像后弧长标注的逆时针方向反转，起点和终点互换
+                let m = transform.matrix();
+                if dim.dim_type == DimensionType::ArcLength && m[(0, 0)] * m[(1, 1)] - m[(0, 1)] * m[(1, 0)] < 0.0 {
+                    std::mem::swap(&mut dim.definition_point2, &mut dim.definition_point3);
+                }
+                Geometry::Dimension(dim)
+            }
+            Geometry::Tolerance(tolerance) => {
+                // 框格按文字方式变换：插入点和方向随变换，框格本身保持正交
+                let mut tolerance = tolerance.clone();
+                let frame = Text::new(tolerance.position, "", tolerance.text_height).with_rotation(tolerance.rotation);
+                let text = transform_text(&frame, transform);
+                tolerance.position = text.position;
+                tolerance.rotation = text.rotation;
+                tolerance.text_height = text.height;
+                if let Some(leader) = &mut tolerance.leader {
+                    leader.vertices = leader.vertices.iter().map(point).collect();
+                    leader.arrow_size *= linear_scale(transform);
+                }
+                Geometry::Tolerance(tolerance)
+            }
+            Geometry::Ellipse(ellipse) => match &similarity {
+                Some(s) => Geometry::Ellipse(s.ellipse(ellipse, transform)),
+                None => Geometry::Ellipse(affine_ellipse(ellipse, transform)),
+            },
+            Geometry::Spline(spline) => Geometry::Spline(transform_spline(spline, transform)),
+            Geometry::Hatch(hatch) => Geometry::Hatch(transform_hatch(hatch, similarity.as_ref(), transform)),
+            Geometry::Leader(leader) => {
+                let mut leader = leader.clone();
+                let scale = linear_scale(transform);
+                leader.vertices = leader.vertices.iter().map(point).collect();
+                leader.arrow_size *= scale;
+                leader.text_height *= scale;
+                Geometry::Leader(leader)
+            }
+            Geometry::Insert(reference) => Geometry::Insert(compose_reference(reference, transform).unwrap_or_else(|| {
+                let (sx, sy) = transform.scale_component();
+                let mut approx = reference.clone();
+                approx.insertion_point = point(&reference.insertion_point);
+                approx.rotation += transform.rotation_angle();
+                approx.scale_x *= sx;
+                approx.scale_y *= sy;
+                approx.attributes = transform_attributes(&reference.attributes, transform);
+                approx
+            })),
+        }
+    }
+}
+
+/// 变换文字：保持可读，只变换插入点、方向和高度，不镜像字形
+///
+/// 镜像后的文字框按两种方式之一摆正：沿文字方向反向（左右对齐互换），或翻到基线另一侧
+/// （上下对齐互换），取文字方向朝右的一种。
+pub(crate) fn transform_text(text: &Text, transform: &Transform2D) -> Text {
+    let mut text = text.clone();
+    let direction = Vector2::new(text.rotation.cos(), text.rotation.sin());
+    let up = Vector2::new(-direction.y, direction.x);
+    let mut direction = transform.transform_vector(&direction);
+    let up = transform.transform_vector(&up);
+    let up_scale = up.norm();
+    text.position = transform.transform_point(&text.position);
+    text.end_point = text.end_point.map(|end| transform.transform_point(&end));
+    if direction.perp(&up) < 0.0 {
+        if direction.x < -EPSILON || (direction.x.abs() <= EPSILON && direction.y < 0.0) {
+            direction = -direction;
+            text.alignment = text.alignment.mirrored_horizontally();
+            if let Some(end) = text.end_point.as_mut() {
+                std::mem::swap(&mut text.position, end);
+            }
+        } else {
+            text.alignment = text.alignment.mirrored_vertically();
+        }
+    }
+    text.rotation = direction.y.atan2(direction.x);
+    text.height *= up_scale;
+    // 非等比缩放时由宽度系数吸收两个方向的比例差
+    if up_scale > f64::EPSILON {
+        text.width_factor *= direction.norm() / up_scale;
+    }
+    text
+}
+
+/// 变换块参照的属性值
+pub(crate) fn transform_attributes(attributes: &[BlockAttribute], transform: &Transform2D) -> Vec<BlockAttribute> {
+    attributes
+        .iter()
+        .map(|attribute| BlockAttribute {
+            tag: attribute.tag.clone(),
+            text: transform_text(&attribute.text, transform),
+            invisible: attribute.invisible,
+        })
+        .collect()
+}
+
+/// 样条在仿射变换下保持不变，直接变换控制点、拟合点和端点切向
+fn transform_spline(spline: &Spline, transform: &Transform2D) -> Spline {
+    let mut spline = spline.clone();
+    spline.control_points = spline.control_points.iter().map(|p| transform.transform_point(p)).collect();
+    spline.fit_points = spline.fit_points.iter().map(|p| transform.transform_point(p)).collect();
+    spline.start_tangent = spline.start_tangent.map(|v| transform.transform_vector(&v));
+    spline.end_tangent = spline.end_tangent.map(|v| transform.transform_vector(&v));
+    spline
+}
+
+fn transform_hatch(hatch: &Hatch, similarity: Option<&Similarity>, transform: &Transform2D) -> Hatch {
+    let point = |p: &Point2| transform.transform_point(p);
+    let boundaries = hatch
+        .boundaries
+        .iter()
+        .map(|boundary| {
+            let elements = boundary
+                .elements
+                .iter()
+                .flat_map(|element| match (element, similarity) {
+                    (HatchBoundaryElement::Line(line), _) => {
+                        vec![HatchBoundaryElement::Line(Line::new(point(&line.start), point(&line.end)))]
+                    }
+                    (HatchBoundaryElement::Arc(arc), Some(s)) => vec![HatchBoundaryElement::Arc(s.arc(arc, transform))],
+                    (HatchBoundaryElement::Arc(arc), None) => {
+                        vec![HatchBoundaryElement::Ellipse(affine_arc(arc, transform))]
+                    }
+                    (HatchBoundaryElement::Ellipse(ellipse), Some(s)) => {
+                        vec![HatchBoundaryElement::Ellipse(s.ellipse(ellipse, transform))]
+                    }
+                    (HatchBoundaryElement::Ellipse(ellipse), None) => {
+                        vec![HatchBoundaryElement::Ellipse(affine_ellipse(ellipse, transform))]
+                    }
+                    (HatchBoundaryElement::Spline(spline), _) => {
+                        vec![HatchBoundaryElement::Spline(transform_spline(spline, transform))]
+                    }
+                })
+                .collect();
+            HatchBoundary::new(elements, boundary.is_outer)
+        })
+        .collect();
+
+    let angle = match similarity {
+        Some(s) => s.angle(hatch.angle),
+        None => {
+            let direction = transform.transform_vector(&Vector2::new(hatch.angle.cos(), hatch.angle.sin()));
+            direction.y.atan2(direction.x)
+        }
+    };
+
+    Hatch {
+        boundaries,
+        pattern_type: hatch.pattern_type.clone(),
+        angle,
+        scale: hatch.scale * linear_scale(transform),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::geometry::TextAlignment;
     use crate::math::approx_eq;
 
     #[test]
@@ -215,5 +566,68 @@ mod tests {
         assert!(approx_eq(restored.x, p.x));
         assert!(approx_eq(restored.y, p.y));
     }
+
+    #[test]
+    fn test_non_uniform_scale_turns_circle_into_ellipse() {
+        let circle = Geometry::Circle(Circle::new(Point2::new(1.0, 1.0), 2.0));
+        let Geometry::Ellipse(ellipse) = circle.transform(&Transform2D::scale(3.0, 1.0)) else {
+            panic!("expected ellipse");
+        };
+
+        assert!((ellipse.center - Point2::new(3.0, 1.0)).norm() < 1e-9);
+        assert!(approx_eq(ellipse.major_radius(), 6.0));
+        assert!(approx_eq(ellipse.ratio, 1.0 / 3.0));
+        assert!(approx_eq(ellipse.major_axis.y, 0.0));
+        assert!(ellipse.is_full());
+    }
+
+    #[test]
+    fn test_affine_arc_matches_transformed_points() {
+        let arc = Arc::new(Point2::new(2.0, -1.0), 1.5, 0.3, 2.0);
+        let shear = Transform2D::from_matrix(Matrix3::new(1.0, 0.8, 4.0, 0.2, 2.0, -1.0, 0.0, 0.0, 1.0));
+        // 错切和带镜像的非均匀缩放
+        for transform in [shear, Transform2D::scale(-2.0, 0.5)] {
+            let Geometry::Ellipse(ellipse) = Geometry::Arc(arc.clone()).transform(&transform) else {
+                panic!("expected elliptical arc");
+            };
+            let on_arc = |angle: f64| {
+                transform.transform_point(&(arc.center + Vector2::new(angle.cos(), angle.sin()) * arc.radius))
+            };
+            let (start, end) = (on_arc(arc.start_angle), on_arc(arc.end_angle));
+            let middle = on_arc(0.5 * (arc.start_angle + arc.end_angle));
+            let first = ellipse.point_at_param(ellipse.start_param);
+            let last = ellipse.point_at_param(ellipse.end_param);
+
+            // 镜像后圆弧方向反转，起止点互换
+            assert!((first - start).norm() < 1e-9 || (first - end).norm() < 1e-9);
+            assert!((last - start).norm() < 1e-9 || (last - end).norm() < 1e-9);
+            assert!((first - last).norm() > 1e-3);
+            let mid_param = 0.5 * (ellipse.start_param + ellipse.end_param);
+            assert!((ellipse.point_at_param(mid_param) - middle).norm() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_mirrored_text_stays_readable() {
+        let text = Geometry::Text(Text::new(Point2::new(1.0, 2.0), "ABC", 2.5));
+
+        // 竖直镜像线：文字仍从左向右，锚点变为右端
+        let Geometry::Text(mirrored) = text.transform(&Transform2D::mirror_y()) else {
+            panic!("expected text");
+        };
+        assert!((mirrored.position - Point2::new(-1.0, 2.0)).norm() < 1e-9);
+        assert!(approx_eq(mirrored.rotation, 0.0));
+        assert_eq!(mirrored.alignment, TextAlignment::Right);
+        assert!(approx_eq(mirrored.height, 2.5));
+
+        // 水平镜像线：文字保持正立，挂在镜像后的基线下方
+        let Geometry::Text(mirrored) = text.transform(&Transform2D::mirror_x()) else {
+            panic!("expected text");
+        };
+        assert!((mirrored.position - Point2::new(1.0, -2.0)).norm() < 1e-9);
+        assert!(approx_eq(mirrored.rotation, 0.0));
+        assert_eq!(mirrored.alignment, TextAlignment::TopLeft);
+        assert!(!mirrored.backwards && !mirrored.upside_down);
+    }
 }
 
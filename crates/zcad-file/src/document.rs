@@ -4,11 +4,16 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
+use zcad_core::block::{BlockError, BlockTable};
+use zcad_core::dimstyle::DimStyleManager;
 use zcad_core::entity::{Entity, EntityId};
+use zcad_core::geometry::Geometry;
 use zcad_core::layer::LayerManager;
 use zcad_core::layout::LayoutManager;
 use zcad_core::math::BoundingBox2;
 use zcad_core::spatial::SpatialIndex;
+use zcad_core::textstyle::TextStyleManager;
+use zcad_core::transaction::EditTarget;
 
 /// 文档元数据
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -83,6 +88,15 @@ pub struct Document {
     /// 布局管理器
     pub layout_manager: LayoutManager,
 
+    /// 块定义表
+    pub blocks: BlockTable,
+
+    /// 标注样式管理器
+    pub dim_styles: DimStyleManager,
+
+    /// 文字样式管理器
+    pub text_styles: TextStyleManager,
+
     /// 是否已修改
     modified: bool,
 
@@ -100,6 +114,9 @@ impl Document {
             spatial_index: SpatialIndex::default_grid(),
             views: Vec::new(),
             layout_manager: LayoutManager::new(),
+            blocks: BlockTable::new(),
+            dim_styles: DimStyleManager::new(),
+            text_styles: TextStyleManager::new(),
             modified: false,
             file_path: None,
         }
@@ -151,9 +168,10 @@ impl Document {
     }
 
     /// 添加实体
-    pub fn add_entity(&mut self, entity: Entity) -> EntityId {
+    pub fn add_entity(&mut self, mut entity: Entity) -> EntityId {
         let id = entity.id;
-        let bbox = entity.bounding_box();
+        entity.touch();
+        let bbox = self.blocks.entity_bounding_box(&entity);
 
         self.spatial_index.insert(id, bbox);
         self.entities.insert(id, entity);
@@ -163,10 +181,19 @@ impl Document {
     }
 
     /// 删除实体
+    ///
+    /// 引用该实体的关联标注会解除关联，保持当前位置
     pub fn remove_entity(&mut self, id: &EntityId) -> Option<Entity> {
         self.spatial_index.remove(id);
         self.modified = true;
-        self.entities.remove(id)
+        let removed = self.entities.remove(id)?;
+
+        for entity in self.entities.values_mut() {
+            if let Geometry::Dimension(dim) = &mut entity.geometry {
+                dim.dissociate(id);
+            }
+        }
+        Some(removed)
     }
 
     /// 获取实体
@@ -174,18 +201,47 @@ impl Document {
         self.entities.get(id)
     }
 
-    /// 获取可变实体
+    /// 获取可变实体（视为已修改，分配新的修订号）
     pub fn get_entity_mut(&mut self, id: &EntityId) -> Option<&mut Entity> {
         self.modified = true;
-        self.entities.get_mut(id)
+        let entity = self.entities.get_mut(id)?;
+        entity.touch();
+        Some(entity)
     }
 
     /// 更新实体（并更新空间索引）
-    pub fn update_entity(&mut self, id: &EntityId, entity: Entity) {
-        let bbox = entity.bounding_box();
+    ///
+    /// 引用该实体的关联标注会随之重新计算
+    pub fn update_entity(&mut self, id: &EntityId, mut entity: Entity) {
+        entity.touch();
+        let bbox = self.blocks.entity_bounding_box(&entity);
         self.spatial_index.update(*id, bbox);
         self.entities.insert(*id, entity);
         self.modified = true;
+        self.update_associative_dimensions(id);
+    }
+
+    /// 重新计算引用指定实体的关联标注
+    fn update_associative_dimensions(&mut self, target: &EntityId) {
+        for id in self.associative_dimensions(target) {
+            let Some(mut entity) = self.entities.get(&id).cloned() else { continue };
+            let Geometry::Dimension(dim) = &mut entity.geometry else { continue };
+            if !dim.update_associations(|other| self.entities.get(other).map(|e| &e.geometry)) {
+                continue;
+            }
+            entity.touch();
+            self.spatial_index.update(id, self.blocks.entity_bounding_box(&entity));
+            self.entities.insert(id, entity);
+        }
+    }
+
+    /// 引用指定实体的关联标注
+    fn associative_dimensions(&self, target: &EntityId) -> Vec<EntityId> {
+        self.entities
+            .values()
+            .filter(|e| matches!(&e.geometry, Geometry::Dimension(dim) if dim.references(target)))
+            .map(|e| e.id)
+            .collect()
     }
 
     /// 查询矩形区域内的实体
@@ -206,10 +262,76 @@ impl Document {
 
         self.query_rect(&rect)
             .into_iter()
-            .filter(|e| e.geometry.contains_point(point, tolerance))
+            .filter(|e| self.blocks.entity_contains_point(e, point, tolerance))
             .collect()
     }
 
+    /// 分解块参照
+    ///
+    /// 用块定义中的实体（只展开一层）替换块参照，返回新实体的 ID
+    pub fn explode_entity(&mut self, id: &EntityId) -> Result<Vec<EntityId>, BlockError> {
+        let entity = self.entities.get(id).ok_or(BlockError::NotABlockReference)?;
+        let parts = self.blocks.explode(entity)?;
+
+        self.remove_entity(id);
+        Ok(parts.into_iter().map(|part| self.add_entity(part)).collect())
+    }
+
+    /// 将实体展开为基本图元（块参照按块定义递归展开，标注按标注样式分解）
+    ///
+    /// 块定义缺失或存在循环引用的块参照会被跳过并记录警告
+    pub fn resolve_entities<'a>(&self, entities: impl IntoIterator<Item = &'a Entity>) -> Vec<Entity> {
+        let mut resolved = Vec::new();
+        for entity in entities {
+            match self.blocks.resolve(entity) {
+                Ok(parts) => resolved.extend(parts.into_iter().flat_map(|part| self.explode_dimension(part))),
+                Err(e) => tracing::warn!("Skipping block reference {:?}: {}", entity.id, e),
+            }
+        }
+        resolved
+    }
+
+    /// 按标注样式将标注分解为基本图元，其它实体原样返回
+    fn explode_dimension(&self, entity: Entity) -> Vec<Entity> {
+        let Geometry::Dimension(dim) = &entity.geometry else {
+            return vec![entity];
+        };
+        dim.explode(self.dim_styles.resolve(&dim.style_name))
+            .into_iter()
+            .map(|mut geometry| {
+                self.text_styles.update_fonts(&mut geometry);
+                Entity {
+                    geometry,
+                    ..entity.clone()
+                }
+            })
+            .collect()
+    }
+
+    /// 按文字样式表刷新所有实体和块定义中文字的字体
+    ///
+    /// 加载文件或修改文字样式后调用；字体影响文字范围，因此同时重建空间索引
+    pub fn update_text_fonts(&mut self) {
+        for entity in self.entities.values_mut() {
+            self.text_styles.update_fonts(&mut entity.geometry);
+            entity.touch();
+        }
+        let names: Vec<String> = self.blocks.block_names().into_iter().map(str::to_string).collect();
+        for name in names {
+            if let Some(block) = self.blocks.get_block_mut(&name) {
+                for entity in &mut block.entities {
+                    self.text_styles.update_fonts(&mut entity.geometry);
+                }
+            }
+        }
+        self.rebuild_spatial_index();
+    }
+
+    /// 获取所有实体展开块参照后的基本图元（用于导出）
+    pub fn resolved_entities(&self) -> Vec<Entity> {
+        self.resolve_entities(self.entities.values())
+    }
+
     /// 获取所有实体
     pub fn all_entities(&self) -> impl Iterator<Item = &Entity> {
         self.entities.values()
@@ -224,10 +346,10 @@ impl Document {
     pub fn bounds(&self) -> Option<BoundingBox2> {
         let mut iter = self.entities.values();
         let first = iter.next()?;
-        let mut bbox = first.bounding_box();
+        let mut bbox = self.blocks.entity_bounding_box(first);
 
         for entity in iter {
-            bbox = bbox.union(&entity.bounding_box());
+            bbox = bbox.union(&self.blocks.entity_bounding_box(entity));
         }
 
         Some(bbox)
@@ -259,17 +381,123 @@ impl Document {
     }
 
     /// 重建空间索引
+    ///
+    /// 块参照的范围取决于块定义，修改块表后需要重建
     pub fn rebuild_spatial_index(&mut self) {
         self.spatial_index.clear();
         for (id, entity) in &self.entities {
-            self.spatial_index.insert(*id, entity.bounding_box());
+            self.spatial_index.insert(*id, self.blocks.entity_bounding_box(entity));
         }
     }
 }
 
+/// 事务通过公开的增删改接口编辑文档，空间索引和关联标注随之更新
+impl EditTarget for Document {
+    fn entity(&self, id: &EntityId) -> Option<&Entity> {
+        self.get_entity(id)
+    }
+
+    fn insert_entity(&mut self, entity: Entity) {
+        self.add_entity(entity);
+    }
+
+    fn take_entity(&mut self, id: &EntityId) -> Option<Entity> {
+        self.remove_entity(id)
+    }
+
+    fn replace_entity(&mut self, entity: Entity) {
+        let id = entity.id;
+        self.update_entity(&id, entity);
+    }
+
+    fn dependent_entities(&self, id: &EntityId) -> Vec<EntityId> {
+        self.associative_dimensions(id)
+    }
+
+    fn layers(&self) -> &LayerManager {
+        &self.layers
+    }
+
+    fn layers_mut(&mut self) -> &mut LayerManager {
+        self.modified = true;
+        &mut self.layers
+    }
+}
+
 impl Default for Document {
     fn default() -> Self {
         Self::new()
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zcad_core::associative::AssociativePoint;
+    use zcad_core::geometry::{Dimension, Line};
+    use zcad_core::math::{Point2, Vector2};
+    use zcad_core::snap::SnapType;
+    use zcad_core::transaction::Transaction;
+
+    #[test]
+    fn test_transaction_restores_associative_dimension() {
+        let mut doc = Document::new();
+        let line_id = doc.add_entity(Entity::new(Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0)))));
+        let dim = Dimension::new(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0), Point2::new(5.0, 5.0)).with_associations(
+            Some(AssociativePoint::new(line_id, SnapType::Endpoint, 0)),
+            Some(AssociativePoint::new(line_id, SnapType::Endpoint, 1)),
+        );
+        let dim_id = doc.add_entity(Entity::new(Geometry::Dimension(dim)));
+        let dimension = |doc: &Document| match &doc.get_entity(&dim_id).unwrap().geometry {
+            Geometry::Dimension(dim) => dim.clone(),
+            _ => unreachable!(),
+        };
+
+        // 移动直线，标注随之更新；撤销后恢复
+        let mut transaction = Transaction::new(&mut doc, "移动");
+        transaction.move_entities(&[line_id], Vector2::new(0.0, 10.0));
+        let moved = transaction.commit();
+        assert_eq!(dimension(&doc).definition_point1, Point2::new(0.0, 10.0));
+        moved.undo(&mut doc);
+        assert_eq!(dimension(&doc).definition_point1, Point2::new(0.0, 0.0));
+
+        // 删除直线会解除关联；撤销后关联恢复，再次移动直线时标注继续跟随
+        let mut transaction = Transaction::new(&mut doc, "删除");
+        transaction.remove_entity(&line_id);
+        let deleted = transaction.commit();
+        assert!(!dimension(&doc).is_associative());
+        deleted.undo(&mut doc);
+        assert!(dimension(&doc).references(&line_id));
+        moved.redo(&mut doc);
+        assert_eq!(dimension(&doc).definition_point2, Point2::new(10.0, 10.0));
+    }
+
+    #[test]
+    fn test_edits_assign_new_revisions() {
+        let mut doc = Document::new();
+        let line = Entity::new(Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0))));
+        let line_id = doc.add_entity(line.clone());
+        let dim = Dimension::new(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0), Point2::new(5.0, 5.0))
+            .with_associations(Some(AssociativePoint::new(line_id, SnapType::Endpoint, 0)), None);
+        let dim_id = doc.add_entity(Entity::new(Geometry::Dimension(dim)));
+        let revision = |doc: &Document, id: &EntityId| doc.get_entity(id).unwrap().revision;
+
+        // 更新实体时换新修订号，随之移动的关联标注也是
+        let (line_rev, dim_rev) = (revision(&doc, &line_id), revision(&doc, &dim_id));
+        let mut moved = line.clone();
+        moved.geometry = Geometry::Line(Line::new(Point2::new(0.0, 5.0), Point2::new(10.0, 5.0)));
+        doc.update_entity(&line_id, moved);
+        assert_ne!(revision(&doc, &line_id), line_rev);
+        assert_ne!(revision(&doc, &dim_id), dim_rev);
+
+        // 用旧副本更新同样视为修改
+        let line_rev = revision(&doc, &line_id);
+        doc.update_entity(&line_id, line);
+        assert_ne!(revision(&doc, &line_id), line_rev);
+
+        let line_rev = revision(&doc, &line_id);
+        doc.get_entity_mut(&line_id).unwrap().visible = false;
+        assert_ne!(revision(&doc, &line_id), line_rev);
+    }
+}
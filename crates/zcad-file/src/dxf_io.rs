@@ -2,56 +2,268 @@
 //!
 //! 支持AutoCAD DXF格式的读写，包括：
 //! - 模型空间实体
+//! - 块定义（BLOCKS）与块参照（INSERT/MINSERT，含属性）
 //! - 图纸空间（Layout）
 //! - 视口（Viewport）
+//! - 标注样式（DIMSTYLE），标注图形按样式生成匿名块（*D）
+//! - 文字样式（STYLE），竖排标志另存于 ZCAD 扩展数据
+//! - 颜色：完整 ACI 调色板、真彩色（组码 420）与随层/随块
 
 use crate::document::Document;
-use crate::dxf_raw::{DxfRawParser, DxfWriter, parse_layouts, parse_viewports};
+use crate::dxf_raw::{
+    DxfRawParser, DxfWriter, parse_layer_true_colors, parse_layouts, parse_vertical_text_styles, parse_viewports,
+};
 use crate::error::FileError;
 use std::path::Path;
+use zcad_core::block::{AttributeDefinition, Block, BlockAttribute, BlockReference};
+use zcad_core::dimstyle::{self, DimStyle, DimTextAlignment, DimTextVertical};
 use zcad_core::entity::Entity;
+use zcad_core::gdt::{DatumReference, GdtSymbol, MaterialCondition, Tolerance, ToleranceRow, ToleranceValue};
 use zcad_core::geometry::{
-    Arc, Circle, Ellipse, Geometry, Leader, Line, Polyline, PolylineVertex, 
-    Spline, Text,
+    Arc, Circle, Dimension, DimensionType, Ellipse, Geometry, Hatch, HatchBoundaryElement, HatchPatternType, Leader,
+    Line, OrdinateAxis, Polyline, PolylineVertex, Spline, SplineType, Text, TextAlignment,
 };
 use zcad_core::layout::{Layout, PaperSize, PaperOrientation, Viewport, ViewportId, ViewportStatus};
-use zcad_core::math::{Point2, Vector2};
+use zcad_core::hatch_pattern;
+use zcad_core::math::{Point2, Vector2, EPSILON};
+use zcad_core::mtext::{MText, MTextAttachment};
 use zcad_core::properties::{Color, Properties};
+use zcad_core::tessellation;
+use zcad_core::textstyle::TextStyle;
+use zcad_core::units::{LinearFormat, Unit};
 
 /// 从DXF文件导入
 pub fn import(path: &Path) -> Result<Document, FileError> {
     let drawing = dxf::Drawing::load_file(path).map_err(|e| FileError::Dxf(e.to_string()))?;
 
     let mut document = Document::new();
+    let mut raw_parser = DxfRawParser::load(path).ok();
 
-    // 导入图层
+    // 导入图层（dxf 库不读取图层的真彩色，由原始解析器读取）
+    let layer_true_colors = raw_parser.as_mut().map(parse_layer_true_colors).unwrap_or_default();
     for layer in drawing.layers() {
-        let color = aci_to_color(layer.color.index().unwrap_or(7) as u8);
+        let true_color = layer_true_colors
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(&layer.name))
+            .map(|(_, rgb)| *rgb)
+            .or_else(|| true_color_from_xdata(&layer.x_data));
+        let color = match true_color {
+            Some(rgb) => Color::from_hex(rgb),
+            None => Color::from_aci(layer.color.index().unwrap_or(7)),
+        };
         let new_layer = zcad_core::layer::Layer::new(&layer.name).with_color(color);
         document.layers.add_layer(new_layer);
     }
 
-    // 导入模型空间实体
-    for entity in drawing.entities() {
-        if let Some(zcad_entity) = convert_dxf_entity(entity) {
-            document.add_entity(zcad_entity);
+    // 导入文字样式（dxf 库不读取 STYLE 的标志位，竖排标志由原始解析器读取）
+    let vertical_styles = raw_parser.as_mut().map(parse_vertical_text_styles).unwrap_or_default();
+    for dxf_style in drawing.styles() {
+        let mut style = text_style_from_dxf(dxf_style);
+        style.vertical |= vertical_styles.iter().any(|name| name.eq_ignore_ascii_case(&dxf_style.name));
+        document.text_styles.set_style(style);
+    }
+    document
+        .text_styles
+        .set_current_style(&text_style_name_from_dxf(&drawing.header.text_style));
+
+    // 导入标注样式
+    for dxf_style in drawing.dim_styles() {
+        let mut style = dim_style_from_dxf(dxf_style, drawing.header.version);
+        // DIMTXSTY 是文字样式的句柄
+        if let Some(text_style) = drawing
+            .styles()
+            .find(|s| s.handle.as_string().eq_ignore_ascii_case(&dxf_style.dimension_text_style))
+        {
+            style.text_style = text_style_name_from_dxf(&text_style.name);
         }
+        document.dim_styles.set_style(style);
+    }
+    document
+        .dim_styles
+        .set_current_style(&dim_style_name_from_dxf(&drawing.header.dimension_style_name));
+
+    // 导入块定义（块参照依赖块表，需先于实体导入）
+    import_blocks(&drawing, &mut document);
+
+    // 导入模型空间实体
+    for zcad_entity in convert_dxf_entities(&document, drawing.entities()) {
+        document.add_entity(zcad_entity);
     }
 
     // 使用原始解析器导入完整的布局和视口信息
-    if let Ok(mut raw_parser) = DxfRawParser::load(path) {
-        import_layouts_full(&mut raw_parser, &drawing, &mut document);
+    if let Some(raw_parser) = raw_parser.as_mut() {
+        import_layouts_full(raw_parser, &drawing, &mut document);
     } else {
         // 回退到简化模式
         import_layouts_simplified(&drawing, &mut document);
     }
 
+    // 按文字样式设置文字的字体
+    document.update_text_fonts();
+
     // 设置文件路径
     document.set_file_path(path);
 
     Ok(document)
 }
 
+/// 是否是布局块（*Model_Space、*Paper_Space 等），这些块的内容作为空间实体导入
+fn is_layout_block(name: &str) -> bool {
+    let name = name.to_ascii_uppercase();
+    name.starts_with("*MODEL_SPACE") || name.starts_with("*PAPER_SPACE")
+}
+
+/// DXF 标注和形位公差的文字高度来自标注样式（DIMTXT）
+fn apply_dim_style_text_height(document: &Document, entity: &mut Entity) {
+    match &mut entity.geometry {
+        Geometry::Dimension(dim) => dim.text_height = document.dim_styles.resolve(&dim.style_name).text_height,
+        Geometry::Tolerance(tolerance) => {
+            tolerance.text_height = document.dim_styles.resolve(&tolerance.style_name).text_height;
+        }
+        _ => {}
+    }
+}
+
+/// 转换一组 DXF 实体
+///
+/// 注释类型为形位公差的 LEADER 附加到末端最近的 TOLERANCE 上（末端须在一个框格高度之内），
+/// 找不到对应框格时作为普通引线导入
+fn convert_dxf_entities<'a>(
+    document: &Document,
+    entities: impl IntoIterator<Item = &'a dxf::entities::Entity>,
+) -> Vec<Entity> {
+    let mut converted = Vec::new();
+    let mut tolerance_leaders = Vec::new();
+    for entity in entities {
+        let Some(mut zcad_entity) = convert_dxf_entity(entity) else {
+            continue;
+        };
+        apply_dim_style_text_height(document, &mut zcad_entity);
+        match &entity.specific {
+            dxf::entities::EntityType::Leader(leader)
+                if leader.annotation_type == dxf::enums::LeaderCreationAnnotationType::WithToleranceAnnotation =>
+            {
+                tolerance_leaders.push(zcad_entity)
+            }
+            _ => converted.push(zcad_entity),
+        }
+    }
+
+    for leader_entity in tolerance_leaders {
+        let Geometry::Leader(leader) = &leader_entity.geometry else {
+            continue;
+        };
+        let Some(end) = leader.vertices.last() else {
+            continue;
+        };
+        let target = converted
+            .iter_mut()
+            .filter_map(|e| match &mut e.geometry {
+                Geometry::Tolerance(tolerance) if tolerance.leader.is_none() => {
+                    let distance = (tolerance.position - end).norm();
+                    (distance <= tolerance.frame_height()).then_some((distance, tolerance))
+                }
+                _ => None,
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0));
+        match target {
+            Some((_, tolerance)) => tolerance.leader = Some(leader.clone()),
+            None => converted.push(leader_entity),
+        }
+    }
+    converted
+}
+
+/// 是否是标注图形块（*D），这些块由标注按样式重新生成，不导入
+fn is_dimension_block(name: &str) -> bool {
+    name.len() > 2 && name[..2].eq_ignore_ascii_case("*D") && name[2..].bytes().all(|b| b.is_ascii_digit())
+}
+
+/// 导入块定义
+fn import_blocks(drawing: &dxf::Drawing, document: &mut Document) {
+    for dxf_block in drawing.blocks() {
+        if is_layout_block(&dxf_block.name) || is_dimension_block(&dxf_block.name) {
+            continue;
+        }
+
+        let base_point = Point2::new(dxf_block.base_point.x, dxf_block.base_point.y);
+        let mut block = Block::new(&dxf_block.name, base_point).with_description(&dxf_block.description);
+        block.is_anonymous = dxf_block.is_anonymous();
+
+        let mut geometry = Vec::new();
+        for entity in &dxf_block.entities {
+            match &entity.specific {
+                dxf::entities::EntityType::AttributeDefinition(attdef) => {
+                    block.add_attribute_definition(convert_dxf_attribute_definition(attdef));
+                }
+                _ => geometry.push(entity),
+            }
+        }
+        for zcad_entity in convert_dxf_entities(document, geometry) {
+            block.add_entity(zcad_entity);
+        }
+
+        if !document.blocks.add_block(block) {
+            tracing::warn!("DXF 中存在重复的块定义 {}，已忽略", dxf_block.name);
+        }
+    }
+
+    // 循环引用的块无法展开，保留定义但给出警告
+    for name in document.blocks.block_names() {
+        if let Some(cycle) = document.blocks.find_cycle(name) {
+            tracing::warn!("块 {} 存在循环引用: {}", name, cycle.join(" -> "));
+        }
+    }
+}
+
+fn convert_dxf_attribute_definition(attdef: &dxf::entities::AttributeDefinition) -> AttributeDefinition {
+    let position = Point2::new(attdef.location.x, attdef.location.y);
+    let mut text = Text::new(position, attdef.value.clone(), attdef.text_height);
+    text.rotation = attdef.rotation.to_radians();
+    apply_text_style_from_dxf(
+        &mut text,
+        &attdef.text_style_name,
+        attdef.relative_x_scale_factor,
+        attdef.oblique_angle,
+        attdef.text_generation_flags,
+    );
+    apply_text_justification_from_dxf(
+        &mut text,
+        attdef.horizontal_text_justification,
+        attdef.vertical_text_justification,
+        &attdef.second_alignment_point,
+    );
+
+    let mut definition = AttributeDefinition::new(&attdef.text_tag, text);
+    definition.prompt = attdef.prompt.clone();
+    definition.invisible = attdef.is_invisible();
+    definition.constant = attdef.is_constant();
+    definition
+}
+
+fn convert_dxf_attribute(attrib: &dxf::entities::Attribute) -> BlockAttribute {
+    let position = Point2::new(attrib.location.x, attrib.location.y);
+    let mut text = Text::new(position, attrib.value.clone(), attrib.text_height);
+    text.rotation = attrib.rotation.to_radians();
+    apply_text_style_from_dxf(
+        &mut text,
+        &attrib.text_style_name,
+        attrib.relative_x_scale_factor,
+        attrib.oblique_angle,
+        attrib.text_generation_flags,
+    );
+    apply_text_justification_from_dxf(
+        &mut text,
+        attrib.horizontal_text_justification,
+        attrib.vertical_text_justification,
+        &attrib.second_alignment_point,
+    );
+
+    let mut attribute = BlockAttribute::new(&attrib.attribute_tag, text);
+    attribute.invisible = attrib.is_invisible();
+    attribute
+}
+
 /// 完整的布局导入（使用原始解析器）
 fn import_layouts_full(
     raw_parser: &mut DxfRawParser,
@@ -390,18 +602,47 @@ fn convert_dxf_entity(entity: &dxf::entities::Entity) -> Option<Entity> {
             let rotation = text.rotation.to_radians();
             let mut zcad_text = Text::new(position, text.value.clone(), height);
             zcad_text.rotation = rotation;
+            apply_text_style_from_dxf(
+                &mut zcad_text,
+                &text.text_style_name,
+                text.relative_x_scale_factor,
+                text.oblique_angle,
+                text.text_generation_flags,
+            );
+            apply_text_justification_from_dxf(
+                &mut zcad_text,
+                text.horizontal_text_justification,
+                text.vertical_text_justification,
+                &text.second_alignment_point,
+            );
             Geometry::Text(zcad_text)
         }
 
+        // dxf 库在 R13 及以上版本的 ATTRIB/ATTDEF 后总会写出一个空的嵌入 MTEXT，
+        // 读回时成为独立实体，这里跳过空内容的 MTEXT
+        dxf::entities::EntityType::MText(mtext) if mtext.text.is_empty() && mtext.extended_text.is_empty() => {
+            return None;
+        }
+
         dxf::entities::EntityType::MText(mtext) => {
             let position = Point2::new(mtext.insertion_point.x, mtext.insertion_point.y);
-            let height = mtext.initial_text_height;
-            let rotation = mtext.rotation_angle.to_radians();
-            // MText 内容可能包含格式代码，这里简化处理
-            let content = mtext.text.replace("\\P", "\n"); // 简单的换行处理
-            let mut zcad_text = Text::new(position, content, height);
-            zcad_text.rotation = rotation;
-            Geometry::Text(zcad_text)
+            // 超过 250 个字符的内容先写入组码 3，最后一段在组码 1
+            let content = mtext.extended_text.concat() + &mtext.text;
+            let content = content.strip_suffix(MTEXT_ESCAPE_PADDING).map(str::to_string).unwrap_or(content);
+            // 方向以 X 轴方向为准，未给出时使用旋转角
+            let direction = &mtext.x_axis_direction;
+            let rotation = if (direction.x - 1.0).abs() > EPSILON || direction.y.abs() > EPSILON {
+                direction.y.atan2(direction.x)
+            } else {
+                mtext.rotation_angle.to_radians()
+            };
+            let mut zcad_mtext = MText::new(position, content, mtext.initial_text_height)
+                .with_width(mtext.reference_rectangle_width.max(0.0))
+                .with_rotation(rotation)
+                .with_attachment(mtext_attachment_from_dxf(mtext.attachment_point))
+                .with_line_spacing(mtext.line_spacing_factor);
+            zcad_mtext.style_name = text_style_name_from_dxf(&mtext.text_style_name);
+            Geometry::MText(zcad_mtext)
         }
 
         dxf::entities::EntityType::ModelPoint(point) => {
@@ -418,29 +659,7 @@ fn convert_dxf_entity(entity: &dxf::entities::Entity) -> Option<Entity> {
             Geometry::Ellipse(Ellipse::arc(center, major_axis, ratio, start_param, end_param))
         }
 
-        dxf::entities::EntityType::Spline(spline) => {
-            let degree = spline.degree_of_curve as u8;
-            let control_points: Vec<Point2> = spline
-                .control_points
-                .iter()
-                .map(|p| Point2::new(p.x, p.y))
-                .collect();
-            let knots: Vec<f64> = spline.knot_values.clone();
-            let fit_points: Vec<Point2> = spline
-                .fit_points
-                .iter()
-                .map(|p| Point2::new(p.x, p.y))
-                .collect();
-            let closed = spline.is_closed();
-            
-            let mut zcad_spline = Spline::new(degree);
-            zcad_spline.control_points = control_points;
-            zcad_spline.knots = knots;
-            zcad_spline.fit_points = fit_points;
-            zcad_spline.closed = closed;
-            
-            Geometry::Spline(zcad_spline)
-        }
+        dxf::entities::EntityType::Spline(spline) => Geometry::Spline(spline_from_dxf(spline)),
 
         dxf::entities::EntityType::Leader(leader) => {
             let vertices: Vec<Point2> = leader
@@ -454,6 +673,14 @@ fn convert_dxf_entity(entity: &dxf::entities::Entity) -> Option<Entity> {
             Geometry::Leader(zcad_leader)
         }
 
+        dxf::entities::EntityType::Tolerance(tolerance) => {
+            let position = Point2::new(tolerance.insertion_point.x, tolerance.insertion_point.y);
+            let mut zcad_tolerance = tolerance_from_dxf_text(&tolerance.display_text, position);
+            zcad_tolerance.rotation = tolerance.direction_vector.y.atan2(tolerance.direction_vector.x);
+            zcad_tolerance.style_name = dim_style_name_from_dxf(&tolerance.dimension_style_name);
+            Geometry::Tolerance(zcad_tolerance)
+        }
+
         dxf::entities::EntityType::RotatedDimension(dim) => {
             // RotatedDimension (AcDbRotatedDimension/AcDbAlignedDimension)
             // definition_point_2 (13) = Extension line 1 origin (Start point)
@@ -465,6 +692,7 @@ fn convert_dxf_entity(entity: &dxf::entities::Entity) -> Option<Entity> {
             let location = Point2::new(dim.dimension_base.definition_point_1.x, dim.dimension_base.definition_point_1.y);
             
             let mut zcad_dim = zcad_core::geometry::Dimension::new(p1, p2, location);
+            zcad_dim.style_name = dim_style_name_from_dxf(&dim.dimension_base.dimension_style_name);
             
             match dim.dimension_base.dimension_type {
                 dxf::enums::DimensionType::Aligned => {
@@ -499,6 +727,7 @@ fn convert_dxf_entity(entity: &dxf::entities::Entity) -> Option<Entity> {
 
             let mut zcad_dim = zcad_core::geometry::Dimension::new(center, point_on_curve, text_pos);
             zcad_dim.dim_type = zcad_core::geometry::DimensionType::Radius;
+            zcad_dim.style_name = dim_style_name_from_dxf(&dim.dimension_base.dimension_style_name);
 
             if !dim.dimension_base.text.is_empty() && dim.dimension_base.text != "<>" {
                 zcad_dim.text_override = Some(dim.dimension_base.text.clone());
@@ -522,6 +751,7 @@ fn convert_dxf_entity(entity: &dxf::entities::Entity) -> Option<Entity> {
 
             let mut zcad_dim = zcad_core::geometry::Dimension::new(center, p1, text_pos);
             zcad_dim.dim_type = zcad_core::geometry::DimensionType::Diameter;
+            zcad_dim.style_name = dim_style_name_from_dxf(&dim.dimension_base.dimension_style_name);
 
             if !dim.dimension_base.text.is_empty() && dim.dimension_base.text != "<>" {
                 zcad_dim.text_override = Some(dim.dimension_base.text.clone());
@@ -532,17 +762,65 @@ fn convert_dxf_entity(entity: &dxf::entities::Entity) -> Option<Entity> {
             Geometry::Dimension(zcad_dim)
         }
 
+        dxf::entities::EntityType::AngularThreePointDimension(dim) => {
+            // 10: 尺寸弧上一点，13/14: 两条边上的点（弧长标注为起点/终点），15: 顶点（圆心）
+            let point = |p: &dxf::Point| Point2::new(p.x, p.y);
+            let vertex = point(&dim.definition_point_4);
+            let first = point(&dim.definition_point_2);
+            let second = point(&dim.definition_point_3);
+            let arc_location = point(&dim.dimension_base.definition_point_1);
+            let mut zcad_dim = if is_arc_length_dimension(entity) {
+                Dimension::arc_length(vertex, first, second, arc_location)
+            } else {
+                Dimension::angular(vertex, first, second, arc_location)
+            };
+            apply_dimension_base(&mut zcad_dim, &dim.dimension_base);
+            Geometry::Dimension(zcad_dim)
+        }
+
+        dxf::entities::EntityType::OrdinateDimension(dim) => {
+            // 10: 坐标原点，13: 特征点，14: 引线端点
+            let point = |p: &dxf::Point| Point2::new(p.x, p.y);
+            let axis = if dim.dimension_base.is_ordinate_x_type {
+                OrdinateAxis::X
+            } else {
+                OrdinateAxis::Y
+            };
+            let mut zcad_dim = Dimension::ordinate(
+                point(&dim.definition_point_2),
+                point(&dim.dimension_base.definition_point_1),
+                point(&dim.definition_point_3),
+                axis,
+            );
+            apply_dimension_base(&mut zcad_dim, &dim.dimension_base);
+            Geometry::Dimension(zcad_dim)
+        }
+
+        dxf::entities::EntityType::Insert(insert) => {
+            // INSERT 与 MINSERT 共用同一实体，行列数为 0 或 1 时为普通插入
+            let location = Point2::new(insert.location.x, insert.location.y);
+            let reference = BlockReference::new(&insert.name, location)
+                .with_scale(insert.x_scale_factor, insert.y_scale_factor)
+                .with_rotation_degrees(insert.rotation)
+                .with_array(
+                    insert.column_count.max(1) as u32,
+                    insert.row_count.max(1) as u32,
+                    insert.column_spacing,
+                    insert.row_spacing,
+                )
+                .with_attributes(insert.attributes().map(convert_dxf_attribute).collect());
+            Geometry::Insert(reference)
+        }
+
         // TODO: 支持更多实体类型
         _ => return None,
     };
 
     // 提取属性
-    let color = entity
-        .common
-        .color
-        .index()
-        .map(|i| aci_to_color(i as u8))
-        .unwrap_or(Color::BY_LAYER);
+    let true_color = Some(entity.common.color_24_bit as u32 & 0xFF_FFFF)
+        .filter(|rgb| *rgb != 0)
+        .or_else(|| true_color_from_xdata(&entity.common.x_data));
+    let color = color_from_dxf(&entity.common.color, true_color);
 
     let properties = Properties::with_color(color);
 
@@ -552,18 +830,41 @@ fn convert_dxf_entity(entity: &dxf::entities::Entity) -> Option<Entity> {
 /// 导出到DXF文件
 pub fn export(document: &Document, path: &Path) -> Result<(), FileError> {
     let mut drawing = dxf::Drawing::new();
+    // R12 的 DIMENSION 没有子类标记，dxf 库无法读回；也不支持 LWPOLYLINE、ELLIPSE、SPLINE
+    drawing.header.version = dxf::enums::AcadVersion::R2000;
 
-    // 导出图层
+    // 导出图层（dxf 库不写图层的真彩色，另存于 ZCAD 扩展数据）
     for layer in document.layers.all_layers() {
         let mut dxf_layer = dxf::tables::Layer::default();
         dxf_layer.name = layer.name.clone();
-        dxf_layer.color = dxf::Color::from_index(color_to_aci(&layer.color));
+        let (color, true_color) = color_to_dxf(&layer.color);
+        dxf_layer.color = if color.index().is_some() { color } else { dxf::Color::from_index(7) };
+        if let Some(rgb) = true_color {
+            dxf_layer.x_data.push(true_color_xdata(rgb));
+        }
         drawing.add_layer(dxf_layer);
     }
 
+    // 导出文字样式（标注样式通过句柄引用文字样式，需先导出）
+    export_text_styles(document, &mut drawing);
+
+    // 导出标注样式
+    for style in document.dim_styles.styles() {
+        let mut dxf_style = dim_style_to_dxf(style);
+        let text_style = text_style_name_to_dxf(&style.text_style);
+        if let Some(handle) = drawing.styles().find(|s| s.name.eq_ignore_ascii_case(&text_style)).map(|s| s.handle) {
+            dxf_style.dimension_text_style = handle.as_string();
+        }
+        drawing.add_dim_style(dxf_style);
+    }
+    drawing.header.dimension_style_name = dim_style_name_to_dxf(&document.dim_styles.current_style().name);
+
+    // 导出块定义
+    export_blocks(document, &mut drawing);
+
     // 导出模型空间实体
     for entity in document.all_entities() {
-        if let Some(dxf_entity) = convert_to_dxf_entity(entity) {
+        for dxf_entity in convert_entity_into_drawing(entity, document, &mut drawing) {
             drawing.add_entity(dxf_entity);
         }
     }
@@ -571,6 +872,8 @@ pub fn export(document: &Document, path: &Path) -> Result<(), FileError> {
     // 导出图纸空间实体（如果有）
     export_paper_space_entities(document, &mut drawing);
 
+    finish_true_colors(&mut drawing);
+
     drawing
         .save_file(path)
         .map_err(|e| FileError::Dxf(e.to_string()))?;
@@ -584,13 +887,935 @@ fn export_paper_space_entities(document: &Document, drawing: &mut dxf::Drawing)
     for layout in document.layout_manager.layouts() {
         // 导出图纸空间实体
         for entity in &layout.paper_space_entities {
-            if let Some(dxf_entity) = convert_to_dxf_entity(entity) {
+            for dxf_entity in convert_entity_into_drawing(entity, document, drawing) {
                 drawing.add_entity(dxf_entity);
             }
         }
     }
 }
 
+/// 导出块定义（按名称排序，保证输出稳定）
+fn export_blocks(document: &Document, drawing: &mut dxf::Drawing) {
+    for block in sorted_blocks(document) {
+        let mut dxf_block = dxf::Block {
+            name: block.name.clone(),
+            base_point: dxf::Point::new(block.base_point.x, block.base_point.y, 0.0),
+            description: block.description.clone(),
+            ..Default::default()
+        };
+        dxf_block.set_is_anonymous(block.is_anonymous);
+        dxf_block.set_has_non_consistent_attribute_definitions(
+            block.attribute_definitions.iter().any(|def| !def.constant),
+        );
+
+        for entity in &block.entities {
+            dxf_block.entities.extend(convert_entity_into_drawing(entity, document, drawing));
+        }
+        for definition in &block.attribute_definitions {
+            let specific = dxf::entities::EntityType::AttributeDefinition(attribute_definition_to_dxf(definition));
+            dxf_block.entities.push(dxf::entities::Entity::new(specific));
+        }
+
+        drawing.add_block(dxf_block);
+    }
+}
+
+/// 转换需要向图形添加附属对象的实体
+///
+/// - 块参照的属性值作为 ATTRIB 附加到 INSERT 上（属性句柄由图形分配）
+/// - 图案填充见 [`export_pattern_hatch`]
+/// - 标注见 [`export_dimension`]
+/// - 带引线的形位公差输出 TOLERANCE 和注释类型为形位公差的 LEADER，见 [`export_tolerance`]
+fn convert_entity_into_drawing(
+    entity: &Entity,
+    document: &Document,
+    drawing: &mut dxf::Drawing,
+) -> Vec<dxf::entities::Entity> {
+    match &entity.geometry {
+        Geometry::Hatch(hatch) => return export_pattern_hatch(entity, hatch, drawing).into_iter().collect(),
+        Geometry::Dimension(dim) => return export_dimension(entity, dim, document, drawing).into_iter().collect(),
+        Geometry::Tolerance(tolerance) => return export_tolerance(entity, tolerance, document),
+        _ => {}
+    }
+
+    let Some(mut dxf_entity) = convert_to_dxf_entity(entity) else {
+        return Vec::new();
+    };
+    if let (Geometry::Insert(reference), dxf::entities::EntityType::Insert(insert)) =
+        (&entity.geometry, &mut dxf_entity.specific)
+    {
+        for attribute in &reference.attributes {
+            insert.add_attribute(drawing, attribute_to_dxf(attribute));
+        }
+    }
+    vec![dxf_entity]
+}
+
+/// 形位公差导出为 TOLERANCE，样式名按文档解析；引线导出为注释类型为形位公差的 LEADER
+fn export_tolerance(entity: &Entity, tolerance: &Tolerance, document: &Document) -> Vec<dxf::entities::Entity> {
+    let Some(mut dxf_entity) = convert_to_dxf_entity(entity) else {
+        return Vec::new();
+    };
+    if let dxf::entities::EntityType::Tolerance(dxf_tolerance) = &mut dxf_entity.specific {
+        dxf_tolerance.dimension_style_name =
+            dim_style_name_to_dxf(&document.dim_styles.resolve(&tolerance.style_name).name);
+    }
+    let mut exported = vec![dxf_entity];
+    if let Some(leader) = &tolerance.leader {
+        let leader_entity = Entity {
+            geometry: Geometry::Leader(leader.clone()),
+            ..entity.clone()
+        };
+        if let Some(mut dxf_leader) = convert_to_dxf_entity(&leader_entity) {
+            if let dxf::entities::EntityType::Leader(dxf_leader) = &mut dxf_leader.specific {
+                dxf_leader.annotation_type = dxf::enums::LeaderCreationAnnotationType::WithToleranceAnnotation;
+                dxf_leader.use_hookline = false;
+            }
+            exported.push(dxf_leader);
+        }
+    }
+    exported
+}
+
+/// 图案填充导出为匿名块（*X）中的图案线，并返回引用该块的 INSERT
+///
+/// dxf 库不支持 HATCH 实体，这里与 AutoCAD 分解填充的结果一致；
+/// 完整的 HATCH 实体由 [`export_full`] 写出。实心填充暂不导出。
+fn export_pattern_hatch(
+    entity: &Entity,
+    hatch: &Hatch,
+    drawing: &mut dxf::Drawing,
+) -> Option<dxf::entities::Entity> {
+    let segments = hatch_pattern::pattern_segments(hatch);
+    if segments.is_empty() {
+        return None;
+    }
+
+    let name = anonymous_block_name(drawing, "*X");
+
+    let mut block = dxf::Block {
+        name: name.clone(),
+        ..Default::default()
+    };
+    block.set_is_anonymous(true);
+    for [a, b] in segments {
+        let p1 = dxf::Point::new(a.x, a.y, 0.0);
+        // 图案中的点（零长度线段）导出为 POINT
+        let specific = if (b - a).norm() < EPSILON {
+            dxf::entities::EntityType::ModelPoint(dxf::entities::ModelPoint::new(p1))
+        } else {
+            dxf::entities::EntityType::Line(dxf::entities::Line::new(p1, dxf::Point::new(b.x, b.y, 0.0)))
+        };
+        let mut dxf_entity = dxf::entities::Entity::new(specific);
+        dxf_entity.common.color = dxf::Color::by_block();
+        block.entities.push(dxf_entity);
+    }
+    drawing.add_block(block);
+
+    let mut reference = entity.clone();
+    reference.geometry = Geometry::Insert(BlockReference::new(name, Point2::origin()));
+    convert_to_dxf_entity(&reference)
+}
+
+/// 未使用的匿名块名称（如 *D1、*X2）
+fn anonymous_block_name(drawing: &dxf::Drawing, prefix: &str) -> String {
+    let mut index = 1;
+    while drawing.blocks().any(|b| b.name == format!("{}{}", prefix, index)) {
+        index += 1;
+    }
+    format!("{}{}", prefix, index)
+}
+
+/// 导出标注，并将按标注样式分解的图形写入匿名块（*D）
+///
+/// 其它程序读取 DXF 时直接显示该块，不必自行按样式重新生成
+fn export_dimension(
+    entity: &Entity,
+    dim: &Dimension,
+    document: &Document,
+    drawing: &mut dxf::Drawing,
+) -> Option<dxf::entities::Entity> {
+    let mut dxf_entity = convert_to_dxf_entity(entity)?;
+    let style = document.dim_styles.resolve(&dim.style_name);
+
+    let name = anonymous_block_name(drawing, "*D");
+    let mut block = dxf::Block {
+        name: name.clone(),
+        ..Default::default()
+    };
+    block.set_is_anonymous(true);
+    for geometry in dim.explode(style) {
+        let part = Entity::new(geometry);
+        let converted = match &part.geometry {
+            Geometry::Hatch(hatch) => dimension_fill_to_dxf(hatch),
+            _ => convert_to_dxf_entity(&part).into_iter().collect(),
+        };
+        for mut dxf_part in converted {
+            dxf_part.common.color = dxf::Color::by_block();
+            block.entities.push(dxf_part);
+        }
+    }
+    drawing.add_block(block);
+
+    if let Some(base) = dimension_base_mut(&mut dxf_entity.specific) {
+        base.dimension_style_name = dim_style_name_to_dxf(&style.name);
+        base.block_name = name;
+    }
+    if dim.dim_type == DimensionType::ArcLength {
+        if !drawing.app_ids().any(|app| app.name == ZCAD_APP_ID) {
+            drawing.add_app_id(dxf::tables::AppId {
+                name: ZCAD_APP_ID.to_string(),
+                ..Default::default()
+            });
+        }
+        dxf_entity.common.x_data.push(dxf::XData {
+            application_name: ZCAD_APP_ID.to_string(),
+            items: vec![dxf::XDataItem::Str(ARC_LENGTH_XDATA.to_string())],
+        });
+    }
+    Some(dxf_entity)
+}
+
+/// 标注箭头的实心填充：三角形导出为 SOLID，其它形状导出边界
+fn dimension_fill_to_dxf(hatch: &Hatch) -> Vec<dxf::entities::Entity> {
+    tessellation::hatch_loops(hatch, tessellation::DEFAULT_TOLERANCE)
+        .into_iter()
+        .filter_map(|mut ring| {
+            if ring.len() > 3 && (ring[0] - ring[ring.len() - 1]).norm() < EPSILON {
+                ring.pop();
+            }
+            let point = |p: &Point2| dxf::Point::new(p.x, p.y, 0.0);
+            let specific = if ring.len() == 3 {
+                dxf::entities::EntityType::Solid(dxf::entities::Solid::new(
+                    point(&ring[0]),
+                    point(&ring[1]),
+                    point(&ring[2]),
+                    point(&ring[2]),
+                ))
+            } else {
+                let outline = Polyline::new(ring.iter().copied().map(PolylineVertex::new).collect(), true);
+                return convert_to_dxf_entity(&Entity::new(Geometry::Polyline(outline)));
+            };
+            Some(dxf::entities::Entity::new(specific))
+        })
+        .collect()
+}
+
+fn dimension_base_mut(specific: &mut dxf::entities::EntityType) -> Option<&mut dxf::entities::DimensionBase> {
+    match specific {
+        dxf::entities::EntityType::RotatedDimension(dim) => Some(&mut dim.dimension_base),
+        dxf::entities::EntityType::RadialDimension(dim) => Some(&mut dim.dimension_base),
+        dxf::entities::EntityType::DiameterDimension(dim) => Some(&mut dim.dimension_base),
+        dxf::entities::EntityType::AngularThreePointDimension(dim) => Some(&mut dim.dimension_base),
+        dxf::entities::EntityType::OrdinateDimension(dim) => Some(&mut dim.dimension_base),
+        _ => None,
+    }
+}
+
+/// 标记弧长标注的扩展数据应用名
+const ZCAD_APP_ID: &str = "ZCAD";
+/// 弧长标注导出为三点角度标注时附加的扩展数据标记
+const ARC_LENGTH_XDATA: &str = "ARC_LENGTH";
+
+/// 三点角度标注是否带有弧长标注的扩展数据标记
+fn is_arc_length_dimension(entity: &dxf::entities::Entity) -> bool {
+    entity.common.x_data.iter().any(|x| {
+        x.application_name == ZCAD_APP_ID
+            && x.items.iter().any(|item| matches!(item, dxf::XDataItem::Str(s) if s == ARC_LENGTH_XDATA))
+    })
+}
+
+/// 读取标注的通用属性：样式、覆盖文本和用户指定的文字位置
+fn apply_dimension_base(dim: &mut Dimension, base: &dxf::entities::DimensionBase) {
+    dim.style_name = dim_style_name_from_dxf(&base.dimension_style_name);
+    if !base.text.is_empty() && base.text != "<>" {
+        dim.text_override = Some(base.text.clone());
+    }
+    if base.is_at_user_defined_location {
+        dim.text_position = Some(Point2::new(base.text_mid_point.x, base.text_mid_point.y));
+    }
+}
+
+/// TOLERANCE 显示文本中框格的分隔符
+const GDT_CELL_SEPARATOR: &str = "%%v";
+/// TOLERANCE 显示文本中的换行
+const GDT_LINE_SEPARATOR: &str = "^J";
+
+/// GDT 字体中几何特征符号的字符
+fn gdt_symbol_code(symbol: GdtSymbol) -> char {
+    match symbol {
+        GdtSymbol::Angularity => 'a',
+        GdtSymbol::Perpendicularity => 'b',
+        GdtSymbol::Flatness => 'c',
+        GdtSymbol::ProfileOfSurface => 'd',
+        GdtSymbol::Circularity => 'e',
+        GdtSymbol::Parallelism => 'f',
+        GdtSymbol::Cylindricity => 'g',
+        GdtSymbol::CircularRunout => 'h',
+        GdtSymbol::Symmetry => 'i',
+        GdtSymbol::Position => 'j',
+        GdtSymbol::ProfileOfLine => 'k',
+        GdtSymbol::Concentricity => 'r',
+        GdtSymbol::TotalRunout => 't',
+        GdtSymbol::Straightness => 'u',
+    }
+}
+
+fn gdt_symbol_from_code(code: char) -> Option<GdtSymbol> {
+    GdtSymbol::ALL.into_iter().find(|&symbol| gdt_symbol_code(symbol) == code)
+}
+
+/// GDT 字体中包容条件的字符与圆圈内的字母相同（小写）
+fn material_from_code(code: char) -> Option<MaterialCondition> {
+    match code {
+        'm' => Some(MaterialCondition::Maximum),
+        'l' => Some(MaterialCondition::Least),
+        's' => Some(MaterialCondition::RegardlessOfFeatureSize),
+        _ => None,
+    }
+}
+
+fn gdt_code(code: char) -> String {
+    format!("{{\\Fgdt;{}}}", code)
+}
+
+fn material_to_dxf(material: Option<MaterialCondition>) -> String {
+    material.map(|m| gdt_code(m.letter().to_ascii_lowercase())).unwrap_or_default()
+}
+
+/// 形位公差编码为 TOLERANCE 显示文本
+///
+/// 每行依次为符号、公差 1、公差 2 和三个基准，以 `%%v` 分隔（省略末尾的空框格，但至少保留一个分隔符）；
+/// 行之间以 `^J` 分隔，其后依次为延伸公差带（数值加 `{\Fgdt;p}`）和基准标识符
+fn tolerance_to_dxf_text(tolerance: &Tolerance) -> String {
+    let tolerance_value = |value: &Option<ToleranceValue>| {
+        value.as_ref().map_or_else(String::new, |v| {
+            let diameter = if v.diameter { gdt_code('n') } else { String::new() };
+            format!("{}{}{}", diameter, v.value, material_to_dxf(v.material))
+        })
+    };
+    let mut lines: Vec<String> = tolerance
+        .rows
+        .iter()
+        .filter(|row| !row.is_empty())
+        .map(|row| {
+            let mut cells = vec![
+                row.symbol.map(|s| gdt_code(gdt_symbol_code(s))).unwrap_or_default(),
+                tolerance_value(&row.tolerance1),
+                tolerance_value(&row.tolerance2),
+            ];
+            cells.extend(
+                row.datums
+                    .iter()
+                    .take(zcad_core::gdt::MAX_DATUMS)
+                    .map(|d| format!("{}{}", d.datum, material_to_dxf(d.material))),
+            );
+            while cells.len() > 2 && cells.last().is_some_and(String::is_empty) {
+                cells.pop();
+            }
+            cells.join(GDT_CELL_SEPARATOR)
+        })
+        .collect();
+    if let Some(height) = tolerance.projected_zone.as_ref().filter(|s| !s.is_empty()) {
+        lines.push(format!("{}{}", height, gdt_code('p')));
+    }
+    if let Some(identifier) = tolerance.datum_identifier.as_ref().filter(|s| !s.is_empty()) {
+        lines.push(identifier.clone());
+    }
+    lines.join(GDT_LINE_SEPARATOR)
+}
+
+/// 拆分框格内容：普通文字和 GDT 字体字符（`{\Fgdt;x}`，`%%c` 视为直径符号 n）
+fn split_gdt_cell(cell: &str) -> (String, Vec<char>) {
+    let mut text = String::new();
+    let mut codes = Vec::new();
+    let mut rest = cell;
+    while !rest.is_empty() {
+        let lower = rest.to_ascii_lowercase();
+        if lower.starts_with("{\\fgdt;") {
+            let end = rest.find('}').unwrap_or(rest.len());
+            codes.extend(lower[7..end].chars());
+            rest = &rest[(end + 1).min(rest.len())..];
+        } else if lower.starts_with("%%c") {
+            codes.push('n');
+            rest = &rest[3..];
+        } else {
+            let c = rest.chars().next().unwrap_or_default();
+            text.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    (text.trim().to_string(), codes)
+}
+
+/// 解析 TOLERANCE 显示文本，见 [`tolerance_to_dxf_text`]
+fn tolerance_from_dxf_text(text: &str, position: Point2) -> Tolerance {
+    let mut tolerance = Tolerance::new(position, DimStyle::default().text_height);
+    let tolerance_value = |cell: &str| {
+        let (value, codes) = split_gdt_cell(cell);
+        let diameter = codes.contains(&'n');
+        let material = codes.iter().find_map(|&c| material_from_code(c));
+        (diameter || material.is_some() || !value.is_empty()).then_some(ToleranceValue {
+            value,
+            diameter,
+            material,
+        })
+    };
+
+    for line in text.split(GDT_LINE_SEPARATOR) {
+        if line.contains(GDT_CELL_SEPARATOR) {
+            let cells: Vec<&str> = line.split(GDT_CELL_SEPARATOR).collect();
+            let cell = |i: usize| cells.get(i).copied().unwrap_or_default();
+            let (_, symbol_codes) = split_gdt_cell(cell(0));
+            let row = ToleranceRow {
+                symbol: symbol_codes.into_iter().find_map(gdt_symbol_from_code),
+                tolerance1: tolerance_value(cell(1)),
+                tolerance2: tolerance_value(cell(2)),
+                datums: (3..3 + zcad_core::gdt::MAX_DATUMS)
+                    .filter_map(|i| {
+                        let (datum, codes) = split_gdt_cell(cell(i));
+                        (!datum.is_empty()).then(|| DatumReference {
+                            datum,
+                            material: codes.into_iter().find_map(material_from_code),
+                        })
+                    })
+                    .collect(),
+            };
+            tolerance.rows.push(row);
+        } else {
+            let (value, codes) = split_gdt_cell(line);
+            if codes.contains(&'p') {
+                tolerance.projected_zone = Some(value);
+            } else if !value.is_empty() {
+                tolerance.datum_identifier = Some(value);
+            }
+        }
+    }
+    tolerance
+}
+
+/// DXF 中默认标注样式名为 STANDARD，对应 ZCAD 的 "Standard"
+fn dim_style_name_from_dxf(name: &str) -> String {
+    if name.eq_ignore_ascii_case("STANDARD") {
+        DimStyle::default().name
+    } else {
+        name.to_string()
+    }
+}
+
+fn dim_style_name_to_dxf(name: &str) -> String {
+    if name.eq_ignore_ascii_case(&DimStyle::default().name) {
+        "STANDARD".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+fn text_style_name_from_dxf(name: &str) -> String {
+    if name.eq_ignore_ascii_case("STANDARD") {
+        TextStyle::default().name
+    } else {
+        name.to_string()
+    }
+}
+
+/// 未指定样式的文字使用 DXF 默认样式
+fn text_style_name_to_dxf(name: &str) -> String {
+    if name.is_empty() || name.eq_ignore_ascii_case(&TextStyle::default().name) {
+        "STANDARD".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// 文字生成标志：反向
+const TEXT_BACKWARDS: i32 = 2;
+/// 文字生成标志：倒置
+const TEXT_UPSIDE_DOWN: i32 = 4;
+/// 竖排文字样式的扩展数据标记
+const VERTICAL_XDATA: &str = "VERTICAL";
+
+/// DXF 倾斜角（度，可能以 0~360 表示负角度）转弧度
+fn oblique_angle_from_dxf(degrees: f64) -> f64 {
+    let degrees = if degrees > 180.0 { degrees - 360.0 } else { degrees };
+    degrees.to_radians()
+}
+
+/// 倾斜角转 DXF 的 0~360 度
+fn oblique_angle_to_dxf(radians: f64) -> f64 {
+    radians.to_degrees().rem_euclid(360.0)
+}
+
+fn text_generation_flags(text: &Text) -> i32 {
+    let mut flags = 0;
+    if text.backwards {
+        flags |= TEXT_BACKWARDS;
+    }
+    if text.upside_down {
+        flags |= TEXT_UPSIDE_DOWN;
+    }
+    flags
+}
+
+/// 读取 DXF 单行文字（及属性）的样式、宽度系数、倾斜角和镜像标志
+fn apply_text_style_from_dxf(text: &mut Text, style_name: &str, width_factor: f64, oblique: f64, flags: i32) {
+    text.style_name = text_style_name_from_dxf(style_name);
+    text.width_factor = if width_factor > 0.0 { width_factor } else { 1.0 };
+    text.oblique_angle = oblique_angle_from_dxf(oblique);
+    text.backwards = flags & TEXT_BACKWARDS != 0;
+    text.upside_down = flags & TEXT_UPSIDE_DOWN != 0;
+}
+
+/// DXF 文字对正方式（组码 72/73）转对齐方式；对齐、中间和布满忽略竖直对正
+fn text_alignment_from_dxf(
+    horizontal: dxf::enums::HorizontalTextJustification,
+    vertical: dxf::enums::VerticalTextJustification,
+) -> TextAlignment {
+    use dxf::enums::HorizontalTextJustification as H;
+    use dxf::enums::VerticalTextJustification as V;
+    match (horizontal, vertical) {
+        (H::Aligned, _) => TextAlignment::Aligned,
+        (H::Middle, _) => TextAlignment::Middle,
+        (H::Fit, _) => TextAlignment::Fit,
+        (H::Left, V::Baseline) => TextAlignment::Left,
+        (H::Center, V::Baseline) => TextAlignment::Center,
+        (H::Right, V::Baseline) => TextAlignment::Right,
+        (H::Left, V::Bottom) => TextAlignment::BottomLeft,
+        (H::Center, V::Bottom) => TextAlignment::BottomCenter,
+        (H::Right, V::Bottom) => TextAlignment::BottomRight,
+        (H::Left, V::Middle) => TextAlignment::MiddleLeft,
+        (H::Center, V::Middle) => TextAlignment::MiddleCenter,
+        (H::Right, V::Middle) => TextAlignment::MiddleRight,
+        (H::Left, V::Top) => TextAlignment::TopLeft,
+        (H::Center, V::Top) => TextAlignment::TopCenter,
+        (H::Right, V::Top) => TextAlignment::TopRight,
+    }
+}
+
+fn text_alignment_to_dxf(
+    alignment: TextAlignment,
+) -> (dxf::enums::HorizontalTextJustification, dxf::enums::VerticalTextJustification) {
+    use dxf::enums::HorizontalTextJustification as H;
+    use dxf::enums::VerticalTextJustification as V;
+    match alignment {
+        TextAlignment::Left => (H::Left, V::Baseline),
+        TextAlignment::Center => (H::Center, V::Baseline),
+        TextAlignment::Right => (H::Right, V::Baseline),
+        TextAlignment::Aligned => (H::Aligned, V::Baseline),
+        TextAlignment::Middle => (H::Middle, V::Baseline),
+        TextAlignment::Fit => (H::Fit, V::Baseline),
+        TextAlignment::BottomLeft => (H::Left, V::Bottom),
+        TextAlignment::BottomCenter => (H::Center, V::Bottom),
+        TextAlignment::BottomRight => (H::Right, V::Bottom),
+        TextAlignment::MiddleLeft => (H::Left, V::Middle),
+        TextAlignment::MiddleCenter => (H::Center, V::Middle),
+        TextAlignment::MiddleRight => (H::Right, V::Middle),
+        TextAlignment::TopLeft => (H::Left, V::Top),
+        TextAlignment::TopCenter => (H::Center, V::Top),
+        TextAlignment::TopRight => (H::Right, V::Top),
+    }
+}
+
+/// 读取文字对正方式：左对齐基线文字以第一对齐点（组码 10）定位，对齐和布满以两个对齐点为基线两端，
+/// 其它方式以第二对齐点（组码 11）定位
+fn apply_text_justification_from_dxf(
+    text: &mut Text,
+    horizontal: dxf::enums::HorizontalTextJustification,
+    vertical: dxf::enums::VerticalTextJustification,
+    second_alignment_point: &dxf::Point,
+) {
+    let second = Point2::new(second_alignment_point.x, second_alignment_point.y);
+    text.alignment = text_alignment_from_dxf(horizontal, vertical);
+    match text.alignment {
+        TextAlignment::Left => {}
+        TextAlignment::Aligned | TextAlignment::Fit => text.end_point = Some(second),
+        _ => text.position = second,
+    }
+}
+
+/// 文字对正方式转 DXF：(第一对齐点, 第二对齐点, 水平对正, 竖直对正)
+///
+/// 非左对齐基线文字的第一对齐点为按字体度量计算的基线起点
+fn text_justification_to_dxf(
+    text: &Text,
+) -> (
+    dxf::Point,
+    dxf::Point,
+    dxf::enums::HorizontalTextJustification,
+    dxf::enums::VerticalTextJustification,
+) {
+    let point = |p: Point2| dxf::Point::new(p.x, p.y, 0.0);
+    let (horizontal, vertical) = text_alignment_to_dxf(text.alignment);
+    let (location, second) = match text.alignment {
+        TextAlignment::Left => (text.position, Point2::origin()),
+        TextAlignment::Aligned | TextAlignment::Fit => {
+            (text.position, text.end_point.unwrap_or_else(|| text.baseline().1))
+        }
+        _ => (text.baseline().0, text.position),
+    };
+    (point(location), point(second), horizontal, vertical)
+}
+
+/// DXF 样条标志：有理（NURBS）
+const SPLINE_RATIONAL: i32 = 4;
+
+/// DXF 样条转 ZCAD 样条
+///
+/// 有理样条读取权重；只有拟合点没有控制点的样条由拟合点插值计算控制点。
+fn spline_from_dxf(spline: &dxf::entities::Spline) -> Spline {
+    let point = |p: &dxf::Point| Point2::new(p.x, p.y);
+    // DXF 中切向为零向量表示未指定
+    let tangent = |p: &dxf::Point| Some(Vector2::new(p.x, p.y)).filter(|v| v.norm() > EPSILON);
+
+    let mut zcad_spline = Spline::new(spline.degree_of_curve as u8);
+    zcad_spline.control_points = spline.control_points.iter().map(point).collect();
+    zcad_spline.knots = spline.knot_values.clone();
+    zcad_spline.fit_points = spline.fit_points.iter().map(point).collect();
+    zcad_spline.closed = spline.is_closed();
+    zcad_spline.start_tangent = tangent(&spline.start_tangent);
+    zcad_spline.end_tangent = tangent(&spline.end_tangent);
+    // 默认公差 1e-10 视为精确插值
+    zcad_spline.fit_tolerance = if spline.fit_tolerance > EPSILON { spline.fit_tolerance } else { 0.0 };
+
+    if spline.flags & SPLINE_RATIONAL != 0 && spline.weight_values.len() == zcad_spline.control_points.len() {
+        zcad_spline.spline_type = SplineType::Nurbs;
+        zcad_spline.weights = spline.weight_values.clone();
+    }
+    if zcad_spline.control_points.is_empty() {
+        zcad_spline.refit();
+    }
+    zcad_spline
+}
+
+/// ZCAD 样条转 DXF 样条
+fn spline_to_dxf(spline: &Spline) -> dxf::entities::Spline {
+    let point = |p: &Point2| dxf::Point::new(p.x, p.y, 0.0);
+    let tangent = |v: &Option<Vector2>| v.map_or_else(dxf::Point::origin, |v| dxf::Point::new(v.x, v.y, 0.0));
+
+    let mut dxf_spline = dxf::entities::Spline {
+        degree_of_curve: spline.degree as i32,
+        control_points: spline.control_points.iter().map(point).collect(),
+        knot_values: spline.knots.clone(),
+        fit_points: spline.fit_points.iter().map(point).collect(),
+        start_tangent: tangent(&spline.start_tangent),
+        end_tangent: tangent(&spline.end_tangent),
+        ..Default::default()
+    };
+    if spline.fit_tolerance > 0.0 {
+        dxf_spline.fit_tolerance = spline.fit_tolerance;
+    }
+    if spline.closed {
+        dxf_spline.flags |= 1; // Closed spline
+    }
+    if spline.spline_type == SplineType::Nurbs || spline.is_rational() {
+        dxf_spline.flags |= SPLINE_RATIONAL;
+        dxf_spline.weight_values = (0..spline.control_points.len()).map(|i| spline.weight(i)).collect();
+    }
+    dxf_spline
+}
+
+/// DXF 文字样式转 ZCAD 文字样式（竖排标志由调用方补充）
+fn text_style_from_dxf(dxf_style: &dxf::tables::Style) -> TextStyle {
+    let vertical = dxf_style.x_data.iter().any(|x| {
+        x.application_name == ZCAD_APP_ID
+            && x.items.iter().any(|item| matches!(item, dxf::XDataItem::Str(s) if s == VERTICAL_XDATA))
+    });
+    TextStyle {
+        name: text_style_name_from_dxf(&dxf_style.name),
+        font_file: dxf_style.primary_font_file_name.clone(),
+        big_font_file: dxf_style.big_font_file_name.clone(),
+        height: dxf_style.text_height,
+        width_factor: if dxf_style.width_factor > 0.0 { dxf_style.width_factor } else { 1.0 },
+        oblique_angle: oblique_angle_from_dxf(dxf_style.oblique_angle),
+        vertical,
+        backwards: dxf_style.text_generation_flags & TEXT_BACKWARDS != 0,
+        upside_down: dxf_style.text_generation_flags & TEXT_UPSIDE_DOWN != 0,
+    }
+}
+
+/// 导出文字样式表，替换 dxf 库默认创建的同名样式
+///
+/// dxf 库写出的 STYLE 标志位始终为 0，竖排样式以 ZCAD 扩展数据标记
+fn export_text_styles(document: &Document, drawing: &mut dxf::Drawing) {
+    for style in document.text_styles.styles() {
+        let name = text_style_name_to_dxf(&style.name);
+        let mut x_data = Vec::new();
+        if style.vertical {
+            if !drawing.app_ids().any(|app| app.name == ZCAD_APP_ID) {
+                drawing.add_app_id(dxf::tables::AppId {
+                    name: ZCAD_APP_ID.to_string(),
+                    ..Default::default()
+                });
+            }
+            x_data.push(dxf::XData {
+                application_name: ZCAD_APP_ID.to_string(),
+                items: vec![dxf::XDataItem::Str(VERTICAL_XDATA.to_string())],
+            });
+        }
+        let mut flags = 0;
+        if style.backwards {
+            flags |= TEXT_BACKWARDS;
+        }
+        if style.upside_down {
+            flags |= TEXT_UPSIDE_DOWN;
+        }
+        let fields = |dxf_style: &mut dxf::tables::Style| {
+            dxf_style.text_height = style.height;
+            dxf_style.width_factor = style.width_factor;
+            dxf_style.oblique_angle = oblique_angle_to_dxf(style.oblique_angle);
+            dxf_style.text_generation_flags = flags;
+            dxf_style.primary_font_file_name = if style.font_file.is_empty() {
+                "txt".to_string()
+            } else {
+                style.font_file.clone()
+            };
+            dxf_style.big_font_file_name = style.big_font_file.clone();
+            dxf_style.x_data = x_data.clone();
+        };
+        if let Some(existing) = drawing.styles_mut().find(|s| s.name.eq_ignore_ascii_case(&name)) {
+            fields(existing);
+            continue;
+        }
+        let mut dxf_style = dxf::tables::Style {
+            name,
+            ..Default::default()
+        };
+        fields(&mut dxf_style);
+        drawing.add_style(dxf_style);
+    }
+    drawing.header.text_style = text_style_name_to_dxf(&document.text_styles.current_style().name);
+}
+
+/// DXF 箭头块名称（DIMBLK），空名称为实心闭合箭头
+fn arrow_from_dxf(block_name: &str) -> dimstyle::ArrowType {
+    use dimstyle::ArrowType;
+    match block_name.to_ascii_uppercase().as_str() {
+        "_CLOSEDBLANK" => ArrowType::ClosedBlank,
+        "_OPEN" => ArrowType::Open,
+        "_DOT" => ArrowType::Dot,
+        "_DOTSMALL" => ArrowType::DotSmall,
+        "_DOTBLANK" => ArrowType::DotBlank,
+        "_ORIGIN" => ArrowType::Origin,
+        "_OPEN90" => ArrowType::RightAngle,
+        "_OBLIQUE" => ArrowType::Oblique,
+        "_NONE" => ArrowType::None,
+        "_ARCHTICK" => ArrowType::ArchitecturalTick,
+        "_INTEGRAL" => ArrowType::Integral,
+        _ => ArrowType::ClosedFilled,
+    }
+}
+
+fn arrow_to_dxf(arrow: dimstyle::ArrowType) -> &'static str {
+    use dimstyle::ArrowType;
+    match arrow {
+        ArrowType::ClosedFilled => "",
+        ArrowType::ClosedBlank => "_CLOSEDBLANK",
+        ArrowType::Open => "_OPEN",
+        ArrowType::Dot => "_DOT",
+        ArrowType::DotSmall => "_DOTSMALL",
+        ArrowType::DotBlank => "_DOTBLANK",
+        ArrowType::Origin => "_ORIGIN",
+        ArrowType::RightAngle => "_OPEN90",
+        ArrowType::Oblique => "_OBLIQUE",
+        ArrowType::None => "_NONE",
+        ArrowType::ArchitecturalTick => "_ARCHTICK",
+        ArrowType::Integral => "_INTEGRAL",
+    }
+}
+
+/// DXF 标注样式转 ZCAD 标注样式
+///
+/// 替代单位比例因子（DIMALTF）为毫米到英寸时识别为英寸，否则保留为主单位的比例因子；
+/// DXF 中的下偏差（DIMTM）为正数表示向下，ZCAD 中以负数保存；
+/// 旧版本文件中没有的变量（如 R13 之前的 DIMDEC）保留默认值
+fn dim_style_from_dxf(dxf_style: &dxf::tables::DimStyle, version: dxf::enums::AcadVersion) -> DimStyle {
+    use dxf::enums::AcadVersion;
+
+    let (arrow1, arrow2) = if dxf_style.use_separate_arrow_blocks_for_dimensions {
+        (&dxf_style.first_arrow_block_name, &dxf_style.second_arrow_block_name)
+    } else {
+        (&dxf_style.arrow_block_name, &dxf_style.arrow_block_name)
+    };
+    // DIMPOST："前缀<>后缀"，不含 "<>" 时整体为后缀
+    let (prefix, suffix) = dxf_style
+        .dimensioning_suffix
+        .split_once("<>")
+        .unwrap_or(("", &dxf_style.dimensioning_suffix));
+    let defaults = DimStyle::default();
+    let inch_factor = zcad_core::units::convert(1.0, defaults.linear_unit, Unit::Inch);
+    let (alternate_unit, alternate_scale_factor) =
+        if (dxf_style.alternate_dimensioning_scale_factor - inch_factor).abs() < 1e-9 {
+            (Unit::Inch, 1.0)
+        } else {
+            (defaults.linear_unit, dxf_style.alternate_dimensioning_scale_factor)
+        };
+
+    let mut style = DimStyle {
+        name: dim_style_name_from_dxf(&dxf_style.name),
+        extension_line_extension: dxf_style.dimension_extension_line_extension,
+        extension_line_offset: dxf_style.dimension_extension_line_offset,
+        suppress_extension_line1: dxf_style.suppress_first_dimension_extension_line,
+        suppress_extension_line2: dxf_style.suppress_second_dimension_extension_line,
+        baseline_spacing: dxf_style.dimension_line_increment,
+        arrow_type1: arrow_from_dxf(arrow1),
+        arrow_type2: arrow_from_dxf(arrow2),
+        leader_arrow_type: arrow_from_dxf(&dxf_style.dimension_leader_block_name),
+        arrow_size: dxf_style.dimensioning_arrow_size,
+        text_height: dxf_style.dimensioning_text_height,
+        text_gap: dxf_style.dimension_line_gap.abs(),
+        text_vertical: if dxf_style.text_above_dimension_line {
+            DimTextVertical::Above
+        } else {
+            DimTextVertical::Centered
+        },
+        text_aligned: !dxf_style.dimension_text_inside_horizontal,
+        linear_scale_factor: dxf_style.dimension_linear_measurement_scale_factor,
+        prefix: prefix.to_string(),
+        suffix: suffix.to_string(),
+        show_tolerance: dxf_style.generate_dimension_tolerances,
+        tolerance_upper: dxf_style.dimension_plus_tolerance,
+        tolerance_lower: -dxf_style.dimension_minus_tolerance,
+        show_alternate_units: dxf_style.use_alternate_dimensioning,
+        alternate_unit,
+        alternate_precision: dxf_style.alternate_dimensioning_decimal_places.clamp(0, 8) as u8,
+        alternate_scale_factor,
+        ..defaults
+    };
+
+    if version >= AcadVersion::R13 {
+        style.text_horizontal = match dxf_style.dimension_text_justification {
+            dxf::enums::DimensionTextJustification::AboveLineNextToFirstExtension => DimTextAlignment::Left,
+            dxf::enums::DimensionTextJustification::AboveLineNextToSecondExtension => DimTextAlignment::Right,
+            _ => DimTextAlignment::Center,
+        };
+        style.linear_precision = dxf_style.dimension_unit_tolerance_decimal_places.clamp(0, 8) as u8;
+        style.tolerance_precision = dxf_style.dimension_tolerace_decimal_places.clamp(0, 8) as u8;
+        style.angle_format = match dxf_style.dimensioning_angle_format {
+            dxf::enums::AngleFormat::DegreesMinutesSeconds => zcad_core::units::AngleFormat::DegreesMinutesSeconds,
+            dxf::enums::AngleFormat::Gradians => zcad_core::units::AngleFormat::Gradians,
+            dxf::enums::AngleFormat::Radians => zcad_core::units::AngleFormat::Radians,
+            dxf::enums::AngleFormat::SurveyorsUnits => zcad_core::units::AngleFormat::Surveyors,
+            dxf::enums::AngleFormat::DecimalDegrees => zcad_core::units::AngleFormat::DegreesDecimal,
+        };
+    }
+    if version >= AcadVersion::R2000 {
+        style.linear_format = match dxf_style.dimension_non_angular_units {
+            dxf::enums::NonAngularUnits::Scientific => LinearFormat::Scientific,
+            dxf::enums::NonAngularUnits::Engineering => LinearFormat::Engineering,
+            dxf::enums::NonAngularUnits::Architectural => LinearFormat::Architectural,
+            dxf::enums::NonAngularUnits::Fractional => LinearFormat::Fractional,
+            _ => LinearFormat::Decimal,
+        };
+        style.angle_precision = dxf_style.angular_dimension_precision.clamp(0, 8) as u8;
+    }
+    style
+}
+
+/// ZCAD 标注样式转 DXF 标注样式
+fn dim_style_to_dxf(style: &DimStyle) -> dxf::tables::DimStyle {
+    let separate_arrows = style.arrow_type1 != style.arrow_type2;
+    let dimensioning_suffix = if style.prefix.is_empty() {
+        style.suffix.clone()
+    } else {
+        format!("{}<>{}", style.prefix, style.suffix)
+    };
+    let horizontal = !style.text_aligned;
+
+    dxf::tables::DimStyle {
+        name: dim_style_name_to_dxf(&style.name),
+        dimension_extension_line_extension: style.extension_line_extension,
+        dimension_extension_line_offset: style.extension_line_offset,
+        suppress_first_dimension_extension_line: style.suppress_extension_line1,
+        suppress_second_dimension_extension_line: style.suppress_extension_line2,
+        dimension_line_increment: style.baseline_spacing,
+        use_separate_arrow_blocks_for_dimensions: separate_arrows,
+        arrow_block_name: arrow_to_dxf(style.arrow_type1).to_string(),
+        first_arrow_block_name: arrow_to_dxf(style.arrow_type1).to_string(),
+        second_arrow_block_name: arrow_to_dxf(style.arrow_type2).to_string(),
+        dimension_leader_block_name: arrow_to_dxf(style.leader_arrow_type).to_string(),
+        dimensioning_arrow_size: style.arrow_size,
+        dimensioning_text_height: style.text_height,
+        dimension_line_gap: style.text_gap,
+        dimension_text_justification: match style.text_horizontal {
+            DimTextAlignment::Left => dxf::enums::DimensionTextJustification::AboveLineNextToFirstExtension,
+            DimTextAlignment::Right => dxf::enums::DimensionTextJustification::AboveLineNextToSecondExtension,
+            _ => dxf::enums::DimensionTextJustification::AboveLineCenter,
+        },
+        text_above_dimension_line: style.text_vertical != DimTextVertical::Centered,
+        dimension_text_inside_horizontal: horizontal,
+        dimension_text_outside_horizontal: horizontal,
+        dimension_non_angular_units: match style.linear_format {
+            LinearFormat::Scientific => dxf::enums::NonAngularUnits::Scientific,
+            LinearFormat::Engineering => dxf::enums::NonAngularUnits::Engineering,
+            LinearFormat::Architectural => dxf::enums::NonAngularUnits::Architectural,
+            LinearFormat::Fractional => dxf::enums::NonAngularUnits::Fractional,
+            _ => dxf::enums::NonAngularUnits::Decimal,
+        },
+        dimension_unit_tolerance_decimal_places: style.linear_precision as i16,
+        dimension_linear_measurement_scale_factor: style.linear_scale_factor,
+        dimensioning_suffix,
+        dimensioning_angle_format: match style.angle_format {
+            zcad_core::units::AngleFormat::DegreesMinutesSeconds => dxf::enums::AngleFormat::DegreesMinutesSeconds,
+            zcad_core::units::AngleFormat::Gradians => dxf::enums::AngleFormat::Gradians,
+            zcad_core::units::AngleFormat::Radians => dxf::enums::AngleFormat::Radians,
+            zcad_core::units::AngleFormat::Surveyors => dxf::enums::AngleFormat::SurveyorsUnits,
+            zcad_core::units::AngleFormat::DegreesDecimal => dxf::enums::AngleFormat::DecimalDegrees,
+        },
+        angular_dimension_precision: style.angle_precision as i16,
+        generate_dimension_tolerances: style.show_tolerance,
+        dimension_plus_tolerance: style.tolerance_upper,
+        dimension_minus_tolerance: -style.tolerance_lower,
+        dimension_tolerace_decimal_places: style.tolerance_precision as i16,
+        use_alternate_dimensioning: style.show_alternate_units,
+        alternate_dimensioning_decimal_places: style.alternate_precision as i16,
+        alternate_dimensioning_scale_factor: zcad_core::units::convert(1.0, style.linear_unit, style.alternate_unit)
+            * style.alternate_scale_factor,
+        ..Default::default()
+    }
+}
+
+fn attribute_definition_to_dxf(definition: &AttributeDefinition) -> dxf::entities::AttributeDefinition {
+    let text = &definition.text;
+    let mut attdef = dxf::entities::AttributeDefinition {
+        location: dxf::Point::new(text.position.x, text.position.y, 0.0),
+        text_height: text.height,
+        value: text.content.clone(),
+        rotation: text.rotation.to_degrees(),
+        text_style_name: text_style_name_to_dxf(&text.style_name),
+        relative_x_scale_factor: text.width_factor,
+        oblique_angle: oblique_angle_to_dxf(text.oblique_angle),
+        text_generation_flags: text_generation_flags(text),
+        prompt: definition.prompt.clone(),
+        text_tag: definition.tag.clone(),
+        ..Default::default()
+    };
+    (attdef.location, attdef.second_alignment_point, attdef.horizontal_text_justification, attdef.vertical_text_justification) =
+        text_justification_to_dxf(text);
+    attdef.set_is_invisible(definition.invisible);
+    attdef.set_is_constant(definition.constant);
+    attdef
+}
+
+fn attribute_to_dxf(attribute: &BlockAttribute) -> dxf::entities::Attribute {
+    let text = &attribute.text;
+    let mut attrib = dxf::entities::Attribute {
+        location: dxf::Point::new(text.position.x, text.position.y, 0.0),
+        text_height: text.height,
+        value: text.content.clone(),
+        rotation: text.rotation.to_degrees(),
+        text_style_name: text_style_name_to_dxf(&text.style_name),
+        relative_x_scale_factor: text.width_factor,
+        oblique_angle: oblique_angle_to_dxf(text.oblique_angle),
+        text_generation_flags: text_generation_flags(text),
+        attribute_tag: attribute.tag.clone(),
+        ..Default::default()
+    };
+    (attrib.location, attrib.second_alignment_point, attrib.horizontal_text_justification, attrib.vertical_text_justification) =
+        text_justification_to_dxf(text);
+    attrib.set_is_invisible(attribute.invisible);
+    attrib
+}
+
 /// 使用原始写入器导出完整的 DXF（包括布局和视口）
 /// 
 /// 此函数生成包含完整 Layout/Viewport 信息的 DXF 文件
@@ -672,7 +1897,11 @@ fn write_tables_section(writer: &mut DxfWriter, document: &Document) {
         writer.write_handle_only();
         writer.write_pair(2, &layer.name);
         writer.write_pair(70, if layer.visible { 0 } else { 1 });
-        writer.write_pair(62, color_to_aci(&layer.color) as i32);
+        let (color, true_color) = color_to_dxf(&layer.color);
+        writer.write_pair(62, color.index().unwrap_or(7) as i32);
+        if let Some(rgb) = true_color {
+            writer.write_pair(420, rgb as i32);
+        }
         writer.write_pair(6, "CONTINUOUS");
     }
     
@@ -685,7 +1914,7 @@ fn write_tables_section(writer: &mut DxfWriter, document: &Document) {
     writer.write_pair(0, "TABLE");
     writer.write_pair(2, "BLOCK_RECORD");
     writer.write_handle_only();
-    writer.write_pair(70, 2 + document.layout_manager.layouts().len() as i32);
+    writer.write_pair(70, 2 + document.blocks.block_count() as i32);
     
     // *Model_Space
     writer.write_pair(0, "BLOCK_RECORD");
@@ -697,13 +1926,20 @@ fn write_tables_section(writer: &mut DxfWriter, document: &Document) {
     writer.write_pair(5, &paper_handle);
     writer.write_pair(2, "*Paper_Space");
     
+    // 用户块
+    for block in sorted_blocks(document) {
+        writer.write_pair(0, "BLOCK_RECORD");
+        writer.write_handle_only();
+        writer.write_pair(2, &block.name);
+    }
+    
     writer.write_pair(0, "ENDTAB");
     
     writer.end_section();
 }
 
 /// 写入 BLOCKS 段
-fn write_blocks_section(writer: &mut DxfWriter, _document: &Document) {
+fn write_blocks_section(writer: &mut DxfWriter, document: &Document) {
     writer.begin_section("BLOCKS");
     
     // *Model_Space 块
@@ -732,9 +1968,96 @@ fn write_blocks_section(writer: &mut DxfWriter, _document: &Document) {
     writer.write_handle_only();
     writer.write_pair(8, "0");
     
+    // 用户块
+    for block in sorted_blocks(document) {
+        let has_attributes = block.attribute_definitions.iter().any(|def| !def.constant);
+        let mut flags = 0;
+        if block.is_anonymous {
+            flags |= 1;
+        }
+        if has_attributes {
+            flags |= 2;
+        }
+
+        writer.write_pair(0, "BLOCK");
+        writer.write_handle_only();
+        writer.write_pair(8, "0");
+        writer.write_pair(2, &block.name);
+        writer.write_pair(70, flags);
+        writer.write_pair(10, block.base_point.x);
+        writer.write_pair(20, block.base_point.y);
+        writer.write_pair(30, 0.0);
+        writer.write_pair(3, &block.name);
+        if !block.description.is_empty() {
+            writer.write_pair(4, &block.description);
+        }
+
+        for entity in &block.entities {
+            write_entity(writer, entity, false);
+        }
+        for definition in &block.attribute_definitions {
+            write_attribute_definition(writer, definition);
+        }
+
+        writer.write_pair(0, "ENDBLK");
+        writer.write_handle_only();
+        writer.write_pair(8, "0");
+    }
+    
     writer.end_section();
 }
 
+/// 按名称排序的块定义
+fn sorted_blocks(document: &Document) -> Vec<&Block> {
+    let mut blocks: Vec<&Block> = document.blocks.iter().collect();
+    blocks.sort_by(|a, b| a.name.cmp(&b.name));
+    blocks
+}
+
+/// 写入属性定义（ATTDEF）
+fn write_attribute_definition(writer: &mut DxfWriter, definition: &AttributeDefinition) {
+    let text = &definition.text;
+    let mut flags = 0;
+    if definition.invisible {
+        flags |= 1;
+    }
+    if definition.constant {
+        flags |= 2;
+    }
+
+    writer.write_pair(0, "ATTDEF");
+    writer.write_handle_only();
+    writer.write_pair(8, "0");
+    writer.write_pair(10, text.position.x);
+    writer.write_pair(20, text.position.y);
+    writer.write_pair(30, 0.0);
+    writer.write_pair(40, text.height);
+    writer.write_pair(1, &text.content);
+    writer.write_pair(50, text.rotation.to_degrees());
+    writer.write_pair(3, &definition.prompt);
+    writer.write_pair(2, &definition.tag);
+    writer.write_pair(70, flags);
+}
+
+/// 写入属性值（ATTRIB）
+fn write_attribute(writer: &mut DxfWriter, attribute: &BlockAttribute, is_paper_space: bool) {
+    let text = &attribute.text;
+    writer.write_pair(0, "ATTRIB");
+    writer.write_handle_only();
+    if is_paper_space {
+        writer.write_pair(67, 1);
+    }
+    writer.write_pair(8, "0");
+    writer.write_pair(10, text.position.x);
+    writer.write_pair(20, text.position.y);
+    writer.write_pair(30, 0.0);
+    writer.write_pair(40, text.height);
+    writer.write_pair(1, &text.content);
+    writer.write_pair(2, &attribute.tag);
+    writer.write_pair(70, if attribute.invisible { 1 } else { 0 });
+    writer.write_pair(50, text.rotation.to_degrees());
+}
+
 /// 写入 ENTITIES 段
 fn write_entities_section(writer: &mut DxfWriter, document: &Document) {
     writer.begin_section("ENTITIES");
@@ -833,12 +2156,172 @@ fn write_entity(writer: &mut DxfWriter, entity: &Entity, is_paper_space: bool) {
             writer.write_pair(1, &text.content);
             writer.write_pair(50, text.rotation.to_degrees());
         }
+        Geometry::MText(mtext) => {
+            writer.write_pair(0, "MTEXT");
+            writer.write_handle_only();
+            if is_paper_space {
+                writer.write_pair(67, 1);
+            }
+            writer.write_pair(8, "0");
+            writer.write_pair(10, mtext.position.x);
+            writer.write_pair(20, mtext.position.y);
+            writer.write_pair(30, 0.0);
+            writer.write_pair(40, mtext.height);
+            writer.write_pair(41, mtext.width);
+            writer.write_pair(71, mtext_attachment_to_dxf(mtext.attachment) as i32);
+            let (extended_text, text) = split_mtext_content(&mtext.content);
+            for chunk in &extended_text {
+                writer.write_pair(3, chunk);
+            }
+            writer.write_pair(1, &text);
+            writer.write_pair(50, mtext.rotation.to_degrees());
+            writer.write_pair(44, mtext.line_spacing);
+        }
+        Geometry::Insert(reference) => {
+            writer.write_pair(0, "INSERT");
+            writer.write_handle_only();
+            if is_paper_space {
+                writer.write_pair(67, 1);
+            }
+            writer.write_pair(8, "0");
+            if !reference.attributes.is_empty() {
+                writer.write_pair(66, 1);
+            }
+            writer.write_pair(2, &reference.block_name);
+            writer.write_pair(10, reference.insertion_point.x);
+            writer.write_pair(20, reference.insertion_point.y);
+            writer.write_pair(30, 0.0);
+            writer.write_pair(41, reference.scale_x);
+            writer.write_pair(42, reference.scale_y);
+            writer.write_pair(43, 1.0);
+            writer.write_pair(50, reference.rotation.to_degrees());
+            writer.write_pair(70, reference.column_count as i32);
+            writer.write_pair(71, reference.row_count as i32);
+            writer.write_pair(44, reference.column_spacing);
+            writer.write_pair(45, reference.row_spacing);
+
+            if !reference.attributes.is_empty() {
+                for attribute in &reference.attributes {
+                    write_attribute(writer, attribute, is_paper_space);
+                }
+                writer.write_pair(0, "SEQEND");
+                writer.write_handle_only();
+                writer.write_pair(8, "0");
+            }
+        }
+        Geometry::Hatch(hatch) => write_hatch(writer, hatch, is_paper_space),
         _ => {
             // 其他几何类型暂不支持
         }
     }
 }
 
+/// 写入填充（HATCH）
+///
+/// 边界中的样条按采样点写为线段边；图案线按 DXF 约定写入放置后的世界坐标
+fn write_hatch(writer: &mut DxfWriter, hatch: &Hatch, is_paper_space: bool) {
+    let (pattern_name, pattern_kind) = match &hatch.pattern_type {
+        HatchPatternType::Solid => ("SOLID".to_string(), 1),
+        HatchPatternType::Predefined(name) => (name.clone(), 1),
+        HatchPatternType::Custom { .. } => ("_USER".to_string(), 2),
+    };
+    let is_solid = matches!(hatch.pattern_type, HatchPatternType::Solid);
+
+    writer.write_pair(0, "HATCH");
+    writer.write_handle_only();
+    if is_paper_space {
+        writer.write_pair(67, 1);
+    }
+    writer.write_pair(8, "0");
+    writer.write_pair(100, "AcDbEntity");
+    writer.write_pair(100, "AcDbHatch");
+    writer.write_pair(10, 0.0);
+    writer.write_pair(20, 0.0);
+    writer.write_pair(30, 0.0);
+    writer.write_pair(210, 0.0);
+    writer.write_pair(220, 0.0);
+    writer.write_pair(230, 1.0);
+    writer.write_pair(2, &pattern_name);
+    writer.write_pair(70, if is_solid { 1 } else { 0 });
+    writer.write_pair(71, 0);
+
+    writer.write_pair(91, hatch.boundaries.len() as i32);
+    for boundary in &hatch.boundaries {
+        // 样条边展开为线段，需先确定边数
+        let mut edges: Vec<HatchBoundaryElement> = Vec::new();
+        for element in &boundary.elements {
+            match element {
+                HatchBoundaryElement::Spline(spline) => {
+                    let points = tessellation::spline_points(spline, tessellation::DEFAULT_TOLERANCE);
+                    edges.extend(points.windows(2).map(|w| HatchBoundaryElement::Line(Line::new(w[0], w[1]))));
+                }
+                other => edges.push(other.clone()),
+            }
+        }
+
+        writer.write_pair(92, if boundary.is_outer { 1 } else { 0 });
+        writer.write_pair(93, edges.len() as i32);
+        for edge in &edges {
+            match edge {
+                HatchBoundaryElement::Line(line) => {
+                    writer.write_pair(72, 1);
+                    writer.write_pair(10, line.start.x);
+                    writer.write_pair(20, line.start.y);
+                    writer.write_pair(11, line.end.x);
+                    writer.write_pair(21, line.end.y);
+                }
+                HatchBoundaryElement::Arc(arc) => {
+                    writer.write_pair(72, 2);
+                    writer.write_pair(10, arc.center.x);
+                    writer.write_pair(20, arc.center.y);
+                    writer.write_pair(40, arc.radius);
+                    writer.write_pair(50, arc.start_angle.to_degrees());
+                    writer.write_pair(51, arc.end_angle.to_degrees());
+                    writer.write_pair(73, 1);
+                }
+                HatchBoundaryElement::Ellipse(ellipse) => {
+                    writer.write_pair(72, 3);
+                    writer.write_pair(10, ellipse.center.x);
+                    writer.write_pair(20, ellipse.center.y);
+                    writer.write_pair(11, ellipse.major_axis.x);
+                    writer.write_pair(21, ellipse.major_axis.y);
+                    writer.write_pair(40, ellipse.ratio);
+                    writer.write_pair(50, ellipse.start_param.to_degrees());
+                    writer.write_pair(51, ellipse.end_param.to_degrees());
+                    writer.write_pair(73, 1);
+                }
+                HatchBoundaryElement::Spline(_) => unreachable!("样条边已展开为线段"),
+            }
+        }
+        writer.write_pair(97, 0);
+    }
+
+    writer.write_pair(75, 0); // 奇偶填充
+    writer.write_pair(76, pattern_kind);
+    if !is_solid {
+        let lines = hatch_pattern::PatternLibrary::builtin()
+            .pattern_lines(hatch)
+            .unwrap_or(&[]);
+        writer.write_pair(52, hatch.angle.to_degrees());
+        writer.write_pair(41, hatch.scale);
+        writer.write_pair(77, 0);
+        writer.write_pair(78, lines.len() as i32);
+        for line in lines {
+            let placed = hatch_pattern::place_line(line, hatch);
+            writer.write_pair(53, placed.angle.to_degrees());
+            writer.write_pair(43, placed.base_point.x);
+            writer.write_pair(44, placed.base_point.y);
+            writer.write_pair(45, placed.offset.x);
+            writer.write_pair(46, placed.offset.y);
+            writer.write_pair(79, placed.dash_pattern.len() as i32);
+            for dash in &placed.dash_pattern {
+                writer.write_pair(49, *dash);
+            }
+        }
+    }
+    writer.write_pair(98, 0);
+}
+
 /// 写入视口
 fn write_viewport(writer: &mut DxfWriter, viewport: &Viewport) {
     writer.write_pair(0, "VIEWPORT");
@@ -1011,6 +2494,15 @@ fn convert_to_dxf_entity(entity: &Entity) -> Option<dxf::entities::Entity> {
             dxf_text.text_height = text.height;
             dxf_text.value = text.content.clone();
             dxf_text.rotation = text.rotation.to_degrees();
+            dxf_text.text_style_name = text_style_name_to_dxf(&text.style_name);
+            dxf_text.relative_x_scale_factor = text.width_factor;
+            dxf_text.oblique_angle = oblique_angle_to_dxf(text.oblique_angle);
+            dxf_text.text_generation_flags = text_generation_flags(text);
+            let (location, second, horizontal, vertical) = text_justification_to_dxf(text);
+            dxf_text.location = location;
+            dxf_text.second_alignment_point = second;
+            dxf_text.horizontal_text_justification = horizontal;
+            dxf_text.vertical_text_justification = vertical;
             dxf::entities::EntityType::Text(dxf_text)
         }
         Geometry::Dimension(dim) => {
@@ -1061,6 +2553,34 @@ fn convert_to_dxf_entity(entity: &Entity) -> Option<dxf::entities::Entity> {
                     
                     dxf::entities::EntityType::DiameterDimension(dxf_dim)
                 },
+                zcad_core::geometry::DimensionType::Angular | zcad_core::geometry::DimensionType::ArcLength => {
+                    // 弧长标注在 R2000 中没有对应实体，按三点角度标注导出并用扩展数据标记
+                    base.dimension_type = dxf::enums::DimensionType::AngularThreePoint;
+                    base.is_at_user_defined_location = dim.text_position.is_some();
+                    base.definition_point_1 = dxf::Point::new(dim.line_location.x, dim.line_location.y, 0.0);
+
+                    dxf::entities::EntityType::AngularThreePointDimension(dxf::entities::AngularThreePointDimension {
+                        dimension_base: base,
+                        definition_point_2: dxf::Point::new(dim.definition_point2.x, dim.definition_point2.y, 0.0),
+                        definition_point_3: dxf::Point::new(dim.definition_point3.x, dim.definition_point3.y, 0.0),
+                        definition_point_4: dxf::Point::new(dim.definition_point1.x, dim.definition_point1.y, 0.0),
+                        ..Default::default()
+                    })
+                },
+                zcad_core::geometry::DimensionType::Ordinate => {
+                    base.dimension_type = dxf::enums::DimensionType::Ordinate;
+                    base.is_ordinate_x_type = dim.ordinate_axis == OrdinateAxis::X;
+                    base.is_at_user_defined_location = dim.text_position.is_some();
+                    // 10: 坐标原点
+                    base.definition_point_1 = dxf::Point::new(dim.definition_point2.x, dim.definition_point2.y, 0.0);
+
+                    // 13: 特征点，14: 引线端点
+                    dxf::entities::EntityType::OrdinateDimension(dxf::entities::OrdinateDimension {
+                        dimension_base: base,
+                        definition_point_2: dxf::Point::new(dim.definition_point1.x, dim.definition_point1.y, 0.0),
+                        definition_point_3: dxf::Point::new(dim.line_location.x, dim.line_location.y, 0.0),
+                    })
+                },
                 _ => {
                     // definition_point_1 (10) = Dimension line definition point
                     base.definition_point_1 = dxf::Point::new(dim.line_location.x, dim.line_location.y, 0.0);
@@ -1098,25 +2618,7 @@ fn convert_to_dxf_entity(entity: &Entity) -> Option<dxf::entities::Entity> {
             dxf::entities::EntityType::Ellipse(dxf_ellipse)
         }
 
-        Geometry::Spline(spline) => {
-            let mut dxf_spline = dxf::entities::Spline::default();
-            dxf_spline.degree_of_curve = spline.degree as i32;
-            dxf_spline.control_points = spline
-                .control_points
-                .iter()
-                .map(|p| dxf::Point::new(p.x, p.y, 0.0))
-                .collect();
-            dxf_spline.knot_values = spline.knots.clone();
-            dxf_spline.fit_points = spline
-                .fit_points
-                .iter()
-                .map(|p| dxf::Point::new(p.x, p.y, 0.0))
-                .collect();
-            if spline.closed {
-                dxf_spline.flags |= 1; // Closed spline
-            }
-            dxf::entities::EntityType::Spline(dxf_spline)
-        }
+        Geometry::Spline(spline) => dxf::entities::EntityType::Spline(spline_to_dxf(spline)),
 
         Geometry::Hatch(_hatch) => {
             // TODO: 实现完整的 Hatch 导出
@@ -1124,6 +2626,22 @@ fn convert_to_dxf_entity(entity: &Entity) -> Option<dxf::entities::Entity> {
             return None;
         }
 
+        Geometry::Insert(reference) => {
+            // 属性值需要图形分配句柄，见 convert_entity_into_drawing
+            dxf::entities::EntityType::Insert(dxf::entities::Insert {
+                name: reference.block_name.clone(),
+                location: dxf::Point::new(reference.insertion_point.x, reference.insertion_point.y, 0.0),
+                x_scale_factor: reference.scale_x,
+                y_scale_factor: reference.scale_y,
+                rotation: reference.rotation.to_degrees(),
+                column_count: reference.column_count as i16,
+                row_count: reference.row_count as i16,
+                column_spacing: reference.column_spacing,
+                row_spacing: reference.row_spacing,
+                ..Default::default()
+            })
+        }
+
         Geometry::Leader(leader) => {
             let mut dxf_leader = dxf::entities::Leader::default();
             dxf_leader.vertices = leader
@@ -1133,51 +2651,791 @@ fn convert_to_dxf_entity(entity: &Entity) -> Option<dxf::entities::Entity> {
                 .collect();
             dxf::entities::EntityType::Leader(dxf_leader)
         }
+
+        Geometry::MText(mtext) => {
+            let (extended_text, text) = split_mtext_content(&mtext.content);
+            dxf::entities::EntityType::MText(dxf::entities::MText {
+                insertion_point: dxf::Point::new(mtext.position.x, mtext.position.y, 0.0),
+                initial_text_height: mtext.height,
+                reference_rectangle_width: mtext.width,
+                attachment_point: mtext_attachment_to_dxf(mtext.attachment),
+                extended_text,
+                text,
+                x_axis_direction: dxf::Vector::new(mtext.rotation.cos(), mtext.rotation.sin(), 0.0),
+                rotation_angle: mtext.rotation.to_degrees(),
+                line_spacing_factor: mtext.line_spacing,
+                text_style_name: text_style_name_to_dxf(&mtext.style_name),
+                ..Default::default()
+            })
+        }
+
+        Geometry::Tolerance(tolerance) => dxf::entities::EntityType::Tolerance(dxf::entities::Tolerance {
+            dimension_style_name: dim_style_name_to_dxf(&tolerance.style_name),
+            insertion_point: dxf::Point::new(tolerance.position.x, tolerance.position.y, 0.0),
+            display_text: tolerance_to_dxf_text(tolerance),
+            direction_vector: dxf::Vector::new(tolerance.rotation.cos(), tolerance.rotation.sin(), 0.0),
+            ..Default::default()
+        }),
     };
 
     let mut dxf_entity = dxf::entities::Entity::new(specific);
 
     // 设置颜色
-    if !entity.properties.color.is_by_layer() {
-        dxf_entity.common.color =
-            dxf::Color::from_index(color_to_aci(&entity.properties.color));
+    let (color, true_color) = color_to_dxf(&entity.properties.color);
+    dxf_entity.common.color = color;
+    match true_color {
+        // dxf 库以 0 表示没有真彩色，纯黑另存于 ZCAD 扩展数据
+        Some(0) => dxf_entity.common.x_data.push(true_color_xdata(0)),
+        Some(rgb) => dxf_entity.common.color_24_bit = rgb as i32,
+        None => {}
     }
 
     Some(dxf_entity)
 }
 
-/// AutoCAD颜色索引(ACI)转ZCAD颜色
-fn aci_to_color(aci: u8) -> Color {
-    match aci {
-        1 => Color::RED,
-        2 => Color::YELLOW,
-        3 => Color::GREEN,
-        4 => Color::CYAN,
-        5 => Color::BLUE,
-        6 => Color::MAGENTA,
-        7 => Color::WHITE,
-        8 => Color::GRAY,
-        _ => Color::WHITE,
+/// DXF 组码 1/3 中每段字符串的最大长度
+const MTEXT_CHUNK_LENGTH: usize = 250;
+/// 空分组，不影响格式，用于补足末尾的转义序列
+const MTEXT_ESCAPE_PADDING: &str = "{}";
+
+/// 未结束的转义序列的起点
+///
+/// dxf 库读取字符串时把反斜杠及其后 6 个字符作为一组解析 `\U+XXXX`，
+/// 位于字符串末尾、不足 7 个字符的序列（如 `\P`、`\S1/2;`）会被丢弃
+fn unfinished_escape(chars: &[char]) -> Option<usize> {
+    let mut index = 0;
+    while index < chars.len() {
+        if chars[index] == '\\' {
+            if index + 7 > chars.len() {
+                return Some(index);
+            }
+            index += 7;
+        } else {
+            index += 1;
+        }
     }
+    None
 }
 
-/// ZCAD颜色转AutoCAD颜色索引
-fn color_to_aci(color: &Color) -> u8 {
-    if color.is_by_layer() || color.is_by_block() {
-        return 7; // 默认白色（ByLayer/ByBlock在其他地方处理）
+/// 拆分 MTEXT 内容：前面的整段写入组码 3，最后一段写入组码 1
+///
+/// 分段处避开转义序列，最后一段以转义序列结尾时补一个空分组
+fn split_mtext_content(content: &str) -> (Vec<String>, String) {
+    let mut rest: Vec<char> = content.chars().collect();
+    let mut chunks = Vec::new();
+    while rest.len() > MTEXT_CHUNK_LENGTH {
+        let end = unfinished_escape(&rest[..MTEXT_CHUNK_LENGTH])
+            .filter(|start| *start > 0)
+            .unwrap_or(MTEXT_CHUNK_LENGTH);
+        chunks.push(rest.drain(..end).collect());
     }
+    let mut text: String = rest.iter().collect();
+    if unfinished_escape(&rest).is_some() {
+        text.push_str(MTEXT_ESCAPE_PADDING);
+    }
+    (chunks, text)
+}
 
-    // 简单的颜色匹配
-    match (color.r, color.g, color.b) {
-        (255, 0, 0) => 1,
-        (255, 255, 0) => 2,
-        (0, 255, 0) => 3,
-        (0, 255, 255) => 4,
-        (0, 0, 255) => 5,
-        (255, 0, 255) => 6,
-        (255, 255, 255) => 7,
-        (128, 128, 128) => 8,
-        _ => 7, // 默认白色
+fn mtext_attachment_from_dxf(attachment: dxf::enums::AttachmentPoint) -> MTextAttachment {
+    use dxf::enums::AttachmentPoint;
+    match attachment {
+        AttachmentPoint::TopLeft => MTextAttachment::TopLeft,
+        AttachmentPoint::TopCenter => MTextAttachment::TopCenter,
+        AttachmentPoint::TopRight => MTextAttachment::TopRight,
+        AttachmentPoint::MiddleLeft => MTextAttachment::MiddleLeft,
+        AttachmentPoint::MiddleCenter => MTextAttachment::MiddleCenter,
+        AttachmentPoint::MiddleRight => MTextAttachment::MiddleRight,
+        AttachmentPoint::BottomLeft => MTextAttachment::BottomLeft,
+        AttachmentPoint::BottomCenter => MTextAttachment::BottomCenter,
+        AttachmentPoint::BottomRight => MTextAttachment::BottomRight,
+    }
+}
+
+fn mtext_attachment_to_dxf(attachment: MTextAttachment) -> dxf::enums::AttachmentPoint {
+    use dxf::enums::AttachmentPoint;
+    match attachment {
+        MTextAttachment::TopLeft => AttachmentPoint::TopLeft,
+        MTextAttachment::TopCenter => AttachmentPoint::TopCenter,
+        MTextAttachment::TopRight => AttachmentPoint::TopRight,
+        MTextAttachment::MiddleLeft => AttachmentPoint::MiddleLeft,
+        MTextAttachment::MiddleCenter => AttachmentPoint::MiddleCenter,
+        MTextAttachment::MiddleRight => AttachmentPoint::MiddleRight,
+        MTextAttachment::BottomLeft => AttachmentPoint::BottomLeft,
+        MTextAttachment::BottomCenter => AttachmentPoint::BottomCenter,
+        MTextAttachment::BottomRight => AttachmentPoint::BottomRight,
+    }
+}
+
+/// DXF 颜色索引（组码 62）和真彩色（组码 420）转 ZCAD 颜色
+fn color_from_dxf(color: &dxf::Color, true_color: Option<u32>) -> Color {
+    if let Some(rgb) = true_color {
+        Color::from_hex(rgb)
+    } else if color.is_by_block() {
+        Color::BY_BLOCK
+    } else {
+        color.index().map(Color::from_aci).unwrap_or(Color::BY_LAYER)
     }
 }
 
+/// ZCAD 颜色转 DXF 颜色索引和真彩色
+///
+/// 调色板颜色只写索引（保留原索引）；其它颜色写最接近的索引，并附带真彩色
+fn color_to_dxf(color: &Color) -> (dxf::Color, Option<u32>) {
+    if color.is_by_block() {
+        (dxf::Color::by_block(), None)
+    } else if color.is_by_layer() {
+        (dxf::Color::by_layer(), None)
+    } else if color.is_aci() {
+        (dxf::Color::from_index(color.to_aci()), None)
+    } else {
+        (dxf::Color::from_index(color.nearest_aci()), Some(color.to_hex()))
+    }
+}
+
+/// 真彩色的扩展数据标记，其后紧跟 0xRRGGBB
+const TRUE_COLOR_XDATA: &str = "TRUE_COLOR";
+
+/// dxf 库写不出的真彩色（图层的真彩色、实体的纯黑）存为 ZCAD 扩展数据
+fn true_color_xdata(rgb: u32) -> dxf::XData {
+    dxf::XData {
+        application_name: ZCAD_APP_ID.to_string(),
+        items: vec![
+            dxf::XDataItem::Str(TRUE_COLOR_XDATA.to_string()),
+            dxf::XDataItem::Long(rgb as i32),
+        ],
+    }
+}
+
+/// 读取 ZCAD 导出时写入扩展数据的真彩色
+fn true_color_from_xdata(x_data: &[dxf::XData]) -> Option<u32> {
+    x_data
+        .iter()
+        .filter(|x| x.application_name == ZCAD_APP_ID)
+        .find_map(|x| {
+            let marker = x
+                .items
+                .iter()
+                .position(|item| matches!(item, dxf::XDataItem::Str(s) if s == TRUE_COLOR_XDATA))?;
+            match x.items.get(marker + 1) {
+                Some(dxf::XDataItem::Long(rgb)) => Some(*rgb as u32 & 0xFF_FFFF),
+                _ => None,
+            }
+        })
+}
+
+/// 导出收尾：有实体写出真彩色（组码 420）时升级到 R2004，否则保持 R2000；
+/// 用到真彩色扩展数据时注册 ZCAD 应用名
+fn finish_true_colors(drawing: &mut dxf::Drawing) {
+    let entities = || drawing.entities().chain(drawing.blocks().flat_map(|b| b.entities.iter()));
+    let has_true_color = entities().any(|e| e.common.color_24_bit != 0);
+    let has_xdata = entities()
+        .map(|e| &e.common.x_data)
+        .chain(drawing.layers().map(|l| &l.x_data))
+        .any(|x_data| true_color_from_xdata(x_data).is_some());
+
+    if has_true_color {
+        drawing.header.version = dxf::enums::AcadVersion::R2004;
+    }
+    if has_xdata && !drawing.app_ids().any(|app| app.name == ZCAD_APP_ID) {
+        drawing.add_app_id(dxf::tables::AppId {
+            name: ZCAD_APP_ID.to_string(),
+            ..Default::default()
+        });
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zcad_core::block::BlockTable;
+
+    /// 块 "Valve"：一条线段和一个属性定义；模型空间中一个 2×3 的阵列插入
+    fn document_with_blocks() -> Document {
+        let mut document = Document::new();
+        let mut block = Block::new("Valve", Point2::new(1.0, 2.0)).with_description("supplier symbol");
+        block.add_entity(Entity::new(Geometry::Line(Line::new(Point2::new(1.0, 2.0), Point2::new(11.0, 2.0)))));
+        let mut definition = AttributeDefinition::new("NO", Text::new(Point2::new(1.0, 4.0), "?", 2.5));
+        definition.prompt = "编号".to_string();
+        block.add_attribute_definition(definition);
+        document.blocks.add_block(block);
+
+        let reference = BlockReference::new("Valve", Point2::new(100.0, 50.0))
+            .with_scale(2.0, 3.0)
+            .with_rotation_degrees(30.0)
+            .with_array(2, 3, 40.0, 25.0);
+        let mut reference = reference.clone().with_attributes(
+            document.blocks.get_block("Valve").unwrap().default_attributes(&reference),
+        );
+        reference.set_attribute_value("NO", "V-101");
+        document.add_entity(Entity::new(Geometry::Insert(reference)));
+        document
+    }
+
+    fn check_roundtrip(blocks: &BlockTable, entities: Vec<&Entity>) {
+        let block = blocks.get_block("Valve").expect("block imported");
+        assert!((block.base_point - Point2::new(1.0, 2.0)).norm() < 1e-9);
+        assert_eq!(block.entity_count(), 1);
+        assert_eq!(block.attribute_definitions.len(), 1);
+        assert_eq!(block.attribute_definitions[0].tag, "NO");
+        assert_eq!(block.attribute_definitions[0].text.content, "?");
+
+        let references: Vec<&BlockReference> = entities
+            .iter()
+            .filter_map(|e| match &e.geometry {
+                Geometry::Insert(r) => Some(r),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(references.len(), 1);
+        let reference = references[0];
+        assert_eq!(reference.block_name, "Valve");
+        assert!((reference.insertion_point - Point2::new(100.0, 50.0)).norm() < 1e-9);
+        assert!((reference.scale_x - 2.0).abs() < 1e-9);
+        assert!((reference.scale_y - 3.0).abs() < 1e-9);
+        assert!((reference.rotation - 30f64.to_radians()).abs() < 1e-9);
+        assert_eq!((reference.column_count, reference.row_count), (2, 3));
+        assert!((reference.column_spacing - 40.0).abs() < 1e-9);
+        assert!((reference.row_spacing - 25.0).abs() < 1e-9);
+        assert_eq!(reference.attribute("NO").map(|a| a.value()), Some("V-101"));
+    }
+
+    #[test]
+    fn test_blocks_and_inserts_roundtrip() {
+        let file_path = std::env::temp_dir().join("test_blocks_roundtrip.dxf");
+        export(&document_with_blocks(), &file_path).unwrap();
+
+        let loaded = import(&file_path).unwrap();
+        check_roundtrip(&loaded.blocks, loaded.all_entities().collect());
+        // 展开后 6 个阵列实例各有一条线段，加上一个属性文字
+        let resolved = loaded.resolved_entities();
+        assert_eq!(resolved.len(), 7);
+
+        std::fs::remove_file(&file_path).ok();
+    }
+
+    #[test]
+    fn test_blocks_written_by_raw_writer() {
+        let file_path = std::env::temp_dir().join("test_blocks_raw_writer.dxf");
+        export_full(&document_with_blocks(), &file_path).unwrap();
+
+        let drawing = dxf::Drawing::load_file(&file_path).unwrap();
+        let mut document = Document::new();
+        import_blocks(&drawing, &mut document);
+        let entities: Vec<Entity> = drawing.entities().filter_map(convert_dxf_entity).collect();
+        check_roundtrip(&document.blocks, entities.iter().collect());
+
+        std::fs::remove_file(&file_path).ok();
+    }
+
+    fn pattern_hatch_document() -> Document {
+        let corners = [
+            Point2::new(0.0, 0.0),
+            Point2::new(50.0, 0.0),
+            Point2::new(50.0, 50.0),
+            Point2::new(0.0, 50.0),
+        ];
+        let boundary = zcad_core::geometry::HatchBoundary::new(
+            (0..4)
+                .map(|i| HatchBoundaryElement::Line(Line::new(corners[i], corners[(i + 1) % 4])))
+                .collect(),
+            true,
+        );
+        let mut document = Document::new();
+        document.add_entity(Entity::new(Geometry::Hatch(Hatch::pattern(vec![boundary], "ANSI31", 0.0, 2.0))));
+        document
+    }
+
+    #[test]
+    fn test_pattern_hatch_exported_as_anonymous_block() {
+        let file_path = std::env::temp_dir().join("test_hatch_block.dxf");
+        export(&pattern_hatch_document(), &file_path).unwrap();
+
+        let loaded = import(&file_path).unwrap();
+        let block = loaded.blocks.iter().find(|b| b.is_anonymous).expect("hatch block");
+        assert!(block.name.starts_with("*X"));
+        assert!(block.entity_count() > 5);
+        let resolved = loaded.resolved_entities();
+        assert_eq!(resolved.len(), block.entity_count());
+        assert!(resolved.iter().all(|e| matches!(e.geometry, Geometry::Line(_))));
+
+        std::fs::remove_file(&file_path).ok();
+    }
+
+    #[test]
+    fn test_hatch_written_by_raw_writer() {
+        let file_path = std::env::temp_dir().join("test_hatch_raw_writer.dxf");
+        export_full(&pattern_hatch_document(), &file_path).unwrap();
+
+        let content = std::fs::read_to_string(&file_path).unwrap();
+        let pairs: Vec<(&str, &str)> = content
+            .lines()
+            .map(str::trim)
+            .collect::<Vec<_>>()
+            .chunks_exact(2)
+            .map(|pair| (pair[0], pair[1]))
+            .collect();
+        let start = pairs.iter().position(|p| *p == ("0", "HATCH")).expect("HATCH entity");
+        let hatch: Vec<(&str, &str)> = pairs[start + 1..].iter().take_while(|p| p.0 != "0").copied().collect();
+        let value = |code: &str| hatch.iter().find(|p| p.0 == code).map(|p| p.1.parse::<f64>().unwrap());
+
+        assert!(hatch.contains(&("2", "ANSI31")));
+        assert_eq!(value("91"), Some(1.0));
+        assert_eq!(value("93"), Some(4.0));
+        assert_eq!(value("78"), Some(1.0));
+        assert_eq!(value("53"), Some(45.0));
+        // 图案线偏移为世界坐标：垂直于 45° 方向，间距 3.175 × 2
+        let (dx, dy) = (value("45").unwrap(), value("46").unwrap());
+        assert!((dx.hypot(dy) - 6.35).abs() < 1e-9);
+        assert!((dx + dy).abs() < 1e-9);
+
+        std::fs::remove_file(&file_path).ok();
+    }
+
+    #[test]
+    fn test_dim_styles_roundtrip() {
+        let file_path = std::env::temp_dir().join("test_dim_styles.dxf");
+        let mut document = Document::new();
+        let style = DimStyle {
+            arrow_type2: dimstyle::ArrowType::Dot,
+            suppress_extension_line1: true,
+            text_vertical: DimTextVertical::Centered,
+            show_alternate_units: true,
+            prefix: "L=".to_string(),
+            ..DimStyle::mechanical()
+        };
+        let dim = Dimension::new(Point2::new(0.0, 0.0), Point2::new(40.0, 0.0), Point2::new(20.0, 12.0))
+            .with_style(&style);
+        document.dim_styles.set_style(style);
+        document.add_entity(Entity::new(Geometry::Dimension(dim)));
+        export(&document, &file_path).unwrap();
+
+        // 标注图形写入匿名块，块中包含公差、替代单位文字和 SOLID 箭头
+        let drawing = dxf::Drawing::load_file(&file_path).unwrap();
+        let block = drawing.blocks().find(|b| is_dimension_block(&b.name)).expect("dimension block");
+        assert!(block.entities.iter().any(|e| matches!(&e.specific, dxf::entities::EntityType::Solid(_))));
+        assert!(block.entities.iter().any(
+            |e| matches!(&e.specific, dxf::entities::EntityType::Text(t) if t.value == "L=40.000±0.10 [1.57\"]")
+        ));
+
+        let loaded = import(&file_path).unwrap();
+        assert!(loaded.blocks.iter().all(|b| !is_dimension_block(&b.name)));
+        let imported = loaded.dim_styles.get_style("Mechanical").unwrap();
+        assert_eq!(imported.arrow_type1, dimstyle::ArrowType::ClosedFilled);
+        assert_eq!(imported.arrow_type2, dimstyle::ArrowType::Dot);
+        assert!(imported.suppress_extension_line1);
+        assert_eq!(imported.text_vertical, DimTextVertical::Centered);
+        assert_eq!(imported.prefix, "L=");
+        assert_eq!(imported.linear_precision, 3);
+        assert_eq!(imported.baseline_spacing, 7.0);
+        assert!((imported.tolerance_lower + 0.1).abs() < 1e-9);
+        assert_eq!(imported.alternate_unit, Unit::Inch);
+        assert!(loaded.dim_styles.get_style("Standard").is_some());
+
+        let Geometry::Dimension(loaded_dim) = &loaded.all_entities().next().unwrap().geometry else {
+            panic!("expected dimension");
+        };
+        assert_eq!(loaded_dim.style_name, "Mechanical");
+        assert_eq!(loaded_dim.text_height, 3.5);
+
+        std::fs::remove_file(&file_path).ok();
+    }
+
+    #[test]
+    fn test_angular_arc_length_and_ordinate_dimensions_roundtrip() {
+        let file_path = std::env::temp_dir().join("test_angular_dimensions.dxf");
+        let mut document = Document::new();
+        let center = Point2::new(5.0, 5.0);
+        document.add_entity(Entity::new(Geometry::Dimension(Dimension::angular(
+            center,
+            Point2::new(15.0, 5.0),
+            Point2::new(5.0, 15.0),
+            Point2::new(-10.0, 0.0),
+        ))));
+        document.add_entity(Entity::new(Geometry::Dimension(Dimension::arc_length(
+            center,
+            Point2::new(15.0, 5.0),
+            Point2::new(5.0, 15.0),
+            Point2::new(20.0, 20.0),
+        ))));
+        let mut ordinate = Dimension::ordinate(
+            Point2::new(30.0, 12.0),
+            Point2::new(10.0, 2.0),
+            Point2::new(45.0, 14.0),
+            OrdinateAxis::Y,
+        );
+        ordinate.text_position = Some(Point2::new(52.0, 14.0));
+        document.add_entity(Entity::new(Geometry::Dimension(ordinate)));
+        export(&document, &file_path).unwrap();
+
+        let loaded = import(&file_path).unwrap();
+        let dims: Vec<&Dimension> = loaded
+            .all_entities()
+            .filter_map(|e| match &e.geometry {
+                Geometry::Dimension(dim) => Some(dim),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(dims.len(), 3);
+        let find = |dim_type: DimensionType| *dims.iter().find(|d| d.dim_type == dim_type).unwrap();
+
+        // 尺寸弧位于另一侧，测量 270°
+        let angular = find(DimensionType::Angular);
+        assert_eq!(angular.definition_point1, center);
+        assert_eq!(angular.definition_point3, Point2::new(5.0, 15.0));
+        assert!((angular.measurement() - 270.0).abs() < 1e-9);
+        assert!(angular.text_position.is_none());
+
+        let arc_length = find(DimensionType::ArcLength);
+        assert!((arc_length.measurement() - 5.0 * std::f64::consts::PI).abs() < 1e-9);
+
+        let ordinate = find(DimensionType::Ordinate);
+        assert_eq!(ordinate.ordinate_axis, OrdinateAxis::Y);
+        assert_eq!(ordinate.definition_point2, Point2::new(10.0, 2.0));
+        assert_eq!(ordinate.line_location, Point2::new(45.0, 14.0));
+        assert_eq!(ordinate.text_position, Some(Point2::new(52.0, 14.0)));
+        assert!((ordinate.measurement() - 10.0).abs() < 1e-9);
+
+        std::fs::remove_file(&file_path).ok();
+    }
+
+    #[test]
+    fn test_tolerance_roundtrip() {
+        let file_path = std::env::temp_dir().join("test_tolerance.dxf");
+        let mut document = Document::new();
+        let tolerance = Tolerance::new(Point2::new(10.0, 20.0), 2.5)
+            .with_row(
+                ToleranceRow::new(GdtSymbol::Position)
+                    .with_tolerance(
+                        ToleranceValue::new("0.05")
+                            .with_diameter()
+                            .with_material(MaterialCondition::Maximum),
+                    )
+                    .with_datum(DatumReference::new("A"))
+                    .with_datum(DatumReference::new("B").with_material(MaterialCondition::Least))
+                    .with_datum(DatumReference::new("C")),
+            )
+            .with_row(
+                ToleranceRow::new(GdtSymbol::Flatness)
+                    .with_tolerance(ToleranceValue::new("0.01")),
+            )
+            .with_projected_zone("12")
+            .with_datum_identifier("D")
+            .with_rotation(std::f64::consts::FRAC_PI_2)
+            .with_leader(Leader::new(vec![
+                Point2::new(0.0, 0.0),
+                Point2::new(5.0, 5.0),
+            ]));
+        document.add_entity(Entity::new(Geometry::Tolerance(tolerance.clone())));
+        export(&document, &file_path).unwrap();
+
+        let loaded = import(&file_path).unwrap();
+        assert!(!loaded
+            .all_entities()
+            .any(|e| matches!(e.geometry, Geometry::Leader(_))));
+        let loaded_tolerance = loaded
+            .all_entities()
+            .find_map(|e| match &e.geometry {
+                Geometry::Tolerance(t) => Some(t.clone()),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(loaded_tolerance.rows, tolerance.rows);
+        assert_eq!(loaded_tolerance.projected_zone.as_deref(), Some("12"));
+        assert_eq!(loaded_tolerance.datum_identifier.as_deref(), Some("D"));
+        assert!((loaded_tolerance.rotation - tolerance.rotation).abs() < 1e-9);
+        assert!((loaded_tolerance.position - tolerance.position).norm() < 1e-9);
+        let leader = loaded_tolerance.leader.unwrap();
+        assert_eq!(leader.vertices.len(), 2);
+        assert_eq!(*leader.vertices.last().unwrap(), tolerance.position);
+
+        std::fs::remove_file(&file_path).ok();
+    }
+
+    #[test]
+    fn test_tolerance_text_codec() {
+        let row = ToleranceRow::new(GdtSymbol::Perpendicularity)
+            .with_tolerance(ToleranceValue::new("0.1"))
+            .with_datum(DatumReference::new("A"));
+        let tolerance = Tolerance::new(Point2::origin(), 2.5).with_row(row);
+        let text = tolerance_to_dxf_text(&tolerance);
+        assert_eq!(text, "{\\Fgdt;b}%%v0.1%%v%%vA");
+
+        // %%c 作为直径符号读入
+        let parsed = tolerance_from_dxf_text("{\\Fgdt;j}%%v%%c0.2{\\Fgdt;m}%%vA", Point2::origin());
+        let value = parsed.rows[0].tolerance1.as_ref().unwrap();
+        assert_eq!(parsed.rows[0].symbol, Some(GdtSymbol::Position));
+        assert!(value.diameter);
+        assert_eq!(value.value, "0.2");
+        assert_eq!(value.material, Some(MaterialCondition::Maximum));
+    }
+
+    #[test]
+    fn test_mtext_roundtrip() {
+        let file_path = std::env::temp_dir().join("test_mtext.dxf");
+        let mut document = Document::new();
+        // 超过 250 个字符，导出时拆分到组码 3
+        let content = format!("{{\\fArial|b1|i0;标题}}\\P{}\\S1/2;", "word ".repeat(60));
+        let mtext = MText::new(Point2::new(5.0, 8.0), content.clone(), 2.5)
+            .with_width(40.0)
+            .with_rotation(std::f64::consts::FRAC_PI_6)
+            .with_attachment(MTextAttachment::MiddleCenter)
+            .with_line_spacing(1.5);
+        document.add_entity(Entity::new(Geometry::MText(mtext)));
+        export(&document, &file_path).unwrap();
+
+        let loaded = import(&file_path).unwrap();
+        let loaded_mtext = loaded
+            .all_entities()
+            .find_map(|e| match &e.geometry {
+                Geometry::MText(m) => Some(m.clone()),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(loaded_mtext.content, content);
+        assert_eq!(loaded_mtext.position, Point2::new(5.0, 8.0));
+        assert_eq!(loaded_mtext.width, 40.0);
+        assert_eq!(loaded_mtext.attachment, MTextAttachment::MiddleCenter);
+        assert_eq!(loaded_mtext.line_spacing, 1.5);
+        assert!((loaded_mtext.rotation - std::f64::consts::FRAC_PI_6).abs() < 1e-9);
+        assert!(loaded_mtext.paragraphs()[0][0].format.bold);
+
+        std::fs::remove_file(&file_path).ok();
+    }
+
+    #[test]
+    fn test_text_styles_roundtrip() {
+        let file_path = std::env::temp_dir().join("test_text_styles.dxf");
+        let mut document = Document::new();
+        let mut style = TextStyle::new("Title").with_font("romans.shx").with_height(5.0).with_width_factor(0.8);
+        style.oblique_angle = (-15f64).to_radians();
+        style.vertical = true;
+        style.upside_down = true;
+        document.text_styles.set_style(style);
+        document.text_styles.set_current_style("Title");
+        let mut dim_style = DimStyle::new("Site");
+        dim_style.text_style = "Title".to_string();
+        document.dim_styles.set_style(dim_style);
+
+        let mut text = Text::new(Point2::new(1.0, 2.0), "ZCAD", 5.0);
+        document.text_styles.current_style().apply_to_text(&mut text);
+        text.backwards = true;
+        document.add_entity(Entity::new(Geometry::Text(text)));
+        export(&document, &file_path).unwrap();
+
+        let loaded = import(&file_path).unwrap();
+        let style = loaded.text_styles.get_style("Title").unwrap();
+        assert_eq!(loaded.text_styles.current_style().name, "Title");
+        assert_eq!(style.font_file, "romans.shx");
+        assert_eq!(style.height, 5.0);
+        assert_eq!(style.width_factor, 0.8);
+        assert!((style.oblique_angle + 15f64.to_radians()).abs() < 1e-9);
+        assert!(style.vertical && style.upside_down && !style.backwards);
+        assert!(loaded.text_styles.get_style("Standard").is_some());
+        assert_eq!(loaded.dim_styles.get_style("Site").unwrap().text_style, "Title");
+
+        let text = loaded
+            .all_entities()
+            .find_map(|e| match &e.geometry {
+                Geometry::Text(t) => Some(t.clone()),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(text.style_name, "Title");
+        assert_eq!(text.font.as_deref(), Some("romans"));
+        assert_eq!(text.width_factor, 0.8);
+        assert!((text.oblique_angle + 15f64.to_radians()).abs() < 1e-9);
+        assert!(text.backwards && text.upside_down);
+
+        std::fs::remove_file(&file_path).ok();
+    }
+
+    #[test]
+    fn test_text_justification_roundtrip() {
+        let file_path = std::env::temp_dir().join("test_text_justification.dxf");
+        let mut document = Document::new();
+        let alignments = [
+            TextAlignment::Left,
+            TextAlignment::MiddleCenter,
+            TextAlignment::TopRight,
+            TextAlignment::BottomLeft,
+            TextAlignment::Middle,
+            TextAlignment::Aligned,
+            TextAlignment::Fit,
+        ];
+        for (i, alignment) in alignments.into_iter().enumerate() {
+            let position = Point2::new(0.0, i as f64 * 10.0);
+            let mut text = Text::new(position, format!("T{i}"), 2.5).with_alignment(alignment);
+            if alignment.is_two_point() {
+                text.end_point = Some(position + Vector2::new(30.0, 5.0));
+            }
+            document.add_entity(Entity::new(Geometry::Text(text)));
+        }
+        export(&document, &file_path).unwrap();
+
+        // 非左对齐文字的组码 10 为基线起点，11 为对齐点
+        let drawing = dxf::Drawing::load_file(&file_path).unwrap();
+        let centered = drawing
+            .entities()
+            .find_map(|e| match &e.specific {
+                dxf::entities::EntityType::Text(t) if t.value == "T1" => Some(t.clone()),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(centered.vertical_text_justification, dxf::enums::VerticalTextJustification::Middle);
+        assert!((centered.second_alignment_point.y - 10.0).abs() < 1e-9);
+        assert!(centered.location.x < 0.0);
+
+        let loaded = import(&file_path).unwrap();
+        for (i, alignment) in alignments.into_iter().enumerate() {
+            let text = loaded
+                .all_entities()
+                .find_map(|e| match &e.geometry {
+                    Geometry::Text(t) if t.content == format!("T{i}") => Some(t.clone()),
+                    _ => None,
+                })
+                .unwrap();
+            assert_eq!(text.alignment, alignment);
+            assert!((text.position - Point2::new(0.0, i as f64 * 10.0)).norm() < 1e-9);
+            if alignment.is_two_point() {
+                assert!((text.end_point.unwrap() - text.position - Vector2::new(30.0, 5.0)).norm() < 1e-9);
+            }
+        }
+
+        std::fs::remove_file(&file_path).ok();
+    }
+
+    #[test]
+    fn test_spline_roundtrip() {
+        let file_path = std::env::temp_dir().join("test_spline_roundtrip.dxf");
+        let mut document = Document::new();
+        let fit_points = vec![Point2::new(0.0, 0.0), Point2::new(10.0, 10.0), Point2::new(20.0, 0.0), Point2::new(30.0, 10.0)];
+        let fit = Spline::from_fit_points(fit_points.clone(), 3, false)
+            .with_end_tangents(Some(Vector2::new(0.0, 1.0)), None);
+        document.add_entity(Entity::new(Geometry::Spline(fit.clone())));
+        let mut nurbs = Spline::from_control_points(fit_points.clone(), 3, false);
+        nurbs.set_weight(1, 2.5);
+        document.add_entity(Entity::new(Geometry::Spline(nurbs)));
+        export(&document, &file_path).unwrap();
+
+        let loaded = import(&file_path).unwrap();
+        let splines: Vec<Spline> = loaded
+            .all_entities()
+            .filter_map(|e| match &e.geometry {
+                Geometry::Spline(s) => Some(s.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(splines.len(), 2);
+        let loaded_fit = splines.iter().find(|s| s.is_fit_spline()).unwrap();
+        assert_eq!(loaded_fit.fit_points, fit_points);
+        assert!((loaded_fit.start_tangent.unwrap() - Vector2::new(0.0, 1.0)).norm() < 1e-9);
+        assert!(loaded_fit.end_tangent.is_none());
+        for (a, b) in loaded_fit.control_points.iter().zip(&fit.control_points) {
+            assert!((a - b).norm() < 1e-6);
+        }
+        let loaded_nurbs = splines.iter().find(|s| !s.is_fit_spline()).unwrap();
+        assert_eq!(loaded_nurbs.spline_type, SplineType::Nurbs);
+        assert!((loaded_nurbs.weight(1) - 2.5).abs() < 1e-9);
+
+        // 只有拟合点的样条导入时由拟合点插值得到控制点
+        let mut drawing = dxf::Drawing::new();
+        drawing.header.version = dxf::enums::AcadVersion::R2000;
+        let dxf_spline = dxf::entities::Spline {
+            degree_of_curve: 3,
+            fit_points: fit_points.iter().map(|p| dxf::Point::new(p.x, p.y, 0.0)).collect(),
+            ..Default::default()
+        };
+        drawing.add_entity(dxf::entities::Entity::new(dxf::entities::EntityType::Spline(dxf_spline)));
+        drawing.save_file(&file_path).unwrap();
+        let loaded = import(&file_path).unwrap();
+        let spline = loaded
+            .all_entities()
+            .find_map(|e| match &e.geometry {
+                Geometry::Spline(s) => Some(s.clone()),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(spline.control_points.len(), fit_points.len());
+        for p in &fit_points {
+            assert!(spline.distance_to_point(p) < 0.05);
+        }
+
+        std::fs::remove_file(&file_path).ok();
+    }
+
+    #[test]
+    fn test_colors_roundtrip() {
+        let file_path = std::env::temp_dir().join("test_colors.dxf");
+        let mut document = Document::new();
+        document.layers.add_layer(zcad_core::layer::Layer::new("Pipes").with_color(Color::from_aci(30)));
+        document.layers.add_layer(zcad_core::layer::Layer::new("Walls").with_color(Color::from_hex(0x336699)));
+        document.layers.add_layer(zcad_core::layer::Layer::new("Ink").with_color(Color::BLACK));
+        let colors = [
+            Color::BY_LAYER,
+            Color::BY_BLOCK,
+            Color::from_aci(142),
+            Color::from_aci(255),
+            Color::from_hex(0x123456),
+            Color::BLACK,
+        ];
+        for (i, color) in colors.iter().enumerate() {
+            let line = Line::new(Point2::new(0.0, i as f64), Point2::new(10.0, i as f64));
+            document.add_entity(Entity::new(Geometry::Line(line)).with_properties(Properties::with_color(*color)));
+        }
+        export(&document, &file_path).unwrap();
+
+        let loaded = import(&file_path).unwrap();
+        let pipes = loaded.layers.get_layer("Pipes").unwrap().color;
+        assert_eq!((pipes, pipes.aci), (Color::from_aci(30), Some(30)));
+        let walls = loaded.layers.get_layer("Walls").unwrap().color;
+        assert_eq!((walls, walls.aci), (Color::from_hex(0x336699), None));
+        assert_eq!(loaded.layers.get_layer("Ink").unwrap().color, Color::BLACK);
+
+        let mut loaded_colors: Vec<(f64, Color)> = loaded
+            .all_entities()
+            .filter_map(|e| match &e.geometry {
+                Geometry::Line(line) => Some((line.start.y, e.properties.color)),
+                _ => None,
+            })
+            .collect();
+        loaded_colors.sort_by(|a, b| a.0.total_cmp(&b.0));
+        assert_eq!(loaded_colors.len(), colors.len());
+        for ((_, loaded), original) in loaded_colors.iter().zip(&colors) {
+            assert_eq!(loaded, original);
+            assert_eq!(loaded.aci, original.aci);
+            assert_eq!(loaded.is_by_block(), original.is_by_block());
+        }
+
+        // 真彩色附带最接近的索引，供不支持真彩色的程序使用
+        let drawing = dxf::Drawing::load_file(&file_path).unwrap();
+        assert_eq!(drawing.header.version, dxf::enums::AcadVersion::R2004);
+        let line = drawing.entities().find(|e| e.common.color_24_bit != 0).unwrap();
+        assert_eq!(line.common.color_24_bit, 0x123456);
+        assert_eq!(line.common.color.index(), Some(Color::from_hex(0x123456).nearest_aci()));
+
+        // 其它程序写在图层表中的真彩色由原始解析器读取
+        export_full(&document, &file_path).unwrap();
+        let loaded = import(&file_path).unwrap();
+        assert_eq!(loaded.layers.get_layer("Walls").unwrap().color, Color::from_hex(0x336699));
+        assert_eq!(loaded.layers.get_layer("Pipes").unwrap().color.aci, Some(30));
+        assert_eq!(loaded.layers.get_layer("Ink").unwrap().color, Color::BLACK);
+
+        // 只用到调色板颜色时仍输出 R2000
+        let mut document = Document::new();
+        for color in [Color::BLACK, Color::from_aci(30)] {
+            let line = Line::new(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0));
+            document.add_entity(Entity::new(Geometry::Line(line)).with_properties(Properties::with_color(color)));
+        }
+        export(&document, &file_path).unwrap();
+        let drawing = dxf::Drawing::load_file(&file_path).unwrap();
+        assert_eq!(drawing.header.version, dxf::enums::AcadVersion::R2000);
+        let loaded = import(&file_path).unwrap();
+        let mut colors: Vec<Color> = loaded.all_entities().map(|e| e.properties.color).collect();
+        colors.sort_by_key(|c| c.to_hex());
+        assert_eq!(colors, vec![Color::BLACK, Color::from_aci(30)]);
+        assert!(colors[0].aci.is_none() && !colors[0].is_by_block());
+
+        std::fs::remove_file(&file_path).ok();
+    }
+}
@@ -388,6 +388,60 @@ pub fn parse_viewports(parser: &mut DxfRawParser) -> Vec<DxfViewport> {
     viewports
 }
 
+/// 解析 TABLES 段中竖排（标志位 4）的文字样式名称
+pub fn parse_vertical_text_styles(parser: &mut DxfRawParser) -> Vec<String> {
+    let mut names = Vec::new();
+
+    parser.position = 0;
+    if !parser.skip_to(2, Some("TABLES")) {
+        return names;
+    }
+
+    while let Some(pair) = parser.advance() {
+        if pair.code == 0 && pair.value.trim() == "ENDSEC" {
+            break;
+        }
+
+        if pair.code == 0 && pair.value.trim() == "STYLE" {
+            let pairs = parser.read_until_zero();
+            let name = pairs.iter().find(|p| p.code == 2).map(|p| p.value.trim().to_string());
+            let flags = pairs.iter().find(|p| p.code == 70).and_then(|p| p.as_i32()).unwrap_or(0);
+            if let Some(name) = name.filter(|_| flags & 4 != 0) {
+                names.push(name);
+            }
+        }
+    }
+
+    names
+}
+
+/// 从 LAYER 表读取带真彩色（组码 420）的图层，返回图层名和 0xRRGGBB
+pub fn parse_layer_true_colors(parser: &mut DxfRawParser) -> Vec<(String, u32)> {
+    let mut colors = Vec::new();
+
+    parser.position = 0;
+    if !parser.skip_to(2, Some("TABLES")) {
+        return colors;
+    }
+
+    while let Some(pair) = parser.advance() {
+        if pair.code == 0 && pair.value.trim() == "ENDSEC" {
+            break;
+        }
+
+        if pair.code == 0 && pair.value.trim() == "LAYER" {
+            let pairs = parser.read_until_zero();
+            let name = pairs.iter().find(|p| p.code == 2).map(|p| p.value.trim().to_string());
+            let rgb = pairs.iter().find(|p| p.code == 420).and_then(|p| p.as_i32());
+            if let (Some(name), Some(rgb)) = (name, rgb) {
+                colors.push((name, rgb as u32 & 0xFF_FFFF));
+            }
+        }
+    }
+
+    colors
+}
+
 /// DXF 写入器
 pub struct DxfWriter {
     output: Vec<String>,
@@ -2,11 +2,24 @@
 //!
 //! 支持将 CAD 图纸导出为多种格式：PDF、SVG、PNG、JPG
 
+use crate::document::Document;
 use crate::error::FileError;
+use crate::pdf::{self, ContentStream, ObjectRef, PdfWriter, MM_TO_PT};
+use crate::raster::RasterExporter;
+use zcad_core::curve::PolylineSegment;
 use zcad_core::entity::Entity;
-use zcad_core::geometry::Geometry;
-use zcad_core::math::{Point2, Vector2};
-use zcad_core::properties::Color;
+use zcad_core::geometry::{
+    Arc, ArrowType, Ellipse, Geometry, Hatch, HatchBoundaryElement, HatchPatternType, Leader, Spline,
+    Text,
+};
+use zcad_core::dimstyle::DimStyle;
+use zcad_core::font::{FontManager, Glyph};
+use zcad_core::hatch_pattern;
+use zcad_core::layer::{Layer, LayerManager};
+use zcad_core::layout::{Layout, Viewport, ViewportStatus};
+use zcad_core::math::{BoundingBox2, Point2, Vector2, EPSILON};
+use zcad_core::properties::{Color, LineType, LineWeight, Properties};
+use zcad_core::tessellation;
 
 /// 纸张大小
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -77,13 +90,18 @@ impl Default for PageSetup {
 }
 
 impl PageSetup {
-    /// 获取可打印区域尺寸（毫米）
-    pub fn printable_size(&self) -> (f64, f64) {
+    /// 获取考虑方向后的纸张尺寸（毫米）
+    pub fn paper_dimensions(&self) -> (f64, f64) {
         let (paper_w, paper_h) = self.paper_size.dimensions_mm();
-        let (w, h) = match self.orientation {
+        match self.orientation {
             Orientation::Portrait => (paper_w, paper_h),
             Orientation::Landscape => (paper_h, paper_w),
-        };
+        }
+    }
+
+    /// 获取可打印区域尺寸（毫米）
+    pub fn printable_size(&self) -> (f64, f64) {
+        let (w, h) = self.paper_dimensions();
         let (top, right, bottom, left) = self.margins;
         (w - left - right, h - top - bottom)
     }
@@ -119,6 +137,9 @@ impl PrintArea {
     }
 }
 
+/// SVG、PDF 等矢量输出中细分曲线所按的分辨率（点/英寸），与常见绘图仪的精度相当
+const VECTOR_DPI: f64 = 1200.0;
+
 /// SVG 导出器
 pub struct SvgExporter {
     page_setup: PageSetup,
@@ -143,13 +164,13 @@ impl SvgExporter {
     /// 导出实体为 SVG 字符串
     pub fn export(&self, entities: &[Entity]) -> Result<String, FileError> {
         // 计算所有实体的包围盒
-        let bounds = self.calculate_bounds(entities);
+        let bounds = calculate_bounds(&self.page_setup, entities);
         
         // 获取页面尺寸
         let (page_width, page_height) = self.page_setup.printable_size();
         
         // 计算缩放和偏移
-        let (scale, offset) = self.calculate_transform(&bounds, page_width, page_height);
+        let (scale, offset) = calculate_transform(&self.page_setup, &bounds, page_width, page_height);
         
         // 生成 SVG
         let mut svg = String::new();
@@ -176,11 +197,12 @@ impl SvgExporter {
         ));
 
         // 渲染每个实体
+        let tolerance = tessellation::plot_tolerance(VECTOR_DPI, scale);
         for entity in entities {
             let color = &entity.properties.color;
             let stroke_width = self.line_weight_to_mm(&entity.properties.line_weight).max(0.1);
             
-            if let Some(svg_elem) = self.geometry_to_svg(&entity.geometry, color, stroke_width) {
+            if let Some(svg_elem) = self.geometry_to_svg(&entity.geometry, color, stroke_width, tolerance) {
                 svg.push_str(&format!("    {}\n", svg_elem));
             }
         }
@@ -191,57 +213,8 @@ impl SvgExporter {
         Ok(svg)
     }
 
-    /// 计算所有实体的包围盒
-    fn calculate_bounds(&self, entities: &[Entity]) -> PrintArea {
-        if entities.is_empty() {
-            return PrintArea::new(Point2::origin(), Point2::new(100.0, 100.0));
-        }
-
-        let mut min_x = f64::MAX;
-        let mut min_y = f64::MAX;
-        let mut max_x = f64::MIN;
-        let mut max_y = f64::MIN;
-
-        for entity in entities {
-            let bbox = entity.geometry.bounding_box();
-            min_x = min_x.min(bbox.min.x);
-            min_y = min_y.min(bbox.min.y);
-            max_x = max_x.max(bbox.max.x);
-            max_y = max_y.max(bbox.max.y);
-        }
-
-        // 如果有指定打印区域，使用它
-        if let Some(ref area) = self.page_setup.print_area {
-            return area.clone();
-        }
-
-        PrintArea::new(Point2::new(min_x, min_y), Point2::new(max_x, max_y))
-    }
-
-    /// 计算变换参数
-    fn calculate_transform(&self, bounds: &PrintArea, page_width: f64, page_height: f64) -> (f64, Vector2) {
-        let content_width = bounds.width();
-        let content_height = bounds.height();
-        
-        let scale = if self.page_setup.fit_to_page {
-            let scale_x = page_width / content_width;
-            let scale_y = page_height / content_height;
-            scale_x.min(scale_y) * 0.95 // 留一点边距
-        } else {
-            self.page_setup.scale
-        };
-        
-        // 居中偏移
-        let scaled_width = content_width * scale;
-        let scaled_height = content_height * scale;
-        let offset_x = (page_width - scaled_width) / 2.0 - bounds.min.x * scale;
-        let offset_y = (page_height - scaled_height) / 2.0 - bounds.min.y * scale;
-        
-        (scale, Vector2::new(offset_x, offset_y))
-    }
-
-    /// 将几何体转换为 SVG 元素
-    fn geometry_to_svg(&self, geometry: &Geometry, color: &Color, stroke_width: f64) -> Option<String> {
+    /// 将几何体转换为 SVG 元素，`tolerance` 为曲线细分的弦高容差（绘图单位）
+    fn geometry_to_svg(&self, geometry: &Geometry, color: &Color, stroke_width: f64, tolerance: f64) -> Option<String> {
         let stroke_color = format!("rgb({},{},{})", color.r, color.g, color.b);
         let style = format!(
             r#"stroke="{}" stroke-width="{:.2}" fill="none""#,
@@ -257,25 +230,6 @@ impl SvgExporter {
                 r#"<circle cx="{:.4}" cy="{:.4}" r="{:.4}" {}/>"#,
                 circle.center.x, circle.center.y, circle.radius, style
             )),
-            Geometry::Arc(arc) => {
-                let start_x = arc.center.x + arc.radius * arc.start_angle.cos();
-                let start_y = arc.center.y + arc.radius * arc.start_angle.sin();
-                let end_x = arc.center.x + arc.radius * arc.end_angle.cos();
-                let end_y = arc.center.y + arc.radius * arc.end_angle.sin();
-                
-                let sweep_angle = arc.end_angle - arc.start_angle;
-                let large_arc = if sweep_angle.abs() > std::f64::consts::PI { 1 } else { 0 };
-                let sweep = if sweep_angle > 0.0 { 1 } else { 0 };
-                
-                Some(format!(
-                    r#"<path d="M {:.4} {:.4} A {:.4} {:.4} 0 {} {} {:.4} {:.4}" {}/>"#,
-                    start_x, start_y,
-                    arc.radius, arc.radius,
-                    large_arc, sweep,
-                    end_x, end_y,
-                    style
-                ))
-            }
             Geometry::Point(point) => {
                 let size = 1.0;
                 Some(format!(
@@ -283,67 +237,54 @@ impl SvgExporter {
                     point.position.x, point.position.y, size, stroke_color
                 ))
             }
-            Geometry::Polyline(polyline) => {
-                if polyline.vertices.is_empty() {
+            Geometry::Arc(_) | Geometry::Polyline(_) | Geometry::Ellipse(_) | Geometry::Spline(_) => {
+                // 曲线按输出分辨率细分，与屏幕显示和光栅导出使用同一套结果
+                let paths = tessellation::tessellate(geometry, tolerance);
+                if paths.is_empty() {
                     return None;
                 }
-                
-                let mut path = String::new();
-                for (i, vertex) in polyline.vertices.iter().enumerate() {
-                    if i == 0 {
-                        path.push_str(&format!("M {:.4} {:.4}", vertex.point.x, vertex.point.y));
-                    } else {
-                        path.push_str(&format!(" L {:.4} {:.4}", vertex.point.x, vertex.point.y));
-                    }
-                }
-                if polyline.closed {
-                    path.push_str(" Z");
-                }
-                
-                Some(format!(r#"<path d="{}" {}/>"#, path, style))
-            }
-            Geometry::Ellipse(ellipse) => {
-                let major_len = ellipse.major_axis.norm();
-                let minor_len = major_len * ellipse.ratio;
-                let rotation = ellipse.major_axis.y.atan2(ellipse.major_axis.x) * 180.0 / std::f64::consts::PI;
-                
-                Some(format!(
-                    r#"<ellipse cx="{:.4}" cy="{:.4}" rx="{:.4}" ry="{:.4}" transform="rotate({:.2} {:.4} {:.4})" {}/>"#,
-                    ellipse.center.x, ellipse.center.y,
-                    major_len, minor_len,
-                    rotation, ellipse.center.x, ellipse.center.y,
-                    style
-                ))
-            }
-            Geometry::Spline(spline) => {
-                // 简化处理：使用多段线近似
-                if spline.control_points.len() < 2 {
-                    return None;
-                }
-                
-                let mut path = String::new();
-                let first = &spline.control_points[0];
-                path.push_str(&format!("M {:.4} {:.4}", first.x, first.y));
-                
-                // 对于简单情况，直接连接控制点
-                for point in spline.control_points.iter().skip(1) {
-                    path.push_str(&format!(" L {:.4} {:.4}", point.x, point.y));
-                }
-                
-                if spline.closed {
-                    path.push_str(" Z");
-                }
-                
-                Some(format!(r#"<path d="{}" {}/>"#, path, style))
+                let d = paths
+                    .iter()
+                    .map(|path| {
+                        let closed = path.len() > 2 && path[0] == path[path.len() - 1];
+                        if closed { svg_path_data(path) + " Z" } else { svg_path_data(path) }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                Some(format!(r#"<path d="{}" {}/>"#, d, style))
             }
             Geometry::Text(text) => {
-                // 简单的文本渲染
+                // 所有字符都有字形时输出轮廓路径，与屏幕显示一致；否则退回 SVG 文本
+                if FontManager::shared().covers(&text.content, None) {
+                    let (filled, stroked): (Vec<_>, Vec<_>) = text.outlines().into_iter().partition(|g| g.filled);
+                    let path_data = |glyphs: &[Glyph]| {
+                        glyphs
+                            .iter()
+                            .flat_map(|glyph| &glyph.contours)
+                            .map(|contour| svg_path_data(contour))
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    };
+                    let mut elements = Vec::new();
+                    if !filled.is_empty() {
+                        elements.push(format!(
+                            r#"<path d="{}" fill="{}" fill-rule="nonzero" stroke="none"/>"#,
+                            path_data(&filled),
+                            stroke_color
+                        ));
+                    }
+                    if !stroked.is_empty() {
+                        elements.push(format!(r#"<path d="{}" {} stroke-linecap="round"/>"#, path_data(&stroked), style));
+                    }
+                    return (!elements.is_empty()).then(|| elements.join("\n    "));
+                }
                 let font_size = text.height;
+                let (start, _) = text.baseline();
                 Some(format!(
                     r#"<text x="{:.4}" y="{:.4}" font-size="{:.2}" fill="{}" transform="scale(1,-1) translate(0,{:.4})">{}</text>"#,
-                    text.position.x, -text.position.y, font_size, stroke_color,
-                    -2.0 * text.position.y,
-                    text.content
+                    start.x, -start.y, font_size, stroke_color,
+                    -2.0 * start.y,
+                    text.display_text()
                 ))
             }
             Geometry::Leader(leader) => {
@@ -387,31 +328,66 @@ impl SvgExporter {
                 Some(format!(r#"<path d="{}" {}/>"#, path, style))
             }
             Geometry::Dimension(dim) => {
-                // 标注渲染较复杂，这里简化处理
-                let p1 = dim.definition_point1;
-                let p2 = dim.definition_point2;
-                let text_pos = dim.get_text_position();
-                
-                let mut elements = vec![];
-                
-                // 绘制标注线
-                elements.push(format!(
-                    r#"<line x1="{:.4}" y1="{:.4}" x2="{:.4}" y2="{:.4}" {}/>"#,
-                    p1.x, p1.y, p2.x, p2.y, style
-                ));
-                
-                // 绘制文本
-                elements.push(format!(
-                    r#"<text x="{:.4}" y="{:.4}" font-size="{:.2}" fill="{}" text-anchor="middle" transform="scale(1,-1) translate(0,{:.4})">{}</text>"#,
-                    text_pos.x, -text_pos.y, dim.text_height, stroke_color,
-                    -2.0 * text_pos.y,
-                    dim.display_text()
-                ));
-                
+                // 未经 Document::resolved_entities 分解的标注按默认样式分解
+                let elements: Vec<String> = dim
+                    .explode(&DimStyle::default())
+                    .iter()
+                    .filter_map(|part| self.geometry_to_svg(part, color, stroke_width, tolerance))
+                    .collect();
+                Some(elements.join("\n    "))
+            }
+            Geometry::MText(mtext) => {
+                // 多行文字按排版结果分解，带颜色的片段使用自身颜色
+                let elements: Vec<String> = mtext
+                    .explode_with_colors()
+                    .iter()
+                    .filter_map(|(part, part_color)| {
+                        self.geometry_to_svg(part, part_color.as_ref().unwrap_or(color), stroke_width, tolerance)
+                    })
+                    .collect();
                 Some(elements.join("\n    "))
             }
-            Geometry::Hatch(_) => {
-                // 填充渲染需要更复杂的处理
+            Geometry::Tolerance(gdt) => {
+                // 特征控制框分解为框格线、符号、文字和引线
+                let elements: Vec<String> = gdt
+                    .explode()
+                    .iter()
+                    .filter_map(|part| self.geometry_to_svg(part, color, stroke_width, tolerance))
+                    .collect();
+                Some(elements.join("\n    "))
+            }
+            Geometry::Hatch(hatch) => {
+                let loops = tessellation::hatch_loops(hatch, tolerance);
+                if loops.is_empty() {
+                    return None;
+                }
+                let segments = hatch_pattern::pattern_segments(hatch);
+                let d = if matches!(hatch.pattern_type, HatchPatternType::Solid) || segments.is_empty() {
+                    loops
+                        .iter()
+                        .map(|ring| svg_path_data(ring) + " Z")
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                } else {
+                    segments
+                        .iter()
+                        .map(|segment| svg_path_data(segment))
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                };
+
+                if matches!(hatch.pattern_type, HatchPatternType::Solid) {
+                    Some(format!(
+                        r#"<path d="{}" fill="{}" fill-rule="evenodd" stroke="none"/>"#,
+                        d, stroke_color
+                    ))
+                } else {
+                    // 圆头线帽使图案中的点（零长度线段）可见
+                    Some(format!(r#"<path d="{}" {} stroke-linecap="round"/>"#, d, style))
+                }
+            }
+            Geometry::Insert(_) => {
+                // 块参照需先通过 Document::resolved_entities 展开
                 None
             }
         }
@@ -425,43 +401,164 @@ impl SvgExporter {
     }
 }
 
-/// PDF 导出器（使用 SVG 转换）
+/// 折线的 SVG 路径数据（`M x y L x y ...`）
+fn svg_path_data(points: &[Point2]) -> String {
+    points
+        .iter()
+        .enumerate()
+        .map(|(i, p)| format!("{} {:.4} {:.4}", if i == 0 { "M" } else { "L" }, p.x, p.y))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 计算所有实体的包围盒
+pub(crate) fn calculate_bounds(page_setup: &PageSetup, entities: &[Entity]) -> PrintArea {
+    if entities.is_empty() {
+        return PrintArea::new(Point2::origin(), Point2::new(100.0, 100.0));
+    }
+
+    let mut min_x = f64::MAX;
+    let mut min_y = f64::MAX;
+    let mut max_x = f64::MIN;
+    let mut max_y = f64::MIN;
+
+    for entity in entities {
+        let bbox = entity.geometry.bounding_box();
+        min_x = min_x.min(bbox.min.x);
+        min_y = min_y.min(bbox.min.y);
+        max_x = max_x.max(bbox.max.x);
+        max_y = max_y.max(bbox.max.y);
+    }
+
+    // 如果有指定打印区域，使用它
+    if let Some(ref area) = page_setup.print_area {
+        return area.clone();
+    }
+
+    PrintArea::new(Point2::new(min_x, min_y), Point2::new(max_x, max_y))
+}
+
+/// 计算变换参数
+pub(crate) fn calculate_transform(page_setup: &PageSetup, bounds: &PrintArea, page_width: f64, page_height: f64) -> (f64, Vector2) {
+    let content_width = bounds.width();
+    let content_height = bounds.height();
+    
+    let scale = if page_setup.fit_to_page {
+        // 零宽或零高的方向不参与适应（如单个点或水平线）
+        let scale_x = if content_width > EPSILON { page_width / content_width } else { f64::INFINITY };
+        let scale_y = if content_height > EPSILON { page_height / content_height } else { f64::INFINITY };
+        let fit = scale_x.min(scale_y);
+        if fit.is_finite() { fit * 0.95 } else { 1.0 } // 留一点边距
+    } else {
+        page_setup.scale
+    };
+    
+    // 居中偏移
+    let scaled_width = content_width * scale;
+    let scaled_height = content_height * scale;
+    let offset_x = (page_width - scaled_width) / 2.0 - bounds.min.x * scale;
+    let offset_y = (page_height - scaled_height) / 2.0 - bounds.min.y * scale;
+    
+    (scale, Vector2::new(offset_x, offset_y))
+}
+
+/// PDF 导出器
+///
+/// 直接生成 PDF 1.7 矢量文件：
+/// - 线宽按 `LineWeight`（毫米）输出，线型按 `LineType::pattern` 生成虚线模式
+/// - 圆弧、圆、椭圆转换为三次贝塞尔曲线
+/// - 实心填充使用奇偶规则填充边界
+/// - 文字作为可选择的文本输出（西文使用 Helvetica，中文使用 STSong-Light）
+/// - 设置图层表后，每个可打印图层对应一个可选内容组（OCG）
+///
+/// `export_layouts` 将文档的全部布局按图纸空间输出为多页 PDF。
 pub struct PdfExporter {
     page_setup: PageSetup,
+    layers: Option<LayerManager>,
 }
 
 impl PdfExporter {
     pub fn new(page_setup: PageSetup) -> Self {
-        Self { page_setup }
+        Self {
+            page_setup,
+            layers: None,
+        }
+    }
+
+    /// 设置图层表
+    ///
+    /// 用于解析 ByLayer 颜色/线型/线宽，跳过冻结和不可打印的图层，并为每个图层生成可选内容组
+    pub fn with_layers(mut self, layers: LayerManager) -> Self {
+        self.layers = Some(layers);
+        self
     }
 
     /// 导出为 PDF
-    /// 
-    /// 注意：实际的 PDF 生成需要额外的库（如 printpdf）
-    /// 这里提供一个简化的接口
     pub fn export(&self, entities: &[Entity]) -> Result<Vec<u8>, FileError> {
-        // 首先生成 SVG
-        let svg_exporter = SvgExporter::new(self.page_setup.clone());
-        let svg_content = svg_exporter.export(entities)?;
-        
-        // 注意：完整的 PDF 导出需要使用 printpdf 或类似的库
-        // 这里返回 SVG 内容的字节，作为占位符
-        // 在实际实现中，应该使用 svg2pdf 或直接使用 printpdf 渲染
-        
-        // 简化实现：返回包装的 SVG
-        let pdf_placeholder = format!(
-            "PDF Export Placeholder\n\
-             Paper: {:?}\n\
-             Orientation: {:?}\n\
-             Entities: {}\n\n\
-             SVG Content:\n{}",
-            self.page_setup.paper_size,
-            self.page_setup.orientation,
-            entities.len(),
-            svg_content
+        let (page_width, page_height) = self.page_setup.paper_dimensions();
+        let (print_width, print_height) = self.page_setup.printable_size();
+        if page_width <= 0.0 || page_height <= 0.0 || print_width <= 0.0 || print_height <= 0.0 {
+            return Err(FileError::InvalidFormat(format!(
+                "Invalid page size {:.1}x{:.1}mm with margins {:?}",
+                page_width, page_height, self.page_setup.margins
+            )));
+        }
+        let (_, _, bottom, left) = self.page_setup.margins;
+
+        let bounds = calculate_bounds(&self.page_setup, entities);
+        let (scale, offset) = calculate_transform(&self.page_setup, &bounds, print_width, print_height);
+        let transform = PageTransform::new(scale, 0.0, offset + Vector2::new(left, bottom));
+        let mut page = PdfPage::new(page_width, page_height, transform);
+
+        // 裁剪到可打印区域
+        page.content.save_state();
+        page.content.rect(
+            left * MM_TO_PT,
+            bottom * MM_TO_PT,
+            print_width * MM_TO_PT,
+            print_height * MM_TO_PT,
         );
-        
-        Ok(pdf_placeholder.into_bytes())
+        page.content.clip();
+
+        let mut plotted = PlottedLayers::default();
+        page.draw_entities(entities.iter(), self.layers.as_ref(), &mut plotted, &[]);
+        page.content.restore_state();
+
+        Ok(write_pdf(vec![page], &plotted.layers))
+    }
+
+    /// 将文档的所有布局输出为多页 PDF（每个布局一页）
+    ///
+    /// 每页使用布局自身的纸张设置；模型空间内容按各视口的比例、旋转映射到图纸，
+    /// 并裁剪到视口边界，随后绘制布局的图纸空间实体。此时忽略 `PageSetup`，
+    /// 图层始终取自文档。
+    pub fn export_layouts(&self, document: &Document) -> Result<Vec<u8>, FileError> {
+        let layouts = document.layout_manager.layouts();
+        if layouts.is_empty() {
+            return Err(FileError::InvalidFormat("Document has no layouts to plot".to_string()));
+        }
+
+        // 块参照展开为基本图元后再绘制
+        let model = document.resolved_entities();
+        let model: Vec<&Entity> = model.iter().collect();
+        let paper: Vec<Vec<Entity>> = layouts
+            .iter()
+            .map(|layout| document.resolve_entities(&layout.paper_space_entities))
+            .collect();
+        let mut plotted = PlottedLayers::default();
+        let mut pages = Vec::with_capacity(layouts.len());
+        for (layout, paper) in layouts.iter().zip(&paper) {
+            pages.push(plot_layout(layout, &model, paper, &document.layers, &mut plotted)?);
+        }
+
+        Ok(write_pdf(pages, &plotted.layers))
+    }
+
+    /// 将文档的所有布局导出到 PDF 文件
+    pub fn export_layouts_to_file(&self, document: &Document, path: &std::path::Path) -> Result<(), FileError> {
+        let pdf_data = self.export_layouts(document)?;
+        std::fs::write(path, pdf_data)?;
+        Ok(())
     }
 
     /// 导出到文件
@@ -472,6 +569,764 @@ impl PdfExporter {
     }
 }
 
+/// 页面资源中可选内容组的属性名
+fn optional_content_tag(index: usize) -> String {
+    format!("OC{}", index)
+}
+
+/// 已输出的图层，每个对应一个可选内容组（所有页面共享）
+#[derive(Default)]
+struct PlottedLayers<'a> {
+    layers: Vec<&'a Layer>,
+}
+
+impl<'a> PlottedLayers<'a> {
+    /// 获取图层的可选内容组属性名，首次使用时登记
+    fn tag(&mut self, layer: &'a Layer) -> String {
+        let index = match self.layers.iter().position(|l| l.id == layer.id) {
+            Some(index) => index,
+            None => {
+                self.layers.push(layer);
+                self.layers.len() - 1
+            }
+        };
+        optional_content_tag(index)
+    }
+}
+
+/// 按图层分组（图层表顺序），找不到图层的实体放在最后一组
+pub(crate) fn group_by_layer<'a>(
+    entities: impl Iterator<Item = &'a Entity>,
+    layers: Option<&'a LayerManager>,
+) -> Vec<(Option<&'a Layer>, Vec<&'a Entity>)> {
+    let visible = entities.filter(|e| e.visible);
+
+    let Some(layers) = layers else {
+        return vec![(None, visible.collect())];
+    };
+
+    let mut groups: Vec<(Option<&Layer>, Vec<&Entity>)> =
+        layers.all_layers().iter().map(|layer| (Some(layer), Vec::new())).collect();
+    let mut orphans = Vec::new();
+    for entity in visible {
+        match layers.all_layers().iter().position(|l| l.id == entity.layer_id) {
+            Some(index) => groups[index].1.push(entity),
+            None => orphans.push(entity),
+        }
+    }
+    if !orphans.is_empty() {
+        groups.push((None, orphans));
+    }
+    groups
+}
+
+/// 绘制一个布局页面
+fn plot_layout<'a>(
+    layout: &'a Layout,
+    model: &[&'a Entity],
+    paper_entities: &'a [Entity],
+    layers: &'a LayerManager,
+    plotted: &mut PlottedLayers<'a>,
+) -> Result<PdfPage, FileError> {
+    let (width, height) = layout.paper_dimensions();
+    if width <= 0.0 || height <= 0.0 {
+        return Err(FileError::InvalidFormat(format!(
+            "Layout '{}' has invalid paper size {:.1}x{:.1}mm",
+            layout.name, width, height
+        )));
+    }
+
+    // 图纸坐标（毫米）→ 打印纸张坐标
+    let paper_scale = if layout.plot_scale > EPSILON { 1.0 / layout.plot_scale } else { 1.0 };
+    let paper = PageTransform::new(
+        paper_scale,
+        0.0,
+        Vector2::new(layout.plot_offset.0, layout.plot_offset.1),
+    );
+    let mut page = PdfPage::new(width, height, paper);
+
+    for viewport in &layout.viewports {
+        if viewport.status == ViewportStatus::Hidden || viewport.scale <= EPSILON {
+            continue;
+        }
+        let (min, max) = viewport.paper_bounds();
+        let (x0, y0) = page.to_page(min);
+        let (x1, y1) = page.to_page(max);
+
+        // 模型空间内容：经视口变换后裁剪到视口边界
+        page.content.save_state();
+        page.content.rect(x0, y0, x1 - x0, y1 - y0);
+        page.content.clip();
+        page.transform = paper.compose(&PageTransform::viewport(viewport));
+        let visible = viewport_model_box(viewport);
+        let entities = model
+            .iter()
+            .copied()
+            .filter(|e| e.geometry.bounding_box().intersects(&visible));
+        page.draw_entities(entities, Some(layers), plotted, &viewport.frozen_layers);
+        page.transform = paper;
+        page.content.restore_state();
+
+        if viewport.show_border {
+            let (r, g, b) = viewport.border_color;
+            page.content.set_stroke_rgb(r, g, b);
+            page.content.set_line_width(0.25 * MM_TO_PT);
+            page.content.set_dash(&[], 0.0);
+            page.content.rect(x0, y0, x1 - x0, y1 - y0);
+            page.content.stroke();
+        }
+    }
+
+    page.draw_entities(paper_entities.iter(), Some(layers), plotted, &[]);
+    Ok(page)
+}
+
+/// 视口显示的模型空间范围（考虑视口旋转）
+fn viewport_model_box(viewport: &Viewport) -> BoundingBox2 {
+    let (min, max) = viewport.paper_bounds();
+    let corners = [min, Point2::new(max.x, min.y), max, Point2::new(min.x, max.y)];
+    BoundingBox2::from_points(corners.iter().map(|c| viewport.paper_to_model(*c)))
+}
+
+/// 组装 PDF 文档：字体、可选内容组、页面树和目录
+fn write_pdf(pages: Vec<PdfPage>, layers: &[&Layer]) -> Vec<u8> {
+    let mut writer = PdfWriter::new();
+    let catalog = writer.reserve();
+    let page_tree = writer.reserve();
+
+    let helvetica =
+        writer.add("<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>");
+    let cjk_font = if pages.iter().any(|p| p.uses_cjk_font) {
+        Some(add_cjk_font(&mut writer))
+    } else {
+        None
+    };
+
+    let ocgs: Vec<ObjectRef> = layers
+        .iter()
+        .map(|layer| writer.add(format!("<< /Type /OCG /Name {} >>", pdf::text_string(&layer.name))))
+        .collect();
+
+    let mut resources = format!("<< /Font << /F1 {}", helvetica);
+    if let Some(font) = cjk_font {
+        resources.push_str(&format!(" /F2 {}", font));
+    }
+    resources.push_str(" >>");
+    if !ocgs.is_empty() {
+        resources.push_str(" /Properties <<");
+        for (index, ocg) in ocgs.iter().enumerate() {
+            resources.push_str(&format!(" {} {}", pdf::name(&optional_content_tag(index)), ocg));
+        }
+        resources.push_str(" >>");
+    }
+    resources.push_str(" >>");
+
+    let mut kids = Vec::with_capacity(pages.len());
+    for page in pages {
+        let (width, height) = (page.width * MM_TO_PT, page.height * MM_TO_PT);
+        let contents = writer.add_stream("", &page.content.into_bytes());
+        kids.push(writer.add(format!(
+            "<< /Type /Page /Parent {} /MediaBox [0 0 {} {}] /Resources {} /Contents {} >>",
+            page_tree,
+            pdf::number(width),
+            pdf::number(height),
+            resources,
+            contents
+        )));
+    }
+    let kid_list: Vec<String> = kids.iter().map(|k| k.to_string()).collect();
+    writer.set(
+        page_tree,
+        format!("<< /Type /Pages /Kids [{}] /Count {} >>", kid_list.join(" "), kids.len()),
+    );
+
+    let mut catalog_dict = format!("<< /Type /Catalog /Pages {}", page_tree);
+    if !ocgs.is_empty() {
+        let all: Vec<String> = ocgs.iter().map(|o| o.to_string()).collect();
+        let hidden: Vec<String> = ocgs
+            .iter()
+            .zip(layers)
+            .filter(|(_, layer)| !layer.visible)
+            .map(|(o, _)| o.to_string())
+            .collect();
+        catalog_dict.push_str(&format!(
+            " /OCProperties << /OCGs [{0}] /D << /Order [{0}] /OFF [{1}] >> >>",
+            all.join(" "),
+            hidden.join(" ")
+        ));
+    }
+    catalog_dict.push_str(" >>");
+    writer.set(catalog, catalog_dict);
+
+    let info = writer.add(format!(
+        "<< /Producer (ZCAD) /CreationDate {} >>",
+        pdf::literal_string(&chrono::Local::now().format("D:%Y%m%d%H%M%S").to_string())
+    ));
+
+    writer.finish(catalog, Some(info))
+}
+
+/// 添加中文字体（Adobe-GB1 预定义 CMap，不嵌入，由阅读器提供字形）
+fn add_cjk_font(writer: &mut PdfWriter) -> ObjectRef {
+    let descriptor = writer.add(
+        "<< /Type /FontDescriptor /FontName /STSong-Light /Flags 6 /FontBBox [-25 -254 1000 880] \
+         /ItalicAngle 0 /Ascent 880 /Descent -120 /CapHeight 880 /StemV 93 >>",
+    );
+    let descendant = writer.add(format!(
+        "<< /Type /Font /Subtype /CIDFontType0 /BaseFont /STSong-Light \
+         /CIDSystemInfo << /Registry (Adobe) /Ordering (GB1) /Supplement 4 >> \
+         /FontDescriptor {} /DW 1000 >>",
+        descriptor
+    ));
+    writer.add(format!(
+        "<< /Type /Font /Subtype /Type0 /BaseFont /STSong-Light /Encoding /UniGB-UTF16-H \
+         /DescendantFonts [{}] >>",
+        descendant
+    ))
+}
+
+/// 解析 ByLayer/ByBlock 之后的打印样式
+pub(crate) struct PlotStyle {
+    pub color: Color,
+    pub line_type: LineType,
+    pub line_weight_mm: f64,
+}
+
+impl PlotStyle {
+    /// `background` 为纸张颜色，与其相同的白色/黑色会取反以保持可见
+    pub fn resolve(properties: &Properties, layer: Option<&Layer>, background: Color) -> Self {
+        let mut color = if properties.color.is_by_layer() {
+            layer.map(|l| l.color).unwrap_or(Color::BLACK)
+        } else if properties.color.is_by_block() {
+            Color::BLACK
+        } else {
+            properties.color
+        };
+        // 白纸上的白色（或黑底上的黑色）不可见，按打印惯例取反
+        let light_background = (background.r as u32 + background.g as u32 + background.b as u32) > 384;
+        if light_background && (color.r, color.g, color.b) == (255, 255, 255) {
+            color = Color::BLACK;
+        } else if !light_background && (color.r, color.g, color.b) == (0, 0, 0) {
+            color = Color::WHITE;
+        }
+
+        let line_type = match &properties.line_type {
+            LineType::ByLayer => layer.map(|l| l.line_type.clone()).unwrap_or(LineType::Continuous),
+            LineType::ByBlock => LineType::Continuous,
+            other => other.clone(),
+        };
+
+        let line_weight_mm = match properties.line_weight {
+            LineWeight::Width(w) => w,
+            LineWeight::ByLayer => match layer.map(|l| l.line_weight) {
+                Some(LineWeight::Width(w)) => w,
+                _ => 0.25,
+            },
+            LineWeight::Default | LineWeight::ByBlock => 0.25,
+        };
+
+        Self {
+            color,
+            line_type,
+            line_weight_mm,
+        }
+    }
+}
+
+/// 箭头三角形的三个顶点：尖端、左侧、右侧
+pub(crate) fn arrow_triangle(tip: Point2, direction: Vector2, size: f64) -> [Point2; 3] {
+    let dir = direction.normalize();
+    let perp = Vector2::new(-dir.y, dir.x);
+    let base = tip - dir * size;
+    [tip, base + perp * (size / 6.0), base - perp * (size / 6.0)]
+}
+
+/// 路径段（模型坐标）
+#[derive(Debug, Clone, Copy)]
+enum PathSegment {
+    Line(Point2, Point2),
+    /// 三次贝塞尔曲线：起点、两个控制点、终点
+    Cubic(Point2, Point2, Point2, Point2),
+}
+
+impl PathSegment {
+    fn start(&self) -> Point2 {
+        match self {
+            PathSegment::Line(p, _) | PathSegment::Cubic(p, _, _, _) => *p,
+        }
+    }
+
+    fn end(&self) -> Point2 {
+        match self {
+            PathSegment::Line(_, p) | PathSegment::Cubic(_, _, _, p) => *p,
+        }
+    }
+
+    fn reversed(&self) -> Self {
+        match *self {
+            PathSegment::Line(a, b) => PathSegment::Line(b, a),
+            PathSegment::Cubic(a, b, c, d) => PathSegment::Cubic(d, c, b, a),
+        }
+    }
+}
+
+/// 圆弧转换为贝塞尔曲线，`sweep` 带符号（正值为逆时针），每段不超过 90°
+fn arc_segments(center: Point2, radius: f64, start_angle: f64, sweep: f64) -> Vec<PathSegment> {
+    elliptical_segments(
+        center,
+        Vector2::new(radius, 0.0),
+        Vector2::new(0.0, radius),
+        start_angle,
+        sweep,
+    )
+}
+
+/// 椭圆弧（参数形式 `center + u·cos(t) + v·sin(t)`）转换为贝塞尔曲线
+///
+/// 仿射变换保持贝塞尔曲线不变，因此先在单位圆上求控制点再映射
+fn elliptical_segments(center: Point2, u: Vector2, v: Vector2, start: f64, sweep: f64) -> Vec<PathSegment> {
+    if sweep.abs() < EPSILON {
+        return Vec::new();
+    }
+    let count = (sweep.abs() / std::f64::consts::FRAC_PI_2).ceil().max(1.0) as usize;
+    let step = sweep / count as f64;
+    let k = 4.0 / 3.0 * (step / 4.0).tan();
+    let map = |x: f64, y: f64| center + u * x + v * y;
+
+    (0..count)
+        .map(|i| {
+            let a0 = start + step * i as f64;
+            let a1 = a0 + step;
+            let (s0, c0) = a0.sin_cos();
+            let (s1, c1) = a1.sin_cos();
+            PathSegment::Cubic(
+                map(c0, s0),
+                map(c0 - k * s0, s0 + k * c0),
+                map(c1 + k * s1, s1 - k * c1),
+                map(c1, s1),
+            )
+        })
+        .collect()
+}
+
+/// 多段线的一段
+fn polyline_segment_path(segment: &PolylineSegment) -> Vec<PathSegment> {
+    if segment.is_arc() {
+        arc_segments(segment.center, segment.radius, segment.start_angle, segment.sweep)
+    } else {
+        vec![PathSegment::Line(segment.start, segment.end)]
+    }
+}
+
+fn ellipse_segments(ellipse: &Ellipse) -> Vec<PathSegment> {
+    let sweep = if ellipse.is_full() {
+        std::f64::consts::TAU
+    } else {
+        let mut sweep = ellipse.end_param - ellipse.start_param;
+        if sweep <= 0.0 {
+            sweep += std::f64::consts::TAU;
+        }
+        sweep
+    };
+    let v = ellipse.minor_axis_direction() * ellipse.minor_radius();
+    elliptical_segments(ellipse.center, ellipse.major_axis, v, ellipse.start_param, sweep)
+}
+
+fn spline_segments(spline: &Spline, tolerance: f64) -> Vec<PathSegment> {
+    polyline_segments(&tessellation::spline_points(spline, tolerance))
+}
+
+fn polyline_segments(points: &[Point2]) -> Vec<PathSegment> {
+    points.windows(2).map(|w| PathSegment::Line(w[0], w[1])).collect()
+}
+
+fn arc_geometry_segments(arc: &Arc) -> Vec<PathSegment> {
+    arc_segments(arc.center, arc.radius, arc.start_angle, arc.sweep_angle())
+}
+
+/// 曲线类几何体的路径（每个元素为一条子路径及其是否闭合）
+///
+/// 圆弧和椭圆弧输出为精确的贝塞尔曲线，样条按 `tolerance`（绘图单位）细分
+fn curve_paths(geometry: &Geometry, tolerance: f64) -> Vec<(Vec<PathSegment>, bool)> {
+    match geometry {
+        Geometry::Line(line) => vec![(vec![PathSegment::Line(line.start, line.end)], false)],
+        Geometry::Circle(circle) => vec![(
+            arc_segments(circle.center, circle.radius, 0.0, std::f64::consts::TAU),
+            true,
+        )],
+        Geometry::Arc(arc) => vec![(arc_geometry_segments(arc), false)],
+        Geometry::Polyline(polyline) => {
+            if polyline.vertices.len() < 2 {
+                return Vec::new();
+            }
+            let segments = polyline.segments().flat_map(|segment| polyline_segment_path(&segment)).collect();
+            vec![(segments, polyline.closed)]
+        }
+        Geometry::Ellipse(ellipse) => vec![(ellipse_segments(ellipse), ellipse.is_full())],
+        Geometry::Spline(spline) => vec![(spline_segments(spline, tolerance), spline.closed)],
+        _ => Vec::new(),
+    }
+}
+
+/// 填充边界元素的路径段
+fn hatch_element_segments(element: &HatchBoundaryElement, tolerance: f64) -> Vec<PathSegment> {
+    match element {
+        HatchBoundaryElement::Line(line) => vec![PathSegment::Line(line.start, line.end)],
+        HatchBoundaryElement::Arc(arc) => arc_geometry_segments(arc),
+        HatchBoundaryElement::Ellipse(ellipse) => ellipse_segments(ellipse),
+        HatchBoundaryElement::Spline(spline) => spline_segments(spline, tolerance),
+    }
+}
+
+/// 首尾相接地排列边界元素（元素方向可能不一致）
+fn chain_segments(elements: Vec<Vec<PathSegment>>) -> Vec<PathSegment> {
+    let mut result: Vec<PathSegment> = Vec::new();
+    for element in elements {
+        let (Some(first), Some(last)) = (element.first(), element.last()) else {
+            continue;
+        };
+        let reverse = match result.last() {
+            Some(previous) => {
+                let end = previous.end();
+                (first.start() - end).norm() > (last.end() - end).norm()
+            }
+            None => false,
+        };
+        if reverse {
+            result.extend(element.iter().rev().map(|s| s.reversed()));
+        } else {
+            result.extend(element);
+        }
+    }
+    result
+}
+
+/// 绘图坐标 → 纸张毫米的相似变换：`offset + R(rotation)·p·scale`
+#[derive(Debug, Clone, Copy)]
+struct PageTransform {
+    scale: f64,
+    rotation: f64,
+    offset: Vector2,
+}
+
+impl PageTransform {
+    fn new(scale: f64, rotation: f64, offset: Vector2) -> Self {
+        Self {
+            scale,
+            rotation,
+            offset,
+        }
+    }
+
+    /// 视口的模型空间 → 图纸空间变换（与 `Viewport::model_to_paper` 一致）
+    fn viewport(viewport: &Viewport) -> Self {
+        Self::new(
+            1.0 / viewport.scale,
+            viewport.rotation,
+            viewport.model_to_paper(Point2::origin()).coords,
+        )
+    }
+
+    fn apply(&self, p: Point2) -> Point2 {
+        let (sin, cos) = self.rotation.sin_cos();
+        let v = Vector2::new(p.x * cos - p.y * sin, p.x * sin + p.y * cos) * self.scale;
+        Point2::from(self.offset + v)
+    }
+
+    /// 组合变换：先应用 `inner`，再应用自身
+    fn compose(&self, inner: &PageTransform) -> Self {
+        Self::new(
+            self.scale * inner.scale,
+            self.rotation + inner.rotation,
+            self.apply(Point2::from(inner.offset)).coords,
+        )
+    }
+}
+
+/// 正在绘制的 PDF 页面
+///
+/// 绘图坐标先经 `transform` 映射到纸张毫米，再换算为 PDF 点
+struct PdfPage {
+    /// 纸张宽度（毫米）
+    width: f64,
+    /// 纸张高度（毫米）
+    height: f64,
+    transform: PageTransform,
+    content: ContentStream,
+    uses_cjk_font: bool,
+}
+
+impl PdfPage {
+    fn new(width: f64, height: f64, transform: PageTransform) -> Self {
+        let mut content = ContentStream::new();
+        content.set_line_cap(1);
+        content.set_line_join(1);
+        Self {
+            width,
+            height,
+            transform,
+            content,
+            uses_cjk_font: false,
+        }
+    }
+
+    /// 绘图坐标 → PDF 点
+    fn to_page(&self, p: Point2) -> (f64, f64) {
+        let q = self.transform.apply(p);
+        (q.x * MM_TO_PT, q.y * MM_TO_PT)
+    }
+
+    /// 曲线细分的弦高容差（绘图单位）
+    fn tolerance(&self) -> f64 {
+        tessellation::plot_tolerance(VECTOR_DPI, self.transform.scale)
+    }
+
+    /// 按图层绘制实体：每个图层包在各自的可选内容标记中，
+    /// 跳过冻结、不可打印以及 `frozen_layers` 中列出的图层
+    fn draw_entities<'a>(
+        &mut self,
+        entities: impl Iterator<Item = &'a Entity>,
+        layers: Option<&'a LayerManager>,
+        plotted: &mut PlottedLayers<'a>,
+        frozen_layers: &[String],
+    ) {
+        for (layer, group) in group_by_layer(entities, layers) {
+            match layer {
+                Some(layer) => {
+                    if group.is_empty()
+                        || layer.frozen
+                        || !layer.plottable
+                        || frozen_layers.iter().any(|name| name == &layer.name)
+                    {
+                        continue;
+                    }
+                    self.content.begin_optional_content(&plotted.tag(layer));
+                    for entity in group {
+                        self.draw_entity(entity, Some(layer));
+                    }
+                    self.content.end_marked_content();
+                }
+                None => {
+                    for entity in group {
+                        self.draw_entity(entity, None);
+                    }
+                }
+            }
+        }
+    }
+
+    fn draw_entity(&mut self, entity: &Entity, layer: Option<&Layer>) {
+        let style = PlotStyle::resolve(&entity.properties, layer, Color::WHITE);
+        let Color { r, g, b, .. } = style.color;
+        self.content.set_stroke_rgb(r, g, b);
+        self.content.set_fill_rgb(r, g, b);
+        self.content.set_line_width(style.line_weight_mm * MM_TO_PT);
+
+        // 线型模式以图形单位定义，随打印比例缩放
+        let mut dashes: Vec<f64> = style
+            .line_type
+            .pattern()
+            .iter()
+            .map(|d| d.abs() * self.transform.scale * MM_TO_PT)
+            .collect();
+        if dashes.iter().sum::<f64>() <= EPSILON {
+            dashes.clear();
+        }
+        self.content.set_dash(&dashes, 0.0);
+
+        match &entity.geometry {
+            Geometry::Point(point) => {
+                let radius = style.line_weight_mm.max(0.5) / 2.0 / self.transform.scale;
+                self.fill_paths(&[arc_segments(point.position, radius, 0.0, std::f64::consts::TAU)]);
+            }
+            Geometry::Text(text) => self.draw_text(text),
+            Geometry::Dimension(dim) => {
+                // 未经 Document::resolved_entities 分解的标注按默认样式分解
+                for geometry in dim.explode(&DimStyle::default()) {
+                    self.draw_entity(&Entity { geometry, ..entity.clone() }, layer);
+                }
+            }
+            Geometry::MText(mtext) => {
+                for (geometry, color) in mtext.explode_with_colors() {
+                    let mut part = Entity { geometry, ..entity.clone() };
+                    part.properties.color = color.unwrap_or(part.properties.color);
+                    self.draw_entity(&part, layer);
+                }
+            }
+            Geometry::Tolerance(tolerance) => {
+                for geometry in tolerance.explode() {
+                    self.draw_entity(&Entity { geometry, ..entity.clone() }, layer);
+                }
+            }
+            Geometry::Hatch(hatch) => self.draw_hatch(hatch),
+            Geometry::Leader(leader) => self.draw_leader(leader),
+            geometry => {
+                for (segments, closed) in curve_paths(geometry, self.tolerance()) {
+                    self.add_subpath(&segments, closed);
+                    self.content.stroke();
+                }
+            }
+        }
+    }
+
+    /// 添加一条子路径；不相连的段之间闭合路径用直线连接，开放路径另起子路径
+    fn add_subpath(&mut self, segments: &[PathSegment], closed: bool) {
+        let Some(first) = segments.first() else {
+            return;
+        };
+        let (x, y) = self.to_page(first.start());
+        self.content.move_to(x, y);
+        let mut current = (x, y);
+
+        for segment in segments {
+            let (sx, sy) = self.to_page(segment.start());
+            if (sx - current.0).hypot(sy - current.1) > 0.01 {
+                if closed {
+                    self.content.line_to(sx, sy);
+                } else {
+                    self.content.move_to(sx, sy);
+                }
+            }
+            match *segment {
+                PathSegment::Line(_, end) => {
+                    let (ex, ey) = self.to_page(end);
+                    self.content.line_to(ex, ey);
+                    current = (ex, ey);
+                }
+                PathSegment::Cubic(_, c1, c2, end) => {
+                    let (x1, y1) = self.to_page(c1);
+                    let (x2, y2) = self.to_page(c2);
+                    let (ex, ey) = self.to_page(end);
+                    self.content.curve_to(x1, y1, x2, y2, ex, ey);
+                    current = (ex, ey);
+                }
+            }
+        }
+
+        if closed {
+            self.content.close_path();
+        }
+    }
+
+    fn fill_paths(&mut self, loops: &[Vec<PathSegment>]) {
+        for segments in loops {
+            self.add_subpath(segments, true);
+        }
+        self.content.fill_even_odd();
+    }
+
+    fn draw_hatch(&mut self, hatch: &Hatch) {
+        let tolerance = self.tolerance();
+        let loops: Vec<Vec<PathSegment>> = hatch
+            .boundaries
+            .iter()
+            .map(|boundary| {
+                chain_segments(boundary.elements.iter().map(|e| hatch_element_segments(e, tolerance)).collect())
+            })
+            .filter(|segments| !segments.is_empty())
+            .collect();
+        if loops.is_empty() {
+            return;
+        }
+
+        match hatch.pattern_type {
+            HatchPatternType::Solid => self.fill_paths(&loops),
+            HatchPatternType::Predefined(_) | HatchPatternType::Custom { .. } => {
+                let segments = hatch_pattern::pattern_segments(hatch);
+                if segments.is_empty() {
+                    // 图案未知时输出边界
+                    for segments in &loops {
+                        self.add_subpath(segments, true);
+                    }
+                    self.content.stroke();
+                    return;
+                }
+
+                // 图案自带虚线，不再叠加线型；圆头线帽使点（零长度线段）可见
+                self.content.save_state();
+                self.content.set_dash(&[], 0.0);
+                self.content.set_line_cap(1);
+                for [a, b] in segments {
+                    let (ax, ay) = self.to_page(a);
+                    let (bx, by) = self.to_page(b);
+                    self.content.move_to(ax, ay);
+                    self.content.line_to(bx, by);
+                }
+                self.content.stroke();
+                self.content.restore_state();
+            }
+        }
+    }
+
+    fn draw_text(&mut self, text: &Text) {
+        if text.content.is_empty() || text.height <= 0.0 {
+            return;
+        }
+        // 两点对齐方式先按两点确定字高、宽度系数和方向，文字从基线起点开始输出
+        let text = text.fitted();
+        let (origin, _) = text.baseline();
+        let (x, y) = self.to_page(origin);
+        let size = text.height * self.transform.scale * MM_TO_PT;
+        let (sin, cos) = (text.rotation + self.transform.rotation).sin_cos();
+        let width_factor = text.width_factor;
+
+        // WinAnsi 能表示的文本使用 Helvetica，其余（中文等）使用 CID 字体
+        let content = text.display_text();
+        let win_ansi = content.chars().all(|c| matches!(c as u32, 0x20..=0x7E | 0xA0..=0xFF));
+        let (font, encoded) = if win_ansi {
+            ("F1", pdf::literal_string(&content))
+        } else {
+            self.uses_cjk_font = true;
+            ("F2", pdf::utf16_hex_string(&content, false))
+        };
+        self.content.show_text(font, size, [cos * width_factor, sin * width_factor, -sin, cos, x, y], &encoded);
+    }
+
+    fn draw_arrow(&mut self, tip: Point2, direction: Vector2, size: f64, arrow_type: ArrowType) {
+        if size <= EPSILON || direction.norm() < EPSILON {
+            return;
+        }
+        let [tip, left, right] = arrow_triangle(tip, direction, size);
+        let triangle = vec![
+            PathSegment::Line(tip, left),
+            PathSegment::Line(left, right),
+            PathSegment::Line(right, tip),
+        ];
+
+        match arrow_type {
+            ArrowType::None => {}
+            ArrowType::ClosedFilled => self.fill_paths(&[triangle]),
+            ArrowType::ClosedBlank => {
+                self.add_subpath(&triangle, true);
+                self.content.stroke();
+            }
+            ArrowType::Open => {
+                self.add_subpath(&[PathSegment::Line(left, tip), PathSegment::Line(tip, right)], false);
+                self.content.stroke();
+            }
+            ArrowType::Dot => {
+                self.fill_paths(&[arc_segments(tip, size / 4.0, 0.0, std::f64::consts::TAU)]);
+            }
+            ArrowType::Circle => {
+                self.add_subpath(&arc_segments(tip, size / 4.0, 0.0, std::f64::consts::TAU), true);
+                self.content.stroke();
+            }
+        }
+    }
+
+    fn draw_leader(&mut self, leader: &Leader) {
+        self.add_subpath(&polyline_segments(&leader.vertices), false);
+        self.content.stroke();
+
+        if let (Some(tip), Some(direction)) = (leader.arrow_point(), leader.arrow_direction()) {
+            self.draw_arrow(tip, direction, leader.arrow_size, leader.arrow_type);
+        }
+        if let Some(text) = leader.text_entity() {
+            self.draw_text(&text);
+        }
+    }
+}
+
 /// 导出格式
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ExportFormat {
@@ -497,12 +1352,15 @@ pub fn export_entities(
             let exporter = PdfExporter::new(page_setup);
             exporter.export_to_file(entities, path)
         }
-        ExportFormat::Png | ExportFormat::Jpg => {
-            // PNG/JPG 导出需要图像渲染库
-            Err(FileError::InvalidFormat(format!(
-                "{:?} export requires image rendering (not yet implemented)",
-                format
-            )))
+        ExportFormat::Png => {
+            let exporter = RasterExporter::new(page_setup);
+            std::fs::write(path, exporter.export_png(entities)?)?;
+            Ok(())
+        }
+        ExportFormat::Jpg => {
+            let exporter = RasterExporter::new(page_setup);
+            std::fs::write(path, exporter.export_jpeg(entities)?)?;
+            Ok(())
         }
     }
 }
@@ -533,4 +1391,184 @@ mod tests {
         assert_eq!(w, 190.0);
         assert_eq!(h, 277.0);
     }
+
+    fn pdf_text(entities: &[Entity], exporter: &PdfExporter) -> String {
+        let data = exporter.export(entities).unwrap();
+        assert!(data.starts_with(b"%PDF-1.7"));
+        assert!(data.ends_with(b"%%EOF\n"));
+        String::from_utf8_lossy(&data).into_owned()
+    }
+
+    #[test]
+    fn test_pdf_export_page_and_paths() {
+        use zcad_core::geometry::{Circle, Line};
+
+        let entities = vec![
+            Entity::new(Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(100.0, 0.0)))),
+            Entity::new(Geometry::Circle(Circle::new(Point2::new(50.0, 50.0), 25.0))),
+        ];
+        let text = pdf_text(&entities, &PdfExporter::new(PageSetup::default()));
+
+        // A4 横向：297 x 210 mm
+        assert!(text.contains("/MediaBox [0 0 841.89 595.276]"));
+        assert!(text.contains(" l\n"));
+        assert!(text.contains(" c\n"));
+        assert!(text.contains("S\n"));
+    }
+
+    #[test]
+    fn test_pdf_line_weight_and_dash() {
+        use zcad_core::geometry::Line;
+
+        let properties = Properties::default()
+            .set_color(Color::RED)
+            .set_line_type(LineType::Dashed)
+            .set_line_weight(LineWeight::Width(0.5));
+        let entities = vec![Entity::new(Geometry::Line(Line::new(
+            Point2::new(0.0, 0.0),
+            Point2::new(100.0, 0.0),
+        )))
+        .with_properties(properties)];
+        let setup = PageSetup {
+            fit_to_page: false,
+            scale: 1.0,
+            ..Default::default()
+        };
+        let text = pdf_text(&entities, &PdfExporter::new(setup));
+
+        // 0.5mm = 1.417pt；Dashed = [12, -6] 图形单位，1:1 打印
+        assert!(text.contains("1.417 w"));
+        assert!(text.contains("[34.016 17.008] 0 d"));
+        assert!(text.contains("1 0 0 RG"));
+    }
+
+    #[test]
+    fn test_pdf_layers_hatch_and_text() {
+        use zcad_core::geometry::{HatchBoundary, Line};
+
+        let mut layers = LayerManager::new();
+        let wall = layers.create_layer("墙体");
+        layers.get_layer_mut("墙体").unwrap().visible = false;
+        let hidden = layers.create_layer("Hidden");
+        layers.get_layer_mut("Hidden").unwrap().plottable = false;
+
+        let square = [
+            Point2::new(0.0, 0.0),
+            Point2::new(10.0, 0.0),
+            Point2::new(10.0, 10.0),
+            Point2::new(0.0, 10.0),
+        ];
+        let boundary = HatchBoundary::new(
+            (0..4)
+                .map(|i| HatchBoundaryElement::Line(Line::new(square[i], square[(i + 1) % 4])))
+                .collect(),
+            true,
+        );
+        let entities = vec![
+            Entity::new(Geometry::Hatch(Hatch::solid(vec![boundary]))).with_layer(wall),
+            Entity::new(Geometry::Text(Text::new(Point2::new(0.0, 12.0), "门 (A)", 2.5))).with_layer(wall),
+            Entity::new(Geometry::Text(Text::new(Point2::new(0.0, 15.0), "Plan", 2.5))),
+            Entity::new(Geometry::Line(Line::new(square[0], square[2]))).with_layer(hidden),
+        ];
+        let text = pdf_text(&entities, &PdfExporter::new(PageSetup::default()).with_layers(layers));
+
+        // 可见的图层为一个可选内容组，初始隐藏；不可打印的图层不输出
+        assert_eq!(text.matches("/Type /OCG").count(), 1);
+        assert!(text.contains("/Name <FEFF58994F53>"));
+        assert!(text.contains("/OC /OC0 BDC"));
+        assert!(text.contains("/OFF ["));
+        assert!(!text.contains("/OFF []"));
+
+        assert!(text.contains("f*"));
+        assert!(text.contains("/UniGB-UTF16-H"));
+        assert!(text.contains("<95E80020002800410029> Tj"));
+        assert!(text.contains("(Plan) Tj"));
+    }
+
+    #[test]
+    fn test_svg_curves_are_tessellated() {
+        use zcad_core::geometry::{Polyline, PolylineVertex};
+
+        let mut arc = Ellipse::new(Point2::new(50.0, 50.0), Vector2::new(40.0, 0.0), 0.5);
+        arc.end_param = std::f64::consts::PI;
+        let semicircle = Polyline::new(
+            vec![
+                PolylineVertex::with_bulge(Point2::new(0.0, 0.0), 1.0),
+                PolylineVertex::new(Point2::new(20.0, 0.0)),
+            ],
+            false,
+        );
+        let entities = vec![Entity::new(Geometry::Ellipse(arc)), Entity::new(Geometry::Polyline(semicircle))];
+        let svg = SvgExporter::new(PageSetup::default()).export(&entities).unwrap();
+
+        // 椭圆弧不再输出为整椭圆，凸度段按弧线细分
+        assert!(!svg.contains("<ellipse"));
+        let paths: Vec<&str> = svg.lines().filter(|line| line.contains("<path")).collect();
+        assert_eq!(paths.len(), 2);
+        assert!(paths.iter().all(|path| path.matches(" L ").count() > 16));
+        assert!(paths[1].contains("M 0.0000 0.0000") && paths[1].contains("L 20.0000 0.0000"));
+    }
+
+    #[test]
+    fn test_page_transform_matches_viewport() {
+        use zcad_core::layout::ViewportId;
+
+        let mut viewport = Viewport::new(ViewportId::new(1), Point2::new(20.0, 30.0), 200.0, 100.0);
+        viewport.view_center = Point2::new(500.0, 250.0);
+        viewport.scale = 50.0;
+        viewport.rotation = 0.3;
+
+        let paper = PageTransform::new(0.5, 0.0, Vector2::new(5.0, 7.0));
+        let combined = paper.compose(&PageTransform::viewport(&viewport));
+        for p in [Point2::new(0.0, 0.0), Point2::new(500.0, 250.0), Point2::new(-120.0, 800.0)] {
+            let expected = paper.apply(viewport.model_to_paper(p));
+            let actual = combined.apply(p);
+            assert!((expected - actual).norm() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_pdf_export_layouts() {
+        use zcad_core::geometry::{Circle, Line};
+
+        let mut document = Document::new();
+        document.add_entity(Entity::new(Geometry::Line(Line::new(
+            Point2::new(-50.0, 0.0),
+            Point2::new(50.0, 0.0),
+        ))));
+        // 不在任何视口范围内的实体不输出
+        document.add_entity(Entity::new(Geometry::Circle(Circle::new(Point2::new(1.0e6, 1.0e6), 1.0))));
+
+        let id = document.layout_manager.add_layout("Detail");
+        let layout = document.layout_manager.get_layout_mut(id).unwrap();
+        layout.paper_size = zcad_core::layout::PaperSize::A4;
+        let viewport = layout.add_viewport(Point2::new(20.0, 20.0), 100.0, 80.0);
+        layout.get_viewport_mut(viewport).unwrap().scale = 10.0;
+        let hidden = layout.add_viewport(Point2::new(150.0, 20.0), 50.0, 50.0);
+        layout.get_viewport_mut(hidden).unwrap().status = ViewportStatus::Hidden;
+        layout.add_paper_entity(Entity::new(Geometry::Text(Text::new(Point2::new(20.0, 10.0), "Title", 5.0))));
+
+        let text = String::from_utf8_lossy(
+            &PdfExporter::new(PageSetup::default()).export_layouts(&document).unwrap(),
+        )
+        .into_owned();
+
+        assert!(text.contains("/Count 2"));
+        // Layout1（A3 横向）与 Detail（A4 横向）
+        assert!(text.contains("/MediaBox [0 0 1190.551 841.89]"));
+        assert!(text.contains("/MediaBox [0 0 841.89 595.276]"));
+        // Layout1 一个视口 + Detail 一个可见视口，各自裁剪
+        assert_eq!(text.matches("W n").count(), 2);
+        assert_eq!(text.matches(" c\n").count(), 0);
+        assert!(text.contains("(Title) Tj"));
+
+        // Detail 视口中心 (70, 60)，1:10 比例：模型 x = ±50 → 图纸 65..75mm
+        assert!(text.contains(&format!(
+            "{} {} m\n{} {} l",
+            pdf::number(65.0 * MM_TO_PT),
+            pdf::number(60.0 * MM_TO_PT),
+            pdf::number(75.0 * MM_TO_PT),
+            pdf::number(60.0 * MM_TO_PT)
+        )));
+    }
 }
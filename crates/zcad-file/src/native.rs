@@ -28,9 +28,10 @@ use zcad_core::entity::Entity;
 use zcad_core::layer::Layer;
 use zcad_core::layout::{Layout, LayoutId, PaperSize, PaperOrientation, Viewport, ViewportId, ViewportStatus, SpaceType};
 use zcad_core::math::Point2;
-use zcad_core::dimstyle::DimStyle;
+use zcad_core::dimstyle::{DimStyle, DimStyleManager};
 use zcad_core::units::Unit;
 use zcad_core::block::Block;
+use zcad_core::textstyle::{TextStyle, TextStyleManager};
 
 /// 文件魔数 "ZCAD"
 const MAGIC: &[u8; 4] = b"ZCAD";
@@ -38,8 +39,13 @@ const MAGIC: &[u8; 4] = b"ZCAD";
 /// 当前文件格式版本
 /// - v1: 基础实体和图层
 /// - v2: 添加视图
-/// - v3: 添加布局、视口、标注样式、块定义、单位设置
-const FORMAT_VERSION: u32 = 3;
+/// - v3: 添加布局、视口、标注样式、块定义、单位设置（块和标注样式字段始终为空）
+/// - v4: 块定义表和标注样式随文档保存
+/// - v5: 文字样式表随文档保存
+const FORMAT_VERSION: u32 = 5;
+
+/// 可读取的最早格式版本
+const MIN_FORMAT_VERSION: u32 = 1;
 
 /// Zstd 压缩级别（1-22，3 是默认值，平衡速度和压缩比）
 const COMPRESSION_LEVEL: i32 = 3;
@@ -281,6 +287,75 @@ struct FileContent {
     /// 绘图单位
     #[serde(default = "default_unit")]
     drawing_unit: String,
+
+    // === v5 新增字段 ===
+
+    /// 文字样式
+    #[serde(default)]
+    text_styles: Vec<TextStyle>,
+
+    /// 当前文字样式名称
+    #[serde(default)]
+    current_text_style: String,
+}
+
+/// v1 文件内容：元数据、图层和实体
+#[derive(Debug, Deserialize)]
+struct FileContentV1 {
+    metadata: DocumentMetadata,
+    layers: Vec<Layer>,
+    entities: Vec<Entity>,
+}
+
+/// v2 文件内容：增加保存的视图
+#[derive(Debug, Deserialize)]
+struct FileContentV2 {
+    metadata: DocumentMetadata,
+    layers: Vec<Layer>,
+    entities: Vec<Entity>,
+    views: Vec<SavedView>,
+}
+
+impl From<FileContentV1> for FileContentV2 {
+    fn from(v1: FileContentV1) -> Self {
+        Self {
+            metadata: v1.metadata,
+            layers: v1.layers,
+            entities: v1.entities,
+            views: Vec::new(),
+        }
+    }
+}
+
+impl From<FileContentV2> for FileContent {
+    fn from(v2: FileContentV2) -> Self {
+        Self {
+            metadata: v2.metadata,
+            layers: v2.layers,
+            entities: v2.entities,
+            views: v2.views,
+            layouts: Vec::new(),
+            current_space: default_space_type(),
+            blocks: Vec::new(),
+            dim_styles: Vec::new(),
+            current_dim_style: "Standard".to_string(),
+            drawing_unit: default_unit(),
+            text_styles: Vec::new(),
+            current_text_style: "Standard".to_string(),
+        }
+    }
+}
+
+/// 按文件版本反序列化并迁移到当前格式
+///
+/// v3 与当前格式结构相同，只是块和标注样式为空，加载时使用默认标注样式
+fn read_content(version: u32, data: &[u8]) -> Result<FileContent, FileError> {
+    let content = match version {
+        1 => FileContentV2::from(rmp_serde::from_slice::<FileContentV1>(data)?).into(),
+        2 => rmp_serde::from_slice::<FileContentV2>(data)?.into(),
+        _ => rmp_serde::from_slice::<FileContent>(data)?,
+    };
+    Ok(content)
 }
 
 fn default_space_type() -> SerializableSpaceType {
@@ -306,9 +381,16 @@ pub fn save(document: &Document, path: &Path) -> Result<(), FileError> {
         SpaceType::Paper(id) => SerializableSpaceType::Paper(id.0),
     };
     
+    // 块定义按名称排序，保证输出稳定
+    let mut blocks: Vec<Block> = document.blocks.iter().cloned().collect();
+    blocks.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut metadata = document.metadata.clone();
+    metadata.format_version = FORMAT_VERSION;
+
     // 收集文件内容
     let content = FileContent {
-        metadata: document.metadata.clone(),
+        metadata,
         layers: document.layers.all_layers().iter().cloned().collect(),
         entities: document.all_entities().cloned().collect(),
         views: document.views.clone(),
@@ -316,10 +398,14 @@ pub fn save(document: &Document, path: &Path) -> Result<(), FileError> {
         // v3 新增
         layouts,
         current_space,
-        blocks: Vec::new(), // TODO: 从 document 获取块定义
-        dim_styles: Vec::new(), // TODO: 从 document 获取标注样式
-        current_dim_style: "Standard".to_string(),
+        blocks,
+        dim_styles: document.dim_styles.styles().to_vec(),
+        current_dim_style: document.dim_styles.current_style().name.clone(),
         drawing_unit: document.metadata.units.clone(),
+
+        // v5 新增
+        text_styles: document.text_styles.styles().to_vec(),
+        current_text_style: document.text_styles.current_style().name.clone(),
     };
 
     // 序列化为 MessagePack
@@ -341,10 +427,11 @@ pub fn save(document: &Document, path: &Path) -> Result<(), FileError> {
     writer.flush()?;
 
     tracing::info!(
-        "Saved {} entities, {} layers, {} layouts to {} ({} bytes compressed)",
+        "Saved {} entities, {} layers, {} layouts, {} blocks to {} ({} bytes compressed)",
         content.entities.len(),
         content.layers.len(),
         content.layouts.len(),
+        content.blocks.len(),
         path.display(),
         compressed_data.len()
     );
@@ -367,6 +454,12 @@ pub fn load(path: &Path) -> Result<Document, FileError> {
             header.version, FORMAT_VERSION
         )));
     }
+    if header.version < MIN_FORMAT_VERSION {
+        return Err(FileError::UnsupportedVersion(format!(
+            "File version {} is not supported",
+            header.version
+        )));
+    }
 
     // 读取压缩数据
     let mut compressed_data = vec![0u8; header.compressed_size as usize];
@@ -375,8 +468,8 @@ pub fn load(path: &Path) -> Result<Document, FileError> {
     // 解压缩
     let msgpack_data = zstd::decode_all(compressed_data.as_slice())?;
 
-    // 反序列化
-    let content: FileContent = rmp_serde::from_slice(&msgpack_data)?;
+    // 反序列化（旧版本迁移到当前格式）
+    let content = read_content(header.version, &msgpack_data)?;
 
     // 重建文档
     let mut document = Document::new();
@@ -427,14 +520,22 @@ pub fn load(path: &Path) -> Result<Document, FileError> {
         }
     }
 
-    // 重建空间索引
-    document.rebuild_spatial_index();
+    // === v4: 加载块定义和标注样式 ===
+    for block in content.blocks {
+        document.blocks.add_block(block);
+    }
+    document.dim_styles = DimStyleManager::from_styles(content.dim_styles, &content.current_dim_style);
+
+    // === v5: 加载文字样式，刷新文字字体并重建空间索引 ===
+    document.text_styles = TextStyleManager::from_styles(content.text_styles, &content.current_text_style);
+    document.update_text_fonts();
 
     tracing::info!(
-        "Loaded {} entities, {} layers, {} layouts from {}",
+        "Loaded {} entities, {} layers, {} layouts, {} blocks from {}",
         document.entity_count(),
         document.layers.count(),
         document.layout_manager.layouts().len(),
+        document.blocks.block_count(),
         path.display()
     );
 
@@ -480,6 +581,178 @@ mod tests {
         std::fs::remove_file(&file_path).ok();
     }
 
+    #[test]
+    fn test_blocks_and_dim_styles_roundtrip() {
+        let file_path = std::env::temp_dir().join("test_blocks_dimstyles.zcad");
+
+        let mut doc = Document::new();
+        let mut block = Block::new("Bolt", Point2::new(5.0, 5.0)).with_description("M10");
+        block.add_entity(Entity::new(Geometry::Line(Line::new(
+            Point2::new(0.0, 0.0),
+            Point2::new(10.0, 10.0),
+        ))));
+        doc.blocks.add_block(block);
+
+        let mut style = DimStyle::new("Site");
+        style.text_height = 7.5;
+        doc.dim_styles.add_style(style);
+        doc.dim_styles.set_current_style("Site");
+
+        save(&doc, &file_path).expect("Failed to save");
+        let loaded = load(&file_path).expect("Failed to load");
+
+        let block = loaded.blocks.get_block("Bolt").expect("block missing");
+        assert_eq!(block.base_point, Point2::new(5.0, 5.0));
+        assert_eq!(block.description, "M10");
+        assert_eq!(block.entity_count(), 1);
+
+        assert_eq!(loaded.dim_styles.styles().len(), 5);
+        assert_eq!(loaded.dim_styles.current_style().name, "Site");
+        assert_eq!(loaded.dim_styles.current_style().text_height, 7.5);
+        assert_eq!(loaded.metadata.format_version, FORMAT_VERSION);
+
+        std::fs::remove_file(&file_path).ok();
+    }
+
+    #[test]
+    fn test_text_styles_roundtrip() {
+        use zcad_core::geometry::Text;
+
+        let file_path = std::env::temp_dir().join("test_text_styles.zcad");
+
+        let mut doc = Document::new();
+        let mut style = TextStyle::new("Title").with_font("romans.shx").with_width_factor(0.8);
+        style.backwards = true;
+        doc.text_styles.set_style(style.clone());
+        doc.text_styles.set_current_style("Title");
+
+        let mut text = Text::new(Point2::origin(), "ZCAD", 5.0);
+        style.apply_to_text(&mut text);
+        let id = doc.add_entity(Entity::new(Geometry::Text(text)));
+
+        save(&doc, &file_path).expect("Failed to save");
+        let loaded = load(&file_path).expect("Failed to load");
+
+        assert_eq!(loaded.text_styles.style_names(), ["Standard", "Title"]);
+        assert_eq!(loaded.text_styles.current_style(), &style);
+        let Geometry::Text(text) = &loaded.get_entity(&id).unwrap().geometry else {
+            panic!("expected text");
+        };
+        assert_eq!(text.style_name, "Title");
+        assert_eq!(text.font.as_deref(), Some("romans"));
+        assert_eq!(text.width_factor, 0.8);
+        assert!(text.backwards);
+
+        std::fs::remove_file(&file_path).ok();
+    }
+
+    #[test]
+    fn test_associative_dimension_roundtrip() {
+        use zcad_core::associative::AssociativePoint;
+        use zcad_core::geometry::Dimension;
+        use zcad_core::snap::SnapType;
+
+        let file_path = std::env::temp_dir().join("test_associative_dim.zcad");
+
+        let mut doc = Document::new();
+        let line = Entity::new(Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0))));
+        let line_id = doc.add_entity(line);
+        let dim = Dimension::new(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0), Point2::new(5.0, 5.0))
+            .with_associations(
+                Some(AssociativePoint::new(line_id, SnapType::Endpoint, 0)),
+                Some(AssociativePoint::new(line_id, SnapType::Endpoint, 1)),
+            );
+        let dim_id = doc.add_entity(Entity::new(Geometry::Dimension(dim)));
+
+        save(&doc, &file_path).expect("Failed to save");
+        let mut loaded = load(&file_path).expect("Failed to load");
+        std::fs::remove_file(&file_path).ok();
+
+        // 移动直线后标注跟随
+        let mut line = loaded.get_entity(&line_id).unwrap().clone();
+        line.geometry = Geometry::Line(Line::new(Point2::new(0.0, 10.0), Point2::new(30.0, 10.0)));
+        loaded.update_entity(&line_id, line);
+        let Geometry::Dimension(dim) = &loaded.get_entity(&dim_id).unwrap().geometry else {
+            panic!("expected dimension");
+        };
+        assert_eq!(dim.definition_point2, Point2::new(30.0, 10.0));
+        assert!((dim.measurement() - 30.0).abs() < 1e-9);
+        assert!((dim.line_location - Point2::new(15.0, 15.0)).norm() < 1e-9);
+
+        // 删除直线后标注保留，但不再关联
+        loaded.remove_entity(&line_id);
+        let Geometry::Dimension(dim) = &loaded.get_entity(&dim_id).unwrap().geometry else {
+            panic!("expected dimension");
+        };
+        assert!(!dim.is_associative());
+        assert_eq!(dim.definition_point1, Point2::new(0.0, 10.0));
+    }
+
+    /// 以指定版本号写入原始文件内容
+    fn write_raw(path: &Path, version: u32, content: &impl Serialize) {
+        let data = zstd::encode_all(rmp_serde::to_vec(content).unwrap().as_slice(), COMPRESSION_LEVEL).unwrap();
+        let mut header = FileHeader::new(data.len() as u32);
+        header.version = version;
+        let mut file = File::create(path).unwrap();
+        header.write(&mut file).unwrap();
+        file.write_all(&data).unwrap();
+    }
+
+    #[test]
+    fn test_migrate_old_versions() {
+        let file_path = std::env::temp_dir().join("test_migrate.zcad");
+        let metadata = DocumentMetadata {
+            title: "Old".to_string(),
+            ..Default::default()
+        };
+        let layers = zcad_core::layer::LayerManager::new().all_layers().to_vec();
+        let entities = vec![Entity::new(Geometry::Line(Line::new(
+            Point2::new(0.0, 0.0),
+            Point2::new(1.0, 0.0),
+        )))];
+        let view = SavedView {
+            name: "Top".to_string(),
+            center_x: 1.0,
+            center_y: 2.0,
+            zoom: 3.0,
+        };
+
+        // v1：元数据、图层、实体
+        write_raw(&file_path, 1, &(&metadata, &layers, &entities));
+        let doc = load(&file_path).expect("Failed to load v1");
+        assert_eq!(doc.metadata.title, "Old");
+        assert_eq!(doc.entity_count(), 1);
+        assert!(doc.views.is_empty());
+        assert_eq!(doc.dim_styles.current_style().name, "Standard");
+        assert_eq!(doc.blocks.block_count(), 0);
+
+        // v2：增加视图
+        write_raw(&file_path, 2, &(&metadata, &layers, &entities, vec![view.clone()]));
+        let doc = load(&file_path).expect("Failed to load v2");
+        assert_eq!(doc.views.len(), 1);
+        assert_eq!(doc.views[0].name, "Top");
+
+        // v3：块与标注样式为空，使用默认样式
+        let content = FileContent::from(FileContentV2 {
+            metadata: metadata.clone(),
+            layers: layers.clone(),
+            entities: entities.clone(),
+            views: vec![view],
+        });
+        write_raw(&file_path, 3, &content);
+        let doc = load(&file_path).expect("Failed to load v3");
+        assert_eq!(doc.entity_count(), 1);
+        assert_eq!(doc.dim_styles.style_names().len(), 4);
+
+        // 版本 0 与更新的版本不支持
+        write_raw(&file_path, 0, &content);
+        assert!(matches!(load(&file_path), Err(FileError::UnsupportedVersion(_))));
+        write_raw(&file_path, FORMAT_VERSION + 1, &content);
+        assert!(matches!(load(&file_path), Err(FileError::UnsupportedVersion(_))));
+
+        std::fs::remove_file(&file_path).ok();
+    }
+
     #[test]
     fn test_invalid_magic() {
         let temp_dir = std::env::temp_dir();
@@ -0,0 +1,373 @@
+//! PDF 1.7 底层写入器
+//!
+//! 不依赖外部库的最小 PDF 生成器，负责：
+//! - 间接对象表、交叉引用表（xref）与文件尾（trailer）
+//! - 内容流操作符（路径、颜色、线型、文本、可选内容标记）
+//! - 字符串与名称对象的编码
+//!
+//! 页面、字体与图层（可选内容组）的组织由 [`crate::export::PdfExporter`] 完成。
+
+use std::fmt::{self, Write as _};
+
+/// 毫米到 PDF 点（1/72 英寸）的换算系数
+pub const MM_TO_PT: f64 = 72.0 / 25.4;
+
+/// 间接对象引用
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ObjectRef(pub u32);
+
+impl fmt::Display for ObjectRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} 0 R", self.0)
+    }
+}
+
+/// PDF 文档写入器
+///
+/// 对象按编号顺序保存，`finish` 时统一写出并生成交叉引用表。
+/// 可以先用 [`PdfWriter::reserve`] 预留编号，稍后再填充内容（用于相互引用的对象，如页面树）。
+#[derive(Debug, Default)]
+pub struct PdfWriter {
+    objects: Vec<Option<Vec<u8>>>,
+}
+
+impl PdfWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 预留一个对象编号
+    pub fn reserve(&mut self) -> ObjectRef {
+        self.objects.push(None);
+        ObjectRef(self.objects.len() as u32)
+    }
+
+    /// 设置已预留对象的内容
+    pub fn set(&mut self, object: ObjectRef, body: impl Into<Vec<u8>>) {
+        self.objects[object.0 as usize - 1] = Some(body.into());
+    }
+
+    /// 添加一个对象
+    pub fn add(&mut self, body: impl Into<Vec<u8>>) -> ObjectRef {
+        let object = self.reserve();
+        self.set(object, body);
+        object
+    }
+
+    /// 添加一个流对象
+    ///
+    /// `dict` 为流字典中除 `/Length` 以外的条目（可以为空）
+    pub fn add_stream(&mut self, dict: &str, data: &[u8]) -> ObjectRef {
+        let mut body = Vec::with_capacity(data.len() + 64);
+        if dict.is_empty() {
+            body.extend_from_slice(format!("<< /Length {} >>\nstream\n", data.len()).as_bytes());
+        } else {
+            body.extend_from_slice(format!("<< {} /Length {} >>\nstream\n", dict, data.len()).as_bytes());
+        }
+        body.extend_from_slice(data);
+        body.extend_from_slice(b"\nendstream");
+        self.add(body)
+    }
+
+    /// 写出完整的 PDF 文件
+    ///
+    /// 未填充的预留对象写为 `null`
+    pub fn finish(self, catalog: ObjectRef, info: Option<ObjectRef>) -> Vec<u8> {
+        let mut out = Vec::new();
+        // 第二行的高位字节提示这是二进制文件
+        out.extend_from_slice(b"%PDF-1.7\n%\xE2\xE3\xCF\xD3\n");
+
+        let mut offsets = Vec::with_capacity(self.objects.len());
+        for (index, body) in self.objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend_from_slice(format!("{} 0 obj\n", index + 1).as_bytes());
+            match body {
+                Some(body) => out.extend_from_slice(body),
+                None => out.extend_from_slice(b"null"),
+            }
+            out.extend_from_slice(b"\nendobj\n");
+        }
+
+        // 交叉引用表：每条记录固定 20 字节
+        let xref_offset = out.len();
+        let mut xref = format!("xref\n0 {}\n0000000000 65535 f \n", offsets.len() + 1);
+        for offset in &offsets {
+            let _ = writeln!(xref, "{:010} 00000 n ", offset);
+        }
+        out.extend_from_slice(xref.as_bytes());
+
+        let mut trailer = format!("trailer\n<< /Size {} /Root {}", offsets.len() + 1, catalog);
+        if let Some(info) = info {
+            let _ = write!(trailer, " /Info {}", info);
+        }
+        let _ = write!(trailer, " >>\nstartxref\n{}\n%%EOF\n", xref_offset);
+        out.extend_from_slice(trailer.as_bytes());
+
+        out
+    }
+}
+
+/// 页面内容流构建器
+///
+/// 坐标单位为 PDF 点，原点位于页面左下角
+#[derive(Debug, Default)]
+pub struct ContentStream {
+    buf: String,
+}
+
+impl ContentStream {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn op(&mut self, operands: &[f64], operator: &str) {
+        for value in operands {
+            self.buf.push_str(&number(*value));
+            self.buf.push(' ');
+        }
+        self.buf.push_str(operator);
+        self.buf.push('\n');
+    }
+
+    /// 保存图形状态（q）
+    pub fn save_state(&mut self) {
+        self.op(&[], "q");
+    }
+
+    /// 恢复图形状态（Q）
+    pub fn restore_state(&mut self) {
+        self.op(&[], "Q");
+    }
+
+    /// 线宽（w）
+    pub fn set_line_width(&mut self, width: f64) {
+        self.op(&[width], "w");
+    }
+
+    /// 线端样式（J）：0 = 平头，1 = 圆头，2 = 方头
+    pub fn set_line_cap(&mut self, cap: u8) {
+        self.op(&[cap as f64], "J");
+    }
+
+    /// 线连接样式（j）：0 = 尖角，1 = 圆角，2 = 斜角
+    pub fn set_line_join(&mut self, join: u8) {
+        self.op(&[join as f64], "j");
+    }
+
+    /// 虚线模式（d），空数组表示实线
+    pub fn set_dash(&mut self, dashes: &[f64], phase: f64) {
+        let items: Vec<String> = dashes.iter().map(|d| number(*d)).collect();
+        let _ = writeln!(self.buf, "[{}] {} d", items.join(" "), number(phase));
+    }
+
+    /// 描边颜色（RG）
+    pub fn set_stroke_rgb(&mut self, r: u8, g: u8, b: u8) {
+        self.op(&[r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0], "RG");
+    }
+
+    /// 填充颜色（rg）
+    pub fn set_fill_rgb(&mut self, r: u8, g: u8, b: u8) {
+        self.op(&[r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0], "rg");
+    }
+
+    pub fn move_to(&mut self, x: f64, y: f64) {
+        self.op(&[x, y], "m");
+    }
+
+    pub fn line_to(&mut self, x: f64, y: f64) {
+        self.op(&[x, y], "l");
+    }
+
+    /// 三次贝塞尔曲线（c）
+    pub fn curve_to(&mut self, x1: f64, y1: f64, x2: f64, y2: f64, x3: f64, y3: f64) {
+        self.op(&[x1, y1, x2, y2, x3, y3], "c");
+    }
+
+    /// 闭合当前子路径（h）
+    pub fn close_path(&mut self) {
+        self.op(&[], "h");
+    }
+
+    /// 矩形子路径（re）
+    pub fn rect(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        self.op(&[x, y, width, height], "re");
+    }
+
+    /// 描边（S）
+    pub fn stroke(&mut self) {
+        self.op(&[], "S");
+    }
+
+    /// 奇偶规则填充（f*）
+    pub fn fill_even_odd(&mut self) {
+        self.op(&[], "f*");
+    }
+
+    /// 以当前路径为裁剪区域（W n）
+    pub fn clip(&mut self) {
+        self.op(&[], "W n");
+    }
+
+    /// 开始可选内容标记（/OC /name BDC）
+    pub fn begin_optional_content(&mut self, property: &str) {
+        let _ = writeln!(self.buf, "/OC {} BDC", name(property));
+    }
+
+    /// 结束标记内容（EMC）
+    pub fn end_marked_content(&mut self) {
+        self.op(&[], "EMC");
+    }
+
+    /// 绘制一段文本
+    ///
+    /// `matrix` 为文本矩阵 `[a b c d e f]`，`encoded` 为已编码的字符串对象
+    pub fn show_text(&mut self, font: &str, size: f64, matrix: [f64; 6], encoded: &str) {
+        self.op(&[], "BT");
+        let _ = writeln!(self.buf, "{} {} Tf", name(font), number(size));
+        self.op(&matrix, "Tm");
+        let _ = writeln!(self.buf, "{} Tj", encoded);
+        self.op(&[], "ET");
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.buf.into_bytes()
+    }
+}
+
+/// 格式化数字：最多保留 3 位小数，去掉多余的零
+pub fn number(value: f64) -> String {
+    if !value.is_finite() {
+        return "0".to_string();
+    }
+    let mut s = format!("{:.3}", value);
+    if s.contains('.') {
+        while s.ends_with('0') {
+            s.pop();
+        }
+        if s.ends_with('.') {
+            s.pop();
+        }
+    }
+    if s == "-0" {
+        s = "0".to_string();
+    }
+    s
+}
+
+/// 名称对象（`/Name`），对分隔符和非打印字符使用 `#xx` 转义
+pub fn name(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 1);
+    out.push('/');
+    for byte in s.bytes() {
+        let regular = (b'!'..=b'~').contains(&byte)
+            && !matches!(byte, b'#' | b'/' | b'(' | b')' | b'<' | b'>' | b'[' | b']' | b'{' | b'}' | b'%');
+        if regular {
+            out.push(byte as char);
+        } else {
+            let _ = write!(out, "#{:02X}", byte);
+        }
+    }
+    out
+}
+
+/// 字面量字符串（`(...)`），按 Latin-1 编码，无法编码的字符替换为 `?`
+///
+/// 适用于使用 WinAnsiEncoding 的基本字体
+pub fn literal_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('(');
+    for c in s.chars() {
+        let code = c as u32;
+        match c {
+            '(' | ')' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            ' '..='~' => out.push(c),
+            _ if code < 0x100 => {
+                let _ = write!(out, "\\{:03o}", code);
+            }
+            _ => out.push('?'),
+        }
+    }
+    out.push(')');
+    out
+}
+
+/// UTF-16BE 十六进制字符串（`<...>`）
+///
+/// `bom` 为 true 时添加字节序标记，用于文本字符串；CID 字体的显示字符串不需要
+pub fn utf16_hex_string(s: &str, bom: bool) -> String {
+    let mut out = String::from("<");
+    if bom {
+        out.push_str("FEFF");
+    }
+    for unit in s.encode_utf16() {
+        let _ = write!(out, "{:04X}", unit);
+    }
+    out.push('>');
+    out
+}
+
+/// 文本字符串（文档信息、图层名等）：ASCII 直接使用字面量，否则使用 UTF-16BE
+pub fn text_string(s: &str) -> String {
+    if s.is_ascii() {
+        literal_string(s)
+    } else {
+        utf16_hex_string(s, true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_number_format() {
+        assert_eq!(number(1.0), "1");
+        assert_eq!(number(0.5), "0.5");
+        assert_eq!(number(2.83465), "2.835");
+        assert_eq!(number(-0.0001), "0");
+        assert_eq!(number(f64::NAN), "0");
+    }
+
+    #[test]
+    fn test_string_encoding() {
+        assert_eq!(literal_string("a(b)\\"), "(a\\(b\\)\\\\)");
+        assert_eq!(literal_string("Ø中"), "(\\330?)");
+        assert_eq!(utf16_hex_string("墙体", true), "<FEFF58994F53>");
+        assert_eq!(name("Layer 1/A"), "/Layer#201#2FA");
+    }
+
+    #[test]
+    fn test_writer_xref_offsets() {
+        let mut writer = PdfWriter::new();
+        let catalog = writer.reserve();
+        let pages = writer.add("<< /Type /Pages /Kids [] /Count 0 >>");
+        writer.set(catalog, format!("<< /Type /Catalog /Pages {} >>", pages));
+        let data = writer.finish(catalog, None);
+        assert!(data.starts_with(b"%PDF-1.7"));
+        assert!(data.ends_with(b"%%EOF\n"));
+
+        // 文件头含二进制字节，按字节偏移解析
+        let find = |needle: &[u8]| data.windows(needle.len()).rposition(|w| w == needle).unwrap();
+        let tail = std::str::from_utf8(&data[find(b"startxref\n")..]).unwrap();
+        let start: usize = tail.lines().nth(1).unwrap().parse().unwrap();
+        assert!(data[start..].starts_with(b"xref"));
+
+        // 每个对象的偏移量都指向对应的 "n 0 obj"
+        let table = std::str::from_utf8(&data[start..]).unwrap();
+        let entries: Vec<usize> = table
+            .lines()
+            .skip(3)
+            .take(2)
+            .map(|line| line[..10].parse().unwrap())
+            .collect();
+        assert!(data[entries[0]..].starts_with(b"1 0 obj"));
+        assert!(data[entries[1]..].starts_with(b"2 0 obj"));
+    }
+}
@@ -0,0 +1,860 @@
+//! 光栅图像导出
+//!
+//! 纯 CPU 软件光栅化器，将实体绘制为 PNG/JPEG 图像，不依赖 GPU，
+//! 可用于无显卡环境生成缩略图和视觉回归测试图像。
+//!
+//! - 曲线使用与渲染器相同的 [`zcad_core::tessellation`] 细分结果
+//! - 页面变换、打印样式（ByLayer 颜色/线型/线宽）与 PDF 导出一致
+//! - 覆盖率按有向面积累积计算，线条、填充和文字均为抗锯齿输出
+//! - 文字使用 [`zcad_core::font`] 排版的字形：TrueType 轮廓填充，SHX 笔画描边
+
+use crate::error::FileError;
+use crate::export::{
+    arrow_triangle, calculate_bounds, calculate_transform, group_by_layer, PageSetup, PlotStyle,
+};
+use zcad_core::dimstyle::DimStyle;
+use zcad_core::entity::Entity;
+use zcad_core::geometry::{ArrowType, Geometry, Hatch, HatchPatternType, Leader, Text};
+use zcad_core::hatch_pattern;
+use zcad_core::layer::{Layer, LayerManager};
+use zcad_core::math::{Point2, Vector2, EPSILON};
+use zcad_core::properties::Color;
+use zcad_core::tessellation;
+
+const MM_PER_INCH: f64 = 25.4;
+
+/// 图像单边最大像素数，防止 DPI 过大耗尽内存
+const MAX_DIMENSION: u32 = 16384;
+
+/// RGBA8 图像，按行从上到下存储
+#[derive(Debug, Clone)]
+pub struct RasterImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+    /// 分辨率（像素/英寸）
+    pub dpi: f64,
+}
+
+impl RasterImage {
+    /// 创建以背景色填充的图像
+    pub fn new(width: u32, height: u32, background: Color, dpi: f64) -> Self {
+        let pixels = [background.r, background.g, background.b, background.a]
+            .repeat(width as usize * height as usize);
+        Self {
+            width,
+            height,
+            pixels,
+            dpi,
+        }
+    }
+
+    /// 获取像素（RGBA）
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        [self.pixels[i], self.pixels[i + 1], self.pixels[i + 2], self.pixels[i + 3]]
+    }
+
+    /// 编码为 PNG（写入 pHYs 分辨率）
+    pub fn to_png(&self) -> Result<Vec<u8>, FileError> {
+        let mut data = Vec::new();
+        let mut encoder = png::Encoder::new(&mut data, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let pixels_per_meter = (self.dpi / MM_PER_INCH * 1000.0).round() as u32;
+        encoder.set_pixel_dims(Some(png::PixelDimensions {
+            xppu: pixels_per_meter,
+            yppu: pixels_per_meter,
+            unit: png::Unit::Meter,
+        }));
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&self.pixels))
+            .map_err(|e| FileError::InvalidFormat(format!("PNG encode error: {}", e)))?;
+        Ok(data)
+    }
+
+    /// 编码为 JPEG，透明像素合成到白色背景上
+    pub fn to_jpeg(&self, quality: u8) -> Result<Vec<u8>, FileError> {
+        let rgb: Vec<u8> = self
+            .pixels
+            .chunks_exact(4)
+            .flat_map(|p| {
+                let alpha = p[3] as u32;
+                let over_white = |c: u8| ((c as u32 * alpha + 255 * (255 - alpha) + 127) / 255) as u8;
+                [over_white(p[0]), over_white(p[1]), over_white(p[2])]
+            })
+            .collect();
+
+        let mut data = Vec::new();
+        let mut encoder = jpeg_encoder::Encoder::new(&mut data, quality.clamp(1, 100));
+        encoder.set_density(jpeg_encoder::PixelDensity::dpi(self.dpi.round().clamp(1.0, u16::MAX as f64) as u16));
+        encoder
+            .encode(&rgb, self.width as u16, self.height as u16, jpeg_encoder::ColorType::Rgb)
+            .map_err(|e| FileError::InvalidFormat(format!("JPEG encode error: {}", e)))?;
+        Ok(data)
+    }
+
+    /// 以给定覆盖率（0..=1）将颜色按 source-over 合成到像素上
+    fn blend(&mut self, x: usize, y: usize, color: Color, coverage: f32) {
+        let i = (y * self.width as usize + x) * 4;
+        let dst = &mut self.pixels[i..i + 4];
+        let src_alpha = coverage.clamp(0.0, 1.0);
+        let dst_alpha = dst[3] as f32 / 255.0;
+        let out_alpha = src_alpha + dst_alpha * (1.0 - src_alpha);
+        if out_alpha <= 0.0 {
+            return;
+        }
+        for (channel, src) in dst.iter_mut().zip([color.r, color.g, color.b]) {
+            let value = (src as f32 * src_alpha + *channel as f32 * dst_alpha * (1.0 - src_alpha)) / out_alpha;
+            *channel = value.round() as u8;
+        }
+        dst[3] = (out_alpha * 255.0).round() as u8;
+    }
+}
+
+/// 光栅图像导出器
+///
+/// 以整张纸张为画布，按 DPI 决定像素尺寸；内容按 `PageSetup` 的打印范围
+/// 和比例放置到可打印区域并裁剪，与 PDF 导出的版面一致。
+pub struct RasterExporter {
+    page_setup: PageSetup,
+    dpi: f64,
+    background: Color,
+    jpeg_quality: u8,
+    layers: Option<LayerManager>,
+}
+
+impl RasterExporter {
+    pub fn new(page_setup: PageSetup) -> Self {
+        Self {
+            page_setup,
+            dpi: 150.0,
+            background: Color::WHITE,
+            jpeg_quality: 90,
+            layers: None,
+        }
+    }
+
+    /// 设置分辨率（像素/英寸），默认 150
+    pub fn with_dpi(mut self, dpi: f64) -> Self {
+        self.dpi = dpi;
+        self
+    }
+
+    /// 设置背景色，默认白色
+    ///
+    /// 与背景同色的白色/黑色实体会取反显示
+    pub fn with_background(mut self, background: Color) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// 设置 JPEG 质量（1-100），默认 90
+    pub fn with_jpeg_quality(mut self, quality: u8) -> Self {
+        self.jpeg_quality = quality;
+        self
+    }
+
+    /// 设置图层表
+    ///
+    /// 用于解析 ByLayer 样式，并跳过关闭、冻结和不可打印的图层
+    pub fn with_layers(mut self, layers: LayerManager) -> Self {
+        self.layers = Some(layers);
+        self
+    }
+
+    /// 渲染为图像
+    pub fn render(&self, entities: &[Entity]) -> Result<RasterImage, FileError> {
+        if !self.dpi.is_finite() || self.dpi <= 0.0 {
+            return Err(FileError::InvalidFormat(format!("Invalid DPI {}", self.dpi)));
+        }
+        let (page_width, page_height) = self.page_setup.paper_dimensions();
+        let (print_width, print_height) = self.page_setup.printable_size();
+        if page_width <= 0.0 || page_height <= 0.0 || print_width <= 0.0 || print_height <= 0.0 {
+            return Err(FileError::InvalidFormat(format!(
+                "Invalid page size {:.1}x{:.1}mm with margins {:?}",
+                page_width, page_height, self.page_setup.margins
+            )));
+        }
+
+        let px_per_mm = self.dpi / MM_PER_INCH;
+        let width = (page_width * px_per_mm).round();
+        let height = (page_height * px_per_mm).round();
+        if width < 1.0 || height < 1.0 || width > MAX_DIMENSION as f64 || height > MAX_DIMENSION as f64 {
+            return Err(FileError::InvalidFormat(format!(
+                "Image size {}x{} px at {} DPI is out of range (max {} px)",
+                width, height, self.dpi, MAX_DIMENSION
+            )));
+        }
+        let (width, height) = (width as u32, height as u32);
+
+        let (top, right, bottom, left) = self.page_setup.margins;
+        let bounds = calculate_bounds(&self.page_setup, entities);
+        let (scale, offset) = calculate_transform(&self.page_setup, &bounds, print_width, print_height);
+
+        let transform = PixelTransform {
+            scale,
+            offset: offset + Vector2::new(left, bottom),
+            px_per_mm,
+            height: height as f64,
+        };
+        // 可打印区域（像素）
+        let clip = ClipRect {
+            x0: (left * px_per_mm).floor().max(0.0) as usize,
+            y0: (top * px_per_mm).floor().max(0.0) as usize,
+            x1: ((page_width - right) * px_per_mm).ceil().min(width as f64) as usize,
+            y1: ((page_height - bottom) * px_per_mm).ceil().min(height as f64) as usize,
+        };
+
+        let mut canvas = Canvas {
+            image: RasterImage::new(width, height, self.background, self.dpi),
+            transform,
+            clip,
+            background: self.background,
+        };
+        for (layer, group) in group_by_layer(entities.iter(), self.layers.as_ref()) {
+            if let Some(layer) = layer {
+                if !layer.visible || layer.frozen || !layer.plottable {
+                    continue;
+                }
+            }
+            for entity in group {
+                canvas.draw_entity(entity, layer);
+            }
+        }
+        Ok(canvas.image)
+    }
+
+    /// 导出为 PNG
+    pub fn export_png(&self, entities: &[Entity]) -> Result<Vec<u8>, FileError> {
+        self.render(entities)?.to_png()
+    }
+
+    /// 导出为 JPEG
+    pub fn export_jpeg(&self, entities: &[Entity]) -> Result<Vec<u8>, FileError> {
+        self.render(entities)?.to_jpeg(self.jpeg_quality)
+    }
+
+    /// 导出到文件，按扩展名选择格式（`.jpg`/`.jpeg` 为 JPEG，其余为 PNG）
+    pub fn export_to_file(&self, entities: &[Entity], path: &std::path::Path) -> Result<(), FileError> {
+        let is_jpeg = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("jpg") || ext.eq_ignore_ascii_case("jpeg"));
+        let data = if is_jpeg {
+            self.export_jpeg(entities)?
+        } else {
+            self.export_png(entities)?
+        };
+        std::fs::write(path, data)?;
+        Ok(())
+    }
+}
+
+/// 绘图坐标 → 像素坐标（y 轴向下）
+struct PixelTransform {
+    /// 绘图单位 → 毫米
+    scale: f64,
+    /// 纸张上的偏移（毫米）
+    offset: Vector2,
+    px_per_mm: f64,
+    /// 图像高度（像素）
+    height: f64,
+}
+
+impl PixelTransform {
+    fn apply(&self, p: Point2) -> [f32; 2] {
+        let x = (p.x * self.scale + self.offset.x) * self.px_per_mm;
+        let y = self.height - (p.y * self.scale + self.offset.y) * self.px_per_mm;
+        [x as f32, y as f32]
+    }
+
+    /// 绘图单位长度 → 像素
+    fn length(&self, length: f64) -> f32 {
+        (length * self.scale * self.px_per_mm) as f32
+    }
+
+    /// 曲线细分的弦高容差（绘图单位）
+    fn tolerance(&self) -> f64 {
+        tessellation::screen_tolerance(1.0 / (self.scale * self.px_per_mm))
+    }
+}
+
+/// 像素裁剪矩形（右、下边界不含）
+struct ClipRect {
+    x0: usize,
+    y0: usize,
+    x1: usize,
+    y1: usize,
+}
+
+/// 有向边（像素坐标）
+type Edge = ([f32; 2], [f32; 2]);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FillRule {
+    NonZero,
+    EvenOdd,
+}
+
+struct Canvas {
+    image: RasterImage,
+    transform: PixelTransform,
+    clip: ClipRect,
+    background: Color,
+}
+
+impl Canvas {
+    fn draw_entity(&mut self, entity: &Entity, layer: Option<&Layer>) {
+        let style = PlotStyle::resolve(&entity.properties, layer, self.background);
+        let pen = Pen {
+            color: style.color,
+            // 细线至少一个像素宽
+            half_width: (style.line_weight_mm * self.transform.px_per_mm).max(1.0) as f32 / 2.0,
+            // 线型模式以图形单位定义，随打印比例缩放
+            dashes: style
+                .line_type
+                .pattern()
+                .iter()
+                .map(|d| {
+                    let length = self.transform.length(d.abs());
+                    if *d < 0.0 { -length } else { length }
+                })
+                .collect(),
+        };
+
+        match &entity.geometry {
+            Geometry::Point(point) => {
+                let radius = style.line_weight_mm.max(0.5) / 2.0 / self.transform.scale;
+                let dot = circle(point.position, radius, self.transform.tolerance());
+                self.fill_polygons(&[dot], FillRule::NonZero, pen.color);
+            }
+            Geometry::Text(text) => self.draw_text(text, &pen),
+            Geometry::Dimension(dim) => {
+                // 未经 Document::resolved_entities 分解的标注按默认样式分解
+                for geometry in dim.explode(&DimStyle::default()) {
+                    self.draw_entity(&Entity { geometry, ..entity.clone() }, layer);
+                }
+            }
+            Geometry::MText(mtext) => {
+                for (geometry, color) in mtext.explode_with_colors() {
+                    let mut part = Entity { geometry, ..entity.clone() };
+                    part.properties.color = color.unwrap_or(part.properties.color);
+                    self.draw_entity(&part, layer);
+                }
+            }
+            Geometry::Tolerance(tolerance) => {
+                for geometry in tolerance.explode() {
+                    self.draw_entity(&Entity { geometry, ..entity.clone() }, layer);
+                }
+            }
+            Geometry::Hatch(hatch) => self.draw_hatch(hatch, &pen),
+            Geometry::Leader(leader) => self.draw_leader(leader, &pen),
+            geometry => self.stroke(&tessellation::tessellate(geometry, self.transform.tolerance()), &pen),
+        }
+    }
+
+    fn draw_hatch(&mut self, hatch: &Hatch, pen: &Pen) {
+        let loops = tessellation::hatch_loops(hatch, self.transform.tolerance());
+        match hatch.pattern_type {
+            HatchPatternType::Solid => self.fill_polygons(&loops, FillRule::EvenOdd, pen.color),
+            HatchPatternType::Predefined(_) | HatchPatternType::Custom { .. } => {
+                let segments = hatch_pattern::pattern_segments(hatch);
+                if segments.is_empty() {
+                    // 图案未知时输出边界
+                    self.stroke(&loops, pen);
+                    return;
+                }
+
+                // 图案自带虚线，不再叠加线型；点画为线宽大小的圆点
+                let (dots, lines): (Vec<_>, Vec<_>) =
+                    segments.into_iter().partition(|[a, b]| (b - a).norm() < EPSILON);
+                let solid = Pen {
+                    color: pen.color,
+                    half_width: pen.half_width,
+                    dashes: Vec::new(),
+                };
+                let lines: Vec<Vec<Point2>> = lines.into_iter().map(|[a, b]| vec![a, b]).collect();
+                self.stroke(&lines, &solid);
+
+                let radius = pen.half_width as f64 / self.transform.px_per_mm / self.transform.scale;
+                let tolerance = self.transform.tolerance();
+                let dots: Vec<Vec<Point2>> = dots.into_iter().map(|[p, _]| circle(p, radius, tolerance)).collect();
+                self.fill_polygons(&dots, FillRule::NonZero, pen.color);
+            }
+        }
+    }
+
+    fn draw_leader(&mut self, leader: &Leader, pen: &Pen) {
+        self.stroke(std::slice::from_ref(&leader.vertices), pen);
+        if let (Some(tip), Some(direction)) = (leader.arrow_point(), leader.arrow_direction()) {
+            self.draw_arrow(tip, direction, leader.arrow_size, leader.arrow_type, pen);
+        }
+        if let Some(text) = leader.text_entity() {
+            self.draw_text(&text, pen);
+        }
+    }
+
+    fn draw_arrow(&mut self, tip: Point2, direction: Vector2, size: f64, arrow_type: ArrowType, pen: &Pen) {
+        if size <= EPSILON || direction.norm() < EPSILON {
+            return;
+        }
+        let [tip, left, right] = arrow_triangle(tip, direction, size);
+        match arrow_type {
+            ArrowType::None => {}
+            ArrowType::ClosedFilled => {
+                self.fill_polygons(&[vec![tip, left, right, tip]], FillRule::NonZero, pen.color)
+            }
+            ArrowType::ClosedBlank => self.stroke(&[vec![tip, left, right, tip]], pen),
+            ArrowType::Open => self.stroke(&[vec![left, tip, right]], pen),
+            ArrowType::Dot => {
+                let dot = circle(tip, size / 4.0, self.transform.tolerance());
+                self.fill_polygons(&[dot], FillRule::NonZero, pen.color)
+            }
+            ArrowType::Circle => self.stroke(&[circle(tip, size / 4.0, self.transform.tolerance())], pen),
+        }
+    }
+
+    fn draw_text(&mut self, text: &Text, pen: &Pen) {
+        if text.content.is_empty() || text.height <= 0.0 {
+            return;
+        }
+        // TrueType 字形按非零规则填充，SHX 笔画按画笔描边
+        let (filled, stroked): (Vec<_>, Vec<_>) = text.outlines().into_iter().partition(|g| g.filled);
+        let contours: Vec<Vec<Point2>> = filled.into_iter().flat_map(|glyph| glyph.contours).collect();
+        self.fill_polygons(&contours, FillRule::NonZero, pen.color);
+        let strokes: Vec<Vec<Point2>> = stroked.into_iter().flat_map(|glyph| glyph.contours).collect();
+        self.stroke(&strokes, pen);
+    }
+
+    /// 描边折线（绘图坐标）；线段为矩形，顶点处为圆形连接和端点
+    fn stroke(&mut self, paths: &[Vec<Point2>], pen: &Pen) {
+        let dashed = pen.dashes.iter().map(|d| d.abs()).sum::<f32>() >= 1.0;
+        let mut edges = Vec::new();
+        for path in paths {
+            let pixels: Vec<[f32; 2]> = path.iter().map(|p| self.transform.apply(*p)).collect();
+            if dashed {
+                for dash in dash_path(&pixels, &pen.dashes) {
+                    stroke_edges(&dash, pen.half_width, &mut edges);
+                }
+            } else {
+                stroke_edges(&pixels, pen.half_width, &mut edges);
+            }
+        }
+        self.fill_edges(&edges, FillRule::NonZero, pen.color);
+    }
+
+    /// 填充多边形（绘图坐标，每个多边形自动闭合）
+    fn fill_polygons(&mut self, polygons: &[Vec<Point2>], rule: FillRule, color: Color) {
+        let mut edges = Vec::new();
+        for polygon in polygons {
+            let pixels: Vec<[f32; 2]> = polygon.iter().map(|p| self.transform.apply(*p)).collect();
+            push_polygon(&pixels, &mut edges);
+        }
+        self.fill_edges(&edges, rule, color);
+    }
+
+    /// 按有向面积累积计算覆盖率并合成到图像
+    ///
+    /// 每条边在其经过的像素中累加带符号的面积贡献，按行前缀求和即得绕数，
+    /// 再按填充规则换算为覆盖率。
+    fn fill_edges(&mut self, edges: &[Edge], rule: FillRule, color: Color) {
+        let (mut min_x, mut min_y) = (f32::MAX, f32::MAX);
+        let (mut max_x, mut max_y) = (f32::MIN, f32::MIN);
+        for (p0, p1) in edges {
+            for p in [p0, p1] {
+                min_x = min_x.min(p[0]);
+                min_y = min_y.min(p[1]);
+                max_x = max_x.max(p[0]);
+                max_y = max_y.max(p[1]);
+            }
+        }
+        if !(min_x.is_finite() && min_y.is_finite() && max_x.is_finite() && max_y.is_finite()) {
+            return;
+        }
+
+        let x0 = (min_x.floor().max(0.0) as usize).max(self.clip.x0);
+        let y0 = (min_y.floor().max(0.0) as usize).max(self.clip.y0);
+        let x1 = (max_x.ceil().max(0.0) as usize).min(self.clip.x1);
+        let y1 = (max_y.ceil().max(0.0) as usize).min(self.clip.y1);
+        if x0 >= x1 || y0 >= y1 {
+            return;
+        }
+
+        let (width, height) = (x1 - x0, y1 - y0);
+        let stride = width + 2;
+        let mut area = vec![0.0f32; stride * height];
+        let origin = [x0 as f32, y0 as f32];
+        for (p0, p1) in edges {
+            let p0 = [p0[0] - origin[0], p0[1] - origin[1]];
+            let p1 = [p1[0] - origin[0], p1[1] - origin[1]];
+            for (a, b) in clip_edge_x(p0, p1, width as f32) {
+                accumulate(&mut area, stride, height, width as f32, a, b);
+            }
+        }
+
+        for row in 0..height {
+            let mut winding = 0.0f32;
+            for column in 0..width {
+                winding += area[row * stride + column];
+                let coverage = match rule {
+                    FillRule::NonZero => winding.abs().min(1.0),
+                    FillRule::EvenOdd => {
+                        let w = winding.abs() % 2.0;
+                        if w > 1.0 { 2.0 - w } else { w }
+                    }
+                };
+                if coverage > 0.5 / 255.0 {
+                    self.image.blend(x0 + column, y0 + row, color, coverage);
+                }
+            }
+        }
+    }
+}
+
+/// 解析后的画笔（像素单位）
+struct Pen {
+    color: Color,
+    half_width: f32,
+    /// 线型模式：正值为实线段，负值为间隔，零为点
+    dashes: Vec<f32>,
+}
+
+/// 闭合整圆（绘图坐标）
+fn circle(center: Point2, radius: f64, tolerance: f64) -> Vec<Point2> {
+    tessellation::arc_points(center, radius, 0.0, std::f64::consts::TAU, tolerance)
+}
+
+/// 多边形的边（自动闭合）
+fn push_polygon(points: &[[f32; 2]], edges: &mut Vec<Edge>) {
+    if points.len() < 2 {
+        return;
+    }
+    edges.extend(points.windows(2).map(|w| (w[0], w[1])));
+    let (first, last) = (points[0], points[points.len() - 1]);
+    if first != last {
+        edges.push((last, first));
+    }
+}
+
+/// 折线描边的轮廓：每段一个矩形，每个顶点一个圆，方向一致以便按非零规则合并
+fn stroke_edges(path: &[[f32; 2]], half_width: f32, edges: &mut Vec<Edge>) {
+    for w in path.windows(2) {
+        let (p0, p1) = (w[0], w[1]);
+        let (dx, dy) = (p1[0] - p0[0], p1[1] - p0[1]);
+        let length = dx.hypot(dy);
+        if length < 1e-4 {
+            continue;
+        }
+        let n = [-dy / length * half_width, dx / length * half_width];
+        push_polygon(
+            &[
+                [p0[0] + n[0], p0[1] + n[1]],
+                [p1[0] + n[0], p1[1] + n[1]],
+                [p1[0] - n[0], p1[1] - n[1]],
+                [p0[0] - n[0], p0[1] - n[1]],
+            ],
+            edges,
+        );
+    }
+
+    // 圆形连接与端点；与矩形同为顺时针
+    let segments = (half_width * 2.0).clamp(8.0, 64.0) as usize;
+    for p in path {
+        let ring: Vec<[f32; 2]> = (0..segments)
+            .map(|i| {
+                let angle = -(i as f32) * std::f32::consts::TAU / segments as f32;
+                [p[0] + half_width * angle.cos(), p[1] + half_width * angle.sin()]
+            })
+            .collect();
+        push_polygon(&ring, edges);
+    }
+}
+
+/// 按线型模式拆分折线（像素单位），零长度的点输出为单点路径
+fn dash_path(path: &[[f32; 2]], pattern: &[f32]) -> Vec<Vec<[f32; 2]>> {
+    let mut dashes = Vec::new();
+    let Some(&start) = path.first() else {
+        return dashes;
+    };
+
+    let mut index = 0;
+    let mut remaining = pattern[0].abs();
+    let mut drawing = pattern[0] >= 0.0;
+    let mut current = if drawing { vec![start] } else { Vec::new() };
+
+    for w in path.windows(2) {
+        let (a, b) = (w[0], w[1]);
+        let length = (b[0] - a[0]).hypot(b[1] - a[1]);
+        let mut travelled = 0.0;
+        while length - travelled > remaining {
+            travelled += remaining;
+            let t = travelled / length;
+            let p = [a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t];
+            if drawing {
+                current.push(p);
+                dashes.push(std::mem::take(&mut current));
+            }
+            index = (index + 1) % pattern.len();
+            remaining = pattern[index].abs();
+            drawing = pattern[index] >= 0.0;
+            if drawing {
+                current = vec![p];
+            }
+        }
+        remaining -= length - travelled;
+        if drawing {
+            current.push(b);
+        }
+    }
+    if drawing && !current.is_empty() {
+        dashes.push(current);
+    }
+    dashes
+}
+
+/// 在 x = 0 和 x = width 处拆分边，并把超出部分压到边界上
+///
+/// 左侧之外的部分压到 x = 0 后仍贡献完整的绕数，右侧之外的部分落在缓冲区的保留列中
+fn clip_edge_x(p0: [f32; 2], p1: [f32; 2], width: f32) -> Vec<Edge> {
+    let mut ts = vec![0.0, 1.0];
+    let dx = p1[0] - p0[0];
+    if dx.abs() > f32::EPSILON {
+        for boundary in [0.0, width] {
+            let t = (boundary - p0[0]) / dx;
+            if t > 0.0 && t < 1.0 {
+                ts.push(t);
+            }
+        }
+    }
+    ts.sort_by(|a, b| a.total_cmp(b));
+
+    let at = |t: f32| {
+        [
+            (p0[0] + dx * t).clamp(0.0, width),
+            p0[1] + (p1[1] - p0[1]) * t,
+        ]
+    };
+    ts.windows(2).map(|w| (at(w[0]), at(w[1]))).collect()
+}
+
+/// 累加一条边的有向面积（x 已位于 [0, width] 内）
+fn accumulate(area: &mut [f32], stride: usize, height: usize, width: f32, p0: [f32; 2], p1: [f32; 2]) {
+    if (p0[1] - p1[1]).abs() <= f32::EPSILON {
+        return;
+    }
+    let (direction, p0, p1) = if p0[1] < p1[1] { (1.0, p0, p1) } else { (-1.0, p1, p0) };
+    let dxdy = (p1[0] - p0[0]) / (p1[1] - p0[1]);
+    let mut x = p0[0];
+    if p0[1] < 0.0 {
+        x -= p0[1] * dxdy;
+    }
+
+    let y_start = p0[1].max(0.0) as usize;
+    let y_end = height.min(p1[1].ceil().max(0.0) as usize);
+    for y in y_start..y_end {
+        let row = y * stride;
+        let dy = ((y + 1) as f32).min(p1[1]) - (y as f32).max(p0[1]);
+        let x_next = (x + dxdy * dy).clamp(0.0, width);
+        let d = dy * direction;
+        let (xa, xb) = if x < x_next { (x, x_next) } else { (x_next, x) };
+        let xa_floor = xa.floor();
+        let xa_i = xa_floor as usize;
+        let xb_ceil = xb.ceil();
+        let xb_i = xb_ceil as usize;
+
+        if xb_i <= xa_i + 1 {
+            // 整行只跨一个像素
+            let mid = 0.5 * (x + x_next) - xa_floor;
+            area[row + xa_i] += d - d * mid;
+            area[row + xa_i + 1] += d * mid;
+        } else {
+            let s = (xb - xa).recip();
+            let xa_fract = xa - xa_floor;
+            let a0 = 0.5 * s * (1.0 - xa_fract) * (1.0 - xa_fract);
+            let xb_fract = xb - xb_ceil + 1.0;
+            let am = 0.5 * s * xb_fract * xb_fract;
+            area[row + xa_i] += d * a0;
+            if xb_i == xa_i + 2 {
+                area[row + xa_i + 1] += d * (1.0 - a0 - am);
+            } else {
+                let a1 = s * (1.5 - xa_fract);
+                area[row + xa_i + 1] += d * (a1 - a0);
+                for xi in xa_i + 2..xb_i - 1 {
+                    area[row + xi] += d * s;
+                }
+                let a2 = a1 + (xb_i - xa_i - 3) as f32 * s;
+                area[row + xb_i - 1] += d * (1.0 - a2 - am);
+            }
+            area[row + xb_i] += d * am;
+        }
+        x = x_next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::{Orientation, PaperSize, PrintArea};
+    use zcad_core::geometry::{HatchBoundary, HatchBoundaryElement, Line, TextAlignment};
+    use zcad_core::properties::{LineWeight, Properties};
+
+    /// A4 横向、10mm 边距；打印范围 (0,0)-(100,100) 的中心位于纸张中心
+    fn page_setup() -> PageSetup {
+        PageSetup {
+            paper_size: PaperSize::A4,
+            orientation: Orientation::Landscape,
+            print_area: Some(PrintArea::new(Point2::origin(), Point2::new(100.0, 100.0))),
+            ..Default::default()
+        }
+    }
+
+    fn square_hatch(min: f64, max: f64) -> Entity {
+        let corners = [
+            Point2::new(min, min),
+            Point2::new(max, min),
+            Point2::new(max, max),
+            Point2::new(min, max),
+        ];
+        let elements = (0..4)
+            .map(|i| HatchBoundaryElement::Line(Line::new(corners[i], corners[(i + 1) % 4])))
+            .collect();
+        Entity::new(Geometry::Hatch(Hatch::solid(vec![HatchBoundary::new(elements, true)])))
+    }
+
+    #[test]
+    fn test_image_size_follows_dpi() {
+        let image = RasterExporter::new(page_setup()).with_dpi(100.0).render(&[]).unwrap();
+        // 297 x 210 mm @ 100 DPI
+        assert_eq!((image.width, image.height), (1169, 827));
+
+        assert!(RasterExporter::new(page_setup()).with_dpi(0.0).render(&[]).is_err());
+        assert!(RasterExporter::new(page_setup()).with_dpi(100_000.0).render(&[]).is_err());
+    }
+
+    #[test]
+    fn test_fill_coverage_is_antialiased() {
+        // 覆盖 (0.5, 0) 到 (2, 1) 的矩形：首个像素覆盖一半
+        let mut canvas = Canvas {
+            image: RasterImage::new(4, 2, Color::WHITE, 96.0),
+            transform: PixelTransform {
+                scale: 1.0,
+                offset: Vector2::zeros(),
+                px_per_mm: 1.0,
+                height: 2.0,
+            },
+            clip: ClipRect { x0: 0, y0: 0, x1: 4, y1: 2 },
+            background: Color::WHITE,
+        };
+        let mut edges = Vec::new();
+        push_polygon(&[[0.5, 0.0], [2.0, 0.0], [2.0, 1.0], [0.5, 1.0]], &mut edges);
+        canvas.fill_edges(&edges, FillRule::NonZero, Color::BLACK);
+
+        assert_eq!(canvas.image.pixel(0, 0)[0], 128);
+        assert_eq!(canvas.image.pixel(1, 0), [0, 0, 0, 255]);
+        assert_eq!(canvas.image.pixel(2, 0), [255, 255, 255, 255]);
+        assert_eq!(canvas.image.pixel(1, 1), [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn test_render_fill_line_and_clip() {
+        let mut line = Entity::new(Geometry::Line(Line::new(Point2::new(0.0, 90.0), Point2::new(100.0, 90.0))));
+        line.properties = Properties::default()
+            .set_color(Color::new(255, 0, 0))
+            .set_line_weight(LineWeight::Width(1.0));
+        // 超出纸张的直线应被裁剪到可打印区域
+        let long_line = Entity::new(Geometry::Line(Line::new(Point2::new(-500.0, 10.0), Point2::new(500.0, 10.0))));
+        let entities = vec![square_hatch(25.0, 75.0), line, long_line];
+
+        let image = RasterExporter::new(page_setup()).with_dpi(100.0).render(&entities).unwrap();
+        let (cx, cy) = (image.width / 2, image.height / 2);
+        assert_eq!(image.pixel(cx, cy), [0, 0, 0, 255]);
+        assert_eq!(image.pixel(5, 5), [255, 255, 255, 255]);
+
+        // y = 90 → 纸张 y = 105 + 40 * 1.805 mm；1mm 宽的红线
+        let px_per_mm: f64 = 100.0 / 25.4;
+        let red_y = ((210.0 - (105.0 + 40.0 * 1.805)) * px_per_mm) as u32;
+        let pixel = image.pixel(cx, red_y);
+        assert!(pixel[0] > 200 && pixel[1] < 60, "{:?}", pixel);
+
+        // y = 10 的直线在页边距内被裁剪
+        let long_y = ((210.0 - (105.0 - 40.0 * 1.805)) * px_per_mm) as u32;
+        assert!(image.pixel(cx, long_y)[0] < 128);
+        assert_eq!(image.pixel(5, long_y), [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn test_pattern_hatch_draws_clipped_lines() {
+        // LINE 图案按比例放大为间距 10 的水平线，只出现在 25..75 的方框内
+        let Geometry::Hatch(solid) = square_hatch(25.0, 75.0).geometry else {
+            unreachable!()
+        };
+        let hatch = Hatch::pattern(solid.boundaries, "LINE", 0.0, 10.0 / 3.175);
+        let image = RasterExporter::new(page_setup())
+            .with_dpi(100.0)
+            .render(&[Entity::new(Geometry::Hatch(hatch))])
+            .unwrap();
+
+        let px_per_mm: f64 = 100.0 / 25.4;
+        let row = |y: f64| ((210.0 - (105.0 + (y - 50.0) * 1.805)) * px_per_mm) as u32;
+        let column = |x: f64| ((148.5 + (x - 50.0) * 1.805) * px_per_mm) as u32;
+        let darkest = |x: f64, y: f64| (row(y) - 1..=row(y) + 1).map(|r| image.pixel(column(x), r)[0]).min().unwrap();
+
+        assert!(darkest(50.0, 50.0) < 160);
+        assert!(darkest(30.0, 40.0) < 160);
+        assert_eq!(darkest(50.0, 55.0), 255);
+        // 边界不描边，方框外没有图案线
+        assert_eq!(darkest(20.0, 50.0), 255);
+        assert_eq!(darkest(50.0, 75.0 + 2.0), 255);
+    }
+
+    #[test]
+    fn test_dashed_line_has_gaps() {
+        let path = [[0.0, 0.0], [30.0, 0.0]];
+        let dashes = dash_path(&path, &[10.0, -5.0]);
+        assert_eq!(dashes.len(), 2);
+        assert_eq!(dashes[0], vec![[0.0, 0.0], [10.0, 0.0]]);
+        assert_eq!(dashes[1], vec![[15.0, 0.0], [25.0, 0.0]]);
+
+        // 零长度为点
+        let dots = dash_path(&path, &[0.0, -10.0]);
+        assert_eq!(dots.len(), 3);
+    }
+
+    #[test]
+    fn test_text_is_rasterized() {
+        let text = Entity::new(Geometry::Text(
+            Text::new(Point2::new(50.0, 50.0), "HELLO", 10.0).with_alignment(TextAlignment::Center),
+        ));
+        let image = RasterExporter::new(page_setup()).with_dpi(100.0).render(&[text]).unwrap();
+
+        let dark = image.pixels.chunks_exact(4).filter(|p| p[0] < 128).count();
+        assert!(dark > 100, "{}", dark);
+        // 居中对齐：墨迹左右大致对称
+        let columns: Vec<u32> = (0..image.width)
+            .filter(|&x| (0..image.height).any(|y| image.pixel(x, y)[0] < 128))
+            .collect();
+        let center = (columns[0] + columns[columns.len() - 1]) as f64 / 2.0;
+        assert!((center - image.width as f64 / 2.0).abs() < 5.0, "{}", center);
+    }
+
+    #[test]
+    fn test_png_and_jpeg_encoding() {
+        let exporter = RasterExporter::new(page_setup()).with_dpi(72.0);
+        let entities = vec![square_hatch(25.0, 75.0)];
+
+        let png_data = exporter.export_png(&entities).unwrap();
+        assert!(png_data.starts_with(b"\x89PNG\r\n\x1a\n"));
+        let decoder = png::Decoder::new(std::io::Cursor::new(&png_data));
+        let reader = decoder.read_info().unwrap();
+        let info = reader.info();
+        assert_eq!((info.width, info.height), (842, 595));
+        // 72 DPI = 2835 像素/米
+        assert_eq!(info.pixel_dims.unwrap().xppu, 2835);
+
+        let jpeg_data = exporter.with_jpeg_quality(80).export_jpeg(&entities).unwrap();
+        assert_eq!(&jpeg_data[..2], &[0xFF, 0xD8]);
+        assert_eq!(&jpeg_data[jpeg_data.len() - 2..], &[0xFF, 0xD9]);
+    }
+}
@@ -10,6 +10,7 @@ use thiserror::Error;
 use wgpu::util::DeviceExt;
 use zcad_core::geometry::{Circle, Geometry, Line, Point};
 use zcad_core::math::Point2;
+use zcad_core::tessellation;
 
 /// 计算着色器错误
 #[derive(Error, Debug)]
@@ -249,8 +250,8 @@ impl ComputeShader {
         tolerance: f64,
     ) -> Result<Vec<Geometry>, ComputeError> {
         // 将几何体转换为GPU缓冲区格式
-        let geom1_data = self.geometry_to_gpu_data(geom1);
-        let geom2_data = self.geometry_to_gpu_data(geom2);
+        let geom1_data = self.geometry_to_gpu_data(geom1, tolerance);
+        let geom2_data = self.geometry_to_gpu_data(geom2, tolerance);
 
         // 创建输入缓冲区
         let input1_buffer = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
@@ -386,7 +387,7 @@ impl ComputeShader {
         distance: f64,
         tolerance: f64,
     ) -> Result<Vec<Geometry>, ComputeError> {
-        let geom_data = self.geometry_to_gpu_data(geometry);
+        let geom_data = self.geometry_to_gpu_data(geometry, tolerance);
 
         let input_buffer = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Offset Input"),
@@ -494,8 +495,8 @@ impl ComputeShader {
         }
     }
 
-    /// 将几何体转换为GPU数据格式
-    fn geometry_to_gpu_data(&self, geometry: &Geometry) -> Vec<GpuGeometryData> {
+    /// 将几何体转换为GPU数据格式，`tolerance` 为样条细分的弦高容差
+    fn geometry_to_gpu_data(&self, geometry: &Geometry, tolerance: f64) -> Vec<GpuGeometryData> {
         match geometry {
             Geometry::Line(line) => vec![
                 GpuGeometryData {
@@ -579,7 +580,21 @@ impl ComputeShader {
                     param2: 0.0,
                 }
             ],
+            Geometry::MText(mtext) => vec![
+                GpuGeometryData {
+                    geometry_type: 6, // 多行文字同样按插入点处理
+                    x1: mtext.position.x as f32,
+                    y1: mtext.position.y as f32,
+                    x2: 0.0,
+                    y2: 0.0,
+                    radius: mtext.height as f32,
+                    bulge: 0.0,
+                    param1: mtext.rotation as f32,
+                    param2: 0.0,
+                }
+            ],
             Geometry::Dimension(_) => vec![], // 暂不支持GPU计算标注
+            Geometry::Tolerance(_) => vec![], // 暂不支持GPU计算形位公差
             Geometry::Ellipse(ellipse) => vec![
                 GpuGeometryData {
                     geometry_type: 7, // Ellipse
@@ -594,8 +609,8 @@ impl ComputeShader {
                 }
             ],
             Geometry::Spline(spline) => {
-                // 样条曲线简化为采样点
-                let points = spline.sample_points(32);
+                // 样条曲线按运算容差细分为折线点
+                let points = tessellation::spline_points(spline, tolerance);
                 let mut data = Vec::new();
                 for pt in points {
                     data.push(GpuGeometryData {
@@ -613,6 +628,7 @@ impl ComputeShader {
                 data
             },
             Geometry::Hatch(_) => vec![], // 填充不参与GPU计算
+            Geometry::Insert(_) => vec![], // 块参照需先展开
             Geometry::Leader(leader) => {
                 let mut data = Vec::new();
                 for pt in &leader.vertices {
@@ -11,8 +11,11 @@ use crate::vertex::{CameraUniform, LineVertex};
 use thiserror::Error;
 use zcad_core::math::BoundingBox2;
 use wgpu::util::DeviceExt;
-use zcad_core::geometry::{Arc, Circle, Geometry, Line, Polyline, Text};
+use zcad_core::block::BlockTable;
+use zcad_core::entity::Entity;
+use zcad_core::geometry::Geometry;
 use zcad_core::math::Point2;
+use zcad_core::tessellation::{self, TessellationCache};
 use zcad_core::properties::Color;
 
 /// 渲染器错误
@@ -54,6 +57,10 @@ pub struct Renderer {
     // 渲染缓冲区
     line_vertices: Vec<LineVertex>,
 
+    // 曲线细分：当前视图的弦高容差及按实体缓存的结果
+    tolerance: f64,
+    tessellation_cache: TessellationCache,
+
     // 网格设置
     grid_visible: bool,
     grid_spacing: f64,
@@ -148,6 +155,8 @@ impl Renderer {
             compute_shader,
             tile_manager,
             line_vertices: Vec::new(),
+            tolerance: tessellation::DEFAULT_TOLERANCE,
+            tessellation_cache: TessellationCache::new(),
             grid_visible: true,
             grid_spacing: 50.0,
             grid_color: Color::new(60, 60, 70),
@@ -164,11 +173,12 @@ impl Renderer {
         }
     }
 
-    /// 更新相机
+    /// 更新相机，曲线细分的弦高容差随缩放变化
     pub fn update_camera(&mut self, camera: &Camera2D) {
         let uniform = camera.to_uniform();
         self.queue
             .write_buffer(&self.camera_buffer, 0, bytemuck::cast_slice(&[uniform]));
+        self.tolerance = tessellation::screen_tolerance(camera.units_per_pixel());
     }
 
     /// 设置网格可见性
@@ -181,9 +191,10 @@ impl Renderer {
         self.grid_spacing = spacing;
     }
 
-    /// 清空渲染缓冲区
+    /// 清空渲染缓冲区，并清除上一帧没有绘制的实体的细分缓存
     pub fn begin_frame(&mut self) {
         self.line_vertices.clear();
+        self.tessellation_cache.evict_unused();
     }
 
     /// 绘制网格
@@ -258,278 +269,35 @@ impl Renderer {
 
     /// 添加几何体到渲染批次
     pub fn draw_geometry(&mut self, geometry: &Geometry, color: Color) {
-        let color_arr = color.to_f32_array();
-
-        match geometry {
-            Geometry::Point(p) => {
-                let size = 3.0;
-                let x = p.position.x as f32;
-                let y = p.position.y as f32;
-                self.line_vertices.push(LineVertex::new(x - size, y, color_arr));
-                self.line_vertices.push(LineVertex::new(x + size, y, color_arr));
-                self.line_vertices.push(LineVertex::new(x, y - size, color_arr));
-                self.line_vertices.push(LineVertex::new(x, y + size, color_arr));
-            }
-            Geometry::Line(line) => {
-                self.draw_line(line, color_arr);
-            }
-            Geometry::Circle(circle) => {
-                self.draw_circle(circle, color_arr);
-            }
-            Geometry::Arc(arc) => {
-                self.draw_arc(arc, color_arr);
-            }
-            Geometry::Polyline(polyline) => {
-                self.draw_polyline(polyline, color_arr);
-            }
-            Geometry::Text(text) => {
-                // 文本渲染在 GPU 端较为复杂，这里用一个简单的标记点表示
-                // 实际的文本渲染由 egui 处理
-                self.draw_text_marker(text, color_arr);
-            }
-            Geometry::Dimension(_) => {}
-            Geometry::Ellipse(ellipse) => {
-                self.draw_ellipse(ellipse, color_arr);
-            }
-            Geometry::Spline(spline) => {
-                self.draw_spline(spline, color_arr);
-            }
-            Geometry::Hatch(hatch) => {
-                self.draw_hatch(hatch, color_arr);
-            }
-            Geometry::Leader(leader) => {
-                self.draw_leader(leader, color_arr);
-            }
-        }
-    }
-
-    fn draw_text_marker(&mut self, text: &Text, color: [f32; 4]) {
-        // 在文本位置绘制一个小十字标记
-        let x = text.position.x as f32;
-        let y = text.position.y as f32;
-        let size = text.height as f32 * 0.5;
-        
-        self.line_vertices.push(LineVertex::new(x - size, y, color));
-        self.line_vertices.push(LineVertex::new(x + size, y, color));
-        self.line_vertices.push(LineVertex::new(x, y - size, color));
-        self.line_vertices.push(LineVertex::new(x, y + size, color));
-    }
-
-    fn draw_line(&mut self, line: &Line, color: [f32; 4]) {
-        self.line_vertices.push(LineVertex::new(
-            line.start.x as f32,
-            line.start.y as f32,
-            color,
-        ));
-        self.line_vertices.push(LineVertex::new(
-            line.end.x as f32,
-            line.end.y as f32,
-            color,
-        ));
-    }
-
-    fn draw_circle(&mut self, circle: &Circle, color: [f32; 4]) {
-        let segments = (circle.radius * 2.0).clamp(32.0, 256.0) as usize;
-        let angle_step = 2.0 * std::f64::consts::PI / segments as f64;
-
-        for i in 0..segments {
-            let a1 = i as f64 * angle_step;
-            let a2 = (i + 1) as f64 * angle_step;
-
-            let p1 = circle.point_at_angle(a1);
-            let p2 = circle.point_at_angle(a2);
-
-            self.line_vertices
-                .push(LineVertex::new(p1.x as f32, p1.y as f32, color));
-            self.line_vertices
-                .push(LineVertex::new(p2.x as f32, p2.y as f32, color));
-        }
-    }
-
-    fn draw_arc(&mut self, arc: &Arc, color: [f32; 4]) {
-        let sweep = arc.sweep_angle();
-        let segments = ((arc.radius * sweep.abs()).clamp(8.0, 128.0)) as usize;
-        let angle_step = sweep / segments as f64;
-
-        for i in 0..segments {
-            let a1 = arc.start_angle + i as f64 * angle_step;
-            let a2 = arc.start_angle + (i + 1) as f64 * angle_step;
-
-            let p1 = Point2::new(
-                arc.center.x + arc.radius * a1.cos(),
-                arc.center.y + arc.radius * a1.sin(),
-            );
-            let p2 = Point2::new(
-                arc.center.x + arc.radius * a2.cos(),
-                arc.center.y + arc.radius * a2.sin(),
-            );
-
-            self.line_vertices
-                .push(LineVertex::new(p1.x as f32, p1.y as f32, color));
-            self.line_vertices
-                .push(LineVertex::new(p2.x as f32, p2.y as f32, color));
-        }
-    }
-
-    fn draw_polyline(&mut self, polyline: &Polyline, color: [f32; 4]) {
-        if polyline.vertices.len() < 2 {
-            return;
-        }
-
-        for i in 0..polyline.segment_count() {
-            let v1 = &polyline.vertices[i];
-            let v2 = &polyline.vertices[(i + 1) % polyline.vertices.len()];
-
-            if v1.bulge.abs() < 0.001 {
-                self.line_vertices.push(LineVertex::new(
-                    v1.point.x as f32,
-                    v1.point.y as f32,
-                    color,
-                ));
-                self.line_vertices.push(LineVertex::new(
-                    v2.point.x as f32,
-                    v2.point.y as f32,
-                    color,
-                ));
-            } else {
-                // 弧线段细分
-                self.draw_bulge_segment(v1.point, v2.point, v1.bulge, color);
-            }
-        }
-    }
-
-    fn draw_bulge_segment(&mut self, p1: Point2, p2: Point2, bulge: f64, color: [f32; 4]) {
-        let chord = p2 - p1;
-        let chord_len = chord.norm();
-        
-        if chord_len < 0.001 {
-            return;
-        }
-
-        let s = chord_len / 2.0;
-        let h = s * bulge.abs();
-        
-        if h < 0.001 {
-            self.line_vertices.push(LineVertex::new(p1.x as f32, p1.y as f32, color));
-            self.line_vertices.push(LineVertex::new(p2.x as f32, p2.y as f32, color));
-            return;
-        }
-
-        let radius = (s * s + h * h) / (2.0 * h);
-        let angle = 4.0 * bulge.abs().atan();
-        
-        let mid = Point2::new((p1.x + p2.x) / 2.0, (p1.y + p2.y) / 2.0);
-        let d = radius - h;
-        
-        let perp = if bulge > 0.0 {
-            zcad_core::math::Vector2::new(-chord.y, chord.x).normalize()
-        } else {
-            zcad_core::math::Vector2::new(chord.y, -chord.x).normalize()
-        };
-        
-        let center = mid + perp * d;
-        let start_angle = (p1.y - center.y).atan2(p1.x - center.x);
-        
-        let segments = (radius * angle.abs()).clamp(4.0, 64.0) as usize;
-        let angle_step = if bulge > 0.0 { angle / segments as f64 } else { -angle / segments as f64 };
-
-        for i in 0..segments {
-            let a1 = start_angle + i as f64 * angle_step;
-            let a2 = start_angle + (i + 1) as f64 * angle_step;
-
-            let pt1 = Point2::new(center.x + radius * a1.cos(), center.y + radius * a1.sin());
-            let pt2 = Point2::new(center.x + radius * a2.cos(), center.y + radius * a2.sin());
-
-            self.line_vertices.push(LineVertex::new(pt1.x as f32, pt1.y as f32, color));
-            self.line_vertices.push(LineVertex::new(pt2.x as f32, pt2.y as f32, color));
-        }
-    }
-
-    fn draw_ellipse(&mut self, ellipse: &zcad_core::geometry::Ellipse, color: [f32; 4]) {
-        let points = ellipse.sample_points(64);
-        for i in 0..points.len().saturating_sub(1) {
-            self.line_vertices.push(LineVertex::new(
-                points[i].x as f32,
-                points[i].y as f32,
-                color,
-            ));
-            self.line_vertices.push(LineVertex::new(
-                points[i + 1].x as f32,
-                points[i + 1].y as f32,
-                color,
-            ));
-        }
-    }
-
-    fn draw_spline(&mut self, spline: &zcad_core::geometry::Spline, color: [f32; 4]) {
-        let points = spline.sample_points(64);
-        for i in 0..points.len().saturating_sub(1) {
-            self.line_vertices.push(LineVertex::new(
-                points[i].x as f32,
-                points[i].y as f32,
-                color,
-            ));
-            self.line_vertices.push(LineVertex::new(
-                points[i + 1].x as f32,
-                points[i + 1].y as f32,
-                color,
-            ));
-        }
+        push_geometry_vertices(geometry, color, self.tolerance, &mut self.line_vertices);
     }
 
-    fn draw_hatch(&mut self, hatch: &zcad_core::geometry::Hatch, color: [f32; 4]) {
-        // 绘制填充边界
-        for boundary in &hatch.boundaries {
-            for elem in &boundary.elements {
-                match elem {
-                    zcad_core::geometry::HatchBoundaryElement::Line(line) => {
-                        self.draw_line(line, color);
-                    }
-                    zcad_core::geometry::HatchBoundaryElement::Arc(arc) => {
-                        self.draw_arc(arc, color);
-                    }
-                    zcad_core::geometry::HatchBoundaryElement::Ellipse(ellipse) => {
-                        self.draw_ellipse(ellipse, color);
-                    }
-                    zcad_core::geometry::HatchBoundaryElement::Spline(spline) => {
-                        self.draw_spline(spline, color);
+    /// 添加实体到渲染批次，块参照按块定义展开后逐个绘制
+    ///
+    /// `color_of` 解析每个基本图元的显示颜色（ByBlock 已由块表继承）。
+    /// 曲线的细分结果按实体缓存；块参照展开的图元与块定义共用 ID，每帧重新细分。
+    pub fn draw_entity(&mut self, entity: &Entity, blocks: &BlockTable, color_of: impl Fn(&Entity) -> Color) {
+        let tolerance = self.tolerance;
+        match &entity.geometry {
+            Geometry::Insert(_) => match blocks.resolve(entity) {
+                Ok(parts) => {
+                    for part in &parts {
+                        push_geometry_vertices(&part.geometry, color_of(part), tolerance, &mut self.line_vertices);
                     }
                 }
+                Err(e) => {
+                    // 无法展开时只绘制插入点标记
+                    tracing::warn!("Failed to resolve block reference: {}", e);
+                    push_geometry_vertices(&entity.geometry, color_of(entity), tolerance, &mut self.line_vertices);
+                }
+            },
+            Geometry::Point(_) | Geometry::Text(_) | Geometry::MText(_) => {
+                push_geometry_vertices(&entity.geometry, color_of(entity), tolerance, &mut self.line_vertices);
+            }
+            _ => {
+                let paths = self.tessellation_cache.get(entity, tolerance);
+                push_path_vertices(paths, color_of(entity), &mut self.line_vertices);
             }
-        }
-        // TODO: 绘制填充图案
-    }
-
-    fn draw_leader(&mut self, leader: &zcad_core::geometry::Leader, color: [f32; 4]) {
-        // 绘制引线线段
-        for i in 0..leader.vertices.len().saturating_sub(1) {
-            self.line_vertices.push(LineVertex::new(
-                leader.vertices[i].x as f32,
-                leader.vertices[i].y as f32,
-                color,
-            ));
-            self.line_vertices.push(LineVertex::new(
-                leader.vertices[i + 1].x as f32,
-                leader.vertices[i + 1].y as f32,
-                color,
-            ));
-        }
-
-        // 绘制箭头
-        if leader.vertices.len() >= 2 {
-            let arrow_pt = leader.vertices[0];
-            let next_pt = leader.vertices[1];
-            let dir = (arrow_pt - next_pt).normalize();
-            let perp = zcad_core::math::Vector2::new(-dir.y, dir.x);
-            let arrow_size = leader.arrow_size;
-
-            let p1 = arrow_pt - dir * arrow_size + perp * arrow_size * 0.3;
-            let p2 = arrow_pt - dir * arrow_size - perp * arrow_size * 0.3;
-
-            self.line_vertices.push(LineVertex::new(arrow_pt.x as f32, arrow_pt.y as f32, color));
-            self.line_vertices.push(LineVertex::new(p1.x as f32, p1.y as f32, color));
-            self.line_vertices.push(LineVertex::new(arrow_pt.x as f32, arrow_pt.y as f32, color));
-            self.line_vertices.push(LineVertex::new(p2.x as f32, p2.y as f32, color));
         }
     }
 
@@ -682,148 +450,60 @@ impl Renderer {
 
     /// 辅助方法：将几何体绘制到顶点缓冲区
     fn draw_geometry_to_buffer(&self, geometry: &Geometry, color: Color, vertices: &mut Vec<LineVertex>) {
-        let color_arr = color.to_f32_array();
-
-        match geometry {
-            Geometry::Point(p) => {
-                let size = 3.0;
-                let x = p.position.x as f32;
-                let y = p.position.y as f32;
-                vertices.push(LineVertex::new(x - size, y, color_arr));
-                vertices.push(LineVertex::new(x + size, y, color_arr));
-                vertices.push(LineVertex::new(x, y - size, color_arr));
-                vertices.push(LineVertex::new(x, y + size, color_arr));
-            }
-            Geometry::Line(line) => {
-                vertices.push(LineVertex::new(
-                    line.start.x as f32,
-                    line.start.y as f32,
-                    color_arr,
-                ));
-                vertices.push(LineVertex::new(
-                    line.end.x as f32,
-                    line.end.y as f32,
-                    color_arr,
-                ));
-            }
-            Geometry::Circle(circle) => {
-                let segments = (circle.radius * 2.0).clamp(32.0, 256.0) as usize;
-                let angle_step = 2.0 * std::f64::consts::PI / segments as f64;
-
-                for i in 0..segments {
-                    let a1 = i as f64 * angle_step;
-                    let a2 = (i + 1) as f64 * angle_step;
-
-                    let p1 = circle.point_at_angle(a1);
-                    let p2 = circle.point_at_angle(a2);
-
-                    vertices.push(LineVertex::new(p1.x as f32, p1.y as f32, color_arr));
-                    vertices.push(LineVertex::new(p2.x as f32, p2.y as f32, color_arr));
-                }
-            }
-            Geometry::Arc(arc) => {
-                let sweep = arc.sweep_angle();
-                let segments = ((arc.radius * sweep.abs()).clamp(8.0, 128.0)) as usize;
-                let angle_step = sweep / segments as f64;
-
-                for i in 0..segments {
-                    let a1 = arc.start_angle + i as f64 * angle_step;
-                    let a2 = arc.start_angle + (i + 1) as f64 * angle_step;
-
-                    let p1 = Point2::new(
-                        arc.center.x + arc.radius * a1.cos(),
-                        arc.center.y + arc.radius * a1.sin(),
-                    );
-                    let p2 = Point2::new(
-                        arc.center.x + arc.radius * a2.cos(),
-                        arc.center.y + arc.radius * a2.sin(),
-                    );
-
-                    vertices.push(LineVertex::new(p1.x as f32, p1.y as f32, color_arr));
-                    vertices.push(LineVertex::new(p2.x as f32, p2.y as f32, color_arr));
-                }
-            }
-            Geometry::Polyline(polyline) => {
-                if polyline.vertices.len() < 2 {
-                    return;
-                }
+        push_geometry_vertices(geometry, color, self.tolerance, vertices);
+    }
+}
 
-                for i in 0..polyline.segment_count() {
-                    let v1 = &polyline.vertices[i];
-                    let v2 = &polyline.vertices[(i + 1) % polyline.vertices.len()];
-
-                    if v1.bulge.abs() < 0.001 {
-                        vertices.push(LineVertex::new(
-                            v1.point.x as f32,
-                            v1.point.y as f32,
-                            color_arr,
-                        ));
-                        vertices.push(LineVertex::new(
-                            v2.point.x as f32,
-                            v2.point.y as f32,
-                            color_arr,
-                        ));
-                    } else {
-                        // 简化的弧线处理（实际应该细分）
-                        vertices.push(LineVertex::new(
-                            v1.point.x as f32,
-                            v1.point.y as f32,
-                            color_arr,
-                        ));
-                        vertices.push(LineVertex::new(
-                            v2.point.x as f32,
-                            v2.point.y as f32,
-                            color_arr,
-                        ));
-                    }
-                }
-            }
-            Geometry::Text(text) => {
-                // 在文本位置绘制一个小十字标记
-                let x = text.position.x as f32;
-                let y = text.position.y as f32;
-                let size = text.height as f32 * 0.5;
-                
-                vertices.push(LineVertex::new(x - size, y, color_arr));
-                vertices.push(LineVertex::new(x + size, y, color_arr));
-                vertices.push(LineVertex::new(x, y - size, color_arr));
-                vertices.push(LineVertex::new(x, y + size, color_arr));
-            }
-            Geometry::Dimension(_) => {}
-            Geometry::Ellipse(ellipse) => {
-                let points = ellipse.sample_points(64);
-                for i in 0..points.len().saturating_sub(1) {
-                    vertices.push(LineVertex::new(points[i].x as f32, points[i].y as f32, color_arr));
-                    vertices.push(LineVertex::new(points[i + 1].x as f32, points[i + 1].y as f32, color_arr));
-                }
-            }
-            Geometry::Spline(spline) => {
-                let points = spline.sample_points(64);
-                for i in 0..points.len().saturating_sub(1) {
-                    vertices.push(LineVertex::new(points[i].x as f32, points[i].y as f32, color_arr));
-                    vertices.push(LineVertex::new(points[i + 1].x as f32, points[i + 1].y as f32, color_arr));
+/// 将几何体转换为线段顶点对（曲线细分见 [`zcad_core::tessellation`]）
+fn push_geometry_vertices(geometry: &Geometry, color: Color, tolerance: f64, vertices: &mut Vec<LineVertex>) {
+    let color_arr = color.to_f32_array();
+
+    match geometry {
+        Geometry::Point(p) => {
+            let size = 3.0;
+            let x = p.position.x as f32;
+            let y = p.position.y as f32;
+            vertices.push(LineVertex::new(x - size, y, color_arr));
+            vertices.push(LineVertex::new(x + size, y, color_arr));
+            vertices.push(LineVertex::new(x, y - size, color_arr));
+            vertices.push(LineVertex::new(x, y + size, color_arr));
+        }
+        Geometry::Text(text) => {
+            // 字形轮廓（TrueType）或笔画（SHX）按线段绘制，见 zcad_core::font
+            for contour in text.outlines().iter().flat_map(|glyph| &glyph.contours) {
+                for pair in contour.windows(2) {
+                    vertices.push(LineVertex::new(pair[0].x as f32, pair[0].y as f32, color_arr));
+                    vertices.push(LineVertex::new(pair[1].x as f32, pair[1].y as f32, color_arr));
                 }
             }
-            Geometry::Hatch(hatch) => {
-                // 只绘制边界
-                for boundary in &hatch.boundaries {
-                    for elem in &boundary.elements {
-                        match elem {
-                            zcad_core::geometry::HatchBoundaryElement::Line(line) => {
-                                vertices.push(LineVertex::new(line.start.x as f32, line.start.y as f32, color_arr));
-                                vertices.push(LineVertex::new(line.end.x as f32, line.end.y as f32, color_arr));
-                            }
-                            _ => {} // 其他类型简化处理
-                        }
-                    }
-                }
+        }
+        Geometry::MText(mtext) => {
+            for (part, part_color) in mtext.explode_with_colors() {
+                push_geometry_vertices(&part, part_color.unwrap_or(color), tolerance, vertices);
             }
-            Geometry::Leader(leader) => {
-                for i in 0..leader.vertices.len().saturating_sub(1) {
-                    vertices.push(LineVertex::new(leader.vertices[i].x as f32, leader.vertices[i].y as f32, color_arr));
-                    vertices.push(LineVertex::new(leader.vertices[i + 1].x as f32, leader.vertices[i + 1].y as f32, color_arr));
-                }
+        }
+        Geometry::Insert(reference) => {
+            // 未展开的块参照：在每个插入点画一个斜十字
+            let size = 3.0;
+            for p in reference.all_insertion_points() {
+                let (x, y) = (p.x as f32, p.y as f32);
+                vertices.push(LineVertex::new(x - size, y - size, color_arr));
+                vertices.push(LineVertex::new(x + size, y + size, color_arr));
+                vertices.push(LineVertex::new(x - size, y + size, color_arr));
+                vertices.push(LineVertex::new(x + size, y - size, color_arr));
             }
         }
+        _ => push_path_vertices(&tessellation::tessellate(geometry, tolerance), color, vertices),
+    }
+}
+
+/// 将折线转换为线段顶点对
+fn push_path_vertices(paths: &[Vec<Point2>], color: Color, vertices: &mut Vec<LineVertex>) {
+    let color_arr = color.to_f32_array();
+    for path in paths {
+        for pair in path.windows(2) {
+            vertices.push(LineVertex::new(pair[0].x as f32, pair[0].y as f32, color_arr));
+            vertices.push(LineVertex::new(pair[1].x as f32, pair[1].y as f32, color_arr));
+        }
     }
 }
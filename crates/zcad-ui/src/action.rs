@@ -3,9 +3,13 @@
 //! 每个绘图/编辑工具是一个独立的 Action 实现，
 //! 采用状态机模式处理用户交互。
 
+use zcad_core::associative::AssociativePoint;
+use zcad_core::dimstyle::DimStyleManager;
 use zcad_core::entity::{Entity, EntityId};
 use zcad_core::geometry::Geometry;
-use zcad_core::math::Point2;
+use zcad_core::math::{Point2, EPSILON};
+use zcad_core::snap::SnapType;
+use zcad_core::transform::Transform2D;
 
 /// Action 执行结果
 #[derive(Debug, Clone)]
@@ -20,6 +24,8 @@ pub enum ActionResult {
     ModifyEntity(EntityId, Geometry),
     /// 完成当前 action，删除实体
     DeleteEntities(Vec<EntityId>),
+    /// 完成当前 action，分解块参照
+    ExplodeEntities(Vec<EntityId>),
     /// 取消当前 action
     Cancel,
     /// 切换到另一个 action
@@ -45,6 +51,11 @@ pub enum ActionType {
     DrawDimension,
     DrawDimensionRadius,
     DrawDimensionDiameter,
+    DrawDimensionAngular,
+    DrawDimensionArc,
+    DrawDimensionOrdinate,
+    DrawDimensionBaseline,
+    DrawDimensionContinue,
     DrawEllipse,
     DrawSpline,
     DrawLeader,
@@ -61,6 +72,7 @@ pub enum ActionType {
     Fillet,
     Chamfer,
     Erase,
+    Explode,
     
     // 夹点编辑
     GripEdit,
@@ -84,6 +96,11 @@ impl ActionType {
             ActionType::DrawDimension => "Dimension",
             ActionType::DrawDimensionRadius => "Radius Dimension",
             ActionType::DrawDimensionDiameter => "Diameter Dimension",
+            ActionType::DrawDimensionAngular => "Angular Dimension",
+            ActionType::DrawDimensionArc => "Arc Length Dimension",
+            ActionType::DrawDimensionOrdinate => "Ordinate Dimension",
+            ActionType::DrawDimensionBaseline => "Baseline Dimension",
+            ActionType::DrawDimensionContinue => "Continue Dimension",
             ActionType::DrawEllipse => "Ellipse",
             ActionType::DrawSpline => "Spline",
             ActionType::DrawLeader => "Leader",
@@ -98,6 +115,7 @@ impl ActionType {
             ActionType::Fillet => "Fillet",
             ActionType::Chamfer => "Chamfer",
             ActionType::Erase => "Erase",
+            ActionType::Explode => "Explode",
             ActionType::GripEdit => "Grip Edit",
             ActionType::None => "None",
         }
@@ -117,6 +135,11 @@ impl ActionType {
             ActionType::DrawDimension => Some("D"),
             ActionType::DrawDimensionRadius => Some("DRA"),
             ActionType::DrawDimensionDiameter => Some("DDI"),
+            ActionType::DrawDimensionAngular => Some("DAN"),
+            ActionType::DrawDimensionArc => Some("DAR"),
+            ActionType::DrawDimensionOrdinate => Some("DOR"),
+            ActionType::DrawDimensionBaseline => Some("DBA"),
+            ActionType::DrawDimensionContinue => Some("DCO"),
             ActionType::DrawEllipse => Some("EL"),
             ActionType::DrawSpline => Some("SPL"),
             ActionType::DrawLeader => Some("LE"),
@@ -131,6 +154,7 @@ impl ActionType {
             ActionType::Fillet => Some("F"),
             ActionType::Chamfer => Some("CHA"),
             ActionType::Erase => Some("E"),
+            ActionType::Explode => Some("X"),
             ActionType::GripEdit => Some("G"),
             ActionType::None => None,
         }
@@ -143,6 +167,10 @@ pub struct ActionContext<'a> {
     pub mouse_pos: Point2,
     /// 捕捉后的坐标（如果有）
     pub snap_pos: Option<Point2>,
+    /// 捕捉类型（如果有）
+    pub snap_type: Option<SnapType>,
+    /// 捕捉到的实体（如果有）
+    pub snap_entity: Option<EntityId>,
     /// 当前选中的实体
     pub selected_entities: &'a [EntityId],
     /// 所有实体（用于捕捉等）
@@ -153,6 +181,8 @@ pub struct ActionContext<'a> {
     pub reference_point: Option<Point2>,
     /// 当前缩放级别
     pub zoom: f64,
+    /// 文档的标注样式（新建标注按名称解析样式）
+    pub dim_styles: &'a DimStyleManager,
 }
 
 impl<'a> ActionContext<'a> {
@@ -160,6 +190,57 @@ impl<'a> ActionContext<'a> {
     pub fn effective_point(&self) -> Point2 {
         self.snap_pos.unwrap_or(self.mouse_pos)
     }
+
+    /// `point` 处的关联点：仅当该点就是当前捕捉点且捕捉到实体的特征点时返回
+    pub fn associative_point(&self, point: Point2) -> Option<AssociativePoint> {
+        let snap_pos = self.snap_pos.filter(|p| (p - point).norm() < EPSILON)?;
+        let entity = self.entities.iter().find(|e| Some(e.id) == self.snap_entity)?;
+        let tolerance = 5.0 / self.zoom.max(0.001);
+        AssociativePoint::from_snap(entity.id, &entity.geometry, self.snap_type?, snap_pos, tolerance)
+    }
+
+    /// 对指定实体施加变换，返回变换后的几何体（找不到的实体忽略）
+    pub fn transformed_geometries(&self, ids: &[EntityId], transform: &Transform2D) -> Vec<(EntityId, Geometry)> {
+        ids.iter()
+            .filter_map(|id| self.entities.iter().find(|e| e.id == *id))
+            .map(|e| (e.id, e.geometry.transform(transform)))
+            .collect()
+    }
+}
+
+/// 测试用的默认标注样式
+#[cfg(test)]
+static TEST_DIM_STYLES: std::sync::LazyLock<DimStyleManager> = std::sync::LazyLock::new(DimStyleManager::new);
+
+#[cfg(test)]
+impl<'a> ActionContext<'a> {
+    /// 测试用上下文：鼠标位于原点，无捕捉、无选择，使用默认标注样式
+    pub(crate) fn for_test(entities: &'a [Entity]) -> Self {
+        Self {
+            mouse_pos: Point2::origin(),
+            snap_pos: None,
+            snap_type: None,
+            snap_entity: None,
+            selected_entities: &[],
+            entities,
+            ortho_mode: false,
+            reference_point: None,
+            zoom: 1.0,
+            dim_styles: &TEST_DIM_STYLES,
+        }
+    }
+}
+
+/// 测试辅助：取出 Action 创建的第一个标注
+#[cfg(test)]
+pub(crate) fn created_dimension(result: ActionResult) -> zcad_core::geometry::Dimension {
+    match result {
+        ActionResult::CreateEntities(mut geometries) => match geometries.remove(0) {
+            Geometry::Dimension(dim) => dim,
+            other => panic!("expected a dimension, got {:?}", other),
+        },
+        other => panic!("expected created entities, got {:?}", other),
+    }
 }
 
 /// 预览几何体
@@ -0,0 +1,313 @@
+//! 角度标注 Action
+//!
+//! 选择圆弧（标注圆心角）、圆（标注圆上两点的圆心角）、两条直线（标注夹角），
+//! 或直接指定顶点和两条边上的点，最后指定尺寸弧位置
+
+use crate::action::{
+    Action, ActionContext, ActionResult, ActionType, MouseButton, PreviewGeometry,
+};
+use zcad_core::entity::Entity;
+use zcad_core::geometry::{Dimension, Geometry, Line};
+use zcad_core::math::{Point2, Vector2, EPSILON};
+
+/// 角度标注状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    /// 选择圆弧、圆、直线或指定顶点
+    SelectObject,
+    /// 选择第二条直线
+    SelectSecondLine,
+    /// 指定第一条边上的点
+    SetFirstPoint,
+    /// 指定第二条边上的点
+    SetSecondPoint,
+    /// 指定尺寸弧位置
+    SetArcLocation,
+}
+
+/// 角度标注 Action
+pub struct DrawDimensionAngularAction {
+    status: Status,
+    first_line: Option<Line>,
+    vertex: Option<Point2>,
+    first_point: Option<Point2>,
+    second_point: Option<Point2>,
+    /// 由两条直线确定时，两条直线的方向（尺寸弧位置决定取哪一侧）
+    line_directions: Option<(Vector2, Vector2)>,
+}
+
+impl DrawDimensionAngularAction {
+    pub fn new() -> Self {
+        Self {
+            status: Status::SelectObject,
+            first_line: None,
+            vertex: None,
+            first_point: None,
+            second_point: None,
+            line_directions: None,
+        }
+    }
+
+    fn find_entity_at_point<'a>(&self, ctx: &'a ActionContext, point: Point2) -> Option<&'a Entity> {
+        let tolerance = 5.0 / ctx.zoom.max(0.001);
+        ctx.entities.iter().find(|e| {
+            matches!(&e.geometry, Geometry::Line(_) | Geometry::Arc(_) | Geometry::Circle(_))
+                && e.geometry.contains_point(&point, tolerance)
+        })
+    }
+
+    /// 选择对象或指定顶点
+    fn select_object(&mut self, ctx: &ActionContext, point: Point2) {
+        match self.find_entity_at_point(ctx, point).map(|e| &e.geometry) {
+            Some(Geometry::Arc(arc)) => {
+                self.vertex = Some(arc.center);
+                self.first_point = Some(arc.start_point());
+                self.second_point = Some(arc.end_point());
+                self.status = Status::SetArcLocation;
+            }
+            Some(Geometry::Circle(circle)) => {
+                // 拾取点投影到圆上作为第一点
+                let dir = point - circle.center;
+                if dir.norm() > EPSILON {
+                    self.vertex = Some(circle.center);
+                    self.first_point = Some(circle.center + dir.normalize() * circle.radius);
+                    self.status = Status::SetSecondPoint;
+                }
+            }
+            Some(Geometry::Line(line)) => {
+                self.first_line = Some(line.clone());
+                self.status = Status::SelectSecondLine;
+            }
+            _ => {
+                self.vertex = Some(point);
+                self.status = Status::SetFirstPoint;
+            }
+        }
+    }
+
+    /// 选择第二条直线，以两条直线的交点为顶点
+    fn select_second_line(&mut self, ctx: &ActionContext, point: Point2) {
+        let Some(first) = self.first_line.clone() else {
+            return;
+        };
+        let Some(Geometry::Line(second)) = self.find_entity_at_point(ctx, point).map(|e| &e.geometry) else {
+            return;
+        };
+        let (d1, d2) = (first.end - first.start, second.end - second.start);
+        let denom = d1.x * d2.y - d1.y * d2.x;
+        if denom.abs() <= EPSILON * d1.norm() * d2.norm() {
+            return; // 平行线没有夹角
+        }
+        let offset = second.start - first.start;
+        let t = (offset.x * d2.y - offset.y * d2.x) / denom;
+        let vertex = first.start + d1 * t;
+
+        self.vertex = Some(vertex);
+        self.first_point = Some(far_endpoint(&first, vertex));
+        self.second_point = Some(far_endpoint(second, vertex));
+        self.line_directions = Some((d1.normalize(), d2.normalize()));
+        self.status = Status::SetArcLocation;
+    }
+
+    /// 按尺寸弧位置生成标注
+    ///
+    /// 由两条直线确定时，取两条直线之间包含该位置的那个不大于 180° 的夹角
+    fn build_dimension(&self, location: Point2) -> Option<Dimension> {
+        let vertex = self.vertex?;
+        let (mut first, mut second) = (self.first_point?, self.second_point?);
+        if let Some((d1, d2)) = self.line_directions {
+            let w = location - vertex;
+            let cross = |a: Vector2, b: Vector2| a.x * b.y - a.y * b.x;
+            let (a, b) = [(d1, d2), (-d1, d2), (d1, -d2), (-d1, -d2)]
+                .into_iter()
+                .find(|&(a, b)| {
+                    let side = cross(a, b);
+                    cross(a, w) * side >= 0.0 && cross(w, b) * side >= 0.0
+                })?;
+            first = ray_point(first, vertex, a);
+            second = ray_point(second, vertex, b);
+        }
+        Some(Dimension::angular(vertex, first, second, location))
+    }
+}
+
+/// 直线上距离顶点较远的端点
+fn far_endpoint(line: &Line, vertex: Point2) -> Point2 {
+    if (line.start - vertex).norm() >= (line.end - vertex).norm() {
+        line.start
+    } else {
+        line.end
+    }
+}
+
+/// 沿 `dir` 方向的边上一点：直线端点位于该方向时使用端点，否则取单位距离处
+fn ray_point(endpoint: Point2, vertex: Point2, dir: Vector2) -> Point2 {
+    if (endpoint - vertex).dot(&dir) > EPSILON {
+        endpoint
+    } else {
+        vertex + dir
+    }
+}
+
+impl Default for DrawDimensionAngularAction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Action for DrawDimensionAngularAction {
+    fn action_type(&self) -> ActionType {
+        ActionType::DrawDimensionAngular
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    fn on_mouse_move(&mut self, _ctx: &ActionContext) -> ActionResult {
+        ActionResult::Continue
+    }
+
+    fn on_mouse_click(&mut self, ctx: &ActionContext, button: MouseButton) -> ActionResult {
+        match button {
+            MouseButton::Left => {
+                let point = ctx.effective_point();
+                match self.status {
+                    Status::SelectObject => {
+                        self.select_object(ctx, point);
+                        ActionResult::Continue
+                    }
+                    Status::SelectSecondLine => {
+                        self.select_second_line(ctx, point);
+                        ActionResult::Continue
+                    }
+                    _ => self.on_coordinate(ctx, point),
+                }
+            }
+            MouseButton::Right => {
+                if self.status == Status::SelectObject {
+                    ActionResult::Cancel
+                } else {
+                    self.reset();
+                    ActionResult::Continue
+                }
+            }
+            MouseButton::Middle => ActionResult::Continue,
+        }
+    }
+
+    fn on_coordinate(&mut self, _ctx: &ActionContext, coord: Point2) -> ActionResult {
+        match self.status {
+            Status::SelectObject => {
+                // 输入坐标时直接作为顶点
+                self.vertex = Some(coord);
+                self.status = Status::SetFirstPoint;
+                ActionResult::Continue
+            }
+            Status::SelectSecondLine => ActionResult::Continue,
+            Status::SetFirstPoint => {
+                if self.vertex.is_some_and(|v| (coord - v).norm() > EPSILON) {
+                    self.first_point = Some(coord);
+                    self.status = Status::SetSecondPoint;
+                }
+                ActionResult::Continue
+            }
+            Status::SetSecondPoint => {
+                if self.vertex.is_some_and(|v| (coord - v).norm() > EPSILON) {
+                    self.second_point = Some(coord);
+                    self.status = Status::SetArcLocation;
+                }
+                ActionResult::Continue
+            }
+            Status::SetArcLocation => match self.build_dimension(coord) {
+                Some(dim) => {
+                    self.reset();
+                    ActionResult::CreateEntities(vec![Geometry::Dimension(dim)])
+                }
+                None => ActionResult::Continue,
+            },
+        }
+    }
+
+    fn on_command(&mut self, _ctx: &ActionContext, _cmd: &str) -> Option<ActionResult> {
+        None
+    }
+
+    fn get_prompt(&self) -> &str {
+        match self.status {
+            Status::SelectObject => "选择圆弧、圆、直线或指定顶点:",
+            Status::SelectSecondLine => "选择第二条直线:",
+            Status::SetFirstPoint => "指定角的第一个端点:",
+            Status::SetSecondPoint => "指定角的第二个端点:",
+            Status::SetArcLocation => "指定标注弧线位置:",
+        }
+    }
+
+    fn get_preview(&self, ctx: &ActionContext) -> Vec<PreviewGeometry> {
+        let point = ctx.effective_point();
+        match self.status {
+            Status::SetFirstPoint | Status::SetSecondPoint => {
+                let Some(vertex) = self.vertex else {
+                    return Vec::new();
+                };
+                self.first_point
+                    .into_iter()
+                    .chain([point])
+                    .map(|p| PreviewGeometry::reference(Geometry::Line(Line::new(vertex, p))))
+                    .collect()
+            }
+            Status::SetArcLocation => self
+                .build_dimension(point)
+                .map(|dim| PreviewGeometry::new(Geometry::Dimension(dim)))
+                .into_iter()
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_angular_between_two_lines() {
+        let entities = [
+            Entity::new(Geometry::Line(Line::new(Point2::new(-10.0, 0.0), Point2::new(20.0, 0.0)))),
+            Entity::new(Geometry::Line(Line::new(Point2::new(0.0, -5.0), Point2::new(20.0, 15.0)))),
+        ];
+        let mut action = DrawDimensionAngularAction::new();
+        let mut ctx = ActionContext::for_test(&entities);
+
+        ctx.mouse_pos = Point2::new(15.0, 0.0);
+        action.on_mouse_click(&ctx, MouseButton::Left);
+        assert_eq!(action.status, Status::SelectSecondLine);
+        ctx.mouse_pos = Point2::new(15.0, 10.0);
+        action.on_mouse_click(&ctx, MouseButton::Left);
+        assert_eq!(action.status, Status::SetArcLocation);
+
+        // 尺寸弧放在钝角一侧时测量 135°
+        ctx.mouse_pos = Point2::new(-8.0, 1.0);
+        let ActionResult::CreateEntities(created) = action.on_mouse_click(&ctx, MouseButton::Left) else {
+            panic!("expected a dimension");
+        };
+        let Geometry::Dimension(dim) = &created[0] else {
+            panic!("expected a dimension");
+        };
+        assert!((dim.definition_point1 - Point2::new(5.0, 0.0)).norm() < 1e-9);
+        assert!((dim.measurement() - 135.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_angular_by_vertex() {
+        let mut action = DrawDimensionAngularAction::new();
+        let ctx = ActionContext::for_test(&[]);
+
+        action.on_coordinate(&ctx, Point2::new(0.0, 0.0));
+        action.on_coordinate(&ctx, Point2::new(10.0, 0.0));
+        action.on_coordinate(&ctx, Point2::new(0.0, 10.0));
+        let result = action.on_coordinate(&ctx, Point2::new(5.0, 5.0));
+        assert!(matches!(&result, ActionResult::CreateEntities(g) if matches!(&g[0], Geometry::Dimension(d) if (d.measurement() - 90.0).abs() < 1e-9)));
+        assert_eq!(action.status, Status::SelectObject);
+    }
+}
@@ -0,0 +1,157 @@
+//! 弧长标注 Action
+//!
+//! 选择圆弧，然后指定尺寸弧位置
+
+use crate::action::{
+    Action, ActionContext, ActionResult, ActionType, MouseButton, PreviewGeometry,
+};
+use zcad_core::geometry::{Arc, Dimension, Geometry};
+use zcad_core::math::Point2;
+
+/// 弧长标注状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    /// 选择圆弧
+    SelectArc,
+    /// 指定尺寸弧位置
+    SetArcLocation,
+}
+
+/// 弧长标注 Action
+pub struct DrawDimensionArcAction {
+    status: Status,
+    arc: Option<Arc>,
+}
+
+impl DrawDimensionArcAction {
+    pub fn new() -> Self {
+        Self {
+            status: Status::SelectArc,
+            arc: None,
+        }
+    }
+
+    fn build_dimension(&self, location: Point2) -> Option<Dimension> {
+        let arc = self.arc.as_ref()?;
+        Some(Dimension::arc_length(arc.center, arc.start_point(), arc.end_point(), location))
+    }
+}
+
+impl Default for DrawDimensionArcAction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Action for DrawDimensionArcAction {
+    fn action_type(&self) -> ActionType {
+        ActionType::DrawDimensionArc
+    }
+
+    fn reset(&mut self) {
+        self.status = Status::SelectArc;
+        self.arc = None;
+    }
+
+    fn on_mouse_move(&mut self, _ctx: &ActionContext) -> ActionResult {
+        ActionResult::Continue
+    }
+
+    fn on_mouse_click(&mut self, ctx: &ActionContext, button: MouseButton) -> ActionResult {
+        match button {
+            MouseButton::Left => {
+                let point = ctx.effective_point();
+                match self.status {
+                    Status::SelectArc => {
+                        let tolerance = 5.0 / ctx.zoom.max(0.001);
+                        let arc = ctx.entities.iter().find_map(|e| match &e.geometry {
+                            Geometry::Arc(arc) if e.geometry.contains_point(&point, tolerance) => Some(arc.clone()),
+                            _ => None,
+                        });
+                        if arc.is_some() {
+                            self.arc = arc;
+                            self.status = Status::SetArcLocation;
+                        }
+                        ActionResult::Continue
+                    }
+                    Status::SetArcLocation => self.on_coordinate(ctx, point),
+                }
+            }
+            MouseButton::Right => {
+                if self.status == Status::SetArcLocation {
+                    self.reset();
+                    ActionResult::Continue
+                } else {
+                    ActionResult::Cancel
+                }
+            }
+            MouseButton::Middle => ActionResult::Continue,
+        }
+    }
+
+    fn on_coordinate(&mut self, _ctx: &ActionContext, coord: Point2) -> ActionResult {
+        if self.status != Status::SetArcLocation {
+            return ActionResult::Continue;
+        }
+        match self.build_dimension(coord) {
+            Some(dim) => {
+                self.reset();
+                ActionResult::CreateEntities(vec![Geometry::Dimension(dim)])
+            }
+            None => ActionResult::Continue,
+        }
+    }
+
+    fn on_command(&mut self, _ctx: &ActionContext, _cmd: &str) -> Option<ActionResult> {
+        None
+    }
+
+    fn get_prompt(&self) -> &str {
+        match self.status {
+            Status::SelectArc => "选择圆弧:",
+            Status::SetArcLocation => "指定弧长标注位置:",
+        }
+    }
+
+    fn get_preview(&self, ctx: &ActionContext) -> Vec<PreviewGeometry> {
+        if self.status != Status::SetArcLocation {
+            return Vec::new();
+        }
+        self.build_dimension(ctx.effective_point())
+            .map(|dim| PreviewGeometry::new(Geometry::Dimension(dim)))
+            .into_iter()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::created_dimension as created;
+    use zcad_core::entity::Entity;
+    use zcad_core::geometry::DimensionType;
+
+    #[test]
+    fn test_arc_length_dimension() {
+        let arc = Arc::new(Point2::new(0.0, 0.0), 10.0, 0.0, std::f64::consts::FRAC_PI_2);
+        let entities = [Entity::new(Geometry::Arc(arc))];
+        let mut action = DrawDimensionArcAction::new();
+        let mut ctx = ActionContext::for_test(&entities);
+
+        // 点在圆弧之外时保持选择状态
+        ctx.mouse_pos = Point2::new(30.0, 30.0);
+        action.on_mouse_click(&ctx, MouseButton::Left);
+        assert_eq!(action.status, Status::SelectArc);
+
+        let diagonal = std::f64::consts::FRAC_1_SQRT_2;
+        ctx.mouse_pos = Point2::new(10.0 * diagonal, 10.0 * diagonal);
+        action.on_mouse_click(&ctx, MouseButton::Left);
+        assert_eq!(action.status, Status::SetArcLocation);
+
+        ctx.mouse_pos = Point2::new(15.0 * diagonal, 15.0 * diagonal);
+        let dim = created(action.on_mouse_click(&ctx, MouseButton::Left));
+        assert_eq!(dim.dim_type, DimensionType::ArcLength);
+        assert!((dim.measurement() - 5.0 * std::f64::consts::PI).abs() < 1e-9);
+        assert_eq!(action.status, Status::SelectArc);
+    }
+}
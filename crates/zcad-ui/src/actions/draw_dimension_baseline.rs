@@ -0,0 +1,181 @@
+//! 基线标注 Action
+//!
+//! 选择一个对齐或线性标注作为基准（靠近拾取点的延伸线为原点），
+//! 然后连续指定点，每个点生成一个从同一原点出发的标注，
+//! 尺寸线按标注样式的基线间距依次向外排列
+
+use crate::action::{
+    Action, ActionContext, ActionResult, ActionType, MouseButton, PreviewGeometry,
+};
+use zcad_core::geometry::{Dimension, Geometry};
+use zcad_core::math::Point2;
+
+use super::draw_dimension_continue::{apply_chain_style, chain_style, find_linear_dimension};
+
+/// 基线标注状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    /// 选择基准标注
+    SelectBase,
+    /// 指定下一个测量点
+    SetNextPoint,
+}
+
+/// 基线标注 Action
+pub struct DrawDimensionBaselineAction {
+    status: Status,
+    /// 链中最后一个标注
+    last: Option<Dimension>,
+}
+
+impl DrawDimensionBaselineAction {
+    pub fn new() -> Self {
+        Self {
+            status: Status::SelectBase,
+            last: None,
+        }
+    }
+
+    /// 以指定标注（通常是刚创建的线性标注）为基准，跳过选择步骤
+    pub fn with_base(mut self, base: Dimension) -> Self {
+        if base.linear_direction().is_some() {
+            self.last = Some(base);
+            self.status = Status::SetNextPoint;
+        }
+        self
+    }
+}
+
+impl Default for DrawDimensionBaselineAction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DrawDimensionBaselineAction {
+    /// 基线原点到 `point` 的新标注，尺寸线按链的样式间距外移
+    fn next_dimension(&self, ctx: &ActionContext, point: Point2) -> Option<Dimension> {
+        let last = self.last.as_ref()?;
+        let style = chain_style(ctx, last);
+        let mut dim = apply_chain_style(last.baseline(point, style)?, style);
+        // 新测量点落在捕捉的特征点上时与该实体关联
+        dim.associations[1] = ctx.associative_point(point);
+        Some(dim)
+    }
+}
+
+impl Action for DrawDimensionBaselineAction {
+    fn action_type(&self) -> ActionType {
+        ActionType::DrawDimensionBaseline
+    }
+
+    fn reset(&mut self) {
+        self.status = Status::SelectBase;
+        self.last = None;
+    }
+
+    fn on_mouse_move(&mut self, _ctx: &ActionContext) -> ActionResult {
+        ActionResult::Continue
+    }
+
+    fn on_mouse_click(&mut self, ctx: &ActionContext, button: MouseButton) -> ActionResult {
+        match button {
+            MouseButton::Left => {
+                let point = ctx.effective_point();
+                match self.status {
+                    Status::SelectBase => {
+                        if let Some(mut base) = find_linear_dimension(ctx, point) {
+                            // 靠近拾取点的延伸线作为基线原点
+                            if (point - base.definition_point2).norm() < (point - base.definition_point1).norm() {
+                                base.swap_definition_points();
+                            }
+                            self.last = Some(base);
+                            self.status = Status::SetNextPoint;
+                        }
+                        ActionResult::Continue
+                    }
+                    Status::SetNextPoint => self.on_coordinate(ctx, point),
+                }
+            }
+            MouseButton::Right => ActionResult::Cancel,
+            MouseButton::Middle => ActionResult::Continue,
+        }
+    }
+
+    fn on_coordinate(&mut self, ctx: &ActionContext, coord: Point2) -> ActionResult {
+        if self.status != Status::SetNextPoint {
+            return ActionResult::Continue;
+        }
+        match self.next_dimension(ctx, coord) {
+            Some(dim) => {
+                self.last = Some(dim.clone());
+                ActionResult::CreateEntities(vec![Geometry::Dimension(dim)])
+            }
+            None => ActionResult::Continue,
+        }
+    }
+
+    fn on_command(&mut self, _ctx: &ActionContext, cmd: &str) -> Option<ActionResult> {
+        match cmd.to_uppercase().as_str() {
+            "S" | "SELECT" => {
+                self.reset();
+                Some(ActionResult::Continue)
+            }
+            _ => None,
+        }
+    }
+
+    fn get_prompt(&self) -> &str {
+        match self.status {
+            Status::SelectBase => "选择基准标注:",
+            Status::SetNextPoint => "指定第二条延伸线原点或 [选择(S)]:",
+        }
+    }
+
+    fn get_available_commands(&self) -> Vec<&str> {
+        match self.status {
+            Status::SelectBase => vec![],
+            Status::SetNextPoint => vec!["S"],
+        }
+    }
+
+    fn get_preview(&self, ctx: &ActionContext) -> Vec<PreviewGeometry> {
+        if self.status != Status::SetNextPoint {
+            return Vec::new();
+        }
+        self.next_dimension(ctx, ctx.effective_point())
+            .map(|dim| PreviewGeometry::new(Geometry::Dimension(dim)))
+            .into_iter()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::created_dimension as created;
+    use zcad_core::dimstyle::{DimStyle, DimStyleManager};
+    use zcad_core::geometry::DimensionType;
+
+    #[test]
+    fn test_baseline_chain() {
+        let style = DimStyle { name: "Wide".to_string(), baseline_spacing: 12.0, ..DimStyle::default() };
+        let mut dim_styles = DimStyleManager::new();
+        dim_styles.add_style(style.clone());
+        let mut base = Dimension::new(Point2::new(0.0, 0.0), Point2::new(20.0, 0.0), Point2::new(10.0, 10.0))
+            .with_style(&style);
+        base.dim_type = DimensionType::Linear;
+        let mut action = DrawDimensionBaselineAction::new().with_base(base);
+        let mut ctx = ActionContext::for_test(&[]);
+        ctx.dim_styles = &dim_styles;
+
+        // 基线间距取自基准标注的样式，而不是文档当前样式
+        let first = created(action.on_coordinate(&ctx, Point2::new(35.0, 0.0)));
+        let second = created(action.on_coordinate(&ctx, Point2::new(50.0, 0.0)));
+        for (dim, level) in [(first, 1.0), (second, 2.0)] {
+            assert_eq!(dim.definition_point1, Point2::new(0.0, 0.0));
+            assert_eq!(dim.style_name, "Wide");
+            assert!((dim.line_location.y - (10.0 + style.baseline_spacing * level)).abs() < 1e-9);
+        }
+    }
+}
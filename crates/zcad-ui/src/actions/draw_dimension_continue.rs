@@ -0,0 +1,257 @@
+//! 连续标注 Action
+//!
+//! 选择一个对齐或线性标注（从靠近拾取点的延伸线继续），
+//! 然后连续指定点，每个点生成一个从上一条延伸线出发、尺寸线对齐的标注
+
+use crate::action::{
+    Action, ActionContext, ActionResult, ActionType, MouseButton, PreviewGeometry,
+};
+use zcad_core::dimstyle::DimStyle;
+use zcad_core::geometry::{Dimension, Geometry};
+use zcad_core::math::Point2;
+
+/// 连续标注状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    /// 选择起始标注
+    SelectBase,
+    /// 指定下一个测量点
+    SetNextPoint,
+}
+
+/// 连续标注 Action
+pub struct DrawDimensionContinueAction {
+    status: Status,
+    /// 链中最后一个标注
+    last: Option<Dimension>,
+}
+
+impl DrawDimensionContinueAction {
+    pub fn new() -> Self {
+        Self {
+            status: Status::SelectBase,
+            last: None,
+        }
+    }
+
+    /// 从指定标注（通常是刚创建的线性标注）继续，跳过选择步骤
+    pub fn with_base(mut self, base: Dimension) -> Self {
+        if base.linear_direction().is_some() {
+            self.last = Some(base);
+            self.status = Status::SetNextPoint;
+        }
+        self
+    }
+}
+
+impl Default for DrawDimensionContinueAction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 拾取点处的对齐或线性标注
+pub(super) fn find_linear_dimension(ctx: &ActionContext, point: Point2) -> Option<Dimension> {
+    let tolerance = 5.0 / ctx.zoom.max(0.001);
+    ctx.entities.iter().find_map(|e| match &e.geometry {
+        Geometry::Dimension(dim) if dim.linear_direction().is_some() && dim.contains_point(&point, tolerance) => {
+            Some(dim.clone())
+        }
+        _ => None,
+    })
+}
+
+/// 标注链使用的样式：起始标注的样式，未设置或找不到时为文档当前样式
+pub(super) fn chain_style<'a>(ctx: &ActionContext<'a>, base: &Dimension) -> &'a DimStyle {
+    ctx.dim_styles.resolve(&base.style_name)
+}
+
+/// 链中新标注记录解析出的样式；起始标注未设置样式时文字高度也取自样式
+pub(super) fn apply_chain_style(dim: Dimension, style: &DimStyle) -> Dimension {
+    if dim.style_name.is_empty() {
+        return dim.with_style(style);
+    }
+    Dimension { style_name: style.name.clone(), ..dim }
+}
+
+impl DrawDimensionContinueAction {
+    /// 从链中最后一个标注连续到 `point` 的新标注
+    fn next_dimension(&self, ctx: &ActionContext, point: Point2) -> Option<Dimension> {
+        let last = self.last.as_ref()?;
+        let style = chain_style(ctx, last);
+        let mut dim = apply_chain_style(last.continued(point)?, style);
+        // 新测量点落在捕捉的特征点上时与该实体关联
+        dim.associations[1] = ctx.associative_point(point);
+        Some(dim)
+    }
+}
+
+impl Action for DrawDimensionContinueAction {
+    fn action_type(&self) -> ActionType {
+        ActionType::DrawDimensionContinue
+    }
+
+    fn reset(&mut self) {
+        self.status = Status::SelectBase;
+        self.last = None;
+    }
+
+    fn on_mouse_move(&mut self, _ctx: &ActionContext) -> ActionResult {
+        ActionResult::Continue
+    }
+
+    fn on_mouse_click(&mut self, ctx: &ActionContext, button: MouseButton) -> ActionResult {
+        match button {
+            MouseButton::Left => {
+                let point = ctx.effective_point();
+                match self.status {
+                    Status::SelectBase => {
+                        if let Some(mut base) = find_linear_dimension(ctx, point) {
+                            // 从靠近拾取点的延伸线继续
+                            if (point - base.definition_point1).norm() < (point - base.definition_point2).norm() {
+                                base.swap_definition_points();
+                            }
+                            self.last = Some(base);
+                            self.status = Status::SetNextPoint;
+                        }
+                        ActionResult::Continue
+                    }
+                    Status::SetNextPoint => self.on_coordinate(ctx, point),
+                }
+            }
+            MouseButton::Right => ActionResult::Cancel,
+            MouseButton::Middle => ActionResult::Continue,
+        }
+    }
+
+    fn on_coordinate(&mut self, ctx: &ActionContext, coord: Point2) -> ActionResult {
+        if self.status != Status::SetNextPoint {
+            return ActionResult::Continue;
+        }
+        match self.next_dimension(ctx, coord) {
+            Some(dim) => {
+                self.last = Some(dim.clone());
+                ActionResult::CreateEntities(vec![Geometry::Dimension(dim)])
+            }
+            None => ActionResult::Continue,
+        }
+    }
+
+    fn on_command(&mut self, _ctx: &ActionContext, cmd: &str) -> Option<ActionResult> {
+        match cmd.to_uppercase().as_str() {
+            "S" | "SELECT" => {
+                self.reset();
+                Some(ActionResult::Continue)
+            }
+            _ => None,
+        }
+    }
+
+    fn get_prompt(&self) -> &str {
+        match self.status {
+            Status::SelectBase => "选择连续标注:",
+            Status::SetNextPoint => "指定第二条延伸线原点或 [选择(S)]:",
+        }
+    }
+
+    fn get_available_commands(&self) -> Vec<&str> {
+        match self.status {
+            Status::SelectBase => vec![],
+            Status::SetNextPoint => vec!["S"],
+        }
+    }
+
+    fn get_preview(&self, ctx: &ActionContext) -> Vec<PreviewGeometry> {
+        if self.status != Status::SetNextPoint {
+            return Vec::new();
+        }
+        self.next_dimension(ctx, ctx.effective_point())
+            .map(|dim| PreviewGeometry::new(Geometry::Dimension(dim)))
+            .into_iter()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::created_dimension as created;
+    use zcad_core::dimstyle::DimStyleManager;
+    use zcad_core::entity::Entity;
+    use zcad_core::geometry::{DimensionType, Line};
+    use zcad_core::snap::SnapType;
+    use zcad_file::Document;
+
+    fn linear(p1: Point2, p2: Point2, location: Point2) -> Dimension {
+        let mut dim = Dimension::new(p1, p2, location);
+        dim.dim_type = DimensionType::Linear;
+        dim
+    }
+
+    #[test]
+    fn test_continue_chain() {
+        let base = linear(Point2::new(0.0, 0.0), Point2::new(20.0, 0.0), Point2::new(10.0, 10.0))
+            .with_style(&DimStyle::default());
+        let entities = [Entity::new(Geometry::Dimension(base))];
+        let mut action = DrawDimensionContinueAction::new();
+        let mut ctx = ActionContext::for_test(&entities);
+
+        // 拾取靠近第二条延伸线的位置
+        ctx.mouse_pos = Point2::new(18.0, 9.0);
+        action.on_mouse_click(&ctx, MouseButton::Left);
+        assert_eq!(action.status, Status::SetNextPoint);
+
+        let first = created(action.on_coordinate(&ctx, Point2::new(35.0, 0.0)));
+        assert_eq!(first.definition_point1, Point2::new(20.0, 0.0));
+        assert_eq!(first.line_location, Point2::new(10.0, 10.0));
+        let second = created(action.on_coordinate(&ctx, Point2::new(50.0, 0.0)));
+        assert_eq!(second.definition_point1, Point2::new(35.0, 0.0));
+        assert!((second.measurement() - 15.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_continue_applies_current_style() {
+        let base = linear(Point2::new(0.0, 0.0), Point2::new(20.0, 0.0), Point2::new(10.0, 10.0));
+        let mut dim_styles = DimStyleManager::new();
+        dim_styles.set_current_style("Mechanical");
+        let mut action = DrawDimensionContinueAction::new().with_base(base);
+        let mut ctx = ActionContext::for_test(&[]);
+        ctx.dim_styles = &dim_styles;
+
+        // 起始标注未设置样式时使用文档当前样式
+        let dim = created(action.on_coordinate(&ctx, Point2::new(35.0, 0.0)));
+        assert_eq!(dim.style_name, "Mechanical");
+        assert_eq!(dim.text_height, DimStyle::mechanical().text_height);
+    }
+
+    #[test]
+    fn test_continue_to_snapped_endpoint_is_associative() {
+        let line = Entity::new(Geometry::Line(Line::new(Point2::new(30.0, -5.0), Point2::new(35.0, 0.0))));
+        let entities = [line.clone()];
+        let base = linear(Point2::new(0.0, 0.0), Point2::new(20.0, 0.0), Point2::new(10.0, 10.0));
+        let mut action = DrawDimensionContinueAction::new().with_base(base);
+        let mut ctx = ActionContext::for_test(&entities);
+
+        // 捕捉直线终点作为新测量点
+        ctx.mouse_pos = Point2::new(34.8, 0.1);
+        ctx.snap_pos = Some(Point2::new(35.0, 0.0));
+        ctx.snap_type = Some(SnapType::Endpoint);
+        ctx.snap_entity = Some(line.id);
+        let dim = created(action.on_mouse_click(&ctx, MouseButton::Left));
+        assert!(dim.associations[0].is_none());
+        assert!(dim.associations[1].is_some());
+
+        // 修改直线后标注跟随终点移动
+        let mut document = Document::new();
+        document.add_entity(line.clone());
+        let dim_id = document.add_entity(Entity::new(Geometry::Dimension(dim)));
+        let mut moved = line.clone();
+        moved.geometry = Geometry::Line(Line::new(Point2::new(30.0, -5.0), Point2::new(40.0, 0.0)));
+        document.update_entity(&line.id, moved);
+        let Some(Geometry::Dimension(dim)) = document.get_entity(&dim_id).map(|e| &e.geometry) else {
+            panic!("dimension missing");
+        };
+        assert_eq!(dim.definition_point2, Point2::new(40.0, 0.0));
+        assert!((dim.measurement() - 20.0).abs() < 1e-9);
+    }
+}
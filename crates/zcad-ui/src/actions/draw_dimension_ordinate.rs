@@ -0,0 +1,234 @@
+//! 坐标标注 Action
+//!
+//! 指定特征点，然后指定引线端点。默认按引线方向决定标注 X 或 Y 坐标，
+//! 也可以用 X/Y 子命令强制指定；坐标相对世界原点
+
+use crate::action::{
+    Action, ActionContext, ActionResult, ActionType, MouseButton, PreviewGeometry,
+};
+use zcad_core::associative::AssociativePoint;
+use zcad_core::geometry::{Dimension, Geometry, OrdinateAxis};
+use zcad_core::math::{Point2, EPSILON};
+
+/// 坐标标注状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    /// 指定特征点
+    SetFeature,
+    /// 指定引线端点
+    SetLeaderEnd,
+}
+
+/// 坐标标注 Action
+pub struct DrawDimensionOrdinateAction {
+    status: Status,
+    feature: Option<Point2>,
+    /// 特征点捕捉到实体时的关联
+    feature_association: Option<AssociativePoint>,
+    /// 强制的坐标轴（None 表示按引线方向自动选择）
+    axis: Option<OrdinateAxis>,
+}
+
+impl DrawDimensionOrdinateAction {
+    pub fn new() -> Self {
+        Self {
+            status: Status::SetFeature,
+            feature: None,
+            feature_association: None,
+            axis: None,
+        }
+    }
+
+    fn build_dimension(&self, leader_end: Point2) -> Option<Dimension> {
+        let feature = self.feature?;
+        if (leader_end - feature).norm() < EPSILON {
+            return None;
+        }
+        let axis = self.axis.unwrap_or_else(|| OrdinateAxis::from_leader(feature, leader_end));
+        let dim = Dimension::ordinate(feature, Point2::origin(), leader_end, axis);
+        Some(dim.with_associations(self.feature_association, None))
+    }
+}
+
+impl Default for DrawDimensionOrdinateAction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Action for DrawDimensionOrdinateAction {
+    fn action_type(&self) -> ActionType {
+        ActionType::DrawDimensionOrdinate
+    }
+
+    fn reset(&mut self) {
+        self.status = Status::SetFeature;
+        self.feature = None;
+        self.feature_association = None;
+        self.axis = None;
+    }
+
+    fn on_mouse_move(&mut self, _ctx: &ActionContext) -> ActionResult {
+        ActionResult::Continue
+    }
+
+    fn on_mouse_click(&mut self, ctx: &ActionContext, button: MouseButton) -> ActionResult {
+        match button {
+            MouseButton::Left => {
+                let point = ctx.effective_point();
+                self.on_coordinate(ctx, point)
+            }
+            MouseButton::Right => {
+                if self.status == Status::SetLeaderEnd {
+                    self.reset();
+                    ActionResult::Continue
+                } else {
+                    ActionResult::Cancel
+                }
+            }
+            MouseButton::Middle => ActionResult::Continue,
+        }
+    }
+
+    fn on_coordinate(&mut self, ctx: &ActionContext, coord: Point2) -> ActionResult {
+        match self.status {
+            Status::SetFeature => {
+                self.feature = Some(coord);
+                self.feature_association = ctx.associative_point(coord);
+                self.status = Status::SetLeaderEnd;
+                ActionResult::Continue
+            }
+            Status::SetLeaderEnd => match self.build_dimension(coord) {
+                Some(dim) => {
+                    self.reset();
+                    ActionResult::CreateEntities(vec![Geometry::Dimension(dim)])
+                }
+                None => ActionResult::Continue,
+            },
+        }
+    }
+
+    fn on_command(&mut self, _ctx: &ActionContext, cmd: &str) -> Option<ActionResult> {
+        if self.status != Status::SetLeaderEnd {
+            return None;
+        }
+        match cmd.to_uppercase().as_str() {
+            "X" | "XDATUM" => self.axis = Some(OrdinateAxis::X),
+            "Y" | "YDATUM" => self.axis = Some(OrdinateAxis::Y),
+            _ => return None,
+        }
+        Some(ActionResult::Continue)
+    }
+
+    fn get_prompt(&self) -> &str {
+        match self.status {
+            Status::SetFeature => "指定点坐标:",
+            Status::SetLeaderEnd => "指定引线端点或 [X基准(X)/Y基准(Y)]:",
+        }
+    }
+
+    fn get_available_commands(&self) -> Vec<&str> {
+        match self.status {
+            Status::SetFeature => vec![],
+            Status::SetLeaderEnd => vec!["X", "Y"],
+        }
+    }
+
+    fn get_preview(&self, ctx: &ActionContext) -> Vec<PreviewGeometry> {
+        if self.status != Status::SetLeaderEnd {
+            return Vec::new();
+        }
+        self.build_dimension(ctx.effective_point())
+            .map(|dim| PreviewGeometry::new(Geometry::Dimension(dim)))
+            .into_iter()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::created_dimension as created;
+    use zcad_core::entity::Entity;
+    use zcad_core::geometry::Line;
+    use zcad_core::snap::SnapType;
+
+    #[test]
+    fn test_ordinate_axis_from_leader() {
+        let mut action = DrawDimensionOrdinateAction::new();
+        let ctx = ActionContext::for_test(&[]);
+
+        // 竖直引线标注 X 坐标
+        action.on_coordinate(&ctx, Point2::new(10.0, 20.0));
+        let dim = created(action.on_coordinate(&ctx, Point2::new(11.0, 35.0)));
+        assert_eq!(dim.ordinate_axis, OrdinateAxis::X);
+        assert!((dim.measurement() - 10.0).abs() < 1e-9);
+        assert_eq!(action.status, Status::SetFeature);
+
+        // 水平引线标注 Y 坐标
+        action.on_coordinate(&ctx, Point2::new(10.0, 20.0));
+        let dim = created(action.on_coordinate(&ctx, Point2::new(30.0, 22.0)));
+        assert_eq!(dim.ordinate_axis, OrdinateAxis::Y);
+        assert!((dim.measurement() - 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_ordinate_forced_axis() {
+        let mut action = DrawDimensionOrdinateAction::new();
+        let ctx = ActionContext::for_test(&[]);
+
+        // 指定特征点之前不接受坐标轴子命令
+        assert!(action.on_command(&ctx, "Y").is_none());
+        action.on_coordinate(&ctx, Point2::new(10.0, 20.0));
+        assert!(action.on_command(&ctx, "y").is_some());
+        let dim = created(action.on_coordinate(&ctx, Point2::new(11.0, 35.0)));
+        assert_eq!(dim.ordinate_axis, OrdinateAxis::Y);
+        assert!((dim.measurement() - 20.0).abs() < 1e-9);
+
+        // 强制的坐标轴只作用于当前标注
+        action.on_coordinate(&ctx, Point2::new(10.0, 20.0));
+        assert!(action.on_command(&ctx, "XDATUM").is_some());
+        let dim = created(action.on_coordinate(&ctx, Point2::new(30.0, 22.0)));
+        assert_eq!(dim.ordinate_axis, OrdinateAxis::X);
+        action.on_coordinate(&ctx, Point2::new(10.0, 20.0));
+        let dim = created(action.on_coordinate(&ctx, Point2::new(30.0, 22.0)));
+        assert_eq!(dim.ordinate_axis, OrdinateAxis::Y);
+    }
+
+    #[test]
+    fn test_ordinate_snapped_feature_is_associative() {
+        let line = Entity::new(Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(10.0, 20.0))));
+        let entities = [line.clone()];
+        let mut action = DrawDimensionOrdinateAction::new();
+        let mut ctx = ActionContext::for_test(&entities);
+
+        // 捕捉直线终点作为特征点
+        ctx.mouse_pos = Point2::new(10.2, 19.9);
+        ctx.snap_pos = Some(Point2::new(10.0, 20.0));
+        ctx.snap_type = Some(SnapType::Endpoint);
+        ctx.snap_entity = Some(line.id);
+        action.on_mouse_click(&ctx, MouseButton::Left);
+
+        let ctx = ActionContext { mouse_pos: Point2::new(10.0, 35.0), ..ActionContext::for_test(&entities) };
+        let dim = created(action.on_mouse_click(&ctx, MouseButton::Left));
+        assert_eq!(dim.definition_point1, Point2::new(10.0, 20.0));
+        assert_eq!(dim.associations[0], Some(AssociativePoint::new(line.id, SnapType::Endpoint, 1)));
+        assert!(dim.associations[1].is_none());
+    }
+
+    #[test]
+    fn test_ordinate_right_click_resets() {
+        let mut action = DrawDimensionOrdinateAction::new();
+        let ctx = ActionContext::for_test(&[]);
+
+        action.on_coordinate(&ctx, Point2::new(10.0, 20.0));
+        action.on_command(&ctx, "X");
+        assert!(matches!(action.on_mouse_click(&ctx, MouseButton::Right), ActionResult::Continue));
+        assert_eq!(action.status, Status::SetFeature);
+        assert!(action.feature.is_none());
+        assert!(action.axis.is_none());
+
+        // 未指定特征点时右键取消
+        assert!(matches!(action.on_mouse_click(&ctx, MouseButton::Right), ActionResult::Cancel));
+    }
+}
@@ -316,21 +316,10 @@ impl Action for DrawLineAction {
 mod tests {
     use super::*;
 
-    fn create_ctx() -> ActionContext<'static> {
-        ActionContext {
-            mouse_pos: Point2::new(0.0, 0.0),
-            snap_pos: None,
-            selected_entities: &[],
-            entities: &[],
-            ortho_mode: false,
-            reference_point: None,
-        }
-    }
-
     #[test]
     fn test_draw_line_basic() {
         let mut action = DrawLineAction::new();
-        let mut ctx = create_ctx();
+        let mut ctx = ActionContext::for_test(&[]);
 
         // 设置起点
         let result = action.on_coordinate(&ctx, Point2::new(0.0, 0.0));
@@ -346,7 +335,7 @@ mod tests {
     #[test]
     fn test_draw_line_undo() {
         let mut action = DrawLineAction::new();
-        let ctx = create_ctx();
+        let ctx = ActionContext::for_test(&[]);
 
         // 设置起点
         action.on_coordinate(&ctx, Point2::new(0.0, 0.0));
@@ -9,6 +9,11 @@ mod draw_polyline;
 mod draw_rectangle;
 mod draw_point;
 mod draw_ellipse;
+mod draw_dimension_angular;
+mod draw_dimension_arc;
+mod draw_dimension_ordinate;
+mod draw_dimension_baseline;
+mod draw_dimension_continue;
 mod select;
 mod modify_move;
 mod modify_copy;
@@ -20,6 +25,7 @@ mod modify_trim;
 mod modify_extend;
 mod modify_fillet;
 mod modify_chamfer;
+mod modify_explode;
 mod grip_edit;
 
 pub use draw_line::DrawLineAction;
@@ -29,6 +35,11 @@ pub use draw_polyline::DrawPolylineAction;
 pub use draw_rectangle::DrawRectangleAction;
 pub use draw_point::DrawPointAction;
 pub use draw_ellipse::DrawEllipseAction;
+pub use draw_dimension_angular::DrawDimensionAngularAction;
+pub use draw_dimension_arc::DrawDimensionArcAction;
+pub use draw_dimension_ordinate::DrawDimensionOrdinateAction;
+pub use draw_dimension_baseline::DrawDimensionBaselineAction;
+pub use draw_dimension_continue::DrawDimensionContinueAction;
 pub use select::SelectAction;
 pub use modify_move::MoveAction;
 pub use modify_copy::CopyAction;
@@ -40,6 +51,7 @@ pub use modify_trim::TrimAction;
 pub use modify_extend::ExtendAction;
 pub use modify_fillet::FilletAction;
 pub use modify_chamfer::ChamferAction;
+pub use modify_explode::ExplodeAction;
 pub use grip_edit::GripEditAction;
 
 use crate::action::{Action, ActionType};
@@ -55,6 +67,11 @@ pub fn create_action(action_type: ActionType) -> Box<dyn Action> {
         ActionType::DrawRectangle => Box::new(DrawRectangleAction::new()),
         ActionType::DrawPoint => Box::new(DrawPointAction::new()),
         ActionType::DrawEllipse => Box::new(DrawEllipseAction::new()),
+        ActionType::DrawDimensionAngular => Box::new(DrawDimensionAngularAction::new()),
+        ActionType::DrawDimensionArc => Box::new(DrawDimensionArcAction::new()),
+        ActionType::DrawDimensionOrdinate => Box::new(DrawDimensionOrdinateAction::new()),
+        ActionType::DrawDimensionBaseline => Box::new(DrawDimensionBaselineAction::new()),
+        ActionType::DrawDimensionContinue => Box::new(DrawDimensionContinueAction::new()),
         ActionType::Move => Box::new(MoveAction::new()),
         ActionType::Copy => Box::new(CopyAction::new()),
         ActionType::Rotate => Box::new(RotateAction::new()),
@@ -65,6 +82,7 @@ pub fn create_action(action_type: ActionType) -> Box<dyn Action> {
         ActionType::Extend => Box::new(ExtendAction::new()),
         ActionType::Fillet => Box::new(FilletAction::new()),
         ActionType::Chamfer => Box::new(ChamferAction::new()),
+        ActionType::Explode => Box::new(ExplodeAction::new()),
         ActionType::GripEdit => Box::new(GripEditAction::new()),
         _ => Box::new(SelectAction::new()),
     }
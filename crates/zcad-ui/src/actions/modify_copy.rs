@@ -8,6 +8,7 @@ use crate::action::{
 use zcad_core::entity::EntityId;
 use zcad_core::geometry::{Geometry, Line};
 use zcad_core::math::Point2;
+use zcad_core::transform::Transform2D;
 
 /// 复制状态
 #[derive(Debug, Clone, PartialEq)]
@@ -119,11 +120,14 @@ impl Action for CopyAction {
             }
             Status::SetDestination => {
                 if let Some(base) = self.base_point {
-                    // 返回复制结果
-                    let result = ActionResult::ModifyEntities(
-                        self.entity_ids.iter().map(|&id| {
-                            (id, Geometry::Line(Line::new(base, coord)))
-                        }).collect()
+                    // 返回复制结果：平移后的副本作为新实体
+                    let offset = coord - base;
+                    let transform = Transform2D::translation(offset.x, offset.y);
+                    let result = ActionResult::CreateEntities(
+                        ctx.transformed_geometries(&self.entity_ids, &transform)
+                            .into_iter()
+                            .map(|(_, geometry)| geometry)
+                            .collect(),
                     );
                     
                     if !self.multiple {
@@ -0,0 +1,85 @@
+//! 分解 Action
+//!
+//! 参考 LibreCAD 的 RS_ActionBlocksExplode 实现
+
+use crate::action::{
+    Action, ActionContext, ActionResult, ActionType, MouseButton, PreviewGeometry,
+};
+use zcad_core::entity::EntityId;
+use zcad_core::geometry::Geometry;
+use zcad_core::math::Point2;
+
+/// 分解 Action
+///
+/// 只有一个状态：等待选择块参照，确认后由外部用块定义中的实体替换块参照
+pub struct ExplodeAction;
+
+impl ExplodeAction {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// 从选择集中筛选出块参照
+    fn explode_selection(&self, ctx: &ActionContext) -> ActionResult {
+        let ids: Vec<EntityId> = ctx
+            .entities
+            .iter()
+            .filter(|e| ctx.selected_entities.contains(&e.id))
+            .filter(|e| matches!(e.geometry, Geometry::Insert(_)))
+            .map(|e| e.id)
+            .collect();
+
+        if ids.is_empty() {
+            ActionResult::NeedSelection
+        } else {
+            ActionResult::ExplodeEntities(ids)
+        }
+    }
+}
+
+impl Default for ExplodeAction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Action for ExplodeAction {
+    fn action_type(&self) -> ActionType {
+        ActionType::Explode
+    }
+
+    fn reset(&mut self) {}
+
+    fn on_mouse_move(&mut self, _ctx: &ActionContext) -> ActionResult {
+        ActionResult::Continue
+    }
+
+    fn on_mouse_click(&mut self, ctx: &ActionContext, button: MouseButton) -> ActionResult {
+        match button {
+            MouseButton::Left => self.explode_selection(ctx),
+            MouseButton::Right => ActionResult::Cancel,
+            MouseButton::Middle => ActionResult::Continue,
+        }
+    }
+
+    fn on_coordinate(&mut self, ctx: &ActionContext, _coord: Point2) -> ActionResult {
+        self.explode_selection(ctx)
+    }
+
+    fn on_command(&mut self, ctx: &ActionContext, cmd: &str) -> Option<ActionResult> {
+        // 回车确认选择
+        if cmd.trim().is_empty() {
+            Some(self.explode_selection(ctx))
+        } else {
+            None
+        }
+    }
+
+    fn get_prompt(&self) -> &str {
+        "选择要分解的块参照:"
+    }
+
+    fn get_preview(&self, _ctx: &ActionContext) -> Vec<PreviewGeometry> {
+        Vec::new()
+    }
+}
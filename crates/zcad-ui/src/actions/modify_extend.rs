@@ -7,6 +7,7 @@ use crate::action::{
 };
 use zcad_core::entity::EntityId;
 use zcad_core::geometry::{Geometry, Line};
+use zcad_core::intersect::intersection_points;
 use zcad_core::math::{Point2, EPSILON};
 
 /// 延伸状态
@@ -143,13 +144,21 @@ impl ExtendAction {
         let extend_dir = if extend_from_start { -dir } else { dir };
         let extend_point = if extend_from_start { line.start } else { line.end };
         
-        // 创建一个延伸射线（足够长）
-        let ray_end = extend_point + extend_dir * 10000.0;
-        let ray = Line::new(extend_point, ray_end);
-        
         for boundary_id in &self.boundary_entities {
             if let Some(boundary) = ctx.entities.iter().find(|e| e.id == *boundary_id) {
-                let intersections = self.find_intersections(&Geometry::Line(ray.clone()), &boundary.geometry);
+                // 延伸射线长度取到边界包围盒最远角点的距离，任意远的边界都能相交
+                let bounds = boundary.geometry.bounding_box();
+                let reach = [
+                    bounds.min,
+                    bounds.max,
+                    Point2::new(bounds.min.x, bounds.max.y),
+                    Point2::new(bounds.max.x, bounds.min.y),
+                ]
+                .iter()
+                .map(|corner| (corner - extend_point).norm())
+                .fold(0.0, f64::max);
+                let ray = Line::new(extend_point, extend_point + extend_dir * (reach + 1.0));
+                let intersections = intersection_points(&Geometry::Line(ray), &boundary.geometry);
                 
                 for p in intersections {
                     let dist = (p - extend_point).norm();
@@ -171,68 +180,36 @@ impl ExtendAction {
             None
         }
     }
+}
 
-    fn find_intersections(&self, geom1: &Geometry, geom2: &Geometry) -> Vec<Point2> {
-        match (geom1, geom2) {
-            (Geometry::Line(l1), Geometry::Line(l2)) => {
-                self.line_line_intersection(l1, l2).into_iter().collect()
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zcad_core::entity::Entity;
+
+    #[test]
+    fn test_extend_to_distant_boundary() {
+        let line = Entity::new(Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0))));
+        let boundary = Entity::new(Geometry::Line(Line::new(
+            Point2::new(50_000.0, -10.0),
+            Point2::new(50_000.0, 10.0),
+        )));
+        let entities = vec![line.clone(), boundary];
+        let ctx = |point: Point2| ActionContext { mouse_pos: point, ..ActionContext::for_test(&entities) };
+
+        let mut action = ExtendAction::new();
+        action.on_mouse_click(&ctx(Point2::new(50_000.0, 0.0)), MouseButton::Left);
+        action.on_mouse_click(&ctx(Point2::new(50_000.0, 0.0)), MouseButton::Right);
+        let result = action.on_mouse_click(&ctx(Point2::new(9.0, 0.0)), MouseButton::Left);
+        match result {
+            ActionResult::ModifyEntities(modified) => {
+                assert_eq!(modified[0].0, line.id);
+                let Geometry::Line(extended) = &modified[0].1 else {
+                    panic!("expected line");
+                };
+                assert!((extended.end - Point2::new(50_000.0, 0.0)).norm() < 1e-6);
             }
-            (Geometry::Line(line), Geometry::Circle(circle)) => {
-                self.line_circle_intersection(line, circle)
-            }
-            _ => vec![],
-        }
-    }
-
-    fn line_line_intersection(&self, l1: &Line, l2: &Line) -> Option<Point2> {
-        let d1 = l1.end - l1.start;
-        let d2 = l2.end - l2.start;
-
-        let cross = d1.x * d2.y - d1.y * d2.x;
-        
-        if cross.abs() < EPSILON {
-            return None;
-        }
-
-        let d = l2.start - l1.start;
-        let t1 = (d.x * d2.y - d.y * d2.x) / cross;
-        let t2 = (d.x * d1.y - d.y * d1.x) / cross;
-
-        // t1 可以 > 1（射线延伸），t2 必须在 [0, 1] 内（边界线段内）
-        if t1 >= 0.0 && t2 >= 0.0 && t2 <= 1.0 {
-            Some(l1.start + d1 * t1)
-        } else {
-            None
+            _ => panic!("expected extended line"),
         }
     }
-
-    fn line_circle_intersection(&self, line: &Line, circle: &zcad_core::geometry::Circle) -> Vec<Point2> {
-        let d = line.end - line.start;
-        let f = line.start - circle.center;
-
-        let a = d.dot(&d);
-        let b = 2.0 * f.dot(&d);
-        let c = f.dot(&f) - circle.radius * circle.radius;
-
-        let discriminant = b * b - 4.0 * a * c;
-
-        if discriminant < 0.0 {
-            return vec![];
-        }
-
-        let mut intersections = Vec::new();
-        let sqrt_disc = discriminant.sqrt();
-        
-        let t1 = (-b - sqrt_disc) / (2.0 * a);
-        let t2 = (-b + sqrt_disc) / (2.0 * a);
-
-        if t1 >= 0.0 {
-            intersections.push(line.start + d * t1);
-        }
-        if t2 >= 0.0 && (t2 - t1).abs() > EPSILON {
-            intersections.push(line.start + d * t2);
-        }
-
-        intersections
-    }
 }
@@ -8,6 +8,7 @@ use crate::action::{
 use zcad_core::entity::EntityId;
 use zcad_core::geometry::{Geometry, Line};
 use zcad_core::math::Point2;
+use zcad_core::transform::Transform2D;
 
 /// 镜像状态
 #[derive(Debug, Clone, PartialEq)]
@@ -102,13 +103,14 @@ impl Action for MirrorAction {
                 if let Some(p1) = self.point1 {
                     // 确保镜像线有长度
                     if (coord - p1).norm() > 1e-6 {
-                        // 返回镜像结果
-                        let result = ActionResult::ModifyEntities(
-                            self.entity_ids.iter().map(|&id| {
-                                // 使用 Line 来传递镜像线
-                                (id, Geometry::Line(Line::new(p1, coord)))
-                            }).collect()
-                        );
+                        // 删除原对象时修改原对象，否则生成镜像副本
+                        let geometries =
+                            ctx.transformed_geometries(&self.entity_ids, &Transform2D::mirror_line(p1, coord));
+                        let result = if self.delete_original {
+                            ActionResult::ModifyEntities(geometries)
+                        } else {
+                            ActionResult::CreateEntities(geometries.into_iter().map(|(_, geometry)| geometry).collect())
+                        };
                         
                         self.reset();
                         return result;
@@ -8,6 +8,7 @@ use crate::action::{
 use zcad_core::entity::EntityId;
 use zcad_core::geometry::{Geometry, Line};
 use zcad_core::math::Point2;
+use zcad_core::transform::Transform2D;
 
 /// 移动状态
 #[derive(Debug, Clone, PartialEq)]
@@ -121,17 +122,9 @@ impl Action for MoveAction {
             Status::SetDestination => {
                 if let Some(base) = self.base_point {
                     let offset = coord - base;
-                    
-                    // 返回移动结果（由外部处理实际的实体移动）
-                    // 这里返回一个特殊的结果类型来通知外部需要移动实体
-                    let result = ActionResult::ModifyEntities(
-                        self.entity_ids.iter().map(|&id| {
-                            // 创建一个占位几何体，实际移动由外部处理
-                            // 这里需要外部知道偏移量
-                            (id, Geometry::Line(Line::new(base, coord)))
-                        }).collect()
-                    );
-                    
+                    let transform = Transform2D::translation(offset.x, offset.y);
+                    let result = ActionResult::ModifyEntities(ctx.transformed_geometries(&self.entity_ids, &transform));
+
                     self.reset();
                     return result;
                 }
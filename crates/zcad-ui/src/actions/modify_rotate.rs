@@ -8,6 +8,7 @@ use crate::action::{
 use zcad_core::entity::EntityId;
 use zcad_core::geometry::{Geometry, Line};
 use zcad_core::math::Point2;
+use zcad_core::transform::Transform2D;
 
 /// 旋转状态
 #[derive(Debug, Clone, PartialEq)]
@@ -50,6 +51,18 @@ impl RotateAction {
     fn angle_to_point(&self, center: Point2, point: Point2) -> f64 {
         (point.y - center.y).atan2(point.x - center.x)
     }
+
+    /// 对选中对象施加变换：复制模式下生成副本，否则修改原对象
+    fn finish(&mut self, ctx: &ActionContext, transform: &Transform2D) -> ActionResult {
+        let geometries = ctx.transformed_geometries(&self.entity_ids, transform);
+        let result = if self.copy_mode {
+            ActionResult::CreateEntities(geometries.into_iter().map(|(_, geometry)| geometry).collect())
+        } else {
+            ActionResult::ModifyEntities(geometries)
+        };
+        self.reset();
+        result
+    }
 }
 
 impl Default for RotateAction {
@@ -118,18 +131,8 @@ impl Action for RotateAction {
                 if let (Some(center), Some(ref_point)) = (self.center, self.reference_point) {
                     let ref_angle = self.angle_to_point(center, ref_point);
                     let target_angle = self.angle_to_point(center, coord);
-                    let _rotation_angle = target_angle - ref_angle;
-                    
-                    // 返回旋转结果
-                    let result = ActionResult::ModifyEntities(
-                        self.entity_ids.iter().map(|&id| {
-                            // 使用 Line 来传递中心点和目标点（外部需要解析）
-                            (id, Geometry::Line(Line::new(center, coord)))
-                        }).collect()
-                    );
-                    
-                    self.reset();
-                    return result;
+                    let rotation_angle = target_angle - ref_angle;
+                    return self.finish(ctx, &Transform2D::rotation_around(center, rotation_angle));
                 }
                 ActionResult::Continue
             }
@@ -148,21 +151,12 @@ impl Action for RotateAction {
         }
     }
 
-    fn on_value(&mut self, _ctx: &ActionContext, value: f64) -> ActionResult {
+    fn on_value(&mut self, ctx: &ActionContext, value: f64) -> ActionResult {
         // 直接输入角度值（度数）
         if self.status == Status::SetReferenceAngle || self.status == Status::SetTargetAngle {
             if let Some(center) = self.center {
-                let _angle_rad = value.to_radians();
-                
-                // 返回旋转结果
-                let result = ActionResult::ModifyEntities(
-                    self.entity_ids.iter().map(|&id| {
-                        (id, Geometry::Line(Line::new(center, center)))
-                    }).collect()
-                );
-                
-                self.reset();
-                return result;
+                let angle_rad = value.to_radians();
+                return self.finish(ctx, &Transform2D::rotation_around(center, angle_rad));
             }
         }
         ActionResult::Continue
@@ -8,6 +8,7 @@ use crate::action::{
 use zcad_core::entity::EntityId;
 use zcad_core::geometry::{Geometry, Line};
 use zcad_core::math::Point2;
+use zcad_core::transform::Transform2D;
 
 /// 缩放状态
 #[derive(Debug, Clone, PartialEq)]
@@ -45,6 +46,18 @@ impl ScaleAction {
             copy_mode: false,
         }
     }
+
+    /// 对选中对象施加变换：复制模式下生成副本，否则修改原对象
+    fn finish(&mut self, ctx: &ActionContext, transform: &Transform2D) -> ActionResult {
+        let geometries = ctx.transformed_geometries(&self.entity_ids, transform);
+        let result = if self.copy_mode {
+            ActionResult::CreateEntities(geometries.into_iter().map(|(_, geometry)| geometry).collect())
+        } else {
+            ActionResult::ModifyEntities(geometries)
+        };
+        self.reset();
+        result
+    }
 }
 
 impl Default for ScaleAction {
@@ -115,17 +128,8 @@ impl Action for ScaleAction {
                     let target_dist = (coord - center).norm();
                     
                     if ref_dist > 1e-6 {
-                        let _scale_factor = target_dist / ref_dist;
-                        
-                        // 返回缩放结果
-                        let result = ActionResult::ModifyEntities(
-                            self.entity_ids.iter().map(|&id| {
-                                (id, Geometry::Line(Line::new(center, coord)))
-                            }).collect()
-                        );
-                        
-                        self.reset();
-                        return result;
+                        let scale_factor = target_dist / ref_dist;
+                        return self.finish(ctx, &Transform2D::scale_around(center, scale_factor, scale_factor));
                     }
                 }
                 ActionResult::Continue
@@ -145,22 +149,12 @@ impl Action for ScaleAction {
         }
     }
 
-    fn on_value(&mut self, _ctx: &ActionContext, value: f64) -> ActionResult {
+    fn on_value(&mut self, ctx: &ActionContext, value: f64) -> ActionResult {
         // 直接输入缩放比例
         if self.status == Status::SetReferencePoint || self.status == Status::SetTargetPoint {
             if let Some(center) = self.center {
                 if value > 1e-6 {
-                    let _scale_factor = value;
-                    
-                    // 返回缩放结果
-                    let result = ActionResult::ModifyEntities(
-                        self.entity_ids.iter().map(|&id| {
-                            (id, Geometry::Line(Line::new(center, center)))
-                        }).collect()
-                    );
-                    
-                    self.reset();
-                    return result;
+                    return self.finish(ctx, &Transform2D::scale_around(center, value, value));
                 }
             }
         }
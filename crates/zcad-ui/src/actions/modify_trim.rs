@@ -7,6 +7,7 @@ use crate::action::{
 };
 use zcad_core::entity::EntityId;
 use zcad_core::geometry::{Arc, Geometry, Line};
+use zcad_core::intersect::intersect;
 use zcad_core::math::{Point2, EPSILON};
 
 /// 修剪状态
@@ -133,32 +134,22 @@ impl TrimAction {
         }
     }
 
+    /// 收集对象与所有边界的交点在对象上的参数（升序）
+    fn boundary_params(&self, ctx: &ActionContext, geometry: &Geometry) -> Vec<f64> {
+        self.boundary_entities
+            .iter()
+            .filter_map(|id| ctx.entities.iter().find(|e| e.id == *id))
+            .flat_map(|boundary| intersect(geometry, &boundary.geometry))
+            .map(|hit| hit.param1)
+            .collect()
+    }
+
     /// 修剪线段
     fn trim_line(&self, ctx: &ActionContext, line: &Line, click_point: Point2) -> Option<Geometry> {
-        // 找到所有与边界的交点
-        let mut intersections = Vec::new();
-        
-        for boundary_id in &self.boundary_entities {
-            if let Some(boundary) = ctx.entities.iter().find(|e| e.id == *boundary_id) {
-                let points = self.find_intersections(&Geometry::Line(line.clone()), &boundary.geometry);
-                intersections.extend(points);
-            }
-        }
-        
-        if intersections.is_empty() {
-            return None;
-        }
-        
-        // 按到起点的参数排序
-        let line_vec = line.end - line.start;
-        let line_len = line_vec.norm();
-        
-        let mut params: Vec<f64> = intersections
-            .iter()
-            .map(|p| {
-                let v = *p - line.start;
-                v.dot(&line_vec) / (line_len * line_len)
-            })
+        // 找到所有与边界的交点参数
+        let mut params: Vec<f64> = self
+            .boundary_params(ctx, &Geometry::Line(line.clone()))
+            .into_iter()
             .filter(|&t| t > EPSILON && t < 1.0 - EPSILON)
             .collect();
         
@@ -169,20 +160,13 @@ impl TrimAction {
         params.sort_by(|a, b| a.partial_cmp(b).unwrap());
         
         // 确定点击位置的参数
+        let line_vec = line.end - line.start;
+        let line_len = line_vec.norm();
         let click_vec = click_point - line.start;
         let click_t = click_vec.dot(&line_vec) / (line_len * line_len);
         
         // 找到包含点击位置的段
-        let mut segment_start = 0.0;
-        let mut segment_end = 1.0;
-        
-        for &t in &params {
-            if click_t < t {
-                segment_end = t;
-                break;
-            }
-            segment_start = t;
-        }
+        let (segment_start, segment_end) = Self::enclosing_segment(&params, click_t);
         
         // 删除点击的段，保留剩余部分
         // 这里简化处理：返回离点击点较远的那一段
@@ -203,74 +187,58 @@ impl TrimAction {
     }
 
     /// 修剪圆弧
-    fn trim_arc(&self, _ctx: &ActionContext, arc: &Arc, _click_point: Point2) -> Option<Geometry> {
-        // 简化实现：暂不支持圆弧修剪
-        Some(Geometry::Arc(arc.clone()))
-    }
-
-    /// 计算两个几何体的交点
-    fn find_intersections(&self, geom1: &Geometry, geom2: &Geometry) -> Vec<Point2> {
-        match (geom1, geom2) {
-            (Geometry::Line(l1), Geometry::Line(l2)) => {
-                self.line_line_intersection(l1, l2).into_iter().collect()
-            }
-            (Geometry::Line(line), Geometry::Circle(circle)) => {
-                self.line_circle_intersection(line, circle)
-            }
-            _ => vec![],
+    fn trim_arc(&self, ctx: &ActionContext, arc: &Arc, click_point: Point2) -> Option<Geometry> {
+        let sweep = arc.sweep_angle();
+        if sweep < EPSILON {
+            return None;
         }
-    }
 
-    /// 线段-线段交点
-    fn line_line_intersection(&self, l1: &Line, l2: &Line) -> Option<Point2> {
-        let d1 = l1.end - l1.start;
-        let d2 = l2.end - l2.start;
+        // 交点参数为角度，换算为圆弧上的比例
+        let mut params: Vec<f64> = self
+            .boundary_params(ctx, &Geometry::Arc(arc.clone()))
+            .into_iter()
+            .map(|angle| (angle - arc.start_angle) / sweep)
+            .filter(|&t| t > EPSILON && t < 1.0 - EPSILON)
+            .collect();
 
-        let cross = d1.x * d2.y - d1.y * d2.x;
-        
-        if cross.abs() < EPSILON {
+        if params.is_empty() {
             return None;
         }
 
-        let d = l2.start - l1.start;
-        let t1 = (d.x * d2.y - d.y * d2.x) / cross;
-        let t2 = (d.x * d1.y - d.y * d1.x) / cross;
+        params.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let click_angle = (click_point.y - arc.center.y).atan2(click_point.x - arc.center.x);
+        let click_t = (click_angle - arc.start_angle).rem_euclid(std::f64::consts::TAU) / sweep;
+        let (segment_start, segment_end) = Self::enclosing_segment(&params, click_t);
+
+        // 与线段一致：删除点击的段，保留离点击点较远的一侧
+        let start_dist = (click_point - arc.start_point()).norm();
+        let end_dist = (click_point - arc.end_point()).norm();
 
-        if t1 >= 0.0 && t1 <= 1.0 && t2 >= 0.0 && t2 <= 1.0 {
-            Some(l1.start + d1 * t1)
+        if start_dist < end_dist && segment_start > EPSILON {
+            let new_start = arc.start_angle + sweep * segment_start;
+            Some(Geometry::Arc(Arc::new(arc.center, arc.radius, new_start, arc.end_angle)))
+        } else if segment_end < 1.0 - EPSILON {
+            let new_end = arc.start_angle + sweep * segment_end;
+            Some(Geometry::Arc(Arc::new(arc.center, arc.radius, arc.start_angle, new_end)))
         } else {
             None
         }
     }
 
-    /// 线段-圆交点
-    fn line_circle_intersection(&self, line: &Line, circle: &zcad_core::geometry::Circle) -> Vec<Point2> {
-        let d = line.end - line.start;
-        let f = line.start - circle.center;
-
-        let a = d.dot(&d);
-        let b = 2.0 * f.dot(&d);
-        let c = f.dot(&f) - circle.radius * circle.radius;
-
-        let discriminant = b * b - 4.0 * a * c;
-
-        if discriminant < 0.0 {
-            return vec![];
-        }
-
-        let mut intersections = Vec::new();
-        let sqrt_disc = discriminant.sqrt();
-        
-        let t1 = (-b - sqrt_disc) / (2.0 * a);
-        let t2 = (-b + sqrt_disc) / (2.0 * a);
+    /// 在升序参数列表中找到包含 t 的区间
+    fn enclosing_segment(params: &[f64], t: f64) -> (f64, f64) {
+        let mut segment_start = 0.0;
+        let mut segment_end = 1.0;
 
-        if t1 >= 0.0 && t1 <= 1.0 {
-            intersections.push(line.start + d * t1);
-        }
-        if t2 >= 0.0 && t2 <= 1.0 && (t2 - t1).abs() > EPSILON {
-            intersections.push(line.start + d * t2);
+        for &p in params {
+            if t < p {
+                segment_end = p;
+                break;
+            }
+            segment_start = p;
         }
 
-        intersections
+        (segment_start, segment_end)
     }
 }
@@ -51,6 +51,11 @@ impl CommandRegistry {
         self.register(ActionType::DrawDimension, "DIMENSION", &["DIM", "D", "DIMLINEAR", "DIMALIGNED"]);
         self.register(ActionType::DrawDimensionRadius, "DIMRADIUS", &["DRA"]);
         self.register(ActionType::DrawDimensionDiameter, "DIMDIAMETER", &["DDI"]);
+        self.register(ActionType::DrawDimensionAngular, "DIMANGULAR", &["DAN"]);
+        self.register(ActionType::DrawDimensionArc, "DIMARC", &["DAR"]);
+        self.register(ActionType::DrawDimensionOrdinate, "DIMORDINATE", &["DOR"]);
+        self.register(ActionType::DrawDimensionBaseline, "DIMBASELINE", &["DBA"]);
+        self.register(ActionType::DrawDimensionContinue, "DIMCONTINUE", &["DCO"]);
 
         // 修改命令
         self.register(ActionType::Move, "MOVE", &["M"]);
@@ -59,6 +64,7 @@ impl CommandRegistry {
         self.register(ActionType::Scale, "SCALE", &["SC"]);
         self.register(ActionType::Mirror, "MIRROR", &["MI"]);
         self.register(ActionType::Erase, "ERASE", &["E", "DELETE"]);
+        self.register(ActionType::Explode, "EXPLODE", &["X"]);
 
         // 选择
         self.register(ActionType::Select, "SELECT", &["SEL"]);
@@ -225,6 +231,7 @@ mod tests {
         // 快捷键
         assert_eq!(registry.lookup("L"), Some(ActionType::DrawLine));
         assert_eq!(registry.lookup("l"), Some(ActionType::DrawLine));
+        assert_eq!(registry.lookup("X"), Some(ActionType::Explode));
         
         // 不存在的命令
         assert_eq!(registry.lookup("NOTEXIST"), None);
@@ -238,6 +245,11 @@ mod tests {
         assert!(completions.contains(&"DIMENSION".to_string()));
         assert!(completions.contains(&"DIMRADIUS".to_string()));
         assert!(completions.contains(&"DIMDIAMETER".to_string()));
+        assert!(completions.contains(&"DIMANGULAR".to_string()));
+        assert!(completions.contains(&"DIMARC".to_string()));
+        assert!(completions.contains(&"DIMORDINATE".to_string()));
+        assert!(completions.contains(&"DIMBASELINE".to_string()));
+        assert!(completions.contains(&"DIMCONTINUE".to_string()));
     }
 
     #[test]
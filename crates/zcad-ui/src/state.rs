@@ -1,6 +1,7 @@
 //! UI状态管理
 
-use zcad_core::entity::EntityId;
+use zcad_core::entity::{Entity, EntityId};
+use zcad_core::geometry::Geometry;
 use zcad_core::layout::{LayoutManager, LayoutId, ViewportId, SpaceType};
 use zcad_core::math::Point2;
 use zcad_core::snap::{SnapConfig, SnapEngine, SnapPoint, SnapType};
@@ -205,12 +206,15 @@ pub enum EditState {
         content: String,
         height: f64,
     },
-    /// 正在编辑现有文本
+    /// 正在编辑现有文本（单行文字或多行文字）
     TextEdit {
         entity_id: EntityId,
         position: Point2,
+        /// 多行文字为带格式代码的原始内容
         content: String,
         height: f64,
+        /// 多行文字的参考宽度，单行文字为 None
+        width: Option<f64>,
     },
     /// 正在移动实体
     MovingEntities {
@@ -252,6 +256,58 @@ impl Default for EditState {
     }
 }
 
+impl EditState {
+    /// 开始编辑文字实体，非文字实体返回 None
+    pub fn edit_text(entity: &Entity) -> Option<Self> {
+        match &entity.geometry {
+            Geometry::Text(text) => Some(Self::TextEdit {
+                entity_id: entity.id,
+                position: text.position,
+                content: text.content.clone(),
+                height: text.height,
+                width: None,
+            }),
+            Geometry::MText(mtext) => Some(Self::TextEdit {
+                entity_id: entity.id,
+                position: mtext.position,
+                content: mtext.content.clone(),
+                height: mtext.height,
+                width: Some(mtext.width),
+            }),
+            _ => None,
+        }
+    }
+
+    /// 将编辑结果应用到原几何体，返回修改后的几何
+    pub fn apply_text_edit(&self, geometry: &Geometry) -> Option<Geometry> {
+        let Self::TextEdit { position, content, height, width, .. } = self else {
+            return None;
+        };
+        match geometry {
+            Geometry::Text(text) => {
+                let mut text = text.clone();
+                // 两点对齐的终点随插入点平移
+                if let Some(end) = text.end_point.as_mut() {
+                    *end += *position - text.position;
+                }
+                text.position = *position;
+                text.content = content.clone();
+                text.height = *height;
+                Some(Geometry::Text(text))
+            }
+            Geometry::MText(mtext) => {
+                let mut mtext = mtext.clone();
+                mtext.position = *position;
+                mtext.content = content.clone();
+                mtext.height = *height;
+                mtext.width = width.unwrap_or(mtext.width);
+                Some(Geometry::MText(mtext))
+            }
+            _ => None,
+        }
+    }
+}
+
 /// UI状态
 #[derive(Debug)]
 pub struct UiState {